@@ -0,0 +1,161 @@
+// Adaptive FPS Module - Sends fewer frames to a device whose network can't keep up (send errors, or sends
+// blocking on a full socket buffer), stepping back up to the full rate once its sends are clean again
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+use tokio::sync::broadcast;
+
+use crate::config::{self, BandwidthConfig, ConfigDelta};
+use crate::live_stats;
+
+// A send blocking this long means the socket buffer is full (a normal DDP send takes microseconds)
+const SLOW_SEND: Duration = Duration::from_millis(5);
+
+// Pressure is judged once per window - this many failed or slow sends in one window steps the rate down
+const WINDOW: Duration = Duration::from_secs(1);
+const PRESSURE_THRESHOLD: u32 = 3;
+
+// Each step down keeps this fraction of the rate; each step back up undoes one
+const STEP: f64 = 0.75;
+
+// How long sends have to stay clean before the rate steps back up
+const RECOVERY: Duration = Duration::from_secs(5);
+
+static ENABLED: AtomicBool = AtomicBool::new(true);
+static MIN_FPS: AtomicU64 = AtomicU64::new(0x4024_0000_0000_0000);  // 10.0
+
+/// Take the adaptive frame rate settings from the config
+pub fn configure(config: &BandwidthConfig) {
+    ENABLED.store(config.adaptive_fps_enabled, Ordering::Relaxed);
+    MIN_FPS.store(config.adaptive_fps_min.to_bits(), Ordering::Relaxed);
+}
+
+fn min_fps() -> f64 {
+    f64::from_bits(MIN_FPS.load(Ordering::Relaxed))
+}
+
+/// One device's reduced frame rate, tracked from the outcome of its sends
+pub struct AdaptiveRate {
+    limit: Option<f64>,  // Reduced rate in frames per second (None = every frame)
+    window_start: Instant,
+    offered: u32,  // Frames offered to the device this window (sent or not)
+    pressure: u32,  // Failed or slow sends this window
+    calm_since: Instant,  // Last window with any pressure
+}
+
+impl AdaptiveRate {
+    pub fn new() -> Self {
+        let now = Instant::now();
+        AdaptiveRate { limit: None, window_start: now, offered: 0, pressure: 0, calm_since: now }
+    }
+
+    /// Offer a frame - true when it should be skipped to hold the device to its reduced rate
+    pub fn throttled(&mut self, ip: &str, since_last_send: Duration) -> bool {
+        if !ENABLED.load(Ordering::Relaxed) {
+            if self.limit.take().is_some() {
+                live_stats::record_fps_limit(ip, None);
+            }
+            return false;
+        }
+
+        self.offered += 1;
+        let elapsed = self.window_start.elapsed();
+        if elapsed >= WINDOW {
+            let offered_fps = self.offered as f64 / elapsed.as_secs_f64();
+            if self.adjust(offered_fps) {
+                match self.limit {
+                    Some(fps) => println!("🐢 {} can't keep up - sending {:.0} fps", ip, fps),
+                    None => println!("🐇 {} caught up - back to the full frame rate", ip),
+                }
+                live_stats::record_fps_limit(ip, self.limit);
+            }
+            self.window_start = Instant::now();
+            self.offered = 0;
+            self.pressure = 0;
+        }
+
+        matches!(self.limit, Some(fps) if since_last_send.as_secs_f64() < 1.0 / fps)
+    }
+
+    /// Note the outcome of a send to the device
+    pub fn record_send(&mut self, elapsed: Duration, failed: bool) {
+        if failed || elapsed >= SLOW_SEND {
+            self.pressure += 1;
+        }
+    }
+
+    // End of a window: step the rate down under pressure, or back up after a calm spell.
+    // Returns whether the limit changed
+    fn adjust(&mut self, offered_fps: f64) -> bool {
+        if self.pressure >= PRESSURE_THRESHOLD {
+            self.calm_since = Instant::now();
+            let reduced = (self.limit.unwrap_or(offered_fps) * STEP).max(min_fps());
+            if reduced < offered_fps && Some(reduced) != self.limit {
+                self.limit = Some(reduced);
+                return true;
+            }
+        } else if self.pressure > 0 {
+            self.calm_since = Instant::now();
+        } else if let Some(limit) = self.limit {
+            if self.calm_since.elapsed() >= RECOVERY {
+                // Each step up waits out another calm spell
+                self.calm_since = Instant::now();
+                let raised = limit / STEP;
+                self.limit = (raised < offered_fps).then_some(raised);
+                return true;
+            }
+        }
+        false
+    }
+}
+
+/// Keep the adaptive frame rate settings in step with config changes (web UI, API, or file edits)
+pub fn spawn_watcher(config_change_tx: broadcast::Sender<ConfigDelta>) {
+    config::spawn_config_listener(&config_change_tx, &["adaptive_fps_enabled", "adaptive_fps_min"], configure);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_adjust() {
+        let mut rate = AdaptiveRate::new();
+
+        // Under pressure at 60 fps - stepped down, then down again
+        rate.pressure = PRESSURE_THRESHOLD;
+        assert!(rate.adjust(60.0));
+        assert_eq!(rate.limit, Some(45.0));
+        rate.pressure = PRESSURE_THRESHOLD;
+        assert!(rate.adjust(60.0));
+        assert_eq!(rate.limit, Some(33.75));
+
+        // Never below the minimum
+        rate.limit = Some(11.0);
+        rate.pressure = PRESSURE_THRESHOLD;
+        assert!(rate.adjust(60.0));
+        assert_eq!(rate.limit, Some(10.0));
+        rate.pressure = PRESSURE_THRESHOLD;
+        assert!(!rate.adjust(60.0));
+
+        // A stray slow send doesn't step down, but restarts the calm spell
+        rate.pressure = 1;
+        assert!(!rate.adjust(60.0));
+        rate.pressure = 0;
+        assert!(!rate.adjust(60.0));
+
+        // After a calm spell it steps back up, and is unthrottled once it reaches the offered rate
+        rate.limit = Some(50.0);
+        rate.calm_since = Instant::now() - RECOVERY;
+        assert!(rate.adjust(60.0));
+        assert_eq!(rate.limit, None);
+    }
+
+    #[test]
+    fn test_throttled() {
+        let mut rate = AdaptiveRate::new();
+        assert!(!rate.throttled("10.0.0.1", Duration::ZERO));
+        rate.limit = Some(20.0);
+        assert!(rate.throttled("10.0.0.1", Duration::from_millis(10)));
+        assert!(!rate.throttled("10.0.0.1", Duration::from_millis(60)));
+    }
+}
@@ -0,0 +1,306 @@
+// Agent Module - A tiny counter server ("rustwled agent") for hosts whose traffic bandwidth mode shows,
+// and the client side that reads from many of them at once without an SSH session per host
+//
+// Protocol (one TCP connection, newline-terminated text):
+//   agent  -> "rustwled-agent 1 <nonce>"      16 random bytes as hex
+//   client -> "<mac>"                         HMAC-SHA256(token, nonce) as hex
+//   agent  -> "ok" (or "denied" and closes)
+//   agent  -> "eth0: <16 /proc/net/dev fields>" for each reported interface, every second
+use crate::remote_link;
+use anyhow::{anyhow, bail, Context, Result};
+use rand::Rng;
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::io::{BufRead, BufReader as StdBufReader, Read, Write};
+use std::net::{TcpListener, TcpStream as StdTcpStream};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::net::TcpStream;
+use tokio::sync::mpsc;
+
+pub const AGENT_PORT: u16 = 4050;
+
+const GREETING: &str = "rustwled-agent";
+const PROTOCOL_VERSION: u32 = 1;
+const PROC_NET_DEV: &str = "/proc/net/dev";
+
+// Agents send counters every second - this long without any means the connection is stuck
+const STALL_TIMEOUT: Duration = Duration::from_secs(10);
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
+// Time a client gets to answer the challenge
+const AUTH_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Connection state of one agent, exposed via the API
+#[derive(Debug, Clone, Serialize)]
+pub struct AgentStatus {
+    pub host: String,
+    pub connected: bool,
+    pub last_error: Option<String>,
+}
+
+static STATUS: Mutex<Vec<AgentStatus>> = Mutex::new(Vec::new());
+
+pub fn status() -> Vec<AgentStatus> {
+    STATUS.lock().unwrap().clone()
+}
+
+fn update(host: &str, connected: bool, error: Option<String>) {
+    if let Ok(mut agents) = STATUS.lock() {
+        if let Some(agent) = agents.iter_mut().find(|agent| agent.host == host) {
+            agent.connected = connected;
+            if error.is_some() {
+                agent.last_error = error;
+            }
+        }
+    }
+}
+
+/// Agent addresses from a comma-separated "host[:port]" list
+pub fn parse_hosts(list: &str) -> Vec<String> {
+    list.split(',').map(|host| host.trim()).filter(|host| !host.is_empty()).map(|host| host.to_string()).collect()
+}
+
+// Address to connect to - the default port unless one is given
+fn address(host: &str) -> String {
+    if host.contains(':') {
+        host.to_string()
+    } else {
+        format!("{}:{}", host, AGENT_PORT)
+    }
+}
+
+// Interface lines are prefixed with the host (without port) so every host's interfaces are tracked apart
+fn tag_line(host: &str, line: &str) -> Option<String> {
+    let host = host.split(':').next().unwrap_or(host);
+    let (iface, counters) = line.split_once(':')?;
+    Some(format!("{}/{}: {}", host, iface.trim(), counters.trim()))
+}
+
+pub(crate) fn hmac_sha256(key: &[u8], message: &[u8]) -> [u8; 32] {
+    let mut block = [0u8; 64];
+    if key.len() > block.len() {
+        block[..32].copy_from_slice(&Sha256::digest(key));
+    } else {
+        block[..key.len()].copy_from_slice(key);
+    }
+    let mut inner = Sha256::new();
+    inner.update(block.map(|b| b ^ 0x36));
+    inner.update(message);
+    let mut outer = Sha256::new();
+    outer.update(block.map(|b| b ^ 0x5c));
+    outer.update(inner.finalize());
+    let mut mac = [0u8; 32];
+    mac.copy_from_slice(&outer.finalize());
+    mac
+}
+
+pub(crate) fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+// Answer to a challenge - the nonce is signed as sent, so both sides agree on its bytes
+fn answer(token: &str, nonce: &str) -> String {
+    hex(&hmac_sha256(token.as_bytes(), nonce.as_bytes()))
+}
+
+// Compares every byte so the time taken doesn't reveal how much of the answer was right
+pub(crate) fn answer_matches(expected: &str, received: &str) -> bool {
+    expected.len() == received.len()
+        && expected.bytes().zip(received.bytes()).fold(0u8, |diff, (a, b)| diff | (a ^ b)) == 0
+}
+
+// Lines of /proc/net/dev for the chosen interfaces (all but loopback when none are chosen)
+fn select_lines(counters: &str, interfaces: &[String]) -> Vec<String> {
+    counters
+        .lines()
+        .filter_map(|line| line.split_once(':'))
+        .map(|(iface, fields)| (iface.trim(), fields.trim()))
+        .filter(|(iface, _)| {
+            if interfaces.is_empty() {
+                *iface != "lo"
+            } else {
+                interfaces.iter().any(|wanted| wanted == iface)
+            }
+        })
+        .map(|(iface, fields)| format!("{}: {}", iface, fields))
+        .collect()
+}
+
+/// Run the agent: serve this machine's interface counters to bandwidth mode on other hosts
+pub fn run(listen: &str, port: u16, token: Option<String>, interface: Option<String>) -> Result<()> {
+    let token = token
+        .or_else(|| std::env::var("RUSTWLED_AGENT_TOKEN").ok())
+        .filter(|token| !token.is_empty())
+        .ok_or_else(|| anyhow!("Set a shared token with --token or RUSTWLED_AGENT_TOKEN (the same as agent_token on the bandwidth host)"))?;
+    let interfaces: Vec<String> = interface.as_deref().unwrap_or("")
+        .split(',').map(|iface| iface.trim()).filter(|iface| !iface.is_empty()).map(|iface| iface.to_string()).collect();
+
+    let counters = std::fs::read_to_string(PROC_NET_DEV)
+        .with_context(|| format!("The agent reads {} and only runs on Linux", PROC_NET_DEV))?;
+    let reported = select_lines(&counters, &interfaces);
+    if reported.is_empty() {
+        bail!("None of the interfaces {:?} exist on this host", interfaces);
+    }
+
+    let listener = TcpListener::bind((listen, port)).with_context(|| format!("Failed to listen on {}:{}", listen, port))?;
+    println!("rustwled agent listening on {}:{}", listen, port);
+    println!("Reporting: {}", reported.iter().filter_map(|line| line.split(':').next()).collect::<Vec<_>>().join(", "));
+
+    for stream in listener.incoming() {
+        let stream = match stream {
+            Ok(stream) => stream,
+            Err(e) => {
+                eprintln!("Accept failed: {}", e);
+                continue;
+            }
+        };
+        let token = token.clone();
+        let interfaces = interfaces.clone();
+        thread::spawn(move || {
+            let peer = stream.peer_addr().map(|addr| addr.to_string()).unwrap_or_else(|_| "unknown".to_string());
+            match serve(stream, &token, &interfaces, &peer) {
+                Ok(()) => println!("{} disconnected", peer),
+                Err(e) => eprintln!("{}: {}", peer, e),
+            }
+        });
+    }
+    Ok(())
+}
+
+// One client: check its answer to the challenge, then stream counters until it goes away
+fn serve(mut stream: StdTcpStream, token: &str, interfaces: &[String], peer: &str) -> Result<()> {
+    let nonce = hex(&rand::thread_rng().gen::<[u8; 16]>());
+    writeln!(stream, "{} {} {}", GREETING, PROTOCOL_VERSION, nonce)?;
+
+    stream.set_read_timeout(Some(AUTH_TIMEOUT))?;
+    let mut received = String::new();
+    StdBufReader::new(stream.try_clone()?.take(256)).read_line(&mut received)?;
+    if !answer_matches(&answer(token, &nonce), received.trim()) {
+        let _ = writeln!(stream, "denied");
+        bail!("wrong token");
+    }
+    writeln!(stream, "ok")?;
+    println!("{} connected", peer);
+
+    loop {
+        let counters = std::fs::read_to_string(PROC_NET_DEV)?;
+        for line in select_lines(&counters, interfaces) {
+            writeln!(stream, "{}", line)?;
+        }
+        stream.flush()?;
+        thread::sleep(Duration::from_secs(1));
+    }
+}
+
+/// Read counters from every agent, handing each line (tagged with its host) to `on_line` - until
+/// `shutdown` is set or `on_line` returns false. Agents that drop out are reconnected with backoff
+pub async fn stream_all(hosts: Vec<String>, token: String, shutdown: Arc<AtomicBool>, mut on_line: impl FnMut(String) -> bool) {
+    *STATUS.lock().unwrap() = hosts.iter()
+        .map(|host| AgentStatus { host: host.clone(), connected: false, last_error: None })
+        .collect();
+
+    let (tx, mut rx) = mpsc::channel(256);
+    for host in hosts {
+        tokio::spawn(stream_host(host, token.clone(), shutdown.clone(), tx.clone()));
+    }
+    drop(tx);
+
+    while let Some(line) = rx.recv().await {
+        if !on_line(line) {
+            break;
+        }
+    }
+    // Dropping the receiver ends the per-host tasks on their next line
+    STATUS.lock().unwrap().clear();
+}
+
+async fn stream_host(host: String, token: String, shutdown: Arc<AtomicBool>, tx: mpsc::Sender<String>) {
+    let mut attempt = 0;
+    loop {
+        match session(&host, &token, &shutdown, &tx, &mut attempt).await {
+            Ok(()) => return,
+            Err(e) => update(&host, false, Some(e.to_string())),
+        }
+        attempt += 1;
+        if !remote_link::sleep_unless_shutdown(remote_link::backoff(attempt), &shutdown).await {
+            return;
+        }
+    }
+}
+
+// One connection to an agent - Ok when it's time to stop, Err when it should be retried
+async fn session(host: &str, token: &str, shutdown: &AtomicBool, tx: &mpsc::Sender<String>, attempt: &mut u32) -> Result<()> {
+    let stream = tokio::time::timeout(CONNECT_TIMEOUT, TcpStream::connect(address(host)))
+        .await
+        .map_err(|_| anyhow!("connection timed out"))??;
+    let (read, mut write) = stream.into_split();
+    let mut lines = BufReader::new(read).lines();
+
+    let greeting = tokio::time::timeout(STALL_TIMEOUT, lines.next_line()).await.map_err(|_| anyhow!("no greeting"))??.ok_or_else(|| anyhow!("connection closed"))?;
+    let nonce = match greeting.split_whitespace().collect::<Vec<_>>().as_slice() {
+        [GREETING, version, nonce] if version.parse::<u32>().ok() == Some(PROTOCOL_VERSION) => nonce.to_string(),
+        _ => bail!("not a rustwled agent (or a different version)"),
+    };
+    write.write_all(format!("{}\n", answer(token, &nonce)).as_bytes()).await?;
+    let reply = tokio::time::timeout(STALL_TIMEOUT, lines.next_line()).await.map_err(|_| anyhow!("no reply to the token"))??.unwrap_or_default();
+    if reply != "ok" {
+        bail!("agent refused the token");
+    }
+    update(host, true, None);
+    *attempt = 0;
+
+    loop {
+        if shutdown.load(Ordering::Relaxed) {
+            return Ok(());
+        }
+        match tokio::time::timeout(STALL_TIMEOUT, lines.next_line()).await {
+            Ok(Ok(Some(line))) => {
+                if let Some(line) = tag_line(host, &line) {
+                    if tx.send(line).await.is_err() {
+                        return Ok(());  // Bandwidth mode has exited
+                    }
+                }
+            }
+            Ok(Ok(None)) => bail!("connection closed"),
+            Ok(Err(e)) => bail!("read failed: {}", e),
+            Err(_) => bail!("no counters for {}s", STALL_TIMEOUT.as_secs()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hmac_sha256() {
+        // RFC 4231 test case 2
+        assert_eq!(
+            hex(&hmac_sha256(b"Jefe", b"what do ya want for nothing?")),
+            "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+        assert!(answer_matches(&answer("secret", "abcd"), &answer("secret", "abcd")));
+        assert!(!answer_matches(&answer("secret", "abcd"), &answer("wrong", "abcd")));
+        assert!(!answer_matches(&answer("secret", "abcd"), ""));
+    }
+
+    #[test]
+    fn test_select_and_tag_lines() {
+        let counters = "Inter-|   Receive                            |  Transmit\n \
+            face |bytes    packets errs drop fifo frame compressed multicast|bytes    packets errs drop fifo colls carrier compressed\n    \
+            lo: 100 1 0 0 0 0 0 0 100 1 0 0 0 0 0 0\n  \
+            eth0: 2000 20 0 0 0 0 0 0 3000 30 0 0 0 0 0 0\n";
+
+        let all = select_lines(counters, &[]);
+        assert_eq!(all, ["eth0: 2000 20 0 0 0 0 0 0 3000 30 0 0 0 0 0 0"]);
+        assert_eq!(select_lines(counters, &["lo".to_string()]).len(), 1);
+        assert!(select_lines(counters, &["wlan0".to_string()]).is_empty());
+
+        assert_eq!(tag_line("10.0.0.5:4060", &all[0]).unwrap(), "10.0.0.5/eth0: 2000 20 0 0 0 0 0 0 3000 30 0 0 0 0 0 0");
+        assert_eq!(address("10.0.0.5"), "10.0.0.5:4050");
+        assert_eq!(parse_hosts(" a, b:1 ,,"), ["a", "b:1"]);
+    }
+}
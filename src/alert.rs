@@ -0,0 +1,236 @@
+// Alert Module - Alarm animations raised by external monitoring (/api/alert, or Alertmanager/PagerDuty webhooks)
+// While an alert is active it replaces whatever the running mode draws, until it is cleared or times out
+use anyhow::{anyhow, Result};
+use serde_json::Value;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tokio::sync::broadcast;
+
+use crate::config::{self, BandwidthConfig, ConfigDelta};
+use crate::types::Rgb;
+
+/// Alarm animations an alert can show
+pub const ANIMATIONS: &[&str] = &["flash", "pulse", "chase", "solid"];
+
+// Flash and pulse period, and how fast the chase blocks move (LEDs per second)
+const FLASH_PERIOD: f64 = 0.5;
+const PULSE_PERIOD: f64 = 1.5;
+const CHASE_SPEED: f64 = 30.0;
+const CHASE_BLOCK: usize = 5;
+
+static SETTINGS: Mutex<Option<AlertSettings>> = Mutex::new(None);
+static ACTIVE: Mutex<Option<Alert>> = Mutex::new(None);
+
+// Defaults for alerts that don't pick their own animation, color or timeout
+#[derive(Debug, Clone)]
+struct AlertSettings {
+    animation: String,
+    color: String,
+    timeout: Option<Duration>,  // None = until cleared
+    webhook_enabled: bool,
+}
+
+#[derive(Debug, Clone)]
+struct Alert {
+    name: String,
+    animation: String,
+    color: String,
+    started: Instant,
+    until: Option<Instant>,
+}
+
+/// Take the alert defaults from the config
+pub fn configure(config: &BandwidthConfig) {
+    *SETTINGS.lock().unwrap() = Some(AlertSettings {
+        animation: config.alert_animation.clone(),
+        color: config.alert_color.clone(),
+        timeout: (config.alert_timeout_secs > 0).then(|| Duration::from_secs(config.alert_timeout_secs)),
+        webhook_enabled: config.alert_webhook_enabled,
+    });
+}
+
+fn settings() -> AlertSettings {
+    SETTINGS.lock().unwrap().clone().unwrap_or_else(|| {
+        let config = BandwidthConfig::default();
+        AlertSettings {
+            animation: config.alert_animation,
+            color: config.alert_color,
+            timeout: Some(Duration::from_secs(config.alert_timeout_secs)),
+            webhook_enabled: config.alert_webhook_enabled,
+        }
+    })
+}
+
+/// Whether /api/alert/webhook accepts payloads
+pub fn webhook_enabled() -> bool {
+    settings().webhook_enabled
+}
+
+/// Start (or replace) the active alert - unset options take the configured defaults, timeout 0 = until cleared
+pub fn raise(name: &str, animation: Option<&str>, color: Option<&str>, timeout_secs: Option<u64>) -> Result<()> {
+    let defaults = settings();
+    let animation = animation.map(|a| a.trim().to_lowercase()).unwrap_or(defaults.animation);
+    if !ANIMATIONS.contains(&animation.as_str()) {
+        return Err(anyhow!("Unknown animation '{}' (expected one of: {})", animation, ANIMATIONS.join(", ")));
+    }
+    let color = color.map(|c| c.trim().to_string()).unwrap_or(defaults.color);
+    Rgb::from_hex(&color)?;
+    let timeout = match timeout_secs {
+        Some(0) => None,
+        Some(secs) => Some(Duration::from_secs(secs)),
+        None => defaults.timeout,
+    };
+
+    let started = Instant::now();
+    println!("🚨 Alert raised: {}", name);
+    *ACTIVE.lock().unwrap() = Some(Alert {
+        name: name.to_string(),
+        animation,
+        color,
+        started,
+        until: timeout.map(|t| started + t),
+    });
+    Ok(())
+}
+
+/// End the active alert - with a name, only if that alert is the active one. Returns whether one was cleared
+pub fn clear(name: Option<&str>) -> bool {
+    let mut active = ACTIVE.lock().unwrap();
+    match active.as_ref() {
+        Some(alert) if name.is_none() || name == Some(alert.name.as_str()) => {
+            println!("✅ Alert cleared: {}", alert.name);
+            *active = None;
+            true
+        }
+        _ => false,
+    }
+}
+
+/// The active alert for the API (null fields when there is none)
+pub fn status() -> Value {
+    match ACTIVE.lock().unwrap().as_ref() {
+        Some(alert) => serde_json::json!({
+            "active": true,
+            "name": alert.name,
+            "animation": alert.animation,
+            "color": alert.color,
+            "elapsed_secs": alert.started.elapsed().as_secs(),
+            "remaining_secs": alert.until.map(|until| until.saturating_duration_since(Instant::now()).as_secs()),
+        }),
+        None => serde_json::json!({ "active": false, "name": null }),
+    }
+}
+
+/// The alarm frame to send instead of the mode's own, while an alert is active
+pub fn frame(total_leds: usize) -> Option<Vec<u8>> {
+    let mut active = ACTIVE.lock().unwrap();
+    let alert = active.as_ref()?;
+    if matches!(alert.until, Some(until) if Instant::now() >= until) {
+        println!("⏱️  Alert timed out: {}", alert.name);
+        *active = None;
+        return None;
+    }
+    let color = Rgb::from_hex(&alert.color).unwrap_or(Rgb { r: 255, g: 0, b: 0 });
+    Some(render(&alert.animation, color, alert.started.elapsed().as_secs_f64(), total_leds))
+}
+
+// One frame of an alarm animation, `elapsed` seconds after the alert was raised
+fn render(animation: &str, color: Rgb, elapsed: f64, total_leds: usize) -> Vec<u8> {
+    let scaled = |level: f64| [color.r, color.g, color.b].map(|c| (c as f64 * level).round() as u8);
+    let mut frame = vec![0u8; total_leds * 3];
+    for (i, pixel) in frame.chunks_exact_mut(3).enumerate() {
+        let level = match animation {
+            "flash" => if (elapsed / FLASH_PERIOD) as u64 % 2 == 0 { 1.0 } else { 0.0 },
+            // Breathes between a dim glow and full, never quite dark
+            "pulse" => 0.55 - 0.45 * (elapsed / PULSE_PERIOD * std::f64::consts::TAU).cos(),
+            // Alternating lit and dark blocks moving along the strip
+            "chase" => {
+                let position = (i as f64 - elapsed * CHASE_SPEED).rem_euclid(2.0 * CHASE_BLOCK as f64);
+                if position < CHASE_BLOCK as f64 { 1.0 } else { 0.0 }
+            }
+            _ => 1.0,
+        };
+        pixel.copy_from_slice(&scaled(level));
+    }
+    frame
+}
+
+/// What a monitoring webhook asks for
+#[derive(Debug, PartialEq)]
+pub enum WebhookEvent {
+    Raise(String),
+    Clear(String),
+}
+
+/// Read an Alertmanager or PagerDuty (v3 webhook) payload - None for payloads that aren't either
+pub fn parse_webhook(payload: &Value) -> Option<WebhookEvent> {
+    // Alertmanager: {"status": "firing"|"resolved", "groupLabels": {...}, "commonLabels": {"alertname": ...}, "alerts": [...]}
+    if let Some(status) = payload.get("status").and_then(Value::as_str) {
+        let name = ["groupLabels", "commonLabels"].iter()
+            .find_map(|labels| payload[labels]["alertname"].as_str())
+            .or_else(|| payload["alerts"][0]["labels"]["alertname"].as_str())
+            .unwrap_or("alertmanager")
+            .to_string();
+        return match status {
+            "firing" => Some(WebhookEvent::Raise(name)),
+            "resolved" => Some(WebhookEvent::Clear(name)),
+            _ => None,
+        };
+    }
+
+    // PagerDuty: {"event": {"event_type": "incident.triggered", "data": {"title": ..., "id": ...}}}
+    let event = payload.get("event")?;
+    let data = &event["data"];
+    let name = data["title"].as_str().or_else(|| data["id"].as_str()).unwrap_or("pagerduty").to_string();
+    match event["event_type"].as_str()? {
+        "incident.triggered" | "incident.reopened" | "incident.escalated" => Some(WebhookEvent::Raise(name)),
+        "incident.resolved" => Some(WebhookEvent::Clear(name)),
+        _ => None,
+    }
+}
+
+/// Keep the alert defaults in step with config changes (web UI, API, or file edits)
+pub fn spawn_watcher(config_change_tx: broadcast::Sender<ConfigDelta>) {
+    config::spawn_config_listener(&config_change_tx, &["alert_animation", "alert_color", "alert_timeout_secs", "alert_webhook_enabled"], configure);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render() {
+        let red = Rgb { r: 255, g: 0, b: 0 };
+        assert_eq!(render("flash", red, 0.1, 2), vec![255, 0, 0, 255, 0, 0]);
+        assert_eq!(render("flash", red, 0.6, 2), vec![0; 6]);
+        assert_eq!(render("pulse", red, PULSE_PERIOD / 2.0, 1), vec![255, 0, 0]);
+
+        // Chase: alternating blocks that move along the strip
+        let frame = render("chase", red, 0.0, 10);
+        assert_eq!(frame[0], 255);
+        assert_eq!(frame[CHASE_BLOCK * 3], 0);
+        let frame = render("chase", red, 1.0 / CHASE_SPEED, 10);
+        assert_eq!(frame[0], 0);
+        assert_eq!(frame[3], 255);
+    }
+
+    #[test]
+    fn test_parse_webhook() {
+        let firing = serde_json::json!({
+            "status": "firing",
+            "groupLabels": { "alertname": "DiskFull" },
+            "alerts": [{ "status": "firing", "labels": { "alertname": "DiskFull" } }],
+        });
+        assert_eq!(parse_webhook(&firing), Some(WebhookEvent::Raise("DiskFull".to_string())));
+        let resolved = serde_json::json!({ "status": "resolved", "alerts": [{ "labels": { "alertname": "DiskFull" } }] });
+        assert_eq!(parse_webhook(&resolved), Some(WebhookEvent::Clear("DiskFull".to_string())));
+
+        let triggered = serde_json::json!({
+            "event": { "event_type": "incident.triggered", "data": { "id": "Q1", "title": "Site down" } },
+        });
+        assert_eq!(parse_webhook(&triggered), Some(WebhookEvent::Raise("Site down".to_string())));
+        let acknowledged = serde_json::json!({ "event": { "event_type": "incident.acknowledged", "data": {} } });
+        assert_eq!(parse_webhook(&acknowledged), None);
+        assert_eq!(parse_webhook(&serde_json::json!({ "hello": 1 })), None);
+    }
+}
@@ -0,0 +1,162 @@
+// Alloc Track Module - Count heap allocations (global and per thread) so render loops can show they're allocation-free
+// The counting allocator is only installed in debug builds or with the "alloc-track" feature - release builds
+// use the system allocator directly and every count stays at 0
+#[cfg(any(debug_assertions, feature = "alloc-track"))]
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+use crate::live_stats;
+
+// Frames a loop renders before its counter starts checking - buffers grow to size and caches fill during these
+const WARMUP_FRAMES: u64 = 120;
+
+// Minimum time between allocation reports from one loop, so a loop that allocates every frame doesn't bury the other footer notices
+const REPORT_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Whether allocations are being counted in this build
+pub const COUNTING: bool = cfg!(any(debug_assertions, feature = "alloc-track"));
+
+// Two relaxed atomic adds and a thread-local increment per allocation
+#[cfg(any(debug_assertions, feature = "alloc-track"))]
+struct CountingAllocator;
+
+static ALLOCATIONS: AtomicU64 = AtomicU64::new(0);
+static ALLOCATED_BYTES: AtomicU64 = AtomicU64::new(0);
+
+thread_local! {
+    // Const-initialized with no destructor, so touching it never allocates (safe inside the allocator)
+    static THREAD_ALLOCATIONS: Cell<u64> = const { Cell::new(0) };
+}
+
+#[cfg(any(debug_assertions, feature = "alloc-track"))]
+fn count(bytes: usize) {
+    ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+    ALLOCATED_BYTES.fetch_add(bytes as u64, Ordering::Relaxed);
+    let _ = THREAD_ALLOCATIONS.try_with(|c| c.set(c.get() + 1));
+}
+
+#[cfg(any(debug_assertions, feature = "alloc-track"))]
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        count(layout.size());
+        System.alloc(layout)
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        count(layout.size());
+        System.alloc_zeroed(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        count(new_size);
+        System.realloc(ptr, layout, new_size)
+    }
+}
+
+#[cfg(any(debug_assertions, feature = "alloc-track"))]
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+/// Allocations made by the whole process so far
+pub fn allocations() -> u64 {
+    ALLOCATIONS.load(Ordering::Relaxed)
+}
+
+/// Bytes requested by the whole process so far (not freed bytes - a running total)
+pub fn allocated_bytes() -> u64 {
+    ALLOCATED_BYTES.load(Ordering::Relaxed)
+}
+
+/// Allocations made by the calling thread so far
+pub fn thread_allocations() -> u64 {
+    THREAD_ALLOCATIONS.with(|c| c.get())
+}
+
+/// Debug counter for a render loop - counts frames that allocated once the loop has warmed up
+/// Only the loop's own thread is counted, so the TUI, web server and audio callback don't show up
+/// Debug builds print a warning (at most every REPORT_INTERVAL) when steady-state frames allocate
+pub struct FrameAllocCounter {
+    name: &'static str,
+    frames: u64,
+    allocating_frames: u64,  // Frames after warmup that allocated
+    allocations: u64,  // Allocations made by those frames
+    frame_start: u64,
+    last_report: Option<Instant>,
+}
+
+impl FrameAllocCounter {
+    pub fn new(name: &'static str) -> Self {
+        Self {
+            name,
+            frames: 0,
+            allocating_frames: 0,
+            allocations: 0,
+            frame_start: 0,
+            last_report: None,
+        }
+    }
+
+    pub fn begin_frame(&mut self) {
+        self.frame_start = thread_allocations();
+    }
+
+    pub fn end_frame(&mut self) {
+        let allocated = thread_allocations() - self.frame_start;
+        self.frames += 1;
+        if self.frames <= WARMUP_FRAMES || allocated == 0 {
+            return;
+        }
+
+        self.allocating_frames += 1;
+        self.allocations += allocated;
+
+        if cfg!(debug_assertions) && self.last_report.map(|t| t.elapsed() >= REPORT_INTERVAL).unwrap_or(true) {
+            live_stats::record_notice(format!(
+                "[alloc] {}: {} of {} steady-state frames allocated ({} allocations, {} in the last frame)",
+                self.name,
+                self.allocating_frames,
+                self.frames - WARMUP_FRAMES,
+                self.allocations,
+                allocated
+            ));
+            self.last_report = Some(Instant::now());
+        }
+    }
+
+    /// Frames after warmup that allocated - 0 means the loop is allocation-free in steady state
+    pub fn allocating_frames(&self) -> u64 {
+        self.allocating_frames
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[cfg(any(debug_assertions, feature = "alloc-track"))]
+    fn test_frame_alloc_counter() {
+        let mut counter = FrameAllocCounter::new("test");
+        let mut buffer: Vec<u8> = Vec::with_capacity(64);
+
+        for _ in 0..WARMUP_FRAMES + 10 {
+            counter.begin_frame();
+            buffer.clear();
+            buffer.extend_from_slice(&[1, 2, 3]);
+            counter.end_frame();
+        }
+        assert_eq!(counter.allocating_frames(), 0);
+
+        counter.begin_frame();
+        let allocated = std::hint::black_box(vec![0u8; 128]);
+        counter.end_frame();
+        drop(allocated);
+        assert_eq!(counter.allocating_frames(), 1);
+    }
+}
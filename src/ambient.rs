@@ -0,0 +1,211 @@
+// Ambient Module - Auto-brightness from a room light sensor: global_brightness is scaled between
+// min and max as the room gets darker or brighter
+use std::fs;
+use std::io::{Read, Write};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, Instant};
+use tokio::sync::broadcast;
+
+use crate::config::{self, BandwidthConfig, ConfigDelta};
+
+// Linux IIO light sensors (BH1750, TSL2561, VEML7700, ...) expose lux here
+const IIO_DEVICES: &str = "/sys/bus/iio/devices";
+
+// Sensor poll rate, and how often the output scale steps toward the sensor's target
+const READ_INTERVAL: Duration = Duration::from_secs(1);
+const STEP_INTERVAL: Duration = Duration::from_millis(100);
+
+// Time constant of the brightness smoothing - big steps in room light take a few of these to settle
+const RESPONSE_SECS: f64 = 3.0;
+
+const HTTP_TIMEOUT: Duration = Duration::from_secs(2);
+
+// Current multiplier for global_brightness (f64 bits) - 1.0 while auto-brightness is off
+static SCALE: AtomicU64 = AtomicU64::new(0x3FF0_0000_0000_0000);  // 1.0
+
+static SETTINGS: Mutex<Option<AmbientSettings>> = Mutex::new(None);
+
+#[derive(Debug, Clone, PartialEq)]
+struct AmbientSettings {
+    source: String,  // "iio" or "http"
+    sensor: String,  // IIO file (empty = first sensor found) or HTTP URL
+    min_lux: f64,
+    max_lux: f64,
+    min_brightness: f64,
+    max_brightness: f64,
+}
+
+/// Take the sensor settings from the config - "none" turns auto-brightness off and restores full scale
+pub fn configure(config: &BandwidthConfig) {
+    let settings = (config.ambient_source != "none").then(|| AmbientSettings {
+        source: config.ambient_source.clone(),
+        sensor: config.ambient_sensor.clone(),
+        min_lux: config.ambient_min_lux,
+        max_lux: config.ambient_max_lux,
+        min_brightness: config.ambient_min_brightness,
+        max_brightness: config.ambient_max_brightness,
+    });
+    if settings.is_none() {
+        SCALE.store(1.0f64.to_bits(), Ordering::Relaxed);
+    }
+    *SETTINGS.lock().unwrap() = settings;
+}
+
+/// Apply the room-light scale to a brightness override (frames sent without one are left as-is)
+pub fn scale(brightness: Option<f64>) -> Option<f64> {
+    brightness.map(|b| b * f64::from_bits(SCALE.load(Ordering::Relaxed)))
+}
+
+/// Brightness multiplier for a light level - logarithmic between the lux limits, like the eye
+fn target_scale(lux: f64, settings: &AmbientSettings) -> f64 {
+    let (low, high) = ((settings.min_lux + 1.0).ln(), (settings.max_lux + 1.0).ln());
+    let t = if high > low { (((lux.max(0.0) + 1.0).ln() - low) / (high - low)).clamp(0.0, 1.0) } else { 1.0 };
+    settings.min_brightness + (settings.max_brightness - settings.min_brightness) * t
+}
+
+/// Poll the sensor in the background and ease the brightness scale toward the room level
+pub fn spawn_sensor() {
+    thread::spawn(|| {
+        let mut last_read: Option<Instant> = None;
+        let mut target = None;
+        loop {
+            thread::sleep(STEP_INTERVAL);
+            let Some(settings) = SETTINGS.lock().unwrap().clone() else {
+                last_read = None;
+                target = None;
+                continue;
+            };
+
+            if !matches!(last_read, Some(at) if at.elapsed() < READ_INTERVAL) {
+                last_read = Some(Instant::now());
+                match read_lux(&settings) {
+                    Some(lux) => target = Some(target_scale(lux, &settings)),
+                    None => {
+                        if target.is_some() {
+                            eprintln!("⚠️  Ambient light sensor unavailable ({}) - holding brightness", settings.source);
+                        }
+                        target = None;
+                    }
+                }
+            }
+
+            // Exponential approach - steady fades instead of jumps when a lamp switches on
+            if let Some(target) = target {
+                let current = f64::from_bits(SCALE.load(Ordering::Relaxed));
+                let step = 1.0 - (-STEP_INTERVAL.as_secs_f64() / RESPONSE_SECS).exp();
+                SCALE.store((current + (target - current) * step).to_bits(), Ordering::Relaxed);
+            }
+        }
+    });
+}
+
+/// Keep the sensor settings in step with config changes (web UI, API, or file edits)
+pub fn spawn_watcher(config_change_tx: broadcast::Sender<ConfigDelta>) {
+    config::spawn_config_listener(&config_change_tx, &["ambient_source", "ambient_sensor", "ambient_min_lux", "ambient_max_lux", "ambient_min_brightness", "ambient_max_brightness"], configure);
+}
+
+// Current light level in lux, or None if the sensor can't be read
+fn read_lux(settings: &AmbientSettings) -> Option<f64> {
+    match settings.source.as_str() {
+        "iio" => {
+            let path = if settings.sensor.is_empty() { find_iio_sensor()? } else { PathBuf::from(&settings.sensor) };
+            read_iio(&path)
+        }
+        "http" => parse_lux(&http_get(&settings.sensor)?),
+        _ => None,
+    }
+}
+
+// First IIO device with an illuminance channel
+fn find_iio_sensor() -> Option<PathBuf> {
+    let mut devices: Vec<_> = fs::read_dir(IIO_DEVICES).ok()?.filter_map(|e| e.ok()).map(|e| e.path()).collect();
+    devices.sort();
+    devices.iter().find_map(|device| {
+        ["in_illuminance_input", "in_illuminance_raw", "in_illuminance0_input", "in_illuminance0_raw"]
+            .iter()
+            .map(|name| device.join(name))
+            .find(|path| path.exists())
+    })
+}
+
+// Read an IIO channel - *_raw channels are converted with the device's scale and offset when present
+fn read_iio(path: &Path) -> Option<f64> {
+    let read = |p: &Path| fs::read_to_string(p).ok().and_then(|s| s.trim().parse::<f64>().ok());
+    let value = read(path)?;
+    let name = path.file_name()?.to_str()?;
+    match name.strip_suffix("_raw") {
+        Some(channel) => {
+            let dir = path.parent()?;
+            let offset = read(&dir.join(format!("{}_offset", channel))).unwrap_or(0.0);
+            let scale = read(&dir.join(format!("{}_scale", channel))).unwrap_or(1.0);
+            Some((value + offset) * scale)
+        }
+        None => Some(value),
+    }
+}
+
+// Body of a plain HTTP GET (http://host[:port]/path)
+fn http_get(url: &str) -> Option<String> {
+    let rest = url.strip_prefix("http://")?;
+    let (host_port, path) = match rest.find('/') {
+        Some(i) => (&rest[..i], &rest[i..]),
+        None => (rest, "/"),
+    };
+    let addr = if host_port.contains(':') { host_port.to_socket_addrs() } else { (host_port, 80).to_socket_addrs() }.ok()?.next()?;
+    let mut stream = TcpStream::connect_timeout(&addr, HTTP_TIMEOUT).ok()?;
+    stream.set_read_timeout(Some(HTTP_TIMEOUT)).ok()?;
+    write!(stream, "GET {} HTTP/1.0\r\nHost: {}\r\n\r\n", path, host_port).ok()?;
+
+    let mut response = Vec::new();
+    stream.read_to_end(&mut response).ok()?;
+    let response = String::from_utf8_lossy(&response);
+    Some(response.split_once("\r\n\r\n")?.1.to_string())
+}
+
+/// Lux from a sensor's HTTP response - a bare number, or JSON with a "lux", "illuminance" or "value" field
+fn parse_lux(body: &str) -> Option<f64> {
+    let body = body.trim();
+    if let Ok(lux) = body.parse::<f64>() {
+        return Some(lux);
+    }
+    let json: serde_json::Value = serde_json::from_str(body).ok()?;
+    ["lux", "illuminance", "value"].iter().find_map(|key| {
+        let field = &json[*key];
+        field.as_f64().or_else(|| field.as_str().and_then(|s| s.trim().parse().ok()))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_target_scale() {
+        let settings = AmbientSettings {
+            source: "iio".to_string(),
+            sensor: String::new(),
+            min_lux: 0.0,
+            max_lux: 999.0,
+            min_brightness: 0.2,
+            max_brightness: 1.0,
+        };
+        assert_eq!(target_scale(0.0, &settings), 0.2);
+        assert_eq!(target_scale(5000.0, &settings), 1.0);
+
+        // Logarithmic - ~31 lux (a dim room) is halfway
+        let mid = target_scale(30.62, &settings);
+        assert!((mid - 0.6).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_parse_lux() {
+        assert_eq!(parse_lux("123.5\n"), Some(123.5));
+        assert_eq!(parse_lux(r#"{"lux": 42}"#), Some(42.0));
+        assert_eq!(parse_lux(r#"{"state": "on", "value": "7.5"}"#), Some(7.5));
+        assert_eq!(parse_lux("not a sensor"), None);
+    }
+}
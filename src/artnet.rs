@@ -0,0 +1,173 @@
+// Art-Net Module - ArtDmx and ArtSync packets from lighting consoles and media servers, and ArtDmx output
+// to Art-Net nodes (e.g. Falcon and Advatek pixel controllers)
+use anyhow::{anyhow, Result};
+use std::net::{SocketAddr, ToSocketAddrs, UdpSocket};
+use std::time::Duration;
+
+use crate::config::ArtnetUniverseConfig;
+use crate::sacn;
+
+/// UDP port Art-Net nodes listen on
+pub const ARTNET_PORT: u16 = 6454;
+
+/// RGB LEDs carried per universe - 510 of the 512 DMX channels
+pub const LEDS_PER_UNIVERSE: usize = 170;
+
+/// Without an ArtSync for this long, frames go back to completing on their last universe
+pub const SYNC_TIMEOUT: Duration = Duration::from_secs(4);
+
+const ARTNET_ID: &[u8; 8] = b"Art-Net\0";
+const OP_DMX: u16 = 0x5000;
+const OP_SYNC: u16 = 0x5200;
+const PROTOCOL_VERSION: u16 = 14;
+const DMX_HEADER_LEN: usize = 18;
+const SYNC_LEN: usize = 14;
+
+/// An Art-Net packet rustwled acts on
+#[derive(Debug, PartialEq)]
+pub enum ArtnetPacket<'a> {
+    Dmx { universe: u16, data: &'a [u8] },  // 15-bit port-address (net, sub-net, universe)
+    Sync,  // Output the universes received so far, all at once
+}
+
+/// Parse an ArtDmx or ArtSync packet - None for other opcodes (polls, etc.) and anything malformed
+pub fn parse_packet(packet: &[u8]) -> Option<ArtnetPacket<'_>> {
+    if packet.len() < SYNC_LEN || &packet[..8] != ARTNET_ID {
+        return None;
+    }
+    match u16::from_le_bytes([packet[8], packet[9]]) {
+        OP_DMX if packet.len() >= DMX_HEADER_LEN => {
+            let universe = u16::from_le_bytes([packet[14], packet[15] & 0x7f]);
+            let length = u16::from_be_bytes([packet[16], packet[17]]) as usize;
+            let end = (DMX_HEADER_LEN + length).min(packet.len());
+            Some(ArtnetPacket::Dmx { universe, data: &packet[DMX_HEADER_LEN..end] })
+        }
+        OP_SYNC => Some(ArtnetPacket::Sync),
+        _ => None,
+    }
+}
+
+/// Universe mapping for `leds` LEDs - the configured mapping, or consecutive universes from `first_universe`
+pub fn universe_map(configured: &[ArtnetUniverseConfig], first_universe: u16, leds: usize) -> Vec<ArtnetUniverseConfig> {
+    if !configured.is_empty() {
+        return configured.to_vec();
+    }
+    (0..leds.div_ceil(LEDS_PER_UNIVERSE).max(1))
+        .map(|i| ArtnetUniverseConfig {
+            universe: first_universe.saturating_add(i as u16),
+            led_offset: i * LEDS_PER_UNIVERSE,
+            led_count: (leds - (i * LEDS_PER_UNIVERSE).min(leds)).min(LEDS_PER_UNIVERSE),
+        })
+        .collect()
+}
+
+/// 15-bit port-address of a universe: net (0-127), sub-net (0-15), universe (0-15)
+pub fn port_address(net: u8, subnet: u8, universe: u16) -> u16 {
+    (((net as u16) & 0x7f) << 8) | (((subnet as u16) & 0x0f) << 4) | (universe & 0x0f)
+}
+
+/// Sends RGB frames as ArtDmx packets, unicast to one node
+pub struct ArtnetSender {
+    socket: UdpSocket,
+    dest: SocketAddr,
+    port_address: u16,  // Port-address of the first universe - later universes follow on from it
+    start_channel: u16,
+    sequence: u8,  // 1-255 (0 would turn off the node's reordering check)
+    universes: Vec<(u16, Vec<u8>)>,  // Channel data of the frame being sent, per universe
+}
+
+impl ArtnetSender {
+    /// Sender for a node at `host` whose LEDs start at `start_channel` of `port_address`
+    pub fn new(host: &str, port_address: u16, start_channel: u16) -> Result<Self> {
+        let dest = (host, ARTNET_PORT).to_socket_addrs()?.next().ok_or_else(|| anyhow!("Could not resolve {}", host))?;
+        let socket = UdpSocket::bind("0.0.0.0:0")?;
+        Ok(ArtnetSender { socket, dest, port_address, start_channel, sequence: 0, universes: Vec::new() })
+    }
+
+    /// Send RGB data for the LEDs from `led_offset` on, one ArtDmx packet per universe they span
+    /// (LEDs lie across universes as they do for sACN). Returns the number of LED bytes sent
+    pub fn send(&mut self, rgb: &[u8], led_offset: usize) -> Result<usize> {
+        let mut universes = std::mem::take(&mut self.universes);
+        sacn::dmx_universes(rgb, self.port_address, self.start_channel, led_offset, &mut universes);
+
+        self.sequence = self.sequence % 255 + 1;
+        let mut result = Ok(rgb.len());
+        for (port_address, data) in &universes {
+            let packet = dmx_packet(*port_address & 0x7fff, self.sequence, data);
+            if let Err(e) = self.socket.send_to(&packet, self.dest) {
+                result = Err(anyhow!("Art-Net universe {}: {}", port_address, e));
+            }
+        }
+        self.universes = universes;
+        result
+    }
+}
+
+// An ArtDmx packet carrying `dmx` from channel 1
+fn dmx_packet(port_address: u16, sequence: u8, dmx: &[u8]) -> Vec<u8> {
+    let mut p = ARTNET_ID.to_vec();
+    p.extend_from_slice(&OP_DMX.to_le_bytes());
+    p.extend_from_slice(&PROTOCOL_VERSION.to_be_bytes());
+    p.push(sequence);
+    p.push(0);  // Physical input port - informational only
+    p.extend_from_slice(&port_address.to_le_bytes());  // SubUni, then Net
+    // Length must be even, between 2 and 512
+    let dmx = &dmx[..dmx.len().min(512)];
+    let length = (dmx.len() + dmx.len() % 2).max(2);
+    p.extend_from_slice(&(length as u16).to_be_bytes());
+    p.extend_from_slice(dmx);
+    p.resize(DMX_HEADER_LEN + length, 0);
+    p
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dmx_packet(net: u8, sub_uni: u8, dmx: &[u8]) -> Vec<u8> {
+        let mut p = ARTNET_ID.to_vec();
+        p.extend_from_slice(&OP_DMX.to_le_bytes());
+        p.extend_from_slice(&[0, 14, 1, 0, sub_uni, net]);
+        p.extend_from_slice(&(dmx.len() as u16).to_be_bytes());
+        p.extend_from_slice(dmx);
+        p
+    }
+
+    #[test]
+    fn test_parse_packet() {
+        assert_eq!(parse_packet(&dmx_packet(1, 0x23, &[1, 2, 3])), Some(ArtnetPacket::Dmx { universe: 0x123, data: &[1, 2, 3] }));
+
+        let mut sync = ARTNET_ID.to_vec();
+        sync.extend_from_slice(&OP_SYNC.to_le_bytes());
+        sync.extend_from_slice(&[0, 14, 0, 0]);
+        assert_eq!(parse_packet(&sync), Some(ArtnetPacket::Sync));
+
+        // ArtPoll and non-Art-Net packets are ignored
+        let mut poll = sync.clone();
+        poll[9] = 0x20;
+        assert!(parse_packet(&poll).is_none());
+        assert!(parse_packet(b"Not-Net\0 and more bytes").is_none());
+    }
+
+    #[test]
+    fn test_dmx_packet_round_trip() {
+        let address = port_address(1, 2, 3);
+        assert_eq!(address, 0x123);
+        let packet = dmx_packet(address, 7, &[10, 20, 30]);
+        assert_eq!(packet.len(), DMX_HEADER_LEN + 4);  // Padded to an even length
+        assert_eq!(packet[12], 7);
+        assert_eq!(parse_packet(&packet), Some(ArtnetPacket::Dmx { universe: 0x123, data: &[10, 20, 30, 0] }));
+    }
+
+    #[test]
+    fn test_universe_map() {
+        // 400 LEDs from universe 2: 170 + 170 + 60
+        let map = universe_map(&[], 2, 400);
+        assert_eq!(map.len(), 3);
+        assert_eq!((map[2].universe, map[2].led_offset, map[2].led_count), (4, 340, 60));
+
+        // A configured mapping is used as-is
+        let configured = [ArtnetUniverseConfig { universe: 9, led_offset: 100, led_count: 50 }];
+        assert_eq!(universe_map(&configured, 0, 400), configured.to_vec());
+    }
+}
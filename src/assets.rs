@@ -0,0 +1,51 @@
+// Assets Module - Web UI files embedded at compile time, with an optional on-disk override directory
+use rust_embed::RustEmbed;
+use std::borrow::Cow;
+use std::path::{Component, Path, PathBuf};
+
+/// Everything under web/ is compiled into the binary
+#[derive(RustEmbed)]
+#[folder = "web/"]
+struct WebAssets;
+
+/// A web UI file ready to serve
+pub struct Asset {
+    pub data: Cow<'static, [u8]>,
+    pub mime: String,
+}
+
+// Only plain relative paths may be looked up (no "..", absolute, or drive prefixes)
+fn safe_relative_path(path: &str) -> Option<PathBuf> {
+    let path = Path::new(path.trim_start_matches('/'));
+    if path.as_os_str().is_empty() || !path.components().all(|c| matches!(c, Component::Normal(_))) {
+        return None;
+    }
+    Some(path.to_path_buf())
+}
+
+/// Look up a web UI file - a file in override_dir (if set) wins over the embedded copy
+pub fn load(path: &str, override_dir: &str) -> Option<Asset> {
+    let relative = safe_relative_path(path)?;
+    let mime = mime_guess::from_path(&relative).first_or_octet_stream().to_string();
+
+    if !override_dir.is_empty() {
+        if let Ok(data) = std::fs::read(Path::new(override_dir).join(&relative)) {
+            return Some(Asset { data: Cow::Owned(data), mime });
+        }
+    }
+
+    WebAssets::get(&relative.to_string_lossy()).map(|file| Asset { data: file.data, mime })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_embedded_assets() {
+        assert!(load("index.html", "").is_some());
+        assert_eq!(load("/login.html", "").map(|a| a.mime), Some("text/html".to_string()));
+        assert!(load("../Cargo.toml", "").is_none());
+        assert!(load("", "").is_none());
+    }
+}
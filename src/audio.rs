@@ -124,3 +124,243 @@ pub fn find_audio_device(device_name: &str) -> Result<Device> {
     Err(anyhow!("Audio device '{}' not found", device_name))
 }
 
+
+// Low-pass cutoff ahead of downsampling, as a fraction of the output rate (just under its Nyquist frequency)
+const ANTI_ALIAS_CUTOFF: f32 = 0.45;
+
+/// Streaming resampler for interleaved audio, from a device's rate to the fixed analysis rate
+/// Linear interpolation between frames; when downsampling, a low-pass runs first so content above the
+/// new Nyquist frequency doesn't fold back into the spectrum
+pub struct Resampler {
+    channels: usize,
+    step: f64,  // Input frames per output frame
+    position: f64,  // Next output frame, in frames from `previous` (0.0) to the incoming frame (1.0)
+    previous: Vec<f32>,
+    frame: Vec<f32>,  // Incoming frame being gathered from the interleaved samples
+    filters: Vec<LowPass>,  // One per channel when downsampling
+}
+
+impl Resampler {
+    pub fn new(input_rate: f32, output_rate: f32, channels: usize) -> Self {
+        let channels = channels.max(1);
+        let step = input_rate as f64 / output_rate as f64;
+        let filters = if step > 1.0 {
+            (0..channels).map(|_| LowPass::new(output_rate * ANTI_ALIAS_CUTOFF, input_rate)).collect()
+        } else {
+            Vec::new()
+        };
+        Resampler {
+            channels,
+            step,
+            position: 1.0,
+            previous: vec![0.0; channels],
+            frame: Vec::with_capacity(channels),
+            filters,
+        }
+    }
+
+    /// Resample interleaved samples, appending the result to `output`
+    pub fn process(&mut self, input: impl IntoIterator<Item = f32>, output: &mut Vec<f32>) {
+        if self.step == 1.0 {
+            output.extend(input);
+            return;
+        }
+
+        for sample in input {
+            let sample = match self.filters.get_mut(self.frame.len()) {
+                Some(filter) => filter.process(sample),
+                None => sample,
+            };
+            self.frame.push(sample);
+            if self.frame.len() < self.channels {
+                continue;
+            }
+
+            while self.position <= 1.0 {
+                let t = self.position as f32;
+                output.extend(self.previous.iter().zip(&self.frame).map(|(&a, &b)| a + (b - a) * t));
+                self.position += self.step;
+            }
+            self.position -= 1.0;
+            std::mem::swap(&mut self.previous, &mut self.frame);
+            self.frame.clear();
+        }
+    }
+}
+
+// Second-order Butterworth low-pass (RBJ cookbook biquad)
+struct LowPass {
+    b0: f32,
+    b1: f32,
+    b2: f32,
+    a1: f32,
+    a2: f32,
+    x1: f32,
+    x2: f32,
+    y1: f32,
+    y2: f32,
+}
+
+impl LowPass {
+    fn new(cutoff: f32, sample_rate: f32) -> Self {
+        let w0 = 2.0 * std::f32::consts::PI * cutoff / sample_rate;
+        let alpha = w0.sin() / (2.0 * std::f32::consts::FRAC_1_SQRT_2);
+        let cos = w0.cos();
+        let a0 = 1.0 + alpha;
+        LowPass {
+            b0: (1.0 - cos) / 2.0 / a0,
+            b1: (1.0 - cos) / a0,
+            b2: (1.0 - cos) / 2.0 / a0,
+            a1: -2.0 * cos / a0,
+            a2: (1.0 - alpha) / a0,
+            x1: 0.0,
+            x2: 0.0,
+            y1: 0.0,
+            y2: 0.0,
+        }
+    }
+
+    fn process(&mut self, x: f32) -> f32 {
+        let y = self.b0 * x + self.b1 * self.x1 + self.b2 * self.x2 - self.a1 * self.y1 - self.a2 * self.y2;
+        self.x2 = self.x1;
+        self.x1 = x;
+        self.y2 = self.y1;
+        self.y1 = y;
+        y
+    }
+}
+
+// The noise floor drops quickly to a quieter level but creeps up slowly, so it settles on the steady
+// background (fan hum, mains buzz) and music has to hold a note for a long time to raise it
+const FLOOR_FALL_SECS: f32 = 1.0;
+const FLOOR_RISE_SECS: f32 = 30.0;
+
+// Calibration after a reset: the floor rises as fast as it falls, so it starts from the room's level
+const CALIBRATION_SECS: f32 = 3.0;
+
+/// Per-bin noise floor learned continuously from FFT magnitudes, used as an automatic spectrum threshold
+pub struct NoiseFloor {
+    floors: Vec<f32>,
+    learned_secs: f32,  // Time learned since the last reset
+}
+
+impl NoiseFloor {
+    pub fn new() -> Self {
+        NoiseFloor { floors: Vec::new(), learned_secs: 0.0 }
+    }
+
+    /// Forget the learned floor and calibrate again
+    pub fn reset(&mut self) {
+        self.floors.clear();
+        self.learned_secs = 0.0;
+    }
+
+    /// Learn from one frame of magnitudes, then take the floor plus `margin_db` off each bin so only what
+    /// stands above the background remains - returns the largest remaining magnitude
+    pub fn gate(&mut self, bins: &mut [f32], margin_db: f32, frame_secs: f32) -> f32 {
+        if self.floors.len() != bins.len() {
+            // New FFT size - calibrate from scratch
+            self.floors = bins.to_vec();
+            self.learned_secs = 0.0;
+        }
+
+        let fall = (frame_secs / FLOOR_FALL_SECS).min(1.0);
+        let rise = if self.learned_secs < CALIBRATION_SECS { fall } else { (frame_secs / FLOOR_RISE_SECS).min(1.0) };
+        self.learned_secs += frame_secs;
+        let margin = 10.0_f32.powf(margin_db / 20.0);
+
+        let mut max = 0.0_f32;
+        for (mag, floor) in bins.iter_mut().zip(self.floors.iter_mut()) {
+            *floor += (*mag - *floor) * if *mag < *floor { fall } else { rise };
+            *mag = (*mag - *floor * margin).max(0.0);
+            max = max.max(*mag);
+        }
+        max
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Sine at `freq` Hz, interleaved on every channel
+    fn sine(freq: f32, rate: f32, frames: usize, channels: usize) -> Vec<f32> {
+        (0..frames)
+            .flat_map(|i| std::iter::repeat((2.0 * std::f32::consts::PI * freq * i as f32 / rate).sin()).take(channels))
+            .collect()
+    }
+
+    fn zero_crossings(samples: &[f32]) -> usize {
+        samples.windows(2).filter(|w| (w[0] < 0.0) != (w[1] < 0.0)).count()
+    }
+
+    #[test]
+    fn test_resampler_keeps_pitch() {
+        // One second of 1 kHz at 44.1 kHz stereo, fed in uneven blocks, comes out as one second at 48 kHz
+        let input = sine(1000.0, 44100.0, 44100, 2);
+        let mut resampler = Resampler::new(44100.0, 48000.0, 2);
+        let mut output = Vec::new();
+        for block in input.chunks(333) {
+            resampler.process(block.iter().copied(), &mut output);
+        }
+        let frames = output.len() / 2;
+        assert!((47990..=48010).contains(&frames), "{} frames", frames);
+
+        let left: Vec<f32> = output.iter().step_by(2).copied().collect();
+        assert!((1995..=2005).contains(&zero_crossings(&left)));
+        assert_eq!(output[100], output[101]);  // Channels stay in step
+    }
+
+    #[test]
+    fn test_resampler_downsampling() {
+        let mut resampler = Resampler::new(96000.0, 48000.0, 1);
+        let mut output = Vec::new();
+        resampler.process(sine(1000.0, 96000.0, 96000, 1), &mut output);
+        assert!((47990..=48010).contains(&output.len()));
+        assert!((1995..=2005).contains(&zero_crossings(&output)));
+
+        // 40 kHz is above the new Nyquist frequency - filtered out instead of aliasing down to 8 kHz
+        let mut resampler = Resampler::new(96000.0, 48000.0, 1);
+        let mut output = Vec::new();
+        resampler.process(sine(40000.0, 96000.0, 9600, 1), &mut output);
+        let peak = output[1000..].iter().fold(0.0_f32, |max, &v| max.max(v.abs()));
+        assert!(peak < 0.1, "peak {}", peak);
+
+        // Same rate passes straight through
+        let mut resampler = Resampler::new(48000.0, 48000.0, 2);
+        let mut output = Vec::new();
+        resampler.process([0.1, 0.2, 0.3, 0.4], &mut output);
+        assert_eq!(output, vec![0.1, 0.2, 0.3, 0.4]);
+    }
+
+    #[test]
+    fn test_noise_floor() {
+        // A steady hum in bin 1 over quiet hiss - learned and gated out after calibration
+        let mut floor = NoiseFloor::new();
+        let frame_secs = 1.0 / 60.0;
+        let room = |i: usize| [0.01 + (i % 3) as f32 * 0.002, 0.5 + (i % 2) as f32 * 0.02, 0.01];
+        for i in 0..300 {
+            floor.gate(&mut room(i), 10.0, frame_secs);
+        }
+        let mut bins = room(300);
+        assert_eq!(floor.gate(&mut bins, 10.0, frame_secs), 0.0);
+
+        // Quiet music above the floor still comes through, even in the hum's bin
+        let mut bins = [0.1_f32, 2.5, 0.01];
+        let max = floor.gate(&mut bins, 10.0, frame_secs);
+        assert!(bins[0] > 0.0 && bins[1] > 0.0 && bins[2] == 0.0);
+        assert_eq!(max, bins[1]);
+
+        // A held note lasting a few seconds barely raises the floor
+        for _ in 0..300 {
+            floor.gate(&mut [0.1_f32, 0.5, 0.01], 10.0, frame_secs);
+        }
+        let mut bins = [0.1_f32, 0.5, 0.01];
+        floor.gate(&mut bins, 10.0, frame_secs);
+        assert!(bins[0] > 0.0);
+
+        // A different FFT size starts over
+        let mut bins = [1.0_f32; 4];
+        assert_eq!(floor.gate(&mut bins, 0.0, frame_secs), 0.0);
+    }
+}
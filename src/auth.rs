@@ -0,0 +1,362 @@
+// Auth Module - Argon2 password hashing and cookie sessions for the web UI
+use anyhow::{anyhow, Result};
+use argon2::password_hash::{rand_core::OsRng, PasswordHash, PasswordHasher, PasswordVerifier, SaltString};
+use argon2::Argon2;
+use rand::Rng;
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+use tokio::sync::broadcast;
+
+use crate::config::{self, ApiTokenConfig, BandwidthConfig, ConfigDelta};
+
+// Session cookie name and CSRF header expected on state-changing requests
+pub const SESSION_COOKIE: &str = "rustwled_session";
+pub const CSRF_HEADER: &str = "x-csrf-token";
+
+// Sessions expire after this long regardless of activity
+pub const SESSION_TTL: Duration = Duration::from_secs(24 * 60 * 60);
+
+// Config fields that change system-level behaviour (remote SSH commands, agents, the web server and its
+// TLS/auth, instance sync) - admin only
+pub const ADMIN_CONFIG_FIELDS: &[&str] = &[
+    "ssh_host",
+    "ssh_user",
+    "agent_hosts",
+    "agent_token",
+    "httpd_enabled",
+    "httpd_https_enabled",
+    "httpd_ip",
+    "httpd_port",
+    "httpd_assets_dir",
+    "httpd_auth_enabled",
+    "httpd_auth_user",
+    "httpd_auth_pass",
+    "httpd_auth_pass_hash",
+    "httpd_rate_limit_per_minute",
+    "httpd_login_max_attempts",
+    "httpd_login_lockout_secs",
+    "httpd_users",
+    "httpd_mtls_enabled",
+    "httpd_mtls_ca_path",
+    "acme_enabled",
+    "acme_domain",
+    "acme_email",
+    "acme_staging",
+    "acme_http_port",
+    "sync_role",
+    "sync_group",
+    "sync_key",
+    "sync_address",
+    "sync_port",
+    "sync_interval_ms",
+];
+
+/// Access level of a user, session, or API token (ordered: viewer < operator < admin)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Role {
+    Viewer,    // Read config and stats
+    Operator,  // Also change config (modes, colors, effects)
+    Admin,     // Also manage devices, tokens, and system actions
+}
+
+impl Role {
+    pub fn parse(s: &str) -> Option<Role> {
+        match s {
+            "viewer" => Some(Role::Viewer),
+            "operator" => Some(Role::Operator),
+            "admin" => Some(Role::Admin),
+            _ => None,
+        }
+    }
+}
+
+/// Minimum role needed for a request
+pub fn required_role(safe_method: bool, path: &str) -> Role {
+    // Device management (and LAN scans), token management, backups (contain credentials), and system actions
+    if path.starts_with("/api/devices/")
+        || path == "/api/discover"
+        || path.starts_with("/api/tokens")
+        || path == "/api/config/export"
+        || path == "/api/config/import"
+        || path == "/api/shutdown"
+        || path == "/api/system/shutdown"
+    {
+        return Role::Admin;
+    }
+    // The webcam WebSocket is opened with a GET but streams frames to the LEDs
+    if (safe_method && !path.starts_with("/ws/")) || path == "/api/auth/logout" {
+        Role::Viewer
+    } else {
+        Role::Operator
+    }
+}
+
+/// Role granted by an API token scope ("write" = operator, anything else = viewer)
+pub fn token_role(token: &ApiTokenConfig) -> Role {
+    if token.scope == "write" {
+        Role::Operator
+    } else {
+        Role::Viewer
+    }
+}
+
+struct Session {
+    csrf_token: String,
+    role: Role,
+    expires: Instant,
+}
+
+/// Live session details returned to the middleware
+pub struct SessionInfo {
+    pub csrf_token: String,
+    pub role: Role,
+}
+
+fn sessions() -> &'static Mutex<HashMap<String, Session>> {
+    static SESSIONS: OnceLock<Mutex<HashMap<String, Session>>> = OnceLock::new();
+    SESSIONS.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+// Config keys the request checks depend on
+const AUTH_CONFIG_KEYS: &[&str] = &[
+    "httpd_auth_enabled",
+    "httpd_auth_user",
+    "httpd_auth_pass",
+    "httpd_auth_pass_hash",
+    "httpd_users",
+    "api_tokens",
+    "httpd_https_enabled",
+    "httpd_rate_limit_per_minute",
+    "httpd_login_max_attempts",
+    "httpd_login_lockout_secs",
+];
+
+// Last config that loaded - requests are checked against it rather than the file, so a broken or half-written
+// config never opens the server up (None until one has loaded)
+static CONFIG: Mutex<Option<Arc<BandwidthConfig>>> = Mutex::new(None);
+
+/// Take the login and token settings from the config
+pub fn configure(config: &BandwidthConfig) {
+    *CONFIG.lock().unwrap() = Some(Arc::new(config.clone()));
+}
+
+/// Config requests are checked against - None if none has loaded yet
+pub fn current_config() -> Option<Arc<BandwidthConfig>> {
+    CONFIG.lock().unwrap().clone()
+}
+
+/// Keep the login and token settings in step with config changes (web UI, API, or file edits)
+pub fn spawn_watcher(config_change_tx: broadcast::Sender<ConfigDelta>) {
+    config::spawn_config_listener(&config_change_tx, AUTH_CONFIG_KEYS, configure);
+}
+
+/// Whether the web UI requires a login (auth enabled with a username and password hash set)
+pub fn auth_required(config: &BandwidthConfig) -> bool {
+    let primary_configured = !config.httpd_auth_user.is_empty() && !config.httpd_auth_pass_hash.is_empty();
+    config.httpd_auth_enabled && (primary_configured || !config.httpd_users.is_empty())
+}
+
+/// Hash a password into an argon2 PHC string (salt and parameters included)
+pub fn hash_password(password: &str) -> Result<String> {
+    let salt = SaltString::generate(&mut OsRng);
+    Argon2::default()
+        .hash_password(password.as_bytes(), &salt)
+        .map(|hash| hash.to_string())
+        .map_err(|e| anyhow!("Failed to hash password: {}", e))
+}
+
+/// Verify a password against an argon2 PHC string
+pub fn verify_password(password: &str, hash: &str) -> bool {
+    match PasswordHash::new(hash) {
+        Ok(parsed) => Argon2::default().verify_password(password.as_bytes(), &parsed).is_ok(),
+        Err(_) => false,
+    }
+}
+
+/// Compare two byte strings without leaking where they differ
+pub fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    a.iter().zip(b.iter()).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+/// Check a username/password pair against the configured credentials, returning the user's role
+/// The primary httpd_auth_user is always an admin; httpd_users carry their own role
+pub fn authenticate(config: &BandwidthConfig, username: &str, password: &str) -> Option<Role> {
+    let (hash, role) = if !config.httpd_auth_user.is_empty()
+        && constant_time_eq(username.as_bytes(), config.httpd_auth_user.as_bytes())
+    {
+        (config.httpd_auth_pass_hash.as_str(), Role::Admin)
+    } else {
+        match config.httpd_users.iter().find(|u| u.username == username) {
+            Some(user) => (user.password_hash.as_str(), Role::parse(&user.role).unwrap_or(Role::Viewer)),
+            None => ("", Role::Viewer),
+        }
+    };
+
+    // Always run the hash check so a wrong username takes as long as a wrong password
+    let password_ok = verify_password(password, if hash.is_empty() { DUMMY_HASH } else { hash });
+    if password_ok && !hash.is_empty() {
+        Some(role)
+    } else {
+        None
+    }
+}
+
+// Well-formed argon2 hash that matches no password - verified against when the username is unknown
+const DUMMY_HASH: &str = "$argon2id$v=19$m=19456,t=2,p=1$c29tZXNhbHRzb21lc2FsdA$2ujjmBGSpXIW8m+2LNqHmwrdBkfeFR4Lh5MqDE+GyE4";
+
+/// Random 256-bit token as lowercase hex
+pub fn random_token() -> String {
+    let bytes: [u8; 32] = rand::thread_rng().gen();
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// SHA-256 of an API token as lowercase hex
+/// Tokens are random 256-bit values, so a fast hash is enough (unlike passwords)
+pub fn hash_token(token: &str) -> String {
+    Sha256::digest(token.as_bytes()).iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Look up the configured API token matching a bearer token
+pub fn find_api_token<'a>(config: &'a BandwidthConfig, token: &str) -> Option<&'a ApiTokenConfig> {
+    let hash = hash_token(token);
+    config.api_tokens.iter()
+        .find(|t| constant_time_eq(t.token_hash.as_bytes(), hash.as_bytes()))
+}
+
+/// Start a new session for a role, returning (session id, CSRF token)
+pub fn create_session(role: Role) -> (String, String) {
+    let session_id = random_token();
+    let csrf_token = random_token();
+
+    let mut sessions = sessions().lock().unwrap();
+    let now = Instant::now();
+    sessions.retain(|_, s| s.expires > now);
+    sessions.insert(session_id.clone(), Session {
+        csrf_token: csrf_token.clone(),
+        role,
+        expires: now + SESSION_TTL,
+    });
+
+    (session_id, csrf_token)
+}
+
+/// Details of a live session (None if the session is unknown or expired)
+pub fn session_info(session_id: &str) -> Option<SessionInfo> {
+    let mut sessions = sessions().lock().unwrap();
+    match sessions.get(session_id) {
+        Some(session) if session.expires > Instant::now() => Some(SessionInfo {
+            csrf_token: session.csrf_token.clone(),
+            role: session.role,
+        }),
+        Some(_) => {
+            sessions.remove(session_id);
+            None
+        }
+        None => None,
+    }
+}
+
+/// End a session (logout)
+pub fn end_session(session_id: &str) {
+    sessions().lock().unwrap().remove(session_id);
+}
+
+/// Extract the session id from a Cookie header value
+pub fn session_from_cookie(cookie_header: &str) -> Option<&str> {
+    cookie_header
+        .split(';')
+        .filter_map(|pair| pair.trim().split_once('='))
+        .find(|(name, _)| *name == SESSION_COOKIE)
+        .map(|(_, value)| value)
+}
+
+/// Set-Cookie value for a new session
+pub fn session_cookie(session_id: &str, secure: bool) -> String {
+    format!(
+        "{}={}; Path=/; HttpOnly; SameSite=Strict; Max-Age={}{}",
+        SESSION_COOKIE,
+        session_id,
+        SESSION_TTL.as_secs(),
+        if secure { "; Secure" } else { "" }
+    )
+}
+
+/// Set-Cookie value that clears the session cookie
+pub fn clear_session_cookie() -> String {
+    format!("{}=; Path=/; HttpOnly; SameSite=Strict; Max-Age=0", SESSION_COOKIE)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_password_hash_roundtrip() {
+        let hash = hash_password("hunter2").unwrap();
+        assert!(hash.starts_with("$argon2"));
+        assert!(verify_password("hunter2", &hash));
+        assert!(!verify_password("hunter3", &hash));
+        assert!(!verify_password("hunter2", "not-a-hash"));
+    }
+
+    #[test]
+    fn test_required_role() {
+        assert_eq!(required_role(true, "/api/config"), Role::Viewer);
+        assert_eq!(required_role(false, "/api/config"), Role::Operator);
+        assert_eq!(required_role(false, "/api/devices/add"), Role::Admin);
+        assert_eq!(required_role(true, "/api/discover"), Role::Admin);
+        assert_eq!(required_role(true, "/api/tokens"), Role::Admin);
+        assert_eq!(required_role(false, "/api/shutdown"), Role::Admin);
+        assert_eq!(required_role(false, "/api/system/shutdown"), Role::Admin);
+        assert_eq!(required_role(false, "/api/system/restart"), Role::Operator);
+        assert_eq!(required_role(true, "/ws/webcam"), Role::Operator);
+        assert!(Role::Admin > Role::Operator && Role::Operator > Role::Viewer);
+    }
+
+    #[test]
+    fn test_find_api_token() {
+        let token = random_token();
+        let mut config = BandwidthConfig::default();
+        config.api_tokens.push(ApiTokenConfig {
+            name: "script".to_string(),
+            prefix: token[..8].to_string(),
+            token_hash: hash_token(&token),
+            scope: "read".to_string(),
+            created: 0,
+        });
+
+        assert_eq!(find_api_token(&config, &token).map(|t| t.name.as_str()), Some("script"));
+        assert!(find_api_token(&config, &random_token()).is_none());
+    }
+
+    #[test]
+    fn test_session_from_cookie() {
+        assert_eq!(session_from_cookie("a=1; rustwled_session=abc; b=2"), Some("abc"));
+        assert_eq!(session_from_cookie("a=1"), None);
+    }
+
+    #[test]
+    fn test_request_limits_refresh_cached_config() {
+        // The rate limiter and login read the cached config, so changes to their settings have to refresh it
+        let old = BandwidthConfig::default();
+        let changes: [fn(&mut BandwidthConfig); 4] = [
+            |c| c.httpd_rate_limit_per_minute += 1,
+            |c| c.httpd_login_max_attempts += 1,
+            |c| c.httpd_login_lockout_secs += 1,
+            |c| c.httpd_https_enabled = !c.httpd_https_enabled,
+        ];
+        for change in changes {
+            let mut new = old.clone();
+            change(&mut new);
+            assert!(ConfigDelta::between(&old, &new).unwrap().changed_any(AUTH_CONFIG_KEYS));
+        }
+    }
+}
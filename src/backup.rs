@@ -0,0 +1,199 @@
+// Backup Module - Export and import the full configuration (config, custom gradients, presets and palette files)
+// as one archive
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::cli;
+use crate::config::BandwidthConfig;
+use crate::gradients;
+
+// Archive format marker and version - bump the version if the layout changes
+const ARCHIVE_FORMAT: &str = "rustwled-backup";
+const ARCHIVE_VERSION: u32 = 2;
+
+// Keep at most this many automatic pre-import backups
+const MAX_AUTO_BACKUPS: usize = 20;
+
+/// Backup archive - raw file contents so comments and formatting survive a round trip
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackupArchive {
+    pub format: String,
+    pub version: u32,
+    pub created: u64,  // Unix timestamp
+    pub config: String,  // Contents of the config file (TOML)
+    #[serde(default)]
+    pub gradients: String,  // Contents of gradients.conf (custom gradient presets)
+    #[serde(default)]
+    pub presets: BTreeMap<String, String>,  // Saved presets by file name (version 2)
+    #[serde(default)]
+    pub palettes: BTreeMap<String, String>,  // cpt-city/FastLED palette files by file name (version 2)
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0)
+}
+
+fn current_config_path() -> Result<PathBuf> {
+    match BandwidthConfig::load()?.config_path {
+        Some(path) => Ok(path),
+        None => BandwidthConfig::config_path(None),
+    }
+}
+
+/// Directory automatic pre-import backups are written to
+pub fn backups_dir() -> Result<PathBuf> {
+    let home = std::env::var("HOME").context("HOME environment variable not set")?;
+    let dir = PathBuf::from(home).join(".config").join("rustwled").join("backups");
+    fs::create_dir_all(&dir)?;
+    Ok(dir)
+}
+
+// Files directly in a directory by name (a missing directory has none)
+fn read_dir_files(dir: &Path) -> Result<BTreeMap<String, String>> {
+    let mut files = BTreeMap::new();
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Ok(files);
+    };
+    for path in entries.filter_map(|entry| entry.ok().map(|e| e.path())).filter(|p| p.is_file()) {
+        let Some(name) = path.file_name().map(|n| n.to_string_lossy().to_string()) else { continue };
+        let contents = fs::read_to_string(&path).with_context(|| format!("Failed to read {}", path.display()))?;
+        files.insert(name, contents);
+    }
+    Ok(files)
+}
+
+fn write_dir_files(dir: &Path, files: &BTreeMap<String, String>) -> Result<()> {
+    if files.is_empty() {
+        return Ok(());
+    }
+    fs::create_dir_all(dir)?;
+    for (name, contents) in files {
+        fs::write(dir.join(name), contents)?;
+    }
+    Ok(())
+}
+
+// Archived files are written back by name, so a name must not reach outside its directory
+fn valid_file_name(name: &str) -> bool {
+    !name.is_empty() && name != "." && name != ".." && !name.contains(['/', '\\'])
+}
+
+/// Build an archive of the current config, custom gradients, presets and palette files
+pub fn create_archive() -> Result<BackupArchive> {
+    let config = fs::read_to_string(current_config_path()?).context("Failed to read config file")?;
+
+    let gradients_path = gradients::gradients_file_path()?;
+    let gradients = if gradients_path.exists() {
+        fs::read_to_string(&gradients_path).context("Failed to read gradients file")?
+    } else {
+        String::new()
+    };
+
+    Ok(BackupArchive {
+        format: ARCHIVE_FORMAT.to_string(),
+        version: ARCHIVE_VERSION,
+        created: unix_now(),
+        config,
+        gradients,
+        presets: read_dir_files(&cli::presets_dir()?)?,
+        palettes: read_dir_files(&gradients::palettes_dir()?)?,
+    })
+}
+
+/// Check an archive before anything is written - format, version, and that the config parses
+pub fn validate_archive(archive: &BackupArchive) -> Result<BandwidthConfig> {
+    if archive.format != ARCHIVE_FORMAT {
+        anyhow::bail!("Not a rustwled backup (format \"{}\")", archive.format);
+    }
+    if archive.version > ARCHIVE_VERSION {
+        anyhow::bail!("Backup version {} is newer than supported version {}", archive.version, ARCHIVE_VERSION);
+    }
+
+    let config: BandwidthConfig = toml::from_str(&archive.config).context("Backup config is not valid")?;
+
+    for (idx, line) in archive.gradients.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        if !line.contains('=') {
+            anyhow::bail!("Backup gradients line {} is not valid: {}", idx + 1, line);
+        }
+    }
+
+    if let Some(name) = archive.presets.keys().chain(archive.palettes.keys()).find(|name| !valid_file_name(name)) {
+        anyhow::bail!("Backup contains an invalid file name: {}", name);
+    }
+
+    Ok(config)
+}
+
+/// Write the current state to the backups directory, pruning the oldest automatic backups
+pub fn write_backup_file() -> Result<PathBuf> {
+    let archive = create_archive()?;
+    let dir = backups_dir()?;
+    let path = dir.join(format!("{}-{}.json", ARCHIVE_FORMAT, archive.created));
+    fs::write(&path, serde_json::to_string_pretty(&archive)?)?;
+
+    let mut backups: Vec<PathBuf> = fs::read_dir(&dir)?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|p| p.extension().map(|ext| ext == "json").unwrap_or(false))
+        .collect();
+    backups.sort();
+    while backups.len() > MAX_AUTO_BACKUPS {
+        let _ = fs::remove_file(backups.remove(0));
+    }
+
+    Ok(path)
+}
+
+/// Restore an archive - validates first, backs up the current state, then replaces the config and gradients
+/// and writes back the archived presets and palette files
+/// Returns the path of the pre-import backup
+pub fn restore_archive(archive: &BackupArchive) -> Result<PathBuf> {
+    let mut config = validate_archive(archive)?;
+    let backup_path = write_backup_file().context("Failed to back up current config before import")?;
+
+    // Save through BandwidthConfig so imported values are sanitized like any other change
+    config.config_path = Some(current_config_path()?);
+    config.save()?;
+
+    fs::write(gradients::gradients_file_path()?, &archive.gradients)?;
+    write_dir_files(&cli::presets_dir()?, &archive.presets)?;
+    write_dir_files(&gradients::palettes_dir()?, &archive.palettes)?;
+
+    Ok(backup_path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn archive(format: &str, config: &str) -> BackupArchive {
+        BackupArchive {
+            format: format.to_string(),
+            version: ARCHIVE_VERSION,
+            created: 0,
+            config: config.to_string(),
+            gradients: "Sunset = \"FF0000,FFAA00\"\n".to_string(),
+            presets: BTreeMap::from([("evening.conf".to_string(), "mode = \"live\"\n".to_string())]),
+            palettes: BTreeMap::new(),
+        }
+    }
+
+    #[test]
+    fn test_validate_archive() {
+        assert!(validate_archive(&archive(ARCHIVE_FORMAT, "mode = \"live\"\nfps = 30.0\n")).is_ok());
+        assert!(validate_archive(&archive("something-else", "mode = \"live\"\n")).is_err());
+        assert!(validate_archive(&archive(ARCHIVE_FORMAT, "fps = \"fast\"\n")).is_err());
+
+        // Archived files can't be written outside their directory
+        let mut escaping = archive(ARCHIVE_FORMAT, "mode = \"live\"\n");
+        escaping.palettes.insert("../rustwled.conf".to_string(), String::new());
+        assert!(validate_archive(&escaping).is_err());
+    }
+}
@@ -0,0 +1,266 @@
+// Bandwidth History Module - Keeps the last hours of bandwidth readings in a fixed-size ring file next to
+// the config, and plays a past window back through bandwidth mode (LEDs and TUI) with --replay
+//
+// File format: 8-byte magic, u32 LE slot count, u32 LE next slot to write, then per slot:
+//   u64 LE  milliseconds since the Unix epoch (0 = never written)
+//   f32 LE  RX kbps
+//   f32 LE  TX kbps
+use anyhow::{anyhow, bail, Result};
+use std::fs::{File, OpenOptions};
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+const MAGIC: &[u8; 8] = b"RWLBWH01";
+const HEADER_LEN: u64 = 16;
+const SLOT_LEN: u64 = 16;
+
+// One reading is kept per second
+const SAMPLE_INTERVAL_MS: u64 = 1000;
+
+// Gaps in the history (bandwidth mode wasn't running) are skipped over rather than played out
+const MAX_REPLAY_GAP_MS: u64 = 5000;
+
+// Replays send a reading at most this often - bandwidth mode takes in one line per TUI tick
+const REPLAY_STEP: Duration = Duration::from_millis(100);
+
+/// One stored reading
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Sample {
+    pub at_ms: u64,  // Milliseconds since the Unix epoch
+    pub rx_kbps: f64,
+    pub tx_kbps: f64,
+}
+
+/// History file for a config file (each config keeps its own)
+pub fn history_path(config_path: &Path) -> PathBuf {
+    config_path.with_extension("history")
+}
+
+pub fn now_ms() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_millis() as u64).unwrap_or(0)
+}
+
+/// Time of day of a reading as HH:MM:SS (UTC, like the TUI message timestamps)
+pub fn clock(at_ms: u64) -> String {
+    let secs = at_ms / 1000;
+    format!("{:02}:{:02}:{:02}", (secs / 3600) % 24, (secs / 60) % 60, secs % 60)
+}
+
+fn slot_offset(slot: u32) -> u64 {
+    HEADER_LEN + slot as u64 * SLOT_LEN
+}
+
+/// Appends readings to the ring file, overwriting the oldest once it is full
+pub struct HistoryWriter {
+    file: File,
+    slots: u32,
+    next: u32,
+    last_ms: u64,
+}
+
+impl HistoryWriter {
+    /// Open the ring file for `hours` of readings - an existing file of another size is started over
+    pub fn open(path: &Path, hours: u32) -> Result<Self> {
+        let slots = hours.max(1) * 3600;
+        let mut file = OpenOptions::new().read(true).write(true).create(true).truncate(false).open(path)?;
+
+        let mut header = [0u8; HEADER_LEN as usize];
+        let reusable = file.read_exact(&mut header).is_ok()
+            && &header[0..8] == MAGIC
+            && u32::from_le_bytes(header[8..12].try_into()?) == slots
+            && file.metadata()?.len() == slot_offset(slots);
+        let next = if reusable {
+            u32::from_le_bytes(header[12..16].try_into()?) % slots
+        } else {
+            // Zero-filled slots read back as never written
+            file.set_len(0)?;
+            file.set_len(slot_offset(slots))?;
+            file.seek(SeekFrom::Start(0))?;
+            file.write_all(MAGIC)?;
+            file.write_all(&slots.to_le_bytes())?;
+            file.write_all(&0u32.to_le_bytes())?;
+            0
+        };
+
+        Ok(HistoryWriter { file, slots, next, last_ms: 0 })
+    }
+
+    /// Store a reading - the first one in each second is kept, the rest are ignored
+    pub fn record(&mut self, rx_kbps: f64, tx_kbps: f64) -> Result<()> {
+        let now = now_ms();
+        if now < self.last_ms + SAMPLE_INTERVAL_MS {
+            return Ok(());
+        }
+        self.last_ms = now;
+
+        let mut slot = [0u8; SLOT_LEN as usize];
+        slot[0..8].copy_from_slice(&now.to_le_bytes());
+        slot[8..12].copy_from_slice(&(rx_kbps as f32).to_le_bytes());
+        slot[12..16].copy_from_slice(&(tx_kbps as f32).to_le_bytes());
+        self.file.seek(SeekFrom::Start(slot_offset(self.next)))?;
+        self.file.write_all(&slot)?;
+
+        self.next = (self.next + 1) % self.slots;
+        self.file.seek(SeekFrom::Start(12))?;
+        self.file.write_all(&self.next.to_le_bytes())?;
+        Ok(())
+    }
+}
+
+/// Readings taken between `from_ms` and `to_ms`, oldest first
+pub fn read_window(path: &Path, from_ms: u64, to_ms: u64) -> Result<Vec<Sample>> {
+    let mut data = Vec::new();
+    File::open(path)
+        .map_err(|e| anyhow!("No bandwidth history at {} ({}) - set bandwidth_history = true to record it", path.display(), e))?
+        .read_to_end(&mut data)?;
+    if data.len() < HEADER_LEN as usize || &data[0..8] != MAGIC {
+        bail!("{} is not a bandwidth history file", path.display());
+    }
+
+    let mut samples: Vec<Sample> = data[HEADER_LEN as usize..]
+        .chunks_exact(SLOT_LEN as usize)
+        .map(|slot| Sample {
+            at_ms: u64::from_le_bytes(slot[0..8].try_into().unwrap()),
+            rx_kbps: f32::from_le_bytes(slot[8..12].try_into().unwrap()) as f64,
+            tx_kbps: f32::from_le_bytes(slot[12..16].try_into().unwrap()) as f64,
+        })
+        .filter(|sample| sample.at_ms != 0 && sample.at_ms >= from_ms && sample.at_ms <= to_ms)
+        .collect();
+    samples.sort_by_key(|sample| sample.at_ms);
+    Ok(samples)
+}
+
+// "90s", "30m", "8h", "2d"
+fn parse_duration_ms(text: &str) -> Result<u64> {
+    let text = text.trim();
+    let split = text.find(|c: char| !c.is_ascii_digit() && c != '.').unwrap_or(text.len());
+    let value: f64 = text[..split].parse().map_err(|_| anyhow!("invalid duration '{}'", text))?;
+    let unit_ms = match &text[split..] {
+        "s" => 1000.0,
+        "m" | "" => 60_000.0,
+        "h" => 3_600_000.0,
+        "d" => 86_400_000.0,
+        unit => bail!("unknown duration unit '{}' in '{}' (use s, m, h or d)", unit, text),
+    };
+    Ok((value * unit_ms) as u64)
+}
+
+/// Time window for --replay: "AGO" (from then until now) or "AGO+LENGTH"
+/// e.g. "8h" = the last 8 hours, "10h+30m" = the 30 minutes starting 10 hours ago
+pub fn parse_window(spec: &str, now_ms: u64) -> Result<(u64, u64)> {
+    let (ago, length) = match spec.split_once('+') {
+        Some((ago, length)) => (parse_duration_ms(ago)?, Some(parse_duration_ms(length)?)),
+        None => (parse_duration_ms(spec)?, None),
+    };
+    let from = now_ms.saturating_sub(ago);
+    let to = length.map(|length| from + length).unwrap_or(now_ms).min(now_ms);
+    Ok((from, to))
+}
+
+/// Where a replay has got to, for the TUI header
+#[derive(Default)]
+pub struct ReplayProgress {
+    pub position_ms: AtomicU64,
+    pub done: AtomicBool,
+}
+
+// A reading as the line a 1-second netstat would print, so it takes the same path as a live reading
+fn sample_line(rx_kbps: f64, tx_kbps: f64) -> String {
+    format!("0 0 {} 0 0 {} 0", rx_kbps * 1000.0 / 8.0, tx_kbps * 1000.0 / 8.0)
+}
+
+/// Play readings back `speed` times faster than they were recorded, handing them to `on_line`
+/// - until they run out, `shutdown` is set or `on_line` returns false
+pub async fn replay(
+    samples: Vec<Sample>,
+    speed: f64,
+    progress: Arc<ReplayProgress>,
+    shutdown: Arc<AtomicBool>,
+    mut on_line: impl FnMut(String) -> bool,
+) {
+    let step_ms = ((REPLAY_STEP.as_millis() as f64 * speed) as u64).max(1);
+
+    let mut next = 0;
+    let mut position = samples.first().map(|sample| sample.at_ms).unwrap_or(0);
+    while next < samples.len() {
+        if shutdown.load(Ordering::Relaxed) {
+            return;
+        }
+        if samples[next].at_ms > position + MAX_REPLAY_GAP_MS {
+            position = samples[next].at_ms;
+        }
+        let step_end = position + step_ms;
+
+        // The busiest reading of the step, so short spikes still show at high speeds
+        let mut busiest: Option<Sample> = None;
+        while next < samples.len() && samples[next].at_ms <= step_end {
+            let sample = samples[next];
+            let busier = match busiest {
+                Some(b) => sample.rx_kbps + sample.tx_kbps > b.rx_kbps + b.tx_kbps,
+                None => true,
+            };
+            if busier {
+                busiest = Some(sample);
+            }
+            next += 1;
+        }
+        if let Some(sample) = busiest {
+            progress.position_ms.store(sample.at_ms, Ordering::Relaxed);
+            if !on_line(sample_line(sample.rx_kbps, sample.tx_kbps)) {
+                return;
+            }
+        }
+
+        position = step_end;
+        tokio::time::sleep(REPLAY_STEP).await;
+    }
+
+    // Leave the LEDs dark once the window has played
+    progress.done.store(true, Ordering::Relaxed);
+    on_line(sample_line(0.0, 0.0));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_window() {
+        let now = 100 * 3_600_000;
+        assert_eq!(parse_window("8h", now).unwrap(), (92 * 3_600_000, now));
+        assert_eq!(parse_window("10h+30m", now).unwrap(), (90 * 3_600_000, 90 * 3_600_000 + 1_800_000));
+        assert_eq!(parse_window("1h+5h", now).unwrap(), (99 * 3_600_000, now));
+        assert_eq!(parse_window("90s", now).unwrap(), (now - 90_000, now));
+        assert!(parse_window("8x", now).is_err());
+        assert!(parse_window("", now).is_err());
+    }
+
+    #[test]
+    fn test_ring_file() {
+        let path = std::env::temp_dir().join(format!("rustwled_history_test_{}.history", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+
+        let mut writer = HistoryWriter::open(&path, 1).unwrap();
+        writer.record(800.0, 80.0).unwrap();
+        writer.record(900.0, 90.0).unwrap();  // Same second - ignored
+        drop(writer);
+
+        let samples = read_window(&path, 0, u64::MAX).unwrap();
+        assert_eq!(samples.len(), 1);
+        assert_eq!((samples[0].rx_kbps, samples[0].tx_kbps), (800.0, 80.0));
+
+        // Reopening at the same size keeps the history and carries on after it
+        let writer = HistoryWriter::open(&path, 1).unwrap();
+        assert_eq!(writer.next, 1);
+        drop(writer);
+
+        // A different size starts over
+        HistoryWriter::open(&path, 2).unwrap();
+        assert!(read_window(&path, 0, u64::MAX).unwrap().is_empty());
+
+        let _ = std::fs::remove_file(&path);
+    }
+}
@@ -0,0 +1,294 @@
+// Bench Module - Run each mode's render path headlessly and report frame timings and allocations
+use anyhow::{anyhow, Result};
+use std::hint::black_box;
+use std::sync::atomic::AtomicBool;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use crate::alloc_track;
+use crate::config::{BandwidthConfig, SandConfig, WLEDDeviceConfig};
+use crate::geometry::GeometryState;
+use crate::matrix::MatrixLayout;
+use crate::midi::NoteState;
+use crate::multi_device::ColorCorrection;
+use crate::renderer::{self, RenderStateCell, Renderer, SharedRenderState};
+use crate::sand::SandSimulation;
+use crate::tron::TronGame;
+use crate::types::Rgb;
+
+/// Modes that can be benchmarked ("output" is the color correction pass applied before sending)
+pub const BENCH_MODES: &[&str] = &["bandwidth", "midi", "tron", "sand", "geometry", "output"];
+
+/// Timings for one mode
+pub struct BenchResult {
+    pub mode: String,
+    pub frames: u64,
+    pub elapsed: Duration,
+    pub max_frame: Duration,
+    pub allocations: u64,
+    pub allocated_bytes: u64,
+}
+
+impl BenchResult {
+    pub fn fps(&self) -> f64 {
+        self.frames as f64 / self.elapsed.as_secs_f64().max(f64::EPSILON)
+    }
+
+    pub fn micros_per_frame(&self) -> f64 {
+        self.elapsed.as_secs_f64() * 1_000_000.0 / self.frames.max(1) as f64
+    }
+
+    pub fn allocations_per_frame(&self) -> f64 {
+        self.allocations as f64 / self.frames.max(1) as f64
+    }
+}
+
+// Frames rendered before measuring, so buffers and caches have grown to their steady-state size
+const WARMUP_FRAMES: usize = 10;
+
+// Render frames back to back for the given time (setup and warmup are not measured)
+fn measure(mode: &str, duration: Duration, mut render: impl FnMut() -> Result<()>) -> Result<BenchResult> {
+    for _ in 0..WARMUP_FRAMES {
+        render()?;
+    }
+
+    let allocations_before = alloc_track::allocations();
+    let bytes_before = alloc_track::allocated_bytes();
+    let start = Instant::now();
+    let mut frames = 0u64;
+    let mut max_frame = Duration::ZERO;
+
+    while start.elapsed() < duration {
+        let frame_start = Instant::now();
+        render()?;
+        max_frame = max_frame.max(frame_start.elapsed());
+        frames += 1;
+    }
+
+    Ok(BenchResult {
+        mode: mode.to_string(),
+        frames,
+        elapsed: start.elapsed(),
+        max_frame,
+        allocations: alloc_track::allocations() - allocations_before,
+        allocated_bytes: alloc_track::allocated_bytes() - bytes_before,
+    })
+}
+
+fn bench_bandwidth(config: &BandwidthConfig, duration: Duration) -> Result<BenchResult> {
+    // Render into a local device so nothing leaves the machine (render_frame doesn't send anyway)
+    let mut bench_config = config.clone();
+    bench_config.wled_devices = vec![WLEDDeviceConfig {
+        ip: "127.0.0.1".to_string(),
+        led_offset: 0,
+        led_count: config.total_leds,
+        enabled: true,
+        max_fps: 0.0,
+        led_ma: 55.0,
+        psu_amps: 0.0,
+        backup_for: String::new(),
+        reversed: false,
+        mirrored: false,
+        segment: -1,
+        protocol: "ddp".to_string(),
+        universe: 1,
+        start_channel: 1,
+        net: 0,
+        subnet: 0,
+    }];
+
+    // Half of max bandwidth each way, so both halves are lit and animating
+    let mut state = SharedRenderState::from_config(&bench_config);
+    state.current_rx_kbps = state.max_bandwidth_kbps / 2.0;
+    state.current_tx_kbps = state.max_bandwidth_kbps / 2.0;
+    state.enable_interpolation = false;
+
+    let shared_state = Arc::new(RenderStateCell::new(state));
+    let mut renderer = Renderer::new(&bench_config, shared_state, Arc::new(AtomicBool::new(false)))?;
+    let delta_seconds = 1.0 / config.fps.max(1.0);
+    measure("bandwidth", duration, || {
+        black_box(renderer.render_frame(delta_seconds)?);
+        Ok(())
+    })
+}
+
+fn bench_midi(config: &BandwidthConfig, duration: Duration) -> Result<BenchResult> {
+    // A held chord keeps the attack/decay smoothing busy
+    let note_state = NoteState::new();
+    for (i, note) in [48u8, 52, 55, 60, 64, 67, 72].iter().enumerate() {
+        note_state.note_on(0, *note, 64 + i as u8 * 8);
+    }
+
+    let total_leds = config.total_leds;
+    let frame_time_ms = 1000.0 / config.fps.max(1.0);
+    let attack_factor = (frame_time_ms / config.audio.attack_ms as f64).min(1.0) as f32;
+    let decay_factor = (frame_time_ms / config.audio.decay_ms as f64).min(1.0) as f32;
+    let mut smoothed_frame = vec![0.0_f32; total_leds];
+    let mut target_brightness = vec![0.0_f32; total_leds];
+    let mut last_colors = vec![(0_u8, 0_u8, 0_u8); total_leds];
+    let mut buffers = renderer::MidiFrameBuffers::default();
+    measure("midi", duration, || {
+        let frame = renderer::render_midi_to_leds(
+            &note_state,
+            total_leds,
+            config.midi.gradient,
+            None,
+            config.midi.velocity_colors,
+            config.midi.one_to_one,
+            config.midi.channel_mode,
+            None,
+            &mut smoothed_frame,
+            &mut target_brightness,
+            &mut last_colors,
+            attack_factor,
+            decay_factor,
+            None,
+            &mut buffers,
+        )?;
+        black_box(frame);
+        Ok(())
+    })
+}
+
+fn bench_tron(config: &BandwidthConfig, duration: Duration) -> Result<BenchResult> {
+    let tron = &config.tron;
+    let layout = MatrixLayout::for_mode(config, tron.width, tron.height, false);
+    let total_leds = layout.led_count();
+    let mut game = TronGame::new(layout.width(), layout.height(), tron, &config.color_space);
+
+    let mut frame = Vec::new();
+    measure("tron", duration, || {
+        if game.is_game_over() {
+            game.reset();
+        }
+        game.update();
+        game.render(total_leds, &layout, &mut frame);
+        black_box(&frame);
+        Ok(())
+    })
+}
+
+fn bench_sand(config: &BandwidthConfig, duration: Duration) -> Result<BenchResult> {
+    // Always bench plain sand so runs stay comparable whatever particle type is configured
+    let sand = SandConfig { particle_type: "sand".into(), ..config.sand.clone() };
+    let layout = MatrixLayout::for_mode(config, sand.grid_width, sand.grid_height, true);
+    let mut sim = SandSimulation::new(layout.width(), layout.height(), &sand);
+    sim.place_obstacles(sand.obstacles_enabled, sand.obstacle_density as f32);
+
+    let total_leds = layout.led_count();
+    let mut frames = 0u64;
+    let mut frame = Vec::new();
+    measure("sand", duration, || {
+        // Start over now and then so the grid never fills up and settles
+        frames += 1;
+        if frames % 2000 == 0 {
+            sim.clear();
+        }
+        sim.spawn_particles();
+        sim.update();
+        sim.render(total_leds, &layout, &mut frame);
+        black_box(&frame);
+        Ok(())
+    })
+}
+
+fn bench_geometry(config: &BandwidthConfig, duration: Duration) -> Result<BenchResult> {
+    let mut state = GeometryState::new(config.total_leds, config.geometry.grid_width, config.geometry.grid_height, &config.geometry, &config.boid);
+    let mut frame = Vec::new();
+    measure("geometry", duration, || {
+        state.update(config.global_brightness, config.animation_speed, &config.tx_animation_direction, &mut frame);
+        black_box(&frame);
+        Ok(())
+    })
+}
+
+fn bench_output(config: &BandwidthConfig, duration: Duration) -> Result<BenchResult> {
+    // Gamma 2.2 with a color order swap, so the full correction path runs even if the config disables it
+    let correction = ColorCorrection::from_config(config)
+        .unwrap_or_else(|| ColorCorrection::new(2.2, Rgb { r: 255, g: 200, b: 180 }, "GRB"));
+    let frame: Vec<u8> = (0..config.total_leds * 3).map(|i| (i % 256) as u8).collect();
+    let mut out = Vec::with_capacity(frame.len());
+    measure("output", duration, || {
+        out.clear();
+        out.extend_from_slice(&frame);
+        correction.apply(&mut out);
+        black_box(&out);
+        Ok(())
+    })
+}
+
+/// Benchmark the given modes (all of them if empty) for `seconds` each and print a table
+pub fn run(seconds: f64, modes: &[String]) -> Result<()> {
+    let config = BandwidthConfig::load()?;
+    let duration = Duration::from_secs_f64(seconds.max(0.1));
+
+    let modes: Vec<String> = if modes.is_empty() {
+        BENCH_MODES.iter().map(|m| m.to_string()).collect()
+    } else {
+        modes.to_vec()
+    };
+    if let Some(unknown) = modes.iter().find(|m| !BENCH_MODES.contains(&m.as_str())) {
+        return Err(anyhow!("Cannot benchmark '{}' (expected one of: {})", unknown, BENCH_MODES.join(", ")));
+    }
+
+    println!("Benchmarking {} mode(s) for {:.1}s each ({} LEDs)...", modes.len(), duration.as_secs_f64(), config.total_leds);
+    if !alloc_track::COUNTING {
+        println!("(Allocations are not counted in release builds - build with --features alloc-track to count them)");
+    }
+    println!("{:<10} {:>10} {:>12} {:>12} {:>12} {:>14}", "MODE", "FPS", "US/FRAME", "MAX US", "ALLOCS/FRAME", "BYTES/FRAME");
+
+    for mode in &modes {
+        let result = match mode.as_str() {
+            "bandwidth" => bench_bandwidth(&config, duration),
+            "midi" => bench_midi(&config, duration),
+            "tron" => bench_tron(&config, duration),
+            "sand" => bench_sand(&config, duration),
+            "geometry" => bench_geometry(&config, duration),
+            _ => bench_output(&config, duration),
+        };
+
+        match result {
+            Ok(r) => println!(
+                "{:<10} {:>10.0} {:>12.1} {:>12} {:>12.1} {:>14.0}",
+                r.mode,
+                r.fps(),
+                r.micros_per_frame(),
+                r.max_frame.as_micros(),
+                r.allocations_per_frame(),
+                r.allocated_bytes as f64 / r.frames.max(1) as f64
+            ),
+            Err(e) => println!("{:<10} failed: {}", mode, e),
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_measure() {
+        // Warmup frames run first but aren't counted
+        let mut calls = 0u64;
+        let result = measure("count", Duration::from_millis(20), || {
+            calls += 1;
+            Ok(())
+        }).unwrap();
+        assert_eq!(result.frames + WARMUP_FRAMES as u64, calls);
+        assert!(result.frames > 0 && result.elapsed >= Duration::from_millis(20));
+        assert!(result.fps() > 0.0 && result.micros_per_frame() > 0.0);
+
+        // A mode that fails to render fails its benchmark
+        assert!(measure("broken", Duration::from_millis(20), || Err(anyhow!("no device"))).is_err());
+    }
+
+    #[test]
+    fn test_bench_modes_render() {
+        let mut config = BandwidthConfig::default();
+        config.total_leds = 60;
+        for result in [bench_geometry(&config, Duration::from_millis(20)), bench_output(&config, Duration::from_millis(20))] {
+            assert!(result.unwrap().frames > 0);
+        }
+    }
+}
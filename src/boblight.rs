@@ -0,0 +1,159 @@
+// Boblight Module - Boblight server protocol (TCP, line based) so ambilight grabbers can feed the relay
+use std::io::{self, Read, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream};
+
+/// Default boblightd port
+pub const BOBLIGHT_PORT: u16 = 19333;
+
+// Protocol version reported to clients
+const PROTOCOL_VERSION: u32 = 5;
+
+// Depth of each light's scan area into the picture, in percent of the screen
+const SCAN_DEPTH: f64 = 10.0;
+
+// Lines longer than this without a newline are dropped (protects against non-boblight clients)
+const MAX_LINE_LEN: usize = 4096;
+
+struct Client {
+    stream: TcpStream,
+    address: SocketAddr,
+    pending: Vec<u8>,  // Bytes of a line still waiting for its newline
+}
+
+/// Non-blocking boblight server - accepts clients and applies their light colors to an RGB frame
+pub struct BoblightServer {
+    listener: TcpListener,
+    clients: Vec<Client>,
+    lights: usize,
+}
+
+impl BoblightServer {
+    pub fn bind(address: &str, lights: usize) -> io::Result<Self> {
+        let listener = TcpListener::bind(address)?;
+        listener.set_nonblocking(true)?;
+        Ok(BoblightServer { listener, clients: Vec::new(), lights })
+    }
+
+    /// Accept new clients and handle whatever they've sent, writing colors into `frame`
+    /// Returns the client and byte count of the first read with data (None if nothing arrived), and whether a client sent sync
+    pub fn poll(&mut self, frame: &mut [u8]) -> io::Result<(Option<(SocketAddr, usize)>, bool)> {
+        loop {
+            match self.listener.accept() {
+                Ok((stream, address)) => {
+                    stream.set_nonblocking(true)?;
+                    let _ = stream.set_nodelay(true);
+                    self.clients.push(Client { stream, address, pending: Vec::new() });
+                }
+                Err(e) if e.kind() == io::ErrorKind::WouldBlock => break,
+                Err(e) => return Err(e),
+            }
+        }
+
+        let mut received = None;
+        let mut synced = false;
+        let mut buf = [0u8; 4096];
+        let lights = self.lights;
+        self.clients.retain_mut(|client| {
+            loop {
+                match client.stream.read(&mut buf) {
+                    Ok(0) => return false,  // Disconnected
+                    Ok(size) => {
+                        received.get_or_insert((client.address, size));
+                        client.pending.extend_from_slice(&buf[..size]);
+                    }
+                    Err(e) if e.kind() == io::ErrorKind::WouldBlock => break,
+                    Err(_) => return false,
+                }
+            }
+
+            let mut reply = String::new();
+            while let Some(newline) = client.pending.iter().position(|&b| b == b'\n') {
+                let line: Vec<u8> = client.pending.drain(..=newline).collect();
+                synced |= handle_line(String::from_utf8_lossy(&line).trim(), lights, frame, &mut reply);
+            }
+            if client.pending.len() > MAX_LINE_LEN {
+                client.pending.clear();
+            }
+            reply.is_empty() || client.stream.write_all(reply.as_bytes()).is_ok()
+        });
+
+        Ok((received, synced))
+    }
+}
+
+// Handle one command line, appending any reply - returns true for sync
+fn handle_line(line: &str, lights: usize, frame: &mut [u8], reply: &mut String) -> bool {
+    let words: Vec<&str> = line.split_whitespace().collect();
+    match words.as_slice() {
+        ["hello"] => reply.push_str("hello\n"),
+        ["ping"] => reply.push_str("ping 1\n"),
+        ["get", "version"] => reply.push_str(&format!("version {}\n", PROTOCOL_VERSION)),
+        ["get", "lights"] => {
+            reply.push_str(&format!("lights {}\n", lights));
+            for light in 0..lights {
+                let (top, bottom, left, right) = scan_area(light, lights);
+                reply.push_str(&format!("light {:03} scan {:.2} {:.2} {:.2} {:.2}\n", light, top, bottom, left, right));
+            }
+        }
+        ["set", "light", name, "rgb", r, g, b] => {
+            if let (Ok(light), Ok(r), Ok(g), Ok(b)) = (name.parse::<usize>(), r.parse::<f64>(), g.parse::<f64>(), b.parse::<f64>()) {
+                if let Some(pixel) = frame.get_mut(light * 3..light * 3 + 3) {
+                    for (channel, value) in pixel.iter_mut().zip([r, g, b]) {
+                        *channel = (value.clamp(0.0, 1.0) * 255.0).round() as u8;
+                    }
+                }
+            }
+        }
+        ["sync"] => return true,
+        _ => {}  // Priority, speed, interpolation, etc. don't apply to a relay
+    }
+    false
+}
+
+/// Scan area (top, bottom, left, right in percent) of a light - lights run clockwise around the
+/// screen edge from the top left corner, a quarter of them on each side
+fn scan_area(light: usize, lights: usize) -> (f64, f64, f64, f64) {
+    let per_side = lights.div_ceil(4).max(1);
+    let side = (light / per_side).min(3);
+    let step = 100.0 / per_side as f64;
+    let from = (light - side * per_side) as f64 * step;
+    let to = from + step;
+    match side {
+        0 => (0.0, SCAN_DEPTH, from, to),  // Top, left to right
+        1 => (from, to, 100.0 - SCAN_DEPTH, 100.0),  // Right, top to bottom
+        2 => (100.0 - SCAN_DEPTH, 100.0, 100.0 - to, 100.0 - from),  // Bottom, right to left
+        _ => (100.0 - to, 100.0 - from, 0.0, SCAN_DEPTH),  // Left, bottom to top
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_commands() {
+        let mut frame = vec![0u8; 12];
+        let mut reply = String::new();
+        assert!(!handle_line("hello", 4, &mut frame, &mut reply));
+        assert!(!handle_line("get version", 4, &mut frame, &mut reply));
+        assert_eq!(reply, "hello\nversion 5\n");
+
+        handle_line("set light 002 rgb 1.0 0.5 0", 4, &mut frame, &mut reply);
+        assert_eq!(&frame[6..9], &[255, 128, 0]);
+        handle_line("set light 009 rgb 1 1 1", 4, &mut frame, &mut reply);  // No such light
+        assert!(handle_line("sync", 4, &mut frame, &mut reply));
+    }
+
+    #[test]
+    fn test_scan_areas() {
+        // 4 lights - one per side, clockwise from the top
+        assert_eq!(scan_area(0, 4), (0.0, SCAN_DEPTH, 0.0, 100.0));
+        assert_eq!(scan_area(1, 4), (0.0, 100.0, 100.0 - SCAN_DEPTH, 100.0));
+        assert_eq!(scan_area(2, 4), (100.0 - SCAN_DEPTH, 100.0, 0.0, 100.0));
+        assert_eq!(scan_area(3, 4), (0.0, 100.0, 0.0, SCAN_DEPTH));
+
+        let mut reply = String::new();
+        handle_line("get lights", 4, &mut [0u8; 12], &mut reply);
+        assert!(reply.starts_with("lights 4\nlight 000 scan 0.00 10.00 0.00 100.00\n"));
+    }
+}
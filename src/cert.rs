@@ -1,9 +1,20 @@
-// Certificate Management Module - Auto-generate and manage self-signed SSL certificates
-use anyhow::{Context, Result};
+// Certificate Management Module - Auto-generate self-signed SSL certificates or obtain them via ACME (Let's Encrypt)
+use anyhow::{anyhow, Context, Result};
+use axum::{extract::{Path, State}, http::StatusCode, routing::get, Router};
+use instant_acme::{
+    Account, AccountCredentials, AuthorizationStatus, ChallengeType, Identifier, LetsEncrypt, NewAccount, NewOrder, OrderStatus,
+};
 use rcgen::{Certificate, CertificateParams, DistinguishedName, DnType, SanType};
+use serde::{de::DeserializeOwned, Serialize};
+use std::collections::HashMap;
 use std::fs;
-use std::path::PathBuf;
-use std::time::SystemTime;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime};
+
+// Let's Encrypt certificates are valid for 90 days - renew once they are 60 days old
+const ACME_RENEW_AFTER_DAYS: u64 = 60;
 
 /// Get the path to the config directory
 fn config_dir() -> Result<PathBuf> {
@@ -13,6 +24,24 @@ fn config_dir() -> Result<PathBuf> {
     Ok(config_dir)
 }
 
+/// Write a private key or account credentials readable by the owner only
+fn write_private(path: &Path, contents: &[u8]) -> Result<()> {
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::{OpenOptionsExt, PermissionsExt};
+        options.mode(0o600);
+        let mut file = options.open(path)?;
+        // The mode only applies to new files - tighten one left readable by an older version too
+        file.set_permissions(fs::Permissions::from_mode(0o600))?;
+        file.write_all(contents)?;
+    }
+    #[cfg(not(unix))]
+    options.open(path)?.write_all(contents)?;
+    Ok(())
+}
+
 /// Get paths to certificate files
 pub fn cert_paths() -> Result<(PathBuf, PathBuf)> {
     let dir = config_dir()?;
@@ -94,7 +123,7 @@ pub fn generate_certificate(hostname: &str) -> Result<()> {
 
     // Write to files
     fs::write(&cert_path, cert_pem)?;
-    fs::write(&key_path, key_pem)?;
+    write_private(&key_path, key_pem.as_bytes())?;
 
     println!("✅ Certificate generated successfully!");
     println!("   Cert: {:?}", cert_path);
@@ -133,11 +162,307 @@ pub fn ensure_certificates(hostname: &str) -> Result<()> {
 /// Load certificate and key from files
 pub fn load_certificates() -> Result<(Vec<u8>, Vec<u8>)> {
     let (cert_path, key_path) = cert_paths()?;
+    read_cert_files(&cert_path, &key_path)
+}
 
-    let cert = fs::read(&cert_path)
+fn read_cert_files(cert_path: &PathBuf, key_path: &PathBuf) -> Result<(Vec<u8>, Vec<u8>)> {
+    let cert = fs::read(cert_path)
         .with_context(|| format!("Failed to read certificate from {:?}", cert_path))?;
-    let key = fs::read(&key_path)
+    let key = fs::read(key_path)
         .with_context(|| format!("Failed to read private key from {:?}", key_path))?;
 
     Ok((cert, key))
 }
+
+/// Get paths to the ACME-issued certificate files (kept separate from the self-signed ones)
+pub fn acme_cert_paths() -> Result<(PathBuf, PathBuf)> {
+    let dir = config_dir()?;
+    Ok((dir.join("acme_cert.pem"), dir.join("acme_key.pem")))
+}
+
+/// Check if the ACME certificate is missing or due for renewal
+pub fn acme_cert_needs_renewal() -> Result<bool> {
+    let (cert_path, key_path) = acme_cert_paths()?;
+    renewal_due(&cert_path, &key_path, Duration::from_secs(ACME_RENEW_AFTER_DAYS * 24 * 60 * 60))
+}
+
+// Whether a certificate is missing (or its key is) or was written more than `renew_after` ago
+fn renewal_due(cert_path: &Path, key_path: &Path, renew_after: Duration) -> Result<bool> {
+    if !cert_path.exists() || !key_path.exists() {
+        return Ok(true);
+    }
+
+    let metadata = fs::metadata(cert_path)?;
+    if let Ok(modified) = metadata.modified() {
+        if let Ok(duration) = SystemTime::now().duration_since(modified) {
+            return Ok(duration > renew_after);
+        }
+    }
+
+    Ok(false)
+}
+
+/// Load the ACME-issued certificate chain and key
+pub fn load_acme_certificates() -> Result<(Vec<u8>, Vec<u8>)> {
+    let (cert_path, key_path) = acme_cert_paths()?;
+    read_cert_files(&cert_path, &key_path)
+}
+
+/// Ensure a valid ACME certificate exists, obtaining a new one if missing or expiring
+pub async fn ensure_acme_certificate(domain: &str, email: &str, staging: bool, http_port: u16) -> Result<()> {
+    if domain.is_empty() {
+        anyhow::bail!(
+            "ACME enabled but no domain configured.\n\
+             Please set 'acme_domain' in your config file to a public DNS name for this host.\n\
+             Example: acme_domain = \"leds.example.com\""
+        );
+    }
+
+    if acme_cert_needs_renewal()? {
+        obtain_acme_certificate(domain, email, staging, http_port).await?;
+    }
+
+    Ok(())
+}
+
+/// Obtain a certificate from Let's Encrypt using the HTTP-01 challenge
+/// A temporary responder serves /.well-known/acme-challenge/ on http_port while the order is validated
+pub async fn obtain_acme_certificate(domain: &str, email: &str, staging: bool, http_port: u16) -> Result<()> {
+    println!("\n🔐 Requesting Let's Encrypt certificate for: {}", domain);
+
+    let directory_url = if staging {
+        LetsEncrypt::Staging.url()
+    } else {
+        LetsEncrypt::Production.url()
+    };
+
+    let contact = format!("mailto:{}", email);
+    let contacts: Vec<&str> = if email.is_empty() { vec![] } else { vec![contact.as_str()] };
+
+    let account = acme_account(&contacts, directory_url, staging).await?;
+
+    let identifier = Identifier::Dns(domain.to_string());
+    let mut order = account
+        .new_order(&NewOrder { identifiers: &[identifier] })
+        .await
+        .map_err(|e| anyhow!("Failed to create ACME order: {}", e))?;
+
+    let authorizations = order
+        .authorizations()
+        .await
+        .map_err(|e| anyhow!("Failed to fetch ACME authorizations: {}", e))?;
+
+    // Collect HTTP-01 challenge responses (token -> key authorization)
+    let responses: Arc<Mutex<HashMap<String, String>>> = Arc::new(Mutex::new(HashMap::new()));
+    let mut challenge_urls = Vec::new();
+    for authz in &authorizations {
+        match authz.status {
+            AuthorizationStatus::Pending => {}
+            AuthorizationStatus::Valid => continue,
+            ref status => anyhow::bail!("ACME authorization in unexpected state: {:?}", status),
+        }
+
+        let challenge = authz.challenges.iter()
+            .find(|c| c.r#type == ChallengeType::Http01)
+            .ok_or_else(|| anyhow!("ACME server offered no HTTP-01 challenge"))?;
+
+        let key_authorization = order.key_authorization(challenge);
+        responses.lock().unwrap().insert(challenge.token.clone(), key_authorization.as_str().to_string());
+        challenge_urls.push(challenge.url.clone());
+    }
+
+    // Serve challenge responses until the order is validated
+    let responder = Router::new()
+        .route("/.well-known/acme-challenge/:token", get(acme_challenge_response))
+        .with_state(responses);
+    let listener = tokio::net::TcpListener::bind(("0.0.0.0", http_port))
+        .await
+        .with_context(|| format!("Failed to bind ACME challenge responder on port {}", http_port))?;
+    let responder_task = tokio::spawn(async move {
+        let _ = axum::serve(listener, responder).await;
+    });
+
+    let result = finish_acme_order(&mut order, &challenge_urls, domain).await;
+    responder_task.abort();
+    let (cert_chain_pem, key_pem) = result?;
+
+    let (cert_path, key_path) = acme_cert_paths()?;
+    fs::write(&cert_path, cert_chain_pem)?;
+    write_private(&key_path, key_pem.as_bytes())?;
+
+    println!("✅ Let's Encrypt certificate obtained for {}", domain);
+    println!("   Cert: {:?}", cert_path);
+    println!("   Key:  {:?}", key_path);
+
+    Ok(())
+}
+
+/// Get the path to the saved ACME account credentials (staging and production accounts are separate)
+fn acme_account_path(staging: bool) -> Result<PathBuf> {
+    let name = if staging { "acme_account_staging.json" } else { "acme_account.json" };
+    Ok(config_dir()?.join(name))
+}
+
+/// Restore the saved ACME account, or register a new one and save its credentials
+/// Reusing the account keeps renewals from registering a new account each time (and hitting Let's Encrypt's rate limits)
+async fn acme_account(contacts: &[&str], directory_url: &str, staging: bool) -> Result<Account> {
+    let account_path = acme_account_path(staging)?;
+    if account_path.exists() {
+        let restored = match load_credentials::<AccountCredentials>(&account_path) {
+            Ok(credentials) => Account::from_credentials(credentials).await.map_err(|e| anyhow!("{}", e)),
+            Err(e) => Err(e),
+        };
+        match restored {
+            Ok(account) => return Ok(account),
+            Err(e) => println!("⚠️  Could not restore the ACME account from {:?} ({}), registering a new one", account_path, e),
+        }
+    }
+
+    let (account, credentials) = Account::create(
+        &NewAccount {
+            contact: contacts,
+            terms_of_service_agreed: true,
+            only_return_existing: false,
+        },
+        directory_url,
+        None,
+    )
+    .await
+    .map_err(|e| anyhow!("Failed to create ACME account: {}", e))?;
+
+    save_credentials(&account_path, &credentials)
+        .with_context(|| format!("Failed to save ACME account credentials to {:?}", account_path))?;
+    Ok(account)
+}
+
+// Account credentials hold the account's private key - saved readable by the owner only
+fn save_credentials<T: Serialize>(path: &Path, credentials: &T) -> Result<()> {
+    write_private(path, serde_json::to_string_pretty(credentials)?.as_bytes())
+}
+
+fn load_credentials<T: DeserializeOwned>(path: &Path) -> Result<T> {
+    Ok(serde_json::from_str(&fs::read_to_string(path)?)?)
+}
+
+async fn acme_challenge_response(
+    State(responses): State<Arc<Mutex<HashMap<String, String>>>>,
+    Path(token): Path<String>,
+) -> Result<String, StatusCode> {
+    responses.lock().unwrap().get(&token).cloned().ok_or(StatusCode::NOT_FOUND)
+}
+
+/// Mark challenges ready, wait for validation, then finalize and download the certificate
+/// Returns (certificate chain PEM, private key PEM)
+async fn finish_acme_order(
+    order: &mut instant_acme::Order,
+    challenge_urls: &[String],
+    domain: &str,
+) -> Result<(String, String)> {
+    for url in challenge_urls {
+        order.set_challenge_ready(url)
+            .await
+            .map_err(|e| anyhow!("Failed to mark ACME challenge ready: {}", e))?;
+    }
+
+    // Poll with exponential backoff until the order is ready (or fails)
+    let mut delay = Duration::from_millis(500);
+    let mut tries = 0;
+    loop {
+        tokio::time::sleep(delay).await;
+        let state = order.refresh()
+            .await
+            .map_err(|e| anyhow!("Failed to refresh ACME order: {}", e))?;
+
+        match state.status {
+            OrderStatus::Ready => break,
+            OrderStatus::Invalid => anyhow::bail!("ACME order for {} was rejected (challenge failed)", domain),
+            _ => {}
+        }
+
+        tries += 1;
+        if tries >= 10 {
+            anyhow::bail!("Timed out waiting for ACME order validation");
+        }
+        delay = (delay * 2).min(Duration::from_secs(10));
+    }
+
+    // Generate a fresh key pair and CSR for the domain
+    let mut params = CertificateParams::new(vec![domain.to_string()]);
+    params.distinguished_name = DistinguishedName::new();
+    let cert = Certificate::from_params(params)?;
+    let csr = cert.serialize_request_der()?;
+
+    order.finalize(&csr)
+        .await
+        .map_err(|e| anyhow!("Failed to finalize ACME order: {}", e))?;
+
+    let mut tries = 0;
+    let cert_chain_pem = loop {
+        match order.certificate().await.map_err(|e| anyhow!("Failed to download certificate: {}", e))? {
+            Some(cert_chain_pem) => break cert_chain_pem,
+            None => {
+                tries += 1;
+                if tries >= 30 {
+                    anyhow::bail!("Timed out waiting for ACME certificate issuance");
+                }
+                tokio::time::sleep(Duration::from_secs(1)).await;
+            }
+        }
+    };
+
+    Ok((cert_chain_pem, cert.serialize_private_key_pem()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("rustwled_cert_{}_{}", name, std::process::id()))
+    }
+
+    #[test]
+    fn test_renewal_due() {
+        let cert_path = temp_path("renewal.pem");
+        let key_path = temp_path("renewal_key.pem");
+        let renew_after = Duration::from_secs(60 * 24 * 60 * 60);
+        let _ = fs::remove_file(&cert_path);
+        let _ = fs::remove_file(&key_path);
+
+        // Missing certificate or key
+        assert!(renewal_due(&cert_path, &key_path, renew_after).unwrap());
+        fs::write(&cert_path, "cert").unwrap();
+        assert!(renewal_due(&cert_path, &key_path, renew_after).unwrap());
+
+        // Freshly written
+        write_private(&key_path, b"key").unwrap();
+        assert!(!renewal_due(&cert_path, &key_path, renew_after).unwrap());
+
+        // Written longer ago than the renewal age
+        let written = SystemTime::now() - renew_after - Duration::from_secs(60);
+        fs::File::options().write(true).open(&cert_path).unwrap().set_modified(written).unwrap();
+        assert!(renewal_due(&cert_path, &key_path, renew_after).unwrap());
+
+        let _ = fs::remove_file(&cert_path);
+        let _ = fs::remove_file(&key_path);
+    }
+
+    #[test]
+    fn test_credentials_round_trip() {
+        let path = temp_path("account.json");
+        let credentials = serde_json::json!({ "id": "https://acme.example/acct/1", "key_pkcs8": "c2VjcmV0" });
+
+        // Left readable by an older version - saving tightens it
+        fs::write(&path, "{}").unwrap();
+        save_credentials(&path, &credentials).unwrap();
+        assert_eq!(load_credentials::<serde_json::Value>(&path).unwrap(), credentials);
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            assert_eq!(fs::metadata(&path).unwrap().permissions().mode() & 0o777, 0o600);
+        }
+
+        let _ = fs::remove_file(&path);
+    }
+}
@@ -0,0 +1,379 @@
+// CLI Module - One-shot subcommands (discover, import-palettes, preset, set, validate)
+use anyhow::{anyhow, Result};
+use std::io::{Read, Write};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::path::PathBuf;
+use std::time::Duration;
+
+use crate::config::{self, Args, BandwidthConfig, ConfigDelta, NamedGradientConfig, PresetAction};
+use crate::discovery;
+use crate::gradients;
+use crate::multi_device::MultiDeviceConfig;
+use crate::sync;
+
+// Every mode the mode loop knows how to start
+pub const KNOWN_MODES: &[&str] = &["bandwidth", "midi", "live", "relay", "ddp", "sacn", "artnet", "boblight", "webcam", "tron", "geometry", "sand", "demo"];
+
+/// Fetch WLED's /json/info from a host (IP or hostname) - None if nothing answers within the timeout
+pub fn fetch_wled_info(host: &str, timeout: Duration) -> Option<serde_json::Value> {
+    fetch_wled_json(host, "/json/info", timeout)
+}
+
+/// GET a JSON document from a WLED host's web server - None on connection, HTTP or parse failure
+pub fn fetch_wled_json(host: &str, path: &str, timeout: Duration) -> Option<serde_json::Value> {
+    let addr = (host, 80).to_socket_addrs().ok()?.next()?;
+    let mut stream = TcpStream::connect_timeout(&addr, timeout).ok()?;
+    stream.set_read_timeout(Some(timeout)).ok()?;
+    stream.set_write_timeout(Some(timeout)).ok()?;
+    write!(stream, "GET {} HTTP/1.0\r\nHost: {}\r\n\r\n", path, host).ok()?;
+
+    // HTTP/1.0 - the server closes the connection after the body
+    let mut response = Vec::new();
+    stream.read_to_end(&mut response).ok()?;
+    let response = String::from_utf8_lossy(&response);
+    let body = response.split_once("\r\n\r\n")?.1;
+    serde_json::from_str(body).ok()
+}
+
+/// POST a JSON document to a WLED host's web server - Err on connection failure or a non-2xx status
+pub fn post_wled_json(host: &str, path: &str, body: &serde_json::Value, timeout: Duration) -> Result<()> {
+    let addr = (host, 80).to_socket_addrs()?.next().ok_or_else(|| anyhow!("Could not resolve {}", host))?;
+    let mut stream = TcpStream::connect_timeout(&addr, timeout)?;
+    stream.set_read_timeout(Some(timeout))?;
+    stream.set_write_timeout(Some(timeout))?;
+    let body = body.to_string();
+    write!(stream, "POST {} HTTP/1.0\r\nHost: {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\r\n{}", path, host, body.len(), body)?;
+
+    let mut response = Vec::new();
+    stream.read_to_end(&mut response)?;
+    let response = String::from_utf8_lossy(&response);
+    match response.split_whitespace().nth(1) {
+        Some(status) if status.starts_with('2') => Ok(()),
+        status => Err(anyhow!("{} answered {} with HTTP {}", host, path, status.unwrap_or("?"))),
+    }
+}
+
+/// Find WLED controllers and DDP receivers on the LAN, print them, and optionally add new ones to the config
+pub fn discover(timeout: Duration, write: bool) -> Result<()> {
+    println!("Scanning for WLED and DDP devices (mDNS and the local /24)...");
+    let found = discovery::scan(timeout);
+    if found.is_empty() {
+        println!("No devices found");
+        return Ok(());
+    }
+
+    let mut config = BandwidthConfig::load()?;
+    discovery::print_table(&config, &found);
+    if found.iter().any(|d| d.led_count.is_none()) {
+        println!("Devices marked ? don't report their LED count - add them in the web UI or config file");
+    }
+
+    if !write {
+        let new_count = found.iter().filter(|d| d.led_count.is_some() && !discovery::is_configured(&config, d)).count();
+        if new_count > 0 {
+            println!("Run \"rustwled discover --write\" to add {} new device(s) to the config", new_count);
+        }
+        return Ok(());
+    }
+
+    let added = discovery::add_to_config(&mut config, &found);
+    if added > 0 {
+        config.save()?;
+    }
+    println!("Added {} device(s) to wled_devices", added);
+    Ok(())
+}
+
+// Palettes from a WLED device - names come from /json/palettes, colors from the paged /json/palx
+fn fetch_wled_palettes(host: &str) -> Result<Vec<NamedGradientConfig>> {
+    let timeout = Duration::from_secs(5);
+    let names: Vec<String> = fetch_wled_json(host, "/json/palettes", timeout)
+        .and_then(|v| serde_json::from_value(v).ok())
+        .ok_or_else(|| anyhow!("{} did not answer as a WLED device", host))?;
+
+    let mut palettes = Vec::new();
+    let mut page = 0;
+    loop {
+        let palx = fetch_wled_json(host, &format!("/json/palx?page={}", page), timeout)
+            .ok_or_else(|| anyhow!("Failed to read palette page {} from {}", page, host))?;
+        palettes.extend(gradients::import_wled_palx(&palx, &names));
+        // "m" is the last page number
+        page += 1;
+        if page > palx["m"].as_u64().unwrap_or(0) {
+            break;
+        }
+    }
+    Ok(palettes)
+}
+
+/// Import WLED palettes from a device or an exported JSON file, print them, and optionally add them to the config
+pub fn import_palettes(source: &str, write: bool) -> Result<()> {
+    let path = PathBuf::from(source);
+    let palettes = if path.is_file() {
+        let json: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(&path)?)
+            .map_err(|e| anyhow!("{} is not valid JSON: {}", path.display(), e))?;
+        let name = path.file_stem().map(|s| s.to_string_lossy().to_string()).unwrap_or_default();
+        gradients::import_wled_palette_file(&json, &name)
+    } else {
+        println!("Reading palettes from {}...", source);
+        fetch_wled_palettes(source)?
+    };
+
+    if palettes.is_empty() {
+        println!("No importable palettes found (palettes built from segment colors are skipped)");
+        return Ok(());
+    }
+
+    let mut config = BandwidthConfig::load()?;
+    let is_defined = |config: &BandwidthConfig, name: &str| config.gradients.iter().any(|g| g.name.eq_ignore_ascii_case(name));
+
+    for palette in &palettes {
+        let marker = if is_defined(&config, &palette.name) { " (defined)" } else { "" };
+        println!("{:<32} {:>3} stops{}", palette.name, palette.stops.len(), marker);
+    }
+    println!("{} palette(s) found", palettes.len());
+
+    if !write {
+        println!("Run \"rustwled import-palettes {} --write\" to add them to the config's gradients", source);
+        return Ok(());
+    }
+
+    for palette in palettes {
+        config.gradients.retain(|g| !g.name.eq_ignore_ascii_case(&palette.name));
+        config.gradients.push(palette);
+    }
+    config.save()?;
+    println!("Saved to gradients - use them by name, e.g. color = \"{}\"", config.gradients.last().map(|g| g.name.as_str()).unwrap_or_default());
+    Ok(())
+}
+
+// Presets live next to the active config: <config dir>/presets/<name>.conf
+/// Directory saved presets live in, next to the config
+pub fn presets_dir() -> Result<PathBuf> {
+    let config_path = BandwidthConfig::config_path(None)?;
+    Ok(config::config_watch_dir(&config_path).join("presets"))
+}
+
+fn preset_path(name: &str) -> Result<PathBuf> {
+    if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_') {
+        return Err(anyhow!("Invalid preset name '{}' - use letters, digits, '-' and '_'", name));
+    }
+    Ok(presets_dir()?.join(format!("{}.conf", name)))
+}
+
+/// Read a saved preset as a config
+pub fn load_preset(name: &str) -> Result<BandwidthConfig> {
+    let path = preset_path(name)?;
+    let contents = std::fs::read_to_string(&path)
+        .map_err(|_| anyhow!("No preset named '{}'", name))?;
+    let mut preset: BandwidthConfig = toml::from_str(&contents)
+        .map_err(|e| anyhow!("Preset '{}' is not a valid config: {}", name, e))?;
+    preset.sanitize();
+    Ok(preset)
+}
+
+/// List, save, apply or delete config presets
+pub fn preset(action: PresetAction) -> Result<()> {
+    match action {
+        PresetAction::List => {
+            let dir = presets_dir()?;
+            let mut names: Vec<String> = std::fs::read_dir(&dir)
+                .map(|entries| {
+                    entries
+                        .filter_map(|e| e.ok())
+                        .map(|e| e.path())
+                        .filter(|p| p.extension().map(|ext| ext == "conf").unwrap_or(false))
+                        .filter_map(|p| p.file_stem().map(|s| s.to_string_lossy().to_string()))
+                        .collect()
+                })
+                .unwrap_or_default();
+            names.sort();
+
+            if names.is_empty() {
+                println!("No presets saved in {}", dir.display());
+            } else {
+                for name in names {
+                    println!("{}", name);
+                }
+            }
+        }
+        PresetAction::Save { name } => {
+            let path = preset_path(&name)?;
+            let contents = config::read_config_file(&BandwidthConfig::config_path(None)?)?;
+            std::fs::create_dir_all(presets_dir()?)?;
+            std::fs::write(&path, contents)?;
+            println!("Saved preset '{}'", name);
+        }
+        PresetAction::Apply { name } => {
+            let preset = load_preset(&name)?;
+            let config = BandwidthConfig::load()?;
+            let mut applied = apply_preset(&config, &preset)?;
+
+            // Write through save() so the result is sanitized and a running instance reloads it
+            applied.config_path = Some(BandwidthConfig::config_path(None)?);
+            applied.save()?;
+            println!("Applied preset '{}'", name);
+        }
+        PresetAction::Delete { name } => {
+            let path = preset_path(&name)?;
+            std::fs::remove_file(&path).map_err(|_| anyhow!("No preset named '{}'", name))?;
+            println!("Deleted preset '{}'", name);
+        }
+    }
+    Ok(())
+}
+
+// The config with the preset's look taken over - devices, server, auth, tokens and sync stay as they are here,
+// since a preset is a snapshot of an older config and would otherwise bring back revoked tokens and old users
+fn apply_preset(config: &BandwidthConfig, preset: &BandwidthConfig) -> Result<BandwidthConfig> {
+    let mut map = config::config_key_map(config);
+    for (key, value) in config::config_key_map(preset) {
+        if !config::DEVICE_CONFIG_KEYS.contains(&key.as_str()) && !sync::is_local_key(&key) {
+            map.insert(key, value);
+        }
+    }
+    Ok(serde_json::from_value(serde_json::Value::Object(map))?)
+}
+
+// Apply KEY=VALUE pairs through the flat key map, so every config key can be set by its file name
+// Values for string keys are taken literally; everything else is parsed as JSON (numbers, true/false, arrays)
+fn apply_key_values(config: &BandwidthConfig, values: &[String]) -> Result<BandwidthConfig> {
+    let mut map = config::config_key_map(config);
+    for pair in values {
+        let (key, raw) = pair.split_once('=')
+            .ok_or_else(|| anyhow!("Expected KEY=VALUE, got '{}'", pair))?;
+        let key = key.trim();
+        let value = match map.get(key) {
+            Some(serde_json::Value::String(_)) => serde_json::Value::String(raw.to_string()),
+            Some(_) => serde_json::from_str(raw.trim())
+                .map_err(|_| anyhow!("Invalid value for {}: {}", key, raw))?,
+            None => return Err(anyhow!("Unknown config key '{}'", key)),
+        };
+        map.insert(key.to_string(), value);
+    }
+
+    let mut updated: BandwidthConfig = serde_json::from_value(serde_json::Value::Object(map))
+        .map_err(|e| anyhow!("Invalid value: {}", e))?;
+    updated.config_path = config.config_path.clone();
+    Ok(updated)
+}
+
+/// Change settings in the config file and exit
+pub fn set(args: &Args, brightness: Option<f64>, values: &[String]) -> Result<()> {
+    let original = BandwidthConfig::load()?;
+    let mut config = original.clone();
+
+    let mut requested = config.merge_with_args(args);
+    if let Some(percent) = brightness {
+        config.global_brightness = (percent / 100.0).clamp(0.0, 1.0);
+        requested = true;
+    }
+    if !values.is_empty() {
+        config = apply_key_values(&config, values)?;
+        requested = true;
+    }
+    if !requested {
+        return Err(anyhow!("Nothing to set - e.g. \"rustwled set --mode live\" or \"rustwled set --brightness 40\""));
+    }
+
+    // Checked on the result so mode=... is caught as well as --mode
+    if config.mode != original.mode && !KNOWN_MODES.contains(&config.mode.as_str()) {
+        return Err(anyhow!("Unknown mode '{}' (expected one of: {})", config.mode, KNOWN_MODES.join(", ")));
+    }
+
+    config.sanitize();
+    match ConfigDelta::between(&original, &config) {
+        Some(delta) => {
+            config.save()?;
+            println!("Updated {}", delta.keys().join(", "));
+        }
+        None => println!("Nothing changed"),
+    }
+    Ok(())
+}
+
+/// Check a config file for parse errors, unknown keys and inconsistent settings
+pub fn validate(cfg_arg: Option<&str>) -> Result<()> {
+    let path = BandwidthConfig::config_path(cfg_arg)?;
+    println!("Validating {}", path.display());
+
+    let contents = config::read_config_file(&path)?;
+    let mut errors: Vec<String> = Vec::new();
+    let mut warnings: Vec<String> = Vec::new();
+
+    // Keys the config no longer (or never did) understand are ignored on load
+    let table: toml::Table = toml::from_str(&contents).map_err(|e| anyhow!("Parse error: {}", e))?;
+    let known = config::config_key_map(&BandwidthConfig::default());
+    for key in table.keys().filter(|k| !known.contains_key(k.as_str())) {
+        warnings.push(format!("Unknown key '{}' is ignored", key));
+    }
+
+    let mut parsed: BandwidthConfig = toml::from_str(&contents).map_err(|e| anyhow!("Invalid value: {}", e))?;
+    parsed.sanitize();
+
+    if !KNOWN_MODES.contains(&parsed.mode.as_str()) {
+        errors.push(format!("Unknown mode '{}' (expected one of: {})", parsed.mode, KNOWN_MODES.join(", ")));
+    }
+
+    if parsed.wled_devices.is_empty() && parsed.wled_ip.is_empty() {
+        errors.push("No WLED devices configured".to_string());
+    } else if !parsed.wled_devices.is_empty() {
+        if let Err(e) = MultiDeviceConfig::from_config(&parsed).validate() {
+            errors.push(e.to_string());
+        }
+    }
+
+    if parsed.total_leds == 0 {
+        errors.push("total_leds must be greater than 0".to_string());
+    }
+
+    if parsed.matrix.enabled && parsed.matrix.width * parsed.matrix.height > parsed.total_leds {
+        warnings.push(format!(
+            "2D matrix is {}x{} ({} LEDs) but only {} LEDs are configured",
+            parsed.matrix.width,
+            parsed.matrix.height,
+            parsed.matrix.width * parsed.matrix.height,
+            parsed.total_leds
+        ));
+    }
+
+    for warning in &warnings {
+        println!("  warning: {}", warning);
+    }
+    for error in &errors {
+        println!("  error: {}", error);
+    }
+
+    if errors.is_empty() {
+        println!("OK ({} warning(s))", warnings.len());
+        Ok(())
+    } else {
+        Err(anyhow!("{} error(s) found in {}", errors.len(), path.display()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pairs(values: &[&str]) -> Vec<String> {
+        values.iter().map(|v| v.to_string()).collect()
+    }
+
+    #[test]
+    fn test_apply_key_values() {
+        let config = BandwidthConfig::default();
+
+        // Strings are taken literally, everything else parsed - keys nested in sections keep their file names
+        let updated = apply_key_values(&config, &pairs(&["color=fire", "fps=30", "noise_floor=true", "attack_ms=12.5"])).unwrap();
+        assert_eq!(updated.color, "fire");
+        assert_eq!(updated.fps, 30.0);
+        assert!(updated.audio.noise_floor);
+        assert_eq!(updated.audio.attack_ms, 12.5);
+
+        // Nothing is applied when any pair is wrong
+        assert!(apply_key_values(&config, &pairs(&["no_such_key=1"])).is_err());
+        assert!(apply_key_values(&config, &pairs(&["fps=fast"])).is_err());
+        assert!(apply_key_values(&config, &pairs(&["fps"])).is_err());
+    }
+}
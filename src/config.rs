@@ -1,15 +1,74 @@
 // Config Module - Configuration management and command-line argument parsing
 use anyhow::Result;
-use clap::Parser;
+use clap::{Parser, Subcommand};
+use fs2::FileExt;
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
-use std::sync::OnceLock;
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, OnceLock};
+use std::thread;
+use tokio::sync::broadcast;
 
 use crate::gradients;
 
 // Global storage for custom config path
 static CUSTOM_CONFIG_PATH: OnceLock<Option<String>> = OnceLock::new();
 
+// Advisory lock taken around config reads (shared) and writes (exclusive)
+// A sidecar file is locked because saves replace the config file itself
+fn lock_config(path: &Path, exclusive: bool) -> Result<File> {
+    let lock = OpenOptions::new().create(true).write(true).open(path.with_extension("lock"))?;
+    if exclusive {
+        lock.lock_exclusive()?;
+    } else {
+        lock.lock_shared()?;
+    }
+    Ok(lock)
+}
+
+/// Read the config file under a shared lock
+pub fn read_config_file(path: &Path) -> Result<String> {
+    let _lock = lock_config(path, false)?;
+    Ok(std::fs::read_to_string(path)?)
+}
+
+/// Replace the config file atomically - write a temp file in the same directory, then rename it over
+/// Readers (including the file watcher) see either the old or the new file, never a partial one
+pub fn write_config_file(path: &Path, contents: &str) -> Result<()> {
+    static WRITES: AtomicUsize = AtomicUsize::new(0);
+
+    let _lock = lock_config(path, true)?;
+    // Unique per write, so a writer that doesn't honor the lock (another version, an editor) can't share it
+    let tmp_path = path.with_extension(format!("tmp.{}.{}", std::process::id(), WRITES.fetch_add(1, Ordering::Relaxed)));
+    let written = File::create(&tmp_path)
+        .and_then(|mut tmp| {
+            tmp.write_all(contents.as_bytes())?;
+            tmp.sync_all()
+        })
+        .and_then(|_| std::fs::rename(&tmp_path, path));
+    if written.is_err() {
+        let _ = std::fs::remove_file(&tmp_path);
+    }
+    Ok(written?)
+}
+
+/// Whether a watcher event is a change to the config file
+/// Watchers watch the config directory, since atomic saves replace the file instead of modifying it
+pub fn is_config_event(event: &notify::Event, config_path: &Path) -> bool {
+    matches!(event.kind, notify::EventKind::Modify(_) | notify::EventKind::Create(_))
+        && event.paths.iter().any(|p| p.file_name() == config_path.file_name())
+}
+
+/// Directory to watch for config changes
+pub fn config_watch_dir(config_path: &Path) -> PathBuf {
+    match config_path.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir.to_path_buf(),
+        _ => PathBuf::from("."),
+    }
+}
+
 /// Unified color resolution system for bandwidth and live modes
 /// Returns (tx_color_resolved, rx_color_resolved) as comma-separated hex strings
 /// Handles the logic: if tx_color is empty, use color; if rx_color is empty, use color
@@ -31,6 +90,26 @@ pub fn resolve_tx_rx_colors(config: &BandwidthConfig) -> (String, String) {
     (tx_color_str, rx_color_str)
 }
 
+/// Color resolution for the VU meter's audio channels
+/// Returns (left_color_resolved, right_color_resolved); an empty vu_left_color / vu_right_color falls back
+/// to the bandwidth mapping (left = RX, right = TX)
+pub fn resolve_vu_colors(config: &BandwidthConfig) -> (String, String) {
+    let (tx_color_str, rx_color_str) = resolve_tx_rx_colors(config);
+
+    let left_color_str = if !config.vu.left_color.is_empty() {
+        gradients::resolve_color_string(&config.vu.left_color)
+    } else {
+        rx_color_str
+    };
+    let right_color_str = if !config.vu.right_color.is_empty() {
+        gradients::resolve_color_string(&config.vu.right_color)
+    } else {
+        tx_color_str
+    };
+
+    (left_color_str, right_color_str)
+}
+
 #[derive(Parser, Debug)]
 #[command(
     author,
@@ -41,89 +120,247 @@ pub fn resolve_tx_rx_colors(config: &BandwidthConfig) -> (String, String) {
                   Supports both linear and logarithmic scaling, custom color gradients, and remote gateway monitoring."
 )]
 pub struct Args {
+    /// Command to run (defaults to "run" - the plain flags below keep working without it)
+    #[command(subcommand)]
+    pub command: Option<Command>,
+
     /// Maximum bandwidth in Gbps
-    #[arg(short, long)]
+    #[arg(short, long, global = true)]
     pub max: Option<f64>,
 
     /// LED colors (for both TX and RX unless overridden)
-    #[arg(short, long)]
+    #[arg(short, long, global = true)]
     pub color: Option<String>,
 
     /// TX LED colors
-    #[arg(long)]
+    #[arg(long, global = true)]
     pub tx_color: Option<String>,
 
     /// RX LED colors
-    #[arg(long)]
+    #[arg(long, global = true)]
     pub rx_color: Option<String>,
 
     /// Remote SSH host
-    #[arg(short = 'H', long)]
+    #[arg(short = 'H', long, global = true)]
     pub host: Option<String>,
 
     /// WLED device address
-    #[arg(short, long)]
+    #[arg(short, long, global = true)]
     pub wled_ip: Option<String>,
 
     /// Network interface to monitor
-    #[arg(short = 'i', long = "int")]
+    #[arg(short = 'i', long = "int", global = true)]
     pub interface: Option<String>,
 
     /// Total number of LEDs
-    #[arg(short = 'L', long)]
+    #[arg(short = 'L', long, global = true)]
     pub leds: Option<usize>,
 
     /// LED fill direction mode
-    #[arg(short = 'd', long)]
+    #[arg(short = 'd', long, global = true)]
     pub direction: Option<String>,
 
     /// Swap TX and RX half assignments
-    #[arg(short = 's', long)]
+    #[arg(short = 's', long, global = true)]
     pub swap: Option<bool>,
 
     /// Test mode
-    #[arg(short = 't', long)]
+    #[arg(short = 't', long, global = true)]
     pub test: Option<String>,
 
+    /// Test pattern instead of stepping through LEDs (chase, rainbow, rgborder, white)
+    #[arg(long, global = true)]
+    pub test_pattern: Option<String>,
+
+    /// Limit test mode to one configured device (index or IP, or "all" for each device in its own range)
+    /// LED numbers in the test sequence are then relative to the device's first LED
+    #[arg(long, global = true)]
+    pub test_device: Option<String>,
+
+    /// Test pattern speed multiplier (default 1.0)
+    #[arg(long, global = true)]
+    pub test_speed: Option<f64>,
+
+    /// Test pattern brightness in percent (default 50 - raise it to load-test the power supply)
+    #[arg(long, global = true)]
+    pub test_brightness: Option<f64>,
+
     /// Quiet mode
-    #[arg(short = 'q', long)]
+    #[arg(short = 'q', long, global = true)]
     pub quiet: bool,
 
-    /// Visualization mode (bandwidth, midi, live, relay) - overrides --midi and --live flags
-    #[arg(long)]
+    /// Visualization mode (bandwidth, midi, live, relay, ddp) - overrides --midi and --live flags
+    #[arg(long, global = true)]
     pub mode: Option<String>,
 
     /// Enable MIDI mode (kept for backwards compatibility, use --mode=midi instead)
-    #[arg(short = 'M', long)]
+    #[arg(short = 'M', long, global = true)]
     pub midi: bool,
 
     /// MIDI device name (default: "IAC Bus 1" on macOS)
-    #[arg(long)]
+    #[arg(long, global = true)]
     pub midi_device: Option<String>,
 
     /// Shuffle the 12 primary colors randomly at launch
-    #[arg(long)]
+    #[arg(long, global = true)]
     pub midi_random_colors: bool,
 
     /// Live audio spectrum visualization mode (kept for backwards compatibility, use --mode=live instead)
-    #[arg(long)]
+    #[arg(long, global = true)]
     pub live: bool,
 
+    /// Bandwidth mode: replay recorded history instead of live traffic - "AGO" or "AGO+LENGTH" (e.g. "8h", "10h+30m")
+    #[arg(long, global = true)]
+    pub replay: Option<String>,
+
+    /// Replay speed multiplier (default 60 - an hour of history plays in a minute)
+    #[arg(long, global = true)]
+    pub replay_speed: Option<f64>,
+
     /// Delay in milliseconds before sending to WLED (for audio/video sync)
-    #[arg(long)]
+    #[arg(long, global = true)]
     pub delay: Option<u64>,
 
     /// Audio test mode - test audio capture and show peak/RMS levels
-    #[arg(long)]
+    #[arg(long, global = true)]
     pub audio_test: bool,
 
     /// Target framerate (frames per second) for test mode and other modes
-    #[arg(long)]
+    #[arg(long, global = true)]
     pub fps: Option<f64>,
 
     /// Config file path or name (e.g., --cfg /full/path or --cfg myconf for ~/.config/rustwled/myconf.conf)
-    #[arg(long)]
+    #[arg(long, global = true)]
     pub cfg: Option<String>,
+
+    /// Look for WLED/DDP devices on the LAN (mDNS) before starting and add new ones to wled_devices
+    #[arg(long, global = true)]
+    pub discover: bool,
+}
+
+#[derive(Subcommand, Debug, Clone)]
+pub enum Command {
+    /// Run the configured visualization mode (default)
+    Run,
+    /// Step through LEDs to check wiring (e.g. "0-9" or "0,5,10"), show a --test-pattern, or test audio capture with --audio
+    Test {
+        /// LEDs to light in sequence
+        sequence: Option<String>,
+        /// Test audio capture and show peak/RMS levels instead
+        #[arg(long)]
+        audio: bool,
+    },
+    /// Find WLED and DDP devices on the local network (mDNS plus a JSON API probe of the subnet)
+    Discover {
+        /// Seconds to wait for each address to answer
+        #[arg(long, default_value_t = 1.0)]
+        timeout: f64,
+        /// Add devices that are not configured yet to wled_devices
+        #[arg(long)]
+        write: bool,
+    },
+    /// Import WLED palettes as named gradients - from a device (IP or hostname) or a palette JSON exported from one
+    ImportPalettes {
+        /// WLED host, or a JSON file (a custom palette file or a saved /json/palx response)
+        source: String,
+        /// Add the palettes to the config's gradients (replacing ones with the same name)
+        #[arg(long)]
+        write: bool,
+    },
+    /// Manage named config presets (config files stored next to the main config)
+    Preset {
+        #[command(subcommand)]
+        action: PresetAction,
+    },
+    /// Change settings in the config file and exit - a running instance picks them up immediately
+    /// Accepts the usual flags (e.g. "set --mode live", "set --color fire") plus KEY=VALUE pairs
+    Set {
+        /// Global brightness in percent (0-100)
+        #[arg(long)]
+        brightness: Option<f64>,
+        /// Any other config key (e.g. tron_speed=20 or sand_spawn_enabled=false)
+        #[arg(value_name = "KEY=VALUE")]
+        values: Vec<String>,
+    },
+    /// Render each mode headlessly and report frames/sec, frame times and allocations
+    Bench {
+        /// Modes to benchmark (default: all)
+        modes: Vec<String>,
+        /// Seconds to run each mode
+        #[arg(long, default_value_t = 5.0)]
+        seconds: f64,
+    },
+    /// Check the config file for errors and exit
+    Validate,
+    /// Check devices, audio, MIDI, SSH and matrix settings and print a pass/fail report
+    Doctor,
+    /// Measure frame delivery timing and jitter per device, to help tune fps and ddp_delay_ms
+    Latency {
+        /// Seconds to measure for
+        #[arg(long, default_value_t = 10.0)]
+        seconds: f64,
+        /// Also time HTTP requests to each WLED's JSON API
+        #[arg(long)]
+        ping: bool,
+    },
+    /// Play clicks through the speakers and time them on the audio input to set ddp_delay_ms
+    Calibrate {
+        /// Number of clicks to average over
+        #[arg(long, default_value_t = 5)]
+        clicks: usize,
+        /// Save the measured delay as ddp_delay_ms
+        #[arg(long)]
+        write: bool,
+    },
+    /// Run the configured mode while recording every output frame to a file
+    Record {
+        /// File to write the recording to
+        output: PathBuf,
+    },
+    /// Play a recording back to the configured devices
+    Play {
+        /// Recording to play
+        input: PathBuf,
+        /// Start over when the recording ends
+        #[arg(long = "loop")]
+        repeat: bool,
+    },
+    /// Run a virtual WLED that draws the DDP frames it receives in the terminal (devices with ip = "virtual" send to it)
+    Virtual {
+        /// UDP port to listen on (devices use ip = "virtual:PORT" for ports other than 4049)
+        #[arg(long, default_value_t = crate::virtual_device::VIRTUAL_DEVICE_PORT)]
+        port: u16,
+        /// LEDs per row (default: as many as fit the terminal)
+        #[arg(long)]
+        columns: Option<usize>,
+    },
+    /// Serve this machine's interface counters to bandwidth mode elsewhere (list it in agent_hosts there) - Linux only
+    Agent {
+        /// Address to listen on
+        #[arg(long, default_value = "0.0.0.0")]
+        listen: String,
+        /// TCP port to listen on
+        #[arg(long, default_value_t = crate::agent::AGENT_PORT)]
+        port: u16,
+        /// Shared secret, the same as agent_token on the bandwidth host (default: $RUSTWLED_AGENT_TOKEN)
+        #[arg(long)]
+        token: Option<String>,
+        /// Interfaces to report, comma separated (default: all but lo)
+        #[arg(long)]
+        interface: Option<String>,
+    },
+}
+
+#[derive(Subcommand, Debug, Clone)]
+pub enum PresetAction {
+    /// List saved presets
+    List,
+    /// Save the current config as a preset
+    Save { name: String },
+    /// Replace the current config with a preset (a running instance picks it up immediately)
+    Apply { name: String },
+    /// Delete a preset
+    Delete { name: String },
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -132,6 +369,757 @@ pub struct WLEDDeviceConfig {
     pub led_offset: usize,
     pub led_count: usize,
     pub enabled: bool,
+    #[serde(default)]
+    pub max_fps: f64,  // Maximum frames per second sent to this device (0 = unlimited)
+    #[serde(default = "default_led_ma")]
+    pub led_ma: f64,  // Current draw of one LED at full white in milliamps
+    #[serde(default)]
+    pub psu_amps: f64,  // Power supply limit in amps for this device (0 = no current limiting)
+    #[serde(default)]
+    pub backup_for: String,  // IP of the primary device this one takes over for (empty = not a backup)
+    #[serde(default)]
+    pub reversed: bool,  // Reverse LED order on this device (strip mounted backwards)
+    #[serde(default)]
+    pub mirrored: bool,  // Mirror the first half of this device's range onto the second half (the last half when also reversed)
+    #[serde(default = "default_segment")]
+    pub segment: i32,  // WLED segment id to confine output to (-1 = the device's whole range)
+    #[serde(default = "default_protocol")]
+    pub protocol: String,  // Output protocol: "ddp" (WLED), "sacn" (E1.31) or "artnet" (for pixel controllers)
+    #[serde(default = "default_device_universe")]
+    pub universe: u16,  // First universe: sACN 1-63999, Art-Net 0-15 within the net and sub-net
+    #[serde(default = "default_start_channel")]
+    pub start_channel: u16,  // DMX channel of the first LED in the first universe, 1-510 (sacn, artnet)
+    #[serde(default)]
+    pub net: u8,  // Art-Net net, 0-127 (artnet protocol only)
+    #[serde(default)]
+    pub subnet: u8,  // Art-Net sub-net, 0-15 (artnet protocol only)
+}
+
+fn default_led_ma() -> f64 {
+    55.0  // Typical WS2812B at full white (same default as WLED)
+}
+
+fn default_segment() -> i32 {
+    -1
+}
+
+fn default_protocol() -> String {
+    "ddp".to_string()
+}
+
+fn default_start_channel() -> u16 {
+    1
+}
+
+// Devices that don't set a universe start at their protocol's first one - filled in by sanitize()
+const UNSET_UNIVERSE: u16 = u16::MAX;
+
+fn default_device_universe() -> u16 {
+    UNSET_UNIVERSE
+}
+
+/// Additional relay input - one UDP listener whose frames are written to an LED range of the relayed frame
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RelayListenerConfig {
+    pub listen_ip: String,  // IP address to listen on
+    pub listen_port: u16,  // UDP listen port
+    pub protocol: String,  // Input protocol: "raw" (RGB24 stream), "ddp", "sacn", "artnet", or "boblight"
+    pub frame_width: usize,  // Frame width in pixels
+    pub frame_height: usize,  // Frame height in pixels
+    pub led_offset: usize,  // First LED this listener's frame is written to
+    #[serde(default = "default_universe")]
+    pub universe: u16,  // First sACN universe (170 LEDs per universe, sacn protocol only)
+    pub enabled: bool,
+}
+
+fn default_universe() -> u16 {
+    1  // sACN universes start at 1
+}
+
+/// Art-Net universe mapping - one universe's DMX channels (3 per LED) written to an LED range
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ArtnetUniverseConfig {
+    pub universe: u16,  // Port-address (net, sub-net and universe as one 15-bit number)
+    pub led_offset: usize,  // First LED the universe's data is written to
+    #[serde(default = "default_artnet_led_count")]
+    pub led_count: usize,  // LEDs taken from the universe (up to 170)
+}
+
+fn default_artnet_led_count() -> usize {
+    170  // A full universe
+}
+
+/// Physical button on a GPIO pin (BCM numbering) and the action it triggers
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GpioButtonConfig {
+    pub pin: u8,  // BCM pin number, wired to ground through the button
+    pub action: String,  // "next_mode", "prev_mode", "brightness_up", "brightness_down", "blank"
+}
+
+/// Hue light in the entertainment area and the LED range whose average color it shows
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HueLightConfig {
+    pub light_id: u16,  // Light id in the entertainment area (as listed by the bridge)
+    pub led_offset: usize,  // First LED of the range
+    pub led_count: usize,  // LEDs averaged into the light's color
+}
+
+/// LIFX bulb and the LED zone whose average color it shows
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LifxDeviceConfig {
+    pub ip: String,
+    pub led_offset: usize,  // First LED of the zone
+    pub led_count: usize,  // LEDs averaged into the bulb's color
+    pub enabled: bool,
+}
+
+/// Nanoleaf panel and the LED zone whose average color it shows
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NanoleafPanelConfig {
+    pub panel_id: u16,  // Panel id from the controller's layout (GET /api/v1/<token>/panelLayout/layout)
+    pub led_offset: usize,  // First LED of the zone
+    pub led_count: usize,  // LEDs averaged into the panel's color
+}
+
+/// Relay remap rule - copies a range of LEDs from the incoming frame to a new position
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RelayRemapRule {
+    pub src_start: usize,  // First LED of the range in the incoming frame
+    pub dst_start: usize,  // First LED of the range in the forwarded frame
+    pub length: usize,  // Number of LEDs in the range
+    #[serde(default)]
+    pub reverse: bool,  // Reverse LED order within the range
+}
+
+/// Color stop of a named gradient
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GradientStopConfig {
+    pub position: f64,  // Position along the gradient (0.0-1.0)
+    pub color: String,  // Hex color at this position (e.g. "FF8800")
+}
+
+/// User-defined gradient - selectable by name anywhere a gradient name is accepted
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NamedGradientConfig {
+    pub name: String,  // Gradient name (matched case-insensitively)
+    pub stops: Vec<GradientStopConfig>,  // Color stops, sorted by position on load
+}
+
+/// Post-processing effects for one mode - applied to every frame the mode sends, before color correction
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PostFxConfig {
+    pub mode: String,  // Mode these effects apply to ("" = every mode without its own entry)
+    pub width: usize,  // Matrix width in LEDs for 2D blur/mirror (0 = 1D strip)
+    pub blur_radius: f64,  // Gaussian blur sigma in LEDs (0 = off)
+    pub bloom_strength: f64,  // How much bloom light is added (0 = off, 1 = full)
+    pub bloom_threshold: f64,  // Channel level (0-255) above which pixels bloom
+    pub bloom_radius: f64,  // Gaussian sigma of the bloom glow in LEDs
+    pub mirror: String,  // "off", "horizontal" (left half onto right), "vertical" (top onto bottom), or "both"
+}
+
+impl Default for PostFxConfig {
+    fn default() -> Self {
+        PostFxConfig {
+            mode: "".to_string(),
+            width: 0,
+            blur_radius: 0.0,
+            bloom_strength: 0.0,
+            bloom_threshold: 200.0,
+            bloom_radius: 3.0,
+            mirror: "off".to_string(),
+        }
+    }
+}
+
+/// One physical matrix panel placed on the shared 2D canvas - panels are chained in list order
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MatrixPanelConfig {
+    pub x: usize,  // Canvas column of the panel's top-left corner (after rotation)
+    pub y: usize,  // Canvas row of the panel's top-left corner (after rotation)
+    pub width: usize,  // Panel width in LEDs as wired (before rotation)
+    pub height: usize,  // Panel height in LEDs as wired (before rotation)
+    pub rotation: u32,  // Clockwise rotation on the canvas: 0, 90, 180, or 270
+    pub serpentine: bool,  // Odd rows run right to left (zigzag wiring)
+}
+
+impl Default for MatrixPanelConfig {
+    fn default() -> Self {
+        MatrixPanelConfig {
+            x: 0,
+            y: 0,
+            width: 16,
+            height: 16,
+            rotation: 0,
+            serpentine: true,
+        }
+    }
+}
+
+/// Additional web UI user with a role (the primary httpd_auth_user is always an admin)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HttpUserConfig {
+    pub username: String,
+    #[serde(default)]
+    pub password: String,  // Plaintext password to set - replaced by password_hash on load
+    #[serde(default)]
+    pub password_hash: String,  // Argon2 hash of the password
+    #[serde(default = "default_user_role")]
+    pub role: String,  // "viewer" (read only), "operator" (config changes), or "admin" (devices + system)
+}
+
+fn default_user_role() -> String {
+    "viewer".to_string()
+}
+
+/// API token for automation clients - only the SHA-256 hash of the token is stored
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApiTokenConfig {
+    pub name: String,  // Unique label (e.g. "home-assistant")
+    pub prefix: String,  // First characters of the token, for identifying it in listings
+    pub token_hash: String,  // SHA-256 of the token (hex)
+    pub scope: String,  // "read" (GET requests only) or "write" (read + config changes)
+    #[serde(default)]
+    pub created: u64,  // Unix timestamp of creation
+}
+
+// Per-mode settings - each mode's section is flattened into BandwidthConfig, so the
+// config file, web UI field names, and API keep the flat prefixed keys (tron_width, sand_spawn_rate, ...)
+
+/// MIDI mode settings (midi_* keys)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MidiConfig {
+    #[serde(rename = "midi_device")]
+    pub device: String,
+    #[serde(rename = "midi_gradient")]
+    pub gradient: bool,
+    #[serde(rename = "midi_random_colors")]
+    pub random_colors: bool,
+    #[serde(rename = "midi_velocity_colors")]
+    pub velocity_colors: bool,  // Map velocity to color spectrum (instead of note)
+    #[serde(rename = "midi_one_to_one")]
+    pub one_to_one: bool,  // Map 1 LED per note (centered at middle C) instead of spreading across all LEDs
+    #[serde(rename = "midi_channel_mode")]
+    pub channel_mode: bool,  // Use MIDI channels to map notes to LEDs (channel 1 = LEDs 0-127, channel 2 = LEDs 128-255, etc.)
+    #[serde(rename = "midi_morph_preset_a")]
+    pub morph_preset_a: String,  // Preset morphed to by morph_note_a (empty = note plays normally)
+    #[serde(rename = "midi_morph_preset_b")]
+    pub morph_preset_b: String,  // Preset morphed to by morph_note_b (empty = note plays normally)
+    #[serde(rename = "midi_morph_note_a")]
+    pub morph_note_a: u8,  // Trigger note for preset A - pick one outside the playing range (default 0)
+    #[serde(rename = "midi_morph_note_b")]
+    pub morph_note_b: u8,  // Trigger note for preset B (default 1)
+    #[serde(rename = "midi_morph_seconds")]
+    pub morph_seconds: f64,  // How long a morph takes (default 4.0)
+    #[serde(rename = "midi_zones")]
+    pub zones: Vec<MidiZoneConfig>,  // MIDI channels routed to their own devices or LED ranges (empty = all channels on the whole strip)
+}
+
+/// MIDI zone - a channel whose notes light only its devices (or LED range), spread across them as if each were the whole strip
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MidiZoneConfig {
+    pub channel: u8,  // MIDI channel (1-16)
+    #[serde(default)]
+    pub devices: Vec<String>,  // IPs of the devices the channel lights (empty = the LED range below)
+    #[serde(default)]
+    pub led_offset: usize,  // First LED of the range (no devices given)
+    #[serde(default)]
+    pub led_count: usize,  // LEDs in the range (no devices given)
+}
+
+impl Default for MidiConfig {
+    fn default() -> Self {
+        MidiConfig {
+            device: "IAC Bus 1".to_string(),
+            gradient: false,
+            random_colors: false,
+            velocity_colors: false,
+            one_to_one: false,
+            channel_mode: false,
+            morph_preset_a: String::new(),
+            morph_preset_b: String::new(),
+            morph_note_a: 0,
+            morph_note_b: 1,
+            morph_seconds: 4.0,
+            zones: Vec::new(),
+        }
+    }
+}
+
+/// Audio input and analysis settings for live mode (audio_*, noise_floor*, fft_*, cqt* keys, attack_ms and decay_ms)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AudioConfig {
+    #[serde(rename = "audio_device")]
+    pub device: String,  // Audio device name for live mode (empty = prompt user)
+    #[serde(rename = "audio_gain")]
+    pub gain: f64,  // Audio input gain adjustment in percent (-200 to +200)
+    #[serde(rename = "audio_analysis_rate")]
+    pub analysis_rate: u32,  // Sample rate audio is resampled to for analysis (0 = the device's own rate)
+    pub noise_floor: bool,  // Learn the background noise per frequency and only show the spectrum above it
+    pub noise_floor_margin_db: f64,  // How far above the learned floor a frequency has to be to light up (0-30 dB)
+    pub log_scale: bool,
+    pub attack_ms: f32,  // Time in ms for LEDs to fade in (live and MIDI modes)
+    pub decay_ms: f32,   // Time in ms for LEDs to fade out (live and MIDI modes)
+    pub fft_size: usize,  // FFT size for live spectrum analysis (power of two, default 1024)
+    pub fft_window: String,  // Window function applied before each FFT: "hann", "hamming", "blackman"
+    pub fft_overlap: f64,  // Overlap between consecutive FFT windows (0.0-0.95, hop = size * (1 - overlap))
+    pub cqt: bool,  // Constant-Q spectrum: each LED is a fixed fraction of a semitone instead of a linear FFT slice
+    pub cqt_min_freq: f64,  // Frequency of the first LED in constant-Q mode (Hz, default C1 = 32.7)
+    pub cqt_leds_per_semitone: f64,  // LEDs per semitone in constant-Q mode (1 = one LED per note)
+}
+
+impl Default for AudioConfig {
+    fn default() -> Self {
+        AudioConfig {
+            device: "".to_string(),  // Empty = prompt user on first run
+            gain: 0.0,  // No gain adjustment by default
+            analysis_rate: 48000,  // Same bin widths whatever rate the device delivers
+            noise_floor: false,
+            noise_floor_margin_db: 10.0,
+            log_scale: false,
+            attack_ms: 10.0,   // 10ms fast attack for responsive feel
+            decay_ms: 150.0,   // 150ms decay so you can see the notes/hits
+            fft_size: 1024,  // ~23ms at 44.1kHz, 43 Hz per bin
+            fft_window: "hann".to_string(),
+            fft_overlap: 0.5,  // New window every half window
+            cqt: false,
+            cqt_min_freq: 32.70,  // C1
+            cqt_leds_per_semitone: 1.0,
+        }
+    }
+}
+
+/// VU meter settings for live mode (vu, vu_* and peak_* keys)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct VuConfig {
+    #[serde(rename = "vu")]
+    pub enabled: bool,  // VU meter mode for live audio (left/right channels)
+    #[serde(rename = "vu_scale")]
+    pub scale: String,  // VU meter scale: "linear" (fixed boost) or "db" (calibrated dBFS)
+    #[serde(rename = "vu_db_range")]
+    pub db_range: f64,  // dB span of the meter in dB scale (60 = -60..0 dB)
+    #[serde(rename = "vu_db_reference")]
+    pub db_reference: f64,  // dBFS level that fills the meter in dB scale (0 = full scale)
+    #[serde(rename = "vu_ballistics")]
+    pub ballistics: String,  // VU meter ballistics: "peak", "rms", or "vu" (300ms integration)
+    #[serde(rename = "vu_mono")]
+    pub mono: bool,  // Sum L+R into a single full-strip VU meter
+    #[serde(rename = "vu_left_color")]
+    pub left_color: String,  // Left channel colors in VU mode (empty = rx_color / color)
+    #[serde(rename = "vu_right_color")]
+    pub right_color: String,  // Right channel colors in VU mode (empty = tx_color / color)
+    pub peak_hold: bool,  // Enable peak hold LED in VU meter mode
+    pub peak_hold_duration_ms: f64,  // How long to hold the peak LED (in milliseconds)
+    pub peak_hold_color: String,  // Hex color for peak hold LED
+    pub peak_direction_toggle: bool,  // Toggle animation direction on new peak (VU mode with peak hold)
+}
+
+impl Default for VuConfig {
+    fn default() -> Self {
+        VuConfig {
+            enabled: false,
+            scale: "linear".to_string(),
+            db_range: 60.0,
+            db_reference: 0.0,
+            ballistics: "peak".to_string(),
+            mono: false,
+            left_color: "".to_string(),
+            right_color: "".to_string(),
+            peak_hold: false,
+            peak_hold_duration_ms: 1000.0,  // 1 second hold by default
+            peak_hold_color: "FFFFFF".to_string(),  // White peak hold LED
+            peak_direction_toggle: false,  // Disabled by default
+        }
+    }
+}
+
+/// Spectrogram settings for live mode (spectrogram and spectrogram_* keys)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SpectrogramConfig {
+    #[serde(rename = "spectrogram")]
+    pub enabled: bool,  // Spectrogram mode for live audio (scrolling frequency visualization)
+    #[serde(rename = "spectrogram_scroll_direction")]
+    pub scroll_direction: String,  // Scroll direction: "right", "left", "up", "down" (default "right")
+    #[serde(rename = "spectrogram_scroll_speed")]
+    pub scroll_speed: f64,  // Scroll speed in pixels per second (default 30.0)
+    #[serde(rename = "spectrogram_window_size")]
+    pub window_size: usize,  // FFT window size for spectrogram (default 1024)
+    #[serde(rename = "spectrogram_color_mode")]
+    pub color_mode: String,  // Color mapping: "intensity", "frequency", "channel", "volume" (default "intensity")
+    #[serde(rename = "spectrogram_1d")]
+    pub strip: bool,  // Show the spectrogram on a plain strip (latest spectrum with a fading trail)
+    #[serde(rename = "spectrogram_trail_ms")]
+    pub trail_ms: f64,  // How long a 1D spectrogram peak takes to fade out (default 1500)
+    #[serde(rename = "spectrogram_onset")]
+    pub onset: String,  // Onset overlay: "off", "brighten", "accent" (default "off")
+    #[serde(rename = "spectrogram_onset_color")]
+    pub onset_color: String,  // Hex color flashed by the "accent" onset overlay
+    #[serde(rename = "spectrogram_onset_sensitivity")]
+    pub onset_sensitivity: f64,  // Flux over its running average that counts as an onset (default 2.0)
+}
+
+impl Default for SpectrogramConfig {
+    fn default() -> Self {
+        SpectrogramConfig {
+            enabled: false,  // Spectrogram mode disabled by default
+            scroll_direction: "right".to_string(),  // Default scroll right (time flows left to right)
+            scroll_speed: 30.0,  // Default 30 pixels per second
+            window_size: 1024,  // Default 1024 sample window for good frequency resolution
+            color_mode: "intensity".to_string(),  // Default to intensity-based coloring
+            strip: false,  // Scrolling 2D spectrogram by default
+            trail_ms: 1500.0,
+            onset: "off".to_string(),
+            onset_color: "FFFFFF".to_string(),
+            onset_sensitivity: 2.0,
+        }
+    }
+}
+
+/// 2D matrix output settings (matrix_2d_* keys) - rotation and flips apply to every matrix mode
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MatrixConfig {
+    #[serde(rename = "matrix_2d_enabled")]
+    pub enabled: bool,  // Enable 2D matrix output for spectrum visualization
+    #[serde(rename = "matrix_2d_width")]
+    pub width: usize,  // Width of 2D matrix in LEDs/pixels
+    #[serde(rename = "matrix_2d_height")]
+    pub height: usize,  // Height of 2D matrix in LEDs/pixels
+    #[serde(rename = "matrix_2d_gradient_direction")]
+    pub gradient_direction: String,  // Gradient direction: "horizontal" (across frequencies) or "vertical" (across amplitude)
+    #[serde(rename = "matrix_2d_rotation")]
+    pub rotation: u32,  // Clockwise rotation of the whole matrix as mounted: 0, 90, 180, or 270 (all matrix modes)
+    #[serde(rename = "matrix_2d_flip_h")]
+    pub flip_h: bool,  // Mirror the matrix left to right (all matrix modes)
+    #[serde(rename = "matrix_2d_flip_v")]
+    pub flip_v: bool,  // Mirror the matrix top to bottom (all matrix modes)
+}
+
+impl Default for MatrixConfig {
+    fn default() -> Self {
+        MatrixConfig {
+            enabled: false,  // Disabled by default - use 1D strip mode
+            width: 16,  // Default 16x16 matrix
+            height: 16,
+            gradient_direction: "horizontal".to_string(),  // Default to horizontal gradient (across frequencies)
+            rotation: 0,  // Mounted upright
+            flip_h: false,
+            flip_v: false,
+        }
+    }
+}
+
+/// Webcam mode settings (webcam_* keys)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WebcamConfig {
+    #[serde(rename = "webcam_frame_width")]
+    pub frame_width: usize,  // Frame width in pixels for webcam mode (default 16)
+    #[serde(rename = "webcam_frame_height")]
+    pub frame_height: usize,  // Frame height in pixels for webcam mode (default 16)
+    #[serde(rename = "webcam_target_fps")]
+    pub target_fps: f64,  // Target FPS for webcam capture (default 30)
+    #[serde(rename = "webcam_brightness")]
+    pub brightness: f64,  // Brightness multiplier for webcam (0.0 to 2.0, default 0.5 for 50%)
+    #[serde(rename = "webcam_zone_layout")]
+    pub zone_layout: String,  // "pixels" (one LED per pixel), "grid", "edges" (TV backlight), "custom" ([[webcam_zones]])
+    #[serde(rename = "webcam_zone_columns")]
+    pub zone_columns: usize,  // Grid columns, or LEDs along the top/bottom edge
+    #[serde(rename = "webcam_zone_rows")]
+    pub zone_rows: usize,  // Grid rows, or LEDs down each side
+    #[serde(rename = "webcam_zone_depth")]
+    pub zone_depth: f64,  // How far edge zones reach into the picture (percent)
+    #[serde(rename = "webcam_zone_smoothing")]
+    pub zone_smoothing: f64,  // Zone color smoothing (0 = instant, 0.9 = slow), unless a zone sets its own
+}
+
+/// Screen zone - a rectangle of the captured image (percent) averaged onto an LED range
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ScreenZoneConfig {
+    pub x: f64,  // Left edge, percent of the image width
+    pub y: f64,  // Top edge, percent of the image height
+    pub width: f64,
+    pub height: f64,
+    pub led_offset: usize,  // First LED the zone's color is written to
+    pub led_count: usize,
+    #[serde(default)]
+    pub smoothing: Option<f64>,  // Overrides webcam_zone_smoothing for this zone
+}
+
+impl Default for WebcamConfig {
+    fn default() -> Self {
+        WebcamConfig {
+            frame_width: 16,  // Default 16x16 webcam capture
+            frame_height: 16,
+            target_fps: 30.0,  // Default 30 FPS for webcam
+            brightness: 0.5,  // Default 50% brightness to avoid washout
+            zone_layout: "pixels".to_string(),  // Captured pixels map straight to LEDs
+            zone_columns: 16,  // 16:9 edge layout
+            zone_rows: 9,
+            zone_depth: 10.0,
+            zone_smoothing: 0.5,
+        }
+    }
+}
+
+/// Tron/Snake game settings (tron_* keys)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TronConfig {
+    #[serde(rename = "tron_width")]
+    pub width: usize,  // Tron game grid width (default 64)
+    #[serde(rename = "tron_height")]
+    pub height: usize,  // Tron game grid height (default 32)
+    #[serde(rename = "tron_speed_ms")]
+    pub speed_ms: f64,  // Tron game update speed in milliseconds (default 100ms, supports 0.01ms precision)
+    #[serde(rename = "tron_reset_delay_ms")]
+    pub reset_delay_ms: u64,  // Delay before resetting game after game over (default 2000ms)
+    #[serde(rename = "tron_look_ahead")]
+    pub look_ahead: i32,  // How many steps AI looks ahead (default 8)
+    #[serde(rename = "tron_trail_length")]
+    pub trail_length: usize,  // Max trail length, 0 = infinite (default 0)
+    #[serde(rename = "tron_ai_aggression")]
+    pub ai_aggression: f64,  // AI aggressiveness 0.0-1.0 (default 0.3 = cautious)
+    #[serde(rename = "tron_num_players")]
+    pub num_players: usize,  // Number of AI players (1 = Snake, 2-8 = Tron, default 2)
+    #[serde(rename = "tron_food_mode")]
+    pub food_mode: bool,  // Food mode: players compete to eat food and grow (default false)
+    #[serde(rename = "tron_food_max_count")]
+    pub food_max_count: usize,  // Maximum number of food items that can appear simultaneously (default 1)
+    #[serde(rename = "tron_food_ttl_seconds")]
+    pub food_ttl_seconds: u64,  // Food time-to-live in seconds before relocating (default 10)
+    #[serde(rename = "tron_trail_fade")]
+    pub trail_fade: bool,  // Enable trail brightness fading effect (default true)
+    #[serde(rename = "tron_super_food_enabled")]
+    pub super_food_enabled: bool,  // Enable super food spawning (red, 10% chance, +5 length)
+    #[serde(rename = "tron_power_food_enabled")]
+    pub power_food_enabled: bool,  // Enable power food spawning (yellow, 1% chance, 10 second power mode with immunity and 25% speed boost)
+    #[serde(rename = "tron_diagonal_movement")]
+    pub diagonal_movement: bool,  // Enable diagonal movement (8 directions instead of 4)
+    #[serde(rename = "tron_obstacle_density")]
+    pub obstacle_density: f64,  // Share of the arena covered by random walls, new each game (0.0-0.3, 0 = open arena)
+    #[serde(rename = "tron_obstacles")]
+    pub obstacles: String,  // Walls drawn by hand: "x,y" cells and "x1,y1-x2,y2" filled rectangles, separated by ';'
+    #[serde(rename = "tron_player_colors")]
+    pub player_colors: String,  // Comma-separated list of gradients for players (e.g., "rainbow,fire,ocean") - DEPRECATED, use individual fields
+    #[serde(rename = "tron_player_1_color")]
+    pub player_1_color: String,  // Player 1 gradient/color
+    #[serde(rename = "tron_player_2_color")]
+    pub player_2_color: String,  // Player 2 gradient/color
+    #[serde(rename = "tron_player_3_color")]
+    pub player_3_color: String,  // Player 3 gradient/color
+    #[serde(rename = "tron_player_4_color")]
+    pub player_4_color: String,  // Player 4 gradient/color
+    #[serde(rename = "tron_player_5_color")]
+    pub player_5_color: String,  // Player 5 gradient/color
+    #[serde(rename = "tron_player_6_color")]
+    pub player_6_color: String,  // Player 6 gradient/color
+    #[serde(rename = "tron_player_7_color")]
+    pub player_7_color: String,  // Player 7 gradient/color
+    #[serde(rename = "tron_player_8_color")]
+    pub player_8_color: String,  // Player 8 gradient/color
+    #[serde(rename = "tron_animation_speed")]
+    pub animation_speed: f64,  // Speed of gradient animation on trails (0 = disabled)
+    #[serde(rename = "tron_scale_animation_speed")]
+    pub scale_animation_speed: bool,  // Scale animation speed based on trail length
+    #[serde(rename = "tron_animation_direction")]
+    pub animation_direction: String,  // Animation direction: "forward" (head to tail) or "backward" (tail to head)
+    #[serde(rename = "tron_interpolation")]
+    pub interpolation: String,  // Gradient interpolation mode: "linear", "basis", "catmullrom"
+    #[serde(rename = "tron_flip_direction_on_food")]
+    pub flip_direction_on_food: bool,  // Flip animation direction each time a player eats food
+}
+
+impl Default for TronConfig {
+    fn default() -> Self {
+        TronConfig {
+            width: 64,  // Default 64x32 grid for Tron game
+            height: 32,
+            speed_ms: 100.0,  // Default 100ms update interval (10 FPS game speed)
+            reset_delay_ms: 2000,  // Default 2 second delay before resetting after game over
+            look_ahead: 8,  // Look 8 steps ahead
+            trail_length: 0,  // Infinite trail by default
+            ai_aggression: 0.3,  // 30% aggression (cautious play)
+            num_players: 2,  // Default 2 players
+            food_mode: false,  // Food mode disabled by default
+            food_max_count: 1,  // Default 1 food at a time
+            food_ttl_seconds: 10,  // Default 10 seconds before food relocates
+            trail_fade: true,  // Trail fading enabled by default
+            super_food_enabled: true,  // Super food enabled by default
+            power_food_enabled: true,  // Power food enabled by default
+            diagonal_movement: false,  // Diagonal movement disabled by default
+            obstacle_density: 0.0,  // Open arena by default
+            obstacles: String::new(),  // No drawn walls
+            player_colors: "rainbow,fire".to_string(),  // Default colors (deprecated)
+            player_1_color: "rainbow".to_string(),
+            player_2_color: "fire".to_string(),
+            player_3_color: "ocean".to_string(),
+            player_4_color: "forest".to_string(),
+            player_5_color: "sunset".to_string(),
+            player_6_color: "purple".to_string(),
+            player_7_color: "cool".to_string(),
+            player_8_color: "warm".to_string(),
+            animation_speed: 1.0,  // Default animation speed
+            scale_animation_speed: false,  // Don't scale by default
+            animation_direction: "forward".to_string(),  // Head to tail direction
+            interpolation: "catmullrom".to_string(),  // Smooth interpolation by default
+            flip_direction_on_food: false,  // Disabled by default
+        }
+    }
+}
+
+impl TronConfig {
+    /// Gradient/color for each of the eight players, in order
+    pub fn player_colors(&self) -> Vec<String> {
+        [
+            &self.player_1_color, &self.player_2_color, &self.player_3_color, &self.player_4_color,
+            &self.player_5_color, &self.player_6_color, &self.player_7_color, &self.player_8_color,
+        ].iter().map(|color| color.to_string()).collect()
+    }
+}
+
+/// Geometry mode settings (geometry_* keys)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GeometryConfig {
+    #[serde(rename = "geometry_grid_width")]
+    pub grid_width: usize,  // Geometry mode grid width (default 64)
+    #[serde(rename = "geometry_grid_height")]
+    pub grid_height: usize,  // Geometry mode grid height (default 32)
+    #[serde(rename = "geometry_mode_select")]
+    pub mode_select: String,  // Which geometry to show: "cycle", "lissajous", "fibonacci", etc. (default "cycle")
+    #[serde(rename = "geometry_mode_duration_seconds")]
+    pub mode_duration_seconds: f64,  // How long to show each geometry in seconds (default 12.0)
+    #[serde(rename = "geometry_randomize_order")]
+    pub randomize_order: bool,  // Randomize the order geometries are shown (default false)
+}
+
+impl Default for GeometryConfig {
+    fn default() -> Self {
+        GeometryConfig {
+            grid_width: 64,  // Default 64x32 grid
+            grid_height: 32,
+            mode_select: "cycle".to_string(),  // Cycle through all modes by default
+            mode_duration_seconds: 12.0,  // 12 seconds per mode
+            randomize_order: false,  // Sequential order by default
+        }
+    }
+}
+
+/// Boid flocking simulation settings (boid_* keys)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BoidConfig {
+    #[serde(rename = "boid_count")]
+    pub count: usize,  // Number of boids (default 50)
+    #[serde(rename = "boid_separation_distance")]
+    pub separation_distance: f64,  // Separation distance (default 0.1)
+    #[serde(rename = "boid_alignment_distance")]
+    pub alignment_distance: f64,  // Alignment distance (default 0.3)
+    #[serde(rename = "boid_cohesion_distance")]
+    pub cohesion_distance: f64,  // Cohesion distance (default 0.3)
+    #[serde(rename = "boid_max_speed")]
+    pub max_speed: f64,  // Maximum speed (default 0.03)
+    #[serde(rename = "boid_max_force")]
+    pub max_force: f64,  // Maximum steering force (default 0.001)
+    // Predator-prey settings
+    #[serde(rename = "boid_predator_enabled")]
+    pub predator_enabled: bool,  // Enable predator-prey behavior (default false)
+    #[serde(rename = "boid_predator_count")]
+    pub predator_count: usize,  // Number of predators (default 3)
+    #[serde(rename = "boid_predator_speed")]
+    pub predator_speed: f64,  // Predator maximum speed (default 0.04)
+    #[serde(rename = "boid_avoidance_distance")]
+    pub avoidance_distance: f64,  // Distance at which prey avoid predators (default 0.4)
+    #[serde(rename = "boid_chase_force")]
+    pub chase_force: f64,  // Force applied to predator chase (default 0.002)
+}
+
+impl Default for BoidConfig {
+    fn default() -> Self {
+        BoidConfig {
+            count: 50,
+            separation_distance: 0.1,
+            alignment_distance: 0.3,
+            cohesion_distance: 0.3,
+            max_speed: 0.03,
+            max_force: 0.001,
+            predator_enabled: false,
+            predator_count: 3,
+            predator_speed: 0.04,
+            avoidance_distance: 0.4,
+            chase_force: 0.002,
+        }
+    }
+}
+
+/// Falling sand simulation settings (sand_* keys)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SandConfig {
+    #[serde(rename = "sand_grid_width")]
+    pub grid_width: usize,  // Sand grid width (default 64)
+    #[serde(rename = "sand_grid_height")]
+    pub grid_height: usize,  // Sand grid height (default 32)
+    #[serde(rename = "sand_spawn_enabled")]
+    pub spawn_enabled: bool,  // Enable spawning particles (default true)
+    #[serde(rename = "sand_particle_type")]
+    pub particle_type: String,  // Particle type to spawn: sand, water, stone, fire, wood, lava (default "sand")
+    #[serde(rename = "sand_spawn_rate")]
+    pub spawn_rate: f64,  // Spawn rate 0.0-1.0 (default 0.3)
+    #[serde(rename = "sand_spawn_radius")]
+    pub spawn_radius: usize,  // Spawn radius in cells (default 3)
+    #[serde(rename = "sand_spawn_x")]
+    pub spawn_x: usize,  // Spawn X position in cells (default width/2)
+    #[serde(rename = "sand_obstacles_enabled")]
+    pub obstacles_enabled: bool,  // Place random obstacles in bottom quarter (default false)
+    #[serde(rename = "sand_obstacle_density")]
+    pub obstacle_density: f64,  // Obstacle density 0.0-1.0 (default 0.15)
+    #[serde(rename = "sand_fire_enabled")]
+    pub fire_enabled: bool,  // Enable fire spread (default true)
+    #[serde(rename = "sand_color_sand")]
+    pub color_sand: String,  // Color for sand particles (default "C2B280" - tan)
+    #[serde(rename = "sand_color_water")]
+    pub color_water: String,  // Color for water particles (default "0077BE" - blue)
+    #[serde(rename = "sand_color_stone")]
+    pub color_stone: String,  // Color for stone particles (default "808080" - gray)
+    #[serde(rename = "sand_color_fire")]
+    pub color_fire: String,  // Color for fire particles (default "FF4500" - orange-red)
+    #[serde(rename = "sand_color_smoke")]
+    pub color_smoke: String,  // Color for smoke particles (default "404040" - dark gray)
+    #[serde(rename = "sand_color_wood")]
+    pub color_wood: String,  // Color for wood particles (default "8B4513" - saddle brown)
+    #[serde(rename = "sand_color_lava")]
+    pub color_lava: String,  // Color for lava particles (default "FF8C00" - dark orange)
+}
+
+impl Default for SandConfig {
+    fn default() -> Self {
+        SandConfig {
+            grid_width: 64,
+            grid_height: 32,
+            spawn_enabled: true,
+            particle_type: "sand".to_string(),
+            spawn_rate: 0.3,
+            spawn_radius: 3,
+            spawn_x: 32,  // Default to center (width/2)
+            obstacles_enabled: false,
+            obstacle_density: 0.15,
+            fire_enabled: true,
+            color_sand: "C2B280".to_string(),
+            color_water: "0077BE".to_string(),
+            color_stone: "808080".to_string(),
+            color_fire: "FF4500".to_string(),
+            color_smoke: "404040".to_string(),
+            color_wood: "8B4513".to_string(),
+            color_lava: "FF8C00".to_string(),
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -147,143 +1135,165 @@ pub struct BandwidthConfig {
     pub direction: String,
     pub swap: bool,
     pub rx_split_percent: f64,
+    pub bandwidth_layout: String,  // "meter" (RX/TX meter) or "dashboard" (one row per interface on the 2D matrix)
     pub strobe_on_max: bool,
     pub strobe_rate_hz: f64,
     pub strobe_duration_ms: f64,
     pub strobe_color: String,
+    pub strobe_on_beat: bool,  // Flash on detected beats in audio modes
+    pub strobe_midi_note: i32,  // MIDI note that strobes while held in MIDI mode (-1 = off)
     pub animation_speed: f64,
     pub scale_animation_speed: bool,
     pub tx_animation_direction: String,
     pub rx_animation_direction: String,
     pub interpolation_time_ms: f64,
     pub enable_interpolation: bool,  // Enable/disable bandwidth interpolation smoothing
+    pub fill_easing: String,  // Easing for the meter fill between updates: "linear", "ease-in-out", "exponential", "spring"
+    pub animation_easing: String,  // Easing for animation speed changes between updates (same options)
+    pub idle_enabled: bool,  // Fade into an idle animation when there's no bandwidth / audio signal
+    pub idle_timeout_secs: f64,  // Seconds without signal before the idle animation starts
+    pub idle_animation: String,  // Idle animation: "breathing" or "drift"
+    pub idle_color: String,  // Idle color(s) - hex, comma-separated list, or gradient name (drift needs 2+ colors)
+    pub idle_brightness: f64,  // Idle animation brightness (0.0 to 1.0)
     pub wled_ip: String,
     pub multi_device_enabled: bool,
     pub multi_device_send_parallel: bool,
     pub multi_device_fail_fast: bool,
+    pub multi_device_stagger_sends: bool,  // Spread per-device sends across the frame interval instead of bursting
+    pub adaptive_fps_enabled: bool,  // Send fewer frames to a device whose network can't keep up, until it recovers
+    pub adaptive_fps_min: f64,  // Lowest frame rate adaptive FPS drops a device to
     pub wled_devices: Vec<WLEDDeviceConfig>,
     pub interface: String,
     pub ssh_host: String,  // SSH host for remote bandwidth monitoring (empty = local)
     pub ssh_user: String,  // SSH user for remote bandwidth monitoring (empty = current user)
+    pub agent_hosts: String,  // Comma-separated "host[:port]" list of rustwled agents to read counters from (empty = SSH/local)
+    pub agent_token: String,  // Shared secret the agents were started with
     pub total_leds: usize,
     pub use_gradient: bool,
     pub intensity_colors: bool,  // Map utilization/level to color position (all LEDs same color, changes with level)
     pub interpolation: String,
+    pub color_space: String,  // Color space gradients blend in: "rgb", "hsv", "oklab", "lch"
+    pub gradient_animation: String,  // Animate every gradient over time: "off", "hue" (rotate hue), "palette" (cycle gradient_palettes)
+    pub gradient_animation_seconds: f64,  // Seconds per full hue turn, or per palette when cycling
+    pub gradient_palettes: String,  // Comma-separated gradient names to cycle through (e.g. "Fire,Ocean,Neon")
     pub fps: f64,
     pub ddp_delay_ms: f64,  // Delay in milliseconds before sending each DDP packet (for audio/LED sync)
     pub global_brightness: f64,  // Global brightness multiplier (0.0 to 1.0, default 1.0 = 100%)
+    pub gamma: f64,  // Output gamma correction exponent (1.0 = disabled)
+    pub color_order: String,  // Output channel order: "rgb", "rbg", "grb", "gbr", "brg", "bgr"
+    pub color_correction: String,  // Per-channel color correction as hex (FFFFFF = no correction)
+    pub white_point_kelvin: f64,  // Output white point in Kelvin to match room lighting (6500 = neutral, lower = warmer)
+    pub fade_in_ms: f64,  // Ramp output up from black over this long when a mode starts (0 = off)
+    pub fade_out_ms: f64,  // Fade the last frame to black over this long when quitting or switching modes (0 = off)
+    pub frame_interpolation: bool,  // Blend between frames of slow sources (e.g. a 10fps webcam), sending in-between frames
+    pub frame_interpolation_fps: f64,  // Rate the in-between frames go out at
+    pub exit_action: String,  // What the strip shows after quitting: "blank", "restore" (WLED's own state from before startup), "none"
+    pub wled_pause_when_off: bool,  // Stop sending to a device while it's switched off in the WLED app (polls its JSON API)
+    pub wled_brightness_sync: bool,  // Write global_brightness to each device's master brightness instead of dimming frames
+    pub ambient_source: String,  // Room light sensor for auto-brightness: "none", "iio" (Linux sysfs), "http"
+    pub ambient_sensor: String,  // IIO sysfs file (empty = first light sensor found) or HTTP URL returning lux
+    pub ambient_min_lux: f64,  // Light level at or below which output is at ambient_min_brightness
+    pub ambient_max_lux: f64,  // Light level at or above which output is at ambient_max_brightness
+    pub ambient_min_brightness: f64,  // Fraction of global_brightness in a dark room (0.0-1.0)
+    pub ambient_max_brightness: f64,  // Fraction of global_brightness in a bright room (0.0-1.0)
+    pub quiet_hours: String,  // Local times the brightness is capped, e.g. "22:00-07:00" (comma-separated, empty = off)
+    pub quiet_hours_brightness: f64,  // Highest output brightness during quiet hours (0.0-1.0)
+    pub hue_enabled: bool,  // Stream to a Philips Hue entertainment area alongside the WLED devices
+    pub hue_bridge_ip: String,  // Hue bridge IP address
+    pub hue_username: String,  // Application key from pairing with the bridge
+    pub hue_clientkey: String,  // Streaming client key (PSK) from pairing - 32 hex digits
+    pub hue_entertainment_group: String,  // Entertainment area (group id) to stream to
+    pub hue_lights: Vec<HueLightConfig>,  // Hue lights and the LED ranges they mirror
+    pub lifx_devices: Vec<LifxDeviceConfig>,  // LIFX bulbs mirroring LED zones (LAN protocol)
+    pub nanoleaf_ip: String,  // Nanoleaf controller IP (empty = off)
+    pub nanoleaf_token: String,  // Nanoleaf auth token from pairing
+    pub nanoleaf_panels: Vec<NanoleafPanelConfig>,  // Nanoleaf panels mirroring LED zones
+    pub gpio_buttons: Vec<GpioButtonConfig>,  // Raspberry Pi buttons mapped to actions (needs the "gpio" build feature)
+    pub gpio_mode_cycle: String,  // Comma-separated modes the next/prev mode buttons step through
+    pub alert_animation: String,  // Alarm animation while an alert is active: "flash", "pulse", "chase", "solid"
+    pub alert_color: String,  // Hex color of the alarm animation
+    pub alert_timeout_secs: u64,  // Alerts end on their own after this long (0 = only when cleared)
+    pub alert_webhook_enabled: bool,  // Accept Alertmanager/PagerDuty webhooks on /api/alert/webhook
+    pub demo_modes: String,  // Comma-separated modes demo mode rotates through
+    pub demo_interval_secs: f64,  // How long each mode runs in demo mode
+    pub demo_crossfade_ms: f64,  // Crossfade between modes in demo mode (0 = cut)
     pub mode: String,  // Current mode: bandwidth, midi, live
     pub httpd_enabled: bool,
     pub httpd_https_enabled: bool,  // Enable HTTPS (uses same ip/port as HTTP)
+    pub acme_enabled: bool,  // Obtain a Let's Encrypt certificate instead of a self-signed one
+    pub acme_domain: String,  // Public DNS name the certificate is issued for
+    pub acme_email: String,  // Contact email for the ACME account (expiry notices)
+    pub acme_staging: bool,  // Use the Let's Encrypt staging directory (for testing)
+    pub acme_http_port: u16,  // Port the HTTP-01 challenge responder listens on (must be reachable as port 80)
+    pub httpd_mtls_enabled: bool,  // Require HTTPS clients to present a certificate signed by httpd_mtls_ca_path
+    pub httpd_mtls_ca_path: String,  // PEM file with the CA certificate(s) trusted for client certificates
     pub httpd_ip: String,
     pub httpd_port: u16,
+    pub httpd_assets_dir: String,  // Directory whose files override the embedded web UI (empty = embedded only)
     pub httpd_auth_enabled: bool,
     pub httpd_auth_user: String,
-    pub httpd_auth_pass: String,
+    pub httpd_auth_pass: String,  // Plaintext password to set - replaced by httpd_auth_pass_hash on load
+    pub httpd_auth_pass_hash: String,  // Argon2 hash of the web UI password
+    pub httpd_rate_limit_per_minute: u32,  // Max HTTP requests per client IP per minute (0 = unlimited)
+    pub httpd_login_max_attempts: u32,  // Failed logins from one IP before it is locked out
+    pub httpd_login_lockout_secs: u64,  // How long a locked-out IP is refused logins
+    pub httpd_users: Vec<HttpUserConfig>,  // Additional web UI users with viewer/operator/admin roles
+    pub api_tokens: Vec<ApiTokenConfig>,  // Bearer tokens for scripts and integrations
+    pub sync_role: String,  // Instance sync role: "off", "leader", or "follower"
+    pub sync_group: String,  // Sync group name - followers only obey leaders with the same group
+    pub sync_key: String,  // Shared secret sync packets are signed with - followers drop packets without a valid signature
+    pub sync_address: String,  // Broadcast address the leader sends sync packets to
+    pub sync_port: u16,  // UDP port for sync packets
+    pub sync_interval_ms: u64,  // Leader clock beacon interval in milliseconds
     pub test_tx: bool,
     pub test_rx: bool,
     pub test_tx_percent: f64,
     pub test_rx_percent: f64,
-    pub midi_device: String,
-    pub midi_gradient: bool,
-    pub midi_random_colors: bool,
-    pub midi_velocity_colors: bool,  // Map velocity to color spectrum (instead of note)
-    pub midi_one_to_one: bool,  // Map 1 LED per note (centered at middle C) instead of spreading across all LEDs
-    pub midi_channel_mode: bool,  // Use MIDI channels to map notes to LEDs (channel 1 = LEDs 0-127, channel 2 = LEDs 128-255, etc.)
-    pub audio_device: String,  // Audio device name for live mode (empty = prompt user)
-    pub audio_gain: f64,  // Audio input gain adjustment in percent (-200 to +200)
-    pub log_scale: bool,
-    pub attack_ms: f32,  // Time in ms for LEDs to fade in
-    pub decay_ms: f32,   // Time in ms for LEDs to fade out
-    pub vu: bool,  // VU meter mode for live audio (left/right channels)
-    pub peak_hold: bool,  // Enable peak hold LED in VU meter mode
-    pub peak_hold_duration_ms: f64,  // How long to hold the peak LED (in milliseconds)
-    pub peak_hold_color: String,  // Hex color for peak hold LED
-    pub peak_direction_toggle: bool,  // Toggle animation direction on new peak (VU mode with peak hold)
-    pub spectrogram: bool,  // Spectrogram mode for live audio (scrolling frequency visualization)
-    pub spectrogram_scroll_direction: String,  // Scroll direction: "right", "left", "up", "down" (default "right")
-    pub spectrogram_scroll_speed: f64,  // Scroll speed in pixels per second (default 30.0)
-    pub spectrogram_window_size: usize,  // FFT window size for spectrogram (default 1024)
-    pub spectrogram_color_mode: String,  // Color mapping: "intensity", "frequency", "channel", "volume" (default "intensity")
-    pub matrix_2d_enabled: bool,  // Enable 2D matrix output for spectrum visualization
-    pub matrix_2d_width: usize,  // Width of 2D matrix in LEDs/pixels
-    pub matrix_2d_height: usize,  // Height of 2D matrix in LEDs/pixels
-    pub matrix_2d_gradient_direction: String,  // Gradient direction: "horizontal" (across frequencies) or "vertical" (across amplitude)
+    pub bandwidth_history: bool,  // Keep a history of bandwidth readings on disk for --replay
+    pub bandwidth_history_hours: u32,  // Hours of history kept - the oldest readings are overwritten (1-168)
+    #[serde(flatten)]
+    pub midi: MidiConfig,
+    #[serde(flatten)]
+    pub audio: AudioConfig,
+    #[serde(flatten)]
+    pub vu: VuConfig,
+    #[serde(flatten)]
+    pub spectrogram: SpectrogramConfig,
+    #[serde(flatten)]
+    pub matrix: MatrixConfig,
     pub relay_listen_ip: String,  // IP address to listen on for relay mode (default "127.0.0.1")
     pub relay_listen_port: u16,  // UDP listen port for relay mode (default 1234)
     pub relay_frame_width: usize,  // Frame width in pixels for relay mode (default 16)
     pub relay_frame_height: usize,  // Frame height in pixels for relay mode (default 16)
-    pub webcam_frame_width: usize,  // Frame width in pixels for webcam mode (default 16)
-    pub webcam_frame_height: usize,  // Frame height in pixels for webcam mode (default 16)
-    pub webcam_target_fps: f64,  // Target FPS for webcam capture (default 30)
-    pub webcam_brightness: f64,  // Brightness multiplier for webcam (0.0 to 2.0, default 0.5 for 50%)
-    pub tron_width: usize,  // Tron game grid width (default 64)
-    pub tron_height: usize,  // Tron game grid height (default 32)
-    pub tron_speed_ms: f64,  // Tron game update speed in milliseconds (default 100ms, supports 0.01ms precision)
-    pub tron_reset_delay_ms: u64,  // Delay before resetting game after game over (default 2000ms)
-    pub tron_look_ahead: i32,  // How many steps AI looks ahead (default 8)
-    pub tron_trail_length: usize,  // Max trail length, 0 = infinite (default 0)
-    pub tron_ai_aggression: f64,  // AI aggressiveness 0.0-1.0 (default 0.3 = cautious)
-    pub tron_num_players: usize,  // Number of AI players (1 = Snake, 2-8 = Tron, default 2)
-    pub tron_food_mode: bool,  // Food mode: players compete to eat food and grow (default false)
-    pub tron_food_max_count: usize,  // Maximum number of food items that can appear simultaneously (default 1)
-    pub tron_food_ttl_seconds: u64,  // Food time-to-live in seconds before relocating (default 10)
-    pub tron_trail_fade: bool,  // Enable trail brightness fading effect (default true)
-    pub tron_super_food_enabled: bool,  // Enable super food spawning (red, 10% chance, +5 length)
-    pub tron_power_food_enabled: bool,  // Enable power food spawning (yellow, 1% chance, 10 second power mode with immunity and 25% speed boost)
-    pub tron_diagonal_movement: bool,  // Enable diagonal movement (8 directions instead of 4)
-    pub tron_player_colors: String,  // Comma-separated list of gradients for players (e.g., "rainbow,fire,ocean") - DEPRECATED, use individual fields
-    pub tron_player_1_color: String,  // Player 1 gradient/color
-    pub tron_player_2_color: String,  // Player 2 gradient/color
-    pub tron_player_3_color: String,  // Player 3 gradient/color
-    pub tron_player_4_color: String,  // Player 4 gradient/color
-    pub tron_player_5_color: String,  // Player 5 gradient/color
-    pub tron_player_6_color: String,  // Player 6 gradient/color
-    pub tron_player_7_color: String,  // Player 7 gradient/color
-    pub tron_player_8_color: String,  // Player 8 gradient/color
-    pub tron_animation_speed: f64,  // Speed of gradient animation on trails (0 = disabled)
-    pub tron_scale_animation_speed: bool,  // Scale animation speed based on trail length
-    pub tron_animation_direction: String,  // Animation direction: "forward" (head to tail) or "backward" (tail to head)
-    pub tron_interpolation: String,  // Gradient interpolation mode: "linear", "basis", "catmullrom"
-    pub tron_flip_direction_on_food: bool,  // Flip animation direction each time a player eats food
-    pub geometry_grid_width: usize,  // Geometry mode grid width (default 64)
-    pub geometry_grid_height: usize,  // Geometry mode grid height (default 32)
-    pub geometry_mode_select: String,  // Which geometry to show: "cycle", "lissajous", "fibonacci", etc. (default "cycle")
-    pub geometry_mode_duration_seconds: f64,  // How long to show each geometry in seconds (default 12.0)
-    pub geometry_randomize_order: bool,  // Randomize the order geometries are shown (default false)
+    pub ddp_listen_ip: String,  // IP address to listen on for DDP input mode (default "0.0.0.0")
+    pub ddp_listen_port: u16,  // UDP listen port for DDP input mode (default 4048, same as WLED)
+    pub sacn_listen_ip: String,  // IP address to listen on for sACN input mode (default "0.0.0.0")
+    pub sacn_start_universe: u16,  // First sACN universe for sACN input mode - LEDs continue into the following universes
+    pub artnet_listen_ip: String,  // IP address to listen on for Art-Net input mode (default "0.0.0.0")
+    pub artnet_universes: Vec<ArtnetUniverseConfig>,  // Art-Net universe to LED range mapping (empty = consecutive universes from 0)
+    pub boblight_listen_ip: String,  // IP address to listen on for boblight input mode (default "0.0.0.0")
+    pub boblight_listen_port: u16,  // TCP listen port for boblight input mode (default 19333, same as boblightd)
+    pub relay_color_pipeline: bool,  // Apply global brightness, gamma, and color correction to relayed frames (default true)
+    pub relay_listeners: Vec<RelayListenerConfig>,  // Additional relay listeners (relay_listen_ip/port is always the first listener)
+    pub relay_remap: Vec<RelayRemapRule>,  // LED range remap rules for relay mode (empty = pass-through)
+    pub postfx: Vec<PostFxConfig>,  // Per-mode post-processing (blur, bloom, mirror) applied before sending
+    pub gradients: Vec<NamedGradientConfig>,  // User-defined named gradients (name -> color stops)
+    pub webcam_zones: Vec<ScreenZoneConfig>,  // Custom screen zones for webcam_zone_layout = "custom"
+    pub matrix_panels: Vec<MatrixPanelConfig>,  // Physical panels tiled into one 2D canvas for matrix modes (empty = single width x height matrix)
+    #[serde(flatten)]
+    pub webcam: WebcamConfig,
+    #[serde(flatten)]
+    pub tron: TronConfig,
+    #[serde(flatten)]
+    pub geometry: GeometryConfig,
 
     // Boid simulation parameters
-    pub boid_count: usize,  // Number of boids (default 50)
-    pub boid_separation_distance: f64,  // Separation distance (default 0.1)
-    pub boid_alignment_distance: f64,  // Alignment distance (default 0.3)
-    pub boid_cohesion_distance: f64,  // Cohesion distance (default 0.3)
-    pub boid_max_speed: f64,  // Maximum speed (default 0.03)
-    pub boid_max_force: f64,  // Maximum steering force (default 0.001)
-    // Predator-prey settings
-    pub boid_predator_enabled: bool,  // Enable predator-prey behavior (default false)
-    pub boid_predator_count: usize,  // Number of predators (default 3)
-    pub boid_predator_speed: f64,  // Predator maximum speed (default 0.04)
-    pub boid_avoidance_distance: f64,  // Distance at which prey avoid predators (default 0.4)
-    pub boid_chase_force: f64,  // Force applied to predator chase (default 0.002)
+    #[serde(flatten)]
+    pub boid: BoidConfig,
 
     // Falling sand simulation parameters
-    pub sand_grid_width: usize,  // Sand grid width (default 64)
-    pub sand_grid_height: usize,  // Sand grid height (default 32)
-    pub sand_spawn_enabled: bool,  // Enable spawning particles (default true)
-    pub sand_particle_type: String,  // Particle type to spawn: sand, water, stone, fire, wood, lava (default "sand")
-    pub sand_spawn_rate: f64,  // Spawn rate 0.0-1.0 (default 0.3)
-    pub sand_spawn_radius: usize,  // Spawn radius in cells (default 3)
-    pub sand_spawn_x: usize,  // Spawn X position in cells (default width/2)
-    pub sand_obstacles_enabled: bool,  // Place random obstacles in bottom quarter (default false)
-    pub sand_obstacle_density: f64,  // Obstacle density 0.0-1.0 (default 0.15)
-    pub sand_fire_enabled: bool,  // Enable fire spread (default true)
-    pub sand_color_sand: String,  // Color for sand particles (default "C2B280" - tan)
-    pub sand_color_water: String,  // Color for water particles (default "0077BE" - blue)
-    pub sand_color_stone: String,  // Color for stone particles (default "808080" - gray)
-    pub sand_color_fire: String,  // Color for fire particles (default "FF4500" - orange-red)
-    pub sand_color_smoke: String,  // Color for smoke particles (default "404040" - dark gray)
-    pub sand_color_wood: String,  // Color for wood particles (default "8B4513" - saddle brown)
-    pub sand_color_lava: String,  // Color for lava particles (default "FF8C00" - dark orange)
+    #[serde(flatten)]
+    pub sand: SandConfig,
 }
 
 impl Default for BandwidthConfig {
@@ -297,149 +1307,173 @@ impl Default for BandwidthConfig {
             direction: "mirrored".to_string(),
             swap: false,
             rx_split_percent: 50.0,
+            bandwidth_layout: "meter".to_string(),
             strobe_on_max: false,
             strobe_rate_hz: 3.0,
             strobe_duration_ms: 166.0,
             strobe_color: "FFFFFF".to_string(),  // White flash for strobe effect
+            strobe_on_beat: false,
+            strobe_midi_note: -1,  // No strobe note
             animation_speed: 1.0,
             scale_animation_speed: false,
             tx_animation_direction: "right".to_string(),
             rx_animation_direction: "left".to_string(),
             interpolation_time_ms: 1000.0,
             enable_interpolation: true,
+            fill_easing: "linear".to_string(),
+            animation_easing: "linear".to_string(),
+            idle_enabled: false,  // Dark when idle by default
+            idle_timeout_secs: 30.0,
+            idle_animation: "breathing".to_string(),
+            idle_color: "0099FF".to_string(),
+            idle_brightness: 0.3,  // Dim - it's a resting state
             wled_ip: "led.local".to_string(),
             multi_device_enabled: false,
             multi_device_send_parallel: true,
             multi_device_fail_fast: false,
+            multi_device_stagger_sends: false,
+            adaptive_fps_enabled: true,
+            adaptive_fps_min: 10.0,
             wled_devices: vec![
                 WLEDDeviceConfig {
                     ip: "led.local".to_string(),
                     led_offset: 0,
                     led_count: 100,
                     enabled: true,
+                    max_fps: 0.0,
+                    led_ma: default_led_ma(),
+                    psu_amps: 0.0,
+                    backup_for: "".to_string(),
+                    reversed: false,
+                    mirrored: false,
+                    segment: -1,
+                    protocol: default_protocol(),
+                    universe: default_universe(),
+                    start_channel: default_start_channel(),
+                    net: 0,
+                    subnet: 0,
                 }
             ],
             interface: "en0".to_string(),
             ssh_host: "".to_string(),  // Empty = local monitoring
             ssh_user: "".to_string(),  // Empty = current user
+            agent_hosts: String::new(),  // Empty = monitor over SSH or locally
+            agent_token: String::new(),
             total_leds: 1200,
             use_gradient: true,
             intensity_colors: false,  // Default to spatial gradient mode
             interpolation: "linear".to_string(),
+            color_space: "rgb".to_string(),
+            gradient_animation: "off".to_string(),
+            gradient_animation_seconds: 60.0,
+            gradient_palettes: "".to_string(),
             fps: 60.0,
             ddp_delay_ms: 0.0,  // No delay by default
             global_brightness: 1.0,  // Default to 100% brightness
+            gamma: 1.0,  // No gamma correction by default
+            color_order: "rgb".to_string(),  // Standard RGB order
+            color_correction: "FFFFFF".to_string(),  // No color correction
+            white_point_kelvin: 6500.0,  // Neutral white
+            fade_in_ms: 500.0,
+            fade_out_ms: 500.0,
+            frame_interpolation: false,
+            frame_interpolation_fps: 60.0,
+            exit_action: "blank".to_string(),
+            wled_pause_when_off: false,
+            wled_brightness_sync: false,
+            ambient_source: "none".to_string(),  // Auto-brightness off
+            ambient_sensor: String::new(),
+            ambient_min_lux: 5.0,
+            ambient_max_lux: 500.0,
+            ambient_min_brightness: 0.2,
+            ambient_max_brightness: 1.0,
+            quiet_hours: String::new(),  // No quiet hours
+            quiet_hours_brightness: 0.2,
+            hue_enabled: false,
+            hue_bridge_ip: String::new(),
+            hue_username: String::new(),
+            hue_clientkey: String::new(),
+            hue_entertainment_group: "1".to_string(),
+            hue_lights: Vec::new(),
+            lifx_devices: Vec::new(),
+            nanoleaf_ip: String::new(),
+            nanoleaf_token: String::new(),
+            nanoleaf_panels: Vec::new(),
+            gpio_buttons: Vec::new(),  // No buttons
+            gpio_mode_cycle: "bandwidth,live,geometry,sand".to_string(),  // Modes that run without an input source
+            alert_animation: "flash".to_string(),
+            alert_color: "FF0000".to_string(),
+            alert_timeout_secs: 300,  // 5 minutes
+            alert_webhook_enabled: false,
+            demo_modes: "bandwidth,live,geometry,sand,tron".to_string(),  // Everything that runs without an input source
+            demo_interval_secs: 60.0,
+            demo_crossfade_ms: 2000.0,
             mode: "bandwidth".to_string(),  // Default to bandwidth meter mode
             httpd_enabled: true,
             httpd_https_enabled: false,  // Disabled by default
+            acme_enabled: false,  // Self-signed certificate by default
+            acme_domain: "".to_string(),
+            acme_email: "".to_string(),
+            acme_staging: false,
+            acme_http_port: 80,  // HTTP-01 validation always connects to port 80
+            httpd_mtls_enabled: false,  // Any client may connect by default
+            httpd_mtls_ca_path: "".to_string(),
             httpd_ip: "localhost".to_string(),
             httpd_port: 8080,
+            httpd_assets_dir: "".to_string(),  // Serve the web UI embedded in the binary
             httpd_auth_enabled: false,
             httpd_auth_user: "".to_string(),
             httpd_auth_pass: "".to_string(),
+            httpd_auth_pass_hash: "".to_string(),
+            httpd_rate_limit_per_minute: 600,  // Well above what the web UI needs
+            httpd_login_max_attempts: 5,
+            httpd_login_lockout_secs: 300,  // 5 minute lockout
+            httpd_users: Vec::new(),  // Only the primary admin user by default
+            api_tokens: Vec::new(),  // No automation tokens by default
+            sync_role: "off".to_string(),  // Standalone by default
+            sync_group: "default".to_string(),
+            sync_key: String::new(),  // Must be set on the leader and every follower before sync starts
+            sync_address: "255.255.255.255".to_string(),  // LAN broadcast
+            sync_port: 21325,
+            sync_interval_ms: 1000,  // One clock beacon per second
             test_tx: false,
             test_rx: false,
             test_tx_percent: 100.0,
             test_rx_percent: 100.0,
-            midi_device: "IAC Bus 1".to_string(),
-            midi_gradient: false,
-            midi_random_colors: false,
-            midi_velocity_colors: false,
-            midi_one_to_one: false,
-            midi_channel_mode: false,
-            audio_device: "".to_string(),  // Empty = prompt user on first run
-            audio_gain: 0.0,  // No gain adjustment by default
-            log_scale: false,
-            attack_ms: 10.0,   // 10ms fast attack for responsive feel
-            decay_ms: 150.0,   // 150ms decay so you can see the notes/hits
-            vu: false,
-            peak_hold: false,
-            peak_hold_duration_ms: 1000.0,  // 1 second hold by default
-            peak_hold_color: "FFFFFF".to_string(),  // White peak hold LED
-            peak_direction_toggle: false,  // Disabled by default
-            spectrogram: false,  // Spectrogram mode disabled by default
-            spectrogram_scroll_direction: "right".to_string(),  // Default scroll right (time flows left to right)
-            spectrogram_scroll_speed: 30.0,  // Default 30 pixels per second
-            spectrogram_window_size: 1024,  // Default 1024 sample window for good frequency resolution
-            spectrogram_color_mode: "intensity".to_string(),  // Default to intensity-based coloring
-            matrix_2d_enabled: false,  // Disabled by default - use 1D strip mode
-            matrix_2d_width: 16,  // Default 16x16 matrix
-            matrix_2d_height: 16,
-            matrix_2d_gradient_direction: "horizontal".to_string(),  // Default to horizontal gradient (across frequencies)
+            bandwidth_history: false,
+            bandwidth_history_hours: 24,
+            midi: MidiConfig::default(),
+            audio: AudioConfig::default(),
+            vu: VuConfig::default(),
+            spectrogram: SpectrogramConfig::default(),
+            matrix: MatrixConfig::default(),
             relay_listen_ip: "127.0.0.1".to_string(),  // Default to localhost
             relay_listen_port: 1234,  // Default UDP listen port for relay mode
             relay_frame_width: 16,  // Default 16x16 frame
             relay_frame_height: 16,
-            webcam_frame_width: 16,  // Default 16x16 webcam capture
-            webcam_frame_height: 16,
-            webcam_target_fps: 30.0,  // Default 30 FPS for webcam
-            webcam_brightness: 0.5,  // Default 50% brightness to avoid washout
-            tron_width: 64,  // Default 64x32 grid for Tron game
-            tron_height: 32,
-            tron_speed_ms: 100.0,  // Default 100ms update interval (10 FPS game speed)
-            tron_reset_delay_ms: 2000,  // Default 2 second delay before resetting after game over
-            tron_look_ahead: 8,  // Look 8 steps ahead
-            tron_trail_length: 0,  // Infinite trail by default
-            tron_ai_aggression: 0.3,  // 30% aggression (cautious play)
-            tron_num_players: 2,  // Default 2 players
-            tron_food_mode: false,  // Food mode disabled by default
-            tron_food_max_count: 1,  // Default 1 food at a time
-            tron_food_ttl_seconds: 10,  // Default 10 seconds before food relocates
-            tron_trail_fade: true,  // Trail fading enabled by default
-            tron_super_food_enabled: true,  // Super food enabled by default
-            tron_power_food_enabled: true,  // Power food enabled by default
-            tron_diagonal_movement: false,  // Diagonal movement disabled by default
-            tron_player_colors: "rainbow,fire".to_string(),  // Default colors (deprecated)
-            tron_player_1_color: "rainbow".to_string(),
-            tron_player_2_color: "fire".to_string(),
-            tron_player_3_color: "ocean".to_string(),
-            tron_player_4_color: "forest".to_string(),
-            tron_player_5_color: "sunset".to_string(),
-            tron_player_6_color: "purple".to_string(),
-            tron_player_7_color: "cool".to_string(),
-            tron_player_8_color: "warm".to_string(),
-            tron_animation_speed: 1.0,  // Default animation speed
-            tron_scale_animation_speed: false,  // Don't scale by default
-            tron_animation_direction: "forward".to_string(),  // Head to tail direction
-            tron_interpolation: "catmullrom".to_string(),  // Smooth interpolation by default
-            tron_flip_direction_on_food: false,  // Disabled by default
-            geometry_grid_width: 64,  // Default 64x32 grid
-            geometry_grid_height: 32,
-            geometry_mode_select: "cycle".to_string(),  // Cycle through all modes by default
-            geometry_mode_duration_seconds: 12.0,  // 12 seconds per mode
-            geometry_randomize_order: false,  // Sequential order by default
+            ddp_listen_ip: "0.0.0.0".to_string(),  // Accept frames from a leader on any interface
+            ddp_listen_port: 4048,  // Standard DDP port so leaders can treat us like a WLED device
+            sacn_listen_ip: "0.0.0.0".to_string(),  // Accept universes from a console on any interface
+            sacn_start_universe: 1,
+            artnet_listen_ip: "0.0.0.0".to_string(),  // Accept Art-Net from any interface (broadcast or unicast)
+            artnet_universes: Vec::new(),  // Consecutive universes covering total_leds
+            boblight_listen_ip: "0.0.0.0".to_string(),  // Accept grabbers from any interface
+            boblight_listen_port: 19333,  // Standard boblightd port so grabbers work with their defaults
+            relay_color_pipeline: true,  // Calibrate relayed frames like local modes
+            relay_listeners: Vec::new(),  // Single listener by default
+            relay_remap: Vec::new(),  // No remapping by default
+            postfx: Vec::new(),  // No post-processing by default
+            gradients: Vec::new(),  // No user-defined gradients by default
+            matrix_panels: Vec::new(),  // Single matrix by default
+            webcam: WebcamConfig::default(),
+            tron: TronConfig::default(),
+            geometry: GeometryConfig::default(),
 
             // Boid simulation defaults
-            boid_count: 50,
-            boid_separation_distance: 0.1,
-            boid_alignment_distance: 0.3,
-            boid_cohesion_distance: 0.3,
-            boid_max_speed: 0.03,
-            boid_max_force: 0.001,
-            boid_predator_enabled: false,
-            boid_predator_count: 3,
-            boid_predator_speed: 0.04,
-            boid_avoidance_distance: 0.4,
-            boid_chase_force: 0.002,
+            boid: BoidConfig::default(),
 
             // Falling sand defaults
-            sand_grid_width: 64,
-            sand_grid_height: 32,
-            sand_spawn_enabled: true,
-            sand_particle_type: "sand".to_string(),
-            sand_spawn_rate: 0.3,
-            sand_spawn_radius: 3,
-            sand_spawn_x: 32,  // Default to center (width/2)
-            sand_obstacles_enabled: false,
-            sand_obstacle_density: 0.15,
-            sand_fire_enabled: true,
-            sand_color_sand: "C2B280".to_string(),
-            sand_color_water: "0077BE".to_string(),
-            sand_color_stone: "808080".to_string(),
-            sand_color_fire: "FF4500".to_string(),
-            sand_color_smoke: "404040".to_string(),
-            sand_color_wood: "8B4513".to_string(),
-            sand_color_lava: "FF8C00".to_string(),
+            sand: SandConfig::default(),
         }
     }
 }
@@ -516,7 +1550,7 @@ impl BandwidthConfig {
         }
 
         if let Some(ref midi_device) = args.midi_device {
-            self.midi_device = midi_device.clone();
+            self.midi.device = midi_device.clone();
             args_provided = true;
         }
 
@@ -579,10 +1613,37 @@ impl BandwidthConfig {
 
     pub fn load_with_path(cfg_arg: Option<&str>) -> Result<Self> {
         let path = Self::config_path(cfg_arg)?;
-        let contents = std::fs::read_to_string(&path)?;
+        let contents = read_config_file(&path)?;
         let mut parsed: Self = toml::from_str(&contents)?;
         parsed.config_path = Some(path);
         parsed.sanitize();
+        gradients::configure(&parsed);
+
+        // Auto-migrate: Hash plaintext web UI passwords so they are never stored in the clear
+        let mut passwords_hashed = false;
+        if !parsed.httpd_auth_pass.is_empty() {
+            match crate::auth::hash_password(&parsed.httpd_auth_pass) {
+                Ok(hash) => {
+                    parsed.httpd_auth_pass_hash = hash;
+                    parsed.httpd_auth_pass.clear();
+                    passwords_hashed = true;
+                }
+                Err(e) => eprintln!("Failed to hash httpd_auth_pass: {}", e),
+            }
+        }
+        for user in parsed.httpd_users.iter_mut().filter(|u| !u.password.is_empty()) {
+            match crate::auth::hash_password(&user.password) {
+                Ok(hash) => {
+                    user.password_hash = hash;
+                    user.password.clear();
+                    passwords_hashed = true;
+                }
+                Err(e) => eprintln!("Failed to hash password for user {}: {}", user.username, e),
+            }
+        }
+        if passwords_hashed {
+            let _ = parsed.save();
+        }
 
         // Auto-migrate: If wled_devices is empty but wled_ip exists, create device[0]
         if parsed.wled_devices.is_empty() && !parsed.wled_ip.is_empty() {
@@ -592,6 +1653,18 @@ impl BandwidthConfig {
                 led_offset: 0,
                 led_count: parsed.total_leds,
                 enabled: true,
+                max_fps: 0.0,
+                led_ma: default_led_ma(),
+                psu_amps: 0.0,
+                backup_for: "".to_string(),
+                reversed: false,
+                mirrored: false,
+                segment: -1,
+                protocol: default_protocol(),
+                universe: default_universe(),
+                start_channel: default_start_channel(),
+                net: 0,
+                subnet: 0,
             });
             // Save the migrated config
             let _ = parsed.save();
@@ -600,7 +1673,7 @@ impl BandwidthConfig {
         // Auto-calculate total_leds from multi-device config if devices exist
         if !parsed.wled_devices.is_empty() {
             let calculated_total = parsed.wled_devices.iter()
-                .filter(|d| d.enabled)
+                .filter(|d| d.enabled && d.backup_for.is_empty())
                 .map(|d| d.led_offset + d.led_count)
                 .max()
                 .unwrap_or(parsed.total_leds);
@@ -618,72 +1691,335 @@ impl BandwidthConfig {
         self.color = Self::sanitize_color_string(&self.color);
         self.tx_color = Self::sanitize_color_string(&self.tx_color);
         self.rx_color = Self::sanitize_color_string(&self.rx_color);
+        self.vu.left_color = Self::sanitize_color_string(&self.vu.left_color);
+        self.vu.right_color = Self::sanitize_color_string(&self.vu.right_color);
         self.strobe_color = Self::sanitize_color_string(&self.strobe_color);
-        self.peak_hold_color = Self::sanitize_color_string(&self.peak_hold_color);
+        self.vu.peak_hold_color = Self::sanitize_color_string(&self.vu.peak_hold_color);
+        self.idle_color = Self::sanitize_color_string(&self.idle_color);
+        self.idle_animation = self.idle_animation.trim().to_lowercase();
+        if self.idle_animation != "drift" {
+            self.idle_animation = "breathing".to_string();
+        }
+        self.color_correction = Self::sanitize_color_string(&self.color_correction);
+        if self.color_correction.len() != 6 {
+            self.color_correction = "FFFFFF".to_string();
+        }
+        self.white_point_kelvin = self.white_point_kelvin.max(1000.0).min(40000.0);
+        self.fade_in_ms = self.fade_in_ms.max(0.0).min(10000.0);
+        self.fade_out_ms = self.fade_out_ms.max(0.0).min(10000.0);
+        self.frame_interpolation_fps = self.frame_interpolation_fps.max(10.0).min(240.0);
+        self.ambient_source = self.ambient_source.trim().to_lowercase();
+        if !["none", "iio", "http"].contains(&self.ambient_source.as_str()) {
+            self.ambient_source = "none".to_string();
+        }
+        self.ambient_sensor = self.ambient_sensor.trim().to_string();
+        self.ambient_min_lux = self.ambient_min_lux.max(0.0).min(100000.0);
+        self.ambient_max_lux = self.ambient_max_lux.max(self.ambient_min_lux).min(100000.0);
+        self.ambient_min_brightness = self.ambient_min_brightness.max(0.0).min(1.0);
+        self.ambient_max_brightness = self.ambient_max_brightness.max(0.0).min(1.0);
+        self.quiet_hours = crate::quiet_hours::normalize(&self.quiet_hours);
+        self.quiet_hours_brightness = self.quiet_hours_brightness.max(0.0).min(1.0);
+        self.hue_bridge_ip = self.hue_bridge_ip.trim().to_string();
+        self.hue_username = self.hue_username.trim().to_string();
+        self.hue_clientkey = self.hue_clientkey.trim().to_lowercase();
+        self.hue_entertainment_group = self.hue_entertainment_group.trim().to_string();
+        for light in &mut self.hue_lights {
+            light.led_count = light.led_count.max(1);
+        }
+        for device in &mut self.lifx_devices {
+            device.ip = device.ip.trim().to_string();
+            device.led_count = device.led_count.max(1);
+        }
+        self.nanoleaf_ip = self.nanoleaf_ip.trim().to_string();
+        self.nanoleaf_token = self.nanoleaf_token.trim().to_string();
+        for panel in &mut self.nanoleaf_panels {
+            panel.led_count = panel.led_count.max(1);
+        }
+        for button in &mut self.gpio_buttons {
+            button.action = button.action.trim().to_lowercase();
+        }
+        self.gpio_buttons.retain(|b| crate::gpio::ACTIONS.contains(&b.action.as_str()));
+        self.gpio_mode_cycle = self.gpio_mode_cycle.trim().to_lowercase();
+        self.alert_animation = self.alert_animation.trim().to_lowercase();
+        if !crate::alert::ANIMATIONS.contains(&self.alert_animation.as_str()) {
+            self.alert_animation = "flash".to_string();
+        }
+        self.alert_color = self.alert_color.trim().trim_start_matches('#').to_uppercase();
+        if crate::types::Rgb::from_hex(&self.alert_color).is_err() {
+            self.alert_color = "FF0000".to_string();
+        }
+        self.alert_timeout_secs = self.alert_timeout_secs.min(86400);
+        self.demo_modes = self.demo_modes.trim().to_lowercase();
+        self.demo_interval_secs = self.demo_interval_secs.max(5.0).min(86400.0);
+        self.demo_crossfade_ms = self.demo_crossfade_ms.max(0.0).min(30000.0);
+        self.adaptive_fps_min = self.adaptive_fps_min.max(1.0).min(240.0);
+        self.exit_action = self.exit_action.trim().to_lowercase();
+        if !["blank", "restore", "none"].contains(&self.exit_action.as_str()) {
+            self.exit_action = "blank".to_string();
+        }
 
         // Sanitize string values (trim whitespace)
         self.wled_ip = self.wled_ip.trim().to_string();
         self.interface = self.interface.trim().to_string();
         self.ssh_host = self.ssh_host.trim().to_string();
         self.ssh_user = self.ssh_user.trim().to_string();
+        self.agent_hosts = crate::agent::parse_hosts(&self.agent_hosts).join(",");
+        self.agent_token = self.agent_token.trim().to_string();
+        self.sync_key = self.sync_key.trim().to_string();
         self.direction = self.direction.trim().to_lowercase();
         self.tx_animation_direction = self.tx_animation_direction.trim().to_lowercase();
         self.rx_animation_direction = self.rx_animation_direction.trim().to_lowercase();
         self.interpolation = self.interpolation.trim().to_lowercase();
+        self.color_space = self.color_space.trim().to_lowercase();
+        if !["rgb", "hsv", "oklab", "lch"].contains(&self.color_space.as_str()) {
+            self.color_space = "rgb".to_string();
+        }
+        self.gradient_animation = self.gradient_animation.trim().to_lowercase();
+        if !["off", "hue", "palette"].contains(&self.gradient_animation.as_str()) {
+            self.gradient_animation = "off".to_string();
+        }
+        self.gradient_animation_seconds = self.gradient_animation_seconds.max(0.1).min(86400.0);
+        self.vu.scale = self.vu.scale.trim().to_lowercase();
+        if !["linear", "db"].contains(&self.vu.scale.as_str()) {
+            self.vu.scale = "linear".to_string();
+        }
+        self.vu.db_range = self.vu.db_range.max(6.0).min(120.0);
+        self.vu.db_reference = self.vu.db_reference.max(-60.0).min(12.0);
+        self.spectrogram.trail_ms = self.spectrogram.trail_ms.max(50.0).min(30000.0);
+        self.spectrogram.onset = self.spectrogram.onset.trim().to_lowercase();
+        if !["off", "brighten", "accent"].contains(&self.spectrogram.onset.as_str()) {
+            self.spectrogram.onset = "off".to_string();
+        }
+        self.spectrogram.onset_color = Self::sanitize_color_string(&self.spectrogram.onset_color);
+        self.spectrogram.onset_sensitivity = self.spectrogram.onset_sensitivity.max(1.1).min(10.0);
+        self.audio.fft_window = self.audio.fft_window.trim().to_lowercase();
+        if !["hann", "hamming", "blackman"].contains(&self.audio.fft_window.as_str()) {
+            self.audio.fft_window = "hann".to_string();
+        }
+        self.audio.fft_size = self.audio.fft_size.max(256).min(16384).next_power_of_two();
+        self.spectrogram.window_size = self.spectrogram.window_size.max(256).min(16384).next_power_of_two();
+        self.audio.fft_overlap = self.audio.fft_overlap.max(0.0).min(0.95);
+        self.audio.cqt_min_freq = self.audio.cqt_min_freq.max(8.0).min(20000.0);
+        self.audio.cqt_leds_per_semitone = self.audio.cqt_leds_per_semitone.max(1.0).min(12.0);
+        self.vu.ballistics = self.vu.ballistics.trim().to_lowercase();
+        if !["peak", "rms", "vu"].contains(&self.vu.ballistics.as_str()) {
+            self.vu.ballistics = "peak".to_string();
+        }
+        self.gradient_palettes = self.gradient_palettes.trim().to_string();
+        for easing in [&mut self.fill_easing, &mut self.animation_easing] {
+            *easing = easing.trim().to_lowercase();
+            if !["linear", "ease-in-out", "exponential", "spring"].contains(&easing.as_str()) {
+                *easing = "linear".to_string();
+            }
+        }
         self.mode = self.mode.trim().to_lowercase();
+        self.color_order = self.color_order.trim().to_lowercase();
+        if !["rgb", "rbg", "grb", "gbr", "brg", "bgr"].contains(&self.color_order.as_str()) {
+            self.color_order = "rgb".to_string();
+        }
         self.httpd_ip = self.httpd_ip.trim().to_string();
+        self.acme_domain = self.acme_domain.trim().to_lowercase();
+        self.acme_email = self.acme_email.trim().to_string();
+        self.httpd_mtls_ca_path = self.httpd_mtls_ca_path.trim().to_string();
+        self.httpd_assets_dir = self.httpd_assets_dir.trim().to_string();
         self.httpd_auth_user = self.httpd_auth_user.trim().to_string();
-        self.midi_device = self.midi_device.trim().to_string();
-        self.audio_device = self.audio_device.trim().to_string();
+        self.sync_role = self.sync_role.trim().to_lowercase();
+        self.sync_group = self.sync_group.trim().to_string();
+        self.sync_address = self.sync_address.trim().to_string();
+        self.midi.device = self.midi.device.trim().to_string();
+        self.midi.morph_preset_a = self.midi.morph_preset_a.trim().to_string();
+        self.midi.morph_preset_b = self.midi.morph_preset_b.trim().to_string();
+        self.midi.morph_note_a = self.midi.morph_note_a.min(127);
+        self.midi.morph_note_b = self.midi.morph_note_b.min(127);
+        self.midi.morph_seconds = self.midi.morph_seconds.max(0.0).min(600.0);
+        for zone in &mut self.midi.zones {
+            zone.channel = zone.channel.max(1).min(16);
+            zone.devices = zone.devices.iter().map(|ip| ip.trim().to_string()).filter(|ip| !ip.is_empty()).collect();
+            if zone.devices.is_empty() {
+                zone.led_count = zone.led_count.max(1);
+            }
+        }
+        self.audio.device = self.audio.device.trim().to_string();
         self.relay_listen_ip = self.relay_listen_ip.trim().to_string();
+        self.ddp_listen_ip = self.ddp_listen_ip.trim().to_string();
+        self.sacn_listen_ip = self.sacn_listen_ip.trim().to_string();
+        self.artnet_listen_ip = self.artnet_listen_ip.trim().to_string();
+        self.boblight_listen_ip = self.boblight_listen_ip.trim().to_string();
+        self.boblight_listen_port = self.boblight_listen_port.max(1);
+        for range in &mut self.artnet_universes {
+            range.universe = range.universe.min(0x7fff);
+            range.led_count = range.led_count.max(1).min(170);
+        }
+        for user in &mut self.httpd_users {
+            user.username = user.username.trim().to_string();
+            user.role = user.role.trim().to_lowercase();
+            if !["viewer", "operator", "admin"].contains(&user.role.as_str()) {
+                user.role = "viewer".to_string();  // Unknown roles fall back to least privilege
+            }
+        }
+        for token in &mut self.api_tokens {
+            token.name = token.name.trim().to_string();
+            token.scope = token.scope.trim().to_lowercase();
+            if token.scope != "write" {
+                token.scope = "read".to_string();  // Unknown scopes fall back to least privilege
+            }
+        }
+        for listener in &mut self.relay_listeners {
+            listener.listen_ip = listener.listen_ip.trim().to_string();
+            listener.protocol = listener.protocol.trim().to_lowercase();
+            listener.frame_width = listener.frame_width.max(1).min(10000);
+            listener.frame_height = listener.frame_height.max(1).min(10000);
+            listener.universe = listener.universe.max(1).min(63999);
+        }
+        for fx in &mut self.postfx {
+            fx.mode = fx.mode.trim().to_lowercase();
+            fx.mirror = fx.mirror.trim().to_lowercase();
+            if !["off", "horizontal", "vertical", "both"].contains(&fx.mirror.as_str()) {
+                fx.mirror = "off".to_string();
+            }
+            fx.width = fx.width.min(10000);
+            fx.blur_radius = fx.blur_radius.max(0.0).min(50.0);
+            fx.bloom_strength = fx.bloom_strength.max(0.0).min(4.0);
+            fx.bloom_threshold = fx.bloom_threshold.max(0.0).min(254.0);
+            fx.bloom_radius = fx.bloom_radius.max(0.5).min(50.0);
+        }
+        for gradient in &mut self.gradients {
+            gradient.name = gradient.name.trim().to_string();
+            for stop in &mut gradient.stops {
+                stop.color = stop.color.trim().trim_start_matches('#').to_uppercase();
+                stop.position = if stop.position.is_finite() { stop.position.clamp(0.0, 1.0) } else { 0.0 };
+            }
+            gradient.stops.retain(|stop| stop.color.len() == 6 && stop.color.chars().all(|c| c.is_ascii_hexdigit()));
+            gradient.stops.sort_by(|a, b| a.position.total_cmp(&b.position));
+        }
+        self.gradients.retain(|gradient| !gradient.name.is_empty() && !gradient.stops.is_empty());
+        if ![0, 90, 180, 270].contains(&self.matrix.rotation) {
+            self.matrix.rotation = 0;
+        }
+        for panel in &mut self.matrix_panels {
+            if ![0, 90, 180, 270].contains(&panel.rotation) {
+                panel.rotation = 0;
+            }
+            panel.width = panel.width.min(1000);
+            panel.height = panel.height.min(1000);
+            panel.x = panel.x.min(10000);
+            panel.y = panel.y.min(10000);
+        }
+        self.matrix_panels.retain(|panel| panel.width > 0 && panel.height > 0);
 
         // Clamp numeric values to reasonable ranges
         self.max_gbps = self.max_gbps.max(0.1).min(400.0);
         self.total_leds = self.total_leds.max(1).min(100000);
+
+        // Remap rules write into the forwarded frame - keep them inside the strip
+        let total_leds = self.total_leds;
+        for rule in &mut self.relay_remap {
+            rule.length = rule.length.min(total_leds.saturating_sub(rule.dst_start));
+        }
+        self.relay_remap.retain(|rule| rule.length > 0);
         self.fps = self.fps.max(1.0).min(500.0);
         self.ddp_delay_ms = self.ddp_delay_ms.max(0.0).min(10000.0);
         self.global_brightness = self.global_brightness.max(0.0).min(1.0);
+        self.gamma = self.gamma.max(0.1).min(5.0);
         self.rx_split_percent = self.rx_split_percent.max(0.0).min(100.0);
+        self.bandwidth_layout = self.bandwidth_layout.trim().to_lowercase();
+        if !["meter", "dashboard"].contains(&self.bandwidth_layout.as_str()) {
+            self.bandwidth_layout = "meter".to_string();
+        }
         self.strobe_rate_hz = self.strobe_rate_hz.max(0.0).min(100.0);
         self.strobe_duration_ms = self.strobe_duration_ms.max(0.0).min(10000.0);
+        self.strobe_midi_note = self.strobe_midi_note.max(-1).min(127);
         self.animation_speed = self.animation_speed.max(0.0).min(100.0);
         self.interpolation_time_ms = self.interpolation_time_ms.max(0.0).min(10000.0);
+        self.idle_timeout_secs = self.idle_timeout_secs.max(1.0).min(86400.0);
+        self.idle_brightness = self.idle_brightness.max(0.0).min(1.0);
         self.httpd_port = self.httpd_port.max(1).min(65535);
+        self.acme_http_port = self.acme_http_port.max(1).min(65535);
+        self.sync_port = self.sync_port.max(1).min(65535);
+        self.sync_interval_ms = self.sync_interval_ms.max(100).min(60000);
+        self.httpd_login_max_attempts = self.httpd_login_max_attempts.max(1).min(100);
+        self.httpd_login_lockout_secs = self.httpd_login_lockout_secs.min(86400);
         self.test_tx_percent = self.test_tx_percent.max(0.0).min(101.0);
         self.test_rx_percent = self.test_rx_percent.max(0.0).min(101.0);
-        self.attack_ms = self.attack_ms.max(0.0).min(10000.0);
-        self.decay_ms = self.decay_ms.max(0.0).min(10000.0);
-        self.peak_hold_duration_ms = self.peak_hold_duration_ms.max(0.0).min(10000.0);
-        self.audio_gain = self.audio_gain.max(-200.0).min(200.0);
+        self.bandwidth_history_hours = self.bandwidth_history_hours.max(1).min(168);
+        self.audio.attack_ms = self.audio.attack_ms.max(0.0).min(10000.0);
+        self.audio.decay_ms = self.audio.decay_ms.max(0.0).min(10000.0);
+        self.vu.peak_hold_duration_ms = self.vu.peak_hold_duration_ms.max(0.0).min(10000.0);
+        self.audio.gain = self.audio.gain.max(-200.0).min(200.0);
+        if self.audio.analysis_rate != 0 {
+            self.audio.analysis_rate = self.audio.analysis_rate.max(8000).min(192000);
+        }
+        self.audio.noise_floor_margin_db = self.audio.noise_floor_margin_db.max(0.0).min(30.0);
         self.relay_listen_port = self.relay_listen_port.max(1).min(65535);
+        self.ddp_listen_port = self.ddp_listen_port.max(1).min(65535);
+        self.sacn_start_universe = self.sacn_start_universe.max(1).min(63999);
         self.relay_frame_width = self.relay_frame_width.max(1).min(10000);
         self.relay_frame_height = self.relay_frame_height.max(1).min(10000);
-        self.webcam_frame_width = self.webcam_frame_width.max(1).min(10000);
-        self.webcam_frame_height = self.webcam_frame_height.max(1).min(10000);
-        self.webcam_target_fps = self.webcam_target_fps.max(1.0).min(120.0);
-        self.webcam_brightness = self.webcam_brightness.max(0.0).min(2.0);
-        self.tron_width = self.tron_width.max(8).min(256);
-        self.tron_height = self.tron_height.max(8).min(256);
-        self.tron_speed_ms = self.tron_speed_ms.max(5.0).min(10000.0);
-        self.tron_reset_delay_ms = self.tron_reset_delay_ms.max(0).min(10000);
-        self.tron_look_ahead = self.tron_look_ahead.max(1).min(128);
-        self.tron_trail_length = self.tron_trail_length.min(10000);  // 0 is valid (infinite)
-        self.tron_ai_aggression = self.tron_ai_aggression.max(0.0).min(1.0);
-        self.tron_num_players = self.tron_num_players.max(1).min(8);  // 1 = Snake mode
-        self.tron_food_max_count = self.tron_food_max_count.max(1).min(100);  // 1-100 food items
-        self.tron_food_ttl_seconds = self.tron_food_ttl_seconds.max(1).min(300);  // 1-300 seconds
-        self.tron_player_colors = Self::sanitize_color_string(&self.tron_player_colors);
-        self.tron_player_1_color = Self::sanitize_color_string(&self.tron_player_1_color);
-        self.tron_player_2_color = Self::sanitize_color_string(&self.tron_player_2_color);
-        self.tron_player_3_color = Self::sanitize_color_string(&self.tron_player_3_color);
-        self.tron_player_4_color = Self::sanitize_color_string(&self.tron_player_4_color);
-        self.tron_player_5_color = Self::sanitize_color_string(&self.tron_player_5_color);
-        self.tron_player_6_color = Self::sanitize_color_string(&self.tron_player_6_color);
-        self.tron_player_7_color = Self::sanitize_color_string(&self.tron_player_7_color);
-        self.tron_player_8_color = Self::sanitize_color_string(&self.tron_player_8_color);
-        self.tron_animation_speed = self.tron_animation_speed.max(0.0).min(100.0);
-        self.tron_animation_direction = self.tron_animation_direction.trim().to_lowercase();
-        self.tron_interpolation = self.tron_interpolation.trim().to_lowercase();
+        for device in &mut self.wled_devices {
+            device.max_fps = device.max_fps.max(0.0).min(500.0);  // 0 = unlimited
+            device.led_ma = device.led_ma.max(0.0).min(255.0);
+            device.psu_amps = device.psu_amps.max(0.0).min(1000.0);  // 0 = no limit
+            device.backup_for = device.backup_for.trim().to_string();
+            device.segment = device.segment.max(-1).min(31);  // WLED has at most 32 segments
+            device.protocol = device.protocol.trim().to_lowercase();
+            if !["ddp", "sacn", "artnet"].contains(&device.protocol.as_str()) {
+                device.protocol = default_protocol();
+            }
+            if device.universe == UNSET_UNIVERSE {
+                device.universe = if device.protocol == "artnet" { 0 } else { default_universe() };  // Art-Net counts from 0
+            }
+            device.universe = if device.protocol == "artnet" {
+                device.universe.min(15)  // The rest of the port-address is the net and sub-net
+            } else {
+                device.universe.max(1).min(63999)  // E1.31 universe range
+            };
+            device.start_channel = device.start_channel.max(1).min(510);  // Room for at least one LED
+            device.net = device.net.min(127);
+            device.subnet = device.subnet.min(15);
+        }
+        self.webcam.frame_width = self.webcam.frame_width.max(1).min(10000);
+        self.webcam.frame_height = self.webcam.frame_height.max(1).min(10000);
+        self.webcam.target_fps = self.webcam.target_fps.max(1.0).min(120.0);
+        self.webcam.brightness = self.webcam.brightness.max(0.0).min(2.0);
+        self.webcam.zone_layout = self.webcam.zone_layout.trim().to_lowercase();
+        if !["pixels", "grid", "edges", "custom"].contains(&self.webcam.zone_layout.as_str()) {
+            self.webcam.zone_layout = "pixels".to_string();
+        }
+        self.webcam.zone_columns = self.webcam.zone_columns.max(1).min(1000);
+        self.webcam.zone_rows = self.webcam.zone_rows.max(1).min(1000);
+        self.webcam.zone_depth = self.webcam.zone_depth.max(1.0).min(50.0);
+        self.webcam.zone_smoothing = self.webcam.zone_smoothing.max(0.0).min(0.99);
+        for zone in &mut self.webcam_zones {
+            zone.x = zone.x.max(0.0).min(100.0);
+            zone.y = zone.y.max(0.0).min(100.0);
+            zone.width = zone.width.max(0.0).min(100.0 - zone.x);
+            zone.height = zone.height.max(0.0).min(100.0 - zone.y);
+            zone.smoothing = zone.smoothing.map(|s| s.max(0.0).min(0.99));
+        }
+        self.tron.width = self.tron.width.max(8).min(256);
+        self.tron.height = self.tron.height.max(8).min(256);
+        self.tron.speed_ms = self.tron.speed_ms.max(5.0).min(10000.0);
+        self.tron.reset_delay_ms = self.tron.reset_delay_ms.max(0).min(10000);
+        self.tron.look_ahead = self.tron.look_ahead.max(1).min(128);
+        self.tron.trail_length = self.tron.trail_length.min(10000);  // 0 is valid (infinite)
+        self.tron.ai_aggression = self.tron.ai_aggression.max(0.0).min(1.0);
+        self.tron.num_players = self.tron.num_players.max(1).min(8);  // 1 = Snake mode
+        self.tron.food_max_count = self.tron.food_max_count.max(1).min(100);  // 1-100 food items
+        self.tron.food_ttl_seconds = self.tron.food_ttl_seconds.max(1).min(300);  // 1-300 seconds
+        self.tron.obstacle_density = self.tron.obstacle_density.max(0.0).min(0.3);
+        self.tron.obstacles = self.tron.obstacles.trim().to_string();
+        self.tron.player_colors = Self::sanitize_color_string(&self.tron.player_colors);
+        self.tron.player_1_color = Self::sanitize_color_string(&self.tron.player_1_color);
+        self.tron.player_2_color = Self::sanitize_color_string(&self.tron.player_2_color);
+        self.tron.player_3_color = Self::sanitize_color_string(&self.tron.player_3_color);
+        self.tron.player_4_color = Self::sanitize_color_string(&self.tron.player_4_color);
+        self.tron.player_5_color = Self::sanitize_color_string(&self.tron.player_5_color);
+        self.tron.player_6_color = Self::sanitize_color_string(&self.tron.player_6_color);
+        self.tron.player_7_color = Self::sanitize_color_string(&self.tron.player_7_color);
+        self.tron.player_8_color = Self::sanitize_color_string(&self.tron.player_8_color);
+        self.tron.animation_speed = self.tron.animation_speed.max(0.0).min(100.0);
+        self.tron.animation_direction = self.tron.animation_direction.trim().to_lowercase();
+        self.tron.interpolation = self.tron.interpolation.trim().to_lowercase();
     }
 
     /// Sanitize a color string (hex colors or comma-separated list)
@@ -770,8 +2106,15 @@ swap = {}
 # Example: 50.0 = 50/50 split, 70.0 = 70/30 split (RX/TX)
 rx_split_percent = {}
 
+# Bandwidth layout
+# "meter" = the RX/TX meter, "dashboard" = one labeled row per monitored interface
+# (list several in interface, e.g. "eth0,eth1") with an RX bar over a TX bar
+# The dashboard needs a 2D matrix (matrix_2d_enabled) - otherwise the meter is shown
+bandwidth_layout = "{}"
+
 # Strobe entire RX or TX segment when bandwidth exceeds max
 # When enabled, the entire segment will flash on/off when at max utilization
+# In VU mode this strobes a channel while it clips
 strobe_on_max = {}
 
 # Strobe rate in Hz (flashes per second)
@@ -787,6 +2130,14 @@ strobe_duration_ms = {}
 # Default is "000000" (black/off). Can be any hex color like "FF0000" for red
 strobe_color = "{}"
 
+# Strobe on detected beats in audio modes (VU, spectrum, matrix)
+# Each beat fires one flash
+strobe_on_beat = {}
+
+# MIDI note number (0-127) that strobes the whole strip while held in MIDI mode
+# -1 = disabled. Example: 36 = C2 (kick drum on most drum pads)
+strobe_midi_note = {}
+
 # Animation speed in LEDs per frame (0.0 = disabled, 1.0 = 60 LEDs/sec)
 # Controls how fast gradients travel along the strip
 animation_speed = {}
@@ -813,6 +2164,31 @@ interpolation_time_ms = {}
 # Options: true (smooth transitions), false (instant response)
 enable_interpolation = {}
 
+# Easing curve for the meter fill between bandwidth readings
+# Options: "linear", "ease-in-out", "exponential", "spring" (overshoots, then settles)
+fill_easing = "{}"
+
+# Easing curve for animation speed changes between bandwidth readings (with scale_animation_speed)
+# Options: "linear", "ease-in-out", "exponential", "spring"
+animation_easing = "{}"
+
+# Idle animation - fade into a gentle animation when there's no signal (zero bandwidth / silent audio)
+# Returns to the live visualization instantly when activity resumes
+idle_enabled = {}
+
+# Seconds without signal before the idle animation fades in
+idle_timeout_secs = {}
+
+# Idle animation style
+# Options: "breathing" (slow pulse of the first color), "drift" (gradient scrolling slowly, needs 2+ colors)
+idle_animation = "{}"
+
+# Idle color(s) - hex "0099FF", list "FF0000,0000FF", or a gradient name
+idle_color = "{}"
+
+# Idle animation brightness (0.0 to 1.0)
+idle_brightness = {}
+
 # WLED device IP address or hostname
 wled_ip = "{}"
 
@@ -826,6 +2202,15 @@ multi_device_send_parallel = {}
 # Stop all devices if one fails (true) or continue with working devices (false)
 multi_device_fail_fast = {}
 
+# Spread per-device sends across the frame interval instead of bursting them all at once
+# Reduces Wi-Fi contention and dropped frames when many devices share an access point
+multi_device_stagger_sends = {}
+
+# Adaptive FPS - when sends to a device keep failing or stalling (congested Wi-Fi), send it fewer frames,
+# stepping back up to the full rate once it keeps up again (never below adaptive_fps_min)
+adaptive_fps_enabled = {}
+adaptive_fps_min = {}
+
 # Network interface to monitor
 # Can be single interface "eth0" or combined with comma "eth0,eth1"
 interface = "{}"
@@ -838,6 +2223,15 @@ ssh_host = "{}"
 # Example: "myuser"
 ssh_user = "{}"
 
+# rustwled agents to read interface counters from instead of SSH (empty = use ssh_host or this machine)
+# Comma-separated "host" or "host:port" (default port 4050), e.g. "10.0.0.2,10.0.0.3:4060"
+# Start each agent with: rustwled agent --token <agent_token>
+# Traffic from all agents is added together
+agent_hosts = "{}"
+
+# Shared secret the agents were started with
+agent_token = "{}"
+
 # Total number of LEDs in the strip (can be changed while running)
 # TX uses first half (0-N/2), RX uses second half (N/2-N)
 total_leds = {}
@@ -857,6 +2251,20 @@ intensity_colors = {}
 # Options: "linear" (sharp), "basis" (smooth B-spline), "catmullrom" (smooth Catmull-Rom)
 interpolation = "{}"
 
+# Color space gradients blend in (only applies when use_gradient = true)
+# Options: "rgb" (classic), "hsv" (hue wheel), "oklab" (perceptual, no muddy midpoints), "lch" (perceptual with hue)
+color_space = "{}"
+
+# Animate every gradient over time, independent of the positional animation
+# Options: "off", "hue" (slowly rotate the hue), "palette" (cycle through gradient_palettes)
+gradient_animation = "{}"
+
+# Seconds per full hue turn, or how long each palette shows when cycling
+gradient_animation_seconds = {}
+
+# Gradient names to cycle through with gradient_animation = "palette" (comma-separated)
+gradient_palettes = "{}"
+
 # Rendering frame rate (can be changed while running)
 # Try different values like 30, 60, 120, 144 to reduce stuttering
 fps = {}
@@ -872,8 +2280,114 @@ ddp_delay_ms = {}
 # Set WLED's brightness to 255 (100%) and control brightness from here
 global_brightness = {}
 
+# Output gamma correction exponent applied before sending to WLED
+# 1.0 = disabled, 2.2-2.8 = typical for LEDs (makes low levels look more natural)
+gamma = {}
+
+# Output color channel order (for strips wired with a different byte order)
+# Options: "rgb", "rbg", "grb", "gbr", "brg", "bgr"
+color_order = "{}"
+
+# Output color correction as hex per-channel multiplier
+# "FFFFFF" = no correction, "FFB0F0" = typical 5050 LED correction (less green)
+color_correction = "{}"
+
+# Output white point in Kelvin - shifts everything warmer or cooler to match room lighting
+# 6500 = neutral, 2700-3000 = warm (incandescent), 4000 = neutral white, 8000+ = cool
+# Colors can also be given as temperatures anywhere a hex color is accepted (e.g. color = "3000K")
+white_point_kelvin = {}
+
+# Soft start/stop - output ramps up from black over fade_in_ms when a mode starts,
+# and the last frame fades to black over fade_out_ms when quitting or switching modes
+# 0 = off (instant on, last frame left on the strip)
+fade_in_ms = {}
+fade_out_ms = {}
+
+# Motion interpolation for slow sources (e.g. a webcam at 10fps) - each new frame is blended in over the
+# time the source takes between frames, with in-between frames sent at frame_interpolation_fps
+# Adds up to one source frame of latency; sources already at the output rate pass through unchanged
+frame_interpolation = {}
+frame_interpolation_fps = {}
+
+# What the strip shows after rustwled quits
+# "blank" = send a black frame, "restore" = put back each WLED device's preset/effect from before startup,
+# "none" = leave the last frame (WLED falls back to its own effect after its realtime timeout)
+exit_action = "{}"
+
+# Two-way sync with the WLED devices over their JSON API
+# wled_pause_when_off: stop sending to a device while it's switched off in the WLED app, resume when it's back on
+# wled_brightness_sync: set each device's master brightness from global_brightness (frames are then sent at full brightness)
+wled_pause_when_off = {}
+wled_brightness_sync = {}
+
+# Auto-brightness - scale global_brightness with the room's light level
+# "none" = off, "iio" = Linux IIO light sensor (BH1750, TSL2561, VEML7700 on a Raspberry Pi, ...),
+# "http" = a URL returning lux as a bare number or JSON ("lux", "illuminance" or "value" field)
+ambient_source = "{}"
+
+# IIO sysfs file (e.g. "/sys/bus/iio/devices/iio:device0/in_illuminance_input", empty = first light sensor found)
+# or the sensor URL for "http" (e.g. "http://192.168.1.60/lux")
+ambient_sensor = "{}"
+
+# Light levels (lux) mapped to the brightness limits - logarithmic in between, changes ease in over a few seconds
+# Brightness limits are fractions of global_brightness (0.0-1.0)
+ambient_min_lux = {}
+ambient_max_lux = {}
+ambient_min_brightness = {}
+ambient_max_brightness = {}
+
+# Quiet hours - local times of day the output brightness is capped, whatever the mode, API or global_brightness say
+# Comma-separated "HH:MM-HH:MM" windows (a window ending before it starts runs past midnight), empty = off
+# Example: "22:00-07:00" or "22:00-07:00,13:00-14:00"
+quiet_hours = "{}"
+
+# Highest output brightness during quiet hours (0.0-1.0, 0.0 = dark)
+quiet_hours_brightness = {}
+
+# Philips Hue Entertainment - Hue lights mirror the average color of an LED range, streamed alongside the WLED devices
+# Pair with the bridge first (POST /api {"devicetype":"rustwled#host","generateclientkey":true} after pressing the link button)
+# to get the username and clientkey, and create an entertainment area in the Hue app
+# Lights are [[hue_lights]] blocks at the end of the file
+hue_enabled = {}
+hue_bridge_ip = "{}"
+hue_username = "{}"
+hue_clientkey = "{}"
+hue_entertainment_group = "{}"
+
+# Nanoleaf - panels mirror the average color of an LED zone over the external control streaming API
+# Pair by holding the controller's power button for 5-7 seconds, then POST /api/v1/new on port 16021 for the token
+# Panels are [[nanoleaf_panels]] blocks at the end of the file (LIFX bulbs are [[lifx_devices]] blocks)
+nanoleaf_ip = "{}"
+nanoleaf_token = "{}"
+
+# GPIO buttons (Raspberry Pi, build with --features gpio) - modes the next_mode/prev_mode buttons step through
+# Buttons themselves are [[gpio_buttons]] blocks at the end of the file
+gpio_mode_cycle = "{}"
+
+# Alerts - POST /api/alert {{"name": ..., "animation": ..., "color": ..., "timeout_secs": ...}} overrides the current mode
+# with an alarm animation until POST /api/alert/clear or the timeout. Fields left out of the request use these defaults
+# Options: "flash", "pulse", "chase", "solid"
+alert_animation = "{}"
+alert_color = "{}"
+# Seconds before an alert ends on its own (0 = only when cleared)
+alert_timeout_secs = {}
+# Accept Alertmanager and PagerDuty webhooks on /api/alert/webhook (firing/triggered raises, resolved clears)
+# Options: true, false
+alert_webhook_enabled = {}
+
+# Demo Mode (mode = "demo") - modes to rotate through, in order
+# bandwidth runs on simulated traffic and live on a generated music signal, so no input is needed
+# Options: any of "bandwidth", "live", "geometry", "sand", "tron"
+demo_modes = "{}"
+# Seconds each mode runs before the next one takes over
+demo_interval_secs = {}
+# Crossfade from one mode to the next in milliseconds (0 = cut)
+demo_crossfade_ms = {}
+
 # Mode - Current visualization mode (changes apply immediately without restart)
-# Options: "bandwidth" (network traffic), "midi" (MIDI input), "live" (audio visualization)
+# Options: "bandwidth" (network traffic), "midi" (MIDI input), "live" (audio visualization), "ddp" (follow a leader instance),
+#          "sacn" / "artnet" (driven by a lighting console over sACN/E1.31 or Art-Net), "boblight" (ambilight grabbers),
+#          "demo" (rotates through the other modes - see demo_modes)
 mode = "{}"
 
 # HTTP server configuration
@@ -886,6 +2400,34 @@ httpd_enabled = {}
 # Options: true, false
 httpd_https_enabled = {}
 
+# Let's Encrypt (ACME) - Obtain and renew a trusted certificate instead of a self-signed one
+# Requires a public DNS name pointing at this host and port 80 reachable from the internet
+# (HTTP-01 challenge). Certificates are renewed automatically 30 days before expiry.
+# Options: true, false (changes require restart)
+acme_enabled = {}
+
+# Let's Encrypt - Domain name to issue the certificate for (e.g. "leds.example.com")
+acme_domain = "{}"
+
+# Let's Encrypt - Contact email for expiry notices (optional)
+acme_email = "{}"
+
+# Let's Encrypt - Use the staging directory while testing (certificates are not trusted)
+acme_staging = {}
+
+# Let's Encrypt - Port for the HTTP-01 challenge responder
+# Use 80 unless a router/proxy forwards external port 80 to another local port
+acme_http_port = {}
+
+# Mutual TLS - Only accept HTTPS clients presenting a certificate signed by the configured CA
+# Recommended when the web UI is exposed beyond the local network (requires httpd_https_enabled)
+# Options: true, false (changes require restart)
+httpd_mtls_enabled = {}
+
+# Mutual TLS - Path to a PEM file containing the trusted client CA certificate(s)
+# Example: "/home/user/.config/rustwled/client_ca.pem"
+httpd_mtls_ca_path = "{}"
+
 # IP address for the HTTP/HTTPS server to listen on
 # Also used as the hostname for SSL certificate generation when HTTPS is enabled
 # Use "0.0.0.0" to listen on all interfaces, or "127.0.0.1" for localhost only
@@ -894,16 +2436,59 @@ httpd_ip = "{}"
 # Port for the HTTP/HTTPS server to listen on
 httpd_port = {}
 
-# Enable HTTP Basic Authentication
+# Optional directory of web UI files that override the copies embedded in the binary
+# Files are matched by name (index.html, login.html, and anything served under /assets/)
+# Leave empty to serve only the embedded web UI
+httpd_assets_dir = "{}"
+
+# Enable web UI login (session cookie with CSRF protection)
 # Options: true, false
 httpd_auth_enabled = {}
 
-# HTTP Basic Auth username (only used when httpd_auth_enabled = true)
+# Web UI username (only used when httpd_auth_enabled = true)
 httpd_auth_user = "{}"
 
-# HTTP Basic Auth password (only used when httpd_auth_enabled = true)
+# Web UI password - enter a new password here in plaintext to set it
+# It is hashed with argon2 into httpd_auth_pass_hash and cleared on next load
 httpd_auth_pass = "{}"
 
+# Argon2 hash of the web UI password (managed automatically)
+httpd_auth_pass_hash = "{}"
+
+# Maximum HTTP requests per client IP per minute - excess requests get 429 Too Many Requests
+# Set to 0 to disable rate limiting
+httpd_rate_limit_per_minute = {}
+
+# Failed login attempts allowed from one IP before it is locked out
+httpd_login_max_attempts = {}
+
+# Seconds a locked-out IP must wait before it can try to log in again
+httpd_login_lockout_secs = {}
+
+# Instance Sync - Role of this instance in a leader/follower group
+# "leader" broadcasts its mode, config changes, and frame clock
+# "follower" applies them so several machines render the same show in lockstep
+# Local settings (devices, network, HTTP server, calibration) are never synchronized
+# Options: "off", "leader", "follower" (changes require restart)
+sync_role = "{}"
+
+# Instance Sync - Group name (followers ignore leaders from other groups)
+sync_group = "{}"
+
+# Instance Sync - Shared secret every sync packet is signed with (the same on the leader and all followers)
+# Required when sync_role is "leader" or "follower" - followers drop packets that aren't signed with it
+sync_key = "{}"
+
+# Instance Sync - Broadcast address the leader sends to
+# Example: "255.255.255.255" (whole LAN) or "192.168.1.255" (one subnet)
+sync_address = "{}"
+
+# Instance Sync - UDP port for sync packets (must match on leader and followers)
+sync_port = {}
+
+# Instance Sync - Leader clock beacon interval in milliseconds
+sync_interval_ms = {}
+
 # Test Mode - Simulate TX (upload) bandwidth at maximum utilization
 # Options: true, false
 test_tx = {}
@@ -920,6 +2505,14 @@ test_tx_percent = {}
 # Controls how much of max bandwidth to simulate for RX when test_rx is enabled
 test_rx_percent = {}
 
+# Record bandwidth readings (one per second) to a history file next to this config
+# Play a past window back on the LEDs and TUI with --replay, e.g. "rustwled --replay 10h+2h"
+# Options: true, false
+bandwidth_history = {}
+
+# Hours of history kept - once full, the oldest readings are overwritten (1-168, 1.4 MB per day)
+bandwidth_history_hours = {}
+
 # MIDI Mode - MIDI input device name
 # Default: "IAC Bus 1" on macOS
 # Use --midi flag to enable MIDI mode
@@ -950,9 +2543,27 @@ midi_one_to_one = {}
 # Options: true (use channels), false (ignore channels)
 midi_channel_mode = {}
 
+# MIDI Zones - Route MIDI channels to their own devices or LED ranges (e.g. channel 1 lights the desk strip,
+# channel 10 the ceiling), for multi-zone performances from one controller
+# Zones are [[midi_zones]] blocks at the end of the file - with any set, notes on other channels don't light up
+
+# MIDI Preset Morph - Notes that morph the look to a saved preset (see "rustwled preset save")
+# Numbers and colors blend over midi_morph_seconds; other settings switch halfway through
+# The mode, MIDI settings and devices aren't changed. Pick notes outside the playing range
+# (an 88-key piano plays 21-108) - trigger notes don't light up. Empty preset = note plays normally
+midi_morph_preset_a = "{}"
+midi_morph_preset_b = "{}"
+midi_morph_note_a = {}
+midi_morph_note_b = {}
+
+# MIDI Preset Morph Time - Seconds a morph takes (0 = switch instantly)
+midi_morph_seconds = {}
+
 # Audio Device - Audio input device name for live mode
 # Leave empty to be prompted on first run, or set to a device name to use it automatically
 # Example: "BlackHole 2ch" or "MacBook Pro Microphone"
+# Test signals instead of a device: "generator:sweep" (sine sweep 20Hz-20kHz), "generator:pink" (pink noise),
+# "generator:clicks" (metronome) - to check the audio -> FFT -> LED chain without playing music
 audio_device = "{}"
 
 # Audio Gain - Audio input gain adjustment in percent (-100 to +100)
@@ -961,6 +2572,19 @@ audio_device = "{}"
 # Example: 50 (50% boost), -20 (20% reduction)
 audio_gain = {}
 
+# Audio Analysis Rate - Sample rate audio is resampled to before analysis (8000-192000 Hz)
+# A fixed rate keeps FFT bin widths, and so the frequency -> LED mapping, the same on every device
+# whether it delivers 44.1, 48 or 96 kHz. 0 = analyze at the device's own rate
+audio_analysis_rate = {}
+
+# Noise Floor - Learn the background noise (fan hum, mains buzz) at each frequency while running and
+# only show the spectrum above it, so a quiet room stays dark but quiet music still lights the strip
+# Calibrates over the first few seconds, then keeps adapting slowly. Options: true, false
+noise_floor = {}
+
+# Noise Floor Margin - How far above the learned floor (in dB, 0-30) a frequency has to be to light up
+noise_floor_margin_db = {}
+
 # Log Scale - Use logarithmic scaling for bandwidth visualization
 # Options: true, false
 log_scale = {}
@@ -973,12 +2597,68 @@ attack_ms = {}
 # Lower = faster fade-out, Higher = LEDs stay visible longer
 decay_ms = {}
 
+# FFT Size - Samples per FFT in live spectrum modes (power of two, 256-16384)
+# Larger = finer frequency resolution but slower response (1024 = ~23ms at 44.1kHz)
+# The spectrogram uses spectrogram_window_size instead
+fft_size = {}
+
+# FFT Window - Window function applied to each block of samples before the FFT
+# Options: "hann" (good all-rounder), "hamming" (sharper peaks), "blackman" (lowest leakage)
+fft_window = "{}"
+
+# FFT Overlap - How much consecutive FFT windows overlap (0.0-0.95)
+# A new window is taken after size * (1 - overlap) new samples; until then the last one is reused
+fft_overlap = {}
+
+# Constant-Q Spectrum - Map LEDs to musical pitch instead of linear FFT bins
+# Each LED covers a fixed fraction of a semitone, so octaves take equal space and notes line up
+# Options: true, false
+cqt = {}
+
+# Constant-Q Start Frequency (Hz) - Pitch of the first LED (32.7 = C1, 27.5 = A0, 8-20000)
+cqt_min_freq = {}
+
+# Constant-Q LEDs per Semitone - 1 = one LED per note, 2 = quarter tones, etc. (1-12)
+# LEDs above the Nyquist frequency stay dark
+cqt_leds_per_semitone = {}
+
 # VU Meter Mode - Classic digital VU meter for live audio (left/right channels)
 # When enabled in --live mode, LEDs are split in half: first half = left channel, second half = right channel
 # Reuses color gradients, direction, and animation settings from bandwidth mode
 # Options: true, false
 vu = {}
 
+# VU Meter Scale - How channel levels map onto the meter
+# "linear" = fixed 4x boost (a peak at 25% of full scale fills the meter)
+# "db" = calibrated dBFS scale from (vu_db_reference - vu_db_range) up to vu_db_reference
+# Options: "linear", "db"
+vu_scale = "{}"
+
+# dB range of the meter in dB scale (6-120). Example: 60 = -60..0 dB
+vu_db_range = {}
+
+# Reference level in dBFS that fills the meter in dB scale (-60 to +12)
+# Signals above the reference count as clipping. Example: -6 gives 6 dB of headroom
+vu_db_reference = {}
+
+# VU Meter Ballistics - How each channel is measured and smoothed
+# "peak" = window peak with attack/decay smoothing (fast, catches transients)
+# "rms" = window RMS with attack/decay smoothing (closer to perceived loudness)
+# "vu" = RMS integrated over 300ms rising and falling, like an analog VU meter (ignores attack/decay)
+# Options: "peak", "rms", "vu"
+vu_ballistics = "{}"
+
+# VU Mono - Sum left and right into one meter instead of splitting the strip in half
+# With direction "left" or "right" the meter spans the whole strip; "mirrored" and "opposing"
+# show the mono level on both halves. Useful for mono sources and short strips
+# Options: true, false
+vu_mono = {}
+
+# VU Left / Right Colors - Colors for each audio channel in VU mode (hex or gradient, like 'color')
+# Empty = the bandwidth mapping: left uses rx_color, right uses tx_color (each falling back to 'color')
+vu_left_color = "{}"
+vu_right_color = "{}"
+
 # Peak Hold - Enable peak hold LED in VU meter mode
 # When enabled, a single LED will remain lit at the peak position for the specified duration
 # Options: true, false
@@ -1018,6 +2698,27 @@ spectrogram_window_size = {}
 # Options: "intensity" (magnitude->color), "frequency" (Y-position->color), "volume" (overall level shifts hue)
 spectrogram_color_mode = "{}"
 
+# Spectrogram 1D - Show the spectrogram on a plain strip instead of a 2D matrix
+# The strip shows the latest spectrum (low to high frequency) and each LED's peaks fade out
+# over spectrogram_trail_ms, so brightness carries the recent history
+# Options: true, false
+spectrogram_1d = {}
+
+# Spectrogram Trail (ms) - How long a 1D spectrogram peak takes to fade out (50-30000)
+spectrogram_trail_ms = {}
+
+# Spectrogram Onset - Pulse the newest column when a transient (drum hit, pluck) is detected
+# The pulse scrolls along with the spectrogram; on a 1D strip the whole strip pulses
+# Options: "off", "brighten" (lift toward white), "accent" (flash spectrogram_onset_color)
+spectrogram_onset = "{}"
+
+# Spectrogram Onset Color - Hex color flashed by the "accent" onset overlay
+spectrogram_onset_color = "{}"
+
+# Spectrogram Onset Sensitivity - How far spectral flux must jump over its running average to count
+# as an onset (1.1-10.0, lower = more pulses)
+spectrogram_onset_sensitivity = {}
+
 # 2D Matrix Mode - Enable 2D matrix output for spectrum visualization (live mode only)
 # When enabled, spectrum is rendered on a 2D matrix instead of a 1D strip
 # Options: true, false
@@ -1035,6 +2736,16 @@ matrix_2d_height = {}
 # Options: "horizontal" (gradient across frequencies), "vertical" (gradient across amplitude)
 matrix_2d_gradient_direction = "{}"
 
+# 2D Matrix Rotation - Clockwise rotation of the matrix as mounted, applied to every matrix mode
+# (2D spectrum, spectrogram, sand, geometry, tron) so rotated panels don't need per-mode settings
+# Options: 0, 90, 180, 270
+matrix_2d_rotation = {}
+
+# 2D Matrix Flip - Mirror the matrix left to right (flip_h) or top to bottom (flip_v), after rotation
+# Options: true, false
+matrix_2d_flip_h = {}
+matrix_2d_flip_v = {}
+
 # Relay Mode - IP address to listen on for receiving raw RGB24 frames
 # Use "0.0.0.0" to listen on all interfaces, or "127.0.0.1" for localhost only
 # Only used when mode = "relay"
@@ -1050,6 +2761,42 @@ relay_frame_width = {}
 # Relay Frame Height - Height of incoming frame in pixels (relay mode only)
 relay_frame_height = {}
 
+# DDP Input Mode - IP address to listen on for DDP frames from a leader instance
+# Only used when mode = "ddp"
+ddp_listen_ip = "{}"
+
+# DDP Input Mode - UDP listen port for DDP frames (4048 = standard WLED DDP port)
+# Point the leader's wled_devices entry at this host to chain instances
+# Only used when mode = "ddp"
+ddp_listen_port = {}
+
+# sACN Input Mode - IP address to listen on for sACN (E1.31) from a lighting console (port 5568)
+# Multicast universes are joined on this interface (0.0.0.0 = default interface)
+# Only used when mode = "sacn"
+sacn_listen_ip = "{}"
+
+# sACN Input Mode - First universe - 170 RGB LEDs per universe (channels 1-510),
+# total_leds continue into the following universes
+# Only used when mode = "sacn"
+sacn_start_universe = {}
+
+# Art-Net Input Mode - IP address to listen on for ArtDmx/ArtSync (port 6454)
+# Universes map to LED ranges with [[artnet_universes]] blocks (default: consecutive universes from 0, 170 LEDs each)
+# Only used when mode = "artnet"
+artnet_listen_ip = "{}"
+
+# Boblight Input Mode - IP address and TCP port to accept boblight clients on (19333 = standard boblightd port)
+# Ambilight grabbers (Hyperion's boblight output, Kodi's boblight add-on, boblight-X11) see one light per LED,
+# running clockwise around the screen edge from the top left corner
+# Only used when mode = "boblight"
+boblight_listen_ip = "{}"
+boblight_listen_port = {}
+
+# Relay Color Pipeline - Apply global_brightness, gamma, color order and correction to relayed frames
+# Disable to forward incoming frames untouched
+# Options: true, false
+relay_color_pipeline = {}
+
 # Webcam Mode - Frame width in pixels for webcam capture
 # Only used when mode = "webcam"
 webcam_frame_width = {}
@@ -1067,6 +2814,20 @@ webcam_target_fps = {}
 # Default is 0.5 (50%) to prevent washout on bright displays
 webcam_brightness = {}
 
+# Webcam Mode - Zone layout for ambilight / TV backlight setups
+# "pixels" = each captured pixel drives one LED, "grid" = columns x rows cells sharing the strip evenly,
+# "edges" = one LED per zone around the screen edge, clockwise from the top left (columns along top/bottom, rows per side),
+# "custom" = [[webcam_zones]] blocks at the end of the file
+webcam_zone_layout = "{}"
+webcam_zone_columns = {}
+webcam_zone_rows = {}
+
+# Webcam Mode - How far edge zones reach into the picture (percent, 1-50)
+webcam_zone_depth = {}
+
+# Webcam Mode - Zone color smoothing (0 = follow the picture instantly, 0.9 = slow and calm)
+webcam_zone_smoothing = {}
+
 # Tron Game Mode - Grid width in pixels
 # Only used when mode = "tron"
 tron_width = {}
@@ -1143,6 +2904,18 @@ tron_power_food_enabled = {}
 # Options: true (8 directions), false (4 cardinal directions only)
 tron_diagonal_movement = {}
 
+# Tron Game Mode - Random Obstacles
+# Share of the arena covered by walls, laid out afresh each game so AI matches play out differently
+# Walls grow from random seeds in a few Game of Life style generations, so they form clumps and short walls
+# rather than scattered dots. Players die on walls like on the arena edge (power mode doesn't pass them)
+# Range: 0.0 (open arena) to 0.3
+tron_obstacle_density = {}
+
+# Tron Game Mode - Drawn Obstacles
+# Walls that are there every game: "x,y" cells and "x1,y1-x2,y2" filled rectangles, separated by ';'
+# Example: "10,0-10,20;30,12-33,15" (a wall down from the top edge and a 4x4 block)
+tron_obstacles = "{}"
+
 # Tron Game Mode - Player colors (comma-separated gradients) - DEPRECATED
 # Use gradient names like "rainbow,fire,ocean" or hex colors like "00ffff,ff00ff"
 tron_player_colors = "{}"
@@ -1251,156 +3024,788 @@ sand_color_lava = "{}"
             sanitized.direction,
             sanitized.swap,
             sanitized.rx_split_percent,
+            sanitized.bandwidth_layout,
             sanitized.strobe_on_max,
             sanitized.strobe_rate_hz,
             sanitized.strobe_duration_ms,
             sanitized.strobe_color,
+            sanitized.strobe_on_beat,
+            sanitized.strobe_midi_note,
             sanitized.animation_speed,
             sanitized.scale_animation_speed,
             sanitized.tx_animation_direction,
             sanitized.rx_animation_direction,
             sanitized.interpolation_time_ms,
             sanitized.enable_interpolation,
+            sanitized.fill_easing,
+            sanitized.animation_easing,
+            sanitized.idle_enabled,
+            sanitized.idle_timeout_secs,
+            sanitized.idle_animation,
+            sanitized.idle_color,
+            sanitized.idle_brightness,
             sanitized.wled_ip,
             sanitized.multi_device_enabled,
             sanitized.multi_device_send_parallel,
             sanitized.multi_device_fail_fast,
+            sanitized.multi_device_stagger_sends,
+            sanitized.adaptive_fps_enabled,
+            sanitized.adaptive_fps_min,
             sanitized.interface,
             sanitized.ssh_host,
             sanitized.ssh_user,
+            sanitized.agent_hosts,
+            sanitized.agent_token,
             sanitized.total_leds,
             sanitized.use_gradient,
             sanitized.intensity_colors,
             sanitized.interpolation,
+            sanitized.color_space,
+            sanitized.gradient_animation,
+            sanitized.gradient_animation_seconds,
+            sanitized.gradient_palettes,
             sanitized.fps,
             sanitized.ddp_delay_ms,
             sanitized.global_brightness,
+            sanitized.gamma,
+            sanitized.color_order,
+            sanitized.color_correction,
+            sanitized.white_point_kelvin,
+            sanitized.fade_in_ms,
+            sanitized.fade_out_ms,
+            sanitized.frame_interpolation,
+            sanitized.frame_interpolation_fps,
+            sanitized.exit_action,
+            sanitized.wled_pause_when_off,
+            sanitized.wled_brightness_sync,
+            sanitized.ambient_source,
+            sanitized.ambient_sensor,
+            sanitized.ambient_min_lux,
+            sanitized.ambient_max_lux,
+            sanitized.ambient_min_brightness,
+            sanitized.ambient_max_brightness,
+            sanitized.quiet_hours,
+            sanitized.quiet_hours_brightness,
+            sanitized.hue_enabled,
+            sanitized.hue_bridge_ip,
+            sanitized.hue_username,
+            sanitized.hue_clientkey,
+            sanitized.hue_entertainment_group,
+            sanitized.nanoleaf_ip,
+            sanitized.nanoleaf_token,
+            sanitized.gpio_mode_cycle,
+            sanitized.alert_animation,
+            sanitized.alert_color,
+            sanitized.alert_timeout_secs,
+            sanitized.alert_webhook_enabled,
+            sanitized.demo_modes,
+            sanitized.demo_interval_secs,
+            sanitized.demo_crossfade_ms,
             sanitized.mode,
             sanitized.httpd_enabled,
             sanitized.httpd_https_enabled,
+            sanitized.acme_enabled,
+            sanitized.acme_domain,
+            sanitized.acme_email,
+            sanitized.acme_staging,
+            sanitized.acme_http_port,
+            sanitized.httpd_mtls_enabled,
+            sanitized.httpd_mtls_ca_path,
             sanitized.httpd_ip,
             sanitized.httpd_port,
+            sanitized.httpd_assets_dir,
             sanitized.httpd_auth_enabled,
             sanitized.httpd_auth_user,
             sanitized.httpd_auth_pass,
+            sanitized.httpd_auth_pass_hash,
+            sanitized.httpd_rate_limit_per_minute,
+            sanitized.httpd_login_max_attempts,
+            sanitized.httpd_login_lockout_secs,
+            sanitized.sync_role,
+            sanitized.sync_group,
+            sanitized.sync_key,
+            sanitized.sync_address,
+            sanitized.sync_port,
+            sanitized.sync_interval_ms,
             sanitized.test_tx,
             sanitized.test_rx,
             sanitized.test_tx_percent,
             sanitized.test_rx_percent,
-            sanitized.midi_device,
-            sanitized.midi_gradient,
-            sanitized.midi_random_colors,
-            sanitized.midi_velocity_colors,
-            sanitized.midi_one_to_one,
-            sanitized.midi_channel_mode,
-            sanitized.audio_device,
-            sanitized.audio_gain,
-            sanitized.log_scale,
-            sanitized.attack_ms,
-            sanitized.decay_ms,
-            sanitized.vu,
-            sanitized.peak_hold,
-            sanitized.peak_hold_duration_ms,
-            sanitized.peak_hold_color,
-            sanitized.peak_direction_toggle,
-            sanitized.spectrogram,
-            sanitized.spectrogram_scroll_direction,
-            sanitized.spectrogram_scroll_speed,
-            sanitized.spectrogram_window_size,
-            sanitized.spectrogram_color_mode,
-            sanitized.matrix_2d_enabled,
-            sanitized.matrix_2d_width,
-            sanitized.matrix_2d_height,
-            sanitized.matrix_2d_gradient_direction,
+            sanitized.bandwidth_history,
+            sanitized.bandwidth_history_hours,
+            sanitized.midi.device,
+            sanitized.midi.gradient,
+            sanitized.midi.random_colors,
+            sanitized.midi.velocity_colors,
+            sanitized.midi.one_to_one,
+            sanitized.midi.channel_mode,
+            sanitized.midi.morph_preset_a,
+            sanitized.midi.morph_preset_b,
+            sanitized.midi.morph_note_a,
+            sanitized.midi.morph_note_b,
+            sanitized.midi.morph_seconds,
+            sanitized.audio.device,
+            sanitized.audio.gain,
+            sanitized.audio.analysis_rate,
+            sanitized.audio.noise_floor,
+            sanitized.audio.noise_floor_margin_db,
+            sanitized.audio.log_scale,
+            sanitized.audio.attack_ms,
+            sanitized.audio.decay_ms,
+            sanitized.audio.fft_size,
+            sanitized.audio.fft_window,
+            sanitized.audio.fft_overlap,
+            sanitized.audio.cqt,
+            sanitized.audio.cqt_min_freq,
+            sanitized.audio.cqt_leds_per_semitone,
+            sanitized.vu.enabled,
+            sanitized.vu.scale,
+            sanitized.vu.db_range,
+            sanitized.vu.db_reference,
+            sanitized.vu.ballistics,
+            sanitized.vu.mono,
+            sanitized.vu.left_color,
+            sanitized.vu.right_color,
+            sanitized.vu.peak_hold,
+            sanitized.vu.peak_hold_duration_ms,
+            sanitized.vu.peak_hold_color,
+            sanitized.vu.peak_direction_toggle,
+            sanitized.spectrogram.enabled,
+            sanitized.spectrogram.scroll_direction,
+            sanitized.spectrogram.scroll_speed,
+            sanitized.spectrogram.window_size,
+            sanitized.spectrogram.color_mode,
+            sanitized.spectrogram.strip,
+            sanitized.spectrogram.trail_ms,
+            sanitized.spectrogram.onset,
+            sanitized.spectrogram.onset_color,
+            sanitized.spectrogram.onset_sensitivity,
+            sanitized.matrix.enabled,
+            sanitized.matrix.width,
+            sanitized.matrix.height,
+            sanitized.matrix.gradient_direction,
+            sanitized.matrix.rotation,
+            sanitized.matrix.flip_h,
+            sanitized.matrix.flip_v,
             sanitized.relay_listen_ip,
             sanitized.relay_listen_port,
             sanitized.relay_frame_width,
             sanitized.relay_frame_height,
-            sanitized.webcam_frame_width,
-            sanitized.webcam_frame_height,
-            sanitized.webcam_target_fps,
-            sanitized.webcam_brightness,
-            sanitized.tron_width,
-            sanitized.tron_height,
-            sanitized.tron_speed_ms,
-            sanitized.tron_reset_delay_ms,
-            sanitized.tron_look_ahead,
-            sanitized.tron_trail_length,
-            sanitized.tron_ai_aggression,
-            sanitized.tron_num_players,
-            sanitized.tron_food_mode,
-            sanitized.tron_food_max_count,
-            sanitized.tron_food_ttl_seconds,
-            sanitized.tron_trail_fade,
-            sanitized.tron_super_food_enabled,
-            sanitized.tron_power_food_enabled,
-            sanitized.tron_diagonal_movement,
-            sanitized.tron_player_colors,
-            sanitized.tron_player_1_color,
-            sanitized.tron_player_2_color,
-            sanitized.tron_player_3_color,
-            sanitized.tron_player_4_color,
-            sanitized.tron_player_5_color,
-            sanitized.tron_player_6_color,
-            sanitized.tron_player_7_color,
-            sanitized.tron_player_8_color,
-            sanitized.tron_animation_speed,
-            sanitized.tron_scale_animation_speed,
-            sanitized.tron_animation_direction,
-            sanitized.tron_interpolation,
-            sanitized.tron_flip_direction_on_food,
-            sanitized.geometry_grid_width,
-            sanitized.geometry_grid_height,
-            sanitized.geometry_mode_select,
-            sanitized.geometry_mode_duration_seconds,
-            sanitized.geometry_randomize_order,
-            sanitized.boid_count,
-            sanitized.boid_separation_distance,
-            sanitized.boid_alignment_distance,
-            sanitized.boid_cohesion_distance,
-            sanitized.boid_max_speed,
-            sanitized.boid_max_force,
-            sanitized.boid_predator_enabled,
-            sanitized.boid_predator_count,
-            sanitized.boid_predator_speed,
-            sanitized.boid_avoidance_distance,
-            sanitized.boid_chase_force,
-            sanitized.sand_grid_width,
-            sanitized.sand_grid_height,
-            sanitized.sand_spawn_enabled,
-            sanitized.sand_particle_type,
-            sanitized.sand_spawn_rate,
-            sanitized.sand_spawn_radius,
-            sanitized.sand_spawn_x,
-            sanitized.sand_obstacles_enabled,
-            sanitized.sand_obstacle_density,
-            sanitized.sand_fire_enabled,
-            sanitized.sand_color_sand,
-            sanitized.sand_color_water,
-            sanitized.sand_color_stone,
-            sanitized.sand_color_fire,
-            sanitized.sand_color_smoke,
-            sanitized.sand_color_wood,
-            sanitized.sand_color_lava,
+            sanitized.ddp_listen_ip,
+            sanitized.ddp_listen_port,
+            sanitized.sacn_listen_ip,
+            sanitized.sacn_start_universe,
+            sanitized.artnet_listen_ip,
+            sanitized.boblight_listen_ip,
+            sanitized.boblight_listen_port,
+            sanitized.relay_color_pipeline,
+            sanitized.webcam.frame_width,
+            sanitized.webcam.frame_height,
+            sanitized.webcam.target_fps,
+            sanitized.webcam.brightness,
+            sanitized.webcam.zone_layout,
+            sanitized.webcam.zone_columns,
+            sanitized.webcam.zone_rows,
+            sanitized.webcam.zone_depth,
+            sanitized.webcam.zone_smoothing,
+            sanitized.tron.width,
+            sanitized.tron.height,
+            sanitized.tron.speed_ms,
+            sanitized.tron.reset_delay_ms,
+            sanitized.tron.look_ahead,
+            sanitized.tron.trail_length,
+            sanitized.tron.ai_aggression,
+            sanitized.tron.num_players,
+            sanitized.tron.food_mode,
+            sanitized.tron.food_max_count,
+            sanitized.tron.food_ttl_seconds,
+            sanitized.tron.trail_fade,
+            sanitized.tron.super_food_enabled,
+            sanitized.tron.power_food_enabled,
+            sanitized.tron.diagonal_movement,
+            sanitized.tron.obstacle_density,
+            sanitized.tron.obstacles,
+            sanitized.tron.player_colors,
+            sanitized.tron.player_1_color,
+            sanitized.tron.player_2_color,
+            sanitized.tron.player_3_color,
+            sanitized.tron.player_4_color,
+            sanitized.tron.player_5_color,
+            sanitized.tron.player_6_color,
+            sanitized.tron.player_7_color,
+            sanitized.tron.player_8_color,
+            sanitized.tron.animation_speed,
+            sanitized.tron.scale_animation_speed,
+            sanitized.tron.animation_direction,
+            sanitized.tron.interpolation,
+            sanitized.tron.flip_direction_on_food,
+            sanitized.geometry.grid_width,
+            sanitized.geometry.grid_height,
+            sanitized.geometry.mode_select,
+            sanitized.geometry.mode_duration_seconds,
+            sanitized.geometry.randomize_order,
+            sanitized.boid.count,
+            sanitized.boid.separation_distance,
+            sanitized.boid.alignment_distance,
+            sanitized.boid.cohesion_distance,
+            sanitized.boid.max_speed,
+            sanitized.boid.max_force,
+            sanitized.boid.predator_enabled,
+            sanitized.boid.predator_count,
+            sanitized.boid.predator_speed,
+            sanitized.boid.avoidance_distance,
+            sanitized.boid.chase_force,
+            sanitized.sand.grid_width,
+            sanitized.sand.grid_height,
+            sanitized.sand.spawn_enabled,
+            sanitized.sand.particle_type,
+            sanitized.sand.spawn_rate,
+            sanitized.sand.spawn_radius,
+            sanitized.sand.spawn_x,
+            sanitized.sand.obstacles_enabled,
+            sanitized.sand.obstacle_density,
+            sanitized.sand.fire_enabled,
+            sanitized.sand.color_sand,
+            sanitized.sand.color_water,
+            sanitized.sand.color_stone,
+            sanitized.sand.color_fire,
+            sanitized.sand.color_smoke,
+            sanitized.sand.color_wood,
+            sanitized.sand.color_lava,
         );
 
         // Append wled_devices array if multi-device mode is enabled and devices are configured
         if !sanitized.wled_devices.is_empty() {
             contents.push_str("\n# Multi-Device Configuration\n");
             contents.push_str("# Configure multiple WLED controllers - each gets a portion of the LED frame\n");
+            contents.push_str("# ip: Controller address, or \"virtual\" (\"virtual:PORT\") for the terminal emulator started with \"rustwled virtual\"\n");
             contents.push_str("# led_offset: Starting LED position in unified frame\n");
-            contents.push_str("# led_count: Number of LEDs this device controls\n\n");
+            contents.push_str("# led_count: Number of LEDs this device controls\n");
+            contents.push_str("# max_fps: Maximum frames per second sent to this device (0 = unlimited)\n");
+            contents.push_str("# led_ma: Current per LED at full white in mA (used for power estimation)\n");
+            contents.push_str("# psu_amps: Power supply limit in amps - frames are dimmed to stay under it (0 = no limit)\n");
+            contents.push_str("# backup_for: IP of a primary device - this device receives its frames when the primary fails\n");
+            contents.push_str("# reversed: Reverse LED order on this device (for strips mounted backwards)\n");
+            contents.push_str("# mirrored: Mirror the first half of the device's range onto the second half\n");
+            contents.push_str("# segment: WLED segment id to send to, leaving the other segments running WLED effects (-1 = whole range)\n");
+            contents.push_str("# protocol: \"ddp\" (WLED), \"sacn\" (E1.31) or \"artnet\" (Art-Net) - for pixel controllers that don't speak DDP\n");
+            contents.push_str("# universe / start_channel: Universe and DMX channel of the first LED - later LEDs continue\n");
+            contents.push_str("#   into the following universes, 170 LEDs each from channel 1 (sacn and artnet)\n");
+            contents.push_str("# net / subnet: Art-Net net (0-127) and sub-net (0-15) holding the universe (0-15) (artnet only)\n\n");
 
             for device in &sanitized.wled_devices {
                 contents.push_str("[[wled_devices]]\n");
                 contents.push_str(&format!("ip = \"{}\"\n", device.ip));
                 contents.push_str(&format!("led_offset = {}\n", device.led_offset));
                 contents.push_str(&format!("led_count = {}\n", device.led_count));
+                contents.push_str(&format!("enabled = {}\n", device.enabled));
+                contents.push_str(&format!("max_fps = {}\n", device.max_fps));
+                contents.push_str(&format!("led_ma = {}\n", device.led_ma));
+                contents.push_str(&format!("psu_amps = {}\n", device.psu_amps));
+                contents.push_str(&format!("backup_for = \"{}\"\n", device.backup_for));
+                contents.push_str(&format!("reversed = {}\n", device.reversed));
+                contents.push_str(&format!("mirrored = {}\n", device.mirrored));
+                contents.push_str(&format!("segment = {}\n", device.segment));
+                contents.push_str(&format!("protocol = \"{}\"\n", device.protocol));
+                contents.push_str(&format!("universe = {}\n", device.universe));
+                contents.push_str(&format!("start_channel = {}\n", device.start_channel));
+                contents.push_str(&format!("net = {}\n", device.net));
+                contents.push_str(&format!("subnet = {}\n\n", device.subnet));
+            }
+        }
+
+        // Append relay_listeners array if extra relay inputs are configured
+        if !sanitized.relay_listeners.is_empty() {
+            contents.push_str("\n# Relay Listeners - Additional UDP inputs for relay mode\n");
+            contents.push_str("# protocol: \"raw\" (RGB24 stream), \"ddp\", \"sacn\" or \"artnet\" (universe = first universe)\n");
+            contents.push_str("# led_offset: First LED in the relayed frame this listener writes to\n\n");
+
+            for listener in &sanitized.relay_listeners {
+                contents.push_str("[[relay_listeners]]\n");
+                contents.push_str(&format!("listen_ip = \"{}\"\n", listener.listen_ip));
+                contents.push_str(&format!("listen_port = {}\n", listener.listen_port));
+                contents.push_str(&format!("protocol = \"{}\"\n", listener.protocol));
+                contents.push_str(&format!("frame_width = {}\n", listener.frame_width));
+                contents.push_str(&format!("frame_height = {}\n", listener.frame_height));
+                contents.push_str(&format!("led_offset = {}\n", listener.led_offset));
+                contents.push_str(&format!("universe = {}\n", listener.universe));
+                contents.push_str(&format!("enabled = {}\n\n", listener.enabled));
+            }
+        }
+
+        // Append artnet_universes array if a universe mapping is configured
+        if !sanitized.artnet_universes.is_empty() {
+            contents.push_str("\n# Art-Net Universes - Where each universe's DMX data lands on the strip (Art-Net input mode)\n");
+            contents.push_str("# universe: Port-address (net * 256 + sub-net * 16 + universe)\n");
+            contents.push_str("# led_offset: First LED the universe is written to\n");
+            contents.push_str("# led_count: LEDs taken from the universe (up to 170, 3 channels each)\n\n");
+
+            for range in &sanitized.artnet_universes {
+                contents.push_str("[[artnet_universes]]\n");
+                contents.push_str(&format!("universe = {}\n", range.universe));
+                contents.push_str(&format!("led_offset = {}\n", range.led_offset));
+                contents.push_str(&format!("led_count = {}\n\n", range.led_count));
+            }
+        }
+
+        // Append midi_zones array if any MIDI channels are routed
+        if !sanitized.midi.zones.is_empty() {
+            contents.push_str("\n# MIDI Zones - Which devices or LEDs each MIDI channel lights (MIDI mode)\n");
+            contents.push_str("# channel: MIDI channel (1-16)\n");
+            contents.push_str("# devices: IPs of the devices the channel lights - each shows the channel's notes across its whole length\n");
+            contents.push_str("# led_offset, led_count: LED range the channel lights instead, when no devices are given\n\n");
+
+            for zone in &sanitized.midi.zones {
+                contents.push_str("[[midi_zones]]\n");
+                contents.push_str(&format!("channel = {}\n", zone.channel));
+                if zone.devices.is_empty() {
+                    contents.push_str(&format!("led_offset = {}\n", zone.led_offset));
+                    contents.push_str(&format!("led_count = {}\n\n", zone.led_count));
+                } else {
+                    let devices: Vec<String> = zone.devices.iter().map(|ip| format!("\"{}\"", ip)).collect();
+                    contents.push_str(&format!("devices = [{}]\n\n", devices.join(", ")));
+                }
+            }
+        }
+
+        // Append hue_lights array if any Hue lights are mapped
+        if !sanitized.hue_lights.is_empty() {
+            contents.push_str("\n# Hue Lights - Which LEDs each Hue light in the entertainment area mirrors (average color)\n");
+            contents.push_str("# light_id: Light id in the entertainment area\n");
+            contents.push_str("# led_offset: First LED of the range\n");
+            contents.push_str("# led_count: LEDs averaged into the light's color\n\n");
+
+            for light in &sanitized.hue_lights {
+                contents.push_str("[[hue_lights]]\n");
+                contents.push_str(&format!("light_id = {}\n", light.light_id));
+                contents.push_str(&format!("led_offset = {}\n", light.led_offset));
+                contents.push_str(&format!("led_count = {}\n\n", light.led_count));
+            }
+        }
+
+        // Append webcam_zones array if custom screen zones are configured
+        if !sanitized.webcam_zones.is_empty() {
+            contents.push_str("\n# Webcam Zones - Custom screen zones (webcam_zone_layout = \"custom\")\n");
+            contents.push_str("# x, y, width, height: Rectangle of the captured image in percent\n");
+            contents.push_str("# led_offset, led_count: LED range showing the zone's average color\n");
+            contents.push_str("# smoothing: Optional per-zone smoothing (0-0.99), overrides webcam_zone_smoothing\n\n");
+
+            for zone in &sanitized.webcam_zones {
+                contents.push_str("[[webcam_zones]]\n");
+                contents.push_str(&format!("x = {}\n", zone.x));
+                contents.push_str(&format!("y = {}\n", zone.y));
+                contents.push_str(&format!("width = {}\n", zone.width));
+                contents.push_str(&format!("height = {}\n", zone.height));
+                contents.push_str(&format!("led_offset = {}\n", zone.led_offset));
+                contents.push_str(&format!("led_count = {}\n", zone.led_count));
+                if let Some(smoothing) = zone.smoothing {
+                    contents.push_str(&format!("smoothing = {}\n", smoothing));
+                }
+                contents.push('\n');
+            }
+        }
+
+        // Append lifx_devices array if any LIFX bulbs are configured
+        if !sanitized.lifx_devices.is_empty() {
+            contents.push_str("\n# LIFX Bulbs - Each bulb shows the average color of an LED zone (LAN protocol, UDP 56700)\n");
+            contents.push_str("# ip: Bulb IP address\n");
+            contents.push_str("# led_offset: First LED of the zone\n");
+            contents.push_str("# led_count: LEDs averaged into the bulb's color\n");
+            contents.push_str("# enabled: Enable or disable this bulb\n\n");
+
+            for device in &sanitized.lifx_devices {
+                contents.push_str("[[lifx_devices]]\n");
+                contents.push_str(&format!("ip = \"{}\"\n", device.ip));
+                contents.push_str(&format!("led_offset = {}\n", device.led_offset));
+                contents.push_str(&format!("led_count = {}\n", device.led_count));
                 contents.push_str(&format!("enabled = {}\n\n", device.enabled));
             }
         }
 
-        std::fs::write(path, contents)?;
-        Ok(())
+        // Append nanoleaf_panels array if any Nanoleaf panels are mapped
+        if !sanitized.nanoleaf_panels.is_empty() {
+            contents.push_str("\n# Nanoleaf Panels - Each panel shows the average color of an LED zone\n");
+            contents.push_str("# panel_id: Panel id from the controller's layout\n");
+            contents.push_str("# led_offset: First LED of the zone\n");
+            contents.push_str("# led_count: LEDs averaged into the panel's color\n\n");
+
+            for panel in &sanitized.nanoleaf_panels {
+                contents.push_str("[[nanoleaf_panels]]\n");
+                contents.push_str(&format!("panel_id = {}\n", panel.panel_id));
+                contents.push_str(&format!("led_offset = {}\n", panel.led_offset));
+                contents.push_str(&format!("led_count = {}\n\n", panel.led_count));
+            }
+        }
+
+        // Append gpio_buttons array if any buttons are configured
+        if !sanitized.gpio_buttons.is_empty() {
+            contents.push_str("\n# GPIO Buttons - Physical buttons on a Raspberry Pi (requires building with --features gpio)\n");
+            contents.push_str("# pin: BCM pin number - wire the button between the pin and ground (the internal pull-up is used)\n");
+            contents.push_str("# action: \"next_mode\", \"prev_mode\", \"brightness_up\", \"brightness_down\", \"blank\" (toggle)\n\n");
+
+            for button in &sanitized.gpio_buttons {
+                contents.push_str("[[gpio_buttons]]\n");
+                contents.push_str(&format!("pin = {}\n", button.pin));
+                contents.push_str(&format!("action = \"{}\"\n\n", button.action));
+            }
+        }
+
+        // Append relay_remap array if any remap rules are configured
+        if !sanitized.relay_remap.is_empty() {
+            contents.push_str("\n# Relay Remap Rules - Rearrange incoming relay frames before forwarding\n");
+            contents.push_str("# src_start: First LED of the range in the incoming frame\n");
+            contents.push_str("# dst_start: First LED of the range in the forwarded frame\n");
+            contents.push_str("# length: Number of LEDs in the range\n");
+            contents.push_str("# reverse: Reverse LED order within the range\n\n");
+
+            for rule in &sanitized.relay_remap {
+                contents.push_str("[[relay_remap]]\n");
+                contents.push_str(&format!("src_start = {}\n", rule.src_start));
+                contents.push_str(&format!("dst_start = {}\n", rule.dst_start));
+                contents.push_str(&format!("length = {}\n", rule.length));
+                contents.push_str(&format!("reverse = {}\n\n", rule.reverse));
+            }
+        }
+
+        // Append httpd_users array if additional web UI users exist
+        if !sanitized.httpd_users.is_empty() {
+            contents.push_str("\n# Web UI Users - Additional logins with roles (httpd_auth_user is always admin)\n");
+            contents.push_str("# role: \"viewer\" (read only), \"operator\" (config changes), \"admin\" (devices, tokens, shutdown)\n");
+            contents.push_str("# password: Enter a plaintext password to set it - it is hashed into password_hash on next load\n\n");
+
+            for user in &sanitized.httpd_users {
+                contents.push_str("[[httpd_users]]\n");
+                contents.push_str(&format!("username = \"{}\"\n", user.username));
+                contents.push_str(&format!("password = \"{}\"\n", user.password));
+                contents.push_str(&format!("password_hash = \"{}\"\n", user.password_hash));
+                contents.push_str(&format!("role = \"{}\"\n\n", user.role));
+            }
+        }
+
+        // Append api_tokens array if any automation tokens exist
+        if !sanitized.api_tokens.is_empty() {
+            contents.push_str("\n# API Tokens - Bearer tokens for scripts and integrations (manage via /api/tokens)\n");
+            contents.push_str("# scope: \"read\" (stats and config reads) or \"write\" (also config changes)\n");
+            contents.push_str("# Only a hash of each token is stored - the token itself is shown once when created\n\n");
+
+            for token in &sanitized.api_tokens {
+                contents.push_str("[[api_tokens]]\n");
+                contents.push_str(&format!("name = \"{}\"\n", token.name));
+                contents.push_str(&format!("prefix = \"{}\"\n", token.prefix));
+                contents.push_str(&format!("token_hash = \"{}\"\n", token.token_hash));
+                contents.push_str(&format!("scope = \"{}\"\n", token.scope));
+                contents.push_str(&format!("created = {}\n\n", token.created));
+            }
+        }
+
+        // Append postfx array if any mode has post-processing effects
+        if !sanitized.postfx.is_empty() {
+            contents.push_str("\n# Post-Processing Effects - Applied to a mode's frames before they're sent\n");
+            contents.push_str("# mode: Mode the effects apply to (\"\" = every mode without its own entry)\n");
+            contents.push_str("# width: Matrix width in LEDs for 2D blur/mirror (0 = 1D strip)\n");
+            contents.push_str("# blur_radius: Gaussian blur radius in LEDs (0 = off)\n");
+            contents.push_str("# bloom_strength / bloom_threshold / bloom_radius: Glow around channels brighter than the threshold (strength 0 = off)\n");
+            contents.push_str("# mirror: \"off\", \"horizontal\", \"vertical\", or \"both\"\n\n");
+
+            for fx in &sanitized.postfx {
+                contents.push_str("[[postfx]]\n");
+                contents.push_str(&format!("mode = \"{}\"\n", fx.mode));
+                contents.push_str(&format!("width = {}\n", fx.width));
+                contents.push_str(&format!("blur_radius = {}\n", fx.blur_radius));
+                contents.push_str(&format!("bloom_strength = {}\n", fx.bloom_strength));
+                contents.push_str(&format!("bloom_threshold = {}\n", fx.bloom_threshold));
+                contents.push_str(&format!("bloom_radius = {}\n", fx.bloom_radius));
+                contents.push_str(&format!("mirror = \"{}\"\n\n", fx.mirror));
+            }
+        }
+
+        // Append gradients array if any named gradients are defined
+        if !sanitized.gradients.is_empty() {
+            contents.push_str("\n# Named Gradients - Usable by name anywhere a gradient name is accepted (e.g. color = \"Sunrise\")\n");
+            contents.push_str("# name: Gradient name (case-insensitive, built-in names take precedence)\n");
+            contents.push_str("# stops: Color stops with a position (0.0-1.0) and a hex color\n\n");
+
+            for gradient in &sanitized.gradients {
+                contents.push_str("[[gradients]]\n");
+                contents.push_str(&format!("name = \"{}\"\n", gradient.name));
+                let stops: Vec<String> = gradient.stops.iter()
+                    .map(|stop| format!("{{ position = {}, color = \"{}\" }}", stop.position, stop.color))
+                    .collect();
+                contents.push_str(&format!("stops = [{}]\n\n", stops.join(", ")));
+            }
+        }
+
+        // Append matrix panels array if the matrix is tiled from several panels
+        if !sanitized.matrix_panels.is_empty() {
+            contents.push_str("\n# Matrix Panels - Physical panels tiled into one 2D canvas for matrix modes, chained in this order\n");
+            contents.push_str("# x / y: Canvas position of the panel's top-left corner (after rotation)\n");
+            contents.push_str("# width / height: Panel size in LEDs as wired (before rotation)\n");
+            contents.push_str("# rotation: Clockwise rotation on the canvas: 0, 90, 180, or 270\n");
+            contents.push_str("# serpentine: Odd rows run right to left (zigzag wiring)\n\n");
+
+            for panel in &sanitized.matrix_panels {
+                contents.push_str("[[matrix_panels]]\n");
+                contents.push_str(&format!("x = {}\n", panel.x));
+                contents.push_str(&format!("y = {}\n", panel.y));
+                contents.push_str(&format!("width = {}\n", panel.width));
+                contents.push_str(&format!("height = {}\n", panel.height));
+                contents.push_str(&format!("rotation = {}\n", panel.rotation));
+                contents.push_str(&format!("serpentine = {}\n\n", panel.serpentine));
+            }
+        }
+
+        gradients::configure(&sanitized);
+        write_config_file(&path, &contents)
+    }
+}
+
+/// What changed in a config update - broadcast to the running mode, SSE clients, and sync
+#[derive(Debug, Clone)]
+pub enum ConfigDelta {
+    /// Config keys (as named in the config file) that changed, with the new config
+    Changed { keys: Vec<String>, config: Arc<BandwidthConfig> },
+    /// Nothing in the config changed, but the running mode should re-check system requests (restart, shutdown)
+    Wake,
+}
+
+impl ConfigDelta {
+    /// Delta for known changed keys - the config is sanitized the same way save() does
+    pub fn fields(keys: &[&str], config: &BandwidthConfig) -> ConfigDelta {
+        let mut config = config.clone();
+        config.sanitize();
+        ConfigDelta::Changed {
+            keys: keys.iter().map(|k| k.to_string()).collect(),
+            config: Arc::new(config),
+        }
+    }
+
+    /// Delta between two configs (None if no key differs)
+    pub fn between(old: &BandwidthConfig, new: &BandwidthConfig) -> Option<ConfigDelta> {
+        let old_map = config_key_map(old);
+        let new_map = config_key_map(new);
+        let keys: Vec<String> = new_map.iter()
+            .filter(|(key, value)| old_map.get(*key) != Some(*value))
+            .map(|(key, _)| key.clone())
+            .collect();
+
+        if keys.is_empty() {
+            None
+        } else {
+            Some(ConfigDelta::Changed { keys, config: Arc::new(new.clone()) })
+        }
+    }
+
+    /// The new config (None for a wake-up without a config change)
+    pub fn config(&self) -> Option<&BandwidthConfig> {
+        match self {
+            ConfigDelta::Changed { config, .. } => Some(config),
+            ConfigDelta::Wake => None,
+        }
+    }
+
+    /// The new config, or a copy of the current one for a wake-up
+    pub fn config_or(&self, current: &BandwidthConfig) -> BandwidthConfig {
+        self.config().unwrap_or(current).clone()
+    }
+
+    pub fn keys(&self) -> &[String] {
+        match self {
+            ConfigDelta::Changed { keys, .. } => keys,
+            ConfigDelta::Wake => &[],
+        }
+    }
+
+    /// Whether any of the given config keys changed
+    pub fn changed_any(&self, keys: &[&str]) -> bool {
+        self.keys().iter().any(|k| keys.contains(&k.as_str()))
+    }
+
+    /// Whether any key starting with prefix changed (e.g. "tron_" for all tron settings)
+    pub fn changed_prefix(&self, prefix: &str) -> bool {
+        self.keys().iter().any(|k| k.starts_with(prefix))
+    }
+
+    /// Whether any key matching the patterns changed - a pattern ending in '*' matches by prefix
+    pub fn changed_matching(&self, patterns: &[String]) -> bool {
+        patterns.iter().any(|pattern| match pattern.strip_suffix('*') {
+            Some(prefix) => self.changed_prefix(prefix),
+            None => self.changed_any(&[pattern.as_str()]),
+        })
+    }
+}
+
+/// Hand the config to `apply` on a background thread whenever one of `keys` changes (web UI, API, or file
+/// edits) - a key ending in '*' matches every key with that prefix (e.g. "midi_morph_*")
+pub fn spawn_config_listener(config_change_tx: &broadcast::Sender<ConfigDelta>, keys: &[&str], apply: fn(&BandwidthConfig)) {
+    let keys: Vec<String> = keys.iter().map(|k| k.to_string()).collect();
+    let mut config_change_rx = config_change_tx.subscribe();
+    thread::spawn(move || loop {
+        match config_change_rx.blocking_recv() {
+            Ok(delta) => {
+                if delta.changed_matching(&keys) {
+                    if let Some(config) = delta.config() {
+                        apply(config);
+                    }
+                }
+            }
+            // Missed some updates - the file has the latest settings
+            Err(broadcast::error::RecvError::Lagged(_)) => {
+                if let Ok(config) = BandwidthConfig::load() {
+                    apply(&config);
+                }
+            }
+            Err(broadcast::error::RecvError::Closed) => break,
+        }
+    });
+}
+
+// Config as a flat key -> value map (keys match the config file, since mode sections are flattened)
+pub fn config_key_map(config: &BandwidthConfig) -> serde_json::Map<String, serde_json::Value> {
+    match serde_json::to_value(config) {
+        Ok(serde_json::Value::Object(map)) => map,
+        _ => serde_json::Map::new(),
+    }
+}
+
+/// Config keys that affect the output device setup (a change means reconnecting)
+pub const DEVICE_CONFIG_KEYS: &[&str] = &[
+    "wled_ip",
+    "total_leds",
+    "wled_devices",
+    "multi_device_enabled",
+    "multi_device_send_parallel",
+    "multi_device_fail_fast",
+    "multi_device_stagger_sends",
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_flat_keys_load_into_sections() {
+        let config: BandwidthConfig = toml::from_str(
+            "mode = \"tron\"\ntron_width = 48\nsand_spawn_rate = 0.5\nmidi_device = \"Keys\"\nboid_count = 10\n"
+        ).unwrap();
+        assert_eq!(config.tron.width, 48);
+        assert_eq!(config.sand.spawn_rate, 0.5);
+        assert_eq!(config.midi.device, "Keys");
+        assert_eq!(config.boid.count, 10);
+        assert_eq!(config.tron.height, TronConfig::default().height);
+
+        let json = serde_json::to_value(&config).unwrap();
+        assert_eq!(json["tron_width"], 48);
+    }
+
+    #[test]
+    fn test_old_flat_config_file_round_trip() {
+        let path = std::env::temp_dir().join(format!("rustwled_config_test_{}.conf", std::process::id()));
+        std::fs::write(&path, concat!(
+            "mode = \"live\"\naudio_device = \"Loopback\"\naudio_gain = 25.0\nnoise_floor = true\nattack_ms = 20.0\n",
+            "fft_size = 2048\nfft_window = \"blackman\"\ncqt = true\ncqt_min_freq = 55.0\nlog_scale = true\n",
+            "vu = true\nvu_scale = \"db\"\npeak_hold = true\npeak_hold_duration_ms = 500.0\npeak_hold_color = \"FF0000\"\n",
+            "spectrogram = true\nspectrogram_1d = true\n",
+            "matrix_2d_enabled = true\nmatrix_2d_width = 32\nmatrix_2d_height = 8\nmatrix_2d_rotation = 90\nmatrix_2d_flip_h = true\n",
+            "tron_width = 48\nsand_spawn_rate = 0.5\n",
+        )).unwrap();
+
+        let config = BandwidthConfig::load_with_path(path.to_str()).unwrap();
+        assert_eq!(config.audio.device, "Loopback");
+        assert_eq!(config.audio.gain, 25.0);
+        assert!(config.audio.noise_floor);
+        assert_eq!(config.audio.attack_ms, 20.0);
+        assert_eq!(config.audio.fft_size, 2048);
+        assert_eq!(config.audio.fft_window, "blackman");
+        assert!(config.audio.cqt && config.audio.log_scale);
+        assert_eq!(config.audio.cqt_min_freq, 55.0);
+        assert!(config.vu.enabled && config.vu.peak_hold);
+        assert_eq!(config.vu.scale, "db");
+        assert_eq!(config.vu.peak_hold_duration_ms, 500.0);
+        assert_eq!(config.vu.peak_hold_color, "FF0000");
+        assert!(config.spectrogram.enabled && config.spectrogram.strip);
+        assert!(config.matrix.enabled && config.matrix.flip_h);
+        assert_eq!((config.matrix.width, config.matrix.height, config.matrix.rotation), (32, 8, 90));
+        assert_eq!(config.tron.width, 48);
+        assert_eq!(config.sand.spawn_rate, 0.5);
+
+        // Saving writes the same flat keys, so the file reads back unchanged
+        config.save().unwrap();
+        let contents = std::fs::read_to_string(&path).unwrap();
+        assert!(contents.contains("\nfft_size = 2048\n"));
+        assert!(contents.contains("\nmatrix_2d_width = 32\n"));
+        assert!(contents.contains("\npeak_hold = true\n"));
+        let reloaded = BandwidthConfig::load_with_path(path.to_str()).unwrap();
+        let _ = std::fs::remove_file(&path);
+        assert_eq!(config_key_map(&reloaded), config_key_map(&config));
+    }
+
+    #[test]
+    fn test_device_universe_defaults_by_protocol() {
+        let mut config: BandwidthConfig = toml::from_str(concat!(
+            "[[wled_devices]]\nip = \"10.0.0.2\"\nled_offset = 0\nled_count = 170\nenabled = true\nprotocol = \"artnet\"\n",
+            "[[wled_devices]]\nip = \"10.0.0.3\"\nled_offset = 170\nled_count = 170\nenabled = true\nprotocol = \"sacn\"\n",
+        )).unwrap();
+        config.sanitize();
+        assert_eq!(config.wled_devices[0].universe, 0);
+        assert_eq!(config.wled_devices[1].universe, 1);
+    }
+
+    #[test]
+    fn test_config_delta_between() {
+        let old = BandwidthConfig::default();
+        let mut new = old.clone();
+        assert!(ConfigDelta::between(&old, &new).is_none());
+
+        new.fps = 60.0;
+        new.tron.width = 32;
+        let delta = ConfigDelta::between(&old, &new).unwrap();
+        assert!(delta.changed_any(&["fps"]));
+        assert!(delta.changed_prefix("tron_"));
+        assert!(!delta.changed_any(DEVICE_CONFIG_KEYS));
+        assert_eq!(delta.config().map(|c| c.fps), Some(60.0));
+
+        let patterns = |keys: &[&str]| keys.iter().map(|k| k.to_string()).collect::<Vec<_>>();
+        assert!(delta.changed_matching(&patterns(&["gamma", "tron_*"])));
+        assert!(delta.changed_matching(&patterns(&["fps"])));
+        assert!(!delta.changed_matching(&patterns(&["fps_*", "tron_"])));
+    }
+
+    #[test]
+    fn test_resolve_vu_colors() {
+        let mut config = BandwidthConfig { color: "FFFFFF".to_string(), tx_color: "FF0000".to_string(), ..Default::default() };
+        assert_eq!(resolve_vu_colors(&config), ("FFFFFF".to_string(), "FF0000".to_string()));
+
+        config.vu.left_color = "00FF00,0000FF".to_string();
+        assert_eq!(resolve_vu_colors(&config), ("00FF00,0000FF".to_string(), "FF0000".to_string()));
+    }
+
+    #[test]
+    fn test_concurrent_config_writes() {
+        let dir = std::env::temp_dir().join(format!("rustwled_config_writes_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("config.conf");
+        let versions: Vec<String> = (0..4).map(|n| format!("version = {}\n{}", n, "x".repeat(64 * 1024))).collect();
+        write_config_file(&path, &versions[0]).unwrap();
+
+        // Writers racing each other, and a reader that must only ever see one whole version
+        std::thread::scope(|scope| {
+            for contents in &versions {
+                scope.spawn(|| for _ in 0..20 {
+                    write_config_file(&path, contents).unwrap();
+                });
+            }
+            scope.spawn(|| for _ in 0..200 {
+                let read = read_config_file(&path).unwrap();
+                assert!(versions.contains(&read));
+            });
+        });
+
+        // No temp files left behind
+        let mut left: Vec<String> = std::fs::read_dir(&dir).unwrap()
+            .map(|entry| entry.unwrap().file_name().to_string_lossy().to_string())
+            .collect();
+        left.sort();
+        assert_eq!(left, vec!["config.conf", "config.lock"]);
+        let _ = std::fs::remove_dir_all(&dir);
     }
 }
@@ -0,0 +1,149 @@
+// Dashboard Module - Bandwidth mode on a 2D matrix: each monitored interface gets its own row band,
+// labeled on the left, with an RX bar over a TX bar filling to the right
+use crate::font::{self, GLYPH_HEIGHT};
+use crate::matrix::MatrixLayout;
+
+// Labels are drawn dimmer than the bars so the traffic stands out
+const LABEL_COLOR: [u8; 3] = [160, 160, 160];
+
+// Longest label drawn - keeps most of the width for the bars
+const MAX_LABEL_CHARS: usize = 3;
+
+/// Latest traffic on one monitored interface
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct InterfaceRate {
+    pub label: String,  // Short matrix label (see label())
+    pub rx_kbps: f64,
+    pub tx_kbps: f64,
+}
+
+/// Short label for an interface: the name if it fits, otherwise its first letter and trailing number
+/// (eth0 -> "E0", wlan1 -> "W1", enp3s0 -> "E0"), or its first three letters if it has no number
+pub fn label(name: &str) -> String {
+    let chars: Vec<char> = name.chars()
+        .filter(|c| c.is_ascii_alphanumeric())
+        .map(|c| c.to_ascii_uppercase())
+        .collect();
+    if chars.len() <= MAX_LABEL_CHARS {
+        return chars.into_iter().collect();
+    }
+
+    let digits = chars.iter().rev().take_while(|c| c.is_ascii_digit()).count().min(MAX_LABEL_CHARS - 1);
+    if digits == 0 {
+        chars[..MAX_LABEL_CHARS].iter().collect()
+    } else {
+        std::iter::once(chars[0]).chain(chars[chars.len() - digits..].iter().copied()).collect()
+    }
+}
+
+/// Draw one band per interface into `frame` - bar colors come from `rx_color`/`tx_color` at a position
+/// along the bar (0.0 = left end, 1.0 = full)
+pub fn render(
+    frame: &mut [u8],
+    layout: &MatrixLayout,
+    rates: &[InterfaceRate],
+    max_kbps: f64,
+    rx_color: impl Fn(f64) -> [u8; 3],
+    tx_color: impl Fn(f64) -> [u8; 3],
+) {
+    let (width, height) = (layout.width(), layout.height());
+    if rates.is_empty() || width == 0 || height == 0 {
+        return;
+    }
+
+    let mut set = |x: usize, y: usize, color: [u8; 3]| {
+        if let Some(led) = layout.index(x, y) {
+            if let Some(pixel) = frame.get_mut(led * 3..led * 3 + 3) {
+                pixel.copy_from_slice(&color);
+            }
+        }
+    };
+
+    // More interfaces than rows: the first `height` get a row each
+    let band_height = (height / rates.len()).max(1);
+    let label_width = rates.iter().map(|rate| font::text_width(&rate.label)).max().unwrap_or(0);
+    for (i, rate) in rates.iter().enumerate().take(height) {
+        let top = i * band_height;
+
+        // Labels only where they fit, leaving room for a bar
+        let show_label = label_width > 0 && band_height >= GLYPH_HEIGHT && width > label_width + 2;
+        let bar_start = if show_label { label_width + 1 } else { 0 };
+        if show_label {
+            let label_top = top + (band_height - GLYPH_HEIGHT) / 2;
+            for (x, y) in font::text_pixels(&rate.label) {
+                set(x, label_top + y, LABEL_COLOR);
+            }
+        }
+
+        let bar_width = width - bar_start;
+        let lit = |kbps: f64| {
+            let fraction = if max_kbps > 0.0 { (kbps / max_kbps).clamp(0.0, 1.0) } else { 0.0 };
+            (fraction * bar_width as f64).ceil() as usize
+        };
+        let (rx_lit, tx_lit) = (lit(rate.rx_kbps), lit(rate.tx_kbps));
+        let position = |x: usize| if bar_width > 1 { x as f64 / (bar_width - 1) as f64 } else { 0.0 };
+
+        if band_height == 1 {
+            // One row: RX fills from the left, TX from the right
+            for x in 0..tx_lit {
+                set(width - 1 - x, top, tx_color(position(x)));
+            }
+            for x in 0..rx_lit {
+                set(bar_start + x, top, rx_color(position(x)));
+            }
+        } else {
+            let rx_rows = band_height.div_ceil(2);
+            for y in top..top + band_height {
+                let (count, color): (usize, &dyn Fn(f64) -> [u8; 3]) = if y < top + rx_rows {
+                    (rx_lit, &rx_color)
+                } else {
+                    (tx_lit, &tx_color)
+                };
+                for x in 0..count {
+                    set(bar_start + x, y, color(position(x)));
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_label() {
+        assert_eq!(label("en0"), "EN0");
+        assert_eq!(label("eth0"), "E0");
+        assert_eq!(label("wlan12"), "W12");
+        assert_eq!(label("docker"), "DOC");
+        assert_eq!(label("br-lan"), "BRL");
+    }
+
+    #[test]
+    fn test_render_bands() {
+        // Two interfaces on a 12x10 matrix: 5-row bands with a label and bars
+        let layout = MatrixLayout::single(12, 10, false);
+        let mut frame = vec![0; layout.led_count() * 3];
+        let rates = vec![
+            InterfaceRate { label: "E0".to_string(), rx_kbps: 100.0, tx_kbps: 0.0 },
+            InterfaceRate { label: "E1".to_string(), rx_kbps: 0.0, tx_kbps: 50.0 },
+        ];
+        render(&mut frame, &layout, &rates, 100.0, |_| [255, 0, 0], |_| [0, 0, 255]);
+        let pixel = |x: usize, y: usize| {
+            let led = layout.index(x, y).unwrap();
+            [frame[led * 3], frame[led * 3 + 1], frame[led * 3 + 2]]
+        };
+
+        // First band: full RX bar over an empty TX bar, after the 7-pixel label and a gap
+        assert_eq!(pixel(0, 0), LABEL_COLOR);
+        assert_eq!(pixel(8, 0), [255, 0, 0]);
+        assert_eq!(pixel(11, 2), [255, 0, 0]);
+        assert_eq!(pixel(11, 3), [0, 0, 0]);
+
+        // Second band: half a TX bar in its bottom rows
+        assert_eq!(pixel(8, 5), [0, 0, 0]);
+        assert_eq!(pixel(8, 8), [0, 0, 255]);
+        assert_eq!(pixel(11, 8), [0, 0, 0]);
+    }
+}
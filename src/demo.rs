@@ -0,0 +1,243 @@
+// Demo Module - "demo" mode for showing off installations: the other modes take turns on a timer (bandwidth on
+// test data, live audio on a generated signal, geometry, sand, tron), each crossfading into the next
+use std::f64::consts::TAU;
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+use tokio::sync::broadcast;
+
+use crate::config::{self, BandwidthConfig, ConfigDelta};
+use crate::fade;
+use crate::signal_generator;
+use crate::system;
+
+/// Modes the demo can rotate through
+pub const DEMO_MODES: &[&str] = &["bandwidth", "live", "geometry", "sand", "tron"];
+
+/// audio_device name for the generated signal live mode plays in the demo
+pub const GENERATED_AUDIO: &str = "demo:generated";
+
+/// Sample rate of the generated signal (mono)
+pub const GENERATED_SAMPLE_RATE: f32 = signal_generator::SAMPLE_RATE;
+
+// How often the timer checks whether the current mode's turn is over
+const TICK: Duration = Duration::from_millis(250);
+
+// A handed-off frame not picked up by the next mode within this long is stale (e.g. tron, which sends on its own)
+const HANDOFF_TTL: Duration = Duration::from_secs(10);
+
+// Generated signal: a four-on-the-floor loop with a bass line that changes every bar
+const TEMPO_BPM: f64 = 120.0;
+const BASS_NOTES: [f64; 4] = [55.0, 43.65, 65.41, 49.0];  // A1, F1, C2, G1
+
+static ACTIVE: AtomicBool = AtomicBool::new(false);
+static STEP: AtomicUsize = AtomicUsize::new(0);  // Position in the rotation
+static SWITCHING: AtomicBool = AtomicBool::new(false);  // The running mode is ending because its turn is over
+static TURN_STARTED: Mutex<Option<Instant>> = Mutex::new(None);
+static INTERVAL_SECS: AtomicU64 = AtomicU64::new(0);  // f64 bits
+static CROSSFADE_MS: AtomicU64 = AtomicU64::new(0);  // f64 bits
+static HANDOFF: Mutex<Option<Handoff>> = Mutex::new(None);
+
+// Last frame of the previous mode, blended into the next one's first frames
+struct Handoff {
+    frame: Vec<u8>,
+    handed_off: Instant,
+    started: Option<Instant>,  // When the next mode sent its first frame
+}
+
+/// Take the demo timing from the config
+pub fn configure(config: &BandwidthConfig) {
+    INTERVAL_SECS.store(config.demo_interval_secs.to_bits(), Ordering::Relaxed);
+    CROSSFADE_MS.store(config.demo_crossfade_ms.to_bits(), Ordering::Relaxed);
+}
+
+/// Called by the mode loop before starting a mode - the demo's turn timer runs while mode = "demo"
+pub fn set_active(active: bool) {
+    ACTIVE.store(active, Ordering::Relaxed);
+    SWITCHING.store(false, Ordering::Relaxed);
+    let mut turn_started = TURN_STARTED.lock().unwrap();
+    match (active, turn_started.is_some()) {
+        (true, false) => *turn_started = Some(Instant::now()),
+        (false, _) => *turn_started = None,
+        _ => {}  // A mode restarted mid-turn (e.g. after a config edit) keeps the rest of its turn
+    }
+}
+
+/// Modes in the configured rotation, in order (all of them if none are configured)
+fn rotation(config: &BandwidthConfig) -> Vec<String> {
+    let modes: Vec<String> = config.demo_modes.split(',')
+        .map(|m| m.trim().to_lowercase())
+        .filter(|m| DEMO_MODES.contains(&m.as_str()))
+        .collect();
+    if modes.is_empty() {
+        DEMO_MODES.iter().map(|m| m.to_string()).collect()
+    } else {
+        modes
+    }
+}
+
+/// The config for the mode whose turn it is - bandwidth runs on test traffic and live audio on the generated signal
+pub fn turn_config(config: &BandwidthConfig) -> BandwidthConfig {
+    let modes = rotation(config);
+    let mut turn = config.clone();
+    turn.mode = modes[STEP.load(Ordering::Relaxed) % modes.len()].clone();
+    match turn.mode.as_str() {
+        "bandwidth" => {
+            turn.test_tx = true;
+            turn.test_rx = true;
+        }
+        "live" => turn.audio.device = GENERATED_AUDIO.to_string(),
+        _ => {}
+    }
+    turn
+}
+
+/// Whether the running mode is ending to hand over to the next one in the demo
+pub fn switching() -> bool {
+    SWITCHING.load(Ordering::Relaxed)
+}
+
+/// Keep the last frame of a mode whose turn is over, for the next mode to crossfade from
+pub fn hand_off(frame: Vec<u8>) {
+    if frame.is_empty() {
+        return;
+    }
+    *HANDOFF.lock().unwrap() = Some(Handoff { frame, handed_off: Instant::now(), started: None });
+}
+
+/// Whether a new mode will crossfade in (so it shouldn't also fade in from black)
+pub fn crossfading() -> bool {
+    matches!(HANDOFF.lock().unwrap().as_ref(), Some(handoff) if handoff.started.is_some() || handoff.handed_off.elapsed() < HANDOFF_TTL)
+}
+
+/// Blend a finished output frame with the previous mode's last frame while the crossfade lasts
+pub fn crossfade(frame: &[u8]) -> Option<Vec<u8>> {
+    let mut guard = HANDOFF.lock().unwrap();
+    let handoff = guard.as_mut()?;
+    if handoff.started.is_none() && handoff.handed_off.elapsed() >= HANDOFF_TTL {
+        *guard = None;
+        return None;
+    }
+    let started = *handoff.started.get_or_insert_with(Instant::now);
+    let duration = Duration::from_secs_f64(f64::from_bits(CROSSFADE_MS.load(Ordering::Relaxed)).max(0.0) / 1000.0);
+    let progress = fade::ramp(started.elapsed(), duration) as f32;
+    if progress >= 1.0 {
+        *guard = None;
+        return None;
+    }
+    Some(blend(&handoff.frame, frame, progress))
+}
+
+// Mix two frames (0.0 = all `from`, 1.0 = all `to`) - a shorter `from` fades in from black where it runs out
+fn blend(from: &[u8], to: &[u8], progress: f32) -> Vec<u8> {
+    to.iter().enumerate()
+        .map(|(i, &to)| {
+            let from = from.get(i).copied().unwrap_or(0) as f32;
+            (from + (to as f32 - from) * progress).round() as u8
+        })
+        .collect()
+}
+
+/// End each mode's turn when the interval is up - the mode exits as for a restart and the loop starts the next one
+pub fn spawn_timer(config_change_tx: broadcast::Sender<ConfigDelta>) {
+    thread::spawn(move || loop {
+        thread::sleep(TICK);
+        if !ACTIVE.load(Ordering::Relaxed) || SWITCHING.load(Ordering::Relaxed) {
+            continue;
+        }
+        let interval = Duration::from_secs_f64(f64::from_bits(INTERVAL_SECS.load(Ordering::Relaxed)).max(1.0));
+        let mut turn_started = TURN_STARTED.lock().unwrap();
+        if matches!(*turn_started, Some(started) if started.elapsed() >= interval) {
+            *turn_started = Some(Instant::now());
+            STEP.fetch_add(1, Ordering::Relaxed);
+            SWITCHING.store(true, Ordering::Relaxed);
+            system::request_restart();
+            // Wake the running mode - it checks for a restart alongside config changes
+            let _ = config_change_tx.send(ConfigDelta::Wake);
+        }
+    });
+}
+
+/// Keep the demo timing in step with config changes (web UI, API, or file edits)
+pub fn spawn_watcher(config_change_tx: broadcast::Sender<ConfigDelta>) {
+    config::spawn_config_listener(&config_change_tx, &["demo_interval_secs", "demo_crossfade_ms"], configure);
+}
+
+/// Feed live mode's sample buffer with the generated signal in real time, in place of a capture device
+/// Stops once live mode drops the buffer
+pub fn spawn_generated_audio(buffer: &Arc<Mutex<Vec<f32>>>, samples_received: &Arc<AtomicUsize>) {
+    let mut n = 0u64;
+    let mut noise = 0x2545_F491u32;
+    signal_generator::spawn_feed(buffer, samples_received, move || {
+        let sample = generated_sample(n as f64 / GENERATED_SAMPLE_RATE as f64, &mut noise);
+        n += 1;
+        sample
+    });
+}
+
+// One sample of the generated loop at `t` seconds: kick on every beat, hi-hat between them, bass and a chord pad
+fn generated_sample(t: f64, noise: &mut u32) -> f32 {
+    let beat_len = 60.0 / TEMPO_BPM;
+    let beat = t / beat_len;
+    let since_beat = beat.fract() * beat_len;
+    let since_offbeat = (beat + 0.5).fract() * beat_len;
+    let root = BASS_NOTES[(beat / 4.0) as usize % BASS_NOTES.len()];
+
+    // Kick: a sine sweeping down from 150Hz to 50Hz, decaying fast
+    let kick_phase = 50.0 * since_beat + 100.0 * (1.0 - (-30.0 * since_beat).exp()) / 30.0;
+    let kick = (TAU * kick_phase).sin() * (-12.0 * since_beat).exp();
+
+    // Hi-hat: a burst of white noise (xorshift)
+    *noise ^= *noise << 13;
+    *noise ^= *noise >> 17;
+    *noise ^= *noise << 5;
+    let white = *noise as f64 / u32::MAX as f64 * 2.0 - 1.0;
+    let hat = white * (-60.0 * since_offbeat).exp();
+
+    let bass = (TAU * root * t).sin();
+    // Major chord two octaves up, swelling slowly
+    let pad = [4.0, 5.0, 6.0].iter().map(|ratio| (TAU * root * ratio * t).sin()).sum::<f64>() / 3.0
+        * (0.6 + 0.4 * (TAU * t / 8.0).sin());
+
+    (0.6 * kick + 0.15 * hat + 0.3 * bass + 0.15 * pad).clamp(-1.0, 1.0) as f32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_turn_config() {
+        let mut config = BandwidthConfig::default();
+        config.demo_modes = "sand, live, bogus".to_string();
+        assert_eq!(rotation(&config), vec!["sand", "live"]);
+
+        let turn = turn_config(&config);
+        assert!(rotation(&config).contains(&turn.mode));
+        if turn.mode == "live" {
+            assert_eq!(turn.audio.device, GENERATED_AUDIO);
+        }
+
+        config.demo_modes = String::new();
+        assert_eq!(rotation(&config).len(), DEMO_MODES.len());
+    }
+
+    #[test]
+    fn test_blend() {
+        assert_eq!(blend(&[200, 0, 100], &[0, 200, 100], 0.5), vec![100, 100, 100]);
+        assert_eq!(blend(&[200, 0, 100], &[0, 200, 100], 1.0), vec![0, 200, 100]);
+        // A shorter previous frame counts as black past its end
+        assert_eq!(blend(&[200], &[0, 100], 0.25), vec![150, 25]);
+    }
+
+    #[test]
+    fn test_generated_sample() {
+        let mut noise = 1;
+        let samples: Vec<f32> = (0..4410).map(|n| generated_sample(n as f64 / 44100.0, &mut noise)).collect();
+        assert!(samples.iter().all(|s| (-1.0..=1.0).contains(s)));
+        // The kick makes the start of a beat loud
+        let peak = samples.iter().fold(0.0f32, |peak, s| peak.max(s.abs()));
+        assert!(peak > 0.5);
+    }
+}
@@ -0,0 +1,305 @@
+// Discovery Module - Finds WLED controllers and other DDP receivers on the LAN: browses mDNS for
+// _wled._tcp and _ddp._udp, probes the local /24 for WLED's JSON API, and adds what it finds to wled_devices
+use anyhow::{anyhow, Result};
+use std::net::{IpAddr, Ipv4Addr, SocketAddr, UdpSocket};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::cli;
+use crate::config::{BandwidthConfig, WLEDDeviceConfig};
+
+const MDNS_ADDR: &str = "224.0.0.251:5353";
+
+// WLED advertises _wled._tcp; other DDP receivers (ESPixelStick, FPP, ...) advertise _ddp._udp
+const SERVICES: &[&str] = &["_wled._tcp.local", "_ddp._udp.local"];
+
+/// How long a scan waits on mDNS answers and on each probed address
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// A device that answered on WLED's JSON API or over mDNS
+#[derive(Debug, Clone)]
+pub struct FoundDevice {
+    pub ip: Ipv4Addr,
+    pub name: String,
+    pub led_count: Option<usize>,  // None = a DDP receiver without WLED's JSON API - its size isn't known
+    pub version: String,
+}
+
+// Local IPv4 address of the default route (connecting a UDP socket sends nothing)
+fn local_ipv4() -> Result<Ipv4Addr> {
+    let socket = UdpSocket::bind("0.0.0.0:0")?;
+    socket.connect("8.8.8.8:80")?;
+    match socket.local_addr()?.ip() {
+        IpAddr::V4(ip) if !ip.is_loopback() => Ok(ip),
+        _ => Err(anyhow!("Could not determine the local IPv4 address")),
+    }
+}
+
+/// Ask an address for WLED's /json/info - None if nothing WLED-like answers within the timeout
+pub fn probe_wled(ip: Ipv4Addr, timeout: Duration) -> Option<FoundDevice> {
+    wled_device(ip, &cli::fetch_wled_info(&ip.to_string(), timeout)?)
+}
+
+// The device described by a /json/info answer - None if it doesn't report an LED count like WLED does
+fn wled_device(ip: Ipv4Addr, info: &serde_json::Value) -> Option<FoundDevice> {
+    Some(FoundDevice {
+        ip,
+        name: info["name"].as_str().unwrap_or("WLED").to_string(),
+        led_count: Some(info["leds"]["count"].as_u64()? as usize),
+        version: info["ver"].as_str().unwrap_or("?").to_string(),
+    })
+}
+
+// PTR query for each service with the unicast-response bit set, so answers come straight
+// back to our socket and we don't need to join the multicast group (or share port 5353)
+fn mdns_query() -> Vec<u8> {
+    let mut packet = vec![0, 0, 0, 0, 0, SERVICES.len() as u8, 0, 0, 0, 0, 0, 0];  // id 0, no flags, one question per service
+    for service in SERVICES {
+        for label in service.split('.') {
+            packet.push(label.len() as u8);
+            packet.extend_from_slice(label.as_bytes());
+        }
+        packet.push(0);
+        packet.extend_from_slice(&[0x00, 0x0c, 0x80, 0x01]);  // type PTR, class IN with QU bit
+    }
+    packet
+}
+
+// Name at `pos` in a DNS packet (following compression pointers) and the position just past it in the record
+fn read_name(packet: &[u8], mut pos: usize) -> Option<(String, usize)> {
+    let mut labels = Vec::new();
+    let mut end = None;  // Set once a pointer has been followed - the record continues after the pointer
+    for _ in 0..128 {  // Bounds pointer loops in malformed packets
+        let len = *packet.get(pos)? as usize;
+        if len & 0xc0 == 0xc0 {
+            end.get_or_insert(pos + 2);
+            pos = ((len & 0x3f) << 8) | *packet.get(pos + 1)? as usize;
+        } else if len == 0 {
+            return Some((labels.join("."), end.unwrap_or(pos + 1)));
+        } else {
+            labels.push(String::from_utf8_lossy(packet.get(pos + 1..pos + 1 + len)?).to_string());
+            pos += 1 + len;
+        }
+    }
+    None
+}
+
+// Whether an mDNS response carries a PTR record for one of the services
+fn answers_service(packet: &[u8]) -> bool {
+    if packet.len() < 12 || packet[2] & 0x80 == 0 {
+        return false;
+    }
+    let count = |at: usize| u16::from_be_bytes([packet[at], packet[at + 1]]) as usize;
+
+    // Skip any questions echoed back (name, type, class)
+    let mut pos = 12;
+    for _ in 0..count(4) {
+        let Some((_, next)) = read_name(packet, pos) else { return false };
+        pos = next + 4;
+    }
+
+    // Answer, authority and additional records: name, type, class, TTL, data length, data
+    for _ in 0..count(6) + count(8) + count(10) {
+        let Some((name, next)) = read_name(packet, pos) else { return false };
+        let Some(header) = packet.get(next..next + 10) else { return false };
+        let record_type = u16::from_be_bytes([header[0], header[1]]);
+        if record_type == 12 && SERVICES.iter().any(|service| service.eq_ignore_ascii_case(&name)) {
+            return true;
+        }
+        pos = next + 10 + u16::from_be_bytes([header[8], header[9]]) as usize;
+    }
+    false
+}
+
+/// Addresses that answered an mDNS query for WLED or DDP within the timeout
+pub fn mdns_scan(timeout: Duration) -> Vec<Ipv4Addr> {
+    let mut found = Vec::new();
+    let Ok(socket) = UdpSocket::bind("0.0.0.0:0") else {
+        return found;
+    };
+    if socket.send_to(&mdns_query(), MDNS_ADDR).is_err() {
+        return found;
+    }
+
+    let deadline = Instant::now() + timeout;
+    let mut buf = [0u8; 1500];
+    while let Some(remaining) = deadline.checked_duration_since(Instant::now()) {
+        if remaining.is_zero() || socket.set_read_timeout(Some(remaining)).is_err() {
+            break;
+        }
+        match socket.recv_from(&mut buf) {
+            // Only responders naming one of the services count - the sender is the device
+            Ok((len, SocketAddr::V4(addr))) if answers_service(&buf[..len]) => {
+                if !found.contains(addr.ip()) {
+                    found.push(*addr.ip());
+                }
+            }
+            Ok(_) => {}
+            Err(_) => break,
+        }
+    }
+    found
+}
+
+/// Find devices via mDNS and by probing the local /24, sorted by address
+pub fn scan(timeout: Duration) -> Vec<FoundDevice> {
+    let announced = mdns_scan(timeout);
+
+    // Devices found over mDNS may be on another subnet, so they're probed as well - without a default
+    // route (e.g. an offline LAN) the local /24 can't be found and only they are
+    let mut addresses = announced.clone();
+    if let Ok(local) = local_ipv4() {
+        let [a, b, c, _] = local.octets();
+        for ip in (1..=254u8).map(|host| Ipv4Addr::new(a, b, c, host)) {
+            if ip != local && !addresses.contains(&ip) {
+                addresses.push(ip);
+            }
+        }
+    }
+
+    let handles: Vec<_> = addresses
+        .into_iter()
+        .map(|ip| thread::spawn(move || probe_wled(ip, timeout)))
+        .collect();
+    let mut found: Vec<FoundDevice> = handles.into_iter().filter_map(|h| h.join().ok().flatten()).collect();
+
+    // Announced devices without a JSON API still take DDP - they're listed, just without a size
+    for ip in announced {
+        if !found.iter().any(|d| d.ip == ip) {
+            found.push(FoundDevice { ip, name: "DDP device".to_string(), led_count: None, version: "-".to_string() });
+        }
+    }
+
+    found.sort_by_key(|d| d.ip);
+    found
+}
+
+/// Whether a found device is already in wled_devices
+pub fn is_configured(config: &BandwidthConfig, device: &FoundDevice) -> bool {
+    config.wled_devices.iter().any(|d| d.ip == device.ip.to_string())
+}
+
+/// Append found devices that aren't configured yet after the last configured LED range, returning how many
+/// were added - devices of unknown size are left for the user to add with their LED count
+pub fn add_to_config(config: &mut BandwidthConfig, found: &[FoundDevice]) -> usize {
+    let mut next_offset = config.wled_devices.iter().map(|d| d.led_offset + d.led_count).max().unwrap_or(0);
+    let mut added = 0;
+    for device in found {
+        let Some(led_count) = device.led_count else { continue };
+        if is_configured(config, device) {
+            continue;
+        }
+        config.wled_devices.push(WLEDDeviceConfig {
+            ip: device.ip.to_string(),
+            led_offset: next_offset,
+            led_count,
+            enabled: true,
+            max_fps: 0.0,
+            led_ma: 55.0,
+            psu_amps: 0.0,
+            backup_for: String::new(),
+            reversed: false,
+            mirrored: false,
+            segment: -1,
+            protocol: "ddp".to_string(),
+            universe: 1,
+            start_channel: 1,
+            net: 0,
+            subnet: 0,
+        });
+        next_offset += led_count;
+        added += 1;
+    }
+    config.total_leds = config.total_leds.max(next_offset);
+    added
+}
+
+/// Print found devices as a table, marking the ones already configured
+pub fn print_table(config: &BandwidthConfig, found: &[FoundDevice]) {
+    println!("{:<16} {:<24} {:>6}  {:<10}", "IP", "NAME", "LEDS", "VERSION");
+    for device in found {
+        let marker = if is_configured(config, device) { " (configured)" } else { "" };
+        let leds = device.led_count.map(|n| n.to_string()).unwrap_or_else(|| "?".to_string());
+        println!("{:<16} {:<24} {:>6}  {:<10}{}", device.ip, device.name, leds, device.version, marker);
+    }
+    println!("{} device(s) found", found.len());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mdns_query() {
+        let packet = mdns_query();
+        assert_eq!(&packet[4..6], &[0, 2]);  // Two questions
+        let wled = b"\x05_wled\x04_tcp\x05local\x00\x00\x0c\x80\x01";
+        let ddp = b"\x04_ddp\x04_udp\x05local\x00\x00\x0c\x80\x01";
+        assert_eq!(&packet[12..12 + wled.len()], wled);
+        assert_eq!(&packet[12 + wled.len()..], ddp);
+    }
+
+    #[test]
+    fn test_answers_service() {
+        // Response with one PTR answer: <name> PTR <instance>, the instance name compressed against the answer
+        let response = |service: &[u8]| {
+            let mut packet = vec![0, 0, 0x84, 0, 0, 0, 0, 1, 0, 0, 0, 0];
+            packet.extend_from_slice(service);
+            packet.extend_from_slice(&[0x00, 0x0c, 0x00, 0x01, 0, 0, 0x11, 0x94, 0, 6]);
+            packet.extend_from_slice(&[3, b'l', b'e', b'd', 0xc0, 12]);
+            packet
+        };
+        assert!(answers_service(&response(b"\x05_wled\x04_tcp\x05local\x00")));
+        assert!(answers_service(&response(b"\x04_ddp\x04_udp\x05local\x00")));
+        assert!(!answers_service(&response(b"\x05_http\x04_tcp\x05local\x00")));
+
+        // Queries, truncated packets and pointer loops don't count
+        assert!(!answers_service(&mdns_query()));
+        let mut truncated = response(b"\x05_wled\x04_tcp\x05local\x00");
+        truncated.truncate(20);
+        assert!(!answers_service(&truncated));
+        assert!(!answers_service(&[0, 0, 0x84, 0, 0, 0, 0, 1, 0, 0, 0, 0, 0xc0, 12]));
+    }
+
+    #[test]
+    fn test_wled_device() {
+        let ip = Ipv4Addr::new(10, 0, 0, 7);
+        let info = serde_json::json!({ "name": "Desk", "ver": "0.14.4", "leds": { "count": 144, "fps": 42 } });
+        let device = wled_device(ip, &info).unwrap();
+        assert_eq!((device.ip, device.name.as_str(), device.led_count, device.version.as_str()), (ip, "Desk", Some(144), "0.14.4"));
+
+        // Name and version are optional, the LED count isn't
+        let device = wled_device(ip, &serde_json::json!({ "leds": { "count": 30 } })).unwrap();
+        assert_eq!((device.name.as_str(), device.version.as_str()), ("WLED", "?"));
+        assert!(wled_device(ip, &serde_json::json!({ "name": "Some other web server" })).is_none());
+    }
+
+    #[test]
+    fn test_add_to_config() {
+        let mut config = BandwidthConfig::default();
+        config.wled_devices.truncate(1);
+        config.wled_devices[0].ip = "10.0.0.2".to_string();
+        config.wled_devices[0].led_offset = 0;
+        config.wled_devices[0].led_count = 100;
+
+        let device = |ip: [u8; 4], led_count: Option<usize>| FoundDevice {
+            ip: Ipv4Addr::from(ip),
+            name: "WLED".to_string(),
+            led_count,
+            version: "0.14.0".to_string(),
+        };
+        let found = [
+            device([10, 0, 0, 2], Some(100)),  // Already configured
+            device([10, 0, 0, 3], Some(60)),
+            device([10, 0, 0, 4], None),  // Size unknown
+            device([10, 0, 0, 5], Some(40)),
+        ];
+        assert_eq!(add_to_config(&mut config, &found), 2);
+        let ranges: Vec<_> = config.wled_devices.iter().map(|d| (d.ip.as_str(), d.led_offset, d.led_count)).collect();
+        assert_eq!(ranges, [("10.0.0.2", 0, 100), ("10.0.0.3", 100, 60), ("10.0.0.5", 160, 40)]);
+        assert!(config.total_leds >= 200);
+
+        // Running it again adds nothing
+        assert_eq!(add_to_config(&mut config, &found), 0);
+    }
+}
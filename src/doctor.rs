@@ -0,0 +1,276 @@
+// Doctor Module - Check devices, audio, MIDI, SSH and matrix settings and print a pass/fail report
+use anyhow::{anyhow, Result};
+use std::process::Stdio;
+use std::time::Duration;
+
+use crate::audio;
+use crate::cli;
+use crate::config::BandwidthConfig;
+use crate::midi;
+use crate::multi_device::MultiDeviceConfig;
+use crate::virtual_device;
+
+// How long each device / SSH host gets to answer
+const CHECK_TIMEOUT: Duration = Duration::from_secs(3);
+
+#[derive(Clone, Copy, PartialEq)]
+enum Status {
+    Pass,
+    Warn,
+    Fail,
+}
+
+struct Report {
+    failures: usize,
+    warnings: usize,
+}
+
+impl Report {
+    fn check(&mut self, status: Status, message: impl AsRef<str>) {
+        let label = match status {
+            Status::Pass => "PASS",
+            Status::Warn => {
+                self.warnings += 1;
+                "WARN"
+            }
+            Status::Fail => {
+                self.failures += 1;
+                "FAIL"
+            }
+        };
+        println!("  [{}] {}", label, message.as_ref());
+    }
+}
+
+fn check_devices(config: &BandwidthConfig, report: &mut Report) {
+    println!("WLED devices");
+    if config.wled_devices.is_empty() {
+        report.check(Status::Fail, "No devices configured");
+        return;
+    }
+
+    if let Err(e) = MultiDeviceConfig::from_config(config).validate() {
+        report.check(Status::Fail, format!("Device layout: {}", e));
+    }
+
+    // Probe all devices at once so a few offline ones don't add up
+    let handles: Vec<_> = config.wled_devices.iter()
+        .map(|d| {
+            let ip = d.ip.clone();
+            std::thread::spawn(move || cli::fetch_wled_info(&ip, CHECK_TIMEOUT))
+        })
+        .collect();
+
+    for (device, handle) in config.wled_devices.iter().zip(handles) {
+        if !device.enabled {
+            report.check(Status::Pass, format!("{} is disabled - skipped", device.ip));
+            continue;
+        }
+        if virtual_device::is_virtual(&device.ip) {
+            report.check(Status::Pass, format!("{} is a virtual device (run \"rustwled virtual\" to see it) - skipped", device.ip));
+            continue;
+        }
+
+        match handle.join().ok().flatten() {
+            Some(info) => {
+                let name = info["name"].as_str().unwrap_or("WLED");
+                match info["leds"]["count"].as_u64().map(|c| c as usize) {
+                    Some(count) if count < device.led_count => report.check(
+                        Status::Warn,
+                        format!("{} ({}) reachable, but has {} LEDs and {} are configured", device.ip, name, count, device.led_count),
+                    ),
+                    Some(count) => report.check(Status::Pass, format!("{} ({}) reachable, {} LEDs", device.ip, name, count)),
+                    None => report.check(Status::Warn, format!("{} answered but doesn't look like WLED", device.ip)),
+                }
+            }
+            None => report.check(Status::Fail, format!("{} not reachable on port 80", device.ip)),
+        }
+    }
+}
+
+fn check_audio(config: &BandwidthConfig, report: &mut Report) {
+    println!("Audio");
+    match audio::list_audio_devices() {
+        Ok(devices) => report.check(Status::Pass, format!("{} audio device(s) available", devices.len())),
+        Err(e) => {
+            let status = if config.mode == "live" { Status::Fail } else { Status::Warn };
+            report.check(status, format!("No audio devices: {}", e));
+            return;
+        }
+    }
+
+    if config.audio.device.is_empty() {
+        report.check(Status::Pass, "No audio device configured - live mode will prompt for one");
+    } else {
+        match audio::find_audio_device(&config.audio.device) {
+            Ok(_) => report.check(Status::Pass, format!("Configured device \"{}\" found", config.audio.device)),
+            Err(_) => {
+                let status = if config.mode == "live" { Status::Fail } else { Status::Warn };
+                report.check(status, format!("Configured device \"{}\" not found", config.audio.device));
+            }
+        }
+    }
+}
+
+fn check_midi(config: &BandwidthConfig, report: &mut Report) {
+    println!("MIDI");
+    let ports = match midi::list_midi_ports() {
+        Ok(ports) => ports,
+        Err(e) => {
+            report.check(Status::Warn, format!("MIDI unavailable: {}", e));
+            return;
+        }
+    };
+
+    // Same case-insensitive substring match the MIDI mode uses to pick a port
+    let wanted = config.midi.device.to_lowercase();
+    let status = if config.mode == "midi" { Status::Fail } else { Status::Warn };
+    if ports.is_empty() {
+        report.check(status, "No MIDI input ports found");
+    } else if wanted.is_empty() || ports.iter().any(|p| p.to_lowercase().contains(&wanted)) {
+        report.check(Status::Pass, format!("{} MIDI port(s): {}", ports.len(), ports.join(", ")));
+    } else {
+        report.check(status, format!("Port \"{}\" not found (available: {})", config.midi.device, ports.join(", ")));
+    }
+}
+
+fn check_ssh(config: &BandwidthConfig, report: &mut Report) {
+    println!("SSH");
+    if config.ssh_host.is_empty() {
+        report.check(Status::Pass, "Monitoring locally - no SSH host configured");
+        return;
+    }
+
+    let target = if config.ssh_user.is_empty() {
+        config.ssh_host.clone()
+    } else {
+        format!("{}@{}", config.ssh_user, config.ssh_host)
+    };
+
+    // BatchMode fails instead of prompting, since the doctor runs unattended
+    let result = std::process::Command::new("ssh")
+        .args(["-o", "BatchMode=yes", "-o", &format!("ConnectTimeout={}", CHECK_TIMEOUT.as_secs())])
+        .arg(&target)
+        .arg("true")
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status();
+
+    match result {
+        Ok(status) if status.success() => report.check(Status::Pass, format!("{} reachable", target)),
+        Ok(_) => report.check(Status::Fail, format!("{} not reachable (or needs a password - use key auth)", target)),
+        Err(e) => report.check(Status::Fail, format!("Could not run ssh: {}", e)),
+    }
+}
+
+fn check_matrix(config: &BandwidthConfig, report: &mut Report) {
+    println!("Matrix dimensions");
+    let grids = [
+        ("tron", "tron", config.tron.width, config.tron.height),
+        ("sand", "sand", config.sand.grid_width, config.sand.grid_height),
+        ("geometry", "geometry", config.geometry.grid_width, config.geometry.grid_height),
+        ("webcam", "webcam", config.webcam.frame_width, config.webcam.frame_height),
+        ("relay", "relay", config.relay_frame_width, config.relay_frame_height),
+        ("matrix_2d", "live", if config.matrix.enabled { config.matrix.width } else { 0 }, config.matrix.height),
+    ];
+
+    for (name, mode, width, height) in grids {
+        let cells = width * height;
+        if cells == 0 {
+            continue;
+        }
+        // Only the active mode's grid matters right now - the others are informational
+        let active = config.mode == mode;
+        if cells > config.total_leds {
+            let status = if active { Status::Fail } else { Status::Warn };
+            report.check(status, format!("{} grid {}x{} ({} LEDs) is larger than total_leds ({})", name, width, height, cells, config.total_leds));
+        } else if cells < config.total_leds && active {
+            report.check(Status::Warn, format!("{} grid {}x{} ({} LEDs) leaves {} LEDs unused", name, width, height, cells, config.total_leds - cells));
+        } else if active {
+            report.check(Status::Pass, format!("{} grid {}x{} matches total_leds", name, width, height));
+        }
+    }
+}
+
+/// Run every check and print a report - returns Err if anything failed
+pub fn run() -> Result<()> {
+    let config = BandwidthConfig::load()?;
+    println!("Checking config for mode \"{}\" ({} LEDs)\n", config.mode, config.total_leds);
+
+    let mut report = Report { failures: 0, warnings: 0 };
+    check_devices(&config, &mut report);
+    check_audio(&config, &mut report);
+    check_midi(&config, &mut report);
+    check_ssh(&config, &mut report);
+    check_matrix(&config, &mut report);
+
+    println!("\n{} failure(s), {} warning(s)", report.failures, report.warnings);
+    if report.failures > 0 {
+        Err(anyhow!("{} check(s) failed", report.failures))
+    } else {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn report() -> Report {
+        Report { failures: 0, warnings: 0 }
+    }
+
+    #[test]
+    fn test_check_matrix() {
+        let mut config = BandwidthConfig::default();
+        config.mode = "tron".to_string();
+        config.total_leds = 100;
+        config.tron.width = 10;
+        config.tron.height = 10;
+        config.sand.grid_width = 0;
+        config.geometry.grid_width = 0;
+        config.webcam.frame_width = 0;
+        config.relay_frame_width = 0;
+        config.matrix.enabled = false;
+
+        // The active mode's grid fits exactly
+        let mut matches = report();
+        check_matrix(&config, &mut matches);
+        assert_eq!((matches.failures, matches.warnings), (0, 0));
+
+        // Too big is a failure for the active mode, a warning for the others
+        config.sand.grid_width = 20;
+        config.sand.grid_height = 20;
+        config.tron.height = 20;
+        let mut too_big = report();
+        check_matrix(&config, &mut too_big);
+        assert_eq!((too_big.failures, too_big.warnings), (1, 1));
+    }
+
+    #[test]
+    fn test_check_devices_offline() {
+        let mut config = BandwidthConfig::default();
+        config.wled_devices.truncate(1);
+        config.wled_devices[0].ip = "virtual:4048".to_string();
+
+        // Virtual devices aren't probed
+        let mut skipped = report();
+        check_devices(&config, &mut skipped);
+        assert_eq!((skipped.failures, skipped.warnings), (0, 0));
+
+        // Overlapping ranges fail the layout check
+        let mut overlapping = config.wled_devices[0].clone();
+        overlapping.ip = "virtual:4049".to_string();
+        overlapping.led_offset = 1;
+        config.wled_devices.push(overlapping);
+        let mut failed = report();
+        check_devices(&config, &mut failed);
+        assert_eq!(failed.failures, 1);
+
+        config.wled_devices.clear();
+        let mut none = report();
+        check_devices(&config, &mut none);
+        assert_eq!(none.failures, 1);
+    }
+}
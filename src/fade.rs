@@ -0,0 +1,55 @@
+// Fade Module - Soft start/stop for every mode: output ramps up when a mode starts and fades to black when it ends
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+use tokio::sync::broadcast;
+
+use crate::config::{self, BandwidthConfig, ConfigDelta};
+
+// Fade durations in milliseconds (f64 bits) - read by the send path when a mode's output starts and stops
+static FADE_IN_MS: AtomicU64 = AtomicU64::new(0);
+static FADE_OUT_MS: AtomicU64 = AtomicU64::new(0);
+
+/// Take the fade durations from the config
+pub fn configure(config: &BandwidthConfig) {
+    FADE_IN_MS.store(config.fade_in_ms.to_bits(), Ordering::Relaxed);
+    FADE_OUT_MS.store(config.fade_out_ms.to_bits(), Ordering::Relaxed);
+}
+
+/// How long output takes to reach full brightness when a mode starts
+pub fn fade_in() -> Duration {
+    Duration::from_secs_f64(f64::from_bits(FADE_IN_MS.load(Ordering::Relaxed)).max(0.0) / 1000.0)
+}
+
+/// How long the last frame takes to fade to black when a mode ends
+pub fn fade_out() -> Duration {
+    Duration::from_secs_f64(f64::from_bits(FADE_OUT_MS.load(Ordering::Relaxed)).max(0.0) / 1000.0)
+}
+
+/// Progress through a fade of `duration` after `elapsed` (0.0 at the start, 1.0 once finished)
+pub fn ramp(elapsed: Duration, duration: Duration) -> f64 {
+    if duration.is_zero() {
+        return 1.0;
+    }
+    (elapsed.as_secs_f64() / duration.as_secs_f64()).min(1.0)
+}
+
+/// Keep the fade durations in step with config changes (web UI, API, or file edits)
+pub fn spawn_watcher(config_change_tx: broadcast::Sender<ConfigDelta>) {
+    config::spawn_config_listener(&config_change_tx, &["fade_in_ms", "fade_out_ms"], configure);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ramp() {
+        let second = Duration::from_secs(1);
+        assert_eq!(ramp(Duration::ZERO, second), 0.0);
+        assert_eq!(ramp(Duration::from_millis(250), second), 0.25);
+        assert_eq!(ramp(Duration::from_secs(3), second), 1.0);
+
+        // No fade configured - straight to full
+        assert_eq!(ramp(Duration::ZERO, Duration::ZERO), 1.0);
+    }
+}
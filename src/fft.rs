@@ -0,0 +1,115 @@
+// FFT Module - Analysis settings shared by the audio modes (window function, hop between windows, constant-Q bands)
+use std::f32::consts::PI;
+
+/// Window applied to each block of samples before the FFT
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum WindowFunction {
+    Hann,  // Good all-rounder, low leakage
+    Hamming,  // Slightly sharper peaks, higher far-off leakage
+    Blackman,  // Lowest leakage, widest peaks
+}
+
+impl WindowFunction {
+    pub fn from_name(name: &str) -> Self {
+        match name {
+            "hamming" => WindowFunction::Hamming,
+            "blackman" => WindowFunction::Blackman,
+            _ => WindowFunction::Hann,
+        }
+    }
+
+    /// Window weight for sample `i` of an `n` sample block
+    pub fn coefficient(&self, i: usize, n: usize) -> f32 {
+        if n < 2 {
+            return 1.0;
+        }
+        let phase = 2.0 * PI * i as f32 / (n - 1) as f32;
+        match self {
+            WindowFunction::Hann => 0.5 * (1.0 - phase.cos()),
+            WindowFunction::Hamming => 0.54 - 0.46 * phase.cos(),
+            WindowFunction::Blackman => 0.42 - 0.5 * phase.cos() + 0.08 * (2.0 * phase).cos(),
+        }
+    }
+}
+
+/// Samples (per channel) between the starts of consecutive analysis windows
+pub fn hop_samples(fft_size: usize, overlap: f64) -> usize {
+    ((fft_size as f64 * (1.0 - overlap.clamp(0.0, 0.95))).round() as usize).max(1)
+}
+
+/// Constant-Q band for spectrum position `position` as (low, center, high) in Hz - positions step up
+/// by 1/`per_semitone` of a semitone from `min_freq`, each band spanning the gap to its neighbours
+pub fn cqt_band(position: usize, min_freq: f32, per_semitone: f32) -> (f32, f32, f32) {
+    let steps_per_octave = 12.0 * per_semitone.max(0.01);
+    let center = min_freq * 2f32.powf(position as f32 / steps_per_octave);
+    let half_step = 2f32.powf(0.5 / steps_per_octave);
+    (center / half_step, center, center * half_step)
+}
+
+/// Magnitude of a constant-Q band from FFT bin magnitudes (bin i centered at i * bin_hz)
+/// Bands narrower than a bin interpolate at their center; wider bands take their loudest bin
+pub fn cqt_magnitude(bins: &[f32], bin_hz: f32, position: usize, min_freq: f32, per_semitone: f32) -> f32 {
+    let (low, center, high) = cqt_band(position, min_freq, per_semitone);
+    let last = match bins.len().checked_sub(1) {
+        Some(last) if center <= last as f32 * bin_hz => last,
+        _ => return 0.0,  // Above Nyquist
+    };
+
+    let (low_bin, high_bin) = ((low / bin_hz).ceil() as usize, (high / bin_hz).floor() as usize);
+    if high_bin > low_bin {
+        return bins[low_bin..=high_bin.min(last)].iter().fold(0.0_f32, |max, &v| max.max(v));
+    }
+    let pos = center / bin_hz;
+    let below = (pos.floor() as usize).min(last);
+    let above = (below + 1).min(last);
+    let t = pos - below as f32;
+    bins[below] * (1.0 - t) + bins[above] * t
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_window_shapes() {
+        for window in [WindowFunction::Hann, WindowFunction::Hamming, WindowFunction::Blackman] {
+            // Symmetric, peaking at 1.0 in the middle
+            assert!((window.coefficient(0, 9) - window.coefficient(8, 9)).abs() < 1e-6);
+            assert!((window.coefficient(4, 9) - 1.0).abs() < 1e-6);
+        }
+
+        // Hann and Blackman taper to zero, Hamming stops at 0.08
+        assert!(WindowFunction::Hann.coefficient(0, 9).abs() < 1e-6);
+        assert!(WindowFunction::Blackman.coefficient(0, 9).abs() < 1e-6);
+        assert!((WindowFunction::Hamming.coefficient(0, 9) - 0.08).abs() < 1e-6);
+        assert_eq!(WindowFunction::from_name("unknown"), WindowFunction::Hann);
+    }
+
+    #[test]
+    fn test_cqt_bands() {
+        // 1 position per semitone: 12 positions up is an octave
+        let (_, a4, _) = cqt_band(0, 440.0, 1.0);
+        let (_, a5, _) = cqt_band(12, 440.0, 1.0);
+        assert!((a4 - 440.0).abs() < 1e-3 && (a5 - 880.0).abs() < 1e-2);
+
+        // Halving the step doubles the positions per octave
+        let (_, half_octave, _) = cqt_band(12, 440.0, 2.0);
+        assert!((half_octave - 440.0 * 2f32.sqrt()).abs() < 1e-2);
+
+        // Narrow bands interpolate between bins, wide ones take their loudest bin
+        let bins = [0.0, 1.0, 0.0, 0.0, 0.5, 0.25, 0.0, 0.0];
+        assert!((cqt_magnitude(&bins, 10.0, 0, 15.0, 1.0) - 0.5).abs() < 1e-4);
+        assert_eq!(cqt_magnitude(&bins, 10.0, 0, 45.0, 0.05), 0.5);
+
+        // Bands above the last bin are silent
+        assert_eq!(cqt_magnitude(&bins, 10.0, 0, 100.0, 1.0), 0.0);
+    }
+
+    #[test]
+    fn test_hop_samples() {
+        assert_eq!(hop_samples(1024, 0.0), 1024);
+        assert_eq!(hop_samples(1024, 0.5), 512);
+        assert_eq!(hop_samples(1024, 0.75), 256);
+        assert_eq!(hop_samples(16, 1.0), 1);
+    }
+}
@@ -0,0 +1,94 @@
+// Font Module - Tiny 3x5 pixel font for text on LED matrices (digits, A-Z, and a few symbols)
+
+pub const GLYPH_WIDTH: usize = 3;
+pub const GLYPH_HEIGHT: usize = 5;
+
+// Each glyph is 5 rows top to bottom, 3 bits per row (0b100 = left column)
+fn glyph(c: char) -> Option<[u8; GLYPH_HEIGHT]> {
+    Some(match c.to_ascii_uppercase() {
+        '0' => [0b111, 0b101, 0b101, 0b101, 0b111],
+        '1' => [0b010, 0b110, 0b010, 0b010, 0b111],
+        '2' => [0b111, 0b001, 0b111, 0b100, 0b111],
+        '3' => [0b111, 0b001, 0b111, 0b001, 0b111],
+        '4' => [0b101, 0b101, 0b111, 0b001, 0b001],
+        '5' => [0b111, 0b100, 0b111, 0b001, 0b111],
+        '6' => [0b111, 0b100, 0b111, 0b101, 0b111],
+        '7' => [0b111, 0b001, 0b001, 0b001, 0b001],
+        '8' => [0b111, 0b101, 0b111, 0b101, 0b111],
+        '9' => [0b111, 0b101, 0b111, 0b001, 0b111],
+        'A' => [0b010, 0b101, 0b111, 0b101, 0b101],
+        'B' => [0b110, 0b101, 0b110, 0b101, 0b110],
+        'C' => [0b011, 0b100, 0b100, 0b100, 0b011],
+        'D' => [0b110, 0b101, 0b101, 0b101, 0b110],
+        'E' => [0b111, 0b100, 0b110, 0b100, 0b111],
+        'F' => [0b111, 0b100, 0b110, 0b100, 0b100],
+        'G' => [0b011, 0b100, 0b101, 0b101, 0b011],
+        'H' => [0b101, 0b101, 0b111, 0b101, 0b101],
+        'I' => [0b111, 0b010, 0b010, 0b010, 0b111],
+        'J' => [0b001, 0b001, 0b001, 0b101, 0b010],
+        'K' => [0b101, 0b101, 0b110, 0b101, 0b101],
+        'L' => [0b100, 0b100, 0b100, 0b100, 0b111],
+        'M' => [0b101, 0b111, 0b111, 0b101, 0b101],
+        'N' => [0b110, 0b101, 0b101, 0b101, 0b101],
+        'O' => [0b010, 0b101, 0b101, 0b101, 0b010],
+        'P' => [0b110, 0b101, 0b110, 0b100, 0b100],
+        'Q' => [0b010, 0b101, 0b101, 0b110, 0b011],
+        'R' => [0b110, 0b101, 0b110, 0b101, 0b101],
+        'S' => [0b011, 0b100, 0b010, 0b001, 0b110],
+        'T' => [0b111, 0b010, 0b010, 0b010, 0b010],
+        'U' => [0b101, 0b101, 0b101, 0b101, 0b111],
+        'V' => [0b101, 0b101, 0b101, 0b101, 0b010],
+        'W' => [0b101, 0b101, 0b111, 0b111, 0b101],
+        'X' => [0b101, 0b101, 0b010, 0b101, 0b101],
+        'Y' => [0b101, 0b101, 0b010, 0b010, 0b010],
+        'Z' => [0b111, 0b001, 0b010, 0b100, 0b111],
+        '-' => [0b000, 0b000, 0b111, 0b000, 0b000],
+        '.' => [0b000, 0b000, 0b000, 0b000, 0b010],
+        ' ' => [0b000; GLYPH_HEIGHT],
+        _ => return None,
+    })
+}
+
+/// Whether a character can be drawn
+pub fn has_glyph(c: char) -> bool {
+    glyph(c).is_some()
+}
+
+/// Width in pixels of `text`, with one blank column between characters (unknown characters are skipped)
+pub fn text_width(text: &str) -> usize {
+    let glyphs = text.chars().filter(|&c| has_glyph(c)).count();
+    (glyphs * (GLYPH_WIDTH + 1)).saturating_sub(1)
+}
+
+/// Lit pixels of `text` as (x, y) offsets from its top-left corner
+pub fn text_pixels(text: &str) -> impl Iterator<Item = (usize, usize)> + '_ {
+    text.chars()
+        .filter_map(glyph)
+        .enumerate()
+        .flat_map(|(i, rows)| {
+            (0..GLYPH_HEIGHT).flat_map(move |y| {
+                (0..GLYPH_WIDTH)
+                    .filter(move |&x| rows[y] & (0b100 >> x) != 0)
+                    .map(move |x| (i * (GLYPH_WIDTH + 1) + x, y))
+            })
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_text_pixels() {
+        assert_eq!(text_width("E0"), 7);
+        assert_eq!(text_width(""), 0);
+        assert_eq!(text_width("a_b"), 7);  // '_' has no glyph
+
+        // "1" then "-": the dash sits in the middle row of the second cell
+        let pixels: Vec<(usize, usize)> = text_pixels("1-").collect();
+        assert!(pixels.contains(&(1, 0)));
+        assert!(pixels.contains(&(4, 2)) && pixels.contains(&(6, 2)));
+        assert!(!pixels.contains(&(3, 2)));
+        assert_eq!(pixels.iter().filter(|&&(_, y)| y == 4).count(), 3);  // The 1's base
+    }
+}
@@ -0,0 +1,160 @@
+// Frame Interpolation Module - Motion interpolation for modes fed by slow sources (e.g. a webcam at 10fps):
+// the output stage blends from the picture on the strip to each new source frame over the time the source
+// takes between frames, sending in-between frames at the output rate. Costs up to one source frame of latency
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+use tokio::sync::broadcast;
+
+use crate::config::{self, BandwidthConfig, ConfigDelta};
+use crate::multi_device::quantize;
+
+// Sources delivering at least this fraction of the output rate are passed through as they are
+const SLOW_SOURCE_FRACTION: f64 = 1.0 / 1.5;
+
+// A source quiet for this long has paused - its next frame is shown at once rather than blended in
+const MAX_SOURCE_GAP: Duration = Duration::from_secs(1);
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+static OUTPUT_FPS: AtomicU64 = AtomicU64::new(0x404E_0000_0000_0000);  // 60.0 (f64 bits)
+
+/// Take the interpolation settings from the config
+pub fn configure(config: &BandwidthConfig) {
+    ENABLED.store(config.frame_interpolation, Ordering::Relaxed);
+    OUTPUT_FPS.store(config.frame_interpolation_fps.to_bits(), Ordering::Relaxed);
+}
+
+pub fn enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+/// Time between in-between frames
+pub fn output_interval() -> Duration {
+    Duration::from_secs_f64(1.0 / f64::from_bits(OUTPUT_FPS.load(Ordering::Relaxed)).max(1.0))
+}
+
+/// Blends between consecutive source frames (one per output stage)
+#[derive(Default)]
+pub struct Interpolator {
+    from: Vec<u8>,  // What the strip showed when the latest source frame arrived
+    to: Vec<u8>,  // Latest source frame
+    arrived: Option<Instant>,
+    source_interval: Option<Duration>,  // Smoothed time between source frames (None = not a slow source)
+    brightness: Option<f64>,  // Brightness the latest source frame was sent with
+    blended: Vec<u8>,
+    last_sent: Option<Instant>,
+    finished: bool,  // The latest source frame has been sent as it is - nothing left to fill in
+}
+
+impl Interpolator {
+    /// Take a frame from the mode - a repeat of the latest one isn't a new source frame
+    pub fn push(&mut self, frame: &[u8], brightness: Option<f64>, now: Instant) {
+        self.brightness = brightness;
+        if self.arrived.is_some() && frame == &self.to[..] {
+            return;
+        }
+
+        if let Some(arrived) = self.arrived {
+            let sample = now.duration_since(arrived);
+            self.source_interval = if sample < MAX_SOURCE_GAP {
+                Some(match self.source_interval {
+                    Some(interval) => interval.mul_f64(0.8) + sample.mul_f64(0.2),
+                    None => sample,
+                })
+            } else {
+                None
+            };
+        }
+
+        // Carry on from the picture on the strip so early frames don't jump
+        let from = if self.to.len() == frame.len() { self.at(now).to_vec() } else { frame.to_vec() };
+        self.from = from;
+        self.to.clear();
+        self.to.extend_from_slice(frame);
+        self.arrived = Some(now);
+        self.finished = false;
+    }
+
+    // How far the blend to the latest source frame has got (0.0-1.0)
+    fn progress(&self, now: Instant) -> f64 {
+        let (Some(arrived), Some(interval)) = (self.arrived, self.source_interval) else { return 1.0 };
+        if interval.as_secs_f64() * SLOW_SOURCE_FRACTION < output_interval().as_secs_f64() {
+            return 1.0;
+        }
+        (now.duration_since(arrived).as_secs_f64() / interval.as_secs_f64()).min(1.0)
+    }
+
+    // The blended frame at `now`
+    fn at(&mut self, now: Instant) -> &[u8] {
+        let t = self.progress(now) as f32;
+        if t >= 1.0 {
+            return &self.to;
+        }
+        self.blended.clear();
+        self.blended.extend(self.from.iter().zip(&self.to).map(|(&a, &b)| quantize(a as f32 + (b as f32 - a as f32) * t)));
+        &self.blended
+    }
+
+    /// The frame to send now, with the brightness it goes out at
+    pub fn frame(&mut self, now: Instant) -> (&[u8], Option<f64>) {
+        self.last_sent = Some(now);
+        self.finished = self.progress(now) >= 1.0;
+        let brightness = self.brightness;
+        (self.at(now), brightness)
+    }
+
+    /// Whether an in-between frame is due (the blend is under way and an output interval has passed)
+    pub fn due(&self, now: Instant) -> bool {
+        match self.last_sent {
+            _ if self.finished => false,
+            Some(sent) => now.duration_since(sent) >= output_interval(),
+            None => true,
+        }
+    }
+}
+
+/// Keep the interpolation settings in step with config changes (web UI, API, or file edits)
+pub fn spawn_watcher(config_change_tx: broadcast::Sender<ConfigDelta>) {
+    config::spawn_config_listener(&config_change_tx, &["frame_interpolation", "frame_interpolation_fps"], configure);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_blends_slow_source() {
+        let start = Instant::now();
+        let mut interpolator = Interpolator::default();
+        interpolator.push(&[0, 0, 0], None, start);
+        assert_eq!(interpolator.frame(start).0, [0, 0, 0]);
+
+        // 10fps source: the next frame is blended in over 100ms
+        let second = start + Duration::from_millis(100);
+        interpolator.push(&[200, 100, 0], Some(0.5), second);
+        assert_eq!(interpolator.frame(second), (&[0u8, 0, 0][..], Some(0.5)));
+        assert_eq!(interpolator.frame(second + Duration::from_millis(50)).0, [100, 50, 0]);
+        assert!(!interpolator.due(second + Duration::from_millis(51)));
+        assert!(interpolator.due(second + Duration::from_millis(70)));
+        assert_eq!(interpolator.frame(second + Duration::from_millis(100)).0, [200, 100, 0]);
+        assert!(!interpolator.due(second + Duration::from_millis(200)));
+
+        // Repeats of the latest frame don't restart the blend
+        interpolator.push(&[200, 100, 0], None, second + Duration::from_millis(150));
+        assert!(!interpolator.due(second + Duration::from_millis(200)));
+    }
+
+    #[test]
+    fn test_passes_fast_source_through() {
+        let start = Instant::now();
+        let mut interpolator = Interpolator::default();
+        interpolator.push(&[0, 0, 0], None, start);
+        let next = start + Duration::from_millis(16);
+        interpolator.push(&[90, 90, 90], None, next);
+        assert_eq!(interpolator.frame(next).0, [90, 90, 90]);
+
+        // A source that paused is shown at once when it comes back
+        let resumed = next + Duration::from_secs(5);
+        interpolator.push(&[10, 10, 10], None, resumed);
+        assert_eq!(interpolator.frame(resumed).0, [10, 10, 10]);
+    }
+}
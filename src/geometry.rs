@@ -2,6 +2,9 @@
 use std::f64::consts::PI;
 use std::time::{Duration, Instant};
 
+use crate::config::{BoidConfig, GeometryConfig};
+use crate::matrix::MatrixLayout;
+
 const PHI: f64 = 1.618033988749895; // Golden ratio
 const GOLDEN_ANGLE: f64 = 137.5; // Golden angle in degrees
 
@@ -116,23 +119,12 @@ pub struct GeometryState {
     pub grid_width: usize,
     pub grid_height: usize,
     pub frame_buffer: Vec<(f32, f32, f32)>, // RGB float buffer for blending
+    pub blend_buffer: Vec<(f32, f32, f32)>, // Outgoing mode's frame during a transition (kept to avoid reallocating)
     pub fixed_mode: Option<GeometryMode>,  // If Some, stay on this mode; if None, cycle
     pub randomize_order: bool,  // If true, pick random modes when cycling
     pub next_mode: Option<GeometryMode>,  // Pre-selected next mode for smooth transitions
     pub boids: Vec<Boid>,  // Boid positions and velocities
-    // Boid configuration parameters
-    pub boid_count: usize,
-    pub boid_separation_distance: f64,
-    pub boid_alignment_distance: f64,
-    pub boid_cohesion_distance: f64,
-    pub boid_max_speed: f64,
-    pub boid_max_force: f64,
-    // Predator-prey settings
-    pub boid_predator_enabled: bool,
-    pub boid_predator_count: usize,
-    pub boid_predator_speed: f64,
-    pub boid_avoidance_distance: f64,
-    pub boid_chase_force: f64,
+    pub boid: BoidConfig,  // Flocking and predator-prey parameters
     // Gradient colors for rendering
     pub gradient_colors: Vec<(f32, f32, f32)>,  // RGB colors from gradient
     // Gradient animation
@@ -140,33 +132,16 @@ pub struct GeometryState {
     pub animation_direction: String,  // "left" or "right"
     pub last_geometry_cycle: i64,  // Track geometry cycle to detect when animation repeats
     pub last_config_direction: String,  // Track config direction to detect manual changes
+    pub layout: Option<MatrixLayout>,  // Tiled matrix panels (None = row-major grid on the strip)
 }
 
 impl GeometryState {
-    pub fn new(
-        total_leds: usize,
-        grid_width: usize,
-        grid_height: usize,
-        mode_select: &str,
-        duration_seconds: f64,
-        randomize: bool,
-        boid_count: usize,
-        boid_separation_distance: f64,
-        boid_alignment_distance: f64,
-        boid_cohesion_distance: f64,
-        boid_max_speed: f64,
-        boid_max_force: f64,
-        boid_predator_enabled: bool,
-        boid_predator_count: usize,
-        boid_predator_speed: f64,
-        boid_avoidance_distance: f64,
-        boid_chase_force: f64,
-    ) -> Self {
+    pub fn new(total_leds: usize, grid_width: usize, grid_height: usize, geometry: &GeometryConfig, boid: &BoidConfig) -> Self {
         // Parse mode selection
-        let fixed_mode = if mode_select.to_lowercase() == "cycle" {
+        let fixed_mode = if geometry.mode_select.to_lowercase() == "cycle" {
             None
         } else {
-            GeometryMode::from_string(mode_select)
+            GeometryMode::from_string(&geometry.mode_select)
         };
 
         let current_mode = fixed_mode.unwrap_or(GeometryMode::Lissajous);
@@ -177,12 +152,12 @@ impl GeometryState {
         let mut boids = Vec::new();
 
         // Create predators first if enabled
-        let num_predators = if boid_predator_enabled { boid_predator_count } else { 0 };
+        let num_predators = if boid.predator_enabled { boid.predator_count } else { 0 };
         for _ in 0..num_predators {
             // Random angle for initial velocity direction
             let angle = rng.gen_range(0.0..(2.0 * PI));
-            let vx = angle.cos() * boid_predator_speed;
-            let vy = angle.sin() * boid_predator_speed;
+            let vx = angle.cos() * boid.predator_speed;
+            let vy = angle.sin() * boid.predator_speed;
             boids.push(Boid {
                 x: rng.gen_range(-0.8..0.8),
                 y: rng.gen_range(-0.8..0.8),
@@ -193,11 +168,11 @@ impl GeometryState {
         }
 
         // Create regular prey boids
-        for _ in 0..boid_count {
+        for _ in 0..boid.count {
             // Random angle for initial velocity direction
             let angle = rng.gen_range(0.0..(2.0 * PI));
-            let vx = angle.cos() * boid_max_speed;
-            let vy = angle.sin() * boid_max_speed;
+            let vx = angle.cos() * boid.max_speed;
+            let vy = angle.sin() * boid.max_speed;
             boids.push(Boid {
                 x: rng.gen_range(-0.8..0.8),
                 y: rng.gen_range(-0.8..0.8),
@@ -223,32 +198,24 @@ impl GeometryState {
             current_mode,
             mode_start_time: now,
             animation_start_time: now,  // Start continuous animation clock
-            mode_duration: Duration::from_secs_f64(duration_seconds.max(1.0)),
+            mode_duration: Duration::from_secs_f64(geometry.mode_duration_seconds.max(1.0)),
             transition_duration: Duration::from_secs(2), // 2 second transitions
             total_leds,
             grid_width,
             grid_height,
             frame_buffer: vec![(0.0, 0.0, 0.0); total_leds],
+            blend_buffer: vec![(0.0, 0.0, 0.0); total_leds],
             fixed_mode,
-            randomize_order: randomize,
+            randomize_order: geometry.randomize_order,
             next_mode: None,
             boids,
-            boid_count,
-            boid_separation_distance,
-            boid_alignment_distance,
-            boid_cohesion_distance,
-            boid_max_speed,
-            boid_max_force,
-            boid_predator_enabled,
-            boid_predator_count,
-            boid_predator_speed,
-            boid_avoidance_distance,
-            boid_chase_force,
+            boid: boid.clone(),
             gradient_colors: default_gradient_colors,
             animation_offset: 0.0,
             animation_direction: "left".to_string(),
             last_geometry_cycle: -1,
             last_config_direction: "left".to_string(),
+            layout: None,
         }
     }
 
@@ -295,37 +262,14 @@ impl GeometryState {
         )
     }
 
-    pub fn update_boid_config(
-        &mut self,
-        boid_count: usize,
-        boid_separation_distance: f64,
-        boid_alignment_distance: f64,
-        boid_cohesion_distance: f64,
-        boid_max_speed: f64,
-        boid_max_force: f64,
-        boid_predator_enabled: bool,
-        boid_predator_count: usize,
-        boid_predator_speed: f64,
-        boid_avoidance_distance: f64,
-        boid_chase_force: f64,
-    ) {
+    pub fn update_boid_config(&mut self, boid: &BoidConfig) {
         // Check if we need to rebuild boids (count or predator settings changed)
-        let num_predators_old = if self.boid_predator_enabled { self.boid_predator_count } else { 0 };
-        let num_predators_new = if boid_predator_enabled { boid_predator_count } else { 0 };
-        let needs_rebuild = boid_count != self.boid_count || num_predators_new != num_predators_old;
+        let num_predators_old = if self.boid.predator_enabled { self.boid.predator_count } else { 0 };
+        let num_predators_new = if boid.predator_enabled { boid.predator_count } else { 0 };
+        let needs_rebuild = boid.count != self.boid.count || num_predators_new != num_predators_old;
 
         // Update config parameters
-        self.boid_count = boid_count;
-        self.boid_separation_distance = boid_separation_distance;
-        self.boid_alignment_distance = boid_alignment_distance;
-        self.boid_cohesion_distance = boid_cohesion_distance;
-        self.boid_max_speed = boid_max_speed;
-        self.boid_max_force = boid_max_force;
-        self.boid_predator_enabled = boid_predator_enabled;
-        self.boid_predator_count = boid_predator_count;
-        self.boid_predator_speed = boid_predator_speed;
-        self.boid_avoidance_distance = boid_avoidance_distance;
-        self.boid_chase_force = boid_chase_force;
+        self.boid.clone_from(boid);
 
         // Only rebuild boids if count or predator count changed
         if needs_rebuild {
@@ -339,8 +283,8 @@ impl GeometryState {
             for _ in 0..num_predators_new {
                 // Random angle for initial velocity direction
                 let angle = rng.gen_range(0.0..(2.0 * PI));
-                let vx = angle.cos() * boid_predator_speed;
-                let vy = angle.sin() * boid_predator_speed;
+                let vx = angle.cos() * boid.predator_speed;
+                let vy = angle.sin() * boid.predator_speed;
                 self.boids.push(Boid {
                     x: rng.gen_range(-0.8..0.8),
                     y: rng.gen_range(-0.8..0.8),
@@ -351,11 +295,11 @@ impl GeometryState {
             }
 
             // Create prey boids
-            for _ in 0..boid_count {
+            for _ in 0..boid.count {
                 // Random angle for initial velocity direction
                 let angle = rng.gen_range(0.0..(2.0 * PI));
-                let vx = angle.cos() * boid_max_speed;
-                let vy = angle.sin() * boid_max_speed;
+                let vx = angle.cos() * boid.max_speed;
+                let vy = angle.sin() * boid.max_speed;
                 self.boids.push(Boid {
                     x: rng.gen_range(-0.8..0.8),
                     y: rng.gen_range(-0.8..0.8),
@@ -369,7 +313,8 @@ impl GeometryState {
         // The new parameters will affect their behavior on the next update
     }
 
-    pub fn update(&mut self, global_brightness: f64, animation_speed: f64, animation_direction: &str) -> Vec<u8> {
+    /// Advance the animation and render the frame into `output` (reusing its allocation)
+    pub fn update(&mut self, global_brightness: f64, animation_speed: f64, animation_direction: &str, output: &mut Vec<u8>) {
         // Update animation offset for gradient animation
         if animation_speed > 0.0 {
             let half_leds = self.total_leds / 2;
@@ -453,14 +398,15 @@ impl GeometryState {
         if transition_progress > 0.0 {
             // Use the pre-selected next_mode (guaranteed to be Some at this point)
             let next_mode = self.next_mode.unwrap();
-            let mut next_buffer = vec![(0.0, 0.0, 0.0); self.total_leds];
-            std::mem::swap(&mut self.frame_buffer, &mut next_buffer);
+            std::mem::swap(&mut self.frame_buffer, &mut self.blend_buffer);
+            self.frame_buffer.clear();
+            self.frame_buffer.resize(self.total_leds, (0.0, 0.0, 0.0));
 
             self.render_mode(next_mode, mode_time);
 
             // Crossfade blend
             for i in 0..self.total_leds {
-                let (r1, g1, b1) = next_buffer[i];
+                let (r1, g1, b1) = self.blend_buffer[i];
                 let (r2, g2, b2) = self.frame_buffer[i];
                 let alpha = transition_progress as f32;
                 self.frame_buffer[i] = (
@@ -472,14 +418,13 @@ impl GeometryState {
         }
 
         // Convert float buffer to u8 with brightness
-        let mut output = vec![0u8; self.total_leds * 3];
+        output.clear();
+        output.resize(self.total_leds * 3, 0);
         for (i, &(r, g, b)) in self.frame_buffer.iter().enumerate() {
             output[i * 3] = (r * 255.0 * global_brightness as f32).min(255.0).max(0.0) as u8;
             output[i * 3 + 1] = (g * 255.0 * global_brightness as f32).min(255.0).max(0.0) as u8;
             output[i * 3 + 2] = (b * 255.0 * global_brightness as f32).min(255.0).max(0.0) as u8;
         }
-
-        output
     }
 
     // Calculate the current cycle number for each geometry mode
@@ -544,6 +489,11 @@ impl GeometryState {
             return None;
         }
 
+        // Tiled panels map each grid cell to its own LED
+        if let Some(layout) = &self.layout {
+            return layout.index(grid_x as usize, grid_y as usize).filter(|&led| led < self.total_leds);
+        }
+
         // Map 2D grid position to 1D LED strip index (row-major order)
         let grid_index = (grid_y as usize * self.grid_width) + grid_x as usize;
         let total_grid_pixels = self.grid_width * self.grid_height;
@@ -558,6 +508,15 @@ impl GeometryState {
         }
     }
 
+    // LED for a grid cell without scaling to the strip (row-major, or through the panel layout)
+    fn grid_led(&self, x: usize, y: usize) -> Option<usize> {
+        let led = match &self.layout {
+            Some(layout) => layout.index(x, y)?,
+            None => y * self.grid_width + x,
+        };
+        (led < self.total_leds).then_some(led)
+    }
+
     // Mode 1: Lissajous Curves
     fn render_lissajous(&mut self, time: f64) {
         let a = 3.0 + (time * 0.2).sin() * 2.0;
@@ -913,8 +872,7 @@ impl GeometryState {
         loop {
             // Plot this point
             if x >= 0 && x < self.grid_width as i32 && y >= 0 && y < self.grid_height as i32 {
-                let led = (y as usize) * self.grid_width + (x as usize);
-                if led < self.total_leds {
+                if let Some(led) = self.grid_led(x as usize, y as usize) {
                     self.frame_buffer[led] = (
                         self.frame_buffer[led].0.max(r),
                         self.frame_buffer[led].1.max(g),
@@ -975,8 +933,7 @@ impl GeometryState {
                 if iteration < max_iter {
                     let gradient_pos = (iteration as f64 / max_iter as f64) % 1.0;
                     let (r, g, b) = self.get_gradient_color(gradient_pos);
-                    let led = y * self.grid_width + x;
-                    if led < self.total_leds {
+                    if let Some(led) = self.grid_led(x, y) {
                         self.frame_buffer[led] = (r, g, b);
                     }
                 }
@@ -1318,16 +1275,16 @@ impl GeometryState {
         let num_boids = self.boids.len();
 
         // Flocking parameters from config
-        let separation_dist = self.boid_separation_distance;
-        let alignment_dist = self.boid_alignment_distance;
-        let cohesion_dist = self.boid_cohesion_distance;
-        let max_speed = self.boid_max_speed;
-        let max_force = self.boid_max_force;
+        let separation_dist = self.boid.separation_distance;
+        let alignment_dist = self.boid.alignment_distance;
+        let cohesion_dist = self.boid.cohesion_distance;
+        let max_speed = self.boid.max_speed;
+        let max_force = self.boid.max_force;
 
         // Predator-prey parameters
-        let avoidance_dist = self.boid_avoidance_distance;
-        let chase_force = self.boid_chase_force;
-        let predator_speed = self.boid_predator_speed;
+        let avoidance_dist = self.boid.avoidance_distance;
+        let chase_force = self.boid.chase_force;
+        let predator_speed = self.boid.predator_speed;
 
         // Calculate steering forces for each boid
         let mut forces: Vec<(f64, f64)> = Vec::with_capacity(num_boids);
@@ -1364,7 +1321,7 @@ impl GeometryState {
                 let dist = (dx * dx + dy * dy).sqrt();
 
                 // Predator-prey behavior
-                if self.boid_predator_enabled {
+                if self.boid.predator_enabled {
                     if is_predator {
                         // Predators chase prey
                         if !self.boids[j].is_predator && dist < avoidance_dist * 2.0 {
@@ -1576,8 +1533,7 @@ impl GeometryState {
                     g = (g * brightness).min(1.0);
                     b = (b * brightness).min(1.0);
 
-                    let led = y * self.grid_width + x;
-                    if led < self.total_leds {
+                    if let Some(led) = self.grid_led(x, y) {
                         self.frame_buffer[led] = (r, g, b);
                     }
                 }
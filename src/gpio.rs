@@ -0,0 +1,127 @@
+// GPIO Module - Physical buttons on a Raspberry Pi for headless installs: next/previous mode,
+// brightness up/down and blank, configured as [[gpio_buttons]] pin -> action pairs
+// Pin access needs the "gpio" feature (rppal) - without it buttons are ignored with a warning
+use anyhow::Result;
+
+use crate::config::BandwidthConfig;
+use crate::system;
+
+/// Actions a button can be mapped to
+pub const ACTIONS: &[&str] = &["next_mode", "prev_mode", "brightness_up", "brightness_down", "blank"];
+
+// global_brightness change per press
+const BRIGHTNESS_STEP: f64 = 0.1;
+
+/// Carry out a button action - config changes are saved, so the config watcher applies them like a web UI edit
+pub fn run_action(action: &str) -> Result<()> {
+    if action == "blank" {
+        // Same blank as the API's /api/system/blank - modes keep running underneath
+        let blanked = !system::blanked();
+        system::set_blanked(blanked);
+        println!("🔘 Output {}", if blanked { "blanked" } else { "restored" });
+        return Ok(());
+    }
+
+    let mut config = BandwidthConfig::load()?;
+    match action {
+        "next_mode" | "prev_mode" => {
+            let modes = mode_cycle(&config);
+            config.mode = step_mode(&modes, &config.mode, action == "next_mode");
+            println!("🔘 Mode: {}", config.mode);
+        }
+        "brightness_up" | "brightness_down" => {
+            let step = if action == "brightness_up" { BRIGHTNESS_STEP } else { -BRIGHTNESS_STEP };
+            // Snap to the step grid so repeated presses land on round percentages
+            config.global_brightness = ((config.global_brightness + step) / BRIGHTNESS_STEP).round() * BRIGHTNESS_STEP;
+            config.global_brightness = config.global_brightness.clamp(0.0, 1.0);
+            println!("🔘 Brightness: {:.0}%", config.global_brightness * 100.0);
+        }
+        _ => return Ok(()),
+    }
+    config.save()
+}
+
+// Modes the mode buttons step through, in order
+fn mode_cycle(config: &BandwidthConfig) -> Vec<String> {
+    config.gpio_mode_cycle.split(',').map(|m| m.trim().to_lowercase()).filter(|m| !m.is_empty()).collect()
+}
+
+/// The mode after (or before) `current` in the cycle - the first mode if `current` isn't in it
+fn step_mode(modes: &[String], current: &str, forward: bool) -> String {
+    let Some(first) = modes.first() else {
+        return current.to_string();
+    };
+    match modes.iter().position(|m| m == current) {
+        Some(i) if forward => modes[(i + 1) % modes.len()].clone(),
+        Some(i) => modes[(i + modes.len() - 1) % modes.len()].clone(),
+        None => first.clone(),
+    }
+}
+
+/// Watch the configured buttons in the background (pin changes need a restart)
+#[cfg(feature = "gpio")]
+pub fn spawn_buttons(config: &BandwidthConfig) -> Result<()> {
+    use rppal::gpio::{Gpio, Level};
+    use std::time::{Duration, Instant};
+
+    // Pins are sampled this often, and a press must be stable this long to count (contact bounce)
+    const POLL_INTERVAL: Duration = Duration::from_millis(10);
+    const DEBOUNCE: Duration = Duration::from_millis(50);
+
+    if config.gpio_buttons.is_empty() {
+        return Ok(());
+    }
+    let gpio = Gpio::new()?;
+    let mut buttons = Vec::new();
+    for button in &config.gpio_buttons {
+        // Buttons wire the pin to ground - the internal pull-up holds it high while released
+        let pin = gpio.get(button.pin)?.into_input_pullup();
+        println!("🔘 GPIO {} -> {}", button.pin, button.action);
+        buttons.push((pin, button.action.clone(), Level::High, Instant::now(), false));
+    }
+
+    std::thread::spawn(move || loop {
+        for (pin, action, last_level, changed_at, pressed) in &mut buttons {
+            let level = pin.read();
+            if level != *last_level {
+                *last_level = level;
+                *changed_at = Instant::now();
+            } else if changed_at.elapsed() >= DEBOUNCE && (level == Level::Low) != *pressed {
+                *pressed = level == Level::Low;
+                if *pressed {
+                    if let Err(e) = run_action(action) {
+                        eprintln!("⚠️  GPIO {} action failed: {}", action, e);
+                    }
+                }
+            }
+        }
+        std::thread::sleep(POLL_INTERVAL);
+    });
+    Ok(())
+}
+
+/// Without the "gpio" feature there are no pins to watch
+#[cfg(not(feature = "gpio"))]
+pub fn spawn_buttons(config: &BandwidthConfig) -> Result<()> {
+    if !config.gpio_buttons.is_empty() {
+        eprintln!("⚠️  gpio_buttons are configured but rustwled was built without GPIO support (cargo build --features gpio)");
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_step_mode() {
+        let modes: Vec<String> = ["bandwidth", "live", "sand"].iter().map(|m| m.to_string()).collect();
+        assert_eq!(step_mode(&modes, "live", true), "sand");
+        assert_eq!(step_mode(&modes, "sand", true), "bandwidth");
+        assert_eq!(step_mode(&modes, "bandwidth", false), "sand");
+
+        // A mode outside the cycle starts it over
+        assert_eq!(step_mode(&modes, "relay", true), "bandwidth");
+        assert_eq!(step_mode(&[], "relay", true), "relay");
+    }
+}
@@ -0,0 +1,140 @@
+// Gradient Animation Module - Slow hue rotation or palette cycling applied to every gradient lookup
+use arc_swap::ArcSwap;
+use std::sync::{Arc, OnceLock};
+use std::time::Instant;
+use tokio::sync::broadcast;
+
+use crate::config::{self, BandwidthConfig, ConfigDelta};
+use crate::gradients;
+use crate::types::{build_gradient_from_color, hsv_to_rgb, rgb_to_hsv, ColorSpace, GradientLut, InterpolationMode, Rgb};
+
+// Share of each palette step spent crossfading into the next palette (the rest holds)
+const PALETTE_FADE: f64 = 0.2;
+
+/// Running gradient animation - built from the gradient_animation* keys
+pub enum GradientAnimation {
+    Hue { period_secs: f64 },  // Hue turns once around the wheel per period
+    Palette { period_secs: f64, palettes: Vec<GradientLut> },  // Each palette shows for one period
+}
+
+impl GradientAnimation {
+    /// Animation for a config (None = off, or palette cycling without any usable palette)
+    pub fn from_config(config: &BandwidthConfig) -> Option<Self> {
+        let period_secs = config.gradient_animation_seconds.max(0.1);
+        match config.gradient_animation.as_str() {
+            "hue" => Some(GradientAnimation::Hue { period_secs }),
+            "palette" => {
+                let interpolation_mode = match config.interpolation.to_lowercase().as_str() {
+                    "basis" => InterpolationMode::Basis,
+                    "catmullrom" => InterpolationMode::CatmullRom,
+                    _ => InterpolationMode::Linear,
+                };
+                let color_space = ColorSpace::from_name(&config.color_space);
+                let palettes: Vec<GradientLut> = config.gradient_palettes
+                    .split(',')
+                    .map(|name| name.trim())
+                    .filter(|name| !name.is_empty())
+                    .filter_map(|name| {
+                        let hex_colors = gradients::resolve_color_string(name);
+                        build_gradient_from_color(&hex_colors, true, interpolation_mode, color_space).ok()?.0
+                    })
+                    .collect();
+                if palettes.is_empty() {
+                    None
+                } else {
+                    Some(GradientAnimation::Palette { period_secs, palettes })
+                }
+            }
+            _ => None,
+        }
+    }
+
+    /// Animated color for a gradient lookup - phase is elapsed time in periods
+    pub fn apply(&self, base: [u8; 4], pos: f64, phase: f64) -> [u8; 4] {
+        match self {
+            GradientAnimation::Hue { .. } => {
+                let [h, s, v] = rgb_to_hsv(Rgb { r: base[0], g: base[1], b: base[2] });
+                let rotated = hsv_to_rgb([(h + phase.fract() * 360.0).rem_euclid(360.0), s, v]);
+                [rotated.r, rotated.g, rotated.b, base[3]]
+            }
+            GradientAnimation::Palette { palettes, .. } => {
+                let step = phase.floor() as usize;
+                let current = palettes[step % palettes.len()].base_at(pos);
+                let into_step = phase.fract();
+                if into_step < 1.0 - PALETTE_FADE {
+                    return current;
+                }
+                let next = palettes[(step + 1) % palettes.len()].base_at(pos);
+                let t = (into_step - (1.0 - PALETTE_FADE)) / PALETTE_FADE;
+                let mix = |a: u8, b: u8| (a as f64 + (b as f64 - a as f64) * t).round() as u8;
+                [mix(current[0], next[0]), mix(current[1], next[1]), mix(current[2], next[2]), mix(current[3], next[3])]
+            }
+        }
+    }
+
+    fn period_secs(&self) -> f64 {
+        match self {
+            GradientAnimation::Hue { period_secs } | GradientAnimation::Palette { period_secs, .. } => *period_secs,
+        }
+    }
+}
+
+// Animation in effect (None = off) - swapped whole on config changes, read by every gradient lookup
+fn settings() -> &'static ArcSwap<Option<GradientAnimation>> {
+    static SETTINGS: OnceLock<ArcSwap<Option<GradientAnimation>>> = OnceLock::new();
+    SETTINGS.get_or_init(|| ArcSwap::from_pointee(None))
+}
+
+// Animation clock - shared so every mode (and both channels) stay in phase
+fn start() -> Instant {
+    static START: OnceLock<Instant> = OnceLock::new();
+    *START.get_or_init(Instant::now)
+}
+
+/// Make the config's gradient animation live
+pub fn configure(config: &BandwidthConfig) {
+    settings().store(Arc::new(GradientAnimation::from_config(config)));
+}
+
+/// Apply the running gradient animation to a looked-up gradient color (unchanged when off)
+pub fn animate(base: [u8; 4], pos: f64) -> [u8; 4] {
+    let guard = settings().load();
+    match &**guard {
+        Some(animation) => animation.apply(base, pos, start().elapsed().as_secs_f64() / animation.period_secs()),
+        None => base,
+    }
+}
+
+/// Keep the animation in step with config changes (web UI, API, or file edits)
+pub fn spawn_watcher(config_change_tx: broadcast::Sender<ConfigDelta>) {
+    config::spawn_config_listener(&config_change_tx, &["gradient*", "interpolation", "color_space"], configure);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hue_rotation() {
+        let hue = GradientAnimation::Hue { period_secs: 10.0 };
+        assert_eq!(hue.apply([255, 0, 0, 255], 0.0, 0.0), [255, 0, 0, 255]);
+        assert_eq!(hue.apply([255, 0, 0, 255], 0.0, 1.0 / 3.0), [0, 255, 0, 255]);
+        assert_eq!(hue.apply([255, 0, 0, 255], 0.0, 1.0), [255, 0, 0, 255]);
+        // Grays have no hue to rotate
+        assert_eq!(hue.apply([90, 90, 90, 255], 0.5, 0.25), [90, 90, 90, 255]);
+    }
+
+    #[test]
+    fn test_palette_cycling() {
+        let solid = |hex: &str| build_gradient_from_color(hex, true, InterpolationMode::Linear, ColorSpace::Rgb).unwrap().0.unwrap();
+        let palette = GradientAnimation::Palette {
+            period_secs: 10.0,
+            palettes: vec![solid("FF0000,FF0000"), solid("0000FF,0000FF")],
+        };
+        assert_eq!(palette.apply([0, 0, 0, 255], 0.3, 0.5), [255, 0, 0, 255]);
+        assert_eq!(palette.apply([0, 0, 0, 255], 0.3, 1.5), [0, 0, 255, 255]);
+        // Crossfades into the next palette at the end of each step, then wraps around
+        assert_eq!(palette.apply([0, 0, 0, 255], 0.3, 0.95), [64, 0, 191, 255]);
+        assert_eq!(palette.apply([0, 0, 0, 255], 0.3, 2.5), [255, 0, 0, 255]);
+    }
+}
@@ -1,6 +1,61 @@
 // Gradients Module - Spectrum gradient functions and custom gradient management
 use anyhow::Result;
-use std::path::PathBuf;
+use arc_swap::ArcSwap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, OnceLock};
+
+use crate::config::{BandwidthConfig, GradientStopConfig, NamedGradientConfig};
+use crate::types::{build_gradient_from_color, ColorSpace, InterpolationMode, Rgb};
+
+// Points a named gradient's stops are sampled at - enough to keep closely spaced stops distinct
+const NAMED_GRADIENT_SAMPLES: usize = 32;
+
+// Named gradients from the config (name -> sampled hex colors) - swapped whole whenever the config is loaded or saved
+fn named_gradients() -> &'static ArcSwap<Vec<(String, String)>> {
+    static NAMED: OnceLock<ArcSwap<Vec<(String, String)>>> = OnceLock::new();
+    NAMED.get_or_init(|| ArcSwap::from_pointee(Vec::new()))
+}
+
+/// Make the config's named gradients resolvable by resolve_color_string
+pub fn configure(config: &BandwidthConfig) {
+    let named = config.gradients.iter()
+        .filter_map(|gradient| Some((gradient.name.clone(), named_gradient_to_hex_string(gradient)?)))
+        .collect();
+    named_gradients().store(Arc::new(named));
+}
+
+/// Named gradients from the config as (name, hex colors) pairs
+pub fn get_named_gradients() -> Arc<Vec<(String, String)>> {
+    named_gradients().load_full()
+}
+
+/// Sample a named gradient's color stops to comma-separated hex colors (None if it has no valid stops)
+/// Stops are interpolated linearly by position; the ends hold the first and last stop colors
+pub fn named_gradient_to_hex_string(gradient: &NamedGradientConfig) -> Option<String> {
+    let mut stops: Vec<(f64, Rgb)> = gradient.stops.iter()
+        .filter_map(|stop| Some((stop.position.clamp(0.0, 1.0), Rgb::from_hex(&stop.color).ok()?)))
+        .collect();
+    stops.sort_by(|a, b| a.0.total_cmp(&b.0));
+    let (first, last) = (*stops.first()?, *stops.last()?);
+
+    let mut hex_colors = Vec::with_capacity(NAMED_GRADIENT_SAMPLES);
+    for i in 0..NAMED_GRADIENT_SAMPLES {
+        let pos = i as f64 / (NAMED_GRADIENT_SAMPLES - 1) as f64;
+        let color = match stops.windows(2).find(|w| pos >= w[0].0 && pos <= w[1].0) {
+            Some(w) => {
+                let span = w[1].0 - w[0].0;
+                let t = if span > 0.0 { (pos - w[0].0) / span } else { 0.0 };
+                let mix = |a: u8, b: u8| (a as f64 + (b as f64 - a as f64) * t).round() as u8;
+                Rgb { r: mix(w[0].1.r, w[1].1.r), g: mix(w[0].1.g, w[1].1.g), b: mix(w[0].1.b, w[1].1.b) }
+            }
+            None if pos < first.0 => first.1,
+            None => last.1,
+        };
+        hex_colors.push(format!("{:02X}{:02X}{:02X}", color.r, color.g, color.b));
+    }
+
+    Some(hex_colors.join(","))
+}
 
 /// Get list of all available spectrum gradient names
 pub fn get_spectrum_gradient_names() -> Vec<&'static str> {
@@ -25,9 +80,17 @@ pub fn get_spectrum_gradient_names() -> Vec<&'static str> {
         "Turbo",
         "Spectral",
         "Cividis",
+        "Okabe-Ito",
+        "Blue Orange",
+        "Blue Yellow",
     ]
 }
 
+/// Built-in gradients whose colors stay distinguishable with red-green color blindness
+pub fn get_cvd_safe_gradient_names() -> Vec<&'static str> {
+    vec!["Viridis", "Cividis", "Inferno", "Magma", "Plasma", "Okabe-Ito", "Blue Orange", "Blue Yellow"]
+}
+
 /// Get spectrum gradient function by name
 /// Returns a function that maps position (0.0-1.0) to RGB color (r, g, b)
 pub fn get_spectrum_gradient(name: &str) -> Box<dyn Fn(f32) -> (u8, u8, u8) + Send + Sync> {
@@ -52,6 +115,9 @@ pub fn get_spectrum_gradient(name: &str) -> Box<dyn Fn(f32) -> (u8, u8, u8) + Se
         "Turbo" => Box::new(gradient_turbo),
         "Spectral" => Box::new(gradient_spectral),
         "Cividis" => Box::new(gradient_cividis),
+        "Okabe-Ito" => Box::new(gradient_okabe_ito),
+        "Blue Orange" => Box::new(gradient_blue_orange),
+        "Blue Yellow" => Box::new(gradient_blue_yellow),
         _ => Box::new(gradient_rainbow), // Default fallback
     }
 }
@@ -401,6 +467,80 @@ fn gradient_cividis(pos: f32) -> (u8, u8, u8) {
     }
 }
 
+// Blend evenly spaced color stops
+fn even_stops(stops: &[(u8, u8, u8)], pos: f32) -> (u8, u8, u8) {
+    let scaled = pos.clamp(0.0, 1.0) * (stops.len() - 1) as f32;
+    let i = (scaled as usize).min(stops.len() - 2);
+    let t = scaled - i as f32;
+    let ((r1, g1, b1), (r2, g2, b2)) = (stops[i], stops[i + 1]);
+    let mix = |a: u8, b: u8| (a as f32 + (b as f32 - a as f32) * t).round() as u8;
+    (mix(r1, r2), mix(g1, g2), mix(b1, b2))
+}
+
+// Gradient 21: Okabe-Ito (orange -> sky blue -> green -> yellow -> blue -> vermillion -> purple - colorblind safe)
+fn gradient_okabe_ito(pos: f32) -> (u8, u8, u8) {
+    even_stops(&[(230, 159, 0), (86, 180, 233), (0, 158, 115), (240, 228, 66), (0, 114, 178), (213, 94, 0), (204, 121, 167)], pos)
+}
+
+// Gradient 22: Blue Orange (dark blue -> pale blue -> pale orange -> dark orange - colorblind safe diverging)
+fn gradient_blue_orange(pos: f32) -> (u8, u8, u8) {
+    even_stops(&[(8, 69, 148), (66, 146, 198), (198, 219, 239), (253, 208, 162), (241, 105, 19), (140, 45, 4)], pos)
+}
+
+// Gradient 23: Blue Yellow (blue -> sky blue -> yellow - colorblind safe)
+fn gradient_blue_yellow(pos: f32) -> (u8, u8, u8) {
+    even_stops(&[(0, 114, 178), (86, 180, 233), (240, 228, 66)], pos)
+}
+
+/// Color vision deficiency a preview can be shown as
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CvdSimulation {
+    Protanopia,
+    Deuteranopia,
+}
+
+impl CvdSimulation {
+    /// Parse a simulation name - None for "none" (or anything unrecognized)
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "protanopia" => Some(CvdSimulation::Protanopia),
+            "deuteranopia" => Some(CvdSimulation::Deuteranopia),
+            _ => None,
+        }
+    }
+
+    // Machado, Oliveira & Fernandes (2009) at full severity, applied to linear RGB
+    fn matrix(self) -> [[f64; 3]; 3] {
+        match self {
+            CvdSimulation::Protanopia => [
+                [0.152286, 1.052583, -0.204868],
+                [0.114503, 0.786281, 0.099216],
+                [-0.003882, -0.048116, 1.051998],
+            ],
+            CvdSimulation::Deuteranopia => [
+                [0.367322, 0.860646, -0.227968],
+                [0.280085, 0.672501, 0.047413],
+                [-0.011820, 0.042940, 0.968881],
+            ],
+        }
+    }
+
+    /// How an sRGB color looks with this deficiency
+    pub fn simulate(self, [r, g, b]: [u8; 3]) -> [u8; 3] {
+        let to_linear = |c: u8| {
+            let c = c as f64 / 255.0;
+            if c <= 0.04045 { c / 12.92 } else { ((c + 0.055) / 1.055).powf(2.4) }
+        };
+        let to_srgb = |c: f64| {
+            let c = c.clamp(0.0, 1.0);
+            let c = if c <= 0.0031308 { c * 12.92 } else { 1.055 * c.powf(1.0 / 2.4) - 0.055 };
+            (c * 255.0).round() as u8
+        };
+        let linear = [to_linear(r), to_linear(g), to_linear(b)];
+        self.matrix().map(|row| to_srgb(row[0] * linear[0] + row[1] * linear[1] + row[2] * linear[2]))
+    }
+}
+
 /// Convert a gradient name to comma-separated hex colors by sampling at 12 points
 pub fn gradient_to_hex_string(gradient_name: &str) -> String {
     let gradient_fn = get_spectrum_gradient(gradient_name);
@@ -416,6 +556,47 @@ pub fn gradient_to_hex_string(gradient_name: &str) -> String {
     hex_colors.join(",")
 }
 
+/// Render a color spec (gradient name or hex colors) as a PNG strip, built the same way the modes build it
+/// Each column is one sample along the gradient; without a gradient the colors are shown as hard segments
+/// With `cvd` set, the colors are shown as someone with that color vision deficiency would see them
+pub fn render_gradient_preview_png(
+    spec: &str,
+    width: u32,
+    height: u32,
+    use_gradient: bool,
+    interpolation_mode: InterpolationMode,
+    color_space: ColorSpace,
+    cvd: Option<CvdSimulation>,
+) -> Result<Vec<u8>> {
+    use image::ImageEncoder;
+
+    let (gradient, colors, solid_color) = build_gradient_from_color(&resolve_color_string(spec), use_gradient, interpolation_mode, color_space)?;
+    let (width, height) = (width.max(1), height.max(1));
+
+    let mut row = Vec::with_capacity(width as usize * 3);
+    for x in 0..width {
+        let pos = if width > 1 { x as f64 / (width - 1) as f64 } else { 0.0 };
+        let [r, g, b] = match &gradient {
+            Some(lut) => {
+                let [r, g, b, _] = lut.at(pos);
+                [r, g, b]
+            }
+            None if !colors.is_empty() => {
+                let c = colors[((pos * colors.len() as f64) as usize).min(colors.len() - 1)];
+                [c.r, c.g, c.b]
+            }
+            None => [solid_color.r, solid_color.g, solid_color.b],
+        };
+        let rgb = cvd.map_or([r, g, b], |cvd| cvd.simulate([r, g, b]));
+        row.extend_from_slice(&rgb);
+    }
+    let pixels = row.repeat(height as usize);
+
+    let mut png = Vec::new();
+    image::codecs::png::PngEncoder::new(&mut png).write_image(&pixels, width, height, image::ColorType::Rgb8)?;
+    Ok(png)
+}
+
 /// Get path to custom gradients file
 pub fn gradients_file_path() -> Result<PathBuf> {
     let home = std::env::var("HOME")?;
@@ -497,10 +678,225 @@ pub fn delete_custom_gradient(name: &str) -> Result<()> {
     Ok(())
 }
 
+// Stops of one WLED palette - either [[pos, r, g, b], ...] (/json/palx) or a flat [pos, r, g, b, ...] /
+// [pos, "RRGGBB", ...] list (custom palette files), positions 0-255
+// None for palettes that depend on segment colors ("c1") or are random ("r")
+fn wled_palette_stops(data: &serde_json::Value) -> Option<Vec<GradientStopConfig>> {
+    let entries = data.as_array()?;
+    let stop = |pos: &serde_json::Value, color: String| -> Option<GradientStopConfig> {
+        Some(GradientStopConfig { position: pos.as_f64()? / 255.0, color })
+    };
+    let channel = |v: &serde_json::Value| v.as_u64().map(|c| c.min(255));
+
+    let stops: Option<Vec<GradientStopConfig>> = if entries.iter().all(|e| e.is_array()) {
+        entries.iter()
+            .map(|e| {
+                let e = e.as_array()?;
+                let (r, g, b) = (channel(e.get(1)?)?, channel(e.get(2)?)?, channel(e.get(3)?)?);
+                stop(e.first()?, format!("{:02X}{:02X}{:02X}", r, g, b))
+            })
+            .collect()
+    } else if entries.iter().skip(1).step_by(2).all(|e| e.is_string()) {
+        entries.chunks(2)
+            .map(|c| {
+                let hex = c.get(1)?.as_str()?.trim_start_matches('#').to_uppercase();
+                Rgb::from_hex(&hex).ok()?;
+                stop(&c[0], hex)
+            })
+            .collect()
+    } else {
+        entries.chunks(4)
+            .map(|c| {
+                let (r, g, b) = (channel(c.get(1)?)?, channel(c.get(2)?)?, channel(c.get(3)?)?);
+                stop(&c[0], format!("{:02X}{:02X}{:02X}", r, g, b))
+            })
+            .collect()
+    };
+    stops.filter(|s| !s.is_empty())
+}
+
+/// Named gradients from a WLED /json/palx response (or a saved copy of one)
+/// names is WLED's /json/palettes list - palettes are named "WLED <name>" so they don't shadow built-ins
+pub fn import_wled_palx(palx: &serde_json::Value, names: &[String]) -> Vec<NamedGradientConfig> {
+    let Some(palettes) = palx["p"].as_object() else {
+        return Vec::new();
+    };
+
+    let mut imported: Vec<(usize, NamedGradientConfig)> = palettes.iter()
+        .filter_map(|(id, data)| {
+            let id: usize = id.parse().ok()?;
+            let name = names.get(id).cloned().unwrap_or_else(|| format!("Palette {}", id));
+            let stops = wled_palette_stops(data)?;
+            Some((id, NamedGradientConfig { name: format!("WLED {}", name), stops }))
+        })
+        .collect();
+    imported.sort_by_key(|(id, _)| *id);
+    imported.into_iter().map(|(_, gradient)| gradient).collect()
+}
+
+/// Named gradients from a palette JSON exported from WLED - a custom palette file ({"palette": [...]})
+/// named after the file, or a saved /json/palx response
+pub fn import_wled_palette_file(json: &serde_json::Value, name: &str) -> Vec<NamedGradientConfig> {
+    if json.get("p").is_some() {
+        return import_wled_palx(json, &[]);
+    }
+    json.get("palette")
+        .and_then(wled_palette_stops)
+        .map(|stops| vec![NamedGradientConfig { name: format!("WLED {}", name), stops }])
+        .unwrap_or_default()
+}
+
+// Gradients loaded from palette files (name -> sampled hex colors) - filled once at startup
+fn palette_file_gradients() -> &'static ArcSwap<Vec<(String, String)>> {
+    static PALETTE_FILES: OnceLock<ArcSwap<Vec<(String, String)>>> = OnceLock::new();
+    PALETTE_FILES.get_or_init(|| ArcSwap::from_pointee(Vec::new()))
+}
+
+/// Gradients loaded from the palettes directory as (name, hex colors) pairs
+pub fn get_palette_file_gradients() -> Arc<Vec<(String, String)>> {
+    palette_file_gradients().load_full()
+}
+
+/// Palettes directory next to the active config: <config dir>/palettes
+pub fn palettes_dir() -> Result<PathBuf> {
+    let config_path = BandwidthConfig::config_path(None)?;
+    Ok(crate::config::config_watch_dir(&config_path).join("palettes"))
+}
+
+/// Load every cpt-city (.cpt) and FastLED (.pal/.h) palette in a directory so they resolve by name
+/// Returns the number of gradients found (a missing directory just means none)
+pub fn discover_palette_files(dir: &Path) -> usize {
+    let mut paths: Vec<PathBuf> = std::fs::read_dir(dir)
+        .map(|entries| entries.filter_map(|e| e.ok()).map(|e| e.path()).collect())
+        .unwrap_or_default();
+    paths.sort();
+
+    let mut found = Vec::new();
+    for path in paths {
+        let ext = path.extension().map(|e| e.to_string_lossy().to_lowercase()).unwrap_or_default();
+        let stem = path.file_stem().map(|s| s.to_string_lossy().to_string()).unwrap_or_default();
+        let Ok(contents) = std::fs::read_to_string(&path) else {
+            continue;
+        };
+        let gradients = match ext.as_str() {
+            "cpt" => parse_cpt_palette(&contents, &stem).into_iter().collect(),
+            "pal" | "h" => parse_fastled_palettes(&contents, &stem),
+            _ => continue,
+        };
+        if gradients.is_empty() {
+            eprintln!("No palettes found in {}", path.display());
+        }
+        for gradient in gradients {
+            if let Some(hex_colors) = named_gradient_to_hex_string(&gradient) {
+                found.push((gradient.name, hex_colors));
+            }
+        }
+    }
+
+    let count = found.len();
+    palette_file_gradients().store(Arc::new(found));
+    count
+}
+
+/// Parse a cpt-city / GMT color palette table ("z0 r0 g0 b0 z1 r1 g1 b1" segments) into a gradient
+/// Positions are normalized to the table's z range; B/F/N (background/foreground/NaN) lines are ignored
+pub fn parse_cpt_palette(contents: &str, name: &str) -> Option<NamedGradientConfig> {
+    let mut segments: Vec<[f64; 8]> = Vec::new();
+    for line in contents.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') || line.starts_with(['B', 'F', 'N']) {
+            continue;
+        }
+        // Some tables write colors as r/g/b
+        let values: Vec<f64> = line.replace('/', " ")
+            .split_whitespace()
+            .take(8)
+            .map_while(|v| v.parse().ok())
+            .collect();
+        if let Ok(segment) = <[f64; 8]>::try_from(values) {
+            segments.push(segment);
+        }
+    }
+
+    let z_min = segments.iter().map(|s| s[0]).fold(f64::INFINITY, f64::min);
+    let z_max = segments.iter().map(|s| s[4]).fold(f64::NEG_INFINITY, f64::max);
+    if segments.is_empty() || z_max <= z_min {
+        return None;
+    }
+
+    let channel = |v: f64| v.round().clamp(0.0, 255.0) as u8;
+    let stops = segments.iter()
+        .flat_map(|s| [(s[0], s[1], s[2], s[3]), (s[4], s[5], s[6], s[7])])
+        .map(|(z, r, g, b)| GradientStopConfig {
+            position: (z - z_min) / (z_max - z_min),
+            color: format!("{:02X}{:02X}{:02X}", channel(r), channel(g), channel(b)),
+        })
+        .collect();
+    Some(NamedGradientConfig { name: name.to_string(), stops })
+}
+
+/// Parse FastLED gradient palettes (DEFINE_GRADIENT_PALETTE or TProgmemRGBGradientPalette_byte arrays)
+/// Each { ... } block is a list of (index 0-255, r, g, b) entries; unnamed blocks use the file name
+pub fn parse_fastled_palettes(contents: &str, file_name: &str) -> Vec<NamedGradientConfig> {
+    // Strip // and /* */ comments so numbers inside them aren't read as entries
+    let mut source = String::with_capacity(contents.len());
+    let mut rest = contents;
+    while !rest.is_empty() {
+        if let Some(after) = rest.strip_prefix("//") {
+            rest = after.find('\n').map(|i| &after[i..]).unwrap_or("");
+        } else if let Some(after) = rest.strip_prefix("/*") {
+            rest = after.find("*/").map(|i| &after[i + 2..]).unwrap_or("");
+        } else {
+            let c = rest.chars().next().unwrap_or_default();
+            source.push(c);
+            rest = &rest[c.len_utf8()..];
+        }
+    }
+
+    let mut palettes = Vec::new();
+    let mut rest = source.as_str();
+    while let Some(open) = rest.find('{') {
+        let Some(close) = rest[open..].find('}').map(|i| open + i) else {
+            break;
+        };
+        let header = rest[..open].rsplit([';', '}']).next().unwrap_or("");
+        let body = &rest[open + 1..close];
+        rest = &rest[close + 1..];
+
+        // DEFINE_GRADIENT_PALETTE( name ) or "... name[] PROGMEM ="
+        let name = match header.split_once("DEFINE_GRADIENT_PALETTE") {
+            Some((_, args)) => args.trim().trim_start_matches('(').split(')').next().unwrap_or("").trim(),
+            None => header.split('[').next().unwrap_or("").split_whitespace().last().unwrap_or(""),
+        };
+        let name = if name.is_empty() { file_name } else { name };
+
+        let numbers: Vec<u32> = body
+            .split(|c: char| !c.is_ascii_alphanumeric())
+            .filter(|t| !t.is_empty())
+            .filter_map(|t| match t.strip_prefix("0x").or_else(|| t.strip_prefix("0X")) {
+                Some(hex) => u32::from_str_radix(hex, 16).ok(),
+                None => t.parse().ok(),
+            })
+            .collect();
+        let stops: Vec<GradientStopConfig> = numbers.chunks_exact(4)
+            .map(|e| GradientStopConfig {
+                position: e[0].min(255) as f64 / 255.0,
+                color: format!("{:02X}{:02X}{:02X}", e[1].min(255), e[2].min(255), e[3].min(255)),
+            })
+            .collect();
+        if !stops.is_empty() {
+            palettes.push(NamedGradientConfig { name: name.to_string(), stops });
+        }
+    }
+    palettes
+}
+
 /// Resolve a color string which can be:
 /// 1. A built-in gradient name (e.g. "Rainbow")
-/// 2. A custom gradient name (from gradients.conf)
-/// 3. Comma-separated hex colors (e.g. "FF0000,00FF00,0000FF")
+/// 2. A named gradient from the config ([[gradients]] entries)
+/// 3. A gradient from a palette file in the palettes directory
+/// 4. A custom gradient name (from gradients.conf)
+/// 5. Comma-separated hex colors (e.g. "FF0000,00FF00,0000FF")
 /// Returns the comma-separated hex color string
 pub fn resolve_color_string(color_str: &str) -> String {
     let trimmed = color_str.trim();
@@ -513,6 +909,20 @@ pub fn resolve_color_string(color_str: &str) -> String {
         }
     }
 
+    // Check if it's a named gradient from the config (case-insensitive)
+    for (name, hex_colors) in get_named_gradients().iter() {
+        if name.eq_ignore_ascii_case(trimmed) {
+            return hex_colors.clone();
+        }
+    }
+
+    // Check if it's a gradient loaded from a palette file (case-insensitive)
+    for (name, hex_colors) in get_palette_file_gradients().iter() {
+        if name.eq_ignore_ascii_case(trimmed) {
+            return hex_colors.clone();
+        }
+    }
+
     // Check if it's a custom gradient name (case-insensitive)
     if let Ok(custom_gradients) = load_custom_gradients() {
         for (name, hex_colors) in custom_gradients.iter() {
@@ -525,3 +935,127 @@ pub fn resolve_color_string(color_str: &str) -> String {
     // Assume it's already comma-separated hex colors
     color_str.to_string()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::GradientStopConfig;
+
+    fn stop(position: f64, color: &str) -> GradientStopConfig {
+        GradientStopConfig { position, color: color.to_string() }
+    }
+
+    #[test]
+    fn test_named_gradient_sampling() {
+        let gradient = NamedGradientConfig {
+            name: "Dawn".to_string(),
+            stops: vec![stop(1.0, "0000FF"), stop(0.25, "FF0000")],
+        };
+        let hex = named_gradient_to_hex_string(&gradient).unwrap();
+        let colors: Vec<&str> = hex.split(',').collect();
+        assert_eq!(colors.len(), NAMED_GRADIENT_SAMPLES);
+        // Before the first stop holds its color, the end is the last stop
+        assert_eq!(colors[0], "FF0000");
+        assert_eq!(colors[NAMED_GRADIENT_SAMPLES - 1], "0000FF");
+
+        let empty = NamedGradientConfig { name: "Empty".to_string(), stops: vec![stop(0.5, "nothex")] };
+        assert!(named_gradient_to_hex_string(&empty).is_none());
+    }
+
+    #[test]
+    fn test_resolve_named_gradient() {
+        let mut config = BandwidthConfig::default();
+        config.gradients.push(NamedGradientConfig {
+            name: "Test Named Gradient".to_string(),
+            stops: vec![stop(0.0, "112233"), stop(1.0, "112233")],
+        });
+        configure(&config);
+        assert!(resolve_color_string("test named gradient").starts_with("112233,112233"));
+        // Built-in names still win, unknown strings pass through as hex colors
+        assert_eq!(resolve_color_string("Rainbow"), gradient_to_hex_string("Rainbow"));
+        assert_eq!(resolve_color_string("FF0000,00FF00"), "FF0000,00FF00");
+    }
+
+    #[test]
+    fn test_import_wled_palettes() {
+        let palx = serde_json::json!({
+            "m": 0,
+            "p": {
+                "1": ["r", "r", "r", "r"],
+                "6": [[0, 255, 0, 0], [255, 0, 0, 255]],
+            }
+        });
+        let names: Vec<String> = ["Default", "* Random Cycle", "", "", "", "", "Party"].iter().map(|s| s.to_string()).collect();
+        let imported = import_wled_palx(&palx, &names);
+        assert_eq!(imported.len(), 1);
+        assert_eq!(imported[0].name, "WLED Party");
+        assert_eq!(imported[0].stops[1], stop(1.0, "0000FF"));
+
+        let file = serde_json::json!({ "palette": [0, "ff0000", 255, "00ff00"] });
+        let imported = import_wled_palette_file(&file, "palette0");
+        assert_eq!(imported[0].name, "WLED palette0");
+        assert_eq!(imported[0].stops, vec![stop(0.0, "FF0000"), stop(1.0, "00FF00")]);
+
+        let flat = serde_json::json!({ "palette": [0, 255, 0, 0, 255, 0, 0, 255] });
+        assert_eq!(import_wled_palette_file(&flat, "p")[0].stops[1], stop(1.0, "0000FF"));
+    }
+
+    #[test]
+    fn test_render_gradient_preview_png() {
+        let png = render_gradient_preview_png("FF0000,0000FF", 64, 4, true, InterpolationMode::Linear, ColorSpace::Rgb, None).unwrap();
+        assert_eq!(&png[1..4], b"PNG");
+        assert!(render_gradient_preview_png("not a color", 64, 4, true, InterpolationMode::Linear, ColorSpace::Rgb, None).is_err());
+        let simulated = render_gradient_preview_png("Okabe-Ito", 64, 4, true, InterpolationMode::Linear, ColorSpace::Rgb, Some(CvdSimulation::Deuteranopia)).unwrap();
+        assert_ne!(png, simulated);
+    }
+
+    #[test]
+    fn test_cvd_simulation() {
+        // Grays look the same to everyone
+        for cvd in [CvdSimulation::Protanopia, CvdSimulation::Deuteranopia] {
+            assert_eq!(cvd.simulate([0, 0, 0]), [0, 0, 0]);
+            assert_eq!(cvd.simulate([255, 255, 255]), [255, 255, 255]);
+        }
+
+        // Pure red and green both collapse toward a muddy yellow
+        let yellowish = |[r, g, b]: [u8; 3]| r >= g && g > b;
+        assert!(yellowish(CvdSimulation::Deuteranopia.simulate([255, 0, 0])));
+        assert!(yellowish(CvdSimulation::Deuteranopia.simulate([0, 255, 0])));
+        assert_eq!(CvdSimulation::from_name("Protanopia"), Some(CvdSimulation::Protanopia));
+        assert_eq!(CvdSimulation::from_name("none"), None);
+
+        // Every CVD-safe name is a real built-in
+        let names = get_spectrum_gradient_names();
+        assert!(get_cvd_safe_gradient_names().iter().all(|name| names.contains(name)));
+        assert_eq!(gradient_okabe_ito(0.0), (230, 159, 0));
+        assert_eq!(gradient_okabe_ito(1.0), (204, 121, 167));
+    }
+
+    #[test]
+    fn test_parse_cpt_palette() {
+        let cpt = "# COLOR_MODEL = RGB\n0 255 0 0 50 255 255 0\n50 255/255/0 100 0 0 255\nB 0 0 0\nF 255 255 255\n";
+        let gradient = parse_cpt_palette(cpt, "sunny").unwrap();
+        assert_eq!(gradient.name, "sunny");
+        assert_eq!(gradient.stops.len(), 4);
+        assert_eq!(gradient.stops[0], stop(0.0, "FF0000"));
+        assert_eq!(gradient.stops[1], stop(0.5, "FFFF00"));
+        assert_eq!(gradient.stops[3], stop(1.0, "0000FF"));
+        assert!(parse_cpt_palette("# empty\n", "none").is_none());
+    }
+
+    #[test]
+    fn test_parse_fastled_palettes() {
+        let source = "// Gradient palette \"es_test_gp\", 0 1 2 3\n\
+            DEFINE_GRADIENT_PALETTE( es_test_gp ) {\n    0, 255,  0,  0,\n  255,   0,  0, 0xFF};\n\
+            const TProgmemRGBGradientPalette_byte old_style_gp[] PROGMEM = { 0, 1, 2, 3, /* 9 9 9 9 */ 255, 4, 5, 6 };\n";
+        let palettes = parse_fastled_palettes(source, "file");
+        assert_eq!(palettes.len(), 2);
+        assert_eq!(palettes[0].name, "es_test_gp");
+        assert_eq!(palettes[0].stops, vec![stop(0.0, "FF0000"), stop(1.0, "0000FF")]);
+        assert_eq!(palettes[1].name, "old_style_gp");
+        assert_eq!(palettes[1].stops[1], stop(1.0, "040506"));
+
+        let bare = parse_fastled_palettes("{ 0, 0, 0, 0, 255, 255, 255, 255 }", "bare");
+        assert_eq!(bare[0].name, "bare");
+    }
+}
@@ -0,0 +1,235 @@
+// Hue Module - Philips Hue Entertainment streaming: Hue bulbs and strips in an entertainment area mirror
+// the average color of an LED range, alongside the WLED devices
+use anyhow::{anyhow, Result};
+use openssl::ssl::{SslConnector, SslMethod, SslVerifyMode, SslVersion};
+use std::io::{self, Read, Write};
+use std::net::{TcpStream, ToSocketAddrs, UdpSocket};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, Instant};
+use tokio::sync::broadcast;
+
+use crate::config::{self, BandwidthConfig, ConfigDelta, HueLightConfig};
+use crate::multi_device;
+
+/// Entertainment streams go to this DTLS port on the bridge
+pub const HUE_STREAM_PORT: u16 = 2100;
+
+// The bridge drives the lights at 25Hz - sending at twice that hides dropped packets
+const SEND_INTERVAL: Duration = Duration::from_millis(20);
+
+// Wait between attempts when the bridge can't be reached or the stream fails
+const RETRY_INTERVAL: Duration = Duration::from_secs(5);
+
+const HTTP_TIMEOUT: Duration = Duration::from_secs(3);
+
+// Entertainment API v1 message: "HueStream", version 1.0, sequence, reserved, RGB color space, reserved
+const STREAM_HEADER: &[u8; 9] = b"HueStream";
+const DEVICE_TYPE_LIGHT: u8 = 0x00;
+
+// The only cipher suite the bridge accepts
+const CIPHER: &str = "PSK-AES128-GCM-SHA256";
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+static SETTINGS: Mutex<Option<HueSettings>> = Mutex::new(None);
+static COLORS: Mutex<Vec<(u16, [u16; 3])>> = Mutex::new(Vec::new());  // Light id and its 16-bit color
+
+#[derive(Debug, Clone, PartialEq)]
+struct HueSettings {
+    bridge_ip: String,
+    username: String,  // Application key from bridge pairing
+    clientkey: String,  // 32 hex digit PSK from bridge pairing
+    group: String,  // Entertainment area (group id)
+    lights: Vec<HueLightConfig>,
+}
+
+/// Take the bridge settings from the config - streaming starts or stops with hue_enabled
+pub fn configure(config: &BandwidthConfig) {
+    let settings = (config.hue_enabled && !config.hue_bridge_ip.is_empty() && !config.hue_lights.is_empty()).then(|| HueSettings {
+        bridge_ip: config.hue_bridge_ip.clone(),
+        username: config.hue_username.clone(),
+        clientkey: config.hue_clientkey.clone(),
+        group: config.hue_entertainment_group.clone(),
+        lights: config.hue_lights.clone(),
+    });
+    ENABLED.store(settings.is_some(), Ordering::Relaxed);
+    *SETTINGS.lock().unwrap() = settings;
+}
+
+/// Downsample an output frame to the Hue lights - called for every frame sent to the WLED devices
+pub fn update(frame: &[u8]) {
+    if !ENABLED.load(Ordering::Relaxed) {
+        return;
+    }
+    let colors = match SETTINGS.lock().unwrap().as_ref() {
+        Some(settings) => settings.lights.iter().map(|light| (light.light_id, average_color(frame, light))).collect(),
+        None => return,
+    };
+    *COLORS.lock().unwrap() = colors;
+}
+
+/// Average color of a light's LED range, scaled to 16 bits per channel
+fn average_color(frame: &[u8], light: &HueLightConfig) -> [u16; 3] {
+    multi_device::average_zone(frame, light.led_offset, light.led_count).map(|c| c as u16 * 257)  // 0xFF -> 0xFFFF
+}
+
+/// One stream message setting every light's color
+fn stream_message(sequence: u8, colors: &[(u16, [u16; 3])]) -> Vec<u8> {
+    let mut message = Vec::with_capacity(16 + colors.len() * 9);
+    message.extend_from_slice(STREAM_HEADER);
+    message.extend_from_slice(&[0x01, 0x00, sequence, 0x00, 0x00, 0x00, 0x00]);
+    for (id, [r, g, b]) in colors {
+        message.push(DEVICE_TYPE_LIGHT);
+        message.extend_from_slice(&id.to_be_bytes());
+        for channel in [r, g, b] {
+            message.extend_from_slice(&channel.to_be_bytes());
+        }
+    }
+    message
+}
+
+/// Stream to the bridge in the background, reconnecting after failures and following config changes
+pub fn spawn_streamer() {
+    thread::spawn(|| loop {
+        let Some(settings) = SETTINGS.lock().unwrap().clone() else {
+            thread::sleep(Duration::from_millis(500));
+            continue;
+        };
+        match stream(&settings) {
+            Ok(()) => {}  // Settings changed - reconnect with the new ones
+            Err(e) => {
+                eprintln!("⚠️  Hue entertainment stream to {}: {} - retrying in {}s", settings.bridge_ip, e, RETRY_INTERVAL.as_secs());
+                thread::sleep(RETRY_INTERVAL);
+            }
+        }
+        let _ = set_stream_active(&settings, false);
+    });
+}
+
+/// Keep the bridge settings in step with config changes (web UI, API, or file edits)
+pub fn spawn_watcher(config_change_tx: broadcast::Sender<ConfigDelta>) {
+    config::spawn_config_listener(&config_change_tx, &["hue_enabled", "hue_bridge_ip", "hue_username", "hue_clientkey", "hue_entertainment_group", "hue_lights"], configure);
+}
+
+// Activate the entertainment area and send colors until the settings change (Ok) or the stream fails
+fn stream(settings: &HueSettings) -> Result<()> {
+    set_stream_active(settings, true)?;
+
+    let psk = decode_hex(&settings.clientkey).ok_or_else(|| anyhow!("hue_clientkey must be 32 hex digits"))?;
+    let identity = settings.username.clone().into_bytes();
+    let mut builder = SslConnector::builder(SslMethod::dtls())?;
+    builder.set_min_proto_version(Some(SslVersion::DTLS1_2))?;
+    builder.set_cipher_list(CIPHER)?;
+    builder.set_verify(SslVerifyMode::NONE);  // PSK - the bridge has no certificate to check
+    builder.set_psk_client_callback(move |_ssl, _hint, identity_out, psk_out| {
+        if identity.len() >= identity_out.len() || psk.len() > psk_out.len() {
+            return Ok(0);
+        }
+        identity_out[..identity.len()].copy_from_slice(&identity);
+        identity_out[identity.len()] = 0;
+        psk_out[..psk.len()].copy_from_slice(&psk);
+        Ok(psk.len())
+    });
+
+    let socket = UdpSocket::bind("0.0.0.0:0")?;
+    socket.connect((settings.bridge_ip.as_str(), HUE_STREAM_PORT))?;
+    socket.set_read_timeout(Some(HTTP_TIMEOUT))?;
+    let mut config = builder.build().configure()?;
+    config.set_verify_hostname(false);
+    let mut stream = config.connect(&settings.bridge_ip, UdpStream(socket))
+        .map_err(|e| anyhow!("DTLS handshake failed: {}", e))?;
+    println!("💡 Streaming to Hue entertainment area {} on {} ({} lights)", settings.group, settings.bridge_ip, settings.lights.len());
+
+    let mut sequence = 0u8;
+    let mut next_send = Instant::now();
+    while SETTINGS.lock().unwrap().as_ref() == Some(settings) {
+        let message = stream_message(sequence, &COLORS.lock().unwrap());
+        stream.write_all(&message)?;
+        sequence = sequence.wrapping_add(1);
+
+        next_send += SEND_INTERVAL;
+        thread::sleep(next_send.saturating_duration_since(Instant::now()));
+    }
+    Ok(())
+}
+
+// Start or stop streaming mode on the entertainment area (PUT /api/<username>/groups/<group>)
+fn set_stream_active(settings: &HueSettings, active: bool) -> Result<()> {
+    let addr = (settings.bridge_ip.as_str(), 80).to_socket_addrs()?.next()
+        .ok_or_else(|| anyhow!("Could not resolve {}", settings.bridge_ip))?;
+    let mut stream = TcpStream::connect_timeout(&addr, HTTP_TIMEOUT)?;
+    stream.set_read_timeout(Some(HTTP_TIMEOUT))?;
+    let body = serde_json::json!({ "stream": { "active": active } }).to_string();
+    write!(stream, "PUT /api/{}/groups/{} HTTP/1.0\r\nHost: {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\r\n{}",
+        settings.username, settings.group, settings.bridge_ip, body.len(), body)?;
+
+    let mut response = String::new();
+    stream.read_to_string(&mut response)?;
+    // The bridge answers 200 with a list of results - failures are {"error": {...}} entries
+    match response.find("\"description\"") {
+        Some(at) if response.contains("\"error\"") => {
+            let description = response[at..].split('"').nth(3).unwrap_or("unknown error");
+            Err(anyhow!("bridge refused the stream: {}", description))
+        }
+        _ => Ok(()),
+    }
+}
+
+fn decode_hex(hex: &str) -> Option<Vec<u8>> {
+    if hex.len() % 2 != 0 || hex.is_empty() {
+        return None;
+    }
+    (0..hex.len()).step_by(2).map(|i| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok()).collect()
+}
+
+// Connected UDP socket as the byte stream DTLS runs over
+#[derive(Debug)]
+struct UdpStream(UdpSocket);
+
+impl Read for UdpStream {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.0.recv(buf)
+    }
+}
+
+impl Write for UdpStream {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.send(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_average_color() {
+        let frame = [255, 0, 0, 0, 0, 255, 10, 10, 10];
+        let light = HueLightConfig { light_id: 1, led_offset: 0, led_count: 2 };
+        assert_eq!(average_color(&frame, &light), [127 * 257, 0, 127 * 257]);
+
+        // Ranges past the end of the frame are cut short
+        let light = HueLightConfig { light_id: 1, led_offset: 2, led_count: 5 };
+        assert_eq!(average_color(&frame, &light), [2570, 2570, 2570]);
+    }
+
+    #[test]
+    fn test_stream_message() {
+        let message = stream_message(7, &[(3, [0xFFFF, 0x0102, 0])]);
+        assert_eq!(&message[..9], b"HueStream");
+        assert_eq!(&message[9..16], &[1, 0, 7, 0, 0, 0, 0]);
+        assert_eq!(&message[16..], &[0, 0, 3, 0xFF, 0xFF, 0x01, 0x02, 0, 0]);
+    }
+
+    #[test]
+    fn test_decode_hex() {
+        assert_eq!(decode_hex("00ff10"), Some(vec![0, 255, 16]));
+        assert_eq!(decode_hex("abc"), None);
+        assert_eq!(decode_hex("zz"), None);
+    }
+}
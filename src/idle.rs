@@ -0,0 +1,146 @@
+// Idle Module - Fade into a gentle animation when a mode has had no signal for a while
+use std::time::Instant;
+
+use crate::config::BandwidthConfig;
+use crate::gradients;
+use crate::types::{ColorSpace, GradientCache, InterpolationMode};
+
+// Seconds the idle animation takes to fade in once the timeout is reached
+const IDLE_FADE_SECS: f64 = 2.0;
+
+// Seconds per breath for the breathing animation
+const BREATH_PERIOD_SECS: f64 = 4.0;
+
+// Gradient drift speed in strip lengths per second
+const DRIFT_SPEED: f64 = 0.02;
+
+/// Audio sample level (about -60 dBFS) below which live mode counts the input as silent
+pub const SILENCE_LEVEL: f32 = 0.001;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum IdleAnimation {
+    Breathing,  // Whole strip pulses slowly in the first idle color
+    Drift,  // Idle gradient scrolls slowly along the strip
+}
+
+/// Idle settings resolved from the config (idle_* keys)
+#[derive(Debug, Clone, PartialEq)]
+pub struct IdleSettings {
+    pub enabled: bool,
+    pub timeout_secs: f64,
+    pub animation: IdleAnimation,
+    pub color: String,  // Resolved hex color list (gradient names expanded)
+    pub color_space: ColorSpace,
+    pub brightness: f64,
+}
+
+impl IdleSettings {
+    pub fn from_config(config: &BandwidthConfig) -> Self {
+        IdleSettings {
+            enabled: config.idle_enabled,
+            timeout_secs: config.idle_timeout_secs,
+            animation: if config.idle_animation == "drift" { IdleAnimation::Drift } else { IdleAnimation::Breathing },
+            color: gradients::resolve_color_string(&config.idle_color),
+            color_space: ColorSpace::from_name(&config.color_space),
+            brightness: config.idle_brightness,
+        }
+    }
+}
+
+/// Tracks signal activity for one mode and blends the idle animation into its frames
+pub struct IdleAnimator {
+    last_activity: Instant,
+    gradient: GradientCache,
+}
+
+impl IdleAnimator {
+    pub fn new() -> Self {
+        Self {
+            last_activity: Instant::now(),
+            gradient: GradientCache::new(),
+        }
+    }
+
+    /// Call once per frame after rendering - `active` is whether the mode had signal this frame
+    /// Activity cuts the idle animation off immediately; silence fades it in after the timeout
+    pub fn update(&mut self, settings: &IdleSettings, active: bool, frame: &mut [u8]) {
+        let now = Instant::now();
+        if active || !settings.enabled {
+            self.last_activity = now;
+            return;
+        }
+
+        let idle_secs = now.duration_since(self.last_activity).as_secs_f64() - settings.timeout_secs;
+        if idle_secs <= 0.0 {
+            return;
+        }
+        let mix = (idle_secs / IDLE_FADE_SECS).min(1.0);
+
+        let Ok((gradient, _, solid)) = self.gradient.get(&settings.color, true, InterpolationMode::Linear, settings.color_space) else {
+            return;
+        };
+
+        let pixels = frame.len() / 3;
+        let breath = 0.5 - 0.5 * (2.0 * std::f64::consts::PI * idle_secs / BREATH_PERIOD_SECS).cos();
+        let drift = (idle_secs * DRIFT_SPEED).fract();
+
+        for (i, pixel) in frame.chunks_exact_mut(3).enumerate() {
+            let (color, level) = match (settings.animation, gradient) {
+                (IdleAnimation::Drift, Some(lut)) => {
+                    let rgba = lut.at((i as f64 / pixels as f64 + drift).fract());
+                    ([rgba[0], rgba[1], rgba[2]], settings.brightness)
+                }
+                (IdleAnimation::Drift, None) => ([solid.r, solid.g, solid.b], settings.brightness),
+                (IdleAnimation::Breathing, _) => ([solid.r, solid.g, solid.b], settings.brightness * breath),
+            };
+            for (value, target) in pixel.iter_mut().zip(color) {
+                let idle_value = target as f64 * level;
+                *value = (*value as f64 + (idle_value - *value as f64) * mix).round() as u8;
+            }
+        }
+    }
+}
+
+impl Default for IdleAnimator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn settings(timeout_secs: f64) -> IdleSettings {
+        IdleSettings {
+            enabled: true,
+            timeout_secs,
+            animation: IdleAnimation::Drift,
+            color: "FF0000".to_string(),
+            color_space: ColorSpace::Rgb,
+            brightness: 1.0,
+        }
+    }
+
+    #[test]
+    fn test_idle_fades_in_and_cuts_out() {
+        let mut idle = IdleAnimator::new();
+        let mut frame = vec![0u8; 6];
+
+        // Within the timeout nothing changes
+        idle.update(&settings(60.0), false, &mut frame);
+        assert_eq!(frame, vec![0; 6]);
+
+        // Past the timeout (and the fade), the idle color shows
+        idle.last_activity = Instant::now() - std::time::Duration::from_secs(10);
+        idle.update(&settings(1.0), false, &mut frame);
+        assert_eq!(frame, vec![255, 0, 0, 255, 0, 0]);
+
+        // Activity returns the mode's own frame straight away
+        let mut frame = vec![0u8; 6];
+        idle.update(&settings(1.0), true, &mut frame);
+        assert_eq!(frame, vec![0; 6]);
+        idle.update(&settings(1.0), false, &mut frame);
+        assert_eq!(frame, vec![0; 6]);
+    }
+}
@@ -0,0 +1,423 @@
+// Latency Module - Measure DDP frame delivery timing per device and report jitter statistics,
+// and calibrate ddp_delay_ms from the audio round trip (output click heard back on the input)
+use anyhow::{anyhow, Result};
+use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
+use cpal::{FromSample, Sample, SampleFormat, SizedSample};
+use ddp_rs::connection::DDPConnection;
+use ddp_rs::protocol::{PixelConfig, ID};
+use std::net::UdpSocket;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use crate::audio;
+use crate::cli;
+use crate::pacer;
+use crate::config::{BandwidthConfig, WLEDDeviceConfig};
+use crate::virtual_device;
+
+// Timeout for each HTTP ping to WLED's JSON API
+const PING_TIMEOUT: Duration = Duration::from_secs(2);
+
+// Pings are spread over the run at this interval
+const PING_INTERVAL: Duration = Duration::from_millis(500);
+
+// Calibration click - a short full-scale burst of a 2 kHz tone
+const CLICK_SECS: f64 = 0.004;
+const CLICK_HZ: f64 = 2000.0;
+
+// How long to listen for each click before counting it as missed
+const CLICK_TIMEOUT: Duration = Duration::from_millis(1500);
+
+// Quiet time spent measuring the room before the first click, and between clicks
+const NOISE_WINDOW: Duration = Duration::from_millis(1000);
+const CLICK_GAP: Duration = Duration::from_millis(600);
+
+/// Summary of a set of millisecond samples
+#[derive(Debug, Clone, PartialEq)]
+pub struct TimingStats {
+    pub count: usize,
+    pub mean: f64,
+    pub stddev: f64,
+    pub p50: f64,
+    pub p95: f64,
+    pub p99: f64,
+    pub max: f64,
+}
+
+impl TimingStats {
+    pub fn from_samples(samples: &[f64]) -> Option<Self> {
+        if samples.is_empty() {
+            return None;
+        }
+        let mut sorted = samples.to_vec();
+        sorted.sort_by(|a, b| a.total_cmp(b));
+
+        let count = sorted.len();
+        let mean = sorted.iter().sum::<f64>() / count as f64;
+        let variance = sorted.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / count as f64;
+        let percentile = |p: f64| sorted[((count - 1) as f64 * p).round() as usize];
+
+        Some(Self {
+            count,
+            mean,
+            stddev: variance.sqrt(),
+            p50: percentile(0.50),
+            p95: percentile(0.95),
+            p99: percentile(0.99),
+            max: sorted[count - 1],
+        })
+    }
+}
+
+/// Measured delivery for one device
+pub struct DeviceTiming {
+    pub ip: String,
+    pub frames_sent: usize,
+    pub send_errors: usize,
+    pub spacing: Option<TimingStats>,  // Time between consecutive sends (ms) - ideally 1000/fps
+    pub send_time: Option<TimingStats>,  // Time spent in the socket write (ms) - grows under backpressure
+    pub ping: Option<TimingStats>,  // HTTP round trip to /json/info (ms)
+    pub pings_lost: usize,
+    pub wled_fps: Option<u64>,  // Frame rate WLED itself reports receiving
+}
+
+// Send black frames at the target fps for the duration, timing every send
+fn measure_device(device: &WLEDDeviceConfig, fps: f64, duration: Duration, ping: bool) -> Result<DeviceTiming> {
+    let socket = UdpSocket::bind("0.0.0.0:0")?;
+    let mut conn = DDPConnection::try_new(&virtual_device::ddp_address(&device.ip), PixelConfig::default(), ID::Default, socket)
+        .map_err(|e| anyhow!("{}: {:?}", device.ip, e))?;
+
+    let frame = vec![0u8; device.led_count * 3];
+    let interval = Duration::from_secs_f64(1.0 / fps.max(1.0));
+    let mut spacing = Vec::new();
+    let mut send_time = Vec::new();
+    let mut pings = Vec::new();
+    let mut pings_lost = 0;
+    let mut send_errors = 0;
+    let mut wled_fps = None;
+
+    let start = Instant::now();
+    let mut next_send = start;
+    let mut last_send: Option<Instant> = None;
+    let mut next_ping = start;
+
+    while start.elapsed() < duration {
+        pacer::sleep_until(next_send);
+
+        let before = Instant::now();
+        if conn.write(&frame).is_err() {
+            send_errors += 1;
+        }
+        let after = Instant::now();
+        send_time.push((after - before).as_secs_f64() * 1000.0);
+        if let Some(last) = last_send {
+            spacing.push((before - last).as_secs_f64() * 1000.0);
+        }
+        last_send = Some(before);
+        next_send += interval;
+
+        // Pings run inline, so they show up as spacing spikes - that's the cost of a blocking HTTP call
+        if ping && after >= next_ping {
+            let ping_start = Instant::now();
+            match cli::fetch_wled_info(&device.ip, PING_TIMEOUT) {
+                Some(info) => {
+                    pings.push(ping_start.elapsed().as_secs_f64() * 1000.0);
+                    wled_fps = info["leds"]["fps"].as_u64().or(wled_fps);
+                }
+                None => pings_lost += 1,
+            }
+            next_ping = Instant::now() + PING_INTERVAL;
+            // Don't try to catch up on frames missed during the ping
+            next_send = Instant::now();
+        }
+    }
+
+    Ok(DeviceTiming {
+        ip: device.ip.clone(),
+        frames_sent: send_time.len(),
+        send_errors,
+        spacing: TimingStats::from_samples(&spacing),
+        send_time: TimingStats::from_samples(&send_time),
+        ping: TimingStats::from_samples(&pings),
+        pings_lost,
+        wled_fps,
+    })
+}
+
+fn print_stats(label: &str, stats: &Option<TimingStats>) {
+    match stats {
+        Some(s) => println!(
+            "  {:<12} mean {:>7.2}  stddev {:>6.2}  p50 {:>7.2}  p95 {:>7.2}  p99 {:>7.2}  max {:>7.2} ms",
+            label, s.mean, s.stddev, s.p50, s.p95, s.p99, s.max
+        ),
+        None => println!("  {:<12} no samples", label),
+    }
+}
+
+/// Measure every enabled device for `seconds` at the configured fps and print timing statistics
+pub fn run(seconds: f64, ping: bool) -> Result<()> {
+    let config = BandwidthConfig::load()?;
+    let devices: Vec<WLEDDeviceConfig> = config.wled_devices.iter()
+        .filter(|d| d.enabled && d.backup_for.is_empty())
+        .cloned()
+        .collect();
+    if devices.is_empty() {
+        return Err(anyhow!("No enabled devices configured"));
+    }
+
+    let duration = Duration::from_secs_f64(seconds.max(1.0));
+    let target_ms = 1000.0 / config.fps.max(1.0);
+    println!(
+        "Sending black frames to {} device(s) at {:.1} fps ({:.2} ms/frame) for {:.0}s{}...",
+        devices.len(),
+        config.fps,
+        target_ms,
+        duration.as_secs_f64(),
+        if ping { " with HTTP pings" } else { "" }
+    );
+
+    // All devices at once, like a real mode would drive them
+    let handles: Vec<_> = devices.into_iter()
+        .map(|device| {
+            let fps = config.fps;
+            std::thread::spawn(move || measure_device(&device, fps, duration, ping))
+        })
+        .collect();
+
+    for handle in handles {
+        let timing = match handle.join().map_err(|_| anyhow!("Measurement thread panicked"))? {
+            Ok(timing) => timing,
+            Err(e) => {
+                println!("\n{}", e);
+                continue;
+            }
+        };
+
+        println!("\n{} - {} frames sent, {} send errors", timing.ip, timing.frames_sent, timing.send_errors);
+        print_stats("spacing", &timing.spacing);
+        print_stats("socket send", &timing.send_time);
+        if ping {
+            print_stats("http ping", &timing.ping);
+            let total_pings = timing.pings_lost + timing.ping.as_ref().map(|p| p.count).unwrap_or(0);
+            if total_pings > 0 {
+                println!("  {:<12} {} of {} lost ({:.0}%)", "ping loss", timing.pings_lost, total_pings, timing.pings_lost as f64 * 100.0 / total_pings as f64);
+            }
+            if let Some(fps) = timing.wled_fps {
+                println!("  {:<12} WLED reports {} fps received", "wled fps", fps);
+            }
+        }
+
+        // Hints for the two settings this is meant to help tune
+        if let Some(spacing) = &timing.spacing {
+            if spacing.p95 > target_ms * 1.5 {
+                println!("  hint: frames often arrive late - try a lower fps");
+            } else if spacing.stddev > target_ms * 0.25 {
+                println!("  hint: high jitter - a small ddp_delay_ms buffer can smooth playback");
+            }
+        }
+    }
+    Ok(())
+}
+
+/// First frame of an interleaved sample block where any channel reaches `threshold`
+fn detect_onset(samples: &[f32], channels: usize, threshold: f32) -> Option<usize> {
+    samples.chunks(channels.max(1)).position(|frame| frame.iter().any(|s| s.abs() >= threshold))
+}
+
+// Click timing shared between the output and input audio callbacks
+#[derive(Default)]
+struct ClickState {
+    sent_at: Option<Instant>,  // When the output callback wrote the current click
+    heard: Option<Duration>,  // Round trip for the current click, once the input detects it
+    noise_peak: f32,  // Loudest input level while no click is in flight
+    threshold: f32,  // Detection level, set from the noise peak before clicking starts
+}
+
+fn build_click_output<T: SizedSample + FromSample<f32>>(device: &cpal::Device, config: &cpal::StreamConfig, fire: Arc<AtomicBool>, state: Arc<Mutex<ClickState>>) -> Result<cpal::Stream> {
+    let channels = config.channels as usize;
+    let rate = config.sample_rate.0 as f64;
+    let click_frames = (CLICK_SECS * rate) as usize;
+    let mut click_pos: Option<usize> = None;
+    let stream = device.build_output_stream(
+        config,
+        move |data: &mut [T], _| {
+            if click_pos.is_none() && fire.swap(false, Ordering::Relaxed) {
+                click_pos = Some(0);
+                let mut state = state.lock().unwrap();
+                state.sent_at = Some(Instant::now());
+                state.heard = None;
+            }
+            for frame in data.chunks_mut(channels) {
+                let value = match click_pos {
+                    Some(pos) if pos < click_frames => {
+                        click_pos = Some(pos + 1);
+                        (2.0 * std::f64::consts::PI * CLICK_HZ * pos as f64 / rate).sin() as f32
+                    }
+                    _ => {
+                        click_pos = None;
+                        0.0
+                    }
+                };
+                for sample in frame.iter_mut() {
+                    *sample = T::from_sample(value);
+                }
+            }
+        },
+        |err| eprintln!("Audio output error: {}", err),
+        None,
+    )?;
+    Ok(stream)
+}
+
+fn build_click_input<T: SizedSample>(device: &cpal::Device, config: &cpal::StreamConfig, state: Arc<Mutex<ClickState>>) -> Result<cpal::Stream>
+where
+    f32: FromSample<T>,
+{
+    let channels = config.channels as usize;
+    let rate = config.sample_rate.0 as f64;
+    let mut block = Vec::new();
+    let stream = device.build_input_stream(
+        config,
+        move |data: &[T], _| {
+            let now = Instant::now();
+            block.clear();
+            block.extend(data.iter().map(|&s| f32::from_sample(s)));
+
+            let mut state = state.lock().unwrap();
+            match state.sent_at {
+                Some(sent_at) if state.heard.is_none() && state.threshold > 0.0 => {
+                    // The block ends now - step back to when the onset frame was captured
+                    if let Some(frame) = detect_onset(&block, channels, state.threshold) {
+                        let frames_after = (block.len() / channels.max(1)).saturating_sub(frame);
+                        let captured_at = now - Duration::from_secs_f64(frames_after as f64 / rate);
+                        state.heard = Some(captured_at.saturating_duration_since(sent_at));
+                    }
+                }
+                None => {
+                    let peak = block.iter().fold(0.0_f32, |max, s| max.max(s.abs()));
+                    state.noise_peak = state.noise_peak.max(peak);
+                }
+                _ => {}
+            }
+        },
+        |err| eprintln!("Audio input error: {}", err),
+        None,
+    )?;
+    Ok(stream)
+}
+
+/// Play `clicks` clicks through the default output device, time how long each takes to reach the
+/// input (the live mode audio device), and suggest - or with `write`, save - that as ddp_delay_ms
+pub fn calibrate_audio(clicks: usize, write: bool) -> Result<()> {
+    let mut config = BandwidthConfig::load()?;
+    let host = cpal::default_host();
+    let output = host.default_output_device().ok_or_else(|| anyhow!("No default audio output device"))?;
+    let input = if config.audio.device.is_empty() {
+        host.default_input_device().ok_or_else(|| anyhow!("No default audio input device"))?
+    } else {
+        audio::find_audio_device(&config.audio.device)?
+    };
+    println!("Output: {}", output.name().unwrap_or_default());
+    println!("Input:  {}", input.name().unwrap_or_default());
+
+    let fire = Arc::new(AtomicBool::new(false));
+    let state = Arc::new(Mutex::new(ClickState::default()));
+
+    let output_config = output.default_output_config()?;
+    let output_stream = match output_config.sample_format() {
+        SampleFormat::F32 => build_click_output::<f32>(&output, &output_config.into(), fire.clone(), state.clone())?,
+        SampleFormat::I16 => build_click_output::<i16>(&output, &output_config.into(), fire.clone(), state.clone())?,
+        SampleFormat::U16 => build_click_output::<u16>(&output, &output_config.into(), fire.clone(), state.clone())?,
+        format => return Err(anyhow!("Unsupported output sample format {:?}", format)),
+    };
+    let input_config = input.default_input_config()?;
+    let input_stream = match input_config.sample_format() {
+        SampleFormat::F32 => build_click_input::<f32>(&input, &input_config.into(), state.clone())?,
+        SampleFormat::I16 => build_click_input::<i16>(&input, &input_config.into(), state.clone())?,
+        SampleFormat::U16 => build_click_input::<u16>(&input, &input_config.into(), state.clone())?,
+        format => return Err(anyhow!("Unsupported input sample format {:?}", format)),
+    };
+    output_stream.play()?;
+    input_stream.play()?;
+
+    // Listen to the room first so the click has to stand out from it
+    println!("Measuring background noise - keep the room quiet...");
+    std::thread::sleep(NOISE_WINDOW);
+    {
+        let mut state = state.lock().unwrap();
+        state.threshold = (state.noise_peak * 4.0).clamp(0.02, 0.9);
+        println!("Noise peak {:.3}, detecting clicks above {:.3}", state.noise_peak, state.threshold);
+    }
+
+    let mut round_trips = Vec::new();
+    for n in 1..=clicks.max(1) {
+        fire.store(true, Ordering::Relaxed);
+        let started = Instant::now();
+        let heard = loop {
+            std::thread::sleep(Duration::from_millis(5));
+            if let Some(heard) = state.lock().unwrap().heard {
+                break Some(heard);
+            }
+            if started.elapsed() > CLICK_TIMEOUT {
+                break None;
+            }
+        };
+        match heard {
+            Some(heard) => {
+                let ms = heard.as_secs_f64() * 1000.0;
+                println!("  click {}: {:.1} ms", n, ms);
+                round_trips.push(ms);
+            }
+            None => println!("  click {}: not heard", n),
+        }
+
+        // Let the click (and any echo) die away before the next one
+        state.lock().unwrap().sent_at = None;
+        std::thread::sleep(CLICK_GAP);
+    }
+    drop(output_stream);
+    drop(input_stream);
+
+    let stats = TimingStats::from_samples(&round_trips)
+        .ok_or_else(|| anyhow!("No clicks were heard - turn the speakers up or move the microphone closer"))?;
+    print_stats("round trip", &Some(stats.clone()));
+    let delay_ms = stats.p50.round();
+    if stats.stddev > 10.0 {
+        println!("  hint: results vary a lot - check for echoes or background noise and run it again");
+    }
+
+    if write {
+        config.ddp_delay_ms = delay_ms;
+        config.save()?;
+        println!("Set ddp_delay_ms = {}", delay_ms);
+    } else {
+        println!("Suggested ddp_delay_ms = {} (run with --write to save it)", delay_ms);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_timing_stats() {
+        assert!(TimingStats::from_samples(&[]).is_none());
+
+        let stats = TimingStats::from_samples(&[4.0, 2.0, 6.0, 8.0]).unwrap();
+        assert_eq!(stats.count, 4);
+        assert_eq!(stats.mean, 5.0);
+        assert_eq!(stats.max, 8.0);
+        assert!((stats.stddev - 5.0f64.sqrt()).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_detect_onset() {
+        // Stereo block with the click arriving on the right channel of frame 2
+        let samples = [0.01, 0.0, -0.02, 0.01, 0.0, 0.5, 0.9, 0.9];
+        assert_eq!(detect_onset(&samples, 2, 0.1), Some(2));
+        assert_eq!(detect_onset(&samples, 1, 0.1), Some(5));
+        assert_eq!(detect_onset(&samples, 2, 0.95), None);
+    }
+}
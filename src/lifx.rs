@@ -0,0 +1,168 @@
+// LIFX Module - LIFX bulbs over the LAN protocol (UDP): each bulb shows the average color of an LED zone
+use std::net::UdpSocket;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, Instant};
+use tokio::sync::broadcast;
+
+use crate::config::{self, BandwidthConfig, ConfigDelta, LifxDeviceConfig};
+use crate::multi_device;
+
+/// UDP port LIFX devices listen on
+pub const LIFX_PORT: u16 = 56700;
+
+// Bulbs handle about 20 messages a second - each color change fades over the same interval
+const SEND_INTERVAL: Duration = Duration::from_millis(50);
+
+// Resend unchanged colors this often, in case a packet was lost
+const REFRESH_INTERVAL: Duration = Duration::from_secs(1);
+
+// LAN protocol header: frame (8 bytes), frame address (16), protocol header (12)
+const HEADER_LEN: usize = 36;
+const PROTOCOL: u16 = 1024;
+const ADDRESSABLE: u16 = 0x1000;
+const TAGGED: u16 = 0x2000;  // No target MAC - any bulb at the address takes the message
+const SET_COLOR: u16 = 102;
+const KELVIN: u16 = 3500;  // Ignored while saturation is up, white point for unsaturated colors
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+static DEVICES: Mutex<Vec<LifxDeviceConfig>> = Mutex::new(Vec::new());
+static COLORS: Mutex<Vec<[u8; 3]>> = Mutex::new(Vec::new());  // One per device, same order
+
+/// Take the bulbs from the config - no lifx_devices turns the backend off
+pub fn configure(config: &BandwidthConfig) {
+    let devices: Vec<_> = config.lifx_devices.iter().filter(|d| d.enabled).cloned().collect();
+    ENABLED.store(!devices.is_empty(), Ordering::Relaxed);
+    *DEVICES.lock().unwrap() = devices;
+}
+
+/// Downsample an output frame to the bulbs - called for every frame sent to the WLED devices
+pub fn update(frame: &[u8]) {
+    if !ENABLED.load(Ordering::Relaxed) {
+        return;
+    }
+    let colors = DEVICES.lock().unwrap().iter()
+        .map(|d| multi_device::average_zone(frame, d.led_offset, d.led_count))
+        .collect();
+    *COLORS.lock().unwrap() = colors;
+}
+
+/// Send the bulbs' colors in the background - only changes, plus a periodic refresh
+pub fn spawn_streamer() {
+    thread::spawn(|| {
+        let socket = match UdpSocket::bind("0.0.0.0:0") {
+            Ok(socket) => socket,
+            Err(e) => {
+                eprintln!("⚠️  LIFX output unavailable: {}", e);
+                return;
+            }
+        };
+        let mut sent: Vec<[u8; 3]> = Vec::new();
+        let mut last_refresh = Instant::now();
+        let mut sequence = 0u8;
+        loop {
+            thread::sleep(SEND_INTERVAL);
+            let devices = DEVICES.lock().unwrap().clone();
+            let colors = COLORS.lock().unwrap().clone();
+            let refresh = last_refresh.elapsed() >= REFRESH_INTERVAL;
+            if refresh {
+                last_refresh = Instant::now();
+            }
+
+            for (i, (device, color)) in devices.iter().zip(&colors).enumerate() {
+                if !refresh && sent.get(i) == Some(color) {
+                    continue;
+                }
+                let message = set_color_message(sequence, *color, SEND_INTERVAL);
+                if let Err(e) = socket.send_to(&message, (device.ip.as_str(), LIFX_PORT)) {
+                    if refresh {
+                        eprintln!("⚠️  LIFX send to {} failed: {}", device.ip, e);
+                    }
+                }
+                sequence = sequence.wrapping_add(1);
+            }
+            sent = colors;
+        }
+    });
+}
+
+/// Keep the bulbs in step with config changes (web UI, API, or file edits)
+pub fn spawn_watcher(config_change_tx: broadcast::Sender<ConfigDelta>) {
+    config::spawn_config_listener(&config_change_tx, &["lifx_devices"], configure);
+}
+
+/// SetColor (102) for every bulb at the address, fading over `duration`
+fn set_color_message(sequence: u8, rgb: [u8; 3], duration: Duration) -> Vec<u8> {
+    let [hue, saturation, brightness] = rgb_to_hsb(rgb);
+    let size = HEADER_LEN + 13;
+
+    let mut message = Vec::with_capacity(size);
+    // Frame
+    message.extend_from_slice(&(size as u16).to_le_bytes());
+    message.extend_from_slice(&(PROTOCOL | ADDRESSABLE | TAGGED).to_le_bytes());
+    message.extend_from_slice(&std::process::id().to_le_bytes());  // Source
+    // Frame address - no target, no ack or response wanted
+    message.extend_from_slice(&[0u8; 8]);
+    message.extend_from_slice(&[0u8; 6]);
+    message.push(0);
+    message.push(sequence);
+    // Protocol header
+    message.extend_from_slice(&[0u8; 8]);
+    message.extend_from_slice(&SET_COLOR.to_le_bytes());
+    message.extend_from_slice(&[0u8; 2]);
+    // Payload: reserved, HSBK, duration in ms
+    message.push(0);
+    for value in [hue, saturation, brightness, KELVIN] {
+        message.extend_from_slice(&value.to_le_bytes());
+    }
+    message.extend_from_slice(&(duration.as_millis() as u32).to_le_bytes());
+    message
+}
+
+/// RGB to LIFX hue, saturation and brightness (each 0-65535)
+fn rgb_to_hsb([r, g, b]: [u8; 3]) -> [u16; 3] {
+    let (r, g, b) = (r as f64 / 255.0, g as f64 / 255.0, b as f64 / 255.0);
+    let max = r.max(g).max(b);
+    let delta = max - r.min(g).min(b);
+
+    // Sector of the color wheel (0-6), one per primary and secondary color
+    let sector = if delta == 0.0 {
+        0.0
+    } else if max == r {
+        ((g - b) / delta).rem_euclid(6.0)
+    } else if max == g {
+        (b - r) / delta + 2.0
+    } else {
+        (r - g) / delta + 4.0
+    };
+    let hue = sector / 6.0;
+    let saturation = if max == 0.0 { 0.0 } else { delta / max };
+
+    [hue, saturation, max].map(|v| (v * 65535.0).round() as u16)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rgb_to_hsb() {
+        assert_eq!(rgb_to_hsb([255, 0, 0]), [0, 65535, 65535]);
+        assert_eq!(rgb_to_hsb([0, 0, 255]), [43690, 65535, 65535]);
+        assert_eq!(rgb_to_hsb([0, 0, 0]), [0, 0, 0]);
+        assert_eq!(rgb_to_hsb([255, 255, 255]), [0, 0, 65535]);
+    }
+
+    #[test]
+    fn test_set_color_message() {
+        let message = set_color_message(9, [255, 0, 0], Duration::from_millis(50));
+        assert_eq!(message.len(), 49);
+        assert_eq!(u16::from_le_bytes([message[0], message[1]]), 49);
+        assert_eq!(u16::from_le_bytes([message[2], message[3]]), 0x3400);
+        assert_eq!(message[23], 9);
+        assert_eq!(u16::from_le_bytes([message[32], message[33]]), SET_COLOR);
+        // Hue 0, full saturation and brightness, 3500K, 50ms
+        assert_eq!(&message[37..49], &[0, 0, 0xFF, 0xFF, 0xFF, 0xFF, 0xAC, 0x0D, 50, 0, 0, 0]);
+    }
+}
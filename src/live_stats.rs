@@ -0,0 +1,318 @@
+// Live Stats Module - Runtime values published by the running mode for the /api/events/stats stream
+use serde::Serialize;
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+// Values not refreshed within this window belong to a mode that is no longer running
+const STALE_AFTER: Duration = Duration::from_secs(2);
+
+// Window used to count frames for the FPS figure
+const FPS_WINDOW: Duration = Duration::from_secs(1);
+
+// Weight of each new sample in the smoothed frame timings
+const PROFILE_SMOOTHING: f64 = 0.1;
+
+// How long a background notice stays in the TUI footer
+const NOTICE_FOR: Duration = Duration::from_secs(10);
+
+#[derive(Default)]
+struct LiveStats {
+    bandwidth: Option<(f64, f64, Instant)>,    // (rx kbps, tx kbps, updated)
+    audio: Option<(f32, f32, Instant)>,        // (left level, right level, updated)
+    notes: Option<(Vec<ActiveNote>, Instant)>,
+    frame_times: VecDeque<Instant>,
+    devices: HashMap<String, DeviceSend>,
+    fps_limits: HashMap<String, f64>,  // Devices sent at a reduced rate because their network can't keep up
+    profile: FrameProfile,
+    notice: Option<(String, Instant)>,  // Latest message from a background task, shown in the TUI footer
+}
+
+// Where each frame's time goes, smoothed - render is reported by the mode, the rest by the output stage
+#[derive(Default)]
+struct FrameProfile {
+    render: Option<(f64, Instant)>,    // (microseconds, updated)
+    postfx: Option<(f64, Instant)>,    // Alerts, post-processing, color correction and brightness
+    send: Option<(f64, Instant)>,      // Handing the frame to every device
+    buffer_depth: Option<(usize, Instant)>,  // Frames waiting out ddp_delay_ms
+}
+
+struct DeviceSend {
+    error: Option<String>,
+    at: Instant,
+}
+
+/// Currently held MIDI note
+#[derive(Debug, Clone, Serialize)]
+pub struct ActiveNote {
+    pub channel: u8,
+    pub note: u8,
+    pub velocity: u8,
+}
+
+/// Result of the most recent send to one device
+#[derive(Debug, Clone, Serialize)]
+pub struct DeviceSendStatus {
+    pub ip: String,
+    pub ok: bool,
+    pub error: Option<String>,
+    pub ms_since_send: u64,
+    pub fps_limit: Option<f64>,  // Reduced frame rate while the device can't keep up (None = full rate)
+}
+
+/// Smoothed per-frame timings (None for stages the current mode doesn't report)
+#[derive(Debug, Clone, Serialize)]
+pub struct FrameProfileSnapshot {
+    pub render_us: Option<f64>,
+    pub postfx_us: Option<f64>,
+    pub send_us: Option<f64>,
+    pub buffer_depth: Option<usize>,
+}
+
+/// Point-in-time view of the live stats (fields are None when the current mode doesn't produce them)
+#[derive(Debug, Clone, Serialize)]
+pub struct LiveStatsSnapshot {
+    pub rx_kbps: Option<f64>,
+    pub tx_kbps: Option<f64>,
+    pub audio_left: Option<f32>,
+    pub audio_right: Option<f32>,
+    pub active_notes: Option<Vec<ActiveNote>>,
+    pub fps: f64,
+    pub devices: Vec<DeviceSendStatus>,
+    pub profile: FrameProfileSnapshot,
+}
+
+fn stats() -> &'static Mutex<LiveStats> {
+    static STATS: OnceLock<Mutex<LiveStats>> = OnceLock::new();
+    STATS.get_or_init(|| Mutex::new(LiveStats::default()))
+}
+
+fn fresh(at: Instant, now: Instant) -> bool {
+    now.duration_since(at) < STALE_AFTER
+}
+
+/// Record the latest bandwidth reading (bandwidth mode)
+pub fn record_bandwidth(rx_kbps: f64, tx_kbps: f64) {
+    stats().lock().unwrap().bandwidth = Some((rx_kbps, tx_kbps, Instant::now()));
+}
+
+/// Record the current left/right audio levels (0.0-1.0, live mode)
+pub fn record_audio_levels(left: f32, right: f32) {
+    stats().lock().unwrap().audio = Some((left, right, Instant::now()));
+}
+
+/// Record the notes currently held (MIDI mode), as (channel, note, velocity)
+pub fn record_active_notes(notes: &[(u8, u8, u8)]) {
+    // Refill the previous list in place, since this runs every frame
+    let mut stats = stats().lock().unwrap();
+    let (held, at) = stats.notes.get_or_insert_with(|| (Vec::new(), Instant::now()));
+    held.clear();
+    held.extend(notes.iter().map(|&(channel, note, velocity)| ActiveNote { channel, note, velocity }));
+    *at = Instant::now();
+}
+
+/// Count a frame handed to the devices
+pub fn record_frame() {
+    let now = Instant::now();
+    let mut stats = stats().lock().unwrap();
+    stats.frame_times.push_back(now);
+    while let Some(&oldest) = stats.frame_times.front() {
+        if now.duration_since(oldest) > FPS_WINDOW {
+            stats.frame_times.pop_front();
+        } else {
+            break;
+        }
+    }
+}
+
+/// Record the outcome of a send to one device (None = success)
+pub fn record_device_send(ip: &str, error: Option<String>) {
+    let send = DeviceSend {
+        error,
+        at: Instant::now(),
+    };
+    // Update in place when the device is already known, so a send doesn't allocate a key
+    let mut stats = stats().lock().unwrap();
+    match stats.devices.get_mut(ip) {
+        Some(existing) => *existing = send,
+        None => {
+            stats.devices.insert(ip.to_string(), send);
+        }
+    }
+}
+
+// Fold a new timing sample into the running average (restarting it if the last one is stale)
+fn smooth(timing: &mut Option<(f64, Instant)>, elapsed: Duration) {
+    let micros = elapsed.as_secs_f64() * 1_000_000.0;
+    let now = Instant::now();
+    *timing = Some(match *timing {
+        Some((average, at)) if fresh(at, now) => (average + (micros - average) * PROFILE_SMOOTHING, now),
+        _ => (micros, now),
+    });
+}
+
+/// Record how long the mode took to render a frame
+pub fn record_render_time(elapsed: Duration) {
+    smooth(&mut stats().lock().unwrap().profile.render, elapsed);
+}
+
+/// Record how long the output stage took to post-process, correct and dim a frame
+pub fn record_postfx_time(elapsed: Duration) {
+    smooth(&mut stats().lock().unwrap().profile.postfx, elapsed);
+}
+
+/// Record how long sending a frame to the devices took
+pub fn record_send_time(elapsed: Duration) {
+    smooth(&mut stats().lock().unwrap().profile.send, elapsed);
+}
+
+/// Record how many frames are queued for sending (ddp_delay_ms buffer)
+pub fn record_buffer_depth(depth: usize) {
+    stats().lock().unwrap().profile.buffer_depth = Some((depth, Instant::now()));
+}
+
+fn profile_snapshot(profile: &FrameProfile, now: Instant) -> FrameProfileSnapshot {
+    let timing = |t: Option<(f64, Instant)>| t.filter(|&(_, at)| fresh(at, now)).map(|(micros, _)| micros);
+    FrameProfileSnapshot {
+        render_us: timing(profile.render),
+        postfx_us: timing(profile.postfx),
+        send_us: timing(profile.send),
+        buffer_depth: profile.buffer_depth.filter(|&(_, at)| fresh(at, now)).map(|(depth, _)| depth),
+    }
+}
+
+/// Per-frame timing breakdown for the TUI footers
+pub fn profile_line() -> String {
+    let stats = stats().lock().unwrap();
+    let mut line = format_profile(&profile_snapshot(&stats.profile, Instant::now()));
+
+    // Devices held to a lower rate, so a drop in FPS isn't a mystery
+    let mut limits: Vec<_> = stats.fps_limits.iter().collect();
+    limits.sort_by(|a, b| a.0.cmp(b.0));
+    for (ip, fps) in limits {
+        line.push_str(&format!(" | {} throttled to {:.0} fps", ip, fps));
+    }
+
+    if let Some((message, at)) = &stats.notice {
+        if at.elapsed() < NOTICE_FOR {
+            line.push_str(" | ");
+            line.push_str(message);
+        }
+    }
+    line
+}
+
+fn format_profile(profile: &FrameProfileSnapshot) -> String {
+    let micros = |t: Option<f64>| t.map_or("-".to_string(), |us| format!("{:.0}µs", us));
+    format!(
+        "Render: {} | Post-process: {} | Send: {} | Buffer: {}",
+        micros(profile.render_us),
+        micros(profile.postfx_us),
+        micros(profile.send_us),
+        profile.buffer_depth.map_or("-".to_string(), |depth| depth.to_string()),
+    )
+}
+
+/// Show a message from a background task in the TUI footer for a few seconds
+/// (printing would scramble the screen while the TUI is drawn in raw mode)
+pub fn record_notice(message: String) {
+    stats().lock().unwrap().notice = Some((message, Instant::now()));
+}
+
+/// Record a device's reduced frame rate (None = back to the full rate)
+pub fn record_fps_limit(ip: &str, fps: Option<f64>) {
+    let mut stats = stats().lock().unwrap();
+    match fps {
+        Some(fps) => {
+            stats.fps_limits.insert(ip.to_string(), fps);
+        }
+        None => {
+            stats.fps_limits.remove(ip);
+        }
+    }
+}
+
+/// Current stats, with values from stopped modes left out
+pub fn snapshot() -> LiveStatsSnapshot {
+    snapshot_of(&stats().lock().unwrap(), Instant::now())
+}
+
+fn snapshot_of(stats: &LiveStats, now: Instant) -> LiveStatsSnapshot {
+    let bandwidth = stats.bandwidth.filter(|&(_, _, at)| fresh(at, now));
+    let audio = stats.audio.filter(|&(_, _, at)| fresh(at, now));
+    let active_notes = stats.notes.as_ref()
+        .filter(|(_, at)| fresh(*at, now))
+        .map(|(notes, _)| notes.clone());
+
+    let fps = stats.frame_times.iter()
+        .filter(|&&t| now.duration_since(t) <= FPS_WINDOW)
+        .count() as f64 / FPS_WINDOW.as_secs_f64();
+
+    let mut devices: Vec<DeviceSendStatus> = stats.devices.iter()
+        .map(|(ip, send)| DeviceSendStatus {
+            ip: ip.clone(),
+            ok: send.error.is_none(),
+            error: send.error.clone(),
+            ms_since_send: now.duration_since(send.at).as_millis() as u64,
+            fps_limit: stats.fps_limits.get(ip).copied(),
+        })
+        .collect();
+    devices.sort_by(|a, b| a.ip.cmp(&b.ip));
+
+    LiveStatsSnapshot {
+        rx_kbps: bandwidth.map(|(rx, _, _)| rx),
+        tx_kbps: bandwidth.map(|(_, tx, _)| tx),
+        audio_left: audio.map(|(left, _, _)| left),
+        audio_right: audio.map(|(_, right, _)| right),
+        active_notes,
+        fps,
+        devices,
+        profile: profile_snapshot(&stats.profile, now),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_smooth() {
+        let mut timing = None;
+        smooth(&mut timing, Duration::from_micros(100));
+        assert_eq!(timing.map(|(micros, _)| micros.round()), Some(100.0));
+        smooth(&mut timing, Duration::from_micros(200));
+        assert_eq!(timing.map(|(micros, _)| micros.round()), Some(110.0));
+    }
+
+    #[test]
+    fn test_format_profile() {
+        let profile = FrameProfileSnapshot { render_us: Some(1234.4), postfx_us: None, send_us: Some(87.0), buffer_depth: Some(2) };
+        assert_eq!(format_profile(&profile), "Render: 1234µs | Post-process: - | Send: 87µs | Buffer: 2");
+    }
+
+    #[test]
+    fn test_snapshot() {
+        let now = Instant::now();
+        let mut stats = LiveStats::default();
+        stats.bandwidth = Some((120.0, 80.0, now));
+        stats.audio = Some((0.5, 0.25, now - STALE_AFTER));  // Live mode stopped
+        stats.frame_times.extend([now - Duration::from_millis(1500), now - Duration::from_millis(500), now]);
+        stats.devices.insert("10.0.0.2".to_string(), DeviceSend { error: Some("timed out".to_string()), at: now });
+        stats.devices.insert("10.0.0.1".to_string(), DeviceSend { error: None, at: now - Duration::from_millis(20) });
+        stats.fps_limits.insert("10.0.0.2".to_string(), 15.0);
+
+        let snapshot = snapshot_of(&stats, now);
+        assert_eq!((snapshot.rx_kbps, snapshot.tx_kbps), (Some(120.0), Some(80.0)));
+        assert_eq!((snapshot.audio_left, snapshot.audio_right), (None, None));
+        assert!(snapshot.active_notes.is_none());
+        assert_eq!(snapshot.fps, 2.0);
+        let devices: Vec<_> = snapshot.devices.iter().map(|d| (d.ip.as_str(), d.ok, d.ms_since_send, d.fps_limit)).collect();
+        assert_eq!(devices, vec![("10.0.0.1", true, 20, None), ("10.0.0.2", false, 0, Some(15.0))]);
+
+        // Streamed as JSON, stopped modes' fields as null
+        let json = serde_json::to_value(&snapshot).unwrap();
+        assert_eq!(json["rx_kbps"], 120.0);
+        assert!(json["audio_left"].is_null());
+        assert_eq!(json["devices"][1]["error"], "timed out");
+    }
+}
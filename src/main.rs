@@ -9,7 +9,7 @@ use crossterm::terminal::{
 use crossterm::ExecutableCommand;
 use ddp_rs::connection::DDPConnection;
 use ddp_rs::protocol::{PixelConfig, ID};
-use notify::{Config, Event as NotifyEvent, RecommendedWatcher, RecursiveMode, Watcher};
+use notify::{Config, RecommendedWatcher, RecursiveMode, Watcher};
 use ratatui::backend::CrosstermBackend;
 use ratatui::layout::{Alignment, Constraint, Direction, Layout};
 use ratatui::text::{Line, Span};
@@ -19,7 +19,7 @@ use ratatui::Terminal;
 use std::io::{self, Write};
 use std::net::UdpSocket;
 use std::process::Stdio;
-use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
 use std::sync::{mpsc, Arc, Mutex};
 use std::thread;
 use std::time::{Duration, Instant, SystemTime};
@@ -33,6 +33,7 @@ mod types;
 mod gradients;
 mod renderer;
 mod httpd;
+mod assets;
 mod relay;
 mod webcam;
 mod tron;
@@ -41,16 +42,80 @@ mod sand;
 mod config;
 mod multi_device;
 mod cert;
+mod auth;
+mod backup;
+mod sync;
+mod live_stats;
+mod rate_limit;
+mod system;
+mod cli;
+mod recording;
+mod bench;
+mod doctor;
+mod test_patterns;
+mod test_tui;
+mod latency;
+mod alloc_track;
+mod pacer;
+mod postfx;
+mod gradient_anim;
+mod idle;
+mod strobe;
+mod vu;
+mod spectrogram;
+mod matrix;
+mod fft;
+mod fade;
+mod font;
+mod dashboard;
+mod adaptive_fps;
+mod preset_morph;
+mod alert;
+mod demo;
+mod virtual_device;
+mod ambient;
+mod gpio;
+#[cfg(feature = "hue")]
+mod hue;
+mod lifx;
+mod nanoleaf;
+mod sacn;
+mod artnet;
+mod boblight;
+mod piano_roll;
+mod spectrum_bars;
+mod screen_zones;
+mod wled_state;
+mod supervisor;
+mod remote_link;
+mod agent;
+mod bandwidth_history;
+mod quiet_hours;
+mod segments;
+mod frame_interpolation;
+mod signal_generator;
+mod discovery;
+#[cfg(test)]
+mod pipeline_tests;
 
 // Import shared types
-use types::{ModeExitReason, InterpolationMode, Rgb, build_gradient_from_color};
-use multi_device::{MultiDeviceConfig, MultiDeviceManager, WLEDDevice};
+use types::{ModeExitReason, ColorSpace, Easing, InterpolationMode, Rgb, build_gradient_from_color, GradientCache, GradientLut};
+use multi_device::{ColorCorrection, MultiDeviceConfig, MultiDeviceManager, WLEDDevice};
 
 // Import renderer types
-use renderer::{DirectionMode, SharedRenderState, Renderer};
+use renderer::{DirectionMode, RenderStateCell, SharedRenderState, Renderer};
+use alloc_track::FrameAllocCounter;
+use pacer::FramePacer;
+use idle::{IdleAnimator, IdleSettings};
+use strobe::{Strobe, StrobeSettings};
+use matrix::MatrixLayout;
+use fft::WindowFunction;
+use piano_roll::PianoRoll;
+use spectrum_bars::SpectrumBars;
+use vu::{VuBallistics, VuScale};
 
 // Import config types
-use config::{Args, BandwidthConfig, resolve_tx_rx_colors};
+use config::{Args, BandwidthConfig, ConfigDelta, resolve_tx_rx_colors, resolve_vu_colors};
 
 // Detect OS type (Darwin/Linux) via uname
 async fn detect_os(ssh_target: Option<&str>) -> Result<String> {
@@ -70,8 +135,8 @@ async fn detect_os(ssh_target: Option<&str>) -> Result<String> {
     Ok(os_name)
 }
 
-// Spawn bandwidth monitoring command based on OS
-async fn spawn_bandwidth_monitor(args: &Args, config: &BandwidthConfig) -> Result<tokio::process::Child> {
+// Remote host (and user) to monitor over SSH, if any
+fn remote_target(args: &Args, config: &BandwidthConfig) -> Option<(String, Option<String>)> {
     // Priority: config.ssh_host > args.host (for backwards compatibility)
     let ssh_host = if !config.ssh_host.is_empty() {
         Some(&config.ssh_host)
@@ -81,14 +146,19 @@ async fn spawn_bandwidth_monitor(args: &Args, config: &BandwidthConfig) -> Resul
 
     // Use ssh_user from config (CLI doesn't have --user flag, but users can use user@host format in --host)
     let ssh_user = if !config.ssh_user.is_empty() {
-        Some(&config.ssh_user)
+        Some(config.ssh_user.clone())
     } else {
         None
     };
 
-    if let Some(host) = ssh_host {
+    ssh_host.map(|host| (host.clone(), ssh_user))
+}
+
+// Spawn bandwidth monitoring command based on OS
+async fn spawn_bandwidth_monitor(args: &Args, config: &BandwidthConfig) -> Result<tokio::process::Child> {
+    if let Some((host, ssh_user)) = remote_target(args, config) {
         // For remote hosts, use a single SSH connection that auto-detects OS and runs appropriate command
-        spawn_remote_monitor(host, ssh_user, &config.interface).await
+        spawn_remote_monitor(&host, ssh_user.as_ref(), &config.interface, false).await
     } else {
         // Local monitoring - detect OS
         let os = detect_os(None).await?;
@@ -106,7 +176,8 @@ async fn spawn_bandwidth_monitor(args: &Args, config: &BandwidthConfig) -> Resul
 }
 
 // Remote monitoring with OS auto-detection in a single SSH session
-async fn spawn_remote_monitor(host: &String, user: Option<&String>, interface: &str) -> Result<tokio::process::Child> {
+// Reconnects run unattended (the TUI owns the terminal), so they can't prompt for a password
+async fn spawn_remote_monitor(host: &String, user: Option<&String>, interface: &str, reconnect: bool) -> Result<tokio::process::Child> {
     // Construct SSH target: user@host or just host
     let ssh_target = if let Some(u) = user {
         format!("{}@{}", u, host)
@@ -134,13 +205,17 @@ fi
         interface, egrep_pattern
     );
 
-    let child = Command::new("ssh")
-        .arg(&ssh_target)
-        .arg(&script)
-        .stdin(Stdio::inherit())
-        .stdout(Stdio::piped())
-        .stderr(Stdio::inherit())
-        .spawn()?;
+    // Keepalives make ssh itself give up on a dead connection (after ~15s) instead of waiting forever
+    let mut command = Command::new("ssh");
+    command
+        .args(["-o", "ServerAliveInterval=5", "-o", "ServerAliveCountMax=3"])
+        .stdout(Stdio::piped());
+    if reconnect {
+        command.args(["-o", "BatchMode=yes"]).stdin(Stdio::null()).stderr(Stdio::null());
+    } else {
+        command.stdin(Stdio::inherit()).stderr(Stdio::inherit());
+    }
+    let child = command.arg(&ssh_target).arg(&script).spawn()?;
 
     Ok(child)
 }
@@ -241,15 +316,41 @@ struct InterfaceState {
 
 struct BandwidthTracker {
     interfaces: std::collections::HashMap<String, InterfaceState>,
+    rates: Vec<dashboard::InterfaceRate>,  // Latest rate per interface, in the order they were first seen
+    names: Vec<String>,  // Interface name for each entry in rates
 }
 
 impl BandwidthTracker {
     fn new() -> Self {
         BandwidthTracker {
             interfaces: std::collections::HashMap::new(),
+            rates: Vec::new(),
+            names: Vec::new(),
         }
     }
 
+    // Remember an interface's latest rate for the dashboard
+    fn record_rate(&mut self, iface: &str, rx_kbps: f64, tx_kbps: f64) {
+        let index = match self.names.iter().position(|name| name == iface) {
+            Some(index) => index,
+            None => {
+                self.names.push(iface.to_string());
+                self.rates.push(dashboard::InterfaceRate { label: dashboard::label(iface), ..Default::default() });
+                self.rates.len() - 1
+            }
+        };
+        self.rates[index].rx_kbps = rx_kbps;
+        self.rates[index].tx_kbps = tx_kbps;
+    }
+
+    // Combined rate of the interfaces heard from recently - ones whose host dropped out stop counting
+    fn totals(&self) -> (f64, f64) {
+        let now = Instant::now();
+        self.names.iter().zip(&self.rates)
+            .filter(|(name, _)| self.interfaces.get(*name).is_some_and(|state| now.duration_since(state.prev_time) < Duration::from_secs(5)))
+            .fold((0.0, 0.0), |(rx, tx), (_, rate)| (rx + rate.rx_kbps, tx + rate.tx_kbps))
+    }
+
     // Parse /proc/net/dev line and accumulate bandwidth
     // Returns Some when all interfaces have been processed (after collecting all lines)
     fn update_from_procnet_line(&mut self, line: &str) -> Option<(f64, f64)> {
@@ -294,6 +395,7 @@ impl BandwidthTracker {
                 );
 
                 // Return the bandwidth for this interface
+                self.record_rate(iface, rx_kbps, tx_kbps);
                 return Some((rx_kbps, tx_kbps));
             }
         }
@@ -362,20 +464,52 @@ fn parse_led_numbers(test_str: &str) -> Result<Vec<usize>> {
 }
 
 
-async fn test_mode(args: &Args) -> Result<()> {
-    use crate::multi_device::{MultiDeviceConfig, MultiDeviceManager, WLEDDevice};
+async fn test_mode(args: &Args, test_str: Option<&str>) -> Result<()> {
+    use crate::multi_device::{ColorCorrection, MultiDeviceConfig, MultiDeviceManager, WLEDDevice};
 
-    let test_str = args.test.as_ref().unwrap();
-    let led_numbers = parse_led_numbers(test_str)?;
+    // A named pattern takes precedence over stepping through single LEDs
+    let pattern = args.test_pattern.as_deref().map(test_patterns::TestPattern::from_name).transpose()?;
+    let pattern_speed = args.test_speed.unwrap_or(1.0).max(0.0);
+    let pattern_brightness = (args.test_brightness.unwrap_or(50.0) / 100.0).clamp(0.0, 1.0);
 
     // Load config to get device configuration
     let config = BandwidthConfig::load().unwrap_or_default();
 
+    let target_devices = match args.test_device.as_deref() {
+        Some(selector) => test_patterns::select_devices(&config.wled_devices, selector)?,
+        None => Vec::new(),
+    };
+
+    // With --test-device, LED numbers are relative to each targeted device (default: all of its LEDs)
+    let led_numbers = match (pattern, test_str) {
+        (Some(_), _) => Vec::new(),
+        (None, test_str) if !target_devices.is_empty() => {
+            let mut leds = Vec::new();
+            for device in &target_devices {
+                let relative = match test_str {
+                    Some(test_str) => parse_led_numbers(test_str)?,
+                    None => (0..device.led_count).collect(),
+                };
+                leds.extend(relative.into_iter().filter(|&i| i < device.led_count).map(|i| device.led_offset + i));
+            }
+            leds
+        }
+        (None, Some(test_str)) => parse_led_numbers(test_str)?,
+        (None, None) => Vec::new(),
+    };
+
     // Get FPS from args or config, default to 10 FPS
     let fps = args.fps.unwrap_or(config.fps);
     let frame_time_ms = (1000.0 / fps) as u64;
 
-    println!("Test mode: sequencing through LEDs {:?}", led_numbers);
+    for device in &target_devices {
+        println!("Targeting device {} (LEDs {}-{})", device.ip, device.led_offset, device.led_offset + device.led_count.max(1) - 1);
+    }
+    if let Some(pattern) = pattern {
+        println!("Test mode: {:?} pattern at {:.0}% brightness, speed {:.1}x", pattern, pattern_brightness * 100.0, pattern_speed);
+    } else {
+        println!("Test mode: sequencing through LEDs {:?}", led_numbers);
+    }
     println!("Target FPS: {:.1} ({} ms per frame)", fps, frame_time_ms);
 
     // Setup multi-device or single device based on config
@@ -385,6 +519,15 @@ async fn test_mode(args: &Args) -> Result<()> {
     if !config.wled_devices.is_empty() {
         println!("Using device configuration from config file:");
         for (idx, device) in config.wled_devices.iter().enumerate() {
+            if !device.backup_for.is_empty() {
+                println!("  Device {}: {} (backup for {}, {})",
+                    idx,
+                    device.ip,
+                    device.backup_for,
+                    if device.enabled { "enabled" } else { "disabled" }
+                );
+                continue;
+            }
             println!("  Device {}: {} (LEDs {}-{}, {})",
                 idx,
                 device.ip,
@@ -400,12 +543,25 @@ async fn test_mode(args: &Args) -> Result<()> {
             led_offset: d.led_offset,
             led_count: d.led_count,
             enabled: d.enabled,
+            max_fps: d.max_fps,
+            led_ma: d.led_ma,
+            psu_amps: d.psu_amps,
+            backup_for: d.backup_for.clone(),
+            reversed: d.reversed,
+            mirrored: d.mirrored,
+            protocol: d.protocol.clone(),
+            universe: d.universe,
+            start_channel: d.start_channel,
+            net: d.net,
+            subnet: d.subnet,
         }).collect();
 
         let md_config = MultiDeviceConfig {
             devices,
             send_parallel: config.multi_device_send_parallel,
             fail_fast: config.multi_device_fail_fast,
+            stagger_sends: config.multi_device_stagger_sends,
+            color_correction: ColorCorrection::from_config(&config),
         };
 
         match MultiDeviceManager::new(md_config) {
@@ -429,13 +585,12 @@ async fn test_mode(args: &Args) -> Result<()> {
         single_ddp_conn = Some(DDPConnection::try_new(&dest_addr, PixelConfig::default(), ID::Default, socket)?);
     }
 
-    println!("Connected! Starting sequential LED test...");
-
-    let test_color = Rgb::from_hex("FF0000")?;
+    println!("Connected! Starting LED test...");
 
     // Calculate frame size from device configuration
     let total_leds = if !config.wled_devices.is_empty() {
         config.wled_devices.iter()
+            .filter(|d| d.backup_for.is_empty())
             .map(|d| d.led_offset + d.led_count)
             .max()
             .unwrap_or(100)
@@ -445,7 +600,13 @@ async fn test_mode(args: &Args) -> Result<()> {
     let frame_size = total_leds * 3;
 
     println!("Frame size: {} LEDs ({} bytes)", total_leds, frame_size);
-    println!("Testing {} LEDs total", led_numbers.len());
+    if pattern == Some(test_patterns::TestPattern::White) {
+        // Worst case draw before any psu_amps limiting kicks in
+        let led_ma = config.wled_devices.first().map(|d| d.led_ma).unwrap_or(55.0);
+        println!("Estimated draw at full white: {:.1} A", total_leds as f64 * led_ma * pattern_brightness / 1000.0);
+    } else if pattern.is_none() {
+        println!("Testing {} LEDs total", led_numbers.len());
+    }
     println!("Press Ctrl+C or 'q' to quit\n");
 
     // Enable raw mode for keyboard input
@@ -463,36 +624,23 @@ async fn test_mode(args: &Args) -> Result<()> {
         }
     });
 
-    // Continuous loop through each LED in the range
-    'outer: while running.load(Ordering::SeqCst) {
-        for &led_num in &led_numbers {
-            // Check for keyboard input (non-blocking)
+    if let Some(pattern) = pattern {
+        let start = Instant::now();
+        while running.load(Ordering::SeqCst) {
             if poll(std::time::Duration::from_millis(0))? {
                 if let Event::Key(key_event) = read()? {
                     if matches!(key_event.code, KeyCode::Char('q') | KeyCode::Char('Q')) {
-                        running.store(false, Ordering::SeqCst);
-                        break 'outer;
+                        break;
                     }
                 }
             }
 
-            if !running.load(Ordering::SeqCst) {
-                break 'outer;
-            }
-
-            // Create frame with only this LED lit
-            let mut frame = vec![0u8; frame_size];
-            let offset = led_num * 3;
-            if offset + 2 < frame_size {
-                frame[offset] = test_color.r;
-                frame[offset + 1] = test_color.g;
-                frame[offset + 2] = test_color.b;
+            let seconds = start.elapsed().as_secs_f64();
+            let frame = if target_devices.is_empty() {
+                pattern.render(seconds, pattern_speed, total_leds, pattern_brightness)
             } else {
-                eprintln!("Skipping LED {} - exceeds frame size", led_num);
-                continue;
-            }
-
-            // Send via multi-device or single device
+                test_patterns::render_on_devices(pattern, seconds, pattern_speed, total_leds, pattern_brightness, &target_devices)
+            };
             if let Some(manager) = multi_device_manager.as_mut() {
                 if let Err(e) = manager.send_frame(&frame) {
                     eprintln!("Multi-device send error: {:?}", e);
@@ -501,11 +649,37 @@ async fn test_mode(args: &Args) -> Result<()> {
                 conn.write(&frame)?;
             }
 
-            print!("\rLED {} ON  ", led_num);
+            print!("\r{:<72}", pattern.describe(seconds, pattern_speed));
             use std::io::Write;
             std::io::stdout().flush()?;
             tokio::time::sleep(tokio::time::Duration::from_millis(frame_time_ms)).await;
         }
+
+        // Leave the strip dark rather than on the last pattern frame
+        let black = vec![0u8; frame_size];
+        if let Some(manager) = multi_device_manager.as_mut() {
+            let _ = manager.send_frame(&black);
+        } else if let Some(conn) = single_ddp_conn.as_mut() {
+            let _ = conn.write(&black);
+        }
+    }
+
+    // Single LEDs are stepped through interactively (auto-advance at the test FPS with 'a')
+    if pattern.is_none() {
+        let describe = |led_num: usize| match target_devices.iter().find(|d| led_num >= d.led_offset && led_num < d.led_offset + d.led_count) {
+            Some(device) => format!("{} LED {} (LED {})", device.ip, led_num - device.led_offset, led_num),
+            None => format!("LED {}", led_num),
+        };
+        test_tui::run(led_numbers, total_leds, Duration::from_millis(frame_time_ms), &describe, |frame| {
+            if let Some(manager) = multi_device_manager.as_mut() {
+                if let Err(e) = manager.send_frame(frame) {
+                    eprintln!("Multi-device send error: {:?}", e);
+                }
+            } else if let Some(conn) = single_ddp_conn.as_mut() {
+                conn.write(frame)?;
+            }
+            Ok(())
+        })?;
     }
 
     disable_raw_mode()?;
@@ -556,49 +730,49 @@ fn run_first_time_setup(midi_mode: bool) -> Result<BandwidthConfig> {
 
         println!("Selected: {}\n", midi_device);
 
-        // 2. Prompt for Total LEDs
-        let total_leds = loop {
-            print!("Enter total number of LEDs in your strip: ");
-            io::stdout().flush()?;
-
-            let mut input = String::new();
-            io::stdin().read_line(&mut input)?;
-
-            if let Ok(leds) = input.trim().parse::<usize>() {
-                if leds > 0 {
-                    break leds;
+        // 2. WLED devices - offer the ones found on the network, otherwise ask for the strip
+        let mut config = BandwidthConfig::default();
+        config.midi.device = midi_device.clone();
+        if !choose_discovered_devices(&mut config)? {
+            let total_leds = loop {
+                print!("Enter total number of LEDs in your strip: ");
+                io::stdout().flush()?;
+
+                let mut input = String::new();
+                io::stdin().read_line(&mut input)?;
+
+                if let Ok(leds) = input.trim().parse::<usize>() {
+                    if leds > 0 {
+                        break leds;
+                    }
                 }
-            }
-            println!("Invalid input. Please enter a positive number.");
-        };
+                println!("Invalid input. Please enter a positive number.");
+            };
 
-        println!();
+            println!();
 
-        // 3. Prompt for WLED IP
-        print!("Enter WLED IP address or hostname (e.g., led.local or 192.168.1.100): ");
-        io::stdout().flush()?;
-        let mut wled_ip = String::new();
-        io::stdin().read_line(&mut wled_ip)?;
-        let wled_ip = wled_ip.trim().to_string();
+            print!("Enter WLED IP address or hostname (e.g., led.local or 192.168.1.100): ");
+            io::stdout().flush()?;
+            let mut wled_ip = String::new();
+            io::stdin().read_line(&mut wled_ip)?;
+            let wled_ip = wled_ip.trim().to_string();
 
-        if wled_ip.is_empty() {
-            eprintln!("Error: WLED IP address is required!");
-            std::process::exit(1);
+            if wled_ip.is_empty() {
+                eprintln!("Error: WLED IP address is required!");
+                std::process::exit(1);
+            }
+
+            config.total_leds = total_leds;
+            config.wled_ip = wled_ip;
+            config.wled_devices.clear();  // Recreated from wled_ip when the config is loaded
         }
 
         println!("\n=== Configuration Summary ===");
         println!("MIDI Device: {}", midi_device);
-        println!("Total LEDs: {}", total_leds);
-        println!("WLED IP: {}", wled_ip);
+        print_setup_devices(&config);
         println!("\nAll other settings will use default values.");
         println!("You can modify these later via the config file or web interface at http://localhost:8080\n");
 
-        // Create config with provided values and defaults
-        let mut config = BandwidthConfig::default();
-        config.midi_device = midi_device;
-        config.total_leds = total_leds;
-        config.wled_ip = wled_ip;
-
         // Save the config
         config.save()?;
         println!("Configuration saved to: {}\n", BandwidthConfig::config_path(None)?.display());
@@ -624,26 +798,22 @@ fn run_first_time_setup(midi_mode: bool) -> Result<BandwidthConfig> {
         let interface = interfaces[0].clone();
         println!("Auto-selected interface: {}\n", interface);
 
-        // Auto-configure with sensible defaults
-        let wled_ip = "led.local".to_string();
-        let total_leds = 600;
+        // Auto-configure with sensible defaults, using the WLED devices found on the network if there are any
         let max_gbps = 10.0;
+        let mut config = BandwidthConfig { interface: interface.clone(), max_gbps, ..BandwidthConfig::default() };
+        if !choose_discovered_devices(&mut config)? {
+            config.wled_ip = "led.local".to_string();
+            config.total_leds = 600;
+            config.wled_devices.clear();  // Recreated from wled_ip when the config is loaded
+        }
 
         println!("\n=== Configuration Summary ===");
         println!("Interface: {}", interface);
-        println!("WLED IP: {}", wled_ip);
-        println!("Total LEDs: {}", total_leds);
+        print_setup_devices(&config);
         println!("Max Speed: {} Gbps", max_gbps);
         println!("\nAll other settings will use default values.");
         println!("You can modify these later via the config file or web interface at http://localhost:8080\n");
 
-        // Create config with provided values and defaults
-        let mut config = BandwidthConfig::default();
-        config.interface = interface;
-        config.wled_ip = wled_ip;
-        config.total_leds = total_leds;
-        config.max_gbps = max_gbps;
-
         // Save the config
         config.save()?;
         println!("Configuration saved to: {}\n", BandwidthConfig::config_path(None)?.display());
@@ -656,6 +826,70 @@ fn run_first_time_setup(midi_mode: bool) -> Result<BandwidthConfig> {
     }
 }
 
+// Offer the WLED devices found on the network for a new config - false if none were found
+// or the user would rather enter an address
+fn choose_discovered_devices(config: &mut BandwidthConfig) -> Result<bool> {
+    println!("Looking for WLED devices on the network...\n");
+    let found: Vec<_> = discovery::scan(discovery::DEFAULT_TIMEOUT)
+        .into_iter()
+        .filter(|d| d.led_count.is_some())
+        .collect();
+    if found.is_empty() {
+        println!("No WLED devices found.\n");
+        return Ok(false);
+    }
+
+    println!("Found WLED devices:");
+    for (i, device) in found.iter().enumerate() {
+        println!("  {}. {} - {} ({} LEDs)", i + 1, device.ip, device.name, device.led_count.unwrap_or(0));
+    }
+
+    let chosen: Vec<_> = loop {
+        print!("\nUse which devices? (e.g. 1,3 - Enter for all, 0 to enter an address instead): ");
+        io::stdout().flush()?;
+
+        let mut input = String::new();
+        io::stdin().read_line(&mut input)?;
+        let input = input.trim();
+        if input.is_empty() {
+            break found.clone();
+        }
+        if input == "0" {
+            println!();
+            return Ok(false);
+        }
+
+        let picks: Option<Vec<usize>> = input.split(',')
+            .map(|n| n.trim().parse::<usize>().ok().filter(|n| (1..=found.len()).contains(n)))
+            .collect();
+        match picks {
+            Some(picks) => break picks.into_iter().map(|n| found[n - 1].clone()).collect(),
+            None => println!("Invalid selection. Enter numbers between 1 and {} separated by commas", found.len()),
+        }
+    };
+
+    // The chosen devices replace the default one, laid out one after another
+    config.wled_devices.clear();
+    config.total_leds = 0;
+    discovery::add_to_config(config, &chosen);
+    config.wled_ip = config.wled_devices[0].ip.clone();
+    println!();
+    Ok(true)
+}
+
+// Devices in a first-time setup summary
+fn print_setup_devices(config: &BandwidthConfig) {
+    if config.wled_devices.is_empty() {
+        println!("WLED IP: {}", config.wled_ip);
+        println!("Total LEDs: {}", config.total_leds);
+        return;
+    }
+    for device in &config.wled_devices {
+        println!("WLED: {} ({} LEDs)", device.ip, device.led_count);
+    }
+    println!("Total LEDs: {}", config.total_leds);
+}
+
 /// Generate compact config info display for TUI
 fn generate_config_info_display(config: &BandwidthConfig) -> Vec<Line<'static>> {
     vec![
@@ -667,7 +901,7 @@ fn generate_config_info_display(config: &BandwidthConfig) -> Vec<Line<'static>>
         Line::from(format!("wled_ip: {}  |  total_leds: {}  |  fps: {:.0}  |  direction: {}  |  swap: {}",
             config.wled_ip, config.total_leds, config.fps, config.direction, config.swap)),
         Line::from(format!("rx_split_percent: {:.0}%  |  use_gradient: {}  |  interpolation: {}  |  log_scale: {}",
-            config.rx_split_percent, config.use_gradient, config.interpolation, config.log_scale)),
+            config.rx_split_percent, config.use_gradient, config.interpolation, config.audio.log_scale)),
         Line::from(""),
         Line::from(format!("═══ Colors ════════════════════════════════════════════════════════════════")),
         Line::from(format!("color: {}...", if config.color.len() > 40 { &config.color[..40] } else { &config.color })),
@@ -678,19 +912,24 @@ fn generate_config_info_display(config: &BandwidthConfig) -> Vec<Line<'static>>
         Line::from(format!("═══ Animation ═════════════════════════════════════════════════════════════")),
         Line::from(format!("animation_speed: {}  |  scale_animation_speed: {}  |  tx_direction: {}  |  rx_direction: {}",
             config.animation_speed, config.scale_animation_speed, config.tx_animation_direction, config.rx_animation_direction)),
-        Line::from(format!("interpolation_time_ms: {}ms", config.interpolation_time_ms)),
+        Line::from(format!("interpolation_time_ms: {}ms  |  fill_easing: {}  |  animation_easing: {}",
+            config.interpolation_time_ms, config.fill_easing, config.animation_easing)),
         Line::from(""),
         Line::from(format!("═══ Strobe ════════════════════════════════════════════════════════════════")),
         Line::from(format!("strobe_on_max: {}  |  rate: {}Hz  |  duration: {}ms  |  color: {}",
             config.strobe_on_max, config.strobe_rate_hz, config.strobe_duration_ms, config.strobe_color)),
+        Line::from(format!("strobe_on_beat: {}  |  strobe_midi_note: {}",
+            config.strobe_on_beat, config.strobe_midi_note)),
         Line::from(""),
         Line::from(format!("═══ Audio/MIDI ════════════════════════════════════════════════════════════")),
         Line::from(format!("midi_device: {}  |  midi_gradient: {}  |  midi_random_colors: {}  |  midi_velocity_colors: {}",
-            config.midi_device, config.midi_gradient, config.midi_random_colors, config.midi_velocity_colors)),
+            config.midi.device, config.midi.gradient, config.midi.random_colors, config.midi.velocity_colors)),
         Line::from(format!("midi_one_to_one: {}  |  midi_channel_mode: {}  |  vu: {}  |  audio_device: {}",
-            config.midi_one_to_one, config.midi_channel_mode, config.vu, config.audio_device)),
+            config.midi.one_to_one, config.midi.channel_mode, config.vu.enabled, config.audio.device)),
+        Line::from(format!("vu_scale: {}  |  vu_db_range: {} dB  |  vu_db_reference: {} dBFS  |  vu_ballistics: {}  |  vu_mono: {}",
+            config.vu.scale, config.vu.db_range, config.vu.db_reference, config.vu.ballistics, config.vu.mono)),
         Line::from(format!("attack_ms: {:.1}  |  decay_ms: {:.1}  |  ddp_delay_ms: {:.1}",
-            config.attack_ms, config.decay_ms, config.ddp_delay_ms)),
+            config.audio.attack_ms, config.audio.decay_ms, config.ddp_delay_ms)),
         Line::from(""),
         Line::from(format!("═══ HTTP Server ═══════════════════════════════════════════════════════════")),
         Line::from(format!("httpd_enabled: {}  |  httpd_ip: {}  |  httpd_port: {}  |  httpd_auth_enabled: {}",
@@ -702,12 +941,15 @@ fn generate_config_info_display(config: &BandwidthConfig) -> Vec<Line<'static>>
     ]
 }
 
+// Seconds of note history shown in the MIDI piano roll
+const PIANO_ROLL_SECS: u64 = 10;
+
 /// MIDI mode main loop with TUI
-fn run_midi_mode(config: &BandwidthConfig, midi_device: Option<String>, random_colors: bool, config_change_tx: broadcast::Sender<()>) -> Result<ModeExitReason> {
-    let device_name = midi_device.unwrap_or_else(|| config.midi_device.clone());
+fn run_midi_mode(config: &BandwidthConfig, midi_device: Option<String>, random_colors: bool, config_change_tx: broadcast::Sender<ConfigDelta>) -> Result<ModeExitReason> {
+    let device_name = midi_device.unwrap_or_else(|| config.midi.device.clone());
 
     // Create color map if random colors enabled
-    let use_random = random_colors || config.midi_random_colors;
+    let use_random = random_colors || config.midi.random_colors;
     let color_map = if use_random {
         Some(midi::generate_random_color_map())
     } else {
@@ -719,11 +961,9 @@ fn run_midi_mode(config: &BandwidthConfig, midi_device: Option<String>, random_c
     let note_state_callback = note_state.clone();
     let note_state_render = note_state.clone();
 
-    // Event log for TUI (store last 100 events)
-    let event_log: Arc<Mutex<Vec<String>>> = Arc::new(Mutex::new(Vec::new()));
-    let event_log_callback = event_log.clone();
-    let color_map_callback = color_map.clone();
-    let velocity_colors_callback = config.midi_velocity_colors;
+    // Piano roll of recent notes for the TUI
+    let piano_roll = Arc::new(Mutex::new(PianoRoll::new(Duration::from_secs(PIANO_ROLL_SECS))));
+    let piano_roll_callback = piano_roll.clone();
 
     // Debug info for TUI (decay tracking)
     let debug_info: Arc<Mutex<Vec<String>>> = Arc::new(Mutex::new(Vec::new()));
@@ -732,54 +972,21 @@ fn run_midi_mode(config: &BandwidthConfig, midi_device: Option<String>, random_c
     println!("\n🎵 MIDI Mode");
     println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━\n");
 
+    let morph_tx = config_change_tx.clone();
     let _midi_connection = midi::connect_midi(&device_name, move |_timestamp, message, _| {
         if let Some(event) = midi::parse_midi_message(message) {
             match event {
                 midi::MidiEvent::NoteOn { channel, note, velocity } => {
-                    note_state_callback.note_on(channel, note, velocity);
-
-                    // Get actual brightness being used for rendering
-                    let (display_color, actual_brightness) = if velocity_colors_callback {
-                        // Velocity colors mode: color from velocity, full brightness
-                        let color = midi::velocity_to_color(velocity);
-                        (color, 255)
-                    } else {
-                        // Note colors mode: color from note, brightness from velocity
-                        let color = midi::get_note_color(note, color_map_callback.as_ref());
-                        let brightness = midi::velocity_to_brightness(velocity);
-                        (color, brightness)
-                    };
-
-                    // Add to event log for TUI
-                    let mut log = event_log_callback.lock().unwrap();
-                    log.push(format!(
-                        "[NOTE ON ] Ch:{:2} Note:{:3} ({:4})   RGB({:3},{:3},{:3})   Bright: {:3}",
-                        channel + 1,  // Display as 1-16 instead of 0-15
-                        note,
-                        midi::note_number_to_name(note),
-                        display_color.r,
-                        display_color.g,
-                        display_color.b,
-                        actual_brightness
-                    ));
-                    if log.len() > 100 {
-                        log.remove(0);
+                    // Preset morph trigger notes don't light up
+                    if preset_morph::trigger(note, &morph_tx) {
+                        return;
                     }
+                    note_state_callback.note_on(channel, note, velocity);
+                    piano_roll_callback.lock().unwrap().note_on(channel, note, Instant::now());
                 }
                 midi::MidiEvent::NoteOff { channel, note } => {
                     note_state_callback.note_off(channel, note);
-
-                    // Add to event log for TUI
-                    let mut log = event_log_callback.lock().unwrap();
-                    log.push(format!(
-                        "[NOTE OFF] Ch:{:2} Note:{:3} ({:4})",
-                        channel + 1,  // Display as 1-16 instead of 0-15
-                        note,
-                        midi::note_number_to_name(note)
-                    ));
-                    if log.len() > 100 {
-                        log.remove(0);
-                    }
+                    piano_roll_callback.lock().unwrap().note_off(channel, note, Instant::now());
                 }
             }
         }
@@ -791,12 +998,25 @@ fn run_midi_mode(config: &BandwidthConfig, midi_device: Option<String>, random_c
         led_offset: d.led_offset,
         led_count: d.led_count,
         enabled: d.enabled,
+        max_fps: d.max_fps,
+        led_ma: d.led_ma,
+        psu_amps: d.psu_amps,
+        backup_for: d.backup_for.clone(),
+        reversed: d.reversed,
+        mirrored: d.mirrored,
+        protocol: d.protocol.clone(),
+        universe: d.universe,
+        start_channel: d.start_channel,
+        net: d.net,
+        subnet: d.subnet,
     }).collect();
 
     let md_config = MultiDeviceConfig {
         devices,
         send_parallel: config.multi_device_send_parallel,
         fail_fast: config.multi_device_fail_fast,
+        stagger_sends: config.multi_device_stagger_sends,
+        color_correction: ColorCorrection::from_config(&config),
     };
 
     let mut multi_device_manager = MultiDeviceManager::new(md_config)?;
@@ -806,22 +1026,28 @@ fn run_midi_mode(config: &BandwidthConfig, midi_device: Option<String>, random_c
     let mut target_brightness = vec![0.0_f32; config.total_leds];  // Target brightness per LED (independent of velocity functions)
     let mut last_colors = vec![(0_u8, 0_u8, 0_u8); config.total_leds];  // Base RGB color (0-255) per LED, brightness applied separately
 
+    // Channels routed to their own devices or LED ranges (each zone keeps its own smoothing state)
+    let mut midi_zones = renderer::MidiZones::new(midi::zone_ranges(&config.midi.zones, &config.wled_devices, config.total_leds));
+
     // Track current config values for real-time updates
     let mut current_config = config.clone();
     let mut current_fps = current_config.fps;
     let mut frame_time_ms = 1000.0 / current_fps;
 
-    let mut attack_factor = (frame_time_ms / current_config.attack_ms as f64).min(1.0) as f32;
-    let mut decay_factor = (frame_time_ms / current_config.decay_ms as f64).min(1.0) as f32;
+    let mut attack_factor = (frame_time_ms / current_config.audio.attack_ms as f64).min(1.0) as f32;
+    let mut decay_factor = (frame_time_ms / current_config.audio.decay_ms as f64).min(1.0) as f32;
 
     println!("\n✓ Connected to WLED at {}", config.wled_ip);
     println!("✓ LED Count: {}", config.total_leds);
     println!("✓ Running at {:.1} FPS ({:.2}ms per frame)", current_fps, frame_time_ms);
     println!("✓ Attack: {:.1}ms (factor: {:.6}, ~{} frames to complete)",
-             current_config.attack_ms, attack_factor, (current_config.attack_ms as f64 / frame_time_ms).ceil() as u32);
+             current_config.audio.attack_ms, attack_factor, (current_config.audio.attack_ms as f64 / frame_time_ms).ceil() as u32);
     println!("✓ Decay: {:.1}ms (factor: {:.6}, ~{} frames to complete)",
-             current_config.decay_ms, decay_factor, (current_config.decay_ms as f64 / frame_time_ms).ceil() as u32);
-    println!("✓ Velocity colors: {}", if current_config.midi_velocity_colors { "enabled" } else { "disabled" });
+             current_config.audio.decay_ms, decay_factor, (current_config.audio.decay_ms as f64 / frame_time_ms).ceil() as u32);
+    println!("✓ Velocity colors: {}", if current_config.midi.velocity_colors { "enabled" } else { "disabled" });
+    if !midi_zones.is_empty() {
+        println!("✓ MIDI zones: {} (notes on other channels don't light up)", current_config.midi.zones.len());
+    }
     println!("✓ Debug log: /tmp/midi_decay_debug.log");
     println!("\n🎹 Play some notes! Press 'q' to quit.\n");
 
@@ -837,19 +1063,27 @@ fn run_midi_mode(config: &BandwidthConfig, midi_device: Option<String>, random_c
     terminal.clear()?;
 
     // Frame buffer for delay - stores (send_time, frame_data)
-    let mut frame_buffer: std::collections::VecDeque<(Instant, Vec<u8>)> = std::collections::VecDeque::new();
+    // MIDI frames stay in float until the send quantizes them, so slow decays don't step in 8-bit increments
+    let mut frame_buffer: std::collections::VecDeque<(Instant, Vec<f32>)> = std::collections::VecDeque::new();
+    // Sent frames are recycled here, and render scratch space is kept between frames, so steady-state frames don't allocate
+    let mut spare_frames: Vec<Vec<f32>> = Vec::new();
+    let mut midi_buffers = renderer::MidiFrameBuffers::default();
+    let mut held_notes: Vec<(u8, u8, u8)> = Vec::new();
+    let mut alloc_counter = FrameAllocCounter::new("midi");
+    let mut strobe_settings = StrobeSettings::from_config(&current_config);
+    let mut note_strobe = Strobe::new();
 
     // Config info toggle
     let mut show_config_info = false;
 
     // Main loop - use global fps from config
-    let mut frame_duration = Duration::from_secs_f64(1.0 / current_fps);
+    let mut pacer = FramePacer::new(current_fps);
 
     loop {
         let loop_start = Instant::now();
 
-        // Check for keyboard input with brief timeout for better responsiveness
-        if poll(Duration::from_millis(10))? {
+        // Check for keyboard input (non-blocking - the pacer does the waiting)
+        if poll(Duration::from_millis(0))? {
             if let Event::Key(key) = read()? {
                 match key.code {
                     KeyCode::Char('q') | KeyCode::Char('Q') => {
@@ -876,25 +1110,22 @@ fn run_midi_mode(config: &BandwidthConfig, midi_device: Option<String>, random_c
         }
 
         // Check for config updates via SSE broadcast
-        if let Ok(()) = config_change_rx.try_recv() {
-            let new_config = match BandwidthConfig::load() {
-                Ok(c) => c,
-                Err(_) => continue, // Skip if load fails
-            };
+        if let Ok(delta) = config_change_rx.try_recv() {
+            let new_config = delta.config_or(&current_config);
 
             // Update FPS if changed
             if new_config.fps != current_config.fps {
                 current_fps = new_config.fps;
                 frame_time_ms = 1000.0 / current_fps;
-                frame_duration = Duration::from_secs_f64(1.0 / current_fps);
+                pacer.set_fps(current_fps);
             }
 
             // Update attack/decay if changed
-            if new_config.attack_ms != current_config.attack_ms || new_config.fps != current_config.fps {
-                attack_factor = (frame_time_ms / new_config.attack_ms as f64).min(1.0) as f32;
+            if new_config.audio.attack_ms != current_config.audio.attack_ms || new_config.fps != current_config.fps {
+                attack_factor = (frame_time_ms / new_config.audio.attack_ms as f64).min(1.0) as f32;
             }
-            if new_config.decay_ms != current_config.decay_ms || new_config.fps != current_config.fps {
-                decay_factor = (frame_time_ms / new_config.decay_ms as f64).min(1.0) as f32;
+            if new_config.audio.decay_ms != current_config.audio.decay_ms || new_config.fps != current_config.fps {
+                decay_factor = (frame_time_ms / new_config.audio.decay_ms as f64).min(1.0) as f32;
             }
 
             // Resize smoothed frame if total_leds changed
@@ -904,16 +1135,13 @@ fn run_midi_mode(config: &BandwidthConfig, midi_device: Option<String>, random_c
                 last_colors.resize(new_config.total_leds, (0, 0, 0));
             }
 
-            // Reinitialize multi-device manager if device config changed
-            let devices_changed = new_config.wled_devices.len() != current_config.wled_devices.len() ||
-                new_config.wled_devices.iter().zip(current_config.wled_devices.iter()).any(|(new, old)| {
-                    new.ip != old.ip ||
-                    new.led_offset != old.led_offset ||
-                    new.led_count != old.led_count ||
-                    new.enabled != old.enabled
-                }) ||
-                new_config.multi_device_send_parallel != current_config.multi_device_send_parallel ||
-                new_config.multi_device_fail_fast != current_config.multi_device_fail_fast;
+            // Update the multi-device manager in place if device config changed
+            let devices_changed = delta.changed_any(config::DEVICE_CONFIG_KEYS);
+
+            // Zones follow their devices, so they're laid out again when the devices change too
+            if devices_changed || delta.changed_any(&["midi_zones", "total_leds"]) {
+                midi_zones = renderer::MidiZones::new(midi::zone_ranges(&new_config.midi.zones, &new_config.wled_devices, new_config.total_leds));
+            }
 
             if devices_changed {
                 let devices: Vec<WLEDDevice> = new_config.wled_devices.iter().map(|d| WLEDDevice {
@@ -921,28 +1149,40 @@ fn run_midi_mode(config: &BandwidthConfig, midi_device: Option<String>, random_c
                     led_offset: d.led_offset,
                     led_count: d.led_count,
                             enabled: d.enabled,
+                    max_fps: d.max_fps,
+                    led_ma: d.led_ma,
+                    psu_amps: d.psu_amps,
+                    backup_for: d.backup_for.clone(),
+                    reversed: d.reversed,
+                    mirrored: d.mirrored,
+                    protocol: d.protocol.clone(),
+                    universe: d.universe,
+                    start_channel: d.start_channel,
+                    net: d.net,
+                    subnet: d.subnet,
                 }).collect();
 
                 let md_config = MultiDeviceConfig {
                     devices,
                     send_parallel: new_config.multi_device_send_parallel,
                     fail_fast: new_config.multi_device_fail_fast,
+                    stagger_sends: new_config.multi_device_stagger_sends,
+                    color_correction: ColorCorrection::from_config(&new_config),
                 };
 
-                match MultiDeviceManager::new(md_config) {
-                    Ok(new_manager) => {
-                        multi_device_manager = new_manager;
-                        println!("\n✓ Reinitialized multi-device manager");
+                match multi_device_manager.reconfigure(md_config) {
+                    Ok(()) => {
+                        println!("\n✓ Updated multi-device manager");
                     }
                     Err(e) => {
-                        eprintln!("\n⚠️  Failed to reinitialize multi-device manager: {}", e);
+                        eprintln!("\n⚠️  Failed to update multi-device manager: {}", e);
                         eprintln!("   Continuing with previous configuration");
                     }
                 }
             }
 
-            // Check if mode changed - if so, exit MIDI mode to allow mode switch
-            if new_config.mode != "midi" {
+            // Check if mode changed (or a restart was requested) - if so, exit MIDI mode to allow mode switch
+            if new_config.mode != "midi" || system::restart_requested() {
                 println!("\n🔄 Mode changed to '{}', exiting MIDI mode...", new_config.mode);
                 terminal.show_cursor()?;
                 disable_raw_mode()?;
@@ -951,8 +1191,8 @@ fn run_midi_mode(config: &BandwidthConfig, midi_device: Option<String>, random_c
             }
 
             // Check if MIDI device changed - if so, exit and restart with new device
-            if new_config.midi_device != current_config.midi_device {
-                println!("\n🔄 MIDI device changed to '{}', restarting MIDI mode...", new_config.midi_device);
+            if new_config.midi.device != current_config.midi.device {
+                println!("\n🔄 MIDI device changed to '{}', restarting MIDI mode...", new_config.midi.device);
                 terminal.show_cursor()?;
                 disable_raw_mode()?;
                 terminal.backend_mut().execute(LeaveAlternateScreen)?;
@@ -960,41 +1200,75 @@ fn run_midi_mode(config: &BandwidthConfig, midi_device: Option<String>, random_c
             }
 
             current_config = new_config;
+            strobe_settings = StrobeSettings::from_config(&current_config);
         }
 
+        alloc_counter.begin_frame();
+        let render_start = Instant::now();
+        note_state_render.active_notes_into(&mut held_notes);
+        live_stats::record_active_notes(&held_notes);
+        note_strobe.feed_notes(&strobe_settings, &held_notes);
+
         // Render MIDI state to LEDs with attack/decay smoothing
-        let frame = renderer::render_midi_to_leds(
-            &note_state_render,
-            current_config.total_leds,
-            current_config.midi_gradient,
-            color_map.as_ref(),
-            current_config.midi_velocity_colors,
-            current_config.midi_one_to_one,
-            current_config.midi_channel_mode,
-            &mut smoothed_frame,
-            &mut target_brightness,
-            &mut last_colors,
-            attack_factor,
-            decay_factor,
-            Some(&debug_info),
-        )?;
-
-        // Add frame to buffer with scheduled send time
+        let frame = if !midi_zones.is_empty() {
+            midi_zones.render(
+                &note_state_render,
+                current_config.total_leds,
+                current_config.midi.gradient,
+                color_map.as_ref(),
+                current_config.midi.velocity_colors,
+                current_config.midi.one_to_one,
+                attack_factor,
+                decay_factor,
+            )?
+        } else {
+            renderer::render_midi_to_leds(
+                &note_state_render,
+                current_config.total_leds,
+                current_config.midi.gradient,
+                color_map.as_ref(),
+                current_config.midi.velocity_colors,
+                current_config.midi.one_to_one,
+                current_config.midi.channel_mode,
+                None,
+                &mut smoothed_frame,
+                &mut target_brightness,
+                &mut last_colors,
+                attack_factor,
+                decay_factor,
+                Some(&debug_info),
+                &mut midi_buffers,
+            )?
+        };
+
+        // Add a copy of the frame to the buffer with scheduled send time
         let delay_duration = Duration::from_micros((current_config.ddp_delay_ms * 1000.0) as u64);
         let send_time = loop_start + delay_duration;
-        frame_buffer.push_back((send_time, frame));
+        let mut queued = spare_frames.pop().unwrap_or_default();
+        queued.clear();
+        queued.extend_from_slice(frame);
+
+        // Strobe the whole strip while the strobe note is held
+        if note_strobe.update(&strobe_settings, false) {
+            strobe::fill(&mut queued, 0..current_config.total_leds, strobe_settings.color);
+        }
+        live_stats::record_render_time(render_start.elapsed());
+        frame_buffer.push_back((send_time, queued));
 
         // Send all frames that are ready (send_time <= now)
         let now = Instant::now();
         while let Some((send_time, _)) = frame_buffer.front() {
             if *send_time <= now {
                 if let Some((_, frame_to_send)) = frame_buffer.pop_front() {
-                    let _ = multi_device_manager.send_frame_with_brightness(&frame_to_send, Some(current_config.global_brightness));
+                    let _ = multi_device_manager.send_frame_f32(&frame_to_send, Some(current_config.global_brightness));
+                    spare_frames.push(frame_to_send);
                 }
             } else {
                 break;
             }
         }
+        live_stats::record_buffer_depth(frame_buffer.len());
+        alloc_counter.end_frame();
 
         // Update TUI
         terminal.draw(|f| {
@@ -1003,15 +1277,17 @@ fn run_midi_mode(config: &BandwidthConfig, midi_device: Option<String>, random_c
                 .constraints([
                     Constraint::Length(3),  // Header
                     Constraint::Min(10),    // Main content
-                    Constraint::Length(3),  // Footer
+                    Constraint::Length(4),  // Footer
                 ])
                 .split(f.size());
 
             // Header - Mode and sub-mode
             let active_count = note_state_render.count();
-            let sub_mode = if current_config.midi_channel_mode {
+            let sub_mode = if !midi_zones.is_empty() {
+                "Zone Mode"
+            } else if current_config.midi.channel_mode {
                 "Channel Mode"
-            } else if current_config.midi_one_to_one {
+            } else if current_config.midi.one_to_one {
                 "1-to-1 Mode"
             } else {
                 "Spread Mode"
@@ -1028,21 +1304,25 @@ fn run_midi_mode(config: &BandwidthConfig, midi_device: Option<String>, random_c
                     .block(Block::default().borders(Borders::ALL).title("Configuration (Press 'i' to hide)"));
                 f.render_widget(config_widget, chunks[1]);
             } else {
-                // Split main area for event log and debug info
+                // Split main area for the piano roll and debug info
                 let main_chunks = Layout::default()
                     .direction(Direction::Vertical)
                     .constraints([
-                        Constraint::Percentage(50),
-                        Constraint::Percentage(50),
+                        Constraint::Percentage(65),
+                        Constraint::Percentage(35),
                     ])
                     .split(chunks[1]);
 
-                // Event log
-                let log = event_log.lock().unwrap();
-                let log_text: Vec<Line> = log.iter().map(|s| Line::from(s.as_str())).collect();
-                let log_widget = Paragraph::new(log_text)
-                    .block(Block::default().borders(Borders::ALL).title("MIDI Events"));
-                f.render_widget(log_widget, main_chunks[0]);
+                // Piano roll - notes as bars scrolling left, colored by channel (inside the borders)
+                let roll_area = main_chunks[0];
+                let roll_lines = piano_roll.lock().unwrap().lines(
+                    roll_area.width.saturating_sub(2) as usize,
+                    roll_area.height.saturating_sub(2) as usize,
+                    Instant::now(),
+                );
+                let roll_widget = Paragraph::new(roll_lines)
+                    .block(Block::default().borders(Borders::ALL).title(format!("Piano Roll (last {}s, colored by channel)", PIANO_ROLL_SECS)));
+                f.render_widget(roll_widget, roll_area);
 
                 // Debug info
                 let debug = debug_info.lock().unwrap();
@@ -1054,8 +1334,9 @@ fn run_midi_mode(config: &BandwidthConfig, midi_device: Option<String>, random_c
 
             // Footer - Monitoring source and controls
             let footer_text = format!(
-                "Source: MIDI [{}] | WLED: {} | LEDs: {} | FPS: {:.0} | Delay: {:.1}ms | Press 'i' for config, 'q' or Ctrl+C to quit",
-                current_config.midi_device, current_config.wled_ip, current_config.total_leds, current_fps, current_config.ddp_delay_ms
+                "Source: MIDI [{}] | WLED: {} | LEDs: {} | FPS: {:.0} | Delay: {:.1}ms | Press 'i' for config, 'q' or Ctrl+C to quit\n{}",
+                current_config.midi.device, current_config.wled_ip, current_config.total_leds, current_fps, current_config.ddp_delay_ms,
+                live_stats::profile_line()
             );
             let footer = Paragraph::new(footer_text)
                 .block(Block::default().borders(Borders::ALL));
@@ -1063,15 +1344,41 @@ fn run_midi_mode(config: &BandwidthConfig, midi_device: Option<String>, random_c
         })?;
 
         // Frame rate limiting
-        let elapsed = loop_start.elapsed();
-        if elapsed < frame_duration {
-            thread::sleep(frame_duration - elapsed);
-        }
+        pacer.wait();
     }
 }
 
 /// Live audio spectrum visualization mode
-fn run_live_mode(config: &BandwidthConfig, delay_ms: Option<u64>, config_change_tx: broadcast::Sender<()>) -> Result<ModeExitReason> {
+// Run an in-place FFT using a caller-owned scratch buffer (plain `process` allocates scratch on every call)
+fn process_fft(fft: &dyn rustfft::Fft<f32>, buffer: &mut [rustfft::num_complex::Complex<f32>], scratch: &mut Vec<rustfft::num_complex::Complex<f32>>) {
+    let scratch_len = fft.get_inplace_scratch_len();
+    if scratch.len() < scratch_len {
+        scratch.resize(scratch_len, rustfft::num_complex::Complex::new(0.0, 0.0));
+    }
+    fft.process_with_scratch(buffer, &mut scratch[..scratch_len]);
+}
+
+// Fill the FFT buffer with one channel of interleaved samples, windowed
+fn fill_fft_buffer(buffer: &mut Vec<rustfft::num_complex::Complex<f32>>, samples: &[f32], channel: usize, channels: usize, fft_size: usize, window: WindowFunction) {
+    buffer.clear();
+    buffer.extend(samples.iter().skip(channel).step_by(channels).take(fft_size).enumerate().map(|(i, &s)| {
+        rustfft::num_complex::Complex { re: s * window.coefficient(i, fft_size), im: 0.0 }
+    }));
+    // Short sample windows zero-pad up to the planned FFT size
+    buffer.resize(fft_size, rustfft::num_complex::Complex::new(0.0, 0.0));
+}
+
+// Colors the spectrogram is drawn with - it always uses a gradient, so a single color falls back to rainbow
+// Worked out when the colors change rather than every frame
+fn spectrogram_colors(spectrum_color_str: &str) -> String {
+    if spectrum_color_str.contains(',') || spectrum_color_str.contains("rainbow") {
+        spectrum_color_str.to_string()
+    } else {
+        "rainbow".to_string()
+    }
+}
+
+fn run_live_mode(config: &BandwidthConfig, delay_ms: Option<u64>, config_change_tx: broadcast::Sender<ConfigDelta>) -> Result<ModeExitReason> {
     use cpal::traits::{DeviceTrait, StreamTrait};
     use cpal::SampleFormat;
     use rustfft::{FftPlanner, num_complex::Complex};
@@ -1082,9 +1389,9 @@ fn run_live_mode(config: &BandwidthConfig, delay_ms: Option<u64>, config_change_
     println!("\n=== Live Audio Spectrum Visualization ===\n");
 
     // Use audio device from config if available, otherwise prompt user
-    let selected_device_name = if !config.audio_device.is_empty() {
-        println!("Using audio device from config: {}", config.audio_device);
-        config.audio_device.clone()
+    let selected_device_name = if !config.audio.device.is_empty() {
+        println!("Using audio device from config: {}", config.audio.device);
+        config.audio.device.clone()
     } else {
         // List available audio devices using the working audio module
         let device_list = audio::list_audio_devices()?;
@@ -1106,24 +1413,46 @@ fn run_live_mode(config: &BandwidthConfig, delay_ms: Option<u64>, config_change_
         selected
     };
 
-    // Find the actual device
-    let device = audio::find_audio_device(&selected_device_name)?;
+    // Find the actual device - a test signal or demo mode's generated signal stands in for one
+    let test_signal = signal_generator::Signal::from_device_name(&selected_device_name);
+    let device = if selected_device_name == demo::GENERATED_AUDIO || test_signal.is_some() {
+        None
+    } else {
+        Some(audio::find_audio_device(&selected_device_name)?)
+    };
 
     // Get device config
-    let device_config = device.default_input_config()?;
-    let sample_rate = device_config.sample_rate().0 as f32;
-    let sample_format = device_config.sample_format();
+    let device_config = device.as_ref().map(|d| d.default_input_config()).transpose()?;
+    let device_rate = device_config.as_ref().map_or(demo::GENERATED_SAMPLE_RATE, |c| c.sample_rate().0 as f32);
+    let channels = device_config.as_ref().map_or(1, |c| c.channels() as usize);
 
-    println!("Sample rate: {} Hz", sample_rate);
-    println!("Channels: {}", device_config.channels());
-    println!("Format: {:?}", sample_format);
+    // Analysis runs at a fixed rate so bin widths, and the frequency -> LED mapping, don't depend on the device
+    let sample_rate = if device_config.is_some() && config.audio.analysis_rate > 0 {
+        config.audio.analysis_rate as f32
+    } else {
+        device_rate
+    };
+
+    if sample_rate != device_rate {
+        println!("Sample rate: {} Hz (resampled to {} Hz for analysis)", device_rate, sample_rate);
+    } else {
+        println!("Sample rate: {} Hz", sample_rate);
+    }
+    println!("Channels: {}", channels);
+    match &device_config {
+        Some(device_config) => println!("Format: {:?}", device_config.sample_format()),
+        None => match test_signal {
+            Some(signal) => println!("Format: generated test signal ({})", signal.label()),
+            None => println!("Format: generated demo signal"),
+        },
+    }
 
     println!("\nStarting in 2 seconds...");
     thread::sleep(Duration::from_millis(2000));
 
-    // FFT setup - balanced window for responsive transients with good frequency resolution
-    let fft_size = 1024;  // ~23ms latency at 44.1kHz - great balance!
-    // Gives 43 Hz per bin - still very detailed frequency separation
+    // FFT setup - the default 1024 (~23ms at 44.1kHz, 43 Hz per bin) balances responsive transients
+    // with good frequency resolution; changing fft_size restarts the mode
+    let fft_size = config.audio.fft_size;
     let min_freq = 1.0_f32;
     let max_freq = 22050.0_f32;
 
@@ -1142,85 +1471,107 @@ fn run_live_mode(config: &BandwidthConfig, delay_ms: Option<u64>, config_change_
     // Audio buffer - shared between audio thread and processing thread
     let audio_buffer = Arc::new(Mutex::new(Vec::<f32>::new()));
     let audio_buffer_clone = audio_buffer.clone();
+    let samples_received = Arc::new(AtomicUsize::new(0));  // Total samples captured, for stepping analysis windows by the hop
+    let samples_received_clone = samples_received.clone();
 
-    let channels = device_config.channels() as usize;
     println!("Audio has {} channel(s)", channels);
 
     // Build audio stream
     println!("\nStarting audio capture...");
 
-    let stream = match sample_format {
-        SampleFormat::F32 => {
-            let channels = channels;
-            device.build_input_stream(
-                &device_config.into(),
-                move |data: &[f32], _| {
-                    let mut buffer = audio_buffer_clone.lock().unwrap();
-
-                    // For stereo, store interleaved samples - we'll analyze separately later
-                    // For mono, just store as-is
-                    buffer.extend_from_slice(data);
-
-                    // Keep last 2 seconds
-                    let max_size = (sample_rate * 2.0) as usize * channels;
-                    if buffer.len() > max_size {
-                        let drain = buffer.len() - max_size;
-                        buffer.drain(0..drain);
-                    }
+    let _stream = match (device, device_config) {
+        (Some(device), Some(device_config)) => {
+            let sample_format = device_config.sample_format();
+            let stream = match sample_format {
+                SampleFormat::F32 => {
+                    let channels = channels;
+                    let mut resampler = audio::Resampler::new(device_rate, sample_rate, channels);
+                    device.build_input_stream(
+                        &device_config.into(),
+                        move |data: &[f32], _| {
+                            let mut buffer = audio_buffer_clone.lock().unwrap();
+
+                            // For stereo, store interleaved samples - we'll analyze separately later
+                            // For mono, just store as-is
+                            let before = buffer.len();
+                            resampler.process(data.iter().copied(), &mut buffer);
+                            samples_received_clone.fetch_add(buffer.len() - before, Ordering::Relaxed);
+
+                            // Keep last 2 seconds
+                            let max_size = (sample_rate * 2.0) as usize * channels;
+                            if buffer.len() > max_size {
+                                let drain = buffer.len() - max_size;
+                                buffer.drain(0..drain);
+                            }
+                        },
+                        |err| eprintln!("Audio error: {}", err),
+                        None,
+                    )?
                 },
-                |err| eprintln!("Audio error: {}", err),
-                None,
-            )?
-        },
-        SampleFormat::I16 => {
-            let channels = channels;
-            device.build_input_stream(
-                &device_config.into(),
-                move |data: &[i16], _| {
-                    let mut buffer = audio_buffer_clone.lock().unwrap();
-
-                    // Store interleaved samples - we'll analyze separately later
-                    buffer.extend(data.iter().map(|&s| s as f32 / 32768.0));
-
-                    // Keep last 2 seconds
-                    let max_size = (sample_rate * 2.0) as usize * channels;
-                    if buffer.len() > max_size {
-                        let drain = buffer.len() - max_size;
-                        buffer.drain(0..drain);
-                    }
+                SampleFormat::I16 => {
+                    let channels = channels;
+                    let mut resampler = audio::Resampler::new(device_rate, sample_rate, channels);
+                    device.build_input_stream(
+                        &device_config.into(),
+                        move |data: &[i16], _| {
+                            let mut buffer = audio_buffer_clone.lock().unwrap();
+
+                            // Store interleaved samples - we'll analyze separately later
+                            let before = buffer.len();
+                            resampler.process(data.iter().map(|&s| s as f32 / 32768.0), &mut buffer);
+                            samples_received_clone.fetch_add(buffer.len() - before, Ordering::Relaxed);
+
+                            // Keep last 2 seconds
+                            let max_size = (sample_rate * 2.0) as usize * channels;
+                            if buffer.len() > max_size {
+                                let drain = buffer.len() - max_size;
+                                buffer.drain(0..drain);
+                            }
+                        },
+                        |err| eprintln!("Audio error: {}", err),
+                        None,
+                    )?
                 },
-                |err| eprintln!("Audio error: {}", err),
-                None,
-            )?
-        },
-        SampleFormat::U16 => {
-            let channels = channels;
-            device.build_input_stream(
-                &device_config.into(),
-                move |data: &[u16], _| {
-                    let mut buffer = audio_buffer_clone.lock().unwrap();
-
-                    // Store interleaved samples - we'll analyze separately later
-                    buffer.extend(data.iter().map(|&s| (s as f32 - 32768.0) / 32768.0));
-
-                    // Keep last 2 seconds
-                    let max_size = (sample_rate * 2.0) as usize * channels;
-                    if buffer.len() > max_size {
-                        let drain = buffer.len() - max_size;
-                        buffer.drain(0..drain);
-                    }
+                SampleFormat::U16 => {
+                    let channels = channels;
+                    let mut resampler = audio::Resampler::new(device_rate, sample_rate, channels);
+                    device.build_input_stream(
+                        &device_config.into(),
+                        move |data: &[u16], _| {
+                            let mut buffer = audio_buffer_clone.lock().unwrap();
+
+                            // Store interleaved samples - we'll analyze separately later
+                            let before = buffer.len();
+                            resampler.process(data.iter().map(|&s| (s as f32 - 32768.0) / 32768.0), &mut buffer);
+                            samples_received_clone.fetch_add(buffer.len() - before, Ordering::Relaxed);
+
+                            // Keep last 2 seconds
+                            let max_size = (sample_rate * 2.0) as usize * channels;
+                            if buffer.len() > max_size {
+                                let drain = buffer.len() - max_size;
+                                buffer.drain(0..drain);
+                            }
+                        },
+                        |err| eprintln!("Audio error: {}", err),
+                        None,
+                    )?
                 },
-                |err| eprintln!("Audio error: {}", err),
-                None,
-            )?
-        },
+                _ => {
+                    eprintln!("Unsupported sample format: {:?}", sample_format);
+                    std::process::exit(1);
+                }
+            };
+            stream.play()?;
+            Some(stream)
+        }
         _ => {
-            eprintln!("Unsupported sample format: {:?}", sample_format);
-            std::process::exit(1);
+            match test_signal {
+                Some(signal) => signal_generator::spawn(signal, &audio_buffer, &samples_received),
+                None => demo::spawn_generated_audio(&audio_buffer, &samples_received),
+            }
+            None
         }
     };
-
-    stream.play()?;
     println!("Audio stream started");
 
     // Setup multi-device manager
@@ -1229,12 +1580,25 @@ fn run_live_mode(config: &BandwidthConfig, delay_ms: Option<u64>, config_change_
         led_offset: d.led_offset,
         led_count: d.led_count,
         enabled: d.enabled,
+        max_fps: d.max_fps,
+        led_ma: d.led_ma,
+        psu_amps: d.psu_amps,
+        backup_for: d.backup_for.clone(),
+        reversed: d.reversed,
+        mirrored: d.mirrored,
+        protocol: d.protocol.clone(),
+        universe: d.universe,
+        start_channel: d.start_channel,
+        net: d.net,
+        subnet: d.subnet,
     }).collect();
 
     let md_config = MultiDeviceConfig {
         devices,
         send_parallel: config.multi_device_send_parallel,
         fail_fast: config.multi_device_fail_fast,
+        stagger_sends: config.multi_device_stagger_sends,
+        color_correction: ColorCorrection::from_config(&config),
     };
 
     let mut multi_device_manager = MultiDeviceManager::new(md_config)?;
@@ -1261,15 +1625,23 @@ fn run_live_mode(config: &BandwidthConfig, delay_ms: Option<u64>, config_change_
         "catmullrom" => InterpolationMode::CatmullRom,
         _ => InterpolationMode::Linear,
     };
+    let color_space = ColorSpace::from_name(&config.color_space);
 
     let (mut spectrum_gradient, mut spectrum_colors, mut spectrum_solid) =
-        build_gradient_from_color(&spectrum_color_str, config.use_gradient, interpolation_mode)?;
+        build_gradient_from_color(&spectrum_color_str, config.use_gradient, interpolation_mode, color_space)?;
+    let mut spectrogram_color_str = spectrogram_colors(&spectrum_color_str);
+
+    // Gradients used every frame - only rebuilt when their color string or settings change
+    let mut spectrogram_gradient_cache = GradientCache::new();
+    let mut left_gradient_cache = GradientCache::new();
+    let mut right_gradient_cache = GradientCache::new();
+    let mut tui_left_gradient_cache = GradientCache::new();
+    let mut tui_right_gradient_cache = GradientCache::new();
 
     // Track current config values for real-time updates
     let mut current_config = config.clone();
     let mut smoothed_magnitudes = vec![0.0_f32; current_config.total_leds];
     let threshold = 0.12; // Balanced threshold - sensitive but not too noisy
-    let mut frame_count = 0u64;
 
     // VU meter animation offset tracking
     let mut left_animation_offset = 0.0_f64;
@@ -1288,47 +1660,57 @@ fn run_live_mode(config: &BandwidthConfig, delay_ms: Option<u64>, config_change_
     // Track display levels for TUI
     let mut display_left_level = 0.0_f32;
     let mut display_right_level = 0.0_f32;
+    let mut display_left_db = vu::level_to_db(0.0);  // Channel levels in dBFS for the TUI readout
+    let mut display_right_db = vu::level_to_db(0.0);
 
     // Spectrogram buffer: stores frequency data over time for scrolling visualization
-    // Spectrogram REQUIRES 2D matrix mode (frequency vs time)
-    let (spec_width, spec_height) = (current_config.matrix_2d_width, current_config.matrix_2d_height);
+    // The scrolling spectrogram needs 2D matrix mode (frequency vs time); 1D keeps a decaying trail per LED instead
+    // The spectrogram runs progressive rows on a single matrix; the spectrum matrix runs serpentine
+    let spectrogram_layout = MatrixLayout::for_mode(&current_config, current_config.matrix.width, current_config.matrix.height, false);
+    let mut matrix_layout = MatrixLayout::for_mode(&current_config, current_config.matrix.width, current_config.matrix.height, true);
+    let (spec_width, spec_height) = (spectrogram_layout.width(), spectrogram_layout.height());
     // Store as 2D buffer: spectrogram_buffer[time_column][freq_row] = magnitude
     let mut spectrogram_buffer: Vec<Vec<f32>> = vec![vec![0.0; spec_height]; spec_width];
     let mut spec_scroll_accumulator = 0.0_f64;  // Accumulates fractional scroll pixels
+    let mut spectrogram_trail = spectrogram::StripTrail::new();  // 1D spectrogram: decaying peak per LED
+    let mut onset_detector = spectrogram::OnsetDetector::new();
+    let mut spectrogram_pulses: Vec<f32> = Vec::new();  // Onset pulse stored with each time column, scrolling with it
 
     // Store color strings for TUI rendering (gradients will be rebuilt)
     // Initialize with config values, using unified color resolution system
-    // Channel mapping: TX=Right, RX=Left
-    let (tx_color_resolved, rx_color_resolved) = resolve_tx_rx_colors(&current_config);
-    let mut tui_left_color_str = rx_color_resolved;  // Left channel = RX
-    let mut tui_right_color_str = tx_color_resolved;  // Right channel = TX
+    // vu_left_color / vu_right_color, falling back to the channel mapping TX=Right, RX=Left
+    let (mut tui_left_color_str, mut tui_right_color_str) = resolve_vu_colors(&current_config);
     let mut tui_use_gradient = current_config.use_gradient;
     let mut tui_interpolation_mode = match current_config.interpolation.as_str() {
         "basis" => InterpolationMode::Basis,
         "catmullrom" => InterpolationMode::CatmullRom,
         _ => InterpolationMode::Linear,
     };
+    let mut tui_color_space = ColorSpace::from_name(&current_config.color_space);
     let mut tui_left_animation_offset = 0.0_f64;
     let mut tui_right_animation_offset = 0.0_f64;
 
     // Calculate attack/decay factors from config (ms to per-frame multiplier)
     let mut current_fps = current_config.fps;
     let mut frame_time_ms = 1000.0 / current_fps;
-    let mut attack_factor = (frame_time_ms / current_config.attack_ms as f64).min(1.0);
-    let mut decay_factor = (frame_time_ms / current_config.decay_ms as f64).min(1.0);
+    let mut attack_factor = (frame_time_ms / current_config.audio.attack_ms as f64).min(1.0);
+    let mut decay_factor = (frame_time_ms / current_config.audio.decay_ms as f64).min(1.0);
 
     println!("Running at {} FPS ({:.2}ms per frame)", current_fps, frame_time_ms);
     println!("Attack: {}ms ({:.3} per frame, ~{} frames), Decay: {}ms ({:.3} per frame, ~{} frames)",
-             current_config.attack_ms, attack_factor, (current_config.attack_ms as f64 / frame_time_ms) as u32,
-             current_config.decay_ms, decay_factor, (current_config.decay_ms as f64 / frame_time_ms) as u32);
+             current_config.audio.attack_ms, attack_factor, (current_config.audio.attack_ms as f64 / frame_time_ms) as u32,
+             current_config.audio.decay_ms, decay_factor, (current_config.audio.decay_ms as f64 / frame_time_ms) as u32);
 
-    if current_config.spectrogram {
+    if current_config.spectrogram.enabled {
         println!("\n📈 SPECTROGRAM MODE ENABLED");
-        println!("   Scroll direction: {}", current_config.spectrogram_scroll_direction);
-        println!("   Scroll speed: {} pixels/sec", current_config.spectrogram_scroll_speed);
-        println!("   Color mode: {}", current_config.spectrogram_color_mode);
-        println!("   Window size: {} samples", current_config.spectrogram_window_size);
-    } else if current_config.vu {
+        println!("   Scroll direction: {}", current_config.spectrogram.scroll_direction);
+        println!("   Scroll speed: {} pixels/sec", current_config.spectrogram.scroll_speed);
+        println!("   Color mode: {}", current_config.spectrogram.color_mode);
+        println!("   Window size: {} samples", current_config.spectrogram.window_size);
+        if current_config.spectrogram.strip {
+            println!("   Layout: 1D strip ({}ms trail)", current_config.spectrogram.trail_ms);
+        }
+    } else if current_config.vu.enabled {
         println!("\n🎚️  VU METER MODE ENABLED");
         println!("   Left channel:  LEDs 0-{}", current_config.total_leds / 2 - 1);
         println!("   Right channel: LEDs {}-{}", current_config.total_leds / 2, current_config.total_leds - 1);
@@ -1357,15 +1739,33 @@ fn run_live_mode(config: &BandwidthConfig, delay_ms: Option<u64>, config_change_
     // Frame buffer for delay - stores (send_time, frame_data)
     let mut frame_buffer: VecDeque<(Instant, Vec<u8>)> = VecDeque::new();
 
+    // Per-frame working buffers, kept between frames so steady-state frames don't allocate
+    let mut spare_frames: Vec<Vec<u8>> = Vec::new();  // Sent frames, recycled for rendering
+    let mut samples: Vec<f32> = Vec::new();
+    let mut last_window_at = 0usize;  // samples_received when the current analysis window was taken
+    let mut fft_buffer: Vec<Complex<f32>> = Vec::new();
+    let mut fft_scratch: Vec<Complex<f32>> = Vec::new();
+    let mut freq_magnitudes: Vec<f32> = Vec::new();
+    let mut bin_magnitudes: Vec<f32> = Vec::new();
+    let mut left_bins: Vec<f32> = Vec::new();
+    let mut right_bins: Vec<f32> = Vec::new();
+    let mut noise_floors = [audio::NoiseFloor::new(), audio::NoiseFloor::new()];  // Left (or mono) and right spectrum
+    let mut alloc_counter = FrameAllocCounter::new("live");
+    let mut idle_animator = IdleAnimator::new();
+    let mut idle_settings = IdleSettings::from_config(&config);
+    let mut strobe_settings = StrobeSettings::from_config(&config);
+    let mut left_strobe = Strobe::new();  // Left VU channel, or the whole strip in other live modes
+    let mut right_strobe = Strobe::new();
+    let mut spectrum_bars = SpectrumBars::new();
+
     // Config info toggle
     let mut show_config_info = false;
 
     // Main loop - use global fps from config
-    let mut frame_duration = Duration::from_secs_f64(1.0 / current_fps);
+    let mut pacer = FramePacer::new(current_fps);
 
     loop {
         let loop_start = Instant::now();
-        frame_count += 1;
 
         // Check for keyboard input
         if poll(Duration::from_millis(0))? {
@@ -1383,25 +1783,29 @@ fn run_live_mode(config: &BandwidthConfig, delay_ms: Option<u64>, config_change_
         }
 
         // Check for config updates via SSE broadcast
-        if let Ok(()) = config_change_rx.try_recv() {
-            let new_config = match BandwidthConfig::load() {
-                Ok(c) => c,
-                Err(_) => continue, // Skip if load fails
-            };
+        if let Ok(delta) = config_change_rx.try_recv() {
+            let new_config = delta.config_or(&current_config);
 
             // Update FPS if changed
             if new_config.fps != current_config.fps {
                 current_fps = new_config.fps;
                 frame_time_ms = 1000.0 / current_fps;
-                frame_duration = Duration::from_secs_f64(1.0 / current_fps);
+                pacer.set_fps(current_fps);
             }
 
             // Update attack/decay if changed
-            if new_config.attack_ms != current_config.attack_ms || new_config.fps != current_config.fps {
-                attack_factor = (frame_time_ms / new_config.attack_ms as f64).min(1.0);
+            if new_config.audio.attack_ms != current_config.audio.attack_ms || new_config.fps != current_config.fps {
+                attack_factor = (frame_time_ms / new_config.audio.attack_ms as f64).min(1.0);
+            }
+            if new_config.audio.decay_ms != current_config.audio.decay_ms || new_config.fps != current_config.fps {
+                decay_factor = (frame_time_ms / new_config.audio.decay_ms as f64).min(1.0);
             }
-            if new_config.decay_ms != current_config.decay_ms || new_config.fps != current_config.fps {
-                decay_factor = (frame_time_ms / new_config.decay_ms as f64).min(1.0);
+
+            // Calibrate afresh each time noise floor learning is switched on
+            if new_config.audio.noise_floor && !current_config.audio.noise_floor {
+                for floor in noise_floors.iter_mut() {
+                    floor.reset();
+                }
             }
 
             // Resize smoothed magnitudes if total_leds changed
@@ -1411,13 +1815,16 @@ fn run_live_mode(config: &BandwidthConfig, delay_ms: Option<u64>, config_change_
 
             // Update spectrum gradient if color or interpolation settings changed (for FFT mode)
             if new_config.color != current_config.color ||
+               new_config.gradients != current_config.gradients ||
                new_config.use_gradient != current_config.use_gradient ||
-               new_config.interpolation != current_config.interpolation {
+               new_config.interpolation != current_config.interpolation ||
+               new_config.color_space != current_config.color_space {
                 let new_spectrum_color_str = if !new_config.color.is_empty() {
                     gradients::resolve_color_string(&new_config.color)
                 } else {
                     "FF0000,FF7F00,FFFF00,00FF00,0000FF,4B0082,9400D3".to_string()
                 };
+                spectrogram_color_str = spectrogram_colors(&new_spectrum_color_str);
 
                 let new_interpolation_mode = match new_config.interpolation.to_lowercase().as_str() {
                     "basis" => InterpolationMode::Basis,
@@ -1425,7 +1832,7 @@ fn run_live_mode(config: &BandwidthConfig, delay_ms: Option<u64>, config_change_
                     _ => InterpolationMode::Linear,
                 };
 
-                if let Ok((grad, colors, solid)) = build_gradient_from_color(&new_spectrum_color_str, new_config.use_gradient, new_interpolation_mode) {
+                if let Ok((grad, colors, solid)) = build_gradient_from_color(&new_spectrum_color_str, new_config.use_gradient, new_interpolation_mode, ColorSpace::from_name(&new_config.color_space)) {
                     spectrum_gradient = grad;
                     spectrum_colors = colors;
                     spectrum_solid = solid;
@@ -1436,31 +1843,24 @@ fn run_live_mode(config: &BandwidthConfig, delay_ms: Option<u64>, config_change_
             if new_config.color != current_config.color ||
                new_config.tx_color != current_config.tx_color ||
                new_config.rx_color != current_config.rx_color ||
+               new_config.vu.left_color != current_config.vu.left_color ||
+               new_config.vu.right_color != current_config.vu.right_color ||
                new_config.use_gradient != current_config.use_gradient ||
-               new_config.interpolation != current_config.interpolation {
+               new_config.interpolation != current_config.interpolation ||
+               new_config.color_space != current_config.color_space {
                 // Use unified color resolution system
-                // Channel mapping: TX=Right, RX=Left
-                let (tx_color_resolved, rx_color_resolved) = resolve_tx_rx_colors(&new_config);
-                tui_left_color_str = rx_color_resolved;  // Left channel = RX
-                tui_right_color_str = tx_color_resolved;  // Right channel = TX
+                (tui_left_color_str, tui_right_color_str) = resolve_vu_colors(&new_config);
                 tui_use_gradient = new_config.use_gradient;
                 tui_interpolation_mode = match new_config.interpolation.as_str() {
                     "basis" => InterpolationMode::Basis,
                     "catmullrom" => InterpolationMode::CatmullRom,
                     _ => InterpolationMode::Linear,
                 };
+                tui_color_space = ColorSpace::from_name(&new_config.color_space);
             }
 
-            // Reinitialize multi-device manager if device config changed
-            let devices_changed = new_config.wled_devices.len() != current_config.wled_devices.len() ||
-                new_config.wled_devices.iter().zip(current_config.wled_devices.iter()).any(|(new, old)| {
-                    new.ip != old.ip ||
-                    new.led_offset != old.led_offset ||
-                    new.led_count != old.led_count ||
-                    new.enabled != old.enabled
-                }) ||
-                new_config.multi_device_send_parallel != current_config.multi_device_send_parallel ||
-                new_config.multi_device_fail_fast != current_config.multi_device_fail_fast;
+            // Update the multi-device manager in place if device config changed
+            let devices_changed = delta.changed_any(config::DEVICE_CONFIG_KEYS);
 
             if devices_changed {
                 let devices: Vec<WLEDDevice> = new_config.wled_devices.iter().map(|d| WLEDDevice {
@@ -1468,28 +1868,40 @@ fn run_live_mode(config: &BandwidthConfig, delay_ms: Option<u64>, config_change_
                     led_offset: d.led_offset,
                     led_count: d.led_count,
                             enabled: d.enabled,
+                    max_fps: d.max_fps,
+                    led_ma: d.led_ma,
+                    psu_amps: d.psu_amps,
+                    backup_for: d.backup_for.clone(),
+                    reversed: d.reversed,
+                    mirrored: d.mirrored,
+                    protocol: d.protocol.clone(),
+                    universe: d.universe,
+                    start_channel: d.start_channel,
+                    net: d.net,
+                    subnet: d.subnet,
                 }).collect();
 
                 let md_config = MultiDeviceConfig {
                     devices,
                     send_parallel: new_config.multi_device_send_parallel,
                     fail_fast: new_config.multi_device_fail_fast,
+                    stagger_sends: new_config.multi_device_stagger_sends,
+                    color_correction: ColorCorrection::from_config(&new_config),
                 };
 
-                match MultiDeviceManager::new(md_config) {
-                    Ok(new_manager) => {
-                        multi_device_manager = new_manager;
-                        println!("\n✓ Reinitialized multi-device manager");
+                match multi_device_manager.reconfigure(md_config) {
+                    Ok(()) => {
+                        println!("\n✓ Updated multi-device manager");
                     }
                     Err(e) => {
-                        eprintln!("\n⚠️  Failed to reinitialize multi-device manager: {}", e);
+                        eprintln!("\n⚠️  Failed to update multi-device manager: {}", e);
                         eprintln!("   Continuing with previous configuration");
                     }
                 }
             }
 
-            // Check if mode changed - if so, exit live mode to allow mode switch
-            if new_config.mode != "live" {
+            // Check if mode changed (or a restart was requested) - if so, exit live mode to allow mode switch
+            if new_config.mode != "live" || system::restart_requested() {
                 println!("\n🔄 Mode changed to '{}', exiting Live Audio mode...", new_config.mode);
                 terminal.show_cursor()?;
                 disable_raw_mode()?;
@@ -1498,8 +1910,17 @@ fn run_live_mode(config: &BandwidthConfig, delay_ms: Option<u64>, config_change_
             }
 
             // Check if audio device changed - if so, exit and restart with new device
-            if new_config.audio_device != current_config.audio_device && !new_config.audio_device.is_empty() {
-                println!("\n🔄 Audio device changed to '{}', restarting Live Audio mode...", new_config.audio_device);
+            // FFT size and the analysis rate fix the bin layout, so changing either restarts the mode too
+            if new_config.audio.fft_size != current_config.audio.fft_size || new_config.audio.analysis_rate != current_config.audio.analysis_rate {
+                println!("\n🔄 FFT size or analysis rate changed, restarting Live Audio mode...");
+                terminal.show_cursor()?;
+                disable_raw_mode()?;
+                terminal.backend_mut().execute(LeaveAlternateScreen)?;
+                return Ok(ModeExitReason::ModeChanged);
+            }
+
+            if new_config.audio.device != current_config.audio.device && !new_config.audio.device.is_empty() {
+                println!("\n🔄 Audio device changed to '{}', restarting Live Audio mode...", new_config.audio.device);
                 terminal.show_cursor()?;
                 disable_raw_mode()?;
                 terminal.backend_mut().execute(LeaveAlternateScreen)?;
@@ -1507,9 +1928,12 @@ fn run_live_mode(config: &BandwidthConfig, delay_ms: Option<u64>, config_change_
             }
 
             current_config = new_config;
+            idle_settings = IdleSettings::from_config(&current_config);
+            strobe_settings = StrobeSettings::from_config(&current_config);
+            matrix_layout = MatrixLayout::for_mode(&current_config, current_config.matrix.width, current_config.matrix.height, true);
 
             // Update animation directions if peak toggle is disabled
-            if !current_config.peak_direction_toggle {
+            if !current_config.vu.peak_direction_toggle {
                 left_animation_direction = current_config.rx_animation_direction.clone();
                 right_animation_direction = current_config.tx_animation_direction.clone();
             }
@@ -1517,44 +1941,64 @@ fn run_live_mode(config: &BandwidthConfig, delay_ms: Option<u64>, config_change_
 
         // Get audio samples (interleaved if stereo)
         // For VU mode, use smaller sample window (512) for faster response
-        // For FFT mode, use larger window (1024) for better frequency resolution
-        let sample_window = if current_config.vu { 512 } else { fft_size };
-        let mut samples = {
-            let buffer = audio_buffer.lock().unwrap();
-            let needed_samples = sample_window * channels;
-            if buffer.len() >= needed_samples {
-                buffer[buffer.len() - needed_samples..].to_vec()
-            } else {
-                vec![0.0; needed_samples]
-            }
+        // For FFT modes, use the FFT size (or the spectrogram's window) for frequency resolution
+        alloc_counter.begin_frame();
+        let render_start = Instant::now();
+        let sample_window = if current_config.vu.enabled {
+            512
+        } else if current_config.spectrogram.enabled {
+            current_config.spectrogram.window_size
+        } else {
+            fft_size
         };
+        let fft_window = WindowFunction::from_name(&current_config.audio.fft_window);
+
+        // FFT windows step forward by the hop - until that many new samples arrive, the last window is reused
+        let received = samples_received.load(Ordering::Relaxed);
+        let hop = if current_config.vu.enabled { 1 } else { fft::hop_samples(sample_window, current_config.audio.fft_overlap) };
+        let needed_samples = sample_window * channels;
+        if samples.len() != needed_samples || received.saturating_sub(last_window_at) >= hop * channels {
+            last_window_at = received;
+            {
+                let buffer = audio_buffer.lock().unwrap();
+                samples.clear();
+                if buffer.len() >= needed_samples {
+                    samples.extend_from_slice(&buffer[buffer.len() - needed_samples..]);
+                } else {
+                    samples.resize(needed_samples, 0.0);
+                }
+            }
 
-        // Apply audio gain adjustment
-        // Gain formula: multiplier = 1.0 + (audio_gain / 100.0)
-        // audio_gain = 0 → multiplier = 1.0 (no change)
-        // audio_gain = 100 → multiplier = 2.0 (double amplitude)
-        // audio_gain = -100 → multiplier = 0.0 (muted)
-        if current_config.audio_gain != 0.0 {
-            let gain_multiplier = 1.0 + (current_config.audio_gain / 100.0);
-            for sample in samples.iter_mut() {
-                *sample *= gain_multiplier as f32;
+            // Apply audio gain adjustment
+            // Gain formula: multiplier = 1.0 + (audio_gain / 100.0)
+            // audio_gain = 0 → multiplier = 1.0 (no change)
+            // audio_gain = 100 → multiplier = 2.0 (double amplitude)
+            // audio_gain = -100 → multiplier = 0.0 (muted)
+            if current_config.audio.gain != 0.0 {
+                let gain_multiplier = 1.0 + (current_config.audio.gain / 100.0);
+                for sample in samples.iter_mut() {
+                    *sample *= gain_multiplier as f32;
+                }
             }
         }
 
-        // Create frame buffer
-        let mut frame = vec![0u8; current_config.total_leds * 3];
+        // Create frame buffer (a recycled one when available)
+        let mut frame = spare_frames.pop().unwrap_or_default();
+        frame.clear();
+        frame.resize(current_config.total_leds * 3, 0);
 
         // VU METER MODE or SPECTROGRAM MODE or FFT SPECTRUM MODE
-        if current_config.spectrogram {
+        if current_config.spectrogram.enabled {
             // === SPECTROGRAM MODE ===
             // Scrolling frequency visualization (like FFmpeg showspec or Winamp voiceprint)
 
             // 1. Perform FFT on audio samples
-            let window_size = current_config.spectrogram_window_size.min(samples.len() / channels);
-            let mut fft_input = vec![Complex::new(0.0, 0.0); window_size];
+            let window_size = current_config.spectrogram.window_size.min(samples.len() / channels);
+            fft_buffer.clear();
+            fft_buffer.resize(window_size, Complex::new(0.0, 0.0));
 
             // Apply audio gain
-            let gain_multiplier = 1.0 + (current_config.audio_gain / 100.0);
+            let gain_multiplier = 1.0 + (current_config.audio.gain / 100.0);
 
             // Mix down to mono for FFT analysis
             for i in 0..window_size {
@@ -1564,228 +2008,228 @@ fn run_live_mode(config: &BandwidthConfig, delay_ms: Option<u64>, config_change_
                 } else {
                     samples[sample_idx]
                 };
-                fft_input[i] = Complex::new(mono_sample * gain_multiplier as f32, 0.0);
+                fft_buffer[i] = Complex::new(mono_sample * gain_multiplier as f32 * fft_window.coefficient(i, window_size), 0.0);
             }
 
-            // Perform FFT
-            let mut planner = FftPlanner::new();
-            let fft = planner.plan_fft_forward(window_size);
-            fft.process(&mut fft_input);
+            // Perform FFT (the planner caches plans, so this only builds one when the window size changes)
+            let spectrogram_fft = planner.plan_fft_forward(window_size);
+            process_fft(spectrogram_fft.as_ref(), &mut fft_buffer, &mut fft_scratch);
 
             // 2. Extract frequency magnitudes (only positive frequencies)
             let freq_bins = window_size / 2;
-            let mut freq_magnitudes = Vec::with_capacity(spec_height);
+            freq_magnitudes.clear();
 
-            // Map frequency bins to LED rows (log scale for better visual)
-            for row in 0..spec_height {
-                let freq_ratio = (row as f64 / spec_height as f64).powf(2.0);  // Exponential mapping
+            // Map frequency bins to LED rows (log scale for better visual) - the whole strip in 1D
+            let spec_rows = if current_config.spectrogram.strip { current_config.total_leds } else { spec_height };
+            for row in 0..spec_rows {
+                let freq_ratio = (row as f64 / spec_rows as f64).powf(2.0);  // Exponential mapping
                 let bin_idx = (freq_ratio * freq_bins as f64).min((freq_bins - 1) as f64) as usize;
-                let magnitude = (fft_input[bin_idx].re * fft_input[bin_idx].re +
-                                fft_input[bin_idx].im * fft_input[bin_idx].im).sqrt();
+                let magnitude = (fft_buffer[bin_idx].re * fft_buffer[bin_idx].re +
+                                fft_buffer[bin_idx].im * fft_buffer[bin_idx].im).sqrt();
                 freq_magnitudes.push(magnitude * 4.0);  // Scale for visibility
             }
+            spectrogram::record_column(&freq_magnitudes);
+            let onset_overlay = spectrogram::OnsetOverlay::from_config(&current_config.spectrogram.onset, &current_config.spectrogram.onset_color);
+            let onset_pulse = onset_detector.update(&freq_magnitudes, current_config.spectrogram.onset_sensitivity as f32, frame_time_ms / 1000.0);
 
-            // 3. Scroll the spectrogram buffer
-            spec_scroll_accumulator += current_config.spectrogram_scroll_speed * (frame_time_ms / 1000.0);
-            let pixels_to_scroll = spec_scroll_accumulator.floor() as usize;
-            spec_scroll_accumulator -= pixels_to_scroll as f64;
+            // 3. Pick the color mapping
+            let (gradient, _, _) = spectrogram_gradient_cache.get(
+                &spectrogram_color_str,
+                true,  // Always use gradient for spectrogram
+                interpolation_mode,
+                color_space,
+            )?;
 
-            if pixels_to_scroll > 0 {
-                match current_config.spectrogram_scroll_direction.as_str() {
-                    "right" => {
-                        // Shift all columns to the right, insert new data at left
-                        for _ in 0..pixels_to_scroll {
-                            spectrogram_buffer.rotate_right(1);
-                            spectrogram_buffer[0] = freq_magnitudes.clone();
+            // Color for one cell from its magnitude and frequency position (0.0 = lowest)
+            let spec_color = |magnitude: f32, freq_pos: f64| -> [u8; 4] {
+                match current_config.spectrogram.color_mode.as_str() {
+                    "intensity" => {
+                        // Map magnitude to gradient position
+                        if let Some(grad) = gradient {
+                            grad.at(magnitude as f64)
+                        } else {
+                            [0, 0, 0, 255]
                         }
                     }
-                    "left" => {
-                        // Shift all columns to the left, insert new data at right
-                        for _ in 0..pixels_to_scroll {
-                            spectrogram_buffer.rotate_left(1);
-                            spectrogram_buffer[spec_width - 1] = freq_magnitudes.clone();
+                    "frequency" => {
+                        // Map frequency (y position) to gradient
+                        if let Some(grad) = gradient {
+                            let rgba = grad.at(freq_pos);
+                            // Modulate brightness by magnitude
+                            let mag_f64 = magnitude as f64;
+                            [(rgba[0] as f64 * mag_f64) as u8,
+                             (rgba[1] as f64 * mag_f64) as u8,
+                             (rgba[2] as f64 * mag_f64) as u8,
+                             255]
+                        } else {
+                            [0, 0, 0, 255]
                         }
                     }
-                    "down" => {
-                        // Transpose: time is vertical, frequency is horizontal
-                        // Shift rows down, insert new data at top
-                        for _ in 0..pixels_to_scroll {
-                            for col in 0..spec_width {
-                                spectrogram_buffer[col].rotate_right(1);
-                                let freq_idx = (col * spec_height) / spec_width;
-                                spectrogram_buffer[col][0] = freq_magnitudes[freq_idx.min(spec_height - 1)];
-                            }
+                    "volume" => {
+                        // Use overall volume level to shift hue
+                        let vol_level = freq_magnitudes.iter().sum::<f32>() / freq_magnitudes.len() as f32;
+                        if let Some(grad) = gradient {
+                            let hue_shift = (vol_level * 0.5) as f64;
+                            let rgba = grad.at((hue_shift + magnitude as f64 * 0.5).min(1.0));
+                            let mag_f64 = magnitude as f64;
+                            [(rgba[0] as f64 * mag_f64) as u8,
+                             (rgba[1] as f64 * mag_f64) as u8,
+                             (rgba[2] as f64 * mag_f64) as u8,
+                             255]
+                        } else {
+                            [0, 0, 0, 255]
                         }
                     }
-                    "up" => {
-                        // Transpose: time is vertical, frequency is horizontal
-                        // Shift rows up, insert new data at bottom
-                        for _ in 0..pixels_to_scroll {
-                            for col in 0..spec_width {
-                                spectrogram_buffer[col].rotate_left(1);
-                                let freq_idx = (col * spec_height) / spec_width;
-                                spectrogram_buffer[col][spec_height - 1] = freq_magnitudes[freq_idx.min(spec_height - 1)];
-                            }
+                    _ => {
+                        // Default to intensity mode
+                        if let Some(grad) = gradient {
+                            grad.at(magnitude as f64)
+                        } else {
+                            [0, 0, 0, 255]
                         }
                     }
-                    _ => {}  // Unknown direction, do nothing
                 }
-            }
-
-            // 4. Map 2D spectrogram buffer to LED frame with color mapping
-            // For spectrogram, always use a gradient (default to rainbow if none specified)
-            let spec_gradient_str = if spectrum_color_str.contains(',') || spectrum_color_str.contains("rainbow") {
-                spectrum_color_str.clone()
-            } else {
-                "rainbow".to_string()
             };
-            let (gradient, _, _) = build_gradient_from_color(
-                &spec_gradient_str,
-                true,  // Always use gradient for spectrogram
-                interpolation_mode,
-            )?;
 
-            // Find max magnitude in entire buffer for normalization
-            let mut buffer_max = 0.0_f32;
-            for col in &spectrogram_buffer {
-                for &mag in col {
-                    buffer_max = buffer_max.max(mag);
+            if current_config.spectrogram.strip {
+                // 1D: the strip shows the latest spectrum, each LED's peak fading out over the trail time
+                let levels = spectrogram_trail.update(&freq_magnitudes, spec_rows, current_config.spectrogram.trail_ms, frame_time_ms / 1000.0);
+                for (led, magnitude) in levels.enumerate() {
+                    let color = spec_color(magnitude, led as f64 / spec_rows as f64);
+                    let color = onset_overlay.apply([color[0], color[1], color[2]], onset_pulse);
+                    frame[led * 3] = color[0];
+                    frame[led * 3 + 1] = color[1];
+                    frame[led * 3 + 2] = color[2];
                 }
-            }
-            let normalization = if buffer_max > 0.0 { 1.0 / buffer_max } else { 1.0 };
-
-            for x in 0..spec_width {
-                for y in 0..spec_height {
-                    let magnitude = (spectrogram_buffer[x][y] * normalization).min(1.0);
-
-                    // Calculate color based on color mode
-                    let color = match current_config.spectrogram_color_mode.as_str() {
-                        "intensity" => {
-                            // Map magnitude to gradient position
-                            if let Some(ref grad) = gradient {
-                                grad.at(magnitude as f64).to_rgba8()
-                            } else {
-                                [0, 0, 0, 255]
+            } else {
+                // 2D: scroll the spectrogram buffer
+                spec_scroll_accumulator += current_config.spectrogram.scroll_speed * (frame_time_ms / 1000.0);
+                let pixels_to_scroll = spec_scroll_accumulator.floor() as usize;
+                spec_scroll_accumulator -= pixels_to_scroll as f64;
+
+                // Time runs along x when scrolling left/right, along y when scrolling up/down
+                let vertical = matches!(current_config.spectrogram.scroll_direction.as_str(), "up" | "down");
+                spectrogram_pulses.resize(if vertical { spec_height } else { spec_width }, 0.0);
+
+                if pixels_to_scroll > 0 {
+                    match current_config.spectrogram.scroll_direction.as_str() {
+                        "right" => {
+                            // Shift all columns to the right, insert new data at left
+                            for _ in 0..pixels_to_scroll {
+                                spectrogram_buffer.rotate_right(1);
+                                spectrogram_buffer[0].copy_from_slice(&freq_magnitudes);
+                                spectrogram_pulses.rotate_right(1);
+                                spectrogram_pulses[0] = onset_pulse;
                             }
                         }
-                        "frequency" => {
-                            // Map frequency (y position) to gradient
-                            if let Some(ref grad) = gradient {
-                                let freq_pos = y as f64 / spec_height as f64;
-                                let rgba = grad.at(freq_pos).to_rgba8();
-                                // Modulate brightness by magnitude
-                                let mag_f64 = magnitude as f64;
-                                [(rgba[0] as f64 * mag_f64) as u8,
-                                 (rgba[1] as f64 * mag_f64) as u8,
-                                 (rgba[2] as f64 * mag_f64) as u8,
-                                 255]
-                            } else {
-                                [0, 0, 0, 255]
+                        "left" => {
+                            // Shift all columns to the left, insert new data at right
+                            for _ in 0..pixels_to_scroll {
+                                spectrogram_buffer.rotate_left(1);
+                                spectrogram_buffer[spec_width - 1].copy_from_slice(&freq_magnitudes);
+                                spectrogram_pulses.rotate_left(1);
+                                spectrogram_pulses[spec_width - 1] = onset_pulse;
                             }
                         }
-                        "volume" => {
-                            // Use overall volume level to shift hue
-                            let vol_level = freq_magnitudes.iter().sum::<f32>() / freq_magnitudes.len() as f32;
-                            if let Some(ref grad) = gradient {
-                                let hue_shift = (vol_level * 0.5) as f64;
-                                let rgba = grad.at((hue_shift + magnitude as f64 * 0.5).min(1.0)).to_rgba8();
-                                let mag_f64 = magnitude as f64;
-                                [(rgba[0] as f64 * mag_f64) as u8,
-                                 (rgba[1] as f64 * mag_f64) as u8,
-                                 (rgba[2] as f64 * mag_f64) as u8,
-                                 255]
-                            } else {
-                                [0, 0, 0, 255]
+                        "down" => {
+                            // Transpose: time is vertical, frequency is horizontal
+                            // Shift rows down, insert new data at top
+                            for _ in 0..pixels_to_scroll {
+                                for col in 0..spec_width {
+                                    spectrogram_buffer[col].rotate_right(1);
+                                    let freq_idx = (col * spec_height) / spec_width;
+                                    spectrogram_buffer[col][0] = freq_magnitudes[freq_idx.min(spec_height - 1)];
+                                }
+                                spectrogram_pulses.rotate_right(1);
+                                spectrogram_pulses[0] = onset_pulse;
                             }
                         }
-                        _ => {
-                            // Default to intensity mode
-                            if let Some(ref grad) = gradient {
-                                grad.at(magnitude as f64).to_rgba8()
-                            } else {
-                                [0, 0, 0, 255]
+                        "up" => {
+                            // Transpose: time is vertical, frequency is horizontal
+                            // Shift rows up, insert new data at bottom
+                            for _ in 0..pixels_to_scroll {
+                                for col in 0..spec_width {
+                                    spectrogram_buffer[col].rotate_left(1);
+                                    let freq_idx = (col * spec_height) / spec_width;
+                                    spectrogram_buffer[col][spec_height - 1] = freq_magnitudes[freq_idx.min(spec_height - 1)];
+                                }
+                                spectrogram_pulses.rotate_left(1);
+                                spectrogram_pulses[spec_height - 1] = onset_pulse;
                             }
                         }
-                    };
-
-                    // Map 2D spectrogram position to 1D LED strip (flip Y so low freq is at bottom)
-                    let led_idx = (spec_height - 1 - y) * spec_width + x;
+                        _ => {}  // Unknown direction, do nothing
+                    }
+                }
 
-                    if led_idx < current_config.total_leds {
-                        let offset = led_idx * 3;
-                        frame[offset] = color[0];
-                        frame[offset + 1] = color[1];
-                        frame[offset + 2] = color[2];
+                // 4. Find max magnitude in entire buffer for normalization
+                let mut buffer_max = 0.0_f32;
+                for col in &spectrogram_buffer {
+                    for &mag in col {
+                        buffer_max = buffer_max.max(mag);
                     }
                 }
-            }
-        } else if current_config.vu {
-            // === VU METER MODE ===
-            // Classic stereo VU meter: left channel = first half, right channel = second half
+                let normalization = if buffer_max > 0.0 { 1.0 / buffer_max } else { 1.0 };
 
-            // Calculate peak levels for each channel (more responsive than RMS for VU meters)
-            let left_peak;
-            let right_peak;
+                for x in 0..spec_width {
+                    for y in 0..spec_height {
+                        let magnitude = (spectrogram_buffer[x][y] * normalization).min(1.0);
 
-            if channels >= 2 {
-                // Stereo or multi-channel - extract only left (ch 0) and right (ch 1) channels
-                let mut left_max = 0.0_f32;
-                let mut right_max = 0.0_f32;
-                let sample_count = samples.len() / channels;
+                        let color = spec_color(magnitude, y as f64 / spec_height as f64);
+                        let pulse = spectrogram_pulses[if vertical { y } else { x }];
+                        let color = onset_overlay.apply([color[0], color[1], color[2]], pulse);
 
-                for i in 0..sample_count {
-                    let left = samples[i * channels].abs();      // Channel 0 (left)
-                    let right = samples[i * channels + 1].abs(); // Channel 1 (right)
-                    left_max = left_max.max(left);
-                    right_max = right_max.max(right);
-                }
+                        // Map 2D spectrogram position to its LED (flip Y so low freq is at bottom)
+                        let Some(led_idx) = spectrogram_layout.index(x, spec_height - 1 - y) else {
+                            continue;
+                        };
 
-                left_peak = left_max;
-                right_peak = right_max;
-            } else {
-                // Mono - use same signal for both channels
-                let peak = samples.iter().map(|s| s.abs()).fold(0.0_f32, f32::max);
-                left_peak = peak;
-                right_peak = peak;
+                        if led_idx < current_config.total_leds {
+                            let offset = led_idx * 3;
+                            frame[offset] = color[0];
+                            frame[offset + 1] = color[1];
+                            frame[offset + 2] = color[2];
+                        }
+                    }
+                }
             }
+        } else if current_config.vu.enabled {
+            // === VU METER MODE ===
+            // Classic stereo VU meter: left channel = first half, right channel = second half
+
+            // Measure each channel (peak or RMS, per the configured ballistics)
+            let ballistics = VuBallistics::from_name(&current_config.vu.ballistics);
+            let (left_peak, right_peak) = ballistics.measure_levels(&samples, channels, current_config.vu.mono);
 
-            // Apply attack/decay smoothing
+            // Apply attack/decay smoothing (or the fixed VU integration)
             if smoothed_magnitudes.len() != 2 {
                 smoothed_magnitudes = vec![0.0; 2];
             }
 
             for (i, peak) in [left_peak, right_peak].iter().enumerate() {
-                let target = *peak;
-                let current = smoothed_magnitudes[i];
-                smoothed_magnitudes[i] = if target > current {
-                    current + (target - current) * attack_factor as f32
-                } else {
-                    current + (target - current) * decay_factor as f32
-                };
+                smoothed_magnitudes[i] = ballistics.smooth(
+                    smoothed_magnitudes[i],
+                    *peak,
+                    attack_factor as f32,
+                    decay_factor as f32,
+                    frame_time_ms / 1000.0,
+                );
             }
 
-            let mut smoothed_left = smoothed_magnitudes[0];
-            let mut smoothed_right = smoothed_magnitudes[1];
-
-            // Apply VU meter scaling - boost levels for better visibility
-            // Higher sensitivity for more responsive meters
-            let vu_gain = 4.0;
-            let raw_left = smoothed_left * vu_gain;
-            let raw_right = smoothed_right * vu_gain;
+            display_left_db = vu::level_to_db(smoothed_magnitudes[0]);
+            display_right_db = vu::level_to_db(smoothed_magnitudes[1]);
 
-            // Detect clipping (signal over 1.0 = overdriven)
-            let left_clipping = raw_left > 1.0;
-            let right_clipping = raw_right > 1.0;
-
-            smoothed_left = raw_left.min(1.0);
-            smoothed_right = raw_right.min(1.0);
+            // Map levels onto the meter scale (boosted linear or dBFS) - over the top of the scale is clipping
+            let vu_scale = VuScale::from_config(&current_config.vu);
+            let (smoothed_left, left_clipping) = vu_scale.meter(smoothed_magnitudes[0]);
+            let (smoothed_right, right_clipping) = vu_scale.meter(smoothed_magnitudes[1]);
 
             // Update display levels for TUI
             display_left_level = smoothed_left;
             display_right_level = smoothed_right;
+            live_stats::record_audio_levels(display_left_level, display_right_level);
 
-            // Split LEDs in half
-            let half = current_config.total_leds / 2;
+            // Split LEDs in half (or the whole strip for a mono meter filling one way)
+            let half = vu::left_meter_leds(current_config.total_leds, current_config.vu.mono, &current_config.direction);
 
             // Build gradients for left and right channels using cached TUI color strings
             // (TUI color strings are already resolved via unified system at init and when config changes)
@@ -1795,16 +2239,20 @@ fn run_live_mode(config: &BandwidthConfig, delay_ms: Option<u64>, config_change_
                 _ => InterpolationMode::Linear,
             };
 
-            let (left_gradient, left_colors, left_solid) = build_gradient_from_color(
+            let color_space = ColorSpace::from_name(&current_config.color_space);
+
+            let (left_gradient, left_colors, left_solid) = left_gradient_cache.get(
                 &tui_left_color_str,
                 current_config.use_gradient,
                 interpolation_mode,
+                color_space,
             )?;
 
-            let (right_gradient, right_colors, right_solid) = build_gradient_from_color(
+            let (right_gradient, right_colors, right_solid) = right_gradient_cache.get(
                 &tui_right_color_str,
                 current_config.use_gradient,
                 interpolation_mode,
+                color_space,
             )?;
 
             // Update animation offsets (scaled by level if configured)
@@ -1844,22 +2292,18 @@ fn run_live_mode(config: &BandwidthConfig, delay_ms: Option<u64>, config_change_
             tui_left_animation_offset = left_animation_offset;
             tui_right_animation_offset = right_animation_offset;
 
-            // Check for strobe condition (clipping)
-            let strobe_active = current_config.strobe_on_max && (left_clipping || right_clipping);
-            let show_strobe = if strobe_active {
-                let cycle_ms = 1000.0 / current_config.strobe_rate_hz;
-                let phase = (frame_count as f64 * frame_time_ms) % cycle_ms;
-                phase < current_config.strobe_duration_ms
-            } else {
-                false
-            };
+            // Strobe each channel while it clips (or on beats)
+            left_strobe.feed_level(&strobe_settings, left_peak);
+            right_strobe.feed_level(&strobe_settings, right_peak);
+            let show_left_strobe = left_strobe.update(&strobe_settings, left_clipping);
+            let show_right_strobe = right_strobe.update(&strobe_settings, right_clipping);
 
             // Update peak hold tracking for VU mode
-            let peak_hold_color = Rgb::from_hex(&current_config.peak_hold_color).unwrap_or(Rgb { r: 255, g: 255, b: 255 });
+            let peak_hold_color = Rgb::from_hex(&current_config.vu.peak_hold_color).unwrap_or(Rgb { r: 255, g: 255, b: 255 });
 
             // Left channel peak tracking
             let half_leds = half;
-            if current_config.peak_hold {
+            if current_config.vu.peak_hold {
                 let left_lit_count = (smoothed_left * half_leds as f32).round() as usize;
                 let left_current_peak = if left_lit_count > 0 {
                     // Convert from lit count to LED index based on direction
@@ -1876,7 +2320,7 @@ fn run_live_mode(config: &BandwidthConfig, delay_ms: Option<u64>, config_change_
                 // Update peak if current level is higher or peak has expired
                 let should_update_left_peak = if let (Some(peak_led), Some(peak_time)) = (left_peak_led, left_peak_time) {
                     // Check if expired
-                    let expired = peak_time.elapsed().as_secs_f64() * 1000.0 > current_config.peak_hold_duration_ms;
+                    let expired = peak_time.elapsed().as_secs_f64() * 1000.0 > current_config.vu.peak_hold_duration_ms;
                     // Update if current is higher than stored peak or expired
                     expired || left_lit_count > 0 && match current_config.direction.as_str() {
                         "mirrored" | "right" => left_current_peak < peak_led,  // lower index = higher level
@@ -1891,19 +2335,17 @@ fn run_live_mode(config: &BandwidthConfig, delay_ms: Option<u64>, config_change_
                     let is_new_peak_position = left_peak_led.map_or(true, |old_led| old_led != left_current_peak);
 
                     // Toggle animation direction if enabled and this is a new peak position
-                    if current_config.peak_direction_toggle && is_new_peak_position {
-                        left_animation_direction = if left_animation_direction == "left" {
-                            "right".to_string()
-                        } else {
-                            "left".to_string()
-                        };
+                    if current_config.vu.peak_direction_toggle && is_new_peak_position {
+                        let toggled = if left_animation_direction == "left" { "right" } else { "left" };
+                        left_animation_direction.clear();
+                        left_animation_direction.push_str(toggled);
                     }
 
                     left_peak_led = Some(left_current_peak);
                     left_peak_time = Some(Instant::now());
                 } else if let Some(peak_time) = left_peak_time {
                     // Clear peak if expired
-                    if peak_time.elapsed().as_secs_f64() * 1000.0 > current_config.peak_hold_duration_ms {
+                    if peak_time.elapsed().as_secs_f64() * 1000.0 > current_config.vu.peak_hold_duration_ms {
                         left_peak_led = None;
                         left_peak_time = None;
                     }
@@ -1915,7 +2357,7 @@ fn run_live_mode(config: &BandwidthConfig, delay_ms: Option<u64>, config_change_
             }
 
             // Right channel peak tracking
-            if current_config.peak_hold {
+            if current_config.vu.peak_hold {
                 let right_lit_count = (smoothed_right * half_leds as f32).round() as usize;
                 let right_current_peak = if right_lit_count > 0 {
                     match current_config.direction.as_str() {
@@ -1929,7 +2371,7 @@ fn run_live_mode(config: &BandwidthConfig, delay_ms: Option<u64>, config_change_
                 };
 
                 let should_update_right_peak = if let (Some(peak_led), Some(peak_time)) = (right_peak_led, right_peak_time) {
-                    let expired = peak_time.elapsed().as_secs_f64() * 1000.0 > current_config.peak_hold_duration_ms;
+                    let expired = peak_time.elapsed().as_secs_f64() * 1000.0 > current_config.vu.peak_hold_duration_ms;
                     expired || right_lit_count > 0 && match current_config.direction.as_str() {
                         "mirrored" | "left" => right_current_peak > peak_led,
                         _ => right_current_peak < peak_led,
@@ -1943,18 +2385,16 @@ fn run_live_mode(config: &BandwidthConfig, delay_ms: Option<u64>, config_change_
                     let is_new_peak_position = right_peak_led.map_or(true, |old_led| old_led != right_current_peak);
 
                     // Toggle animation direction if enabled and this is a new peak position
-                    if current_config.peak_direction_toggle && is_new_peak_position {
-                        right_animation_direction = if right_animation_direction == "left" {
-                            "right".to_string()
-                        } else {
-                            "left".to_string()
-                        };
+                    if current_config.vu.peak_direction_toggle && is_new_peak_position {
+                        let toggled = if right_animation_direction == "left" { "right" } else { "left" };
+                        right_animation_direction.clear();
+                        right_animation_direction.push_str(toggled);
                     }
 
                     right_peak_led = Some(right_current_peak);
                     right_peak_time = Some(Instant::now());
                 } else if let Some(peak_time) = right_peak_time {
-                    if peak_time.elapsed().as_secs_f64() * 1000.0 > current_config.peak_hold_duration_ms {
+                    if peak_time.elapsed().as_secs_f64() * 1000.0 > current_config.vu.peak_hold_duration_ms {
                         right_peak_led = None;
                         right_peak_time = None;
                     }
@@ -1974,11 +2414,11 @@ fn run_live_mode(config: &BandwidthConfig, delay_ms: Option<u64>, config_change_
                 &left_animation_direction,  // Left = RX (may be toggled)
                 left_animation_offset,
                 left_gradient.as_ref(),
-                &left_colors,
-                left_solid,
+                left_colors,
+                *left_solid,
                 true,  // is_left_channel
                 current_config.intensity_colors,  // intensity colors mode
-                current_config.peak_hold,
+                current_config.vu.peak_hold,
                 left_peak_led,
                 peak_hold_color,
             );
@@ -1993,47 +2433,33 @@ fn run_live_mode(config: &BandwidthConfig, delay_ms: Option<u64>, config_change_
                 &right_animation_direction,  // Right = TX (may be toggled)
                 right_animation_offset,
                 right_gradient.as_ref(),
-                &right_colors,
-                right_solid,
+                right_colors,
+                *right_solid,
                 false,  // is_left_channel
                 current_config.intensity_colors,  // intensity colors mode
-                current_config.peak_hold,
+                current_config.vu.peak_hold,
                 right_peak_led,
                 peak_hold_color,
             );
 
-            // Apply strobe effect if clipping
-            if show_strobe {
-                let strobe_rgb = Rgb::from_hex(&current_config.strobe_color).unwrap_or(Rgb { r: 255, g: 0, b: 0 });
-
-                if left_clipping {
-                    // Strobe left channel
-                    for i in 0..half {
-                        frame[i * 3] = strobe_rgb.r;
-                        frame[i * 3 + 1] = strobe_rgb.g;
-                        frame[i * 3 + 2] = strobe_rgb.b;
-                    }
-                }
-
-                if right_clipping {
-                    // Strobe right channel
-                    for i in half..current_config.total_leds {
-                        frame[i * 3] = strobe_rgb.r;
-                        frame[i * 3 + 1] = strobe_rgb.g;
-                        frame[i * 3 + 2] = strobe_rgb.b;
-                    }
-                }
+            // Apply strobe effect per channel
+            if show_left_strobe {
+                strobe::fill(&mut frame, 0..half, strobe_settings.color);
+            }
+            if show_right_strobe {
+                strobe::fill(&mut frame, half..current_config.total_leds, strobe_settings.color);
             }
 
-        } else if current_config.matrix_2d_enabled {
+        } else if current_config.matrix.enabled {
             // === 2D MATRIX SPECTRUM MODE ===
             // Display spectrum on a 2D matrix with frequency on X-axis and amplitude on Y-axis
-            let width = current_config.matrix_2d_width;
-            let height = current_config.matrix_2d_height;
+            let width = matrix_layout.width();
+            let height = matrix_layout.height();
 
-            // Ensure frame buffer matches matrix size
-            if frame.len() != width * height * 3 {
-                frame = vec![0u8; width * height * 3];
+            // Ensure frame buffer matches the LEDs across the matrix panels
+            if frame.len() != matrix_layout.led_count() * 3 {
+                frame.clear();
+                frame.resize(matrix_layout.led_count() * 3, 0);
             }
 
             // Ensure smoothed_magnitudes matches number of columns (frequency bins)
@@ -2046,21 +2472,13 @@ fn run_live_mode(config: &BandwidthConfig, delay_ms: Option<u64>, config_change_
 
             // Process FFT - combine all channels into mono for 2D display
             let channels_to_process = channels.min(2);
-            let mut bin_magnitudes = vec![0.0_f32; num_bins];
+            bin_magnitudes.clear();
+            bin_magnitudes.resize(num_bins, 0.0);
             let mut max_magnitude = 0.0_f32;
 
             for ch in 0..channels_to_process {
-                let channel_samples: Vec<f32> = samples.iter().skip(ch).step_by(channels).copied().take(fft_size).collect();
-                let mut fft_buffer: Vec<Complex<f32>> = channel_samples
-                    .iter()
-                    .enumerate()
-                    .map(|(i, &s)| {
-                        let window = 0.5 * (1.0 - (2.0 * std::f32::consts::PI * i as f32 / (fft_size - 1) as f32).cos());
-                        Complex { re: s * window, im: 0.0 }
-                    })
-                    .collect();
-
-                fft.process(&mut fft_buffer);
+                fill_fft_buffer(&mut fft_buffer, &samples, ch, channels, fft_size, fft_window);
+                process_fft(fft.as_ref(), &mut fft_buffer, &mut fft_scratch);
 
                 for (i, complex) in fft_buffer.iter().take(num_bins).enumerate() {
                     let mag = (complex.re * complex.re + complex.im * complex.im).sqrt();
@@ -2071,12 +2489,17 @@ fn run_live_mode(config: &BandwidthConfig, delay_ms: Option<u64>, config_change_
 
             // Average magnitudes if combining multiple channels
             if channels_to_process > 1 {
-                for mag in &mut bin_magnitudes {
+                for mag in bin_magnitudes.iter_mut() {
                     *mag /= channels_to_process as f32;
                 }
                 max_magnitude /= channels_to_process as f32;
             }
 
+            // Only what stands above the learned background noise
+            if current_config.audio.noise_floor {
+                max_magnitude = noise_floors[0].gate(&mut bin_magnitudes, current_config.audio.noise_floor_margin_db as f32, frame_time_ms as f32 / 1000.0);
+            }
+
             let normalization = if max_magnitude > 0.0 { 1.0 / max_magnitude } else { 1.0 };
 
             // Map frequency bins to matrix columns with smoothing
@@ -2125,10 +2548,14 @@ fn run_live_mode(config: &BandwidthConfig, delay_ms: Option<u64>, config_change_
                     }
                 };
 
-                // Map frequency column to frequency bin
-                let bin_offset = (freq_col * display_bins) / width;
-                let bin_index = (min_bin + bin_offset).min(max_bin);
-                let magnitude = (bin_magnitudes[bin_index] * normalization).min(1.0);
+                // Map frequency column to frequency bin (linear across the range, or constant-Q semitone bands)
+                let magnitude = if current_config.audio.cqt {
+                    fft::cqt_magnitude(&bin_magnitudes, freq_bin_width, freq_col, current_config.audio.cqt_min_freq as f32, current_config.audio.cqt_leds_per_semitone as f32)
+                } else {
+                    let bin_offset = (freq_col * display_bins) / width;
+                    bin_magnitudes[(min_bin + bin_offset).min(max_bin)]
+                };
+                let magnitude = (magnitude * normalization).min(1.0);
 
                 // Apply threshold and smoothing (use freq_col for smoothing array index)
                 let target = if magnitude > threshold { magnitude } else { 0.0 };
@@ -2144,7 +2571,7 @@ fn run_live_mode(config: &BandwidthConfig, delay_ms: Option<u64>, config_change_
                 let lit_height = (smoothed * height as f32) as usize;
 
                 // Gradient position based on configuration
-                let gradient_pos = if current_config.matrix_2d_gradient_direction == "vertical" {
+                let gradient_pos = if current_config.matrix.gradient_direction == "vertical" {
                     // Vertical: gradient based on amplitude (0.0 = silent, 1.0 = max)
                     smoothed as f64
                 } else {
@@ -2154,8 +2581,7 @@ fn run_live_mode(config: &BandwidthConfig, delay_ms: Option<u64>, config_change_
 
                 // Get color using gradient system
                 let (r, g, b) = if let Some(ref grad) = spectrum_gradient {
-                    let color = grad.at(gradient_pos);
-                    let rgba = color.to_rgba8();
+                    let rgba = grad.at(gradient_pos);
                     (rgba[0], rgba[1], rgba[2])
                 } else if spectrum_colors.len() > 1 {
                     let n = spectrum_colors.len();
@@ -2170,13 +2596,10 @@ fn run_live_mode(config: &BandwidthConfig, delay_ms: Option<u64>, config_change_
                     (spectrum_solid.r, spectrum_solid.g, spectrum_solid.b)
                 };
 
-                // Fill column from bottom to top (serpentine pattern)
+                // Fill column from bottom to top (panel wiring comes from the matrix layout)
                 for row in 0..height {
-                    // Serpentine/zigzag pattern: even rows go left-to-right, odd rows go right-to-left
-                    let led_index = if row % 2 == 0 {
-                        row * width + physical_col
-                    } else {
-                        row * width + (width - 1 - physical_col)
+                    let Some(led_index) = matrix_layout.index(physical_col, row) else {
+                        continue;
                     };
 
                     // Light LED if it's below the amplitude threshold (bottom-up visualization)
@@ -2213,45 +2636,37 @@ fn run_live_mode(config: &BandwidthConfig, delay_ms: Option<u64>, config_change_
                 let half = current_config.total_leds / 2;
 
                 // Process left channel (first half of LEDs) - extract channel 0
-                let left_samples: Vec<f32> = samples.iter().step_by(channels).copied().take(fft_size).collect();
-                let mut left_fft: Vec<Complex<f32>> = left_samples
-                    .iter()
-                    .enumerate()
-                    .map(|(i, &s)| {
-                        let window = 0.5 * (1.0 - (2.0 * std::f32::consts::PI * i as f32 / (fft_size - 1) as f32).cos());
-                        Complex { re: s * window, im: 0.0 }
-                    })
-                    .collect();
-                fft.process(&mut left_fft);
-
-                let mut left_bins = vec![0.0_f32; num_bins];
+                fill_fft_buffer(&mut fft_buffer, &samples, 0, channels, fft_size, fft_window);
+                process_fft(fft.as_ref(), &mut fft_buffer, &mut fft_scratch);
+
+                left_bins.clear();
+                left_bins.resize(num_bins, 0.0);
                 let mut left_max = 0.0_f32;
-                for (i, complex) in left_fft.iter().take(num_bins).enumerate() {
+                for (i, complex) in fft_buffer.iter().take(num_bins).enumerate() {
                     let mag = (complex.re * complex.re + complex.im * complex.im).sqrt();
                     left_bins[i] = mag;
                     left_max = left_max.max(mag);
                 }
+                if current_config.audio.noise_floor {
+                    left_max = noise_floors[0].gate(&mut left_bins, current_config.audio.noise_floor_margin_db as f32, frame_time_ms as f32 / 1000.0);
+                }
                 let left_norm = if left_max > 0.0 { 1.0 / left_max } else { 1.0 };
 
                 // Process right channel (second half of LEDs) - extract channel 1
-                let right_samples: Vec<f32> = samples.iter().skip(1).step_by(channels).copied().take(fft_size).collect();
-                let mut right_fft: Vec<Complex<f32>> = right_samples
-                    .iter()
-                    .enumerate()
-                    .map(|(i, &s)| {
-                        let window = 0.5 * (1.0 - (2.0 * std::f32::consts::PI * i as f32 / (fft_size - 1) as f32).cos());
-                        Complex { re: s * window, im: 0.0 }
-                    })
-                    .collect();
-                fft.process(&mut right_fft);
-
-                let mut right_bins = vec![0.0_f32; num_bins];
+                fill_fft_buffer(&mut fft_buffer, &samples, 1, channels, fft_size, fft_window);
+                process_fft(fft.as_ref(), &mut fft_buffer, &mut fft_scratch);
+
+                right_bins.clear();
+                right_bins.resize(num_bins, 0.0);
                 let mut right_max = 0.0_f32;
-                for (i, complex) in right_fft.iter().take(num_bins).enumerate() {
+                for (i, complex) in fft_buffer.iter().take(num_bins).enumerate() {
                     let mag = (complex.re * complex.re + complex.im * complex.im).sqrt();
                     right_bins[i] = mag;
                     right_max = right_max.max(mag);
                 }
+                if current_config.audio.noise_floor {
+                    right_max = noise_floors[1].gate(&mut right_bins, current_config.audio.noise_floor_margin_db as f32, frame_time_ms as f32 / 1000.0);
+                }
                 let right_norm = if right_max > 0.0 { 1.0 / right_max } else { 1.0 };
 
                 // Map left channel to LEDs
@@ -2283,9 +2698,14 @@ fn run_live_mode(config: &BandwidthConfig, delay_ms: Option<u64>, config_change_
                         }
                     };
 
-                    let bin_offset = (freq_pos * display_bins) / half;
-                    let bin_index = (min_bin + bin_offset).min(max_bin);
-                    let magnitude = (left_bins[bin_index] * left_norm).min(1.0);
+                    // Linear FFT bins across the range, or constant-Q bands stepping up in semitones
+                    let magnitude = if current_config.audio.cqt {
+                        fft::cqt_magnitude(&left_bins, freq_bin_width, freq_pos, current_config.audio.cqt_min_freq as f32, current_config.audio.cqt_leds_per_semitone as f32)
+                    } else {
+                        let bin_offset = (freq_pos * display_bins) / half;
+                        left_bins[(min_bin + bin_offset).min(max_bin)]
+                    };
+                    let magnitude = (magnitude * left_norm).min(1.0);
 
                     // Apply threshold to target BEFORE smoothing (attack/decay)
                     let target = if magnitude > threshold { magnitude } else { 0.0 };
@@ -2309,8 +2729,7 @@ fn run_live_mode(config: &BandwidthConfig, delay_ms: Option<u64>, config_change_
                     // Get color using gradient system (same as bandwidth meter)
                     let (r, g, b) = if let Some(ref grad) = spectrum_gradient {
                         // Use gradient
-                        let color = grad.at(gradient_pos);
-                        let rgba = color.to_rgba8();
+                        let rgba = grad.at(gradient_pos);
                         (rgba[0], rgba[1], rgba[2])
                     } else if spectrum_colors.len() > 1 {
                         // Multiple solid colors - pick one based on position
@@ -2362,9 +2781,14 @@ fn run_live_mode(config: &BandwidthConfig, delay_ms: Option<u64>, config_change_
                         }
                     };
 
-                    let bin_offset = (freq_pos * display_bins) / half;
-                    let bin_index = (min_bin + bin_offset).min(max_bin);
-                    let magnitude = (right_bins[bin_index] * right_norm).min(1.0);
+                    // Linear FFT bins across the range, or constant-Q bands stepping up in semitones
+                    let magnitude = if current_config.audio.cqt {
+                        fft::cqt_magnitude(&right_bins, freq_bin_width, freq_pos, current_config.audio.cqt_min_freq as f32, current_config.audio.cqt_leds_per_semitone as f32)
+                    } else {
+                        let bin_offset = (freq_pos * display_bins) / half;
+                        right_bins[(min_bin + bin_offset).min(max_bin)]
+                    };
+                    let magnitude = (magnitude * right_norm).min(1.0);
 
                     // Apply threshold to target BEFORE smoothing (attack/decay)
                     let target = if magnitude > threshold { magnitude } else { 0.0 };
@@ -2388,8 +2812,7 @@ fn run_live_mode(config: &BandwidthConfig, delay_ms: Option<u64>, config_change_
                     // Get color using gradient system (same as bandwidth meter)
                     let (r, g, b) = if let Some(ref grad) = spectrum_gradient {
                         // Use gradient
-                        let color = grad.at(gradient_pos);
-                        let rgba = color.to_rgba8();
+                        let rgba = grad.at(gradient_pos);
                         (rgba[0], rgba[1], rgba[2])
                     } else if spectrum_colors.len() > 1 {
                         // Multiple solid colors - pick one based on position
@@ -2415,31 +2838,16 @@ fn run_live_mode(config: &BandwidthConfig, delay_ms: Option<u64>, config_change_
             } else {
                 // === MONO SPECTRUM MODE (left/right) ===
                 // Use full LED range for frequency spectrum, average both channels
-                let mut bin_magnitudes = vec![0.0_f32; num_bins];
+                bin_magnitudes.clear();
+                bin_magnitudes.resize(num_bins, 0.0);
                 let mut max_magnitude = 0.0_f32;
 
                 // For multi-channel devices, only process first 2 channels (left and right)
                 let channels_to_process = if channels >= 2 { 2 } else { channels };
 
                 for ch in 0..channels_to_process {
-                    let channel_samples: Vec<f32> = samples
-                        .iter()
-                        .skip(ch)
-                        .step_by(channels)
-                        .copied()
-                        .take(fft_size)
-                        .collect();
-
-                    let mut fft_buffer: Vec<Complex<f32>> = channel_samples
-                        .iter()
-                        .enumerate()
-                        .map(|(i, &s)| {
-                            let window = 0.5 * (1.0 - (2.0 * std::f32::consts::PI * i as f32 / (fft_size - 1) as f32).cos());
-                            Complex { re: s * window, im: 0.0 }
-                        })
-                        .collect();
-
-                    fft.process(&mut fft_buffer);
+                    fill_fft_buffer(&mut fft_buffer, &samples, ch, channels, fft_size, fft_window);
+                    process_fft(fft.as_ref(), &mut fft_buffer, &mut fft_scratch);
 
                     for (i, complex) in fft_buffer.iter().take(num_bins).enumerate() {
                         let mag = (complex.re * complex.re + complex.im * complex.im).sqrt();
@@ -2449,12 +2857,17 @@ fn run_live_mode(config: &BandwidthConfig, delay_ms: Option<u64>, config_change_
                 }
 
                 if channels_to_process > 1 {
-                    for mag in &mut bin_magnitudes {
+                    for mag in bin_magnitudes.iter_mut() {
                         *mag /= channels_to_process as f32;
                     }
                     max_magnitude /= channels_to_process as f32;
                 }
 
+                // Only what stands above the learned background noise
+                if current_config.audio.noise_floor {
+                    max_magnitude = noise_floors[0].gate(&mut bin_magnitudes, current_config.audio.noise_floor_margin_db as f32, frame_time_ms as f32 / 1000.0);
+                }
+
                 let normalization = if max_magnitude > 0.0 { 1.0 / max_magnitude } else { 1.0 };
 
                 for i in 0..current_config.total_leds {
@@ -2469,9 +2882,14 @@ fn run_live_mode(config: &BandwidthConfig, delay_ms: Option<u64>, config_change_
                         (i, i)
                     };
 
-                    let bin_offset = (freq_pos * display_bins) / current_config.total_leds;
-                    let bin_index = (min_bin + bin_offset).min(max_bin);
-                    let magnitude = (bin_magnitudes[bin_index] * normalization).min(1.0);
+                    // Linear FFT bins across the range, or constant-Q bands stepping up in semitones
+                    let magnitude = if current_config.audio.cqt {
+                        fft::cqt_magnitude(&bin_magnitudes, freq_bin_width, freq_pos, current_config.audio.cqt_min_freq as f32, current_config.audio.cqt_leds_per_semitone as f32)
+                    } else {
+                        let bin_offset = (freq_pos * display_bins) / current_config.total_leds;
+                        bin_magnitudes[(min_bin + bin_offset).min(max_bin)]
+                    };
+                    let magnitude = (magnitude * normalization).min(1.0);
 
                     // Apply threshold to target BEFORE smoothing (attack/decay)
                     let target = if magnitude > threshold { magnitude } else { 0.0 };
@@ -2495,8 +2913,7 @@ fn run_live_mode(config: &BandwidthConfig, delay_ms: Option<u64>, config_change_
                     // Get color using gradient system (same as bandwidth meter)
                     let (r, g, b) = if let Some(ref grad) = spectrum_gradient {
                         // Use gradient
-                        let color = grad.at(gradient_pos);
-                        let rgba = color.to_rgba8();
+                        let rgba = grad.at(gradient_pos);
                         (rgba[0], rgba[1], rgba[2])
                     } else if spectrum_colors.len() > 1 {
                         // Multiple solid colors - pick one based on position
@@ -2521,7 +2938,28 @@ fn run_live_mode(config: &BandwidthConfig, delay_ms: Option<u64>, config_change_
             }
         } // End FFT spectrum mode
 
+        let audio_peak = samples.iter().fold(0.0_f32, |peak, s| peak.max(s.abs()));
+
+        // Other live modes strobe the whole strip on beats (VU strobes each channel above)
+        if !current_config.vu.enabled {
+            left_strobe.feed_level(&strobe_settings, audio_peak);
+            if left_strobe.update(&strobe_settings, false) {
+                strobe::fill(&mut frame, 0..current_config.total_leds, strobe_settings.color);
+            }
+        }
+
+        // Fade into the idle animation while the input is silent
+        let audio_active = audio_peak > idle::SILENCE_LEVEL;
+        idle_animator.update(&idle_settings, audio_active, &mut frame);
+
+        // Spectrum bars for the TUI follow the smoothed level of each LED in FFT spectrum mode
+        let fft_spectrum_mode = !current_config.vu.enabled && !current_config.spectrogram.enabled;
+        if fft_spectrum_mode {
+            spectrum_bars.update(&smoothed_magnitudes, &frame, Duration::from_secs_f64(current_config.vu.peak_hold_duration_ms / 1000.0), Instant::now());
+        }
+
         // Add frame to buffer with timestamp
+        live_stats::record_render_time(render_start.elapsed());
         let delay_duration = Duration::from_micros((current_config.ddp_delay_ms * 1000.0) as u64);
         let send_time = loop_start + delay_duration;
         frame_buffer.push_back((send_time, frame));
@@ -2532,11 +2970,39 @@ fn run_live_mode(config: &BandwidthConfig, delay_ms: Option<u64>, config_change_
             if *send_time <= now {
                 if let Some((_, frame_to_send)) = frame_buffer.pop_front() {
                     let _ = multi_device_manager.send_frame_with_brightness(&frame_to_send, Some(current_config.global_brightness));
+                    spare_frames.push(frame_to_send);
                 }
             } else {
                 break;
             }
         }
+        live_stats::record_buffer_depth(frame_buffer.len());
+        alloc_counter.end_frame();
+
+        // TUI gradients from the stored color strings (cached - rebuilt only when they change)
+        let tui_fallback = (None, Vec::new(), Rgb { r: 255, g: 255, b: 255 });
+        let (tui_left_gradient, tui_left_colors, tui_left_solid) = if !tui_left_color_str.is_empty() {
+            match tui_left_gradient_cache.get(&tui_left_color_str, tui_use_gradient, tui_interpolation_mode, tui_color_space) {
+                Ok(cached) => cached,
+                Err(e) => {
+                    eprintln!("Error building left gradient: {}", e);
+                    &tui_fallback
+                }
+            }
+        } else {
+            &tui_fallback
+        };
+        let (tui_right_gradient, tui_right_colors, tui_right_solid) = if !tui_right_color_str.is_empty() {
+            match tui_right_gradient_cache.get(&tui_right_color_str, tui_use_gradient, tui_interpolation_mode, tui_color_space) {
+                Ok(cached) => cached,
+                Err(e) => {
+                    eprintln!("Error building right gradient: {}", e);
+                    &tui_fallback
+                }
+            }
+        } else {
+            &tui_fallback
+        };
 
         // Update TUI
         terminal.draw(|f| {
@@ -2545,14 +3011,16 @@ fn run_live_mode(config: &BandwidthConfig, delay_ms: Option<u64>, config_change_
                 .constraints([
                     Constraint::Length(3),     // Header
                     Constraint::Min(10),       // Main content
-                    Constraint::Length(3),     // Footer
+                    Constraint::Length(4),     // Footer
                 ])
                 .split(f.size());
 
             // Header - Mode and sub-mode
-            let sub_mode = if current_config.spectrogram {
+            let sub_mode = if current_config.spectrogram.enabled && current_config.spectrogram.strip {
+                "Spectrogram (1D)"
+            } else if current_config.spectrogram.enabled {
                 "Spectrogram"
-            } else if current_config.vu {
+            } else if current_config.vu.enabled {
                 "VU Meter"
             } else {
                 "FFT Spectrum"
@@ -2582,35 +3050,8 @@ fn run_live_mode(config: &BandwidthConfig, delay_ms: Option<u64>, config_change_
             // Build gradient bar with colored spans
             let mut bar_spans = vec![Span::raw("[")];
 
-            // Rebuild gradients for TUI from stored color strings
-            let (tui_left_gradient, tui_left_colors, tui_left_solid) = if !tui_left_color_str.is_empty() {
-                build_gradient_from_color(
-                    &tui_left_color_str,
-                    tui_use_gradient,
-                    tui_interpolation_mode,
-                ).unwrap_or_else(|e| {
-                    eprintln!("Error building left gradient: {}", e);
-                    (None, Vec::new(), Rgb { r: 255, g: 255, b: 255 })
-                })
-            } else {
-                (None, Vec::new(), Rgb { r: 255, g: 255, b: 255 })
-            };
-
-            let (tui_right_gradient, tui_right_colors, tui_right_solid) = if !tui_right_color_str.is_empty() {
-                build_gradient_from_color(
-                    &tui_right_color_str,
-                    tui_use_gradient,
-                    tui_interpolation_mode,
-                ).unwrap_or_else(|e| {
-                    eprintln!("Error building right gradient: {}", e);
-                    (None, Vec::new(), Rgb { r: 255, g: 255, b: 255 })
-                })
-            } else {
-                (None, Vec::new(), Rgb { r: 255, g: 255, b: 255 })
-            };
-
             // Helper function to get gradient color with animation
-            let get_gradient_color = |pos: f64, gradient: &Option<colorgrad::Gradient>, colors: &Vec<Rgb>, solid: &Rgb, animation_offset: f64, animation_dir: &str| -> (u8, u8, u8) {
+            let get_gradient_color = |pos: f64, gradient: &Option<GradientLut>, colors: &Vec<Rgb>, solid: &Rgb, animation_offset: f64, animation_dir: &str| -> (u8, u8, u8) {
                 if let Some(grad) = gradient {
                     // Apply animation offset (match LED strip logic)
                     let animated_pos = if animation_dir == "right" {
@@ -2618,8 +3059,7 @@ fn run_live_mode(config: &BandwidthConfig, delay_ms: Option<u64>, config_change_
                     } else {
                         (pos + animation_offset) % 1.0
                     };
-                    let color = grad.at(animated_pos);
-                    let rgba = color.to_rgba8();
+                    let rgba = grad.at(animated_pos);
                     (rgba[0], rgba[1], rgba[2])
                 } else if colors.len() > 1 {
                     // Multiple solid colors - pick one based on position
@@ -2655,7 +3095,7 @@ fn run_live_mode(config: &BandwidthConfig, delay_ms: Option<u64>, config_change_
                     // Left channel filled (from right to left, so reverse positions)
                     for i in 0..left_filled {
                         let pos = (left_filled - 1 - i) as f64 / half_width as f64;
-                        let (r, g, b) = get_gradient_color(pos, &tui_left_gradient, &tui_left_colors, &tui_left_solid, tui_left_animation_offset, left_anim_dir);
+                        let (r, g, b) = get_gradient_color(pos, tui_left_gradient, tui_left_colors, tui_left_solid, tui_left_animation_offset, left_anim_dir);
                         bar_spans.push(Span::styled("█", Style::default().fg(Color::Rgb(r, g, b))));
                     }
 
@@ -2664,7 +3104,7 @@ fn run_live_mode(config: &BandwidthConfig, delay_ms: Option<u64>, config_change_
                     // Right channel filled (from left to right)
                     for i in 0..right_filled {
                         let pos = i as f64 / half_width as f64;
-                        let (r, g, b) = get_gradient_color(pos, &tui_right_gradient, &tui_right_colors, &tui_right_solid, tui_right_animation_offset, right_anim_dir);
+                        let (r, g, b) = get_gradient_color(pos, tui_right_gradient, tui_right_colors, tui_right_solid, tui_right_animation_offset, right_anim_dir);
                         bar_spans.push(Span::styled("█", Style::default().fg(Color::Rgb(r, g, b))));
                     }
                     // Right channel empty space
@@ -2680,7 +3120,7 @@ fn run_live_mode(config: &BandwidthConfig, delay_ms: Option<u64>, config_change_
                     // Left channel filled (left to right)
                     for i in 0..left_filled {
                         let pos = i as f64 / half_width as f64;
-                        let (r, g, b) = get_gradient_color(pos, &tui_left_gradient, &tui_left_colors, &tui_left_solid, tui_left_animation_offset, left_anim_dir);
+                        let (r, g, b) = get_gradient_color(pos, tui_left_gradient, tui_left_colors, tui_left_solid, tui_left_animation_offset, left_anim_dir);
                         bar_spans.push(Span::styled("█", Style::default().fg(Color::Rgb(r, g, b))));
                     }
                     // Left channel empty space
@@ -2697,7 +3137,7 @@ fn run_live_mode(config: &BandwidthConfig, delay_ms: Option<u64>, config_change_
                     // Right channel filled (right to left, so reverse positions)
                     for i in 0..right_filled {
                         let pos = (right_filled - 1 - i) as f64 / half_width as f64;
-                        let (r, g, b) = get_gradient_color(pos, &tui_right_gradient, &tui_right_colors, &tui_right_solid, tui_right_animation_offset, right_anim_dir);
+                        let (r, g, b) = get_gradient_color(pos, tui_right_gradient, tui_right_colors, tui_right_solid, tui_right_animation_offset, right_anim_dir);
                         bar_spans.push(Span::styled("█", Style::default().fg(Color::Rgb(r, g, b))));
                     }
                 }
@@ -2709,7 +3149,7 @@ fn run_live_mode(config: &BandwidthConfig, delay_ms: Option<u64>, config_change_
                     // Left channel filled (left to right)
                     for i in 0..left_filled {
                         let pos = i as f64 / half_width as f64;
-                        let (r, g, b) = get_gradient_color(pos, &tui_left_gradient, &tui_left_colors, &tui_left_solid, tui_left_animation_offset, left_anim_dir);
+                        let (r, g, b) = get_gradient_color(pos, tui_left_gradient, tui_left_colors, tui_left_solid, tui_left_animation_offset, left_anim_dir);
                         bar_spans.push(Span::styled("█", Style::default().fg(Color::Rgb(r, g, b))));
                     }
                     // Left channel empty space
@@ -2722,7 +3162,7 @@ fn run_live_mode(config: &BandwidthConfig, delay_ms: Option<u64>, config_change_
                     // Right channel filled (left to right)
                     for i in 0..right_filled {
                         let pos = i as f64 / half_width as f64;
-                        let (r, g, b) = get_gradient_color(pos, &tui_right_gradient, &tui_right_colors, &tui_right_solid, tui_right_animation_offset, right_anim_dir);
+                        let (r, g, b) = get_gradient_color(pos, tui_right_gradient, tui_right_colors, tui_right_solid, tui_right_animation_offset, right_anim_dir);
                         bar_spans.push(Span::styled("█", Style::default().fg(Color::Rgb(r, g, b))));
                     }
                     // Right channel empty space
@@ -2742,7 +3182,7 @@ fn run_live_mode(config: &BandwidthConfig, delay_ms: Option<u64>, config_change_
                     // Left channel filled (right to left, so reverse positions)
                     for i in 0..left_filled {
                         let pos = (left_filled - 1 - i) as f64 / half_width as f64;
-                        let (r, g, b) = get_gradient_color(pos, &tui_left_gradient, &tui_left_colors, &tui_left_solid, tui_left_animation_offset, left_anim_dir);
+                        let (r, g, b) = get_gradient_color(pos, tui_left_gradient, tui_left_colors, tui_left_solid, tui_left_animation_offset, left_anim_dir);
                         bar_spans.push(Span::styled("█", Style::default().fg(Color::Rgb(r, g, b))));
                     }
 
@@ -2755,7 +3195,7 @@ fn run_live_mode(config: &BandwidthConfig, delay_ms: Option<u64>, config_change_
                     // Right channel filled (right to left, so reverse positions)
                     for i in 0..right_filled {
                         let pos = (right_filled - 1 - i) as f64 / half_width as f64;
-                        let (r, g, b) = get_gradient_color(pos, &tui_right_gradient, &tui_right_colors, &tui_right_solid, tui_right_animation_offset, right_anim_dir);
+                        let (r, g, b) = get_gradient_color(pos, tui_right_gradient, tui_right_colors, tui_right_solid, tui_right_animation_offset, right_anim_dir);
                         bar_spans.push(Span::styled("█", Style::default().fg(Color::Rgb(r, g, b))));
                     }
                 }
@@ -2767,7 +3207,7 @@ fn run_live_mode(config: &BandwidthConfig, delay_ms: Option<u64>, config_change_
                     // Left channel filled (left to right)
                     for i in 0..left_filled {
                         let pos = i as f64 / half_width as f64;
-                        let (r, g, b) = get_gradient_color(pos, &tui_left_gradient, &tui_left_colors, &tui_left_solid, tui_left_animation_offset, left_anim_dir);
+                        let (r, g, b) = get_gradient_color(pos, tui_left_gradient, tui_left_colors, tui_left_solid, tui_left_animation_offset, left_anim_dir);
                         bar_spans.push(Span::styled("█", Style::default().fg(Color::Rgb(r, g, b))));
                     }
                     // Left channel empty space
@@ -2780,7 +3220,7 @@ fn run_live_mode(config: &BandwidthConfig, delay_ms: Option<u64>, config_change_
                     // Right channel filled (left to right)
                     for i in 0..right_filled {
                         let pos = i as f64 / half_width as f64;
-                        let (r, g, b) = get_gradient_color(pos, &tui_right_gradient, &tui_right_colors, &tui_right_solid, tui_right_animation_offset, right_anim_dir);
+                        let (r, g, b) = get_gradient_color(pos, tui_right_gradient, tui_right_colors, tui_right_solid, tui_right_animation_offset, right_anim_dir);
                         bar_spans.push(Span::styled("█", Style::default().fg(Color::Rgb(r, g, b))));
                     }
                     // Right channel empty space
@@ -2790,23 +3230,67 @@ fn run_live_mode(config: &BandwidthConfig, delay_ms: Option<u64>, config_change_
                 }
             };
 
-            // Close bracket and add level indicators
-            bar_spans.push(Span::raw(format!("]  L: {:.1}%{}  R: {:.1}%{}",
-                display_left_level * 100.0,
+            // Close bracket and add level indicators (dBFS on the dB scale)
+            let vu_scale = VuScale::from_config(&current_config.vu);
+            let level_text = |level: f32, db: f32| match vu_scale {
+                VuScale::Linear => format!("{:.1}%", level * 100.0),
+                VuScale::Db { .. } => format!("{:.1} dB", db),
+            };
+            bar_spans.push(Span::raw(format!("]  L: {}{}  R: {}{}",
+                level_text(display_left_level, display_left_db),
                 if display_left_level >= 0.99 { " 🔴" } else { "" },
-                display_right_level * 100.0,
+                level_text(display_right_level, display_right_db),
                 if display_right_level >= 0.99 { " 🔴" } else { "" }
             )));
 
-            let vu_paragraph = Paragraph::new(Line::from(bar_spans))
+            // Scale markers under each half, following the fill direction of that half
+            let mut vu_lines = vec![Line::from(bar_spans)];
+            if vu_scale != VuScale::Linear {
+                let (left_reversed, right_reversed) = match current_config.direction.as_str() {
+                    "mirrored" => (true, false),
+                    "opposing" => (false, true),
+                    "right" => (true, true),
+                    _ => (false, false),
+                };
+                vu_lines.push(Line::from(format!(" {} {}",
+                    vu::marker_row(&vu_scale, half_width, left_reversed),
+                    vu::marker_row(&vu_scale, half_width, right_reversed))));
+            }
+
+            // FFT spectrum mode shows the per-band bars under a compact VU meter
+            let (vu_area, bars_area) = if fft_spectrum_mode {
+                let split = Layout::default()
+                    .direction(Direction::Vertical)
+                    .constraints([
+                        Constraint::Length(vu_lines.len() as u16 + 2),
+                        Constraint::Min(3),
+                    ])
+                    .split(chunks[1]);
+                (split[0], Some(split[1]))
+            } else {
+                (chunks[1], None)
+            };
+
+            let vu_paragraph = Paragraph::new(vu_lines)
                 .block(Block::default().borders(Borders::ALL).title("VU Meter - LED Strip Visualization (LED 0 ← Left | Right → LED 1200)"));
-            f.render_widget(vu_paragraph, chunks[1]);
+            f.render_widget(vu_paragraph, vu_area);
+
+            if let Some(bars_area) = bars_area {
+                let bar_lines = spectrum_bars.lines(
+                    bars_area.width.saturating_sub(2) as usize,
+                    bars_area.height.saturating_sub(2) as usize,
+                );
+                let bars_paragraph = Paragraph::new(bar_lines)
+                    .block(Block::default().borders(Borders::ALL).title(format!("Spectrum - LED 0 ← {} LEDs → (▔ = peak)", current_config.total_leds)));
+                f.render_widget(bars_paragraph, bars_area);
+            }
             }
 
             // Footer - Monitoring source and controls
             let footer_text = format!(
-                "Source: Audio [{}] | {} Hz | {} ch | WLED: {} | LEDs: {} | FPS: {:.0} | Delay: {:.1}ms | Press 'i' for config, 'q' or Ctrl+C to quit",
-                selected_device_name, sample_rate, channels, current_config.wled_ip, current_config.total_leds, current_fps, current_config.ddp_delay_ms
+                "Source: Audio [{}] | {} Hz | {} ch | WLED: {} | LEDs: {} | FPS: {:.0} | Delay: {:.1}ms | Press 'i' for config, 'q' or Ctrl+C to quit\n{}",
+                selected_device_name, sample_rate, channels, current_config.wled_ip, current_config.total_leds, current_fps, current_config.ddp_delay_ms,
+                live_stats::profile_line()
             );
             let footer = Paragraph::new(footer_text)
                 .block(Block::default().borders(Borders::ALL));
@@ -2814,10 +3298,7 @@ fn run_live_mode(config: &BandwidthConfig, delay_ms: Option<u64>, config_change_
         })?;
 
         // Frame rate limiting
-        let elapsed = loop_start.elapsed();
-        if elapsed < frame_duration {
-            thread::sleep(frame_duration - elapsed);
-        }
+        pacer.wait();
     }
 
     // Cleanup
@@ -2831,40 +3312,15 @@ fn run_live_mode(config: &BandwidthConfig, delay_ms: Option<u64>, config_change_
 }
 
 /// Falling Sand simulation mode
-fn run_sand_mode(config: &BandwidthConfig, config_change_tx: broadcast::Sender<()>) -> Result<ModeExitReason> {
+fn run_sand_mode(config: &BandwidthConfig, config_change_tx: broadcast::Sender<ConfigDelta>) -> Result<ModeExitReason> {
     use std::time::{Duration, Instant};
 
-    // Parse particle type from config
-    let particle_type = match config.sand_particle_type.to_lowercase().as_str() {
-        "water" => sand::Particle::Water,
-        "stone" => sand::Particle::Stone,
-        "fire" => sand::Particle::Fire,
-        "smoke" => sand::Particle::Smoke,
-        "wood" => sand::Particle::Wood,
-        "lava" => sand::Particle::Lava,
-        _ => sand::Particle::Sand,
-    };
-
-    // Initialize sand simulation
-    let mut sim = sand::SandSimulation::new(
-        config.sand_grid_width,
-        config.sand_grid_height,
-        particle_type,
-        config.sand_spawn_rate as f32,
-        config.sand_spawn_radius,
-        config.sand_spawn_x,
-        config.sand_fire_enabled,
-        &config.sand_color_sand,
-        &config.sand_color_water,
-        &config.sand_color_stone,
-        &config.sand_color_fire,
-        &config.sand_color_smoke,
-        &config.sand_color_wood,
-        &config.sand_color_lava,
-    );
+    // Initialize sand simulation on the matrix canvas (the sand grid, or the tiled panels)
+    let mut matrix_layout = MatrixLayout::for_mode(config, config.sand.grid_width, config.sand.grid_height, true);
+    let mut sim = sand::SandSimulation::new(matrix_layout.width(), matrix_layout.height(), &config.sand);
 
     // Place obstacles if enabled
-    sim.place_obstacles(config.sand_obstacles_enabled, config.sand_obstacle_density as f32);
+    sim.place_obstacles(config.sand.obstacles_enabled, config.sand.obstacle_density as f32);
 
     // Create multi-device manager
     let devices: Vec<WLEDDevice> = config.wled_devices.iter().map(|d| WLEDDevice {
@@ -2872,12 +3328,25 @@ fn run_sand_mode(config: &BandwidthConfig, config_change_tx: broadcast::Sender<(
         led_offset: d.led_offset,
         led_count: d.led_count,
         enabled: d.enabled,
+        max_fps: d.max_fps,
+        led_ma: d.led_ma,
+        psu_amps: d.psu_amps,
+        backup_for: d.backup_for.clone(),
+        reversed: d.reversed,
+        mirrored: d.mirrored,
+        protocol: d.protocol.clone(),
+        universe: d.universe,
+        start_channel: d.start_channel,
+        net: d.net,
+        subnet: d.subnet,
     }).collect();
 
     let md_config = MultiDeviceConfig {
         devices,
         send_parallel: config.multi_device_send_parallel,
         fail_fast: config.multi_device_fail_fast,
+        stagger_sends: config.multi_device_stagger_sends,
+        color_correction: ColorCorrection::from_config(&config),
     };
 
     let mut md_manager = match MultiDeviceManager::new(md_config) {
@@ -2889,8 +3358,7 @@ fn run_sand_mode(config: &BandwidthConfig, config_change_tx: broadcast::Sender<(
     };
 
     // Frame timing
-    let frame_duration = Duration::from_secs_f64(1.0 / config.fps);
-    let mut last_frame = Instant::now();
+    let mut pacer = FramePacer::new(config.fps);
 
     let mut config_change_rx = config_change_tx.subscribe();
     let mut current_config = config.clone();
@@ -2914,83 +3382,38 @@ fn run_sand_mode(config: &BandwidthConfig, config_change_tx: broadcast::Sender<(
     terminal.clear()?;
     terminal.hide_cursor()?;
 
+    let mut frame = Vec::new();  // Reused for every rendered frame
+    let mut alloc_counter = FrameAllocCounter::new("sand");
+
     loop {
         let loop_start = Instant::now();
 
         // Check for config changes
-        if let Ok(()) = config_change_rx.try_recv() {
-            if let Ok(new_config) = BandwidthConfig::load() {
-                // Check if mode changed
-                if new_config.mode != "sand" {
-                    // Cleanup terminal
-                    terminal.show_cursor().ok();
-                    disable_raw_mode().ok();
-                    execute!(terminal.backend_mut(), LeaveAlternateScreen).ok();
-                    return Ok(ModeExitReason::ModeChanged);
-                }
-
-                // Reinitialize if grid size changed
-                if new_config.sand_grid_width != current_config.sand_grid_width ||
-                   new_config.sand_grid_height != current_config.sand_grid_height {
-                    let new_particle = match new_config.sand_particle_type.to_lowercase().as_str() {
-                        "water" => sand::Particle::Water,
-                        "stone" => sand::Particle::Stone,
-                        "fire" => sand::Particle::Fire,
-                        "smoke" => sand::Particle::Smoke,
-                        "wood" => sand::Particle::Wood,
-                        "lava" => sand::Particle::Lava,
-                        _ => sand::Particle::Sand,
-                    };
-
-                    sim = sand::SandSimulation::new(
-                        new_config.sand_grid_width,
-                        new_config.sand_grid_height,
-                        new_particle,
-                        new_config.sand_spawn_rate as f32,
-                        new_config.sand_spawn_radius,
-                        new_config.sand_spawn_x,
-                        new_config.sand_fire_enabled,
-                        &new_config.sand_color_sand,
-                        &new_config.sand_color_water,
-                        &new_config.sand_color_stone,
-                        &new_config.sand_color_fire,
-                        &new_config.sand_color_smoke,
-                        &new_config.sand_color_wood,
-                        &new_config.sand_color_lava,
-                    );
-
-                    // Place obstacles if enabled
-                    sim.place_obstacles(new_config.sand_obstacles_enabled, new_config.sand_obstacle_density as f32);
-                } else {
-                    // Update config without rebuilding
-                    let new_particle = match new_config.sand_particle_type.to_lowercase().as_str() {
-                        "water" => sand::Particle::Water,
-                        "stone" => sand::Particle::Stone,
-                        "fire" => sand::Particle::Fire,
-                        "smoke" => sand::Particle::Smoke,
-                        "wood" => sand::Particle::Wood,
-                        "lava" => sand::Particle::Lava,
-                        _ => sand::Particle::Sand,
-                    };
+        if let Ok(delta) = config_change_rx.try_recv() {
+            let new_config = delta.config_or(&current_config);
+            // Check if mode changed
+            if new_config.mode != "sand" || system::restart_requested() {
+                // Cleanup terminal
+                terminal.show_cursor().ok();
+                disable_raw_mode().ok();
+                execute!(terminal.backend_mut(), LeaveAlternateScreen).ok();
+                return Ok(ModeExitReason::ModeChanged);
+            }
 
-                    sim.update_config(
-                        new_particle,
-                        new_config.sand_spawn_rate as f32,
-                        new_config.sand_spawn_radius,
-                        new_config.sand_spawn_x,
-                        new_config.sand_fire_enabled,
-                        &new_config.sand_color_sand,
-                        &new_config.sand_color_water,
-                        &new_config.sand_color_stone,
-                        &new_config.sand_color_fire,
-                        &new_config.sand_color_smoke,
-                        &new_config.sand_color_wood,
-                        &new_config.sand_color_lava,
-                    );
-                }
+            // Reinitialize if grid size or matrix layout changed
+            let new_layout = MatrixLayout::for_mode(&new_config, new_config.sand.grid_width, new_config.sand.grid_height, true);
+            if new_layout != matrix_layout {
+                matrix_layout = new_layout;
+                sim = sand::SandSimulation::new(matrix_layout.width(), matrix_layout.height(), &new_config.sand);
 
-                current_config = new_config;
+                // Place obstacles if enabled
+                sim.place_obstacles(new_config.sand.obstacles_enabled, new_config.sand.obstacle_density as f32);
+            } else {
+                // Update config without rebuilding
+                sim.update_config(&new_config.sand);
             }
+
+            current_config = new_config;
         }
 
         // Check for restart flag from web UI
@@ -2998,7 +3421,7 @@ fn run_sand_mode(config: &BandwidthConfig, config_change_tx: broadcast::Sender<(
             // Clear the simulation
             sim.clear();
             // Place obstacles if enabled
-            sim.place_obstacles(current_config.sand_obstacles_enabled, current_config.sand_obstacle_density as f32);
+            sim.place_obstacles(current_config.sand.obstacles_enabled, current_config.sand.obstacle_density as f32);
             // Remove the flag file
             let _ = std::fs::remove_file("/tmp/rustwled_sand_restart");
         }
@@ -3025,7 +3448,7 @@ fn run_sand_mode(config: &BandwidthConfig, config_change_tx: broadcast::Sender<(
                         // Clear the simulation
                         sim.clear();
                         // Place obstacles if enabled
-                        sim.place_obstacles(current_config.sand_obstacles_enabled, current_config.sand_obstacle_density as f32);
+                        sim.place_obstacles(current_config.sand.obstacles_enabled, current_config.sand.obstacle_density as f32);
                     }
                     _ => {}
                 }
@@ -3033,12 +3456,12 @@ fn run_sand_mode(config: &BandwidthConfig, config_change_tx: broadcast::Sender<(
         }
 
         // Render frame if it's time
-        let elapsed = loop_start.duration_since(last_frame);
-        if elapsed >= frame_duration {
-            last_frame = loop_start;
+        if pacer.frame_due(loop_start) {
+            alloc_counter.begin_frame();
+            let render_start = Instant::now();
 
             // Spawn particles (if enabled)
-            if current_config.sand_spawn_enabled {
+            if current_config.sand.spawn_enabled {
                 sim.spawn_particles();
             }
 
@@ -3046,10 +3469,12 @@ fn run_sand_mode(config: &BandwidthConfig, config_change_tx: broadcast::Sender<(
             sim.update();
 
             // Render to LED frame
-            let frame = sim.render(current_config.total_leds);
+            sim.render(current_config.total_leds, &matrix_layout, &mut frame);
+            live_stats::record_render_time(render_start.elapsed());
 
             // Send to WLED devices with brightness applied
             let _ = md_manager.send_frame_with_brightness(&frame, Some(current_config.global_brightness));
+            alloc_counter.end_frame();
         }
 
         // Update TUI
@@ -3059,12 +3484,12 @@ fn run_sand_mode(config: &BandwidthConfig, config_change_tx: broadcast::Sender<(
                 .constraints([
                     Constraint::Length(3),  // Header
                     Constraint::Min(10),    // Main content (simulation visualization placeholder)
-                    Constraint::Length(3),  // Footer
+                    Constraint::Length(4),  // Footer
                 ])
                 .split(f.size());
 
             // Header - Mode and particle type (with quit instructions trailing)
-            let particle_name = match current_config.sand_particle_type.as_str() {
+            let particle_name = match current_config.sand.particle_type.as_str() {
                 "water" => "Water",
                 "stone" => "Stone",
                 "fire" => "Fire",
@@ -3078,7 +3503,7 @@ fn run_sand_mode(config: &BandwidthConfig, config_change_tx: broadcast::Sender<(
             use ratatui::style::{Style, Color};
 
             let header_left = format!("⏳ Falling Sand Mode | Particle: {} | {}x{} Grid",
-                particle_name, current_config.sand_grid_width, current_config.sand_grid_height);
+                particle_name, matrix_layout.width(), matrix_layout.height());
             let header_right = "Press 'r' to restart, 'q' or Ctrl+C to quit";
 
             // Calculate padding to right-align the quit instructions
@@ -3098,10 +3523,10 @@ fn run_sand_mode(config: &BandwidthConfig, config_change_tx: broadcast::Sender<(
             f.render_widget(header, chunks[0]);
 
             // Main content - Simulation info
-            let spawn_status = if current_config.sand_spawn_enabled { "✓ Enabled" } else { "✗ Disabled" };
-            let fire_status = if current_config.sand_fire_enabled { "✓ Enabled" } else { "✗ Disabled" };
-            let obstacles_status = if current_config.sand_obstacles_enabled {
-                format!("✓ Enabled ({}% density)", (current_config.sand_obstacle_density * 100.0) as u8)
+            let spawn_status = if current_config.sand.spawn_enabled { "✓ Enabled" } else { "✗ Disabled" };
+            let fire_status = if current_config.sand.fire_enabled { "✓ Enabled" } else { "✗ Disabled" };
+            let obstacles_status = if current_config.sand.obstacles_enabled {
+                format!("✓ Enabled ({}% density)", (current_config.sand.obstacle_density * 100.0) as u8)
             } else {
                 "✗ Disabled".to_string()
             };
@@ -3114,9 +3539,9 @@ fn run_sand_mode(config: &BandwidthConfig, config_change_tx: broadcast::Sender<(
                 LEDs are displaying the particle simulation in real-time.\n\
                 Use the web interface to adjust colors and settings.",
                 spawn_status,
-                current_config.sand_spawn_rate * 100.0,
-                current_config.sand_spawn_radius,
-                current_config.sand_spawn_x,
+                current_config.sand.spawn_rate * 100.0,
+                current_config.sand.spawn_radius,
+                current_config.sand.spawn_x,
                 fire_status,
                 obstacles_status
             );
@@ -3133,23 +3558,21 @@ fn run_sand_mode(config: &BandwidthConfig, config_change_tx: broadcast::Sender<(
             };
 
             let footer_text = format!(
-                "WLED: {} | LEDs: {} | FPS: {:.0} | Brightness: {}% | Devices: {}",
+                "WLED: {} | LEDs: {} | FPS: {:.0} | Brightness: {}% | Devices: {}\n{}",
                 current_config.wled_ip,
                 current_config.total_leds,
                 current_config.fps,
                 (current_config.global_brightness * 100.0) as u8,
-                device_info
+                device_info,
+                live_stats::profile_line()
             );
             let footer = Paragraph::new(footer_text)
                 .block(Block::default().borders(Borders::ALL));
             f.render_widget(footer, chunks[2]);
         }).ok();
 
-        // Sleep to maintain target FPS
-        let elapsed = loop_start.elapsed();
-        if elapsed < frame_duration {
-            std::thread::sleep(frame_duration - elapsed);
-        }
+        // Sleep until the next frame is due
+        pacer::sleep_until(pacer.next_frame());
     }
 
     // Cleanup (this is unreachable but required for consistency)
@@ -3163,7 +3586,7 @@ fn run_sand_mode(config: &BandwidthConfig, config_change_tx: broadcast::Sender<(
 }
 
 /// Geometry mode - mathematical and harmonic line-art animations
-fn run_geometry_mode(config: &BandwidthConfig, config_change_tx: broadcast::Sender<()>) -> Result<ModeExitReason> {
+fn run_geometry_mode(config: &BandwidthConfig, config_change_tx: broadcast::Sender<ConfigDelta>) -> Result<ModeExitReason> {
     use std::time::{Duration, Instant};
     use std::io;
 
@@ -3182,36 +3605,39 @@ fn run_geometry_mode(config: &BandwidthConfig, config_change_tx: broadcast::Send
         led_offset: d.led_offset,
         led_count: d.led_count,
         enabled: d.enabled,
+        max_fps: d.max_fps,
+        led_ma: d.led_ma,
+        psu_amps: d.psu_amps,
+        backup_for: d.backup_for.clone(),
+        reversed: d.reversed,
+        mirrored: d.mirrored,
+        protocol: d.protocol.clone(),
+        universe: d.universe,
+        start_channel: d.start_channel,
+        net: d.net,
+        subnet: d.subnet,
     }).collect();
 
     let md_config = MultiDeviceConfig {
         devices,
         send_parallel: config.multi_device_send_parallel,
         fail_fast: config.multi_device_fail_fast,
+        stagger_sends: config.multi_device_stagger_sends,
+        color_correction: ColorCorrection::from_config(&config),
     };
 
     let mut multi_device_manager = MultiDeviceManager::new(md_config)?;
 
-    // Create geometry state
+    // Create geometry state (on the tiled matrix canvas if panels are configured)
+    let matrix_layout = MatrixLayout::is_custom(config).then(|| MatrixLayout::for_mode(config, config.geometry.grid_width, config.geometry.grid_height, false));
     let mut geometry_state = geometry::GeometryState::new(
         config.total_leds,
-        config.geometry_grid_width,
-        config.geometry_grid_height,
-        &config.geometry_mode_select,
-        config.geometry_mode_duration_seconds,
-        config.geometry_randomize_order,
-        config.boid_count,
-        config.boid_separation_distance,
-        config.boid_alignment_distance,
-        config.boid_cohesion_distance,
-        config.boid_max_speed,
-        config.boid_max_force,
-        config.boid_predator_enabled,
-        config.boid_predator_count,
-        config.boid_predator_speed,
-        config.boid_avoidance_distance,
-        config.boid_chase_force
+        matrix_layout.as_ref().map_or(config.geometry.grid_width, |layout| layout.width()),
+        matrix_layout.as_ref().map_or(config.geometry.grid_height, |layout| layout.height()),
+        &config.geometry,
+        &config.boid,
     );
+    geometry_state.layout = matrix_layout;
 
     // Build geometry gradient colors from config
     let geometry_color_str = if !config.color.is_empty() {
@@ -3226,7 +3652,7 @@ fn run_geometry_mode(config: &BandwidthConfig, config_change_tx: broadcast::Send
         _ => InterpolationMode::Linear,
     };
 
-    if let Ok((_grad, colors, _solid)) = build_gradient_from_color(&geometry_color_str, config.use_gradient, interpolation_mode) {
+    if let Ok((_grad, colors, _solid)) = build_gradient_from_color(&geometry_color_str, config.use_gradient, interpolation_mode, ColorSpace::from_name(&config.color_space)) {
         let float_colors: Vec<(f32, f32, f32)> = colors.iter().map(|c| (c.r as f32 / 255.0, c.g as f32 / 255.0, c.b as f32 / 255.0)).collect();
         geometry_state.update_colors(float_colors);
     }
@@ -3236,13 +3662,15 @@ fn run_geometry_mode(config: &BandwidthConfig, config_change_tx: broadcast::Send
     let mut current_config = config.clone();
 
     // Frame timing
-    let mut frame_duration = Duration::from_secs_f64(1.0 / config.fps);
-    let mut last_frame = Instant::now();
+    let mut pacer = FramePacer::new(config.fps);
     let mut frame_count = 0u64;
     let mut fps_timer = Instant::now();
 
     // Frame buffer for scheduled sends (non-blocking delay implementation)
     let mut frame_buffer: std::collections::VecDeque<(Instant, Vec<u8>)> = std::collections::VecDeque::new();
+    // Sent frames are recycled here so rendering reuses their allocations
+    let mut spare_frames: Vec<Vec<u8>> = Vec::new();
+    let mut alloc_counter = FrameAllocCounter::new("geometry");
 
     loop {
         let loop_start = Instant::now();
@@ -3270,28 +3698,19 @@ fn run_geometry_mode(config: &BandwidthConfig, config_change_tx: broadcast::Send
         }
 
         // Check for config changes
-        if let Ok(()) = config_change_rx.try_recv() {
-            let new_config = match BandwidthConfig::load() {
-                Ok(c) => c,
-                Err(_) => continue,
-            };
+        if let Ok(delta) = config_change_rx.try_recv() {
+            let new_config = delta.config_or(&current_config);
 
             // Check if mode changed
-            if new_config.mode != "geometry" {
+            if new_config.mode != "geometry" || system::restart_requested() {
                 terminal.show_cursor()?;
                 disable_raw_mode()?;
                 terminal.backend_mut().execute(LeaveAlternateScreen)?;
                 return Ok(ModeExitReason::ModeChanged);
             }
 
-            // Reinitialize multi-device manager if device config changed
-            let devices_changed = new_config.wled_devices.len() != current_config.wled_devices.len() ||
-                new_config.wled_devices.iter().zip(current_config.wled_devices.iter()).any(|(new, old)| {
-                    new.ip != old.ip ||
-                    new.led_offset != old.led_offset ||
-                    new.led_count != old.led_count ||
-                    new.enabled != old.enabled
-                });
+            // Update the multi-device manager in place if device config changed
+            let devices_changed = delta.changed_any(config::DEVICE_CONFIG_KEYS);
 
             if devices_changed {
                 let devices: Vec<WLEDDevice> = new_config.wled_devices.iter().map(|d| WLEDDevice {
@@ -3299,50 +3718,48 @@ fn run_geometry_mode(config: &BandwidthConfig, config_change_tx: broadcast::Send
                     led_offset: d.led_offset,
                     led_count: d.led_count,
                             enabled: d.enabled,
+                    max_fps: d.max_fps,
+                    led_ma: d.led_ma,
+                    psu_amps: d.psu_amps,
+                    backup_for: d.backup_for.clone(),
+                    reversed: d.reversed,
+                    mirrored: d.mirrored,
+                    protocol: d.protocol.clone(),
+                    universe: d.universe,
+                    start_channel: d.start_channel,
+                    net: d.net,
+                    subnet: d.subnet,
                 }).collect();
 
                 let md_config = MultiDeviceConfig {
                     devices,
                     send_parallel: new_config.multi_device_send_parallel,
                     fail_fast: new_config.multi_device_fail_fast,
+                    stagger_sends: new_config.multi_device_stagger_sends,
+                    color_correction: ColorCorrection::from_config(&new_config),
                 };
 
-                match MultiDeviceManager::new(md_config) {
-                    Ok(new_manager) => {
-                        multi_device_manager = new_manager;
-                    }
-                    Err(_e) => {
-                        // Continue with existing manager
-                    }
-                }
+                // On error the existing setup keeps running
+                let _ = multi_device_manager.reconfigure(md_config);
             }
 
             // Reinitialize geometry state if any geometry settings changed
-            if new_config.geometry_grid_width != current_config.geometry_grid_width ||
-               new_config.geometry_grid_height != current_config.geometry_grid_height ||
+            let matrix_layout = MatrixLayout::is_custom(&new_config).then(|| MatrixLayout::for_mode(&new_config, new_config.geometry.grid_width, new_config.geometry.grid_height, false));
+            if new_config.geometry.grid_width != current_config.geometry.grid_width ||
+               new_config.geometry.grid_height != current_config.geometry.grid_height ||
                new_config.total_leds != current_config.total_leds ||
-               new_config.geometry_mode_select != current_config.geometry_mode_select ||
-               new_config.geometry_mode_duration_seconds != current_config.geometry_mode_duration_seconds ||
-               new_config.geometry_randomize_order != current_config.geometry_randomize_order {
+               new_config.geometry.mode_select != current_config.geometry.mode_select ||
+               new_config.geometry.mode_duration_seconds != current_config.geometry.mode_duration_seconds ||
+               new_config.geometry.randomize_order != current_config.geometry.randomize_order ||
+               matrix_layout != geometry_state.layout {
                 geometry_state = geometry::GeometryState::new(
                     new_config.total_leds,
-                    new_config.geometry_grid_width,
-                    new_config.geometry_grid_height,
-                    &new_config.geometry_mode_select,
-                    new_config.geometry_mode_duration_seconds,
-                    new_config.geometry_randomize_order,
-                    new_config.boid_count,
-                    new_config.boid_separation_distance,
-                    new_config.boid_alignment_distance,
-                    new_config.boid_cohesion_distance,
-                    new_config.boid_max_speed,
-                    new_config.boid_max_force,
-                    new_config.boid_predator_enabled,
-                    new_config.boid_predator_count,
-                    new_config.boid_predator_speed,
-                    new_config.boid_avoidance_distance,
-                    new_config.boid_chase_force
+                    matrix_layout.as_ref().map_or(new_config.geometry.grid_width, |layout| layout.width()),
+                    matrix_layout.as_ref().map_or(new_config.geometry.grid_height, |layout| layout.height()),
+                    &new_config.geometry,
+                    &new_config.boid,
                 );
+                geometry_state.layout = matrix_layout;
 
                 // Reapply gradient colors after recreating geometry state
                 let geometry_color_str = if !new_config.color.is_empty() {
@@ -3355,7 +3772,7 @@ fn run_geometry_mode(config: &BandwidthConfig, config_change_tx: broadcast::Send
                     "catmullrom" => InterpolationMode::CatmullRom,
                     _ => InterpolationMode::Linear,
                 };
-                if let Ok((_grad, colors, _solid)) = build_gradient_from_color(&geometry_color_str, new_config.use_gradient, interpolation_mode) {
+                if let Ok((_grad, colors, _solid)) = build_gradient_from_color(&geometry_color_str, new_config.use_gradient, interpolation_mode, ColorSpace::from_name(&new_config.color_space)) {
                     let float_colors: Vec<(f32, f32, f32)> = colors.iter().map(|c| (c.r as f32 / 255.0, c.g as f32 / 255.0, c.b as f32 / 255.0)).collect();
                     geometry_state.update_colors(float_colors);
                 }
@@ -3363,38 +3780,27 @@ fn run_geometry_mode(config: &BandwidthConfig, config_change_tx: broadcast::Send
 
             // Update frame duration if FPS changed
             if new_config.fps != current_config.fps {
-                frame_duration = Duration::from_secs_f64(1.0 / new_config.fps);
+                pacer.set_fps(new_config.fps);
             }
 
             // Update boid config if any boid parameters changed
-            if new_config.boid_count != current_config.boid_count ||
-               new_config.boid_separation_distance != current_config.boid_separation_distance ||
-               new_config.boid_alignment_distance != current_config.boid_alignment_distance ||
-               new_config.boid_cohesion_distance != current_config.boid_cohesion_distance ||
-               new_config.boid_max_speed != current_config.boid_max_speed ||
-               new_config.boid_max_force != current_config.boid_max_force ||
-               new_config.boid_predator_enabled != current_config.boid_predator_enabled ||
-               new_config.boid_predator_count != current_config.boid_predator_count ||
-               new_config.boid_predator_speed != current_config.boid_predator_speed ||
-               new_config.boid_avoidance_distance != current_config.boid_avoidance_distance ||
-               new_config.boid_chase_force != current_config.boid_chase_force {
-                geometry_state.update_boid_config(
-                    new_config.boid_count,
-                    new_config.boid_separation_distance,
-                    new_config.boid_alignment_distance,
-                    new_config.boid_cohesion_distance,
-                    new_config.boid_max_speed,
-                    new_config.boid_max_force,
-                    new_config.boid_predator_enabled,
-                    new_config.boid_predator_count,
-                    new_config.boid_predator_speed,
-                    new_config.boid_avoidance_distance,
-                    new_config.boid_chase_force
-                );
+            if new_config.boid.count != current_config.boid.count ||
+               new_config.boid.separation_distance != current_config.boid.separation_distance ||
+               new_config.boid.alignment_distance != current_config.boid.alignment_distance ||
+               new_config.boid.cohesion_distance != current_config.boid.cohesion_distance ||
+               new_config.boid.max_speed != current_config.boid.max_speed ||
+               new_config.boid.max_force != current_config.boid.max_force ||
+               new_config.boid.predator_enabled != current_config.boid.predator_enabled ||
+               new_config.boid.predator_count != current_config.boid.predator_count ||
+               new_config.boid.predator_speed != current_config.boid.predator_speed ||
+               new_config.boid.avoidance_distance != current_config.boid.avoidance_distance ||
+               new_config.boid.chase_force != current_config.boid.chase_force {
+                geometry_state.update_boid_config(&new_config.boid);
             }
 
             // Update geometry colors if color or gradient settings changed
             if new_config.color != current_config.color ||
+               new_config.gradients != current_config.gradients ||
                new_config.use_gradient != current_config.use_gradient ||
                new_config.interpolation != current_config.interpolation {
                 let new_geometry_color_str = if !new_config.color.is_empty() {
@@ -3409,7 +3815,7 @@ fn run_geometry_mode(config: &BandwidthConfig, config_change_tx: broadcast::Send
                     _ => InterpolationMode::Linear,
                 };
 
-                if let Ok((_grad, colors, _solid)) = build_gradient_from_color(&new_geometry_color_str, new_config.use_gradient, new_interpolation_mode) {
+                if let Ok((_grad, colors, _solid)) = build_gradient_from_color(&new_geometry_color_str, new_config.use_gradient, new_interpolation_mode, ColorSpace::from_name(&new_config.color_space)) {
                     let float_colors: Vec<(f32, f32, f32)> = colors.iter().map(|c| (c.r as f32 / 255.0, c.g as f32 / 255.0, c.b as f32 / 255.0)).collect();
                     geometry_state.update_colors(float_colors);
                 }
@@ -3419,23 +3825,25 @@ fn run_geometry_mode(config: &BandwidthConfig, config_change_tx: broadcast::Send
         }
 
         // Render frame if it's time
-        let elapsed = loop_start.duration_since(last_frame);
-        if elapsed >= frame_duration {
-            last_frame = loop_start;
+        if pacer.frame_due(loop_start) {
 
             // Update geometry and get frame
+            alloc_counter.begin_frame();
             let render_start = Instant::now();
-            let frame = geometry_state.update(
+            let mut frame = spare_frames.pop().unwrap_or_default();
+            geometry_state.update(
                 current_config.global_brightness,
                 current_config.animation_speed,
-                &current_config.tx_animation_direction
+                &current_config.tx_animation_direction,
+                &mut frame,
             );
-            let render_time = render_start.elapsed();
+            live_stats::record_render_time(render_start.elapsed());
 
             // Add frame to buffer with scheduled send time (non-blocking delay)
             let delay_duration = Duration::from_micros((current_config.ddp_delay_ms * 1000.0) as u64);
             let send_time = loop_start + delay_duration;
             frame_buffer.push_back((send_time, frame));
+            alloc_counter.end_frame();
 
             frame_count += 1;
 
@@ -3458,12 +3866,12 @@ fn run_geometry_mode(config: &BandwidthConfig, config_change_tx: broadcast::Send
                     .constraints([
                         Constraint::Length(3),  // Header
                         Constraint::Min(5),     // Main content
-                        Constraint::Length(3),  // Footer
+                        Constraint::Length(4),  // Footer
                     ])
                     .split(f.size());
 
                 // Header - Mode and current geometry
-                let mode_select = &current_config.geometry_mode_select;
+                let mode_select = &current_config.geometry.mode_select;
                 let current_mode_name = format!("{:?}", geometry_state.current_mode);
                 let header_spans = vec![
                     Span::styled(
@@ -3493,7 +3901,7 @@ fn run_geometry_mode(config: &BandwidthConfig, config_change_tx: broadcast::Send
                 // Main content - show geometry info
                 let elapsed_in_mode = geometry_state.mode_start_time.elapsed().as_secs_f64();
                 let time_remaining = (geometry_state.mode_duration.as_secs_f64() - elapsed_in_mode).max(0.0);
-                let grid_info = format!("Grid: {}x{}", current_config.geometry_grid_width, current_config.geometry_grid_height);
+                let grid_info = format!("Grid: {}x{}", geometry_state.grid_width, geometry_state.grid_height);
                 let timing_info = if mode_select == "cycle" {
                     format!("Time in mode: {:.1}s / {:.1}s remaining until transition",
                         elapsed_in_mode, time_remaining)
@@ -3519,13 +3927,12 @@ fn run_geometry_mode(config: &BandwidthConfig, config_change_tx: broadcast::Send
 
                 // Footer - Status
                 let footer_text = format!(
-                    "LEDs: {} | FPS: {:.1} / {:.1} | Render: {:.2}ms | Buffer: {} | Devices: {}",
+                    "LEDs: {} | FPS: {:.1} / {:.1} | Devices: {}\n{}",
                     current_config.total_leds,
                     actual_fps,
                     current_config.fps,
-                    render_time.as_secs_f64() * 1000.0,
-                    frame_buffer.len(),
-                    current_config.wled_devices.len()
+                    current_config.wled_devices.len(),
+                    live_stats::profile_line()
                 );
                 let footer = Paragraph::new(footer_text)
                     .block(Block::default().borders(Borders::ALL));
@@ -3539,14 +3946,17 @@ fn run_geometry_mode(config: &BandwidthConfig, config_change_tx: broadcast::Send
             if *send_time <= now {
                 if let Some((_, frame_to_send)) = frame_buffer.pop_front() {
                     let _ = multi_device_manager.send_frame(&frame_to_send);
+                    spare_frames.push(frame_to_send);
                 }
             } else {
                 break;
             }
         }
+        live_stats::record_buffer_depth(frame_buffer.len());
 
-        // Small sleep to avoid spinning
-        std::thread::sleep(Duration::from_micros(100));
+        // Sleep until the next frame or the next delayed send, whichever comes first
+        let next_send = frame_buffer.front().map(|(send_time, _)| *send_time);
+        pacer::sleep_until(next_send.map_or(pacer.next_frame(), |t| t.min(pacer.next_frame())));
     }
 }
 
@@ -3825,10 +4235,11 @@ fn run_audio_test_mode() -> Result<()> {
 }
 
 /// Spawn HTTP server in a separate thread that can be restarted
-fn spawn_http_server(config: &BandwidthConfig, config_change_tx: broadcast::Sender<()>, webcam_state: Arc<webcam::WebcamState>) -> Result<Option<thread::JoinHandle<()>>> {
+fn spawn_http_server(config: &BandwidthConfig, config_change_tx: broadcast::Sender<ConfigDelta>, webcam_state: Arc<webcam::WebcamState>, relay_stats: Arc<relay::RelayStats>) -> Result<Option<thread::JoinHandle<()>>> {
     if !config.httpd_enabled {
         return Ok(None);
     }
+    httpd::check_tls_settings(config)?;
 
     let ip = config.httpd_ip.clone();
     let port = config.httpd_port;
@@ -3837,7 +4248,7 @@ fn spawn_http_server(config: &BandwidthConfig, config_change_tx: broadcast::Send
     let handle = thread::spawn(move || {
         let rt = tokio::runtime::Runtime::new().unwrap();
         rt.block_on(async {
-            if let Err(e) = httpd::run_http_server(ip.clone(), port, https_enabled, config_change_tx, webcam_state).await {
+            if let Err(e) = httpd::run_http_server(ip.clone(), port, https_enabled, config_change_tx, webcam_state, relay_stats).await {
                 eprintln!("HTTP server error: {}", e);
             }
         });
@@ -3852,7 +4263,10 @@ fn spawn_http_server(config: &BandwidthConfig, config_change_tx: broadcast::Send
 }
 
 /// Watch config file and send control messages when critical settings change
-fn spawn_config_watcher(config_change_tx: broadcast::Sender<()>) -> Result<()> {
+// Quiet period after a config file event before the change is announced
+const CONFIG_WATCH_DEBOUNCE: Duration = Duration::from_millis(150);
+
+fn spawn_config_watcher(config_change_tx: broadcast::Sender<ConfigDelta>) -> Result<()> {
     let config_path = BandwidthConfig::config_path(None)?;
 
     std::thread::spawn(move || -> Result<()> {
@@ -3863,18 +4277,28 @@ fn spawn_config_watcher(config_change_tx: broadcast::Sender<()>) -> Result<()> {
         };
 
         if watcher
-            .watch(&config_path, RecursiveMode::NonRecursive)
+            .watch(&config::config_watch_dir(&config_path), RecursiveMode::NonRecursive)
             .is_err()
         {
             return Ok(());
         }
 
+        // Last loaded config - each change is broadcast as the set of keys that differ from it
+        let mut last_config = BandwidthConfig::load().unwrap_or_default();
+
         loop {
             match rx.recv() {
-                Ok(Ok(NotifyEvent { kind, .. })) => {
-                    if matches!(kind, notify::EventKind::Modify(_)) {
-                        // Notify all SSE clients that config changed
-                        let _ = config_change_tx.send(());
+                Ok(Ok(event)) => {
+                    if config::is_config_event(&event, &config_path) {
+                        // Debounce - one save produces several events; wait for them to settle
+                        while rx.recv_timeout(CONFIG_WATCH_DEBOUNCE).is_ok() {}
+                        // Notify modes and SSE clients of the keys that changed (saves that change nothing are dropped)
+                        if let Ok(new_config) = BandwidthConfig::load() {
+                            if let Some(delta) = ConfigDelta::between(&last_config, &new_config) {
+                                let _ = config_change_tx.send(delta);
+                            }
+                            last_config = new_config;
+                        }
                     }
                 }
                 Err(_) => break,
@@ -3893,15 +4317,43 @@ fn main() -> Result<()> {
     // Set global config path immediately (before any config loads)
     BandwidthConfig::set_config_path(args.cfg.clone());
 
-    // Check if we're in audio test mode
-    if args.audio_test {
-        return run_audio_test_mode();
-    }
+    // Plain invocation runs the visualizer; the old --test/--audio-test flags map onto "test"
+    let command = args.command.clone().unwrap_or_else(|| {
+        if args.audio_test || args.test.is_some() || args.test_pattern.is_some() || args.test_device.is_some() {
+            config::Command::Test { sequence: args.test.clone(), audio: args.audio_test }
+        } else {
+            config::Command::Run
+        }
+    });
 
-    if args.test.is_some() {
-        // Test mode needs tokio runtime
-        let rt = tokio::runtime::Runtime::new()?;
-        return rt.block_on(test_mode(&args));
+    match command {
+        config::Command::Run => {}
+        config::Command::Test { audio: true, .. } => return run_audio_test_mode(),
+        config::Command::Test { sequence, .. } => {
+            if sequence.is_none() && args.test_pattern.is_none() && args.test_device.is_none() {
+                return Err(anyhow::anyhow!("Specify the LEDs to test (e.g. \"rustwled test 0-9\"), a --test-pattern or a --test-device"));
+            }
+            // Test mode needs tokio runtime
+            let rt = tokio::runtime::Runtime::new()?;
+            return rt.block_on(test_mode(&args, sequence.as_deref()));
+        }
+        config::Command::Discover { timeout, write } => return cli::discover(Duration::from_secs_f64(timeout.max(0.1)), write),
+        config::Command::ImportPalettes { source, write } => return cli::import_palettes(&source, write),
+        config::Command::Preset { action } => return cli::preset(action),
+        config::Command::Set { brightness, values } => return cli::set(&args, brightness, &values),
+        config::Command::Bench { modes, seconds } => return bench::run(seconds, &modes),
+        config::Command::Validate => return cli::validate(args.cfg.as_deref()),
+        config::Command::Doctor => return doctor::run(),
+        config::Command::Latency { seconds, ping } => return latency::run(seconds, ping),
+        config::Command::Calibrate { clicks, write } => return latency::calibrate_audio(clicks, write),
+        config::Command::Play { input, repeat } => return recording::play(&input, repeat),
+        config::Command::Virtual { port, columns } => return virtual_device::run(port, columns),
+        config::Command::Agent { listen, port, token, interface } => return agent::run(&listen, port, token, interface),
+        config::Command::Record { output } => {
+            // Record, then continue into the normal run loop - frames are captured as they are sent
+            recording::start(&output)?;
+            println!("Recording output frames to {}", output.display());
+        }
     }
 
     // Get config file path (custom or default)
@@ -3942,7 +4394,20 @@ fn main() -> Result<()> {
         default_config
     };
 
-    let args_provided = config.merge_with_args(&args);
+    let mut args_provided = config.merge_with_args(&args);
+
+    if args.discover {
+        println!("Scanning for WLED and DDP devices...");
+        let found = discovery::scan(discovery::DEFAULT_TIMEOUT);
+        if found.is_empty() {
+            println!("No devices found");
+        } else {
+            discovery::print_table(&config, &found);
+            let added = discovery::add_to_config(&mut config, &found);
+            println!("Added {} device(s) to wled_devices", added);
+            args_provided |= added > 0;
+        }
+    }
 
     // Save config ONLY if:
     // - Config file doesn't exist (first run setup - need to create it)
@@ -3953,6 +4418,14 @@ fn main() -> Result<()> {
 
     println!("Using config file: {}", config.config_path.as_ref().unwrap().display());
 
+    // Load cpt-city and FastLED palette files so they can be used as gradient names
+    if let Ok(dir) = gradients::palettes_dir() {
+        let count = gradients::discover_palette_files(&dir);
+        if count > 0 {
+            println!("Loaded {} palette(s) from {}", count, dir.display());
+        }
+    }
+
     // Create broadcast channel for SSE config change notifications
     // Buffer size of 100 should be enough for config change events
     let (config_change_tx, _config_change_rx) = broadcast::channel(100);
@@ -3961,12 +4434,94 @@ fn main() -> Result<()> {
     let config_arc = Arc::new(tokio::sync::RwLock::new(config.clone()));
     let webcam_state = Arc::new(webcam::WebcamState::new(config_arc));
 
+    // Create shared relay statistics for HTTP server and relay mode
+    let relay_stats = Arc::new(relay::RelayStats::new());
+
     // Start HTTP server if enabled
-    let _http_server_handle = spawn_http_server(&config, config_change_tx.clone(), webcam_state.clone())?;
+    let _http_server_handle = spawn_http_server(&config, config_change_tx.clone(), webcam_state.clone(), relay_stats.clone())?;
 
     // Start config watcher for dynamic changes
     spawn_config_watcher(config_change_tx.clone())?;
 
+    // Start leader/follower instance sync if configured
+    sync::spawn_sync(&config, config_change_tx.clone())?;
+
+    // Post-processing effects for the running mode, kept current as the config changes
+    postfx::configure(&config);
+    postfx::spawn_watcher(config_change_tx.clone());
+
+    // Soft start/stop of every mode's output, kept current as the config changes
+    fade::configure(&config);
+    fade::spawn_watcher(config_change_tx.clone());
+
+    // Motion interpolation for slow sources, kept current as the config changes
+    frame_interpolation::configure(&config);
+    frame_interpolation::spawn_watcher(config_change_tx.clone());
+
+    // Auto-brightness from a room light sensor, kept current as the config changes
+    ambient::configure(&config);
+    ambient::spawn_watcher(config_change_tx.clone());
+    ambient::spawn_sensor();
+
+    // Brightness cap during quiet hours, kept current as the config changes and the clock moves
+    quiet_hours::configure(&config);
+    quiet_hours::spawn_watcher(config_change_tx.clone());
+    quiet_hours::spawn_clock();
+
+    // Per-device frame rate backoff when the network can't keep up, kept current as the config changes
+    adaptive_fps::configure(&config);
+    adaptive_fps::spawn_watcher(config_change_tx.clone());
+    preset_morph::configure(&config);
+    preset_morph::spawn_watcher(config_change_tx.clone());
+
+    // Two-way on/off and brightness sync with the WLED devices, kept current as the config changes
+    wled_state::configure(&config);
+    wled_state::spawn_watcher(config_change_tx.clone());
+    wled_state::spawn_poller();
+
+    // Devices confined to one WLED segment, read from the devices and kept current as the config changes
+    segments::configure(&config);
+    segments::spawn_watcher(config_change_tx.clone());
+    segments::spawn_poller();
+
+    // Philips Hue entertainment streaming alongside the WLED devices, kept current as the config changes
+    #[cfg(feature = "hue")]
+    {
+        hue::configure(&config);
+        hue::spawn_watcher(config_change_tx.clone());
+        hue::spawn_streamer();
+    }
+    #[cfg(not(feature = "hue"))]
+    if config.hue_enabled {
+        eprintln!("⚠️  hue_enabled is set but rustwled was built without Hue support (cargo build --features hue)");
+    }
+
+    // LIFX bulbs and Nanoleaf panels mirroring LED zones, kept current as the config changes
+    lifx::configure(&config);
+    lifx::spawn_watcher(config_change_tx.clone());
+    lifx::spawn_streamer();
+    nanoleaf::configure(&config);
+    nanoleaf::spawn_watcher(config_change_tx.clone());
+    nanoleaf::spawn_streamer();
+
+    // Alarm animation defaults for /api/alert and monitoring webhooks, kept current as the config changes
+    alert::configure(&config);
+    alert::spawn_watcher(config_change_tx.clone());
+
+    // Demo mode's turn timer, kept current as the config changes
+    demo::configure(&config);
+    demo::spawn_watcher(config_change_tx.clone());
+    demo::spawn_timer(config_change_tx.clone());
+
+    // Physical buttons for headless installs (pin changes need a restart)
+    if let Err(e) = gpio::spawn_buttons(&config) {
+        eprintln!("⚠️  GPIO buttons unavailable: {}", e);
+    }
+
+    // Hue rotation / palette cycling for every gradient, kept current as the config changes
+    gradient_anim::configure(&config);
+    gradient_anim::spawn_watcher(config_change_tx.clone());
+
     // Print mode switching info
     println!("\n=== Dynamic Configuration ===");
     println!("Current mode: {}", config.mode);
@@ -3978,59 +4533,127 @@ fn main() -> Result<()> {
     println!("  - Other settings: Apply in real-time");
     println!();
 
+    // Blank the strip or restore WLED's own state once the mode loop exits (on return, or before a hard exit)
+    let mut exit_guard = wled_state::ExitGuard::new(&config);
+
+    // Modes that panic or fail are restarted (terminal restored, strip blanked) rather than ending the process
+    supervisor::install_panic_hook();
+    let mut supervisor = supervisor::Supervisor::new();
+
     // Main mode switching loop - allows dynamic mode changes without restart
     'mode_loop: loop {
+        // Shutdown requested via the API - the previous mode has already exited cleanly
+        if system::shutdown_requested() {
+            println!("\n👋 Application exiting (shutdown requested via API).");
+            return Ok(());
+        }
+        system::clear_restart();
+
         // Reload config to get latest mode setting
         let mut current_config = BandwidthConfig::load().unwrap_or(config.clone());
 
+        // Demo mode runs the other modes in turn - the one whose turn it is runs as if selected
+        demo::set_active(current_config.mode == "demo");
+        if current_config.mode == "demo" {
+            current_config = demo::turn_config(&current_config);
+            println!("\n🎬 Demo: {}", current_config.mode);
+        }
+
         match current_config.mode.as_str() {
             "midi" => {
                 println!("\n🎵 Starting MIDI mode...");
-                match run_midi_mode(&current_config, args.midi_device.clone(), args.midi_random_colors, config_change_tx.clone()) {
-                    Ok(ModeExitReason::UserQuit) => {
+                match supervisor.run("MIDI", &current_config, || run_midi_mode(&current_config, args.midi_device.clone(), args.midi_random_colors, config_change_tx.clone())) {
+                    Some(ModeExitReason::UserQuit) => {
                         println!("\n👋 Application exiting.");
                         return Ok(());
                     }
-                    Ok(ModeExitReason::ModeChanged) => {
+                    Some(ModeExitReason::ModeChanged) => {
                         println!("\n🔄 MIDI mode exited, switching modes...");
                     }
-                    Err(e) => {
-                        eprintln!("\n❌ MIDI mode error: {}", e);
-                        return Err(e);
-                    }
+                    None => continue, // Failed - restart the mode after the backoff
                 }
             }
             "live" => {
                 println!("\n🎧 Starting Live Audio mode...");
-                match run_live_mode(&current_config, args.delay, config_change_tx.clone()) {
-                    Ok(ModeExitReason::UserQuit) => {
+                match supervisor.run("Live Audio", &current_config, || run_live_mode(&current_config, args.delay, config_change_tx.clone())) {
+                    Some(ModeExitReason::UserQuit) => {
                         println!("\n👋 Application exiting.");
                         return Ok(());
                     }
-                    Ok(ModeExitReason::ModeChanged) => {
+                    Some(ModeExitReason::ModeChanged) => {
                         println!("\n🔄 Live Audio mode exited, switching modes...");
                     }
-                    Err(e) => {
-                        eprintln!("\n❌ Live Audio mode error: {}", e);
-                        return Err(e);
-                    }
+                    None => continue, // Failed - restart the mode after the backoff
                 }
             }
             "relay" => {
                 println!("\n🔄 Starting Relay mode...");
                 let shutdown = Arc::new(AtomicBool::new(false));
-                match relay::run_relay_mode(current_config.clone(), shutdown) {
-                    Ok(ModeExitReason::UserQuit) => {
+                match supervisor.run("Relay", &current_config, || relay::run_relay_mode(current_config.clone(), shutdown, relay_stats.clone())) {
+                    Some(ModeExitReason::UserQuit) => {
                         println!("\n👋 Application exiting.");
                         return Ok(());
                     }
-                    Ok(ModeExitReason::ModeChanged) => {
+                    Some(ModeExitReason::ModeChanged) => {
                         println!("\n🔄 Relay mode exited, restarting...");
                     }
-                    Err(e) => {
-                        eprintln!("\n❌ Relay mode error: {}", e);
-                        return Err(e);
+                    None => continue, // Failed - restart the mode after the backoff
+                }
+            }
+            "ddp" => {
+                println!("\n📡 Starting DDP input mode...");
+                let shutdown = Arc::new(AtomicBool::new(false));
+                match supervisor.run("DDP input", &current_config, || relay::run_relay_mode(current_config.clone(), shutdown, relay_stats.clone())) {
+                    Some(ModeExitReason::UserQuit) => {
+                        println!("\n👋 Application exiting.");
+                        return Ok(());
+                    }
+                    Some(ModeExitReason::ModeChanged) => {
+                        println!("\n🔄 DDP input mode exited, restarting...");
+                    }
+                    None => continue, // Failed - restart the mode after the backoff
+                }
+            }
+            "sacn" => {
+                println!("\n🎛️  Starting sACN input mode...");
+                let shutdown = Arc::new(AtomicBool::new(false));
+                match supervisor.run("sACN input", &current_config, || relay::run_relay_mode(current_config.clone(), shutdown, relay_stats.clone())) {
+                    Some(ModeExitReason::UserQuit) => {
+                        println!("\n👋 Application exiting.");
+                        return Ok(());
+                    }
+                    Some(ModeExitReason::ModeChanged) => {
+                        println!("\n🔄 sACN input mode exited, restarting...");
+                    }
+                    None => continue, // Failed - restart the mode after the backoff
+                }
+            }
+            "artnet" => {
+                println!("\n🎛️  Starting Art-Net input mode...");
+                let shutdown = Arc::new(AtomicBool::new(false));
+                match supervisor.run("Art-Net input", &current_config, || relay::run_relay_mode(current_config.clone(), shutdown, relay_stats.clone())) {
+                    Some(ModeExitReason::UserQuit) => {
+                        println!("\n👋 Application exiting.");
+                        return Ok(());
+                    }
+                    Some(ModeExitReason::ModeChanged) => {
+                        println!("\n🔄 Art-Net input mode exited, restarting...");
+                    }
+                    None => continue, // Failed - restart the mode after the backoff
+                }
+            }
+            "boblight" => {
+                println!("\n📺 Starting boblight input mode...");
+                let shutdown = Arc::new(AtomicBool::new(false));
+                match supervisor.run("Boblight input", &current_config, || relay::run_relay_mode(current_config.clone(), shutdown, relay_stats.clone())) {
+                    Some(ModeExitReason::UserQuit) => {
+                        println!("\n👋 Application exiting.");
+                        return Ok(());
                     }
+                    Some(ModeExitReason::ModeChanged) => {
+                        println!("\n🔄 Boblight input mode exited, restarting...");
+                    }
+                    None => continue, // Failed - restart the mode after the backoff
                 }
             }
             "webcam" => {
@@ -4063,18 +4686,17 @@ fn main() -> Result<()> {
                     // Main TUI loop
                     loop {
                         // Check for config changes via SSE broadcast
-                        if let Ok(()) = config_change_rx.try_recv() {
-                            if let Ok(new_config) = BandwidthConfig::load() {
-                                if new_config.mode != "webcam" {
-                                    // Cleanup terminal
-                                    terminal.show_cursor().unwrap();
-                                    disable_raw_mode().unwrap();
-                                    terminal.backend_mut().execute(LeaveAlternateScreen).unwrap();
-                                    println!("\n🔄 Mode changed, restarting...");
-                                    break;
-                                }
-                                config = new_config;
+                        if let Ok(delta) = config_change_rx.try_recv() {
+                            let new_config = delta.config_or(&config);
+                            if new_config.mode != "webcam" || system::restart_requested() {
+                                // Cleanup terminal
+                                terminal.show_cursor().unwrap();
+                                disable_raw_mode().unwrap();
+                                terminal.backend_mut().execute(LeaveAlternateScreen).unwrap();
+                                println!("\n🔄 Mode changed, restarting...");
+                                break;
                             }
+                            config = new_config;
                         }
 
                         // Calculate FPS and get stats
@@ -4104,7 +4726,7 @@ fn main() -> Result<()> {
                                 .constraints([
                                     Constraint::Length(3),  // Header
                                     Constraint::Min(10),    // Main content
-                                    Constraint::Length(3),  // Footer
+                                    Constraint::Length(4),  // Footer
                                 ])
                                 .split(f.size());
 
@@ -4116,7 +4738,7 @@ fn main() -> Result<()> {
                             f.render_widget(header, chunks[0]);
 
                             // Main content - webcam stats
-                            let frame_size = config.webcam_frame_width * config.webcam_frame_height * 3;
+                            let frame_size = config.webcam.frame_width * config.webcam.frame_height * 3;
                             let stats_text = format!(
                                 "Frame Size: {}x{} ({} bytes)\n\
 Target FPS: {:.1}\n\
@@ -4128,10 +4750,10 @@ Frames Dropped: {} ({:.1}%)\n\
 \n\
 WLED IP: {}\n\
 Stream from: http{}://{}:{}",
-                                config.webcam_frame_width,
-                                config.webcam_frame_height,
+                                config.webcam.frame_width,
+                                config.webcam.frame_height,
                                 frame_size,
-                                config.webcam_target_fps,
+                                config.webcam.target_fps,
                                 fps,
                                 current_frame_count,
                                 frames_sent,
@@ -4150,7 +4772,7 @@ Stream from: http{}://{}:{}",
                             f.render_widget(stats, chunks[1]);
 
                             // Footer
-                            let footer_text = "Press 'q' to quit | Change mode in config file to switch modes";
+                            let footer_text = format!("Press 'q' to quit | Change mode in config file to switch modes\n{}", live_stats::profile_line());
                             let footer = Paragraph::new(footer_text)
                                 .style(Style::default().fg(Color::Gray))
                                 .alignment(Alignment::Center)
@@ -4167,6 +4789,7 @@ Stream from: http{}://{}:{}",
                                     disable_raw_mode().unwrap();
                                     terminal.backend_mut().execute(LeaveAlternateScreen).unwrap();
                                     println!("\nExiting...");
+                                    exit_guard.run();
                                     std::process::exit(0);
                                 }
                             }
@@ -4177,13 +4800,13 @@ Stream from: http{}://{}:{}",
                 });
             }
             "tron" => {
-                if current_config.tron_num_players == 1 {
+                if current_config.tron.num_players == 1 {
                     println!("\n🐍 Starting Snake game mode...");
                 } else {
                     println!("\n🎮 Starting Tron game mode...");
                 }
-                println!("   Grid: {}x{}", current_config.tron_width, current_config.tron_height);
-                println!("   Players: {}", current_config.tron_num_players);
+                println!("   Grid: {}x{}", current_config.tron.width, current_config.tron.height);
+                println!("   Players: {}", current_config.tron.num_players);
                 println!("   Press 'q' to exit");
 
                 // Setup terminal for TUI
@@ -4201,7 +4824,6 @@ Stream from: http{}://{}:{}",
                 let pixel_config = PixelConfig::default();
                 let ddp_client = DDPConnection::try_new(&dest_addr, pixel_config, ID::Default, ddp_socket)?;
                 let ddp_client_arc = Arc::new(Mutex::new(Some(ddp_client)));
-                let config_arc = Arc::new(Mutex::new(current_config.clone()));
 
                 // Subscribe to SSE broadcast channel for config changes (no file watching needed)
                 let mut config_change_rx = config_change_tx.subscribe();
@@ -4209,14 +4831,15 @@ Stream from: http{}://{}:{}",
                 // Create shutdown signal for tron game thread
                 let shutdown = Arc::new(AtomicBool::new(false));
 
-                // Spawn tron game in background thread
-                let tron_config_arc = config_arc.clone();
+                // Spawn tron game in background thread (it follows config changes on its own subscription)
+                let tron_config = current_config.clone();
+                let tron_config_rx = config_change_tx.subscribe();
                 let tron_ddp_arc = ddp_client_arc.clone();
                 let tron_shutdown = shutdown.clone();
                 let tron_handle = thread::spawn(move || {
                     let rt = tokio::runtime::Runtime::new().unwrap();
                     rt.block_on(async {
-                        let _ = tron::run_tron_mode(tron_config_arc, tron_ddp_arc, tron_shutdown).await;
+                        let _ = tron::run_tron_mode(tron_config, tron_config_rx, tron_ddp_arc, tron_shutdown).await;
                     });
                 });
 
@@ -4225,20 +4848,19 @@ Stream from: http{}://{}:{}",
                 let mut config = current_config.clone();
                 loop {
                     // Check for config changes via SSE broadcast
-                    if let Ok(()) = config_change_rx.try_recv() {
-                        if let Ok(new_config) = BandwidthConfig::load() {
-                            if new_config.mode != "tron" {
-                                // Mode changed, signal shutdown and wait for thread to finish
-                                shutdown.store(true, Ordering::Relaxed);
-                                terminal.show_cursor().unwrap();
-                                disable_raw_mode().unwrap();
-                                terminal.backend_mut().execute(LeaveAlternateScreen).unwrap();
-                                println!("\n🔄 Mode changed, stopping tron mode...");
-                                let _ = tron_handle.join();
-                                break;
-                            }
-                            config = new_config;
+                    if let Ok(delta) = config_change_rx.try_recv() {
+                        let new_config = delta.config_or(&config);
+                        if new_config.mode != "tron" || system::restart_requested() {
+                            // Mode changed, signal shutdown and wait for thread to finish
+                            shutdown.store(true, Ordering::Relaxed);
+                            terminal.show_cursor().unwrap();
+                            disable_raw_mode().unwrap();
+                            terminal.backend_mut().execute(LeaveAlternateScreen).unwrap();
+                            println!("\n🔄 Mode changed, stopping tron mode...");
+                            let _ = tron_handle.join();
+                            break;
                         }
+                        config = new_config;
                     }
 
                     // Render TUI
@@ -4251,12 +4873,12 @@ Stream from: http{}://{}:{}",
                                 .constraints([
                                     Constraint::Length(3),  // Header
                                     Constraint::Min(10),    // Main content
-                                    Constraint::Length(3),  // Footer
+                                    Constraint::Length(4),  // Footer
                                 ])
                                 .split(f.size());
 
                             // Header - Mode name on left, quit instructions on right
-                            let mode_name = if config.tron_num_players == 1 {
+                            let mode_name = if config.tron.num_players == 1 {
                                 "🐍 Snake Mode"
                             } else {
                                 "🎮 Tron Mode"
@@ -4277,7 +4899,7 @@ Stream from: http{}://{}:{}",
                             f.render_widget(header, chunks[0]);
 
                             // Main content - Game stats
-                            let stats_text = if config.tron_num_players == 1 {
+                            let stats_text = if config.tron.num_players == 1 {
                                 // Single player Snake mode
                                 format!(
                                     "Grid: {}x{}\n\
@@ -4285,26 +4907,26 @@ Game Speed: {}ms per update\n\
 Trail Length: {}\n\
 \n\
 Snake Color: {}",
-                                    config.tron_width,
-                                    config.tron_height,
-                                    config.tron_speed_ms,
-                                    if config.tron_trail_length == 0 { "Infinite".to_string() } else { config.tron_trail_length.to_string() },
-                                    config.tron_player_1_color,
+                                    config.tron.width,
+                                    config.tron.height,
+                                    config.tron.speed_ms,
+                                    if config.tron.trail_length == 0 { "Infinite".to_string() } else { config.tron.trail_length.to_string() },
+                                    config.tron.player_1_color,
                                 )
                             } else {
                                 // Multi-player Tron mode
                                 let player_colors = vec![
-                                    &config.tron_player_1_color,
-                                    &config.tron_player_2_color,
-                                    &config.tron_player_3_color,
-                                    &config.tron_player_4_color,
-                                    &config.tron_player_5_color,
-                                    &config.tron_player_6_color,
-                                    &config.tron_player_7_color,
-                                    &config.tron_player_8_color,
+                                    &config.tron.player_1_color,
+                                    &config.tron.player_2_color,
+                                    &config.tron.player_3_color,
+                                    &config.tron.player_4_color,
+                                    &config.tron.player_5_color,
+                                    &config.tron.player_6_color,
+                                    &config.tron.player_7_color,
+                                    &config.tron.player_8_color,
                                 ];
                                 let active_colors: Vec<String> = player_colors.iter()
-                                    .take(config.tron_num_players)
+                                    .take(config.tron.num_players)
                                     .map(|c| c.to_string())
                                     .collect();
                                 let colors_display = active_colors.join(", ");
@@ -4318,13 +4940,13 @@ Trail Length: {}\n\
 AI Aggression: {:.0}%\n\
 \n\
 Player Colors:\n  {}",
-                                    config.tron_width,
-                                    config.tron_height,
-                                    config.tron_num_players,
-                                    config.tron_speed_ms,
-                                    config.tron_look_ahead,
-                                    if config.tron_trail_length == 0 { "Infinite".to_string() } else { config.tron_trail_length.to_string() },
-                                    config.tron_ai_aggression * 100.0,
+                                    config.tron.width,
+                                    config.tron.height,
+                                    config.tron.num_players,
+                                    config.tron.speed_ms,
+                                    config.tron.look_ahead,
+                                    if config.tron.trail_length == 0 { "Infinite".to_string() } else { config.tron.trail_length.to_string() },
+                                    config.tron.ai_aggression * 100.0,
                                     colors_display,
                                 )
                             };
@@ -4336,8 +4958,9 @@ Player Colors:\n  {}",
 
                             // Footer - Status information
                             let footer_text = format!(
-                                "WLED: {} | Config changes apply automatically",
-                                config.wled_ip
+                                "WLED: {} | Config changes apply automatically\n{}",
+                                config.wled_ip,
+                                live_stats::profile_line()
                             );
                             let footer = Paragraph::new(footer_text)
                                 .style(Style::default().fg(Color::Gray))
@@ -4360,6 +4983,7 @@ Player Colors:\n  {}",
                                 println!("\nStopping tron mode...");
                                 let _ = tron_handle.join();
                                 println!("Exiting...");
+                                exit_guard.run();
                                 std::process::exit(0);
                             }
                         }
@@ -4367,121 +4991,119 @@ Player Colors:\n  {}",
                 }
             }
             "geometry" => {
-                match run_geometry_mode(&current_config, config_change_tx.clone()) {
-                    Ok(ModeExitReason::UserQuit) => {
+                match supervisor.run("Geometry", &current_config, || run_geometry_mode(&current_config, config_change_tx.clone())) {
+                    Some(ModeExitReason::UserQuit) => {
                         println!("\n👋 Application exiting.");
                         return Ok(());
                     }
-                    Ok(ModeExitReason::ModeChanged) => {
+                    Some(ModeExitReason::ModeChanged) => {
                         println!("   Geometry mode exited, checking for mode change...");
                         continue; // Loop back to reload config and check new mode
                     }
-                    Err(e) => {
-                        eprintln!("Geometry mode error: {}", e);
-                        return Err(e);
-                    }
+                    None => continue, // Failed - restart the mode after the backoff
                 }
             }
             "sand" => {
                 println!("\n🏖️  Starting Falling Sand simulation mode...");
-                match run_sand_mode(&current_config, config_change_tx.clone()) {
-                    Ok(ModeExitReason::UserQuit) => {
+                match supervisor.run("Sand", &current_config, || run_sand_mode(&current_config, config_change_tx.clone())) {
+                    Some(ModeExitReason::UserQuit) => {
                         println!("\n👋 Application exiting.");
                         return Ok(());
                     }
-                    Ok(ModeExitReason::ModeChanged) => {
+                    Some(ModeExitReason::ModeChanged) => {
                         println!("   Sand mode exited, checking for mode change...");
                         continue; // Loop back to reload config and check new mode
                     }
-                    Err(e) => {
-                        eprintln!("Sand mode error: {}", e);
-                        return Err(e);
-                    }
+                    None => continue, // Failed - restart the mode after the backoff
                 }
             }
             _ => {
                 println!("\n📊 Starting network monitoring mode...");
 
-                // Check if interface is configured - if not, auto-select first available
-                if current_config.interface.trim().is_empty() {
-                    // Get available interfaces
-                    let available_interfaces = if !current_config.ssh_host.is_empty() {
-                        let ssh_user = if current_config.ssh_user.is_empty() {
-                            None
+                // Agents pick their own interfaces and replays read the history file - only check
+                // the interfaces monitored here or over SSH
+                if current_config.agent_hosts.is_empty() && args.replay.is_none() {
+                    // Check if interface is configured - if not, auto-select first available
+                    if current_config.interface.trim().is_empty() {
+                        // Get available interfaces
+                        let available_interfaces = if !current_config.ssh_host.is_empty() {
+                            let ssh_user = if current_config.ssh_user.is_empty() {
+                                None
+                            } else {
+                                Some(current_config.ssh_user.as_str())
+                            };
+                            _rt.block_on(httpd::get_remote_network_interfaces(&current_config.ssh_host, ssh_user))?
                         } else {
-                            Some(current_config.ssh_user.as_str())
+                            httpd::get_network_interfaces()?
                         };
-                        _rt.block_on(httpd::get_remote_network_interfaces(&current_config.ssh_host, ssh_user))?
-                    } else {
-                        httpd::get_network_interfaces()?
-                    };
 
-                    if available_interfaces.is_empty() {
-                        return Err(anyhow::anyhow!("No network interfaces found"));
-                    }
+                        if available_interfaces.is_empty() {
+                            return Err(anyhow::anyhow!("No network interfaces found"));
+                        }
 
-                    println!("\n⚠️  No network interface configured");
-                    println!("Available interfaces: {}", available_interfaces.join(", "));
-                    println!("\nAuto-selecting first interface: {}", available_interfaces[0]);
-                    println!("(Set this in the web UI or config file to persist)");
+                        println!("\n⚠️  No network interface configured");
+                        println!("Available interfaces: {}", available_interfaces.join(", "));
+                        println!("\nAuto-selecting first interface: {}", available_interfaces[0]);
+                        println!("(Set this in the web UI or config file to persist)");
 
-                    // Auto-select first interface for this session only - DO NOT SAVE to avoid overwriting config
-                    current_config.interface = available_interfaces[0].clone();
-                }
+                        // Auto-select first interface for this session only - DO NOT SAVE to avoid overwriting config
+                        current_config.interface = available_interfaces[0].clone();
+                    }
 
-                // Validate that configured interface(s) actually exist on the host
-                let configured_interfaces: Vec<&str> = current_config.interface.split(',').map(|s| s.trim()).collect();
+                    // Validate that configured interface(s) actually exist on the host
+                    let configured_interfaces: Vec<&str> = current_config.interface.split(',').map(|s| s.trim()).collect();
 
-                // Get available interfaces based on whether we're using SSH or local
-                let available_interfaces = if !current_config.ssh_host.is_empty() {
-                    // Remote SSH host
-                    let ssh_user = if current_config.ssh_user.is_empty() {
-                        None
-                    } else {
-                        Some(current_config.ssh_user.as_str())
-                    };
+                    // Get available interfaces based on whether we're using SSH or local
+                    let available_interfaces = if !current_config.ssh_host.is_empty() {
+                        // Remote SSH host
+                        let ssh_user = if current_config.ssh_user.is_empty() {
+                            None
+                        } else {
+                            Some(current_config.ssh_user.as_str())
+                        };
 
-                    match _rt.block_on(httpd::get_remote_network_interfaces(&current_config.ssh_host, ssh_user)) {
-                        Ok(interfaces) => interfaces,
-                        Err(e) => {
-                            eprintln!("\n❌ Error: Failed to get network interfaces from remote host: {}", e);
-                            return Err(e);
+                        match _rt.block_on(httpd::get_remote_network_interfaces(&current_config.ssh_host, ssh_user)) {
+                            Ok(interfaces) => interfaces,
+                            Err(e) => {
+                                eprintln!("\n❌ Error: Failed to get network interfaces from remote host: {}", e);
+                                return Err(e);
+                            }
                         }
-                    }
-                } else {
-                    // Local host
-                    match httpd::get_network_interfaces() {
-                        Ok(interfaces) => interfaces,
-                        Err(e) => {
-                            eprintln!("\n❌ Error: Failed to get network interfaces: {}", e);
-                            return Err(e);
+                    } else {
+                        // Local host
+                        match httpd::get_network_interfaces() {
+                            Ok(interfaces) => interfaces,
+                            Err(e) => {
+                                eprintln!("\n❌ Error: Failed to get network interfaces: {}", e);
+                                return Err(e);
+                            }
                         }
-                    }
-                };
+                    };
 
-                // Check if all configured interfaces exist
-                let mut invalid_interfaces = Vec::new();
-                for iface in &configured_interfaces {
-                    if !available_interfaces.contains(&iface.to_string()) {
-                        invalid_interfaces.push(*iface);
+                    // Check if all configured interfaces exist
+                    let mut invalid_interfaces = Vec::new();
+                    for iface in &configured_interfaces {
+                        if !available_interfaces.contains(&iface.to_string()) {
+                            invalid_interfaces.push(*iface);
+                        }
                     }
-                }
 
-                // If any interfaces are invalid, auto-select first available
-                if !invalid_interfaces.is_empty() {
-                    eprintln!("\n⚠️  Configured interface(s) not found on host!");
-                    eprintln!("Invalid: {}", invalid_interfaces.join(", "));
-                    eprintln!("Available: {}", available_interfaces.join(", "));
+                    // If any interfaces are invalid, auto-select first available
+                    if !invalid_interfaces.is_empty() {
+                        eprintln!("\n⚠️  Configured interface(s) not found on host!");
+                        eprintln!("Invalid: {}", invalid_interfaces.join(", "));
+                        eprintln!("Available: {}", available_interfaces.join(", "));
 
-                    if available_interfaces.is_empty() {
-                        return Err(anyhow::anyhow!("No network interfaces found"));
-                    }
+                        if available_interfaces.is_empty() {
+                            return Err(anyhow::anyhow!("No network interfaces found"));
+                        }
 
-                    println!("\nAuto-selecting first interface: {}", available_interfaces[0]);
-                    println!("(Set this in the web UI or config file to persist)");
+                        println!("\nAuto-selecting first interface: {}", available_interfaces[0]);
+                        println!("(Set this in the web UI or config file to persist)");
 
-                    // Auto-select first interface for this session only - DO NOT SAVE to avoid overwriting config
-                    current_config.interface = available_interfaces[0].clone();
+                        // Auto-select first interface for this session only - DO NOT SAVE to avoid overwriting config
+                        current_config.interface = available_interfaces[0].clone();
+                    }
                 }
 
                 // Run bandwidth mode inline (break to mode_loop when mode changes)
@@ -4489,23 +5111,48 @@ Player Colors:\n  {}",
                 // Use current_config for this bandwidth mode session
                 let mut config = current_config.clone();
 
-    println!("Connecting to bandwidth monitor...");
-    println!("Interface(s): {}", config.interface);
-    if args.host.is_some() {
-        println!("Please enter your SSH password when prompted...\n");
-    }
+    // --replay plays a window of recorded history instead of live readings
+    let history_path = bandwidth_history::history_path(&config_path);
+    let replay_speed = args.replay_speed.unwrap_or(60.0).max(0.01);
+    let replay = match args.replay.as_deref() {
+        Some(spec) => {
+            let (from, to) = bandwidth_history::parse_window(spec, bandwidth_history::now_ms())?;
+            let samples = bandwidth_history::read_window(&history_path, from, to)?;
+            if samples.is_empty() {
+                return Err(anyhow::anyhow!("No bandwidth history recorded between {} and {} (UTC)",
+                    bandwidth_history::clock(from), bandwidth_history::clock(to)));
+            }
+            Some(samples)
+        }
+        None => None,
+    };
 
-    let child_result = _rt.block_on(spawn_bandwidth_monitor(&args, &config));
-    let mut child = match child_result {
-        Ok(c) => c,
-        Err(e) => {
-            eprintln!("Error: Failed to start bandwidth monitor: {}", e);
-            return Err(e);
+    // Agents report their own interfaces, so no monitor command is needed
+    let agent_hosts = agent::parse_hosts(&config.agent_hosts);
+    let mut child = if let Some(samples) = &replay {
+        println!("Replaying {} readings at {}x...", samples.len(), replay_speed);
+        None
+    } else if agent_hosts.is_empty() {
+        println!("Connecting to bandwidth monitor...");
+        println!("Interface(s): {}", config.interface);
+        if args.host.is_some() {
+            println!("Please enter your SSH password when prompted...\n");
+        }
+
+        match _rt.block_on(spawn_bandwidth_monitor(&args, &config)) {
+            Ok(c) => Some(c),
+            Err(e) => {
+                eprintln!("Error: Failed to start bandwidth monitor: {}", e);
+                return Err(e);
+            }
         }
+    } else {
+        println!("Reading counters from agent(s): {}", agent_hosts.join(", "));
+        None
     };
 
     // For remote connections, wait for first line of output to ensure connection succeeded
-    if args.host.is_some() {
+    if let Some(child) = child.as_mut().filter(|_| args.host.is_some()) {
         println!("Waiting for connection to establish...");
 
         let wait_result = _rt.block_on(async {
@@ -4555,68 +5202,11 @@ Player Colors:\n  {}",
     terminal.clear()?;
     terminal.hide_cursor()?;
 
+    // Create shutdown flag for clean termination
+    let shutdown = Arc::new(AtomicBool::new(false));
+
     // Create shared state for renderer
-    // Resolve color strings (could be gradient names or hex colors)
-    let tx_color = if config.tx_color.is_empty() {
-        gradients::resolve_color_string(&config.color)
-    } else {
-        gradients::resolve_color_string(&config.tx_color)
-    };
-    let rx_color = if config.rx_color.is_empty() {
-        gradients::resolve_color_string(&config.color)
-    } else {
-        gradients::resolve_color_string(&config.rx_color)
-    };
-
-    let interpolation_mode = match config.interpolation.to_lowercase().as_str() {
-        "basis" => InterpolationMode::Basis,
-        "catmullrom" | "catmull-rom" => InterpolationMode::CatmullRom,
-        _ => InterpolationMode::Linear,
-    };
-
-    let direction = match config.direction.to_lowercase().as_str() {
-        "mirrored" => DirectionMode::Mirrored,
-        "opposing" => DirectionMode::Opposing,
-        "left" => DirectionMode::Left,
-        "right" => DirectionMode::Right,
-        _ => DirectionMode::Mirrored,
-    };
-
-    // Create shutdown flag for clean termination
-    let shutdown = Arc::new(AtomicBool::new(false));
-
-    let shared_state = Arc::new(Mutex::new(SharedRenderState {
-        current_rx_kbps: 0.0,
-        current_tx_kbps: 0.0,
-        start_rx_kbps: 0.0,
-        start_tx_kbps: 0.0,
-        last_bandwidth_update: None,
-        animation_speed: config.animation_speed,
-        scale_animation_speed: config.scale_animation_speed,
-        tx_animation_direction: config.tx_animation_direction.clone(),
-        rx_animation_direction: config.rx_animation_direction.clone(),
-        interpolation_time_ms: config.interpolation_time_ms,
-        enable_interpolation: config.enable_interpolation,
-        max_bandwidth_kbps: config.max_gbps * 1000.0 * 1000.0,
-        tx_color,
-        rx_color,
-        use_gradient: config.use_gradient,
-        intensity_colors: config.intensity_colors,
-        interpolation_mode,
-        direction,
-        swap: config.swap,
-        fps: config.fps,
-        ddp_delay_ms: config.ddp_delay_ms,
-        global_brightness: config.global_brightness,
-        total_leds: config.total_leds,
-        rx_split_percent: config.rx_split_percent,
-        strobe_on_max: config.strobe_on_max,
-        strobe_rate_hz: config.strobe_rate_hz,
-        strobe_duration_ms: config.strobe_duration_ms,
-        strobe_color: config.strobe_color.clone(),
-        test_mode: config.test_tx || config.test_rx,
-        generation: 0,
-    }));
+    let shared_state = Arc::new(RenderStateCell::new(SharedRenderState::from_config(&config)));
 
     // Create renderer with multi-device support
     let renderer = match Renderer::new(&config, shared_state.clone(), shutdown.clone()) {
@@ -4630,6 +5220,7 @@ Player Colors:\n  {}",
     };
 
     // Spawn dedicated render thread - runs at 60 FPS independently
+    let device_manager = renderer.device_manager();
     thread::spawn(move || {
         renderer.run();
     });
@@ -4665,28 +5256,67 @@ Player Colors:\n  {}",
         messages.push(format!("[{}] Debug log: /tmp/bandwidth_debug.log", get_timestamp()));
     }
 
-    // Spawn bandwidth reader in separate tokio task
-    let stdout = child.stdout.take().expect("Failed to capture stdout");
-    _rt.spawn(async move {
-        let reader = BufReader::new(stdout);
-        let mut lines = reader.lines();
-
-        // Always create debug log file
-        let mut debug_log = std::fs::File::create("/tmp/bandwidth_debug.log").ok();
-
-        while let Ok(Some(line)) = lines.next_line().await {
-            // Debug: write raw line with timestamp to file when received from SSH
-            if let Some(ref mut log) = debug_log {
-                use std::io::Write;
-                let _ = writeln!(log, "[{}] SSH OUTPUT: {}", get_timestamp(), line);
-                let _ = log.flush(); // Flush immediately so tail -f works
-            }
+    // Always create debug log file
+    let mut debug_log = std::fs::File::create("/tmp/bandwidth_debug.log").ok();
+    let mut forward_line = move |line: String| {
+        // Debug: write raw line with timestamp to file when received from SSH
+        if let Some(ref mut log) = debug_log {
+            use std::io::Write;
+            let _ = writeln!(log, "[{}] SSH OUTPUT: {}", get_timestamp(), line);
+            let _ = log.flush(); // Flush immediately so tail -f works
+        }
 
-            if bandwidth_tx.send(line).is_err() {
-                break; // Main thread dropped receiver, time to exit
-            }
+        // False once the main thread has dropped the receiver, time to exit
+        bandwidth_tx.send(line).is_ok()
+    };
+
+    // Spawn bandwidth reader in separate tokio task
+    let replaying = replay.is_some();
+    let agent_mode = child.is_none() && !replaying;
+    let agent_count = agent_hosts.len();
+    let replay_progress = Arc::new(bandwidth_history::ReplayProgress::default());
+    if let Some(samples) = replay {
+        remote_link::set_local();
+        if !quiet {
+            messages.push(format!("[{}] Replaying bandwidth history from {} to {} (UTC) at {}x", get_timestamp(),
+                bandwidth_history::clock(samples[0].at_ms), bandwidth_history::clock(samples[samples.len() - 1].at_ms), replay_speed));
         }
-    });
+        let progress = replay_progress.clone();
+        let shutdown = shutdown.clone();
+        _rt.spawn(bandwidth_history::replay(samples, replay_speed, progress, shutdown, forward_line));
+    } else if let Some(mut child) = child {
+        if let Some((host, ssh_user)) = remote_target(&args, &config) {
+            // Remote sessions are watched and reconnected if they drop or stall
+            let interface = config.interface.clone();
+            let shutdown = shutdown.clone();
+            _rt.spawn(async move {
+                let respawn = || spawn_remote_monitor(&host, ssh_user.as_ref(), &interface, true);
+                remote_link::supervise(&host, child, respawn, shutdown, forward_line).await;
+                remote_link::set_local();  // Bandwidth mode has exited - no link any more
+            });
+        } else {
+            remote_link::set_local();
+            let stdout = child.stdout.take().expect("Failed to capture stdout");
+            _rt.spawn(async move {
+                let reader = BufReader::new(stdout);
+                let mut lines = reader.lines();
+
+                while let Ok(Some(line)) = lines.next_line().await {
+                    if !forward_line(line) {
+                        break;
+                    }
+                }
+            });
+        }
+    } else {
+        // Agents reconnect on their own - their state is shown per host
+        remote_link::set_local();
+        let token = config.agent_token.clone();
+        let shutdown = shutdown.clone();
+        _rt.spawn(agent::stream_all(agent_hosts, token, shutdown, forward_line));
+    }
+    let mut link_seen = (remote_link::status().state, 0);  // Link state and reconnect attempt last shown
+    let mut agents_up = 0;  // Agents connected when last shown
 
     // Subscribe to SSE broadcast channel for config changes (no file watching needed)
     let mut config_change_rx = config_change_tx.subscribe();
@@ -4697,7 +5327,7 @@ Player Colors:\n  {}",
             // Three-section layout: Header, Main Content, Footer
             let chunks = Layout::default()
                 .direction(Direction::Vertical)
-                .constraints([Constraint::Length(3), Constraint::Min(1), Constraint::Length(3)].as_ref())
+                .constraints([Constraint::Length(3), Constraint::Min(1), Constraint::Length(4)].as_ref())
                 .split(f.size());
 
             // Header - show mode, sub-mode, and interface
@@ -4729,8 +5359,9 @@ Player Colors:\n  {}",
 
             // Footer - show monitoring source and controls
             let footer_text = format!(
-                "Source: Network [{}] | WLED: {} | LEDs: {} | FPS: {:.0} | Delay: {:.1}ms | Press 'i' for config, 'q' or Ctrl+C to quit",
-                config.interface, config.wled_ip, config.total_leds, config.fps, config.ddp_delay_ms
+                "Source: Network [{}] | WLED: {} | LEDs: {} | FPS: {:.0} | Delay: {:.1}ms | Press 'i' for config, 'q' or Ctrl+C to quit\n{}",
+                config.interface, config.wled_ip, config.total_leds, config.fps, config.ddp_delay_ms,
+                live_stats::profile_line()
             );
             let footer = Paragraph::new(footer_text)
                 .block(Block::default().borders(Borders::ALL));
@@ -4743,9 +5374,24 @@ Player Colors:\n  {}",
     // Initialize bandwidth tracker for Linux /proc/net/dev parsing
     let mut bandwidth_tracker: Option<BandwidthTracker> = Some(BandwidthTracker::new());
 
+    // Readings go to the history file for --replay (but not the ones being replayed)
+    let open_history = |config: &BandwidthConfig, messages: &mut Vec<String>| {
+        if !config.bandwidth_history || replaying {
+            return None;
+        }
+        match bandwidth_history::HistoryWriter::open(&history_path, config.bandwidth_history_hours) {
+            Ok(writer) => Some(writer),
+            Err(e) => {
+                messages.push(format!("[{}] Can't record bandwidth history to {}: {}", get_timestamp(), history_path.display(), e));
+                None
+            }
+        }
+    };
+    let mut history = open_history(&config, &mut messages);
+
     // Initialize test mode bandwidth values if enabled
     if config.test_tx || config.test_rx {
-        let mut state = shared_state.lock().unwrap();
+        let mut state = shared_state.write();
         if config.test_rx {
             let test_rx_kbps = config.max_gbps * 1000.0 * 1000.0 * (config.test_rx_percent / 100.0);
             state.current_rx_kbps = test_rx_kbps;
@@ -4805,6 +5451,19 @@ Player Colors:\n  {}",
         match bandwidth_rx.try_recv() {
             Ok(line) => {
                 if let Some((rx_kbps, tx_kbps)) = parse_bandwidth_line(&line, &mut bandwidth_tracker) {
+                    // Agents report many hosts - show their combined traffic
+                    let (rx_kbps, tx_kbps) = match &bandwidth_tracker {
+                        Some(tracker) if agent_mode => tracker.totals(),
+                        _ => (rx_kbps, tx_kbps),
+                    };
+
+                    if let Some(writer) = history.as_mut() {
+                        if let Err(e) = writer.record(rx_kbps, tx_kbps) {
+                            messages.push(format!("[{}] Stopped recording bandwidth history: {}", get_timestamp(), e));
+                            history = None;
+                        }
+                    }
+
                     // Override with test values if test mode is enabled for each direction
                     let rx_kbps = if config.test_rx {
                         config.max_gbps * 1000.0 * 1000.0 * (config.test_rx_percent / 100.0)
@@ -4820,7 +5479,7 @@ Player Colors:\n  {}",
 
                     // Update shared state (non-blocking for renderer)
                     {
-                        let mut state = shared_state.lock().unwrap();
+                        let mut state = shared_state.write();
                         // Store current values as the starting point for interpolation
                         state.start_rx_kbps = state.current_rx_kbps;
                         state.start_tx_kbps = state.current_tx_kbps;
@@ -4829,7 +5488,15 @@ Player Colors:\n  {}",
                         state.current_tx_kbps = tx_kbps;
                         // Record the time when this update happened
                         state.last_bandwidth_update = Some(Instant::now());
+                        // Dashboard rows - per interface from /proc/net/dev, otherwise one row for the whole source
+                        if state.dashboard.is_some() {
+                            state.interface_rates = match &bandwidth_tracker {
+                                Some(tracker) if !tracker.rates.is_empty() && !config.test_rx && !config.test_tx => tracker.rates.clone(),
+                                _ => vec![dashboard::InterfaceRate { label: dashboard::label(&config.interface), rx_kbps, tx_kbps }],
+                            };
+                        }
                     }
+                    live_stats::record_bandwidth(rx_kbps, tx_kbps);
 
                     // Generate messages for UI
                     let rx_leds = calculate_leds(rx_kbps, config.max_gbps * 1000.0 * 1000.0);
@@ -4859,466 +5526,592 @@ Player Colors:\n  {}",
             }
         }
 
-        // Check config file updates via SSE broadcast
-        if let Ok(()) = config_change_rx.try_recv() {
-            if let Ok(new_config) = BandwidthConfig::load() {
-                // Update shared state with new config
-                {
-                    let mut state = shared_state.lock().unwrap();
-
-                    // Handle color updates using unified resolution system
-                    let color_changed = new_config.color != config.color;
-                    let tx_color_changed = new_config.tx_color != config.tx_color;
-                    let rx_color_changed = new_config.rx_color != config.rx_color;
-
-                    if tx_color_changed || rx_color_changed || color_changed {
-                        // Use unified color resolution system
-                        let (resolved_tx_color, resolved_rx_color) = resolve_tx_rx_colors(&new_config);
-
-                        if tx_color_changed || (color_changed && new_config.tx_color.is_empty()) {
-                            state.tx_color = resolved_tx_color.clone();
-                            state.generation += 1;
-                            if !quiet {
-                                if new_config.tx_color.is_empty() {
-                                    messages.push(format!(
-                                        "[{}] TX color updated to: {} (from main color)",
-                                        get_timestamp(),
-                                        new_config.color
-                                    ));
-                                } else {
-                                    messages.push(format!("[{}] TX color updated to: {}", get_timestamp(), new_config.tx_color));
-                                }
-                            }
-                        }
-
-                        if rx_color_changed || (color_changed && new_config.rx_color.is_empty()) {
-                            state.rx_color = resolved_rx_color.clone();
-                            state.generation += 1;
-                            if !quiet {
-                                if new_config.rx_color.is_empty() {
-                                    messages.push(format!(
-                                        "[{}] RX color updated to: {} (from main color)",
-                                        get_timestamp(),
-                                        new_config.color
-                                    ));
-                                } else {
-                                    messages.push(format!("[{}] RX color updated to: {}", get_timestamp(), new_config.rx_color));
-                                }
-                            }
-                        }
+        // SSH link dropped or came back
+        let link = remote_link::status();
+        if link.state != link_seen.0 {
+            match link.state {
+                remote_link::LinkState::Reconnecting => {
+                    // Show no traffic rather than freezing on the last reading
+                    let mut state = shared_state.write();
+                    state.start_rx_kbps = state.current_rx_kbps;
+                    state.start_tx_kbps = state.current_tx_kbps;
+                    state.current_rx_kbps = 0.0;
+                    state.current_tx_kbps = 0.0;
+                    state.last_bandwidth_update = Some(Instant::now());
+                    state.interface_rates.clear();
+                    drop(state);
+                    if !quiet {
+                        messages.push(format!("[{}] SSH link to {} lost ({}), reconnecting...", get_timestamp(),
+                            link.host, link.last_error.as_deref().unwrap_or("unknown")));
                     }
-
-                    // Update max bandwidth
-                    if new_config.max_gbps != config.max_gbps {
-                        state.max_bandwidth_kbps = new_config.max_gbps * 1000.0 * 1000.0;
-                        if !quiet {
-                            messages.push(format!(
-                                "[{}] Max bandwidth updated to: {} Gbps",
-                                get_timestamp(),
-                                new_config.max_gbps
-                            ));
-                        }
+                }
+                remote_link::LinkState::Connected => {
+                    // Byte counters kept counting while the link was down - start the rates afresh
+                    bandwidth_tracker = Some(BandwidthTracker::new());
+                    if !quiet {
+                        messages.push(format!("[{}] SSH link to {} restored", get_timestamp(), link.host));
                     }
+                }
+                remote_link::LinkState::Local => {}
+            }
+        }
+        if (link.state, link.attempts) != link_seen {
+            link_seen = (link.state, link.attempts);
+            needs_render = true;
+        }
+        if agent_mode {
+            let agents = agent::status();
+            let up = agents.iter().filter(|agent| agent.connected).count();
+            if up != agents_up {
+                if !quiet {
+                    messages.push(format!("[{}] Agents connected: {}/{}", get_timestamp(), up, agent_count));
+                }
+                agents_up = up;
+                needs_render = true;
+            }
+        }
 
-                    // Update direction
-                    if new_config.direction != config.direction {
-                        let direction = match new_config.direction.to_lowercase().as_str() {
-                            "mirrored" => DirectionMode::Mirrored,
-                            "opposing" => DirectionMode::Opposing,
-                            "left" => DirectionMode::Left,
-                            "right" => DirectionMode::Right,
-                            _ => DirectionMode::Mirrored,
-                        };
-                        state.direction = direction;
+        // Check config file updates via SSE broadcast
+        if let Ok(delta) = config_change_rx.try_recv() {
+            let new_config = delta.config_or(&config);
+            // Update shared state with new config
+            {
+                let mut state = shared_state.write();
+
+                // Handle color updates using unified resolution system
+                let color_changed = new_config.color != config.color;
+                let tx_color_changed = new_config.tx_color != config.tx_color;
+                let rx_color_changed = new_config.rx_color != config.rx_color;
+
+                if tx_color_changed || rx_color_changed || color_changed {
+                    // Use unified color resolution system
+                    let (resolved_tx_color, resolved_rx_color) = resolve_tx_rx_colors(&new_config);
+
+                    if tx_color_changed || (color_changed && new_config.tx_color.is_empty()) {
+                        state.tx_color = resolved_tx_color.clone();
                         state.generation += 1;
                         if !quiet {
-                            messages.push(format!("[{}] Direction updated to: {}", get_timestamp(), new_config.direction));
+                            if new_config.tx_color.is_empty() {
+                                messages.push(format!(
+                                    "[{}] TX color updated to: {} (from main color)",
+                                    get_timestamp(),
+                                    new_config.color
+                                ));
+                            } else {
+                                messages.push(format!("[{}] TX color updated to: {}", get_timestamp(), new_config.tx_color));
+                            }
                         }
                     }
 
-                    // Update swap
-                    if new_config.swap != config.swap {
-                        state.swap = new_config.swap;
+                    if rx_color_changed || (color_changed && new_config.rx_color.is_empty()) {
+                        state.rx_color = resolved_rx_color.clone();
                         state.generation += 1;
                         if !quiet {
-                            messages.push(format!(
-                                "[{}] Swap: {}",
-                                get_timestamp(),
-                                if new_config.swap { "enabled" } else { "disabled" }
-                            ));
+                            if new_config.rx_color.is_empty() {
+                                messages.push(format!(
+                                    "[{}] RX color updated to: {} (from main color)",
+                                    get_timestamp(),
+                                    new_config.color
+                                ));
+                            } else {
+                                messages.push(format!("[{}] RX color updated to: {}", get_timestamp(), new_config.rx_color));
+                            }
                         }
                     }
+                }
 
-                    // Update RX/TX split percentage
-                    if new_config.rx_split_percent != config.rx_split_percent {
-                        state.rx_split_percent = new_config.rx_split_percent;
-                        if !quiet {
-                            let tx_split = 100.0 - new_config.rx_split_percent;
-                            messages.push(format!(
-                                "[{}] LED split updated to: RX {:.0}% / TX {:.0}%",
-                                get_timestamp(),
-                                new_config.rx_split_percent,
-                                tx_split
-                            ));
-                        }
+                // Update max bandwidth
+                if new_config.max_gbps != config.max_gbps {
+                    state.max_bandwidth_kbps = new_config.max_gbps * 1000.0 * 1000.0;
+                    if !quiet {
+                        messages.push(format!(
+                            "[{}] Max bandwidth updated to: {} Gbps",
+                            get_timestamp(),
+                            new_config.max_gbps
+                        ));
                     }
+                }
 
-                    // Update strobe on max
-                    if new_config.strobe_on_max != config.strobe_on_max {
-                        state.strobe_on_max = new_config.strobe_on_max;
-                        if !quiet {
-                            messages.push(format!(
-                                "[{}] Strobe on max: {}",
-                                get_timestamp(),
-                                if new_config.strobe_on_max { "enabled" } else { "disabled" }
-                            ));
-                        }
+                // Update direction
+                if new_config.direction != config.direction {
+                    let direction = match new_config.direction.to_lowercase().as_str() {
+                        "mirrored" => DirectionMode::Mirrored,
+                        "opposing" => DirectionMode::Opposing,
+                        "left" => DirectionMode::Left,
+                        "right" => DirectionMode::Right,
+                        _ => DirectionMode::Mirrored,
+                    };
+                    state.direction = direction;
+                    state.generation += 1;
+                    if !quiet {
+                        messages.push(format!("[{}] Direction updated to: {}", get_timestamp(), new_config.direction));
                     }
+                }
 
-                    // Update strobe rate
-                    if new_config.strobe_rate_hz != config.strobe_rate_hz {
-                        state.strobe_rate_hz = new_config.strobe_rate_hz;
-                        // Also validate strobe_duration_ms doesn't exceed new cycle time
-                        if new_config.strobe_rate_hz > 0.0 {
-                            let max_duration = 1000.0 / new_config.strobe_rate_hz;
-                            if state.strobe_duration_ms > max_duration {
-                                state.strobe_duration_ms = max_duration;
-                            }
-                        }
-                        if !quiet {
-                            messages.push(format!(
-                                "[{}] Strobe rate updated to: {:.1} Hz",
-                                get_timestamp(),
-                                new_config.strobe_rate_hz
-                            ));
-                        }
+                // Update swap
+                if new_config.swap != config.swap {
+                    state.swap = new_config.swap;
+                    state.generation += 1;
+                    if !quiet {
+                        messages.push(format!(
+                            "[{}] Swap: {}",
+                            get_timestamp(),
+                            if new_config.swap { "enabled" } else { "disabled" }
+                        ));
                     }
+                }
 
-                    // Update strobe duration
-                    if new_config.strobe_duration_ms != config.strobe_duration_ms {
-                        state.strobe_duration_ms = new_config.strobe_duration_ms;
-                        if !quiet {
-                            messages.push(format!(
-                                "[{}] Strobe duration updated to: {:.0} ms",
-                                get_timestamp(),
-                                new_config.strobe_duration_ms
-                            ));
-                        }
+                // Update RX/TX split percentage
+                if new_config.rx_split_percent != config.rx_split_percent {
+                    state.rx_split_percent = new_config.rx_split_percent;
+                    if !quiet {
+                        let tx_split = 100.0 - new_config.rx_split_percent;
+                        messages.push(format!(
+                            "[{}] LED split updated to: RX {:.0}% / TX {:.0}%",
+                            get_timestamp(),
+                            new_config.rx_split_percent,
+                            tx_split
+                        ));
                     }
+                }
 
-                    // Update strobe color
-                    if new_config.strobe_color != config.strobe_color {
-                        state.strobe_color = new_config.strobe_color.clone();
-                        if !quiet {
-                            messages.push(format!(
-                                "[{}] Strobe color updated to: {}",
-                                get_timestamp(),
-                                new_config.strobe_color
-                            ));
-                        }
+                // Update strobe on max
+                if new_config.strobe_on_max != config.strobe_on_max {
+                    state.strobe_on_max = new_config.strobe_on_max;
+                    if !quiet {
+                        messages.push(format!(
+                            "[{}] Strobe on max: {}",
+                            get_timestamp(),
+                            if new_config.strobe_on_max { "enabled" } else { "disabled" }
+                        ));
                     }
+                }
 
-                    // Update animation speed
-                    if new_config.animation_speed != config.animation_speed {
-                        state.animation_speed = new_config.animation_speed;
-                        if !quiet && new_config.animation_speed > 0.0 {
-                            messages.push(format!(
-                                "[{}] Animation speed: {:.3}",
-                                get_timestamp(),
-                                new_config.animation_speed
-                            ));
+                // Update strobe rate
+                if new_config.strobe_rate_hz != config.strobe_rate_hz {
+                    state.strobe_rate_hz = new_config.strobe_rate_hz;
+                    // Also validate strobe_duration_ms doesn't exceed new cycle time
+                    if new_config.strobe_rate_hz > 0.0 {
+                        let max_duration = 1000.0 / new_config.strobe_rate_hz;
+                        if state.strobe_duration_ms > max_duration {
+                            state.strobe_duration_ms = max_duration;
                         }
                     }
-
-                    // Update animation speed scaling
-                    if new_config.scale_animation_speed != config.scale_animation_speed {
-                        state.scale_animation_speed = new_config.scale_animation_speed;
-                        if !quiet {
-                            messages.push(format!(
-                                "[{}] Animation speed scaling: {}",
-                                get_timestamp(),
-                                if new_config.scale_animation_speed {
-                                    "enabled (scales with bandwidth)"
-                                } else {
-                                    "disabled (constant speed)"
-                                }
-                            ));
-                        }
+                    if !quiet {
+                        messages.push(format!(
+                            "[{}] Strobe rate updated to: {:.1} Hz",
+                            get_timestamp(),
+                            new_config.strobe_rate_hz
+                        ));
                     }
+                }
 
-                    // Update TX animation direction
-                    if new_config.tx_animation_direction != config.tx_animation_direction {
-                        state.tx_animation_direction = new_config.tx_animation_direction.clone();
-                        if !quiet {
-                            messages.push(format!(
-                                "[{}] TX animation direction: {}",
-                                get_timestamp(),
-                                new_config.tx_animation_direction
-                            ));
-                        }
+                // Update strobe duration
+                if new_config.strobe_duration_ms != config.strobe_duration_ms {
+                    state.strobe_duration_ms = new_config.strobe_duration_ms;
+                    if !quiet {
+                        messages.push(format!(
+                            "[{}] Strobe duration updated to: {:.0} ms",
+                            get_timestamp(),
+                            new_config.strobe_duration_ms
+                        ));
                     }
+                }
 
-                    // Update RX animation direction
-                    if new_config.rx_animation_direction != config.rx_animation_direction {
-                        state.rx_animation_direction = new_config.rx_animation_direction.clone();
-                        if !quiet {
-                            messages.push(format!(
-                                "[{}] RX animation direction: {}",
-                                get_timestamp(),
-                                new_config.rx_animation_direction
-                            ));
-                        }
+                // Update strobe color
+                if new_config.strobe_color != config.strobe_color {
+                    state.strobe_color = new_config.strobe_color.clone();
+                    if !quiet {
+                        messages.push(format!(
+                            "[{}] Strobe color updated to: {}",
+                            get_timestamp(),
+                            new_config.strobe_color
+                        ));
                     }
+                }
 
-                    // Update interpolation time
-                    if new_config.interpolation_time_ms != config.interpolation_time_ms {
-                        state.interpolation_time_ms = new_config.interpolation_time_ms;
-                        if !quiet {
-                            messages.push(format!(
-                                "[{}] Interpolation time: {} ms",
-                                get_timestamp(),
-                                new_config.interpolation_time_ms
-                            ));
-                        }
+                // Update animation speed
+                if new_config.animation_speed != config.animation_speed {
+                    state.animation_speed = new_config.animation_speed;
+                    if !quiet && new_config.animation_speed > 0.0 {
+                        messages.push(format!(
+                            "[{}] Animation speed: {:.3}",
+                            get_timestamp(),
+                            new_config.animation_speed
+                        ));
                     }
+                }
 
-                    // Update enable interpolation
-                    if new_config.enable_interpolation != config.enable_interpolation {
-                        state.enable_interpolation = new_config.enable_interpolation;
-                        if !quiet {
-                            messages.push(format!(
-                                "[{}] Interpolation: {}",
-                                get_timestamp(),
-                                if new_config.enable_interpolation { "enabled" } else { "disabled" }
-                            ));
-                        }
+                // Update animation speed scaling
+                if new_config.scale_animation_speed != config.scale_animation_speed {
+                    state.scale_animation_speed = new_config.scale_animation_speed;
+                    if !quiet {
+                        messages.push(format!(
+                            "[{}] Animation speed scaling: {}",
+                            get_timestamp(),
+                            if new_config.scale_animation_speed {
+                                "enabled (scales with bandwidth)"
+                            } else {
+                                "disabled (constant speed)"
+                            }
+                        ));
                     }
+                }
 
-                    // Update interpolation
-                    if new_config.interpolation != config.interpolation {
-                        let interpolation_mode = match new_config.interpolation.to_lowercase().as_str() {
-                            "basis" => InterpolationMode::Basis,
-                            "catmullrom" | "catmull-rom" => InterpolationMode::CatmullRom,
-                            _ => InterpolationMode::Linear,
-                        };
-                        state.interpolation_mode = interpolation_mode;
-                        state.generation += 1;
-                        if !quiet {
-                            messages.push(format!(
-                                "[{}] Interpolation updated to: {}",
-                                get_timestamp(),
-                                new_config.interpolation
-                            ));
-                        }
+                // Update TX animation direction
+                if new_config.tx_animation_direction != config.tx_animation_direction {
+                    state.tx_animation_direction = new_config.tx_animation_direction.clone();
+                    if !quiet {
+                        messages.push(format!(
+                            "[{}] TX animation direction: {}",
+                            get_timestamp(),
+                            new_config.tx_animation_direction
+                        ));
                     }
+                }
 
-                    // Update gradient mode
-                    if new_config.use_gradient != config.use_gradient {
-                        state.use_gradient = new_config.use_gradient;
-                        state.generation += 1;
-                        if !quiet {
-                            messages.push(format!(
-                                "[{}] Gradient mode: {}",
-                                get_timestamp(),
-                                if new_config.use_gradient {
-                                    "enabled (smooth gradients)"
-                                } else {
-                                    "disabled (hard segments)"
-                                }
-                            ));
-                        }
+                // Update RX animation direction
+                if new_config.rx_animation_direction != config.rx_animation_direction {
+                    state.rx_animation_direction = new_config.rx_animation_direction.clone();
+                    if !quiet {
+                        messages.push(format!(
+                            "[{}] RX animation direction: {}",
+                            get_timestamp(),
+                            new_config.rx_animation_direction
+                        ));
                     }
+                }
 
-                    // Update intensity colors mode
-                    if new_config.intensity_colors != config.intensity_colors {
-                        state.intensity_colors = new_config.intensity_colors;
-                        state.generation += 1;
-                        if !quiet {
-                            messages.push(format!(
-                                "[{}] Intensity colors: {}",
-                                get_timestamp(),
-                                if new_config.intensity_colors {
-                                    "enabled (level-based color)"
-                                } else {
-                                    "disabled (spatial gradient)"
-                                }
-                            ));
-                        }
+                // Update interpolation time
+                if new_config.interpolation_time_ms != config.interpolation_time_ms {
+                    state.interpolation_time_ms = new_config.interpolation_time_ms;
+                    if !quiet {
+                        messages.push(format!(
+                            "[{}] Interpolation time: {} ms",
+                            get_timestamp(),
+                            new_config.interpolation_time_ms
+                        ));
                     }
+                }
 
-                    // Update FPS
-                    if new_config.fps != config.fps {
-                        state.fps = new_config.fps;
-                        if !quiet {
-                            messages.push(format!("[{}] FPS updated to: {}", get_timestamp(), new_config.fps));
-                        }
+                // Update idle animation settings
+                let idle = IdleSettings::from_config(&new_config);
+                if idle != state.idle {
+                    state.idle = idle;
+                    if !quiet {
+                        messages.push(format!(
+                            "[{}] Idle animation: {}",
+                            get_timestamp(),
+                            if new_config.idle_enabled { new_config.idle_animation.as_str() } else { "disabled" }
+                        ));
                     }
+                }
 
-                    // Update global brightness
-                    if new_config.global_brightness != config.global_brightness {
-                        state.global_brightness = new_config.global_brightness;
-                        if !quiet {
-                            messages.push(format!("[{}] Global brightness updated to: {:.0}%", get_timestamp(), new_config.global_brightness * 100.0));
-                        }
+                // Switch between the meter and the dashboard, or follow matrix changes
+                let dashboard = renderer::dashboard_layout(&new_config);
+                if dashboard != state.dashboard {
+                    state.dashboard = dashboard;
+                    if !quiet {
+                        messages.push(format!(
+                            "[{}] Layout: {}",
+                            get_timestamp(),
+                            if state.dashboard.is_some() { "dashboard" } else { "meter" }
+                        ));
                     }
                 }
 
-                // Check if mode changed - if so, exit bandwidth mode to allow mode switch
-                if new_config.mode != "bandwidth" {
-                    println!("\n🔄 Mode changed to '{}', exiting Bandwidth mode...", new_config.mode);
-                    // Signal render thread to shut down
-                    shutdown.store(true, Ordering::Relaxed);
-                    // Give render thread a moment to exit cleanly
-                    thread::sleep(Duration::from_millis(100));
-                    // Clean up terminal
-                    terminal.show_cursor()?;
-                    disable_raw_mode()?;
-                    terminal.backend_mut().execute(LeaveAlternateScreen)?;
-                    // Exit bandwidth mode and continue mode loop
-                    println!("\n🔄 Bandwidth mode exited, checking for mode change...");
-                    continue 'mode_loop;
+                // Update easing curves
+                if new_config.fill_easing != config.fill_easing || new_config.animation_easing != config.animation_easing {
+                    state.fill_easing = Easing::from_name(&new_config.fill_easing);
+                    state.animation_easing = Easing::from_name(&new_config.animation_easing);
+                    if !quiet {
+                        messages.push(format!(
+                            "[{}] Easing: fill {}, animation {}",
+                            get_timestamp(),
+                            new_config.fill_easing,
+                            new_config.animation_easing
+                        ));
+                    }
                 }
 
-                // Check if network interface changed - restart to apply
-                if new_config.interface != config.interface
-                    || new_config.ssh_host != config.ssh_host
-                    || new_config.ssh_user != config.ssh_user
-                {
-                    println!("\n🔄 Network interface settings changed, restarting bandwidth monitoring...");
-                    // Signal render thread to shut down
-                    shutdown.store(true, Ordering::Relaxed);
-                    // Give render thread a moment to exit cleanly
-                    thread::sleep(Duration::from_millis(100));
-                    // Clean up terminal
-                    terminal.show_cursor()?;
-                    disable_raw_mode()?;
-                    terminal.backend_mut().execute(LeaveAlternateScreen)?;
-                    // Exit and restart bandwidth mode with new interface settings
-                    continue 'mode_loop;
+                // Update enable interpolation
+                if new_config.enable_interpolation != config.enable_interpolation {
+                    state.enable_interpolation = new_config.enable_interpolation;
+                    if !quiet {
+                        messages.push(format!(
+                            "[{}] Interpolation: {}",
+                            get_timestamp(),
+                            if new_config.enable_interpolation { "enabled" } else { "disabled" }
+                        ));
+                    }
                 }
 
-                // Check if total_leds or device config changed - restart to apply
-                let devices_changed = new_config.wled_devices.len() != config.wled_devices.len() ||
-                    new_config.wled_devices.iter().zip(config.wled_devices.iter()).any(|(new, old)| {
-                        new.ip != old.ip ||
-                        new.led_offset != old.led_offset ||
-                        new.led_count != old.led_count ||
-                        new.enabled != old.enabled
-                    }) ||
-                    new_config.multi_device_send_parallel != config.multi_device_send_parallel ||
-                    new_config.multi_device_fail_fast != config.multi_device_fail_fast;
-
-                if new_config.total_leds != config.total_leds || devices_changed {
-                    println!("\n🔄 LED count or device config changed, restarting bandwidth mode...");
-                    // Signal render thread to shut down
-                    shutdown.store(true, Ordering::Relaxed);
-                    // Give render thread a moment to exit cleanly
-                    thread::sleep(Duration::from_millis(100));
-                    // Clean up terminal
-                    terminal.show_cursor()?;
-                    disable_raw_mode()?;
-                    terminal.backend_mut().execute(LeaveAlternateScreen)?;
-                    // Exit and restart bandwidth mode with new settings
-                    continue 'mode_loop;
+                // Update interpolation
+                if new_config.interpolation != config.interpolation {
+                    let interpolation_mode = match new_config.interpolation.to_lowercase().as_str() {
+                        "basis" => InterpolationMode::Basis,
+                        "catmullrom" | "catmull-rom" => InterpolationMode::CatmullRom,
+                        _ => InterpolationMode::Linear,
+                    };
+                    state.interpolation_mode = interpolation_mode;
+                    state.generation += 1;
+                    if !quiet {
+                        messages.push(format!(
+                            "[{}] Interpolation updated to: {}",
+                            get_timestamp(),
+                            new_config.interpolation
+                        ));
+                    }
                 }
 
-                // Check if WLED IP changed - just show message (DDP reconnects automatically)
-                if new_config.wled_ip != config.wled_ip {
+                // Update gradient color space
+                if new_config.color_space != config.color_space {
+                    state.color_space = ColorSpace::from_name(&new_config.color_space);
+                    state.generation += 1;
                     if !quiet {
-                        messages.push(format!("[{}] WLED IP changed to {}", get_timestamp(), new_config.wled_ip));
+                        messages.push(format!(
+                            "[{}] Gradient color space updated to: {}",
+                            get_timestamp(),
+                            new_config.color_space
+                        ));
                     }
                 }
 
-                // Update test mode - immediately update bandwidth values and tracking vars
-                if new_config.test_tx != config.test_tx
-                    || new_config.test_rx != config.test_rx
-                    || new_config.test_tx_percent != config.test_tx_percent
-                    || new_config.test_rx_percent != config.test_rx_percent {
-
-                    // Calculate test bandwidth values
-                    let test_rx_kbps = if new_config.test_rx {
-                        new_config.max_gbps * 1000.0 * 1000.0 * (new_config.test_rx_percent / 100.0)
-                    } else {
-                        0.0
-                    };
-
-                    let test_tx_kbps = if new_config.test_tx {
-                        new_config.max_gbps * 1000.0 * 1000.0 * (new_config.test_tx_percent / 100.0)
-                    } else {
-                        0.0
-                    };
-
-                    // Update shared state only if test mode is enabled
-                    let mut state = shared_state.lock().unwrap();
+                // Update gradient mode
+                if new_config.use_gradient != config.use_gradient {
+                    state.use_gradient = new_config.use_gradient;
+                    state.generation += 1;
+                    if !quiet {
+                        messages.push(format!(
+                            "[{}] Gradient mode: {}",
+                            get_timestamp(),
+                            if new_config.use_gradient {
+                                "enabled (smooth gradients)"
+                            } else {
+                                "disabled (hard segments)"
+                            }
+                        ));
+                    }
+                }
 
-                    // Update test mode flag and target values
-                    state.test_mode = new_config.test_tx || new_config.test_rx;
+                // Update intensity colors mode
+                if new_config.intensity_colors != config.intensity_colors {
+                    state.intensity_colors = new_config.intensity_colors;
+                    state.generation += 1;
+                    if !quiet {
+                        messages.push(format!(
+                            "[{}] Intensity colors: {}",
+                            get_timestamp(),
+                            if new_config.intensity_colors {
+                                "enabled (level-based color)"
+                            } else {
+                                "disabled (spatial gradient)"
+                            }
+                        ));
+                    }
+                }
 
-                    if new_config.test_rx {
-                        state.current_rx_kbps = test_rx_kbps;
+                // Update FPS
+                if new_config.fps != config.fps {
+                    state.fps = new_config.fps;
+                    if !quiet {
+                        messages.push(format!("[{}] FPS updated to: {}", get_timestamp(), new_config.fps));
                     }
+                }
 
-                    if new_config.test_tx {
-                        state.current_tx_kbps = test_tx_kbps;
+                // Update global brightness
+                if new_config.global_brightness != config.global_brightness {
+                    state.global_brightness = new_config.global_brightness;
+                    if !quiet {
+                        messages.push(format!("[{}] Global brightness updated to: {:.0}%", get_timestamp(), new_config.global_brightness * 100.0));
                     }
+                }
+            }
 
-                    drop(state);
+            // Check if mode changed (or a restart was requested) - if so, exit bandwidth mode to allow mode switch
+            if new_config.mode != "bandwidth" || system::restart_requested() {
+                println!("\n🔄 Mode changed to '{}', exiting Bandwidth mode...", new_config.mode);
+                // Signal render thread to shut down
+                shutdown.store(true, Ordering::Relaxed);
+                // Give render thread a moment to exit cleanly
+                thread::sleep(Duration::from_millis(100));
+                // Clean up terminal
+                terminal.show_cursor()?;
+                disable_raw_mode()?;
+                terminal.backend_mut().execute(LeaveAlternateScreen)?;
+                // Exit bandwidth mode and continue mode loop
+                println!("\n🔄 Bandwidth mode exited, checking for mode change...");
+                continue 'mode_loop;
+            }
 
-                    if !quiet {
-                        if new_config.test_tx != config.test_tx {
-                            messages.push(format!(
-                                "[{}] Test TX: {}",
-                                get_timestamp(),
-                                if new_config.test_tx { "enabled" } else { "disabled" }
-                            ));
-                        }
-                        if new_config.test_rx != config.test_rx {
-                            messages.push(format!(
-                                "[{}] Test RX: {}",
-                                get_timestamp(),
-                                if new_config.test_rx { "enabled" } else { "disabled" }
-                            ));
-                        }
-                        if new_config.test_tx_percent != config.test_tx_percent && new_config.test_tx {
-                            messages.push(format!(
-                                "[{}] Test TX utilization: {:.0}%",
-                                get_timestamp(),
-                                new_config.test_tx_percent
-                            ));
+            // Check if network interface changed - restart to apply
+            if new_config.interface != config.interface
+                || new_config.ssh_host != config.ssh_host
+                || new_config.ssh_user != config.ssh_user
+                || new_config.agent_hosts != config.agent_hosts
+                || new_config.agent_token != config.agent_token
+            {
+                println!("\n🔄 Network interface settings changed, restarting bandwidth monitoring...");
+                // Signal render thread to shut down
+                shutdown.store(true, Ordering::Relaxed);
+                // Give render thread a moment to exit cleanly
+                thread::sleep(Duration::from_millis(100));
+                // Clean up terminal
+                terminal.show_cursor()?;
+                disable_raw_mode()?;
+                terminal.backend_mut().execute(LeaveAlternateScreen)?;
+                // Exit and restart bandwidth mode with new interface settings
+                continue 'mode_loop;
+            }
+
+            // Devices added, removed, enabled or moved within the same strip switch over in place;
+            // a new LED count or output setup (or a failed switch-over) restarts the mode to apply
+            let mut devices_changed = delta.changed_any(config::DEVICE_CONFIG_KEYS);
+            if devices_changed && new_config.total_leds == config.total_leds && !delta.changed_any(&["wled_ip", "multi_device_enabled"]) {
+                let result = device_manager.lock().unwrap().reconfigure(MultiDeviceConfig::from_config(&new_config));
+                match result {
+                    Ok(()) => {
+                        devices_changed = false;
+                        if !quiet {
+                            messages.push(format!("[{}] Devices updated ({} connected)", get_timestamp(), device_manager.lock().unwrap().device_count()));
                         }
-                        if new_config.test_rx_percent != config.test_rx_percent && new_config.test_rx {
-                            messages.push(format!(
-                                "[{}] Test RX utilization: {:.0}%",
-                                get_timestamp(),
-                                new_config.test_rx_percent
-                            ));
+                    }
+                    Err(e) => {
+                        if !quiet {
+                            messages.push(format!("[{}] Device update failed ({}), restarting", get_timestamp(), e));
                         }
                     }
                 }
+            }
 
-                // Update config for future comparisons
-                config = new_config;
+            if new_config.total_leds != config.total_leds || devices_changed {
+                println!("\n🔄 LED count or device config changed, restarting bandwidth mode...");
+                // Signal render thread to shut down
+                shutdown.store(true, Ordering::Relaxed);
+                // Give render thread a moment to exit cleanly
+                thread::sleep(Duration::from_millis(100));
+                // Clean up terminal
+                terminal.show_cursor()?;
+                disable_raw_mode()?;
+                terminal.backend_mut().execute(LeaveAlternateScreen)?;
+                // Exit and restart bandwidth mode with new settings
+                continue 'mode_loop;
+            }
 
-                needs_render = true;
+            // Check if WLED IP changed - just show message (DDP reconnects automatically)
+            if new_config.wled_ip != config.wled_ip {
+                if !quiet {
+                    messages.push(format!("[{}] WLED IP changed to {}", get_timestamp(), new_config.wled_ip));
+                }
+            }
+
+            // Update test mode - immediately update bandwidth values and tracking vars
+            if new_config.test_tx != config.test_tx
+                || new_config.test_rx != config.test_rx
+                || new_config.test_tx_percent != config.test_tx_percent
+                || new_config.test_rx_percent != config.test_rx_percent {
+
+                // Calculate test bandwidth values
+                let test_rx_kbps = if new_config.test_rx {
+                    new_config.max_gbps * 1000.0 * 1000.0 * (new_config.test_rx_percent / 100.0)
+                } else {
+                    0.0
+                };
+
+                let test_tx_kbps = if new_config.test_tx {
+                    new_config.max_gbps * 1000.0 * 1000.0 * (new_config.test_tx_percent / 100.0)
+                } else {
+                    0.0
+                };
+
+                // Update shared state only if test mode is enabled
+                let mut state = shared_state.write();
+
+                // Update test mode flag and target values
+                state.test_mode = new_config.test_tx || new_config.test_rx;
+
+                if new_config.test_rx {
+                    state.current_rx_kbps = test_rx_kbps;
+                }
+
+                if new_config.test_tx {
+                    state.current_tx_kbps = test_tx_kbps;
+                }
+
+                drop(state);
+
+                if !quiet {
+                    if new_config.test_tx != config.test_tx {
+                        messages.push(format!(
+                            "[{}] Test TX: {}",
+                            get_timestamp(),
+                            if new_config.test_tx { "enabled" } else { "disabled" }
+                        ));
+                    }
+                    if new_config.test_rx != config.test_rx {
+                        messages.push(format!(
+                            "[{}] Test RX: {}",
+                            get_timestamp(),
+                            if new_config.test_rx { "enabled" } else { "disabled" }
+                        ));
+                    }
+                    if new_config.test_tx_percent != config.test_tx_percent && new_config.test_tx {
+                        messages.push(format!(
+                            "[{}] Test TX utilization: {:.0}%",
+                            get_timestamp(),
+                            new_config.test_tx_percent
+                        ));
+                    }
+                    if new_config.test_rx_percent != config.test_rx_percent && new_config.test_rx {
+                        messages.push(format!(
+                            "[{}] Test RX utilization: {:.0}%",
+                            get_timestamp(),
+                            new_config.test_rx_percent
+                        ));
+                    }
+                }
             }
+
+            if new_config.bandwidth_history != config.bandwidth_history
+                || new_config.bandwidth_history_hours != config.bandwidth_history_hours
+            {
+                drop(history.take());  // Closed before a resize reopens it
+                history = open_history(&new_config, &mut messages);
+            }
+
+            // Update config for future comparisons
+            config = new_config;
+
+            needs_render = true;
         }
 
         // Render only when something changed
         if needs_render {
             // Build interface display string
-            let interface_display = if !config.ssh_host.is_empty() {
+            let interface_display = if replaying {
+                format!("Replay {} UTC ({}x){}",
+                    bandwidth_history::clock(replay_progress.position_ms.load(Ordering::Relaxed)), replay_speed,
+                    if replay_progress.done.load(Ordering::Relaxed) { " - finished" } else { "" })
+            } else if agent_mode {
+                format!("{}/{} agents up", agents_up, agent_count)
+            } else if !config.ssh_host.is_empty() {
                 format!("{} (SSH: {}@{})", config.interface,
                     if config.ssh_user.is_empty() { "user" } else { &config.ssh_user },
                     config.ssh_host)
             } else {
                 config.interface.clone()
             };
+            let link_display = match link_seen {
+                (remote_link::LinkState::Local, _) => String::new(),
+                (remote_link::LinkState::Connected, _) => " | Link: 🟢 up".to_string(),
+                (remote_link::LinkState::Reconnecting, attempt) => format!(" | Link: 🔴 reconnecting (attempt {})", attempt),
+            };
 
             terminal.draw(|f| {
                 // Three-section layout: Header, Main Content, Footer
                 let chunks = Layout::default()
                     .direction(Direction::Vertical)
-                    .constraints([Constraint::Length(3), Constraint::Min(1), Constraint::Length(3)].as_ref())
+                    .constraints([Constraint::Length(3), Constraint::Min(1), Constraint::Length(4)].as_ref())
                     .split(f.size());
 
                 // Header - show mode, sub-mode, and interface
@@ -5327,7 +6120,7 @@ Player Colors:\n  {}",
                 } else {
                     "Normal"
                 };
-                let header_text = format!("📊 Bandwidth Mode | Sub-mode: {} | Interface: {}", sub_mode, interface_display);
+                let header_text = format!("📊 Bandwidth Mode | Sub-mode: {} | Interface: {}{}", sub_mode, interface_display, link_display);
                 let header = Paragraph::new(header_text)
                     .block(Block::default().borders(Borders::ALL));
                 f.render_widget(header, chunks[0]);
@@ -5360,8 +6153,9 @@ Player Colors:\n  {}",
 
                 // Footer - show monitoring source and controls
                 let footer_text = format!(
-                    "Source: Network [{}] | WLED: {} | LEDs: {} | FPS: {:.0} | Delay: {:.1}ms | Press 'i' for config, 'q' or Ctrl+C to quit",
-                    interface_display, config.wled_ip, config.total_leds, config.fps, config.ddp_delay_ms
+                    "Source: Network [{}] | WLED: {} | LEDs: {} | FPS: {:.0} | Delay: {:.1}ms | Press 'i' for config, 'q' or Ctrl+C to quit\n{}",
+                    interface_display, config.wled_ip, config.total_leds, config.fps, config.ddp_delay_ms,
+                    live_stats::profile_line()
                 );
                 let footer = Paragraph::new(footer_text)
                     .block(Block::default().borders(Borders::ALL));
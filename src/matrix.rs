@@ -0,0 +1,200 @@
+// Matrix Module - Shared 2D canvas for matrix modes, mapping canvas pixels to LED indices across tiled panels
+use crate::config::{BandwidthConfig, MatrixPanelConfig};
+
+/// Lookup from canvas (x, y) to LED index, built once from the panel layout
+#[derive(Debug, Clone, PartialEq)]
+pub struct MatrixLayout {
+    width: usize,
+    height: usize,
+    leds: Vec<Option<usize>>,  // Row-major canvas, None where no panel covers the pixel
+    led_count: usize,
+}
+
+impl MatrixLayout {
+    /// One width x height panel covering the whole canvas
+    pub fn single(width: usize, height: usize, serpentine: bool) -> Self {
+        Self::tiled(&[MatrixPanelConfig {
+            x: 0,
+            y: 0,
+            width,
+            height,
+            rotation: 0,
+            serpentine,
+        }])
+    }
+
+    /// Compose panels into one canvas - each panel's LEDs follow on from the previous panel's
+    pub fn tiled(panels: &[MatrixPanelConfig]) -> Self {
+        let rotated_size = |panel: &MatrixPanelConfig| match panel.rotation {
+            90 | 270 => (panel.height, panel.width),
+            _ => (panel.width, panel.height),
+        };
+        let width = panels.iter().map(|p| p.x + rotated_size(p).0).max().unwrap_or(0);
+        let height = panels.iter().map(|p| p.y + rotated_size(p).1).max().unwrap_or(0);
+
+        let mut leds = vec![None; width * height];
+        let mut offset = 0;
+        for panel in panels {
+            let (w, h) = (panel.width, panel.height);
+            for i in 0..w * h {
+                // Position on the panel as wired
+                let ly = i / w;
+                let lx = if panel.serpentine && ly % 2 == 1 { w - 1 - i % w } else { i % w };
+
+                let (cx, cy) = rotate(lx, ly, w, h, panel.rotation);
+                leds[(panel.y + cy) * width + panel.x + cx] = Some(offset + i);
+            }
+            offset += w * h;
+        }
+
+        MatrixLayout { width, height, leds, led_count: offset }
+    }
+
+    /// Turn the whole canvas to match how the matrix is mounted - rotated clockwise, then mirrored
+    pub fn oriented(self, rotation: u32, flip_h: bool, flip_v: bool) -> Self {
+        if rotation == 0 && !flip_h && !flip_v {
+            return self;
+        }
+
+        let (w, h) = (self.width, self.height);
+        let (width, height) = match rotation {
+            90 | 270 => (h, w),
+            _ => (w, h),
+        };
+        let mut leds = vec![None; width * height];
+        for y in 0..h {
+            for x in 0..w {
+                let (mut cx, mut cy) = rotate(x, y, w, h, rotation);
+                if flip_h {
+                    cx = width - 1 - cx;
+                }
+                if flip_v {
+                    cy = height - 1 - cy;
+                }
+                leds[cy * width + cx] = self.leds[y * w + x];
+            }
+        }
+
+        MatrixLayout { width, height, leds, led_count: self.led_count }
+    }
+
+    /// Layout for a matrix mode - the configured panels if any, otherwise the mode's own
+    /// width x height matrix with its usual wiring - turned to the configured orientation
+    pub fn for_mode(config: &BandwidthConfig, width: usize, height: usize, serpentine: bool) -> Self {
+        let layout = if config.matrix_panels.is_empty() {
+            Self::single(width, height, serpentine)
+        } else {
+            Self::tiled(&config.matrix_panels)
+        };
+        layout.oriented(config.matrix.rotation, config.matrix.flip_h, config.matrix.flip_v)
+    }
+
+    /// Whether the config changes the mapping from a plain single matrix (panels, rotation, or flips)
+    pub fn is_custom(config: &BandwidthConfig) -> bool {
+        !config.matrix_panels.is_empty()
+            || config.matrix.rotation != 0
+            || config.matrix.flip_h
+            || config.matrix.flip_v
+    }
+
+    /// Canvas width in pixels
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// Canvas height in pixels
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// LEDs across all panels
+    pub fn led_count(&self) -> usize {
+        self.led_count
+    }
+
+    /// LED index for a canvas pixel (row 0 at the top), None if off the canvas or between panels
+    pub fn index(&self, x: usize, y: usize) -> Option<usize> {
+        if x >= self.width || y >= self.height {
+            return None;
+        }
+        self.leds[y * self.width + x]
+    }
+}
+
+// Where (x, y) on a w x h grid lands after rotating it clockwise by `rotation` degrees
+fn rotate(x: usize, y: usize, w: usize, h: usize, rotation: u32) -> (usize, usize) {
+    match rotation {
+        90 => (h - 1 - y, x),
+        180 => (w - 1 - x, h - 1 - y),
+        270 => (y, w - 1 - x),
+        _ => (x, y),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn panel(x: usize, y: usize, rotation: u32, serpentine: bool) -> MatrixPanelConfig {
+        MatrixPanelConfig { x, y, width: 3, height: 2, rotation, serpentine }
+    }
+
+    #[test]
+    fn test_single_wiring() {
+        let progressive = MatrixLayout::single(3, 2, false);
+        assert_eq!((progressive.width(), progressive.height(), progressive.led_count()), (3, 2, 6));
+        assert_eq!(progressive.index(0, 1), Some(3));
+        assert_eq!(progressive.index(3, 0), None);
+
+        // Serpentine reverses odd rows
+        let serpentine = MatrixLayout::single(3, 2, true);
+        assert_eq!(serpentine.index(0, 1), Some(5));
+        assert_eq!(serpentine.index(2, 1), Some(3));
+    }
+
+    #[test]
+    fn test_rotation() {
+        // 3x2 panel rotated 90 degrees clockwise is 2 wide, 3 tall - LED 0 lands top right
+        let layout = MatrixLayout::tiled(&[panel(0, 0, 90, false)]);
+        assert_eq!((layout.width(), layout.height()), (2, 3));
+        assert_eq!(layout.index(1, 0), Some(0));
+        assert_eq!(layout.index(1, 2), Some(2));
+        assert_eq!(layout.index(0, 0), Some(3));
+
+        let layout = MatrixLayout::tiled(&[panel(0, 0, 180, false)]);
+        assert_eq!(layout.index(2, 1), Some(0));
+        assert_eq!(layout.index(0, 0), Some(5));
+
+        let layout = MatrixLayout::tiled(&[panel(0, 0, 270, false)]);
+        assert_eq!(layout.index(0, 2), Some(0));
+        assert_eq!(layout.index(1, 0), Some(5));
+    }
+
+    #[test]
+    fn test_orientation() {
+        // Rotating the canvas matches mounting a rotated panel
+        let rotated = MatrixLayout::single(3, 2, true).oriented(90, false, false);
+        assert_eq!(rotated, MatrixLayout::tiled(&[panel(0, 0, 90, true)]));
+
+        // Flips mirror the canvas
+        let flipped = MatrixLayout::single(3, 2, false).oriented(0, true, false);
+        assert_eq!(flipped.index(2, 0), Some(0));
+        let flipped = MatrixLayout::single(3, 2, false).oriented(0, false, true);
+        assert_eq!(flipped.index(0, 1), Some(0));
+        let both = MatrixLayout::single(3, 2, false).oriented(0, true, true);
+        assert_eq!(both, MatrixLayout::single(3, 2, false).oriented(180, false, false));
+    }
+
+    #[test]
+    fn test_tiled_panels_chain() {
+        // Two panels side by side, with a gap row under the shorter one
+        let layout = MatrixLayout::tiled(&[
+            panel(0, 0, 0, false),
+            MatrixPanelConfig { x: 3, y: 0, width: 2, height: 3, rotation: 0, serpentine: false },
+        ]);
+        assert_eq!((layout.width(), layout.height(), layout.led_count()), (5, 3, 12));
+        assert_eq!(layout.index(3, 0), Some(6));
+        assert_eq!(layout.index(4, 2), Some(11));
+        assert_eq!(layout.index(0, 2), None);
+    }
+}
@@ -2,8 +2,11 @@
 use anyhow::{anyhow, Result};
 use midir::{MidiInput, MidiInputConnection};
 use std::collections::HashMap;
+use std::ops::Range;
 use std::sync::{Arc, Mutex};
 
+use crate::config::{MidiZoneConfig, WLEDDeviceConfig};
+
 /// RGB color representation
 #[derive(Clone, Copy, Debug)]
 pub struct RGB {
@@ -45,8 +48,16 @@ impl NoteState {
 
     /// Get all active notes with their channels
     pub fn get_active_notes(&self) -> Vec<(u8, u8, u8)> {
+        let mut active = Vec::new();
+        self.active_notes_into(&mut active);
+        active
+    }
+
+    /// Fill `active` with the active notes, reusing its allocation (for per-frame use)
+    pub fn active_notes_into(&self, active: &mut Vec<(u8, u8, u8)>) {
         let notes = self.active_notes.lock().unwrap();
-        notes.iter().map(|((ch, n), v)| (*ch, *n, *v)).collect()
+        active.clear();
+        active.extend(notes.iter().map(|((ch, n), v)| (*ch, *n, *v)));
     }
 
     /// Get count of active notes
@@ -254,14 +265,12 @@ pub fn note_to_led_range(note: u8, leds_per_note: usize, start_offset: usize) ->
 
 /// Get all LED indices for a note in 1-to-1 mapping mode
 /// Middle C (note 60) is at the center, note pattern repeats every 128 LEDs
-/// Returns vector of all LED positions that map to this note
-pub fn note_to_leds_one_to_one(note: u8, total_leds: usize) -> Vec<usize> {
+/// Returns an iterator over all LED positions that map to this note
+pub fn note_to_leds_one_to_one(note: u8, total_leds: usize) -> impl Iterator<Item = usize> {
     const MIDDLE_C: i32 = 60;
     let middle_led = (total_leds / 2) as i32;
     let base_offset = note as i32 - MIDDLE_C;
 
-    let mut leds = Vec::new();
-
     // The note pattern repeats every 128 LEDs
     // Find the first occurrence of this note on the strip (in the 0-127 range)
     let mut first_led = middle_led + base_offset;
@@ -274,14 +283,8 @@ pub fn note_to_leds_one_to_one(note: u8, total_leds: usize) -> Vec<usize> {
         first_led -= 128;
     }
 
-    // Now step through the strip, adding every 128th LED
-    let mut current_led = first_led;
-    while current_led < total_leds as i32 {
-        leds.push(current_led as usize);
-        current_led += 128;
-    }
-
-    leds
+    // Now step through the strip, every 128th LED
+    (first_led as usize..total_leds).step_by(128)
 }
 
 /// Get LED index for a note in channel mode
@@ -298,6 +301,27 @@ pub fn channel_and_note_to_led(channel: u8, note: u8, total_leds: usize) -> Opti
     }
 }
 
+/// LED ranges the MIDI zones light, as (channel 0-15, LEDs) - a zone with devices gives one range per device,
+/// so each device shows the channel's notes across its whole length. Devices not in the device list are skipped
+pub fn zone_ranges(zones: &[MidiZoneConfig], devices: &[WLEDDeviceConfig], total_leds: usize) -> Vec<(u8, Range<usize>)> {
+    let mut ranges = Vec::new();
+    for zone in zones {
+        let channel = zone.channel.max(1).min(16) - 1;
+        if zone.devices.is_empty() {
+            ranges.push((channel, zone.led_offset..zone.led_offset + zone.led_count));
+        } else {
+            for device in devices.iter().filter(|d| zone.devices.contains(&d.ip)) {
+                ranges.push((channel, device.led_offset..device.led_offset + device.led_count));
+            }
+        }
+    }
+    // Clip to the strip, dropping ranges that fall outside it
+    ranges.into_iter()
+        .map(|(channel, leds)| (channel, leds.start.min(total_leds)..leds.end.min(total_leds)))
+        .filter(|(_, leds)| !leds.is_empty())
+        .collect()
+}
+
 /// List all available MIDI input ports
 /// Returns a vector of port names
 pub fn list_midi_ports() -> Result<Vec<String>> {
@@ -387,4 +411,25 @@ mod tests {
         let color = note_to_color(64);
         assert!(color.b > 200 || color.g > 200); // Should be cyan/greenish
     }
+
+    #[test]
+    fn test_note_to_leds_one_to_one() {
+        // Middle C sits at the center LED and repeats every 128 LEDs
+        let leds: Vec<usize> = note_to_leds_one_to_one(60, 300).collect();
+        assert_eq!(leds, vec![22, 150, 278]);
+    }
+
+    #[test]
+    fn test_zone_ranges() {
+        let template = crate::config::BandwidthConfig::default().wled_devices[0].clone();
+        let device = |ip: &str, led_offset, led_count| WLEDDeviceConfig { ip: ip.to_string(), led_offset, led_count, ..template.clone() };
+        let devices = [device("10.0.0.1", 0, 60), device("10.0.0.2", 60, 200), device("10.0.0.3", 260, 40)];
+        let zones = [
+            MidiZoneConfig { channel: 1, devices: vec!["10.0.0.1".to_string()], led_offset: 0, led_count: 0 },
+            MidiZoneConfig { channel: 10, devices: vec!["10.0.0.2".to_string(), "10.0.0.3".to_string(), "10.9.9.9".to_string()], led_offset: 0, led_count: 0 },
+            MidiZoneConfig { channel: 2, devices: Vec::new(), led_offset: 280, led_count: 50 },
+        ];
+        assert_eq!(zone_ranges(&zones, &devices, 300), [(0, 0..60), (9, 60..260), (9, 260..300), (1, 280..300)]);
+        assert!(zone_ranges(&zones[2..], &devices, 200).is_empty());
+    }
 }
@@ -1,40 +1,333 @@
 use anyhow::{anyhow, Result};
+use serde::Serialize;
 use std::net::UdpSocket;
-use std::sync::{Arc, Mutex};
-use std::time::{Duration, Instant};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::mpsc::{self, Receiver, SyncSender};
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 use ddp_rs::connection::DDPConnection;
 use ddp_rs::protocol::{PixelConfig, ID};
 
+use crate::adaptive_fps::AdaptiveRate;
+use crate::alert;
+use crate::ambient;
+use crate::artnet::{self, ArtnetSender};
+use crate::cli;
+use crate::config::BandwidthConfig;
+use crate::demo;
+use crate::fade;
+use crate::frame_interpolation::{self, Interpolator};
+#[cfg(feature = "hue")]
+use crate::hue;
+use crate::lifx;
+use crate::nanoleaf;
+use crate::live_stats;
+use crate::postfx::{self, PostFx};
+use crate::quiet_hours;
+use crate::recording;
+use crate::sacn::SacnSender;
+use crate::segments::{self, Placement};
+use crate::system;
+use crate::types::{white_point_scales, Rgb};
+use crate::virtual_device;
+use crate::wled_state;
+
 // WLED DDP timeout is ~1 second, so send keepalive every 500ms to be safe
 const KEEPALIVE_INTERVAL: Duration = Duration::from_millis(500);
 
+// Failover health checks - a request to the JSON API of each WLED primary that has a backup
+const HEALTH_CHECK_INTERVAL: Duration = Duration::from_secs(2);
+const HEALTH_CHECK_TIMEOUT: Duration = Duration::from_millis(500);
+const MAX_FAILOVER_EVENTS: usize = 100;
+
+// Staggered sends use this fraction of the frame interval, leaving headroom for rendering
+const STAGGER_WINDOW_FRACTION: f64 = 0.75;
+
 #[derive(Debug, Clone)]
 pub struct WLEDDevice {
     pub ip: String,
     pub led_offset: usize,
     pub led_count: usize,
     pub enabled: bool,
+    pub max_fps: f64,  // Maximum frames per second sent to this device (0 = unlimited)
+    pub led_ma: f64,  // Current draw of one LED at full white in milliamps
+    pub psu_amps: f64,  // Power supply limit in amps (0 = no current limiting)
+    pub backup_for: String,  // IP of the primary this device backs up (empty = not a backup)
+    pub reversed: bool,  // Reverse LED order within this device's range
+    pub mirrored: bool,  // Mirror the first half of the range onto the second half
+    pub protocol: String,  // "ddp" (WLED), "sacn" (E1.31) or "artnet" (pixel controllers)
+    pub universe: u16,  // First universe (sacn, artnet - within the net and sub-net)
+    pub start_channel: u16,  // DMX channel of the first LED in the first universe (sacn, artnet)
+    pub net: u8,  // Art-Net net (artnet only)
+    pub subnet: u8,  // Art-Net sub-net (artnet only)
+}
+
+/// Where a device's frames go - DDP to WLED, or E1.31 / Art-Net to pixel controllers
+pub enum DeviceOutput {
+    Ddp(DDPConnection),
+    Sacn(SacnSender),
+    Artnet(ArtnetSender),
+}
+
+impl DeviceOutput {
+    pub fn open(device: &WLEDDevice) -> Result<Self> {
+        match device.protocol.as_str() {
+            "sacn" => return Ok(DeviceOutput::Sacn(SacnSender::new(&device.ip, device.universe, device.start_channel)?)),
+            "artnet" => {
+                let port_address = artnet::port_address(device.net, device.subnet, device.universe);
+                return Ok(DeviceOutput::Artnet(ArtnetSender::new(&device.ip, port_address, device.start_channel)?));
+            }
+            _ => {}
+        }
+        let socket = UdpSocket::bind("0.0.0.0:0")?;
+        Ok(DeviceOutput::Ddp(DDPConnection::try_new(&virtual_device::ddp_address(&device.ip), PixelConfig::default(), ID::Default, socket)?))
+    }
+
+    /// Send RGB data starting `offset` bytes into the device's strip
+    pub fn write_offset(&mut self, data: &[u8], offset: u32) -> Result<usize> {
+        match self {
+            DeviceOutput::Ddp(conn) => Ok(conn.write_offset(data, offset)?),
+            DeviceOutput::Sacn(sender) => sender.send(data, offset as usize / 3),
+            DeviceOutput::Artnet(sender) => sender.send(data, offset as usize / 3),
+        }
+    }
+}
+
+/// A primary/backup switch-over, exposed via the API
+#[derive(Debug, Clone, Serialize)]
+pub struct FailoverEvent {
+    pub timestamp: u64,  // Unix seconds
+    pub primary: String,
+    pub backup: String,
+    pub failed_over: bool,  // true = routed to backup, false = restored to primary
+}
+
+/// Current failover state shared with the HTTP API
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct FailoverStatus {
+    pub active: Vec<String>,  // Primaries currently routed to their backup
+    pub events: Vec<FailoverEvent>,
+}
+
+static FAILOVER_STATUS: Mutex<FailoverStatus> = Mutex::new(FailoverStatus {
+    active: Vec::new(),
+    events: Vec::new(),
+});
+
+/// Snapshot of active failovers and recent failover events
+pub fn failover_status() -> FailoverStatus {
+    FAILOVER_STATUS.lock().map(|status| status.clone()).unwrap_or_default()
+}
+
+fn record_failover(primary: &str, backup: &str, failed_over: bool) {
+    if let Ok(mut status) = FAILOVER_STATUS.lock() {
+        status.active.retain(|ip| ip != primary);
+        if failed_over {
+            status.active.push(primary.to_string());
+        }
+
+        status.events.push(FailoverEvent {
+            timestamp: SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0),
+            primary: primary.to_string(),
+            backup: backup.to_string(),
+            failed_over,
+        });
+        if status.events.len() > MAX_FAILOVER_EVENTS {
+            status.events.remove(0);
+        }
+    }
+}
+
+/// Whether a device can be health checked - WLED answers on its JSON API, but sACN and Art-Net outputs are
+/// send-only here (an ArtPollReply comes back to port 6454, which Art-Net input may hold) and virtual devices
+/// have no web server, so those primaries are never failed over
+fn has_health_check(device: &WLEDDevice) -> bool {
+    device.protocol == "ddp" && !virtual_device::is_virtual(&device.ip)
+}
+
+/// Whether a WLED device answers on its JSON API - an open port alone could be a hung web server
+fn device_reachable(ip: &str) -> bool {
+    cli::fetch_wled_json(ip, "/json/info", HEALTH_CHECK_TIMEOUT).is_some()
+}
+
+/// Route a primary's frames to its backup while it's unreachable, and back once it answers again
+fn update_health(primary_ip: &str, backup_ip: &str, healthy: &AtomicBool, reachable: bool) {
+    if healthy.swap(reachable, Ordering::Relaxed) != reachable {
+        record_failover(primary_ip, backup_ip, !reachable);
+    }
+}
+
+/// Output color calibration - per-channel correction, gamma, and channel order
+/// Applied to every frame right before it is split across devices
+#[derive(Debug, Clone)]
+pub struct ColorCorrection {
+    scales: [f32; 3],     // Per-channel correction (0.0-1.0)
+    gamma: f32,
+    lut: [[f32; 256]; 3],  // Per-channel curve (correction + gamma) for 8-bit input, left unrounded
+    order: [usize; 3],    // Source channel index for each output byte
+}
+
+/// Round a 0.0-255.0 channel value to a byte - the one place the output pipeline loses precision
+pub fn quantize(value: f32) -> u8 {
+    value.round().clamp(0.0, 255.0) as u8
+}
+
+impl ColorCorrection {
+    pub fn new(gamma: f64, correction: Rgb, color_order: &str) -> Self {
+        let scales = [
+            correction.r as f64 / 255.0,
+            correction.g as f64 / 255.0,
+            correction.b as f64 / 255.0,
+        ];
+
+        let mut lut = [[0.0f32; 256]; 3];
+        for (channel, scale) in scales.iter().enumerate() {
+            for value in 0..256 {
+                let normalized = (value as f64 / 255.0) * scale;
+                lut[channel][value] = (normalized.powf(gamma) * 255.0) as f32;
+            }
+        }
+
+        // Each letter selects which source channel is sent in that position (e.g. "grb" = G, R, B)
+        let mut order = [0, 1, 2];
+        if color_order.len() == 3 {
+            for (i, c) in color_order.chars().enumerate() {
+                order[i] = match c {
+                    'r' => 0,
+                    'g' => 1,
+                    'b' => 2,
+                    _ => i,
+                };
+            }
+        }
+
+        ColorCorrection {
+            scales: scales.map(|s| s as f32),
+            gamma: gamma as f32,
+            lut,
+            order,
+        }
+    }
+
+    /// Build from config, returns None when the settings are an identity transform
+    pub fn from_config(config: &BandwidthConfig) -> Option<Self> {
+        let correction = Rgb::from_hex(&config.color_correction).unwrap_or(Rgb { r: 255, g: 255, b: 255 });
+        // Fold the white point (warmth) into the per-channel correction
+        let [wr, wg, wb] = white_point_scales(config.white_point_kelvin);
+        let scale = |c: u8, s: f64| (c as f64 * s).round() as u8;
+        let correction = Rgb { r: scale(correction.r, wr), g: scale(correction.g, wg), b: scale(correction.b, wb) };
+        let is_identity = (config.gamma - 1.0).abs() < f64::EPSILON
+            && correction.r == 255 && correction.g == 255 && correction.b == 255
+            && config.color_order == "rgb";
+
+        if is_identity {
+            None
+        } else {
+            Some(ColorCorrection::new(config.gamma, correction, &config.color_order))
+        }
+    }
+
+    /// Apply correction, gamma, and channel order to an RGB frame in place
+    pub fn apply(&self, frame: &mut [u8]) {
+        self.apply_scaled(frame, 1.0);
+    }
+
+    /// Like apply, with brightness folded in before the single rounding step
+    pub fn apply_scaled(&self, frame: &mut [u8], brightness: f32) {
+        for pixel in frame.chunks_exact_mut(3) {
+            let corrected = [
+                self.lut[0][pixel[0] as usize] * brightness,
+                self.lut[1][pixel[1] as usize] * brightness,
+                self.lut[2][pixel[2] as usize] * brightness,
+            ];
+            pixel[0] = quantize(corrected[self.order[0]]);
+            pixel[1] = quantize(corrected[self.order[1]]);
+            pixel[2] = quantize(corrected[self.order[2]]);
+        }
+    }
+
+    /// Apply correction, gamma, channel order and brightness to a float frame (0.0-255.0 per channel),
+    /// writing the quantized result to `out` (same length as `frame`)
+    pub fn apply_f32(&self, frame: &[f32], brightness: f32, out: &mut [u8]) {
+        for (src, dst) in frame.chunks_exact(3).zip(out.chunks_exact_mut(3)) {
+            let corrected = [
+                self.curve(0, src[0]) * brightness,
+                self.curve(1, src[1]) * brightness,
+                self.curve(2, src[2]) * brightness,
+            ];
+            dst[0] = quantize(corrected[self.order[0]]);
+            dst[1] = quantize(corrected[self.order[1]]);
+            dst[2] = quantize(corrected[self.order[2]]);
+        }
+    }
+
+    fn curve(&self, channel: usize, value: f32) -> f32 {
+        ((value / 255.0).clamp(0.0, 1.0) * self.scales[channel]).powf(self.gamma) * 255.0
+    }
 }
 
 pub struct MultiDeviceConfig {
     pub devices: Vec<WLEDDevice>,
     pub send_parallel: bool,
     pub fail_fast: bool,
+    pub stagger_sends: bool,  // Spread device sends across the frame interval
+    pub color_correction: Option<ColorCorrection>,
 }
 
 impl MultiDeviceConfig {
+    /// Device setup from the config file's wled_devices and multi_device_* settings
+    pub fn from_config(config: &BandwidthConfig) -> Self {
+        let devices = config.wled_devices.iter().map(|d| WLEDDevice {
+            ip: d.ip.clone(),
+            led_offset: d.led_offset,
+            led_count: d.led_count,
+            enabled: d.enabled,
+            max_fps: d.max_fps,
+            led_ma: d.led_ma,
+            psu_amps: d.psu_amps,
+            backup_for: d.backup_for.clone(),
+            reversed: d.reversed,
+            mirrored: d.mirrored,
+            protocol: d.protocol.clone(),
+            universe: d.universe,
+            start_channel: d.start_channel,
+            net: d.net,
+            subnet: d.subnet,
+        }).collect();
+
+        MultiDeviceConfig {
+            devices,
+            send_parallel: config.multi_device_send_parallel,
+            fail_fast: config.multi_device_fail_fast,
+            stagger_sends: config.multi_device_stagger_sends,
+            color_correction: ColorCorrection::from_config(config),
+        }
+    }
+
     pub fn validate(&self) -> Result<()> {
         if self.devices.is_empty() {
             return Err(anyhow!("No devices configured"));
         }
 
-        // Check for overlapping LED ranges
+        // Backups must point at a configured primary device
+        for device in self.devices.iter().filter(|d| !d.backup_for.is_empty()) {
+            if !self.devices.iter().any(|d| d.backup_for.is_empty() && d.ip == device.backup_for) {
+                return Err(anyhow!(
+                    "Backup device {} refers to unknown primary device {}",
+                    device.ip,
+                    device.backup_for
+                ));
+            }
+        }
+
+        // Check for overlapping LED ranges (backups mirror their primary's range)
+        // Identical ranges are allowed - the second device clones the first's output
         for i in 0..self.devices.len() {
-            if !self.devices[i].enabled {
+            if !self.devices[i].enabled || !self.devices[i].backup_for.is_empty() {
                 continue;
             }
             for j in (i + 1)..self.devices.len() {
-                if !self.devices[j].enabled {
+                if !self.devices[j].enabled || !self.devices[j].backup_for.is_empty() {
                     continue;
                 }
                 let dev1_start = self.devices[i].led_offset;
@@ -42,9 +335,10 @@ impl MultiDeviceConfig {
                 let dev2_start = self.devices[j].led_offset;
                 let dev2_end = dev2_start + self.devices[j].led_count;
 
-                if dev1_start < dev2_end && dev1_end > dev2_start {
+                let is_clone = dev1_start == dev2_start && dev1_end == dev2_end;
+                if !is_clone && dev1_start < dev2_end && dev1_end > dev2_start {
                     return Err(anyhow!(
-                        "Overlapping LED ranges: Device {} ({}-{}) overlaps with Device {} ({}-{})",
+                        "Overlapping LED ranges: Device {} ({}-{}) overlaps with Device {} ({}-{}) - use an identical range to clone output",
                         self.devices[i].ip,
                         dev1_start,
                         dev1_end - 1,
@@ -60,29 +354,351 @@ impl MultiDeviceConfig {
     }
 }
 
+#[derive(Clone)]
 struct DeviceConnection {
     device_config: WLEDDevice,
-    ddp_connection: Arc<Mutex<DDPConnection>>,
+    output: Arc<Mutex<DeviceOutput>>,
     last_send_time: Arc<Mutex<Instant>>,
+    min_frame_interval: Option<Duration>,  // Derived from max_fps (None = unlimited)
+    adaptive_rate: Arc<Mutex<AdaptiveRate>>,  // Lowered while the device's network can't keep up
+    backup: Option<Box<DeviceConnection>>,  // Takes over this device's range when it fails
+    healthy: Arc<AtomicBool>,  // Cleared by the health monitor when the device stops responding
 }
 
 impl DeviceConnection {
     fn new(device_config: WLEDDevice) -> Result<Self> {
-        let dest_addr = format!("{}:4048", device_config.ip);
-        let socket = UdpSocket::bind("0.0.0.0:0")?;
-        let ddp_connection = DDPConnection::try_new(&dest_addr, PixelConfig::default(), ID::Default, socket)?;
+        let output = DeviceOutput::open(&device_config)?;
 
         Ok(DeviceConnection {
+            min_frame_interval: min_frame_interval(device_config.max_fps),
             device_config,
-            ddp_connection: Arc::new(Mutex::new(ddp_connection)),
+            output: Arc::new(Mutex::new(output)),
             last_send_time: Arc::new(Mutex::new(Instant::now())),
+            adaptive_rate: Arc::new(Mutex::new(AdaptiveRate::new())),
+            backup: None,
+            healthy: Arc::new(AtomicBool::new(true)),
         })
     }
+
+    /// Connection frames should currently go to - the backup while this device is unhealthy
+    fn active(&self) -> &DeviceConnection {
+        match self.backup {
+            Some(ref backup) if !self.healthy.load(Ordering::Relaxed) => backup,
+            _ => self,
+        }
+    }
+
+    /// Keep the connection (and its send timing) but send `device_config`'s range and settings from now on
+    fn update(&mut self, device_config: WLEDDevice) {
+        self.min_frame_interval = min_frame_interval(device_config.max_fps);
+        self.device_config = device_config;
+    }
+
+    /// Send black once - for a device that stops getting frames, so it doesn't hold its last one
+    fn blank(&self) {
+        let (len, offset) = match segments::placement(&self.device_config.ip) {
+            Placement::Whole => (self.device_config.led_count, 0),
+            Placement::Segment(target) => (self.device_config.led_count.min(target.len), target.ddp_offset()),
+            Placement::Pending => return,
+        };
+        if let Ok(mut conn) = self.output.lock() {
+            let _ = conn.write_offset(&vec![0; len * 3], offset);
+        }
+    }
+}
+
+// Time between frames for a device's max_fps (None = unlimited)
+fn min_frame_interval(max_fps: f64) -> Option<Duration> {
+    if max_fps > 0.0 {
+        Some(Duration::from_secs_f64(1.0 / max_fps))
+    } else {
+        None
+    }
+}
+
+// Connection for `device_config`, reusing one from `existing` with the same IP if there is one
+fn take_or_connect(existing: &mut Vec<DeviceConnection>, device_config: WLEDDevice) -> Result<DeviceConnection> {
+    // A device switched to another protocol (or universe) needs a new connection
+    let same_output = |conn: &DeviceConnection| conn.device_config.ip == device_config.ip
+        && conn.device_config.protocol == device_config.protocol
+        && conn.device_config.universe == device_config.universe
+        && conn.device_config.start_channel == device_config.start_channel
+        && conn.device_config.net == device_config.net
+        && conn.device_config.subnet == device_config.subnet;
+    match existing.iter().position(same_output) {
+        Some(index) => {
+            let mut conn = existing.swap_remove(index);
+            conn.update(device_config);
+            Ok(conn)
+        }
+        None => DeviceConnection::new(device_config),
+    }
+}
+
+/// Apply per-device orientation to a device's slice of the frame, writing into `oriented`
+/// Returns None when the slice can be sent as-is
+fn orient_frame<'a>(frame: &[u8], reversed: bool, mirrored: bool, oriented: &'a mut Vec<u8>) -> Option<&'a [u8]> {
+    if !reversed && !mirrored {
+        return None;
+    }
+
+    let led_count = frame.len() / 3;
+    // Mirroring shows the first half of the slice on both halves, so LED i and LED n-1-i light the same;
+    // reversing flips the slice first, so reversed+mirrored shows the last half on both halves instead
+    let mirror = |led: usize| if mirrored && led >= led_count - led_count / 2 { led_count - 1 - led } else { led };
+    let reverse = |led: usize| if reversed { led_count - 1 - led } else { led };
+
+    oriented.clear();
+    for i in 0..led_count {
+        let src = reverse(mirror(i)) * 3;
+        oriented.extend_from_slice(&frame[src..src + 3]);
+    }
+
+    Some(oriented)
+}
+
+/// Estimated current draw of an RGB frame in milliamps
+/// Each channel contributes a third of the LED's full-white current, scaled by its value
+pub fn estimate_current_ma(frame: &[u8], led_ma: f64) -> f64 {
+    let total: u64 = frame.iter().map(|&v| v as u64).sum();
+    total as f64 / (255.0 * 3.0) * led_ma
+}
+
+/// Average color of an LED zone - for lights that show one color for a range of the strip
+/// (Hue, LIFX, Nanoleaf panels); zones past the end of the frame are cut short
+pub fn average_zone(frame: &[u8], led_offset: usize, led_count: usize) -> [u8; 3] {
+    let start = (led_offset * 3).min(frame.len());
+    let end = ((led_offset + led_count) * 3).min(frame.len());
+    let pixels = &frame[start..end];
+    let count = (pixels.len() / 3).max(1) as u32;
+
+    let mut sums = [0u32; 3];
+    for pixel in pixels.chunks_exact(3) {
+        for (sum, &value) in sums.iter_mut().zip(pixel) {
+            *sum += value as u32;
+        }
+    }
+    sums.map(|sum| (sum / count) as u8)
+}
+
+/// Dim a device frame so its estimated draw stays within the power supply limit
+/// Writes the dimmed frame into `limited`, returns None when the frame is already within budget (or limiting is disabled)
+fn limit_current<'a>(frame: &[u8], led_ma: f64, psu_amps: f64, limited: &'a mut Vec<u8>) -> Option<&'a [u8]> {
+    if psu_amps <= 0.0 || led_ma <= 0.0 {
+        return None;
+    }
+
+    let budget_ma = psu_amps * 1000.0;
+    let estimated_ma = estimate_current_ma(frame, led_ma);
+    if estimated_ma <= budget_ma {
+        return None;
+    }
+
+    let scale = budget_ma / estimated_ma;
+    limited.clear();
+    limited.extend(frame.iter().map(|&v| (v as f64 * scale) as u8));
+    Some(limited)
+}
+
+/// Whether a device with the given minimum frame interval should skip this frame
+/// Frames arriving faster than the device's max_fps are dropped so slow controllers aren't flooded
+fn frame_rate_limited(last_send_time: &Mutex<Instant>, min_frame_interval: Option<Duration>) -> bool {
+    match min_frame_interval {
+        Some(interval) => last_send_time.lock()
+            .map(|last_send| last_send.elapsed() < interval)
+            .unwrap_or(false),
+        None => false,
+    }
+}
+
+/// Whether adaptive FPS skips this frame for the device (also counts it toward the device's offered rate)
+fn adaptive_throttled(adaptive_rate: &Mutex<AdaptiveRate>, ip: &str, last_send_time: &Mutex<Instant>) -> bool {
+    let since_last_send = last_send_time.lock().map(|last_send| last_send.elapsed()).unwrap_or_default();
+    adaptive_rate.lock()
+        .map(|mut adaptive| adaptive.throttled(ip, since_last_send))
+        .unwrap_or(false)
+}
+
+// Long-lived thread sending one device's slice of each frame in parallel mode
+// Each job is the device's stagger delay; each result is the send error, if any
+struct SendWorker {
+    jobs: SyncSender<Option<Duration>>,
+    results: Receiver<Option<String>>,
+}
+
+impl SendWorker {
+    fn spawn(device: DeviceConnection, frame: Arc<RwLock<Vec<u8>>>) -> Self {
+        let (jobs, job_rx) = mpsc::sync_channel::<Option<Duration>>(1);
+        let (result_tx, results) = mpsc::sync_channel(1);
+
+        // Exits when the manager drops its end (devices changed or manager closed)
+        std::thread::spawn(move || {
+            // This device's scratch buffers, reused every frame
+            let mut oriented_frame = Vec::new();
+            let mut limited_frame = Vec::new();
+            while let Ok(stagger_delay) = job_rx.recv() {
+                let error = match frame.read() {
+                    Ok(frame) => send_device_slice(device.active(), &frame, stagger_delay, &mut oriented_frame, &mut limited_frame),
+                    Err(_) => Some(format!("Frame unavailable for {}", device.device_config.ip)),
+                };
+                if result_tx.send(error).is_err() {
+                    break;
+                }
+            }
+        });
+
+        SendWorker { jobs, results }
+    }
 }
 
+/// Send a device's slice of the frame (parallel mode) - returns the error, if any
+fn send_device_slice(device: &DeviceConnection, frame: &[u8], stagger_delay: Option<Duration>, oriented_frame: &mut Vec<u8>, limited_frame: &mut Vec<u8>) -> Option<String> {
+    let device_ip = &device.device_config.ip;
+    let byte_offset = device.device_config.led_offset * 3;
+    let byte_count = device.device_config.led_count * 3;
+
+    // Validate range
+    if byte_offset + byte_count > frame.len() {
+        let err = format!(
+            "Device {} range exceeds frame size: offset={} count={} (device wants LEDs {}-{}, frame has {} LEDs)",
+            device_ip,
+            byte_offset / 3,
+            byte_count / 3,
+            byte_offset / 3,
+            (byte_offset + byte_count) / 3 - 1,
+            frame.len() / 3
+        );
+        eprintln!("{}", err);
+        return Some(err);
+    }
+
+    // Wait for this device's slot in the frame interval
+    if let Some(delay) = stagger_delay {
+        std::thread::sleep(delay);
+    }
+
+    // Skip this frame if the device's max_fps hasn't elapsed yet
+    if frame_rate_limited(&device.last_send_time, device.min_frame_interval) {
+        return None;
+    }
+
+    // Held to a lower rate while its network can't keep up
+    if adaptive_throttled(&device.adaptive_rate, device_ip, &device.last_send_time) {
+        return None;
+    }
+
+    // Switched off in the WLED app - leave it off until it's switched back on
+    if wled_state::switched_off(device_ip) {
+        return None;
+    }
+
+    // Confined to a WLED segment - nothing goes out until its bounds have been read
+    let segment = match segments::placement(device_ip) {
+        Placement::Whole => None,
+        Placement::Segment(target) => Some(target),
+        Placement::Pending => return None,
+    };
+
+    // Extract device frame slice, dimmed if it would exceed the PSU limit
+    let device_frame = &frame[byte_offset..byte_offset + byte_count];
+    let device_frame = orient_frame(device_frame, device.device_config.reversed, device.device_config.mirrored, oriented_frame)
+        .unwrap_or(device_frame);
+    let device_frame = limit_current(device_frame, device.device_config.led_ma, device.device_config.psu_amps, limited_frame)
+        .unwrap_or(device_frame);
+    let device_frame = segment.map_or(device_frame, |target| target.frame(device_frame));
+
+    // Check if we need to send a keepalive (time since last send)
+    let needs_keepalive = {
+        if let Ok(last_send) = device.last_send_time.lock() {
+            last_send.elapsed() >= KEEPALIVE_INTERVAL
+        } else {
+            false
+        }
+    };
+
+    // Skip sending if all zeros AND we don't need a keepalive
+    let all_zeros = device_frame.iter().all(|&b| b == 0);
+    if all_zeros && !needs_keepalive {
+        return None;
+    }
+
+    // Send over the device's protocol - SAME AS SEQUENTIAL MODE
+    if let Ok(mut conn) = device.output.lock() {
+        let send_started = Instant::now();
+        let sent = conn.write_offset(device_frame, segment.map_or(0, |target| target.ddp_offset()));
+        if let Ok(mut adaptive) = device.adaptive_rate.lock() {
+            adaptive.record_send(send_started.elapsed(), sent.is_err());
+        }
+        if let Err(e) = sent {
+            let err = format!("Failed to send to {}: {}", device_ip, e);
+            eprintln!("{}", err);
+            live_stats::record_device_send(device_ip, Some(e.to_string()));
+            return Some(err);
+        }
+        live_stats::record_device_send(device_ip, None);
+        // Update last send time on successful send
+        if let Ok(mut last_send) = device.last_send_time.lock() {
+            *last_send = Instant::now();
+        }
+        None
+    } else {
+        let err = format!("Failed to acquire lock for device {}", device_ip);
+        eprintln!("{}", err);
+        Some(err)
+    }
+}
+
+// Managers currently open - modes fade in only from the first and out only from the last, so a manager
+// rebuilt for a device change (the old one still open while the new one connects) doesn't blink the strip
+static OPEN_MANAGERS: AtomicUsize = AtomicUsize::new(0);
+
+// Time between frames of the fade to black on close
+const FADE_OUT_STEP: Duration = Duration::from_millis(20);
+
 pub struct MultiDeviceManager {
     devices: Vec<DeviceConnection>,
     config: MultiDeviceConfig,
+    health_monitor_stop: Arc<AtomicBool>,
+    last_frame_time: Option<Instant>,
+    frame_interval: Option<Duration>,  // Smoothed time between frames (for staggered sends)
+
+    // Scratch buffers reused every frame so sending doesn't allocate
+    adjusted_frame: Vec<u8>,  // Color corrected / dimmed copy of the frame
+    float_frame: Vec<f32>,  // Float copy of the frame for post-processing
+    postfx: PostFx,
+    blank_frame: Vec<u8>,
+    oriented_frame: Vec<u8>,
+    limited_frame: Vec<u8>,
+
+    // Parallel sends - a long-lived thread per device, started on the first parallel frame after the devices change
+    send_workers: Vec<SendWorker>,
+    parallel_frame: Arc<RwLock<Vec<u8>>>,  // The frame being sent, read by every worker
+
+    // Soft start/stop
+    opened_at: Instant,
+    fade_in: Duration,  // Zero when this manager replaced another
+    last_output: Vec<u8>,  // Last frame sent, faded to black on close
+
+    interpolator: Interpolator,  // Blends between frames of slow sources (frame_interpolation)
+}
+
+impl Drop for MultiDeviceManager {
+    fn drop(&mut self) {
+        self.health_monitor_stop.store(true, Ordering::Relaxed);
+        if OPEN_MANAGERS.fetch_sub(1, Ordering::Relaxed) == 1 {
+            // Demo mode crossfades into the next mode instead of going dark in between
+            if demo::switching() {
+                demo::hand_off(std::mem::take(&mut self.last_output));
+            } else {
+                self.fade_to_black();
+            }
+        }
+    }
+}
+
+// Brightness multiplier for the output stage (brightness above 1.0 is ignored, as before)
+fn output_scale(brightness: Option<f64>) -> f32 {
+    brightness.map_or(1.0, |b| b.min(1.0)) as f32
 }
 
 impl MultiDeviceManager {
@@ -94,7 +710,7 @@ impl MultiDeviceManager {
         config.validate()?;
 
         let mut devices = Vec::new();
-        for device_config in &config.devices {
+        for device_config in config.devices.iter().filter(|d| d.backup_for.is_empty()) {
             if device_config.enabled {
                 match DeviceConnection::new(device_config.clone()) {
                     Ok(conn) => devices.push(conn),
@@ -109,7 +725,222 @@ impl MultiDeviceManager {
             return Err(anyhow!("No devices connected successfully"));
         }
 
-        Ok(MultiDeviceManager { devices, config })
+        // Attach backups to their primaries - a backup sends the primary's LED range
+        for backup_config in config.devices.iter().filter(|d| d.enabled && !d.backup_for.is_empty()) {
+            if let Some(primary) = devices.iter_mut().find(|d| d.device_config.ip == backup_config.backup_for) {
+                let mut mirrored = backup_config.clone();
+                mirrored.led_offset = primary.device_config.led_offset;
+                mirrored.led_count = primary.device_config.led_count;
+                match DeviceConnection::new(mirrored) {
+                    Ok(conn) => primary.backup = Some(Box::new(conn)),
+                    Err(e) => {
+                        eprintln!("Warning: Failed to connect to backup {}: {}", backup_config.ip, e);
+                    }
+                }
+            }
+        }
+
+        let health_monitor_stop = Arc::new(AtomicBool::new(false));
+        Self::spawn_health_monitor(&devices, health_monitor_stop.clone());
+
+        let fade_in = if OPEN_MANAGERS.fetch_add(1, Ordering::Relaxed) == 0 && !demo::crossfading() {
+            fade::fade_in()
+        } else {
+            Duration::ZERO
+        };
+
+        Ok(MultiDeviceManager {
+            devices,
+            config,
+            health_monitor_stop,
+            last_frame_time: None,
+            frame_interval: None,
+            adjusted_frame: Vec::new(),
+            float_frame: Vec::new(),
+            postfx: PostFx::default(),
+            blank_frame: Vec::new(),
+            oriented_frame: Vec::new(),
+            limited_frame: Vec::new(),
+            send_workers: Vec::new(),
+            parallel_frame: Arc::new(RwLock::new(Vec::new())),
+            opened_at: Instant::now(),
+            fade_in,
+            last_output: Vec::new(),
+            interpolator: Interpolator::default(),
+        })
+    }
+
+    // Brightness with the fade-in applied while the mode is starting
+    fn fading_in(&self, brightness: Option<f64>) -> Option<f64> {
+        let elapsed = self.opened_at.elapsed();
+        if elapsed >= self.fade_in {
+            return brightness;
+        }
+        Some(output_scale(brightness) as f64 * fade::ramp(elapsed, self.fade_in))
+    }
+
+    // Fade the last frame sent down to black, leaving the strip dark instead of frozen
+    fn fade_to_black(&mut self) {
+        let duration = fade::fade_out();
+        if duration.is_zero() || self.last_output.is_empty() {
+            return;
+        }
+
+        // Devices capped below the step rate would drop fade frames (and the final black one)
+        let step = self.devices.iter()
+            .filter_map(|d| d.min_frame_interval)
+            .fold(FADE_OUT_STEP, Duration::max);
+        let last = std::mem::take(&mut self.last_output);
+        let mut faded = vec![0u8; last.len()];
+        let started = Instant::now();
+        loop {
+            let level = 1.0 - fade::ramp(started.elapsed(), duration) as f32;
+            for (dst, &src) in faded.iter_mut().zip(&last) {
+                *dst = quantize(src as f32 * level);
+            }
+            let _ = self.send_output(&faded);
+            if level <= 0.0 {
+                break;
+            }
+            std::thread::sleep(step);
+        }
+    }
+
+    /// Switch to a new device setup in place - devices added, removed, enabled, disabled or moved at runtime
+    /// Connections to devices that stay are kept (no reconnect, fade or skipped frame), new ones are connected,
+    /// and devices that drop out are sent black. The next frame is sliced by the new ranges
+    pub fn reconfigure(&mut self, config: MultiDeviceConfig) -> Result<()> {
+        config.validate()?;
+        if !config.devices.iter().any(|d| d.enabled && d.backup_for.is_empty()) {
+            return Err(anyhow!("No enabled devices"));
+        }
+
+        // Workers send to the old setup - new ones start with the next parallel frame
+        self.send_workers.clear();
+
+        // Every current connection, primaries and backups, to be picked up again by IP
+        let mut existing = Vec::new();
+        for mut conn in std::mem::take(&mut self.devices) {
+            if let Some(backup) = conn.backup.take() {
+                existing.push(*backup);
+            }
+            existing.push(conn);
+        }
+        let monitored_before: Vec<(String, String)> = existing.iter()
+            .filter(|conn| !conn.device_config.backup_for.is_empty())
+            .map(|conn| (conn.device_config.backup_for.clone(), conn.device_config.ip.clone()))
+            .collect();
+
+        for device_config in config.devices.iter().filter(|d| d.enabled && d.backup_for.is_empty()) {
+            match take_or_connect(&mut existing, device_config.clone()) {
+                Ok(conn) => self.devices.push(conn),
+                Err(e) => eprintln!("Warning: Failed to connect to {}: {}", device_config.ip, e),
+            }
+        }
+        for backup_config in config.devices.iter().filter(|d| d.enabled && !d.backup_for.is_empty()) {
+            if let Some(primary) = self.devices.iter_mut().find(|d| d.device_config.ip == backup_config.backup_for) {
+                let mut mirrored = backup_config.clone();
+                mirrored.led_offset = primary.device_config.led_offset;
+                mirrored.led_count = primary.device_config.led_count;
+                match take_or_connect(&mut existing, mirrored) {
+                    Ok(conn) => primary.backup = Some(Box::new(conn)),
+                    Err(e) => eprintln!("Warning: Failed to connect to backup {}: {}", backup_config.ip, e),
+                }
+            }
+        }
+
+        // Whatever wasn't picked up was removed or disabled
+        for conn in &existing {
+            conn.blank();
+        }
+
+        // The health monitor watches a fixed set of primaries - restart it if that set changed
+        let monitored_after: Vec<(String, String)> = self.devices.iter()
+            .filter_map(|d| d.backup.as_ref().map(|backup| (d.device_config.ip.clone(), backup.device_config.ip.clone())))
+            .collect();
+        if monitored_after != monitored_before {
+            self.health_monitor_stop.store(true, Ordering::Relaxed);
+            self.health_monitor_stop = Arc::new(AtomicBool::new(false));
+            Self::spawn_health_monitor(&self.devices, self.health_monitor_stop.clone());
+        }
+
+        self.config = config;
+        Ok(())
+    }
+
+    /// Periodically check primaries that have a backup and switch frames over when they stop responding
+    fn spawn_health_monitor(devices: &[DeviceConnection], stop: Arc<AtomicBool>) {
+        for device in devices.iter().filter(|d| d.backup.is_some() && !has_health_check(&d.device_config)) {
+            live_stats::record_notice(format!(
+                "⚠️  {} ({}) can't be health checked - its backup won't take over",
+                device.device_config.ip, device.device_config.protocol,
+            ));
+        }
+        let monitored: Vec<(String, String, Arc<AtomicBool>)> = devices.iter()
+            .filter(|d| has_health_check(&d.device_config))
+            .filter_map(|d| d.backup.as_ref().map(|backup| (
+                d.device_config.ip.clone(),
+                backup.device_config.ip.clone(),
+                d.healthy.clone(),
+            )))
+            .collect();
+
+        if monitored.is_empty() {
+            return;
+        }
+
+        std::thread::spawn(move || {
+            while !stop.load(Ordering::Relaxed) {
+                for (primary_ip, backup_ip, healthy) in &monitored {
+                    update_health(primary_ip, backup_ip, healthy, device_reachable(primary_ip));
+                }
+
+                // Sleep in short steps so the monitor exits promptly when the manager is dropped
+                let started = Instant::now();
+                while started.elapsed() < HEALTH_CHECK_INTERVAL && !stop.load(Ordering::Relaxed) {
+                    std::thread::sleep(Duration::from_millis(100));
+                }
+            }
+
+            // Primaries of a dropped manager are no longer routed anywhere
+            for (primary_ip, _, healthy) in &monitored {
+                if !healthy.load(Ordering::Relaxed) {
+                    if let Ok(mut status) = FAILOVER_STATUS.lock() {
+                        status.active.retain(|ip| ip != primary_ip);
+                    }
+                }
+            }
+        });
+    }
+
+    /// Replace the output color calibration (None = send colors unmodified)
+    pub fn set_color_correction(&mut self, color_correction: Option<ColorCorrection>) {
+        self.config.color_correction = color_correction;
+    }
+
+    /// Track the interval between frames so staggered sends can be spread across it
+    fn update_frame_interval(&mut self) {
+        let now = Instant::now();
+        if let Some(last) = self.last_frame_time {
+            let sample = now.duration_since(last);
+            // Ignore long gaps (paused/idle modes) so they don't stretch the stagger window
+            if sample < Duration::from_secs(1) {
+                self.frame_interval = Some(match self.frame_interval {
+                    Some(interval) => interval.mul_f64(0.9) + sample.mul_f64(0.1),
+                    None => sample,
+                });
+            }
+        }
+        self.last_frame_time = Some(now);
+    }
+
+    /// Delay between consecutive device sends when staggering is enabled
+    fn stagger_slot(&self) -> Option<Duration> {
+        if !self.config.stagger_sends || self.devices.len() < 2 {
+            return None;
+        }
+        self.frame_interval
+            .map(|interval| interval.mul_f64(STAGGER_WINDOW_FRACTION) / self.devices.len() as u32)
     }
 
     pub fn send_frame(&mut self, frame: &[u8]) -> Result<Vec<String>> {
@@ -127,115 +958,230 @@ impl MultiDeviceManager {
             ));
         }
 
-        // Apply brightness if specified
-        let frame_to_send: Vec<u8>;
-        let frame_ref = if let Some(brightness) = brightness {
-            if brightness < 1.0 {
-                // Apply brightness multiplier to all RGB values
-                frame_to_send = frame.iter().map(|&val| {
-                    (val as f64 * brightness).round() as u8
-                }).collect();
-                &frame_to_send
-            } else {
-                frame  // No brightness adjustment needed
-            }
+        let started = Instant::now();
+        recording::record_frame(frame, brightness);
+
+        // Slow sources are blended towards each new frame, the in-between frames going out from tick()
+        if frame_interpolation::enabled() {
+            let mut interpolator = std::mem::take(&mut self.interpolator);
+            interpolator.push(frame, brightness, started);
+            let (frame, brightness) = interpolator.frame(started);
+            let result = self.send_bytes(frame, brightness, started);
+            self.interpolator = interpolator;
+            return result;
+        }
+        self.send_bytes(frame, brightness, started)
+    }
+
+    /// Send the next in-between frame while frame interpolation is blending towards a slow source's latest
+    /// frame - for modes that only send when their source delivers, called from their idle loop
+    /// Returns None when there is nothing to send
+    pub fn tick(&mut self) -> Option<Result<Vec<String>>> {
+        let now = Instant::now();
+        if !frame_interpolation::enabled() || !self.interpolator.due(now) {
+            return None;
+        }
+        let mut interpolator = std::mem::take(&mut self.interpolator);
+        let (frame, brightness) = interpolator.frame(now);
+        let result = self.send_bytes(frame, brightness, now);
+        self.interpolator = interpolator;
+        Some(result)
+    }
+
+    // Dim, correct and send a byte frame (`started` is when it entered the output stage, for the timing breakdown)
+    fn send_bytes(&mut self, frame: &[u8], brightness: Option<f64>, started: Instant) -> Result<Vec<String>> {
+        let brightness = self.fading_in(ambient::scale(quiet_hours::cap(wled_state::frame_brightness(brightness))));
+
+        // An active alert replaces the mode's picture until it is cleared or times out
+        let alarm = alert::frame(frame.len() / 3);
+        let frame = alarm.as_deref().unwrap_or(frame);
+
+        // Post-processing works in float, so byte frames take the float path while effects are on
+        if postfx::enabled() {
+            let mut float_frame = std::mem::take(&mut self.float_frame);
+            float_frame.clear();
+            float_frame.extend(frame.iter().map(|&v| v as f32));
+            let result = self.send_float(&mut float_frame, brightness, started);
+            self.float_frame = float_frame;
+            return result;
+        }
+
+        // Scratch buffers are taken out while in use and put back after sending
+        let mut blank_frame = std::mem::take(&mut self.blank_frame);
+        let mut adjusted = std::mem::take(&mut self.adjusted_frame);
+
+        // Blanked via the API - send black (still refreshed by the keepalive) until un-blanked
+        let frame = if system::blanked() {
+            blank_frame.clear();
+            blank_frame.resize(frame.len(), 0);
+            &blank_frame[..]
         } else {
-            frame  // No brightness specified
+            frame
         };
 
-        if self.config.send_parallel {
-            self.send_parallel(frame_ref)
+        // Apply color correction and brightness if specified - in one pass, so the result is only rounded once
+        let needs_brightness = matches!(brightness, Some(b) if b < 1.0);
+        let frame_ref = if needs_brightness || self.config.color_correction.is_some() {
+            let scale = output_scale(brightness);
+            adjusted.clear();
+            adjusted.extend_from_slice(frame);
+            match self.config.color_correction {
+                Some(ref correction) => correction.apply_scaled(&mut adjusted, scale),
+                None => {
+                    for val in adjusted.iter_mut() {
+                        *val = quantize(*val as f32 * scale);
+                    }
+                }
+            }
+            &adjusted[..]
         } else {
-            self.send_sequential(frame_ref)
+            frame  // No adjustment needed
+        };
+
+        live_stats::record_postfx_time(started.elapsed());
+        let result = self.send_output(frame_ref);
+
+        self.blank_frame = blank_frame;
+        self.adjusted_frame = adjusted;
+        result
+    }
+
+    /// Send a float frame (0.0-255.0 per channel) - for renderers that keep sub-byte precision
+    /// (e.g. attack/decay smoothing), so brightness and gamma are applied before anything is rounded
+    pub fn send_frame_f32(&mut self, frame: &[f32], brightness: Option<f64>) -> Result<Vec<String>> {
+        if frame.len() % 3 != 0 {
+            return Err(anyhow!(
+                "Frame size must be divisible by 3 (RGB), got {} values",
+                frame.len()
+            ));
         }
+
+        // Recordings store bytes, so only quantize a copy when one is running
+        let started = Instant::now();
+        if recording::active() {
+            let raw: Vec<u8> = frame.iter().map(|&v| quantize(v)).collect();
+            recording::record_frame(&raw, brightness);
+        }
+        let brightness = self.fading_in(ambient::scale(quiet_hours::cap(wled_state::frame_brightness(brightness))));
+
+        let mut float_frame = std::mem::take(&mut self.float_frame);
+        float_frame.clear();
+        match alert::frame(frame.len() / 3) {
+            Some(alarm) => float_frame.extend(alarm.iter().map(|&v| v as f32)),
+            None => float_frame.extend_from_slice(frame),
+        }
+        let result = self.send_float(&mut float_frame, brightness, started);
+        self.float_frame = float_frame;
+        result
     }
 
-    fn send_parallel(&mut self, frame: &[u8]) -> Result<Vec<String>> {
-        use std::thread;
-
-        let errors = Arc::new(Mutex::new(Vec::new()));
-        let frame_arc = Arc::new(frame.to_vec());
-
-        thread::scope(|s| {
-            for device in &self.devices {
-                let device_ip = device.device_config.ip.clone();
-                let byte_offset = device.device_config.led_offset * 3;
-                let byte_count = device.device_config.led_count * 3;
-                let frame_clone = Arc::clone(&frame_arc);
-                let errors_clone = Arc::clone(&errors);
-                let conn_clone = Arc::clone(&device.ddp_connection);
-
-                let last_send_clone = Arc::clone(&device.last_send_time);
-
-                s.spawn(move || {
-                    // Validate range
-                    if byte_offset + byte_count > frame_clone.len() {
-                        let err = format!(
-                            "Device {} range exceeds frame size: offset={} count={} (device wants LEDs {}-{}, frame has {} LEDs)",
-                            device_ip,
-                            byte_offset / 3,
-                            byte_count / 3,
-                            byte_offset / 3,
-                            (byte_offset + byte_count) / 3 - 1,
-                            frame_clone.len() / 3
-                        );
-                        eprintln!("{}", err);
-                        errors_clone.lock().unwrap().push(err);
-                        return;
-                    }
+    // Post-process, correct and dim a float frame, rounding to bytes only at the end
+    // (`started` is when the frame entered the output stage, for the timing breakdown)
+    fn send_float(&mut self, frame: &mut [f32], brightness: Option<f64>, started: Instant) -> Result<Vec<String>> {
+        self.postfx.apply(frame);
+        let frame = &*frame;
 
-                    // Extract device frame slice
-                    let device_frame = &frame_clone[byte_offset..byte_offset + byte_count];
-
-                    // Check if we need to send a keepalive (time since last send)
-                    let needs_keepalive = {
-                        if let Ok(last_send) = last_send_clone.lock() {
-                            last_send.elapsed() >= KEEPALIVE_INTERVAL
-                        } else {
-                            false
-                        }
-                    };
-
-                    // Skip sending if all zeros AND we don't need a keepalive
-                    let all_zeros = device_frame.iter().all(|&b| b == 0);
-                    if all_zeros && !needs_keepalive {
-                        return;
-                    }
+        let mut adjusted = std::mem::take(&mut self.adjusted_frame);
+        adjusted.clear();
+        adjusted.resize(frame.len(), 0);
 
-                    // Send using DDPConnection - SAME AS SEQUENTIAL MODE
-                    if let Ok(mut conn) = conn_clone.lock() {
-                        if let Err(e) = conn.write(device_frame) {
-                            let err = format!("Failed to send to {}: {}", device_ip, e);
-                            eprintln!("{}", err);
-                            errors_clone.lock().unwrap().push(err);
-                        } else {
-                            // Update last send time on successful send
-                            if let Ok(mut last_send) = last_send_clone.lock() {
-                                *last_send = Instant::now();
-                            }
-                        }
-                    } else {
-                        let err = format!("Failed to acquire lock for device {}", device_ip);
-                        eprintln!("{}", err);
-                        errors_clone.lock().unwrap().push(err);
+        // Blanked via the API - send black until un-blanked
+        if !system::blanked() {
+            let scale = output_scale(brightness);
+            match self.config.color_correction {
+                Some(ref correction) => correction.apply_f32(frame, scale, &mut adjusted),
+                None => {
+                    for (dst, &src) in adjusted.iter_mut().zip(frame) {
+                        *dst = quantize(src * scale);
                     }
-                });
+                }
             }
-        });
+        }
 
-        let errors = Arc::try_unwrap(errors).unwrap().into_inner().unwrap();
-        if errors.is_empty() {
-            Ok(vec![])
+        live_stats::record_postfx_time(started.elapsed());
+        let result = self.send_output(&adjusted);
+
+        self.adjusted_frame = adjusted;
+        result
+    }
+
+    // Hand a finished (corrected, dimmed) frame to the devices
+    fn send_output(&mut self, frame: &[u8]) -> Result<Vec<String>> {
+        // Demo mode blends in from the previous mode's last frame
+        let crossfaded = demo::crossfade(frame);
+        let frame = crossfaded.as_deref().unwrap_or(frame);
+
+        self.update_frame_interval();
+        live_stats::record_frame();
+        self.last_output.clear();
+        self.last_output.extend_from_slice(frame);
+        #[cfg(feature = "hue")]
+        hue::update(frame);
+        lifx::update(frame);
+        nanoleaf::update(frame);
+
+        let started = Instant::now();
+        let result = if self.config.send_parallel {
+            self.send_parallel(frame)
         } else {
-            Ok(errors)
+            self.send_sequential(frame)
+        };
+        live_stats::record_send_time(started.elapsed());
+        result
+    }
+
+    fn send_parallel(&mut self, frame: &[u8]) -> Result<Vec<String>> {
+        if self.send_workers.len() != self.devices.len() {
+            self.send_workers = self.devices.iter()
+                .map(|device| SendWorker::spawn(device.clone(), Arc::clone(&self.parallel_frame)))
+                .collect();
+        }
+
+        {
+            let mut shared = self.parallel_frame.write().unwrap();
+            shared.clear();
+            shared.extend_from_slice(frame);
+        }
+
+        // Start every device's send, then wait for them all - the frame isn't touched again until they're done
+        let stagger_slot = self.stagger_slot();
+        for (idx, worker) in self.send_workers.iter().enumerate() {
+            let _ = worker.jobs.send(stagger_slot.map(|slot| slot * idx as u32));
         }
+
+        let mut errors = Vec::new();
+        let mut worker_stopped = false;
+        for worker in &self.send_workers {
+            match worker.results.recv() {
+                Ok(Some(err)) => errors.push(err),
+                Ok(None) => {}
+                Err(_) => worker_stopped = true,
+            }
+        }
+
+        // A worker that panicked is replaced on the next frame
+        if worker_stopped {
+            self.send_workers.clear();
+            errors.push("A device send thread stopped - restarting it".to_string());
+        }
+
+        Ok(errors)
     }
 
     fn send_sequential(&mut self, frame: &[u8]) -> Result<Vec<String>> {
         let mut errors = Vec::new();
+        let stagger_slot = self.stagger_slot();
 
-        for device in &mut self.devices {
-            let device_ip = device.device_config.ip.clone();
+        for (idx, device) in self.devices.iter().enumerate() {
+            // Space devices evenly across the frame interval
+            if idx > 0 {
+                if let Some(slot) = stagger_slot {
+                    std::thread::sleep(slot);
+                }
+            }
+
+            let device = device.active();
+            let device_ip = &device.device_config.ip;
             let byte_offset = device.device_config.led_offset * 3;
             let byte_count = device.device_config.led_count * 3;
 
@@ -259,8 +1205,35 @@ impl MultiDeviceManager {
                 continue;
             }
 
-            // Extract slice for this device
+            // Skip this frame if the device's max_fps hasn't elapsed yet
+            if frame_rate_limited(&device.last_send_time, device.min_frame_interval) {
+                continue;
+            }
+
+            // Held to a lower rate while its network can't keep up
+            if adaptive_throttled(&device.adaptive_rate, device_ip, &device.last_send_time) {
+                continue;
+            }
+
+            // Switched off in the WLED app - leave it off until it's switched back on
+            if wled_state::switched_off(device_ip) {
+                continue;
+            }
+
+            // Confined to a WLED segment - nothing goes out until its bounds have been read
+            let segment = match segments::placement(device_ip) {
+                Placement::Whole => None,
+                Placement::Segment(target) => Some(target),
+                Placement::Pending => continue,
+            };
+
+            // Extract slice for this device, dimmed if it would exceed the PSU limit
             let device_frame = &frame[byte_offset..byte_offset + byte_count];
+            let device_frame = orient_frame(device_frame, device.device_config.reversed, device.device_config.mirrored, &mut self.oriented_frame)
+                .unwrap_or(device_frame);
+            let device_frame = limit_current(device_frame, device.device_config.led_ma, device.device_config.psu_amps, &mut self.limited_frame)
+                .unwrap_or(device_frame);
+            let device_frame = segment.map_or(device_frame, |target| target.frame(device_frame));
 
             // Check if we need to send a keepalive (time since last send)
             let needs_keepalive = {
@@ -277,16 +1250,23 @@ impl MultiDeviceManager {
                 continue;
             }
 
-            // Send using DDPConnection - SAME AS SINGLE DEVICE MODE
-            if let Ok(mut conn) = device.ddp_connection.lock() {
-                if let Err(e) = conn.write(device_frame) {
+            // Send over the device's protocol - SAME AS SINGLE DEVICE MODE
+            if let Ok(mut conn) = device.output.lock() {
+                let send_started = Instant::now();
+                let sent = conn.write_offset(device_frame, segment.map_or(0, |target| target.ddp_offset()));
+                if let Ok(mut adaptive) = device.adaptive_rate.lock() {
+                    adaptive.record_send(send_started.elapsed(), sent.is_err());
+                }
+                if let Err(e) = sent {
                     let err = format!("Failed to send to {}: {}", device_ip, e);
                     eprintln!("{}", err);
+                    live_stats::record_device_send(device_ip, Some(e.to_string()));
                     errors.push(err);
                     if self.config.fail_fast {
                         return Err(anyhow!("Failed to send to device"));
                     }
                 } else {
+                    live_stats::record_device_send(device_ip, None);
                     // Update last send time on successful send
                     if let Ok(mut last_send) = device.last_send_time.lock() {
                         *last_send = Instant::now();
@@ -309,3 +1289,211 @@ impl MultiDeviceManager {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_color_order() {
+        let correction = ColorCorrection::new(1.0, Rgb { r: 255, g: 255, b: 255 }, "grb");
+        let mut frame = vec![10, 20, 30];
+        correction.apply(&mut frame);
+        assert_eq!(frame, vec![20, 10, 30]);
+    }
+
+    #[test]
+    fn test_brightness_rounded_once() {
+        // Gamma 2.0 maps 15 to 0.88 - rounding that to 1 before halving used to leave a lit LED
+        let correction = ColorCorrection::new(2.0, Rgb { r: 255, g: 255, b: 255 }, "rgb");
+        let mut frame = vec![15, 128, 255];
+        correction.apply_scaled(&mut frame, 0.5);
+        assert_eq!(frame, vec![0, 32, 128]);
+
+        // Float input keeps its fraction through the curve
+        let mut out = vec![0u8; 3];
+        correction.apply_f32(&[127.5, 0.0, 255.0], 0.5, &mut out);
+        assert_eq!(out, vec![32, 0, 128]);
+    }
+
+    #[test]
+    fn test_gamma_and_correction() {
+        let correction = ColorCorrection::new(2.0, Rgb { r: 255, g: 0, b: 255 }, "rgb");
+        let mut frame = vec![255, 255, 128];
+        correction.apply(&mut frame);
+        assert_eq!(frame[0], 255);
+        assert_eq!(frame[1], 0);
+        assert_eq!(frame[2], 64);
+    }
+
+    fn test_device(ip: &str, led_offset: usize, led_count: usize) -> WLEDDevice {
+        WLEDDevice {
+            ip: ip.to_string(),
+            led_offset,
+            led_count,
+            enabled: true,
+            max_fps: 0.0,
+            led_ma: 55.0,
+            psu_amps: 0.0,
+            backup_for: String::new(),
+            reversed: false,
+            mirrored: false,
+            protocol: "ddp".to_string(),
+            universe: 1,
+            start_channel: 1,
+            net: 0,
+            subnet: 0,
+        }
+    }
+
+    #[test]
+    fn test_cloned_ranges_allowed() {
+        let config = MultiDeviceConfig {
+            devices: vec![test_device("10.0.0.1", 0, 100), test_device("10.0.0.2", 0, 100)],
+            send_parallel: false,
+            fail_fast: false,
+            stagger_sends: false,
+            color_correction: None,
+        };
+        assert!(config.validate().is_ok());
+
+        let config = MultiDeviceConfig {
+            devices: vec![test_device("10.0.0.1", 0, 100), test_device("10.0.0.2", 50, 100)],
+            ..config
+        };
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_health_checked_protocols() {
+        assert!(has_health_check(&test_device("10.0.0.1", 0, 100)));
+        assert!(!has_health_check(&WLEDDevice { protocol: "sacn".to_string(), ..test_device("10.0.0.1", 0, 100) }));
+        assert!(!has_health_check(&WLEDDevice { protocol: "artnet".to_string(), ..test_device("10.0.0.1", 0, 100) }));
+        assert!(!has_health_check(&test_device("virtual:4048", 0, 100)));
+    }
+
+    #[test]
+    fn test_failover() {
+        let mut primary = virtual_device::VirtualDevice::bind(0).unwrap();
+        let mut backup = virtual_device::VirtualDevice::bind(0).unwrap();
+        let primary_ip = format!("virtual:{}", primary.port().unwrap());
+        let backup_ip = format!("virtual:{}", backup.port().unwrap());
+        let mut manager = MultiDeviceManager::new(MultiDeviceConfig {
+            devices: vec![
+                test_device(&primary_ip, 0, 4),
+                WLEDDevice { backup_for: primary_ip.clone(), ..test_device(&backup_ip, 0, 4) },
+            ],
+            send_parallel: false,
+            fail_fast: false,
+            stagger_sends: false,
+            color_correction: None,
+        }).unwrap();
+        let timeout = Duration::from_secs(2);
+        let healthy = manager.devices[0].healthy.clone();
+
+        // Down - frames go to the backup and the failover is reported
+        update_health(&primary_ip, &backup_ip, &healthy, false);
+        assert!(failover_status().active.contains(&primary_ip));
+        manager.send_frame(&[10; 12]).unwrap();
+        assert_eq!(backup.recv_frame(timeout).unwrap().unwrap(), &[10; 12]);
+        assert!(primary.recv_frame(Duration::from_millis(200)).unwrap().is_none());
+
+        // Still down - no second event for the same failover
+        let events = failover_status().events.iter().filter(|e| e.primary == primary_ip).count();
+        update_health(&primary_ip, &backup_ip, &healthy, false);
+        assert_eq!(failover_status().events.iter().filter(|e| e.primary == primary_ip).count(), events);
+
+        // Recovered - frames go back to the primary
+        update_health(&primary_ip, &backup_ip, &healthy, true);
+        assert!(!failover_status().active.contains(&primary_ip));
+        let last = failover_status().events.into_iter().rev().find(|e| e.primary == primary_ip).unwrap();
+        assert!(!last.failed_over);
+        manager.send_frame(&[20; 12]).unwrap();
+        assert_eq!(primary.recv_frame(timeout).unwrap().unwrap(), &[20; 12]);
+    }
+
+    #[test]
+    fn test_orient_frame() {
+        let frame = vec![1, 1, 1, 2, 2, 2, 3, 3, 3, 4, 4, 4];
+        let mut oriented = Vec::new();
+        assert!(orient_frame(&frame, false, false, &mut oriented).is_none());
+        assert_eq!(orient_frame(&frame, true, false, &mut oriented).unwrap(), &[4, 4, 4, 3, 3, 3, 2, 2, 2, 1, 1, 1]);
+        assert_eq!(orient_frame(&frame, false, true, &mut oriented).unwrap(), &[1, 1, 1, 2, 2, 2, 2, 2, 2, 1, 1, 1]);
+        assert_eq!(orient_frame(&frame, true, true, &mut oriented).unwrap(), &[4, 4, 4, 3, 3, 3, 3, 3, 3, 4, 4, 4]);
+
+        // Odd lengths keep the middle LED
+        let frame = vec![1, 1, 1, 2, 2, 2, 3, 3, 3];
+        assert_eq!(orient_frame(&frame, false, true, &mut oriented).unwrap(), &[1, 1, 1, 2, 2, 2, 1, 1, 1]);
+        assert_eq!(orient_frame(&frame, true, true, &mut oriented).unwrap(), &[3, 3, 3, 2, 2, 2, 3, 3, 3]);
+    }
+
+    #[test]
+    fn test_stagger_slot() {
+        let first = virtual_device::VirtualDevice::bind(0).unwrap();
+        let second = virtual_device::VirtualDevice::bind(0).unwrap();
+        let devices = vec![
+            test_device(&format!("virtual:{}", first.port().unwrap()), 0, 4),
+            test_device(&format!("virtual:{}", second.port().unwrap()), 4, 4),
+        ];
+        let config = |devices: &[WLEDDevice]| MultiDeviceConfig {
+            devices: devices.to_vec(),
+            send_parallel: false,
+            fail_fast: false,
+            stagger_sends: true,
+            color_correction: None,
+        };
+        let mut manager = MultiDeviceManager::new(config(&devices)).unwrap();
+
+        // Nothing to spread across until the frame interval is known
+        assert_eq!(manager.stagger_slot(), None);
+
+        // Two devices share 75% of a 40ms frame
+        manager.frame_interval = Some(Duration::from_millis(40));
+        assert_eq!(manager.stagger_slot(), Some(Duration::from_millis(15)));
+
+        // A pause doesn't stretch the window
+        manager.last_frame_time = Some(Instant::now() - Duration::from_secs(2));
+        manager.update_frame_interval();
+        assert_eq!(manager.frame_interval, Some(Duration::from_millis(40)));
+
+        // Off, or one device - sent back to back
+        manager.config.stagger_sends = false;
+        assert_eq!(manager.stagger_slot(), None);
+        let mut single = MultiDeviceManager::new(config(&devices[..1])).unwrap();
+        single.frame_interval = Some(Duration::from_millis(40));
+        assert_eq!(single.stagger_slot(), None);
+    }
+
+    #[test]
+    fn test_max_fps() {
+        assert_eq!(min_frame_interval(0.0), None);
+        assert_eq!(min_frame_interval(20.0), Some(Duration::from_millis(50)));
+
+        // At 20 FPS, a frame 10ms after the last send is dropped and one 60ms after goes out
+        let interval = min_frame_interval(20.0);
+        let last_send = Mutex::new(Instant::now() - Duration::from_millis(10));
+        assert!(frame_rate_limited(&last_send, interval));
+        *last_send.lock().unwrap() = Instant::now() - Duration::from_millis(60);
+        assert!(!frame_rate_limited(&last_send, interval));
+
+        // Unlimited devices take every frame
+        *last_send.lock().unwrap() = Instant::now();
+        assert!(!frame_rate_limited(&last_send, None));
+    }
+
+    #[test]
+    fn test_current_limit() {
+        // 10 LEDs at full white, 60mA each = 600mA
+        let frame = vec![255u8; 30];
+        assert_eq!(estimate_current_ma(&frame, 60.0), 600.0);
+
+        // Within a 1A budget - untouched
+        let mut limited = Vec::new();
+        assert!(limit_current(&frame, 60.0, 1.0, &mut limited).is_none());
+
+        // 0.3A budget - dimmed to half
+        let limited = limit_current(&frame, 60.0, 0.3, &mut limited).unwrap();
+        assert!(limited.iter().all(|&v| v == 127));
+        assert!(estimate_current_ma(&limited, 60.0) <= 300.0);
+    }
+}
@@ -0,0 +1,155 @@
+// Nanoleaf Module - Nanoleaf panels over the external control streaming API: each panel shows the
+// average color of an LED zone
+use anyhow::{anyhow, Result};
+use std::io::{Read, Write};
+use std::net::{TcpStream, ToSocketAddrs, UdpSocket};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, Instant};
+use tokio::sync::broadcast;
+
+use crate::config::{self, BandwidthConfig, ConfigDelta, NanoleafPanelConfig};
+use crate::multi_device;
+
+/// Nanoleaf controllers' REST API port
+pub const NANOLEAF_API_PORT: u16 = 16021;
+
+/// UDP port for external control (v2) frames
+pub const NANOLEAF_STREAM_PORT: u16 = 60222;
+
+// Panels update at about 10-15 frames a second on older controllers - faster is dropped
+const SEND_INTERVAL: Duration = Duration::from_millis(50);
+
+// Wait between attempts when the controller can't be reached
+const RETRY_INTERVAL: Duration = Duration::from_secs(5);
+
+// External control mode ends if another app or the panel's buttons pick an effect - re-enter it this often
+const REACTIVATE_INTERVAL: Duration = Duration::from_secs(30);
+
+const HTTP_TIMEOUT: Duration = Duration::from_secs(3);
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+static SETTINGS: Mutex<Option<NanoleafSettings>> = Mutex::new(None);
+static COLORS: Mutex<Vec<(u16, [u8; 3])>> = Mutex::new(Vec::new());  // Panel id and its color
+
+#[derive(Debug, Clone, PartialEq)]
+struct NanoleafSettings {
+    ip: String,
+    token: String,  // Auth token from pairing
+    panels: Vec<NanoleafPanelConfig>,
+}
+
+/// Take the controller settings from the config - no IP or no panels turns the backend off
+pub fn configure(config: &BandwidthConfig) {
+    let settings = (!config.nanoleaf_ip.is_empty() && !config.nanoleaf_panels.is_empty()).then(|| NanoleafSettings {
+        ip: config.nanoleaf_ip.clone(),
+        token: config.nanoleaf_token.clone(),
+        panels: config.nanoleaf_panels.clone(),
+    });
+    ENABLED.store(settings.is_some(), Ordering::Relaxed);
+    *SETTINGS.lock().unwrap() = settings;
+}
+
+/// Downsample an output frame to the panels - called for every frame sent to the WLED devices
+pub fn update(frame: &[u8]) {
+    if !ENABLED.load(Ordering::Relaxed) {
+        return;
+    }
+    let colors = match SETTINGS.lock().unwrap().as_ref() {
+        Some(settings) => settings.panels.iter()
+            .map(|p| (p.panel_id, multi_device::average_zone(frame, p.led_offset, p.led_count)))
+            .collect(),
+        None => return,
+    };
+    *COLORS.lock().unwrap() = colors;
+}
+
+/// External control (v2) frame: panel count, then id, RGBW and transition time (100ms units) per panel
+fn stream_frame(colors: &[(u16, [u8; 3])]) -> Vec<u8> {
+    let mut frame = Vec::with_capacity(2 + colors.len() * 8);
+    frame.extend_from_slice(&(colors.len() as u16).to_be_bytes());
+    for (id, [r, g, b]) in colors {
+        frame.extend_from_slice(&id.to_be_bytes());
+        frame.extend_from_slice(&[*r, *g, *b, 0]);
+        frame.extend_from_slice(&1u16.to_be_bytes());  // Shortest transition
+    }
+    frame
+}
+
+/// Stream to the controller in the background, reconnecting after failures and following config changes
+pub fn spawn_streamer() {
+    thread::spawn(|| loop {
+        let Some(settings) = SETTINGS.lock().unwrap().clone() else {
+            thread::sleep(Duration::from_millis(500));
+            continue;
+        };
+        if let Err(e) = stream(&settings) {
+            eprintln!("⚠️  Nanoleaf stream to {}: {} - retrying in {}s", settings.ip, e, RETRY_INTERVAL.as_secs());
+            thread::sleep(RETRY_INTERVAL);
+        }
+    });
+}
+
+/// Keep the controller settings in step with config changes (web UI, API, or file edits)
+pub fn spawn_watcher(config_change_tx: broadcast::Sender<ConfigDelta>) {
+    config::spawn_config_listener(&config_change_tx, &["nanoleaf_ip", "nanoleaf_token", "nanoleaf_panels"], configure);
+}
+
+// Enter external control mode and send colors until the settings change (Ok) or sending fails
+fn stream(settings: &NanoleafSettings) -> Result<()> {
+    let socket = UdpSocket::bind("0.0.0.0:0")?;
+    socket.connect((settings.ip.as_str(), NANOLEAF_STREAM_PORT))?;
+
+    let mut activated: Option<Instant> = None;
+    let mut next_send = Instant::now();
+    while SETTINGS.lock().unwrap().as_ref() == Some(settings) {
+        if !matches!(activated, Some(at) if at.elapsed() < REACTIVATE_INTERVAL) {
+            start_external_control(settings)?;
+            if activated.is_none() {
+                println!("🔷 Streaming to Nanoleaf {} ({} panels)", settings.ip, settings.panels.len());
+            }
+            activated = Some(Instant::now());
+        }
+
+        socket.send(&stream_frame(&COLORS.lock().unwrap()))?;
+        next_send += SEND_INTERVAL;
+        thread::sleep(next_send.saturating_duration_since(Instant::now()));
+    }
+    Ok(())
+}
+
+// Switch the panels to external control (PUT /api/v1/<token>/effects)
+fn start_external_control(settings: &NanoleafSettings) -> Result<()> {
+    let addr = (settings.ip.as_str(), NANOLEAF_API_PORT).to_socket_addrs()?.next()
+        .ok_or_else(|| anyhow!("Could not resolve {}", settings.ip))?;
+    let mut stream = TcpStream::connect_timeout(&addr, HTTP_TIMEOUT)?;
+    stream.set_read_timeout(Some(HTTP_TIMEOUT))?;
+    let body = serde_json::json!({
+        "write": { "command": "display", "animType": "extControl", "extControlVersion": "v2" }
+    }).to_string();
+    write!(stream, "PUT /api/v1/{}/effects HTTP/1.0\r\nHost: {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\r\n{}",
+        settings.token, settings.ip, body.len(), body)?;
+
+    let mut response = String::new();
+    stream.read_to_string(&mut response)?;
+    match response.split_whitespace().nth(1) {
+        Some(status) if status.starts_with('2') => Ok(()),
+        Some("401") | Some("403") => Err(anyhow!("controller rejected nanoleaf_token")),
+        status => Err(anyhow!("controller answered HTTP {}", status.unwrap_or("?"))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stream_frame() {
+        let frame = stream_frame(&[(0x1234, [255, 128, 0]), (7, [0, 0, 0])]);
+        assert_eq!(frame.len(), 2 + 2 * 8);
+        assert_eq!(&frame[..2], &[0, 2]);
+        assert_eq!(&frame[2..10], &[0x12, 0x34, 255, 128, 0, 0, 0, 1]);
+        assert_eq!(&frame[10..12], &[0, 7]);
+    }
+}
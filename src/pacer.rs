@@ -0,0 +1,125 @@
+// Pacer Module - Frame pacing with hybrid sleep (OS sleep, then spin for the last millisecond)
+use std::thread;
+use std::time::{Duration, Instant};
+
+// Final stretch before a deadline that's spun instead of slept - OS sleeps routinely overshoot by up to ~1ms
+const SPIN_WINDOW: Duration = Duration::from_millis(1);
+
+/// Block until `deadline`: sleep for all but the last millisecond, then spin the rest
+/// Returns immediately if the deadline has already passed
+pub fn sleep_until(deadline: Instant) {
+    let now = Instant::now();
+    if deadline <= now {
+        return;
+    }
+
+    let remaining = deadline - now;
+    if remaining > SPIN_WINDOW {
+        thread::sleep(remaining - SPIN_WINDOW);
+    }
+    while Instant::now() < deadline {
+        std::hint::spin_loop();
+    }
+}
+
+fn interval_for(fps: f64) -> Duration {
+    Duration::from_secs_f64(1.0 / fps.max(1.0))
+}
+
+/// Fixed-rate frame scheduler - deadlines are laid out on an absolute grid (start + n * interval)
+/// rather than "now + interval", so time spent rendering and sleep overshoot don't accumulate as drift
+pub struct FramePacer {
+    interval: Duration,
+    next_frame: Instant,  // Deadline of the next frame
+}
+
+impl FramePacer {
+    pub fn new(fps: f64) -> Self {
+        let interval = interval_for(fps);
+        Self {
+            interval,
+            next_frame: Instant::now() + interval,
+        }
+    }
+
+    /// Change the frame rate, keeping the current frame's start time
+    pub fn set_fps(&mut self, fps: f64) {
+        let interval = interval_for(fps);
+        if interval != self.interval {
+            let frame_start = self.next_frame.checked_sub(self.interval).unwrap_or(self.next_frame);
+            self.next_frame = frame_start + interval;
+            self.interval = interval;
+        }
+    }
+
+    pub fn interval(&self) -> Duration {
+        self.interval
+    }
+
+    pub fn next_frame(&self) -> Instant {
+        self.next_frame
+    }
+
+    /// For polling loops - true once the next frame's deadline has passed (and schedules the one after)
+    pub fn frame_due(&mut self, now: Instant) -> bool {
+        if now < self.next_frame {
+            return false;
+        }
+        self.advance(now);
+        true
+    }
+
+    /// For one-frame-per-iteration loops - block until the next frame's deadline, then schedule the one after
+    pub fn wait(&mut self) {
+        sleep_until(self.next_frame);
+        self.advance(Instant::now());
+    }
+
+    fn advance(&mut self, now: Instant) {
+        self.next_frame += self.interval;
+        // More than a whole frame behind (slow frame, stalled terminal) - restart the grid from now
+        // instead of bursting out back-to-back frames to catch up
+        if self.next_frame <= now {
+            self.next_frame = now + self.interval;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_frame_pacer_schedule() {
+        let mut pacer = FramePacer::new(100.0);
+        let first = pacer.next_frame();
+        assert_eq!(pacer.interval(), Duration::from_millis(10));
+
+        // Not due before the deadline; due exactly at it, and the next deadline lands on the grid
+        assert!(!pacer.frame_due(first - Duration::from_millis(1)));
+        assert!(pacer.frame_due(first + Duration::from_millis(3)));
+        assert_eq!(pacer.next_frame(), first + Duration::from_millis(10));
+
+        // Falling several frames behind resyncs rather than catching up
+        let late = first + Duration::from_millis(55);
+        assert!(pacer.frame_due(late));
+        assert_eq!(pacer.next_frame(), late + Duration::from_millis(10));
+
+        // Changing rate keeps the current frame's start
+        pacer.set_fps(50.0);
+        assert_eq!(pacer.next_frame(), late + Duration::from_millis(20));
+    }
+
+    #[test]
+    fn test_frame_pacer_wait_holds_rate() {
+        let mut pacer = FramePacer::new(500.0);
+        let start = Instant::now();
+        for _ in 0..10 {
+            pacer.wait();
+        }
+        // 10 frames at 2ms - the grid keeps this from drifting past 20ms plus scheduling slack
+        let elapsed = start.elapsed();
+        assert!(elapsed >= Duration::from_millis(18), "{:?}", elapsed);
+        assert!(elapsed < Duration::from_millis(40), "{:?}", elapsed);
+    }
+}
@@ -0,0 +1,171 @@
+// Piano Roll Module - Scrolling note history for the MIDI TUI (pitch against time, one color per channel)
+use ratatui::style::{Color, Style};
+use ratatui::text::{Line, Span};
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+use crate::midi;
+
+// Most notes kept - the oldest drop off early during very dense playing
+const MAX_NOTES: usize = 2000;
+
+// Width of the note name gutter on the left
+const GUTTER: usize = 4;
+
+// Bar color per MIDI channel (1-16)
+const CHANNEL_COLORS: [Color; 16] = [
+    Color::Cyan,
+    Color::Magenta,
+    Color::Yellow,
+    Color::Green,
+    Color::LightRed,
+    Color::LightBlue,
+    Color::LightMagenta,
+    Color::LightGreen,
+    Color::LightYellow,
+    Color::Red,  // Channel 10 - drums
+    Color::Blue,
+    Color::LightCyan,
+    Color::White,
+    Color::Gray,
+    Color::Rgb(255, 165, 0),
+    Color::Rgb(160, 100, 255),
+];
+
+struct RollNote {
+    channel: u8,
+    note: u8,
+    start: Instant,
+    end: Option<Instant>,  // None while the note is held
+}
+
+/// Notes played over the last `window`, for drawing as bars that scroll left as time passes
+pub struct PianoRoll {
+    notes: VecDeque<RollNote>,
+    window: Duration,
+}
+
+impl PianoRoll {
+    pub fn new(window: Duration) -> Self {
+        Self {
+            notes: VecDeque::new(),
+            window,
+        }
+    }
+
+    pub fn note_on(&mut self, channel: u8, note: u8, at: Instant) {
+        self.prune(at);
+        if self.notes.len() >= MAX_NOTES {
+            self.notes.pop_front();
+        }
+        self.notes.push_back(RollNote { channel, note, start: at, end: None });
+    }
+
+    pub fn note_off(&mut self, channel: u8, note: u8, at: Instant) {
+        if let Some(held) = self.notes.iter_mut().rev().find(|n| n.channel == channel && n.note == note && n.end.is_none()) {
+            held.end = Some(at);
+        }
+    }
+
+    // Drop notes that ended before the window
+    fn prune(&mut self, now: Instant) {
+        let window = self.window;
+        self.notes.retain(|n| !matches!(n.end, Some(end) if now.duration_since(end) > window));
+    }
+
+    /// Draw the roll as `height` lines of `width` characters - newest at the right edge, low notes at
+    /// the bottom, C of each octave labelled in the gutter. The pitch range fits the notes in view.
+    pub fn lines(&self, width: usize, height: usize, now: Instant) -> Vec<Line<'static>> {
+        let columns = width.saturating_sub(GUTTER);
+        if columns == 0 || height == 0 {
+            return Vec::new();
+        }
+        let window_start = now.checked_sub(self.window).unwrap_or(now);
+        let visible: Vec<&RollNote> = self.notes.iter()
+            .filter(|n| !matches!(n.end, Some(end) if end < window_start))
+            .collect();
+
+        // Pitch range: the notes in view, widened to fill the rows (or an octave either side of middle C)
+        let (low, high) = match (visible.iter().map(|n| n.note).min(), visible.iter().map(|n| n.note).max()) {
+            (Some(low), Some(high)) => (low as usize, high as usize),
+            _ => (48, 72),
+        };
+        let span = (high - low + 1).max(height);
+        let low = low.saturating_sub((span - (high - low + 1)) / 2).min(128 - span.min(128));
+        let row_of = |note: u8| ((note as usize).saturating_sub(low) * height / span).min(height - 1);
+
+        // Time to column, with the window spread across the columns
+        let window_secs = self.window.as_secs_f64().max(0.001);
+        let column_of = |at: Instant| {
+            let secs = at.saturating_duration_since(window_start).as_secs_f64();
+            ((secs / window_secs * columns as f64) as usize).min(columns - 1)
+        };
+
+        let mut cells: Vec<Option<u8>> = vec![None; columns * height];
+        for n in &visible {
+            let row = height - 1 - row_of(n.note);
+            let (start, end) = (column_of(n.start.max(window_start)), column_of(n.end.unwrap_or(now)));
+            for cell in &mut cells[row * columns + start..=row * columns + end.max(start)] {
+                *cell = Some(n.channel);
+            }
+        }
+
+        (0..height).map(|row| {
+            // Label the row holding each octave's C
+            let bottom_row = height - 1 - row;
+            let label = (low..(low + span).min(128))
+                .find(|&note| note % 12 == 0 && row_of(note as u8) == bottom_row)
+                .map(|note| midi::note_number_to_name(note as u8))
+                .unwrap_or_default();
+            let mut spans = vec![Span::styled(format!("{:<width$}", label, width = GUTTER), Style::default().fg(Color::DarkGray))];
+
+            // One span per run of cells with the same channel
+            let row_cells = &cells[row * columns..(row + 1) * columns];
+            let mut run_start = 0;
+            for i in 1..=columns {
+                if i < columns && row_cells[i] == row_cells[run_start] {
+                    continue;
+                }
+                let len = i - run_start;
+                spans.push(match row_cells[run_start] {
+                    Some(channel) => Span::styled("█".repeat(len), Style::default().fg(CHANNEL_COLORS[channel as usize % 16])),
+                    None => Span::raw(" ".repeat(len)),
+                });
+                run_start = i;
+            }
+            Line::from(spans)
+        }).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Characters of a rendered line, gutter included
+    fn text(line: &Line) -> String {
+        line.spans.iter().map(|span| span.content.as_ref()).collect()
+    }
+
+    #[test]
+    fn test_notes_scroll_left() {
+        let start = Instant::now();
+        let mut roll = PianoRoll::new(Duration::from_secs(10));
+        roll.note_on(0, 60, start);
+        roll.note_off(0, 60, start + Duration::from_secs(2));
+        roll.note_on(1, 64, start + Duration::from_secs(8));
+
+        // 10 columns over 10 seconds - C4 held for the first 2s, E4 still held at the right edge
+        let lines = roll.lines(GUTTER + 10, 5, start + Duration::from_secs(10));
+        assert_eq!(lines.len(), 5);
+        let rows: Vec<String> = lines.iter().map(text).collect();
+        let c4 = rows.iter().find(|row| row.starts_with("C4")).expect("C4 row labelled");
+        assert_eq!(&c4[GUTTER..], "███       ");
+        assert_eq!(rows[0], format!("{}██", " ".repeat(GUTTER + 8)));
+
+        // Notes that ended before the window are gone
+        roll.note_off(1, 64, start + Duration::from_secs(9));
+        roll.note_on(0, 72, start + Duration::from_secs(30));
+        assert_eq!(roll.notes.len(), 1);
+    }
+}
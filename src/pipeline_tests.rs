@@ -0,0 +1,337 @@
+// Pipeline Tests - Run the modes headlessly against loopback virtual devices and check what actually
+// arrives over DDP: frame sizes, brightness, per-device LED ranges, and config hot reload
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+use crate::config::{self, BandwidthConfig, ConfigDelta, SandConfig, TronConfig, WLEDDeviceConfig};
+use crate::geometry::GeometryState;
+use crate::matrix::MatrixLayout;
+use crate::midi::NoteState;
+use crate::multi_device::{quantize, MultiDeviceConfig, MultiDeviceManager};
+use crate::renderer::{self, RenderStateCell, Renderer, SharedRenderState};
+use crate::sand::SandSimulation;
+use crate::tron::TronGame;
+use crate::virtual_device::VirtualDevice;
+
+const RECV_TIMEOUT: Duration = Duration::from_secs(2);
+
+// Modes draw nothing on some of their first frames - give them this many tries to light an LED
+const MAX_WARMUP_FRAMES: usize = 200;
+
+// A device sending to `capture` (a virtual WLED on a free loopback port)
+fn loopback_device(capture: &VirtualDevice, led_offset: usize, led_count: usize) -> WLEDDeviceConfig {
+    WLEDDeviceConfig {
+        ip: format!("virtual:{}", capture.port().unwrap()),
+        led_offset,
+        led_count,
+        enabled: true,
+        max_fps: 0.0,
+        led_ma: 55.0,
+        psu_amps: 0.0,
+        backup_for: String::new(),
+        reversed: false,
+        mirrored: false,
+        segment: -1,
+        protocol: "ddp".to_string(),
+        universe: 1,
+        start_channel: 1,
+        net: 0,
+        subnet: 0,
+    }
+}
+
+fn config_with(devices: Vec<WLEDDeviceConfig>) -> BandwidthConfig {
+    let mut config = BandwidthConfig::default();
+    config.total_leds = devices.iter().map(|d| d.led_offset + d.led_count).max().unwrap_or(0);
+    config.wled_devices = devices;
+    config
+}
+
+fn manager_for(config: &BandwidthConfig) -> MultiDeviceManager {
+    MultiDeviceManager::new(MultiDeviceConfig::from_config(config)).unwrap()
+}
+
+fn receive(capture: &mut VirtualDevice) -> Vec<u8> {
+    capture.recv_frame(RECV_TIMEOUT).unwrap().expect("no frame received").to_vec()
+}
+
+// Render until the mode lights something (all-black frames aren't sent between keepalives)
+fn first_lit(mut render: impl FnMut() -> Vec<u8>) -> Vec<u8> {
+    (0..MAX_WARMUP_FRAMES)
+        .map(|_| render())
+        .find(|frame| frame.iter().any(|&v| v > 0))
+        .expect("mode never lit an LED")
+}
+
+// Send one rendered frame to a single device covering it, and check it arrives unchanged
+fn assert_delivered(frame: &[u8]) {
+    let mut capture = VirtualDevice::bind(0).unwrap();
+    let config = config_with(vec![loopback_device(&capture, 0, frame.len() / 3)]);
+    let mut manager = manager_for(&config);
+    manager.send_frame(frame).unwrap();
+    assert_eq!(receive(&mut capture), frame);
+}
+
+#[test]
+fn test_tron_frames() {
+    let config = BandwidthConfig::default();
+    let tron = TronConfig { num_players: 2, ..config.tron.clone() };
+    let layout = MatrixLayout::for_mode(&config, tron.width, tron.height, false);
+    let total_leds = layout.led_count();
+    let mut game = TronGame::new(layout.width(), layout.height(), &tron, &config.color_space);
+
+    let frame = first_lit(|| {
+        let mut frame = Vec::new();
+        game.update();
+        game.render(total_leds, &layout, &mut frame);
+        frame
+    });
+    assert_eq!(frame.len(), total_leds * 3);
+    assert_delivered(&frame);
+}
+
+#[test]
+fn test_sand_frames() {
+    let config = BandwidthConfig::default();
+    let sand = SandConfig { particle_type: "sand".into(), spawn_rate: 1.0, fire_enabled: false, ..config.sand.clone() };
+    let layout = MatrixLayout::for_mode(&config, sand.grid_width, sand.grid_height, true);
+    let mut sim = SandSimulation::new(layout.width(), layout.height(), &sand);
+
+    let total_leds = layout.led_count();
+    let frame = first_lit(|| {
+        let mut frame = Vec::new();
+        sim.spawn_particles();
+        sim.update();
+        sim.render(total_leds, &layout, &mut frame);
+        frame
+    });
+    assert_eq!(frame.len(), total_leds * 3);
+    assert_delivered(&frame);
+}
+
+#[test]
+fn test_geometry_frames() {
+    let mut config = BandwidthConfig::default();
+    config.total_leds = 256;
+    let mut state = GeometryState::new(config.total_leds, config.geometry.grid_width, config.geometry.grid_height, &config.geometry, &config.boid);
+
+    let frame = first_lit(|| {
+        let mut frame = Vec::new();
+        state.update(1.0, config.animation_speed, &config.tx_animation_direction, &mut frame);
+        frame
+    });
+    assert_eq!(frame.len(), config.total_leds * 3);
+    assert_delivered(&frame);
+}
+
+#[test]
+fn test_midi_frames() {
+    let config = BandwidthConfig::default();
+    let note_state = NoteState::new();
+    for note in [48u8, 60, 72] {
+        note_state.note_on(0, note, 127);
+    }
+
+    let total_leds = 88;
+    let mut smoothed_frame = vec![0.0_f32; total_leds];
+    let mut target_brightness = vec![0.0_f32; total_leds];
+    let mut last_colors = vec![(0_u8, 0_u8, 0_u8); total_leds];
+    let mut buffers = renderer::MidiFrameBuffers::default();
+    let frame = renderer::render_midi_to_leds(
+        &note_state,
+        total_leds,
+        config.midi.gradient,
+        None,
+        config.midi.velocity_colors,
+        config.midi.one_to_one,
+        config.midi.channel_mode,
+        None,
+        &mut smoothed_frame,
+        &mut target_brightness,
+        &mut last_colors,
+        1.0,
+        1.0,
+        None,
+        &mut buffers,
+    ).unwrap().to_vec();
+    assert_eq!(frame.len(), total_leds * 3);
+
+    // MIDI keeps float precision until the output stage rounds it
+    let mut capture = VirtualDevice::bind(0).unwrap();
+    let mut manager = manager_for(&config_with(vec![loopback_device(&capture, 0, total_leds)]));
+    manager.send_frame_f32(&frame, None).unwrap();
+    let expected: Vec<u8> = frame.iter().map(|&v| quantize(v)).collect();
+    assert!(expected.iter().any(|&v| v > 0));
+    assert_eq!(receive(&mut capture), expected);
+}
+
+#[test]
+fn test_midi_zones() {
+    // Channel 1 lights the first device, channel 10 the second
+    let mut config = BandwidthConfig::default();
+    let capture_a = VirtualDevice::bind(0).unwrap();
+    let capture_b = VirtualDevice::bind(0).unwrap();
+    config.wled_devices = vec![loopback_device(&capture_a, 0, 128), loopback_device(&capture_b, 128, 256)];
+    config.midi.zones = vec![
+        config::MidiZoneConfig { channel: 1, devices: vec![config.wled_devices[0].ip.clone()], led_offset: 0, led_count: 0 },
+        config::MidiZoneConfig { channel: 10, devices: vec![config.wled_devices[1].ip.clone()], led_offset: 0, led_count: 0 },
+    ];
+
+    let note_state = NoteState::new();
+    note_state.note_on(9, 60, 127);
+    let mut zones = renderer::MidiZones::new(crate::midi::zone_ranges(&config.midi.zones, &config.wled_devices, 384));
+    let frame = zones.render(&note_state, 384, false, None, false, false, 1.0, 1.0).unwrap();
+    assert_eq!(frame.len(), 384 * 3);
+    assert!(frame[..128 * 3].iter().all(|&v| v == 0.0));
+    assert!(frame[128 * 3..].iter().any(|&v| v > 0.0));
+
+    // Notes on channels without a zone don't light up
+    note_state.note_off(9, 60);
+    note_state.note_on(2, 60, 127);
+    let frame = zones.render(&note_state, 384, false, None, false, false, 1.0, 1.0).unwrap();
+    assert!(frame.iter().all(|&v| v == 0.0));
+}
+
+#[test]
+fn test_brightness() {
+    let mut capture = VirtualDevice::bind(0).unwrap();
+    let mut manager = manager_for(&config_with(vec![loopback_device(&capture, 0, 10)]));
+
+    manager.send_frame_with_brightness(&[255; 30], Some(0.5)).unwrap();
+    assert_eq!(receive(&mut capture), vec![128; 30]);
+
+    // Brightness above 1.0 doesn't boost the frame
+    manager.send_frame_with_brightness(&[100; 30], Some(2.0)).unwrap();
+    assert_eq!(receive(&mut capture), vec![100; 30]);
+}
+
+#[test]
+fn test_device_offsets() {
+    let mut first = VirtualDevice::bind(0).unwrap();
+    let mut second = VirtualDevice::bind(0).unwrap();
+    let config = config_with(vec![loopback_device(&first, 0, 4), loopback_device(&second, 4, 6)]);
+    let mut manager = manager_for(&config);
+
+    // Every byte different, so a shifted slice can't pass
+    let frame: Vec<u8> = (1..=30).collect();
+    manager.send_frame(&frame).unwrap();
+    assert_eq!(receive(&mut first), frame[..12]);
+    assert_eq!(receive(&mut second), frame[12..]);
+}
+
+#[test]
+fn test_parallel_sends() {
+    let mut first = VirtualDevice::bind(0).unwrap();
+    let mut second = VirtualDevice::bind(0).unwrap();
+    let mut config = config_with(vec![loopback_device(&first, 0, 4), loopback_device(&second, 4, 6)]);
+    config.multi_device_send_parallel = true;
+    config.wled_devices[1].reversed = true;
+    let mut manager = manager_for(&config);
+
+    // The same send threads carry every frame, each slicing and orienting its own device's range
+    for start in [1u8, 101] {
+        let frame: Vec<u8> = (start..start + 30).collect();
+        manager.send_frame(&frame).unwrap();
+        assert_eq!(receive(&mut first), frame[..12]);
+        let reversed: Vec<u8> = frame[12..].chunks(3).rev().flatten().copied().collect();
+        assert_eq!(receive(&mut second), reversed);
+    }
+
+    // Changing the devices replaces the send threads with ones for the new ranges
+    let mut moved = config.clone();
+    moved.wled_devices = vec![loopback_device(&first, 0, 10)];
+    manager.reconfigure(MultiDeviceConfig::from_config(&moved)).unwrap();
+    assert_eq!(receive(&mut second), vec![0; 18]);  // Blanked on the way out
+    manager.send_frame(&[70; 30]).unwrap();
+    assert_eq!(receive(&mut first), vec![70; 30]);
+}
+
+#[test]
+fn test_device_hot_reload() {
+    let mut old_capture = VirtualDevice::bind(0).unwrap();
+    let mut new_capture = VirtualDevice::bind(0).unwrap();
+    let old = config_with(vec![loopback_device(&old_capture, 0, 10)]);
+    let mut manager = manager_for(&old);
+    manager.send_frame(&[50; 30]).unwrap();
+    assert_eq!(receive(&mut old_capture), vec![50; 30]);
+
+    // Moving the strip to another device is a device change - the manager switches over in place, as the modes do
+    let mut new = old.clone();
+    new.wled_devices = vec![loopback_device(&new_capture, 0, 10)];
+    let delta = ConfigDelta::between(&old, &new).unwrap();
+    assert!(delta.changed_any(config::DEVICE_CONFIG_KEYS));
+    manager.reconfigure(MultiDeviceConfig::from_config(delta.config().unwrap())).unwrap();
+    assert_eq!(receive(&mut old_capture), vec![0; 30]);  // Blanked on the way out
+
+    manager.send_frame(&[60; 30]).unwrap();
+    assert_eq!(receive(&mut new_capture), vec![60; 30]);
+    assert!(old_capture.recv_frame(Duration::from_millis(200)).unwrap().is_none());
+}
+
+#[test]
+fn test_device_hot_add_remove() {
+    let mut first = VirtualDevice::bind(0).unwrap();
+    let mut second = VirtualDevice::bind(0).unwrap();
+    let config = config_with(vec![loopback_device(&first, 0, 4)]);
+    let mut manager = manager_for(&config);
+    let frame: Vec<u8> = (1..=30).collect();
+
+    // Added next to the running device - the frame is re-sliced across both from the next send
+    let both = config_with(vec![loopback_device(&first, 0, 4), loopback_device(&second, 4, 6)]);
+    manager.reconfigure(MultiDeviceConfig::from_config(&both)).unwrap();
+    assert_eq!(manager.device_count(), 2);
+    manager.send_frame(&frame).unwrap();
+    assert_eq!(receive(&mut first), frame[..12]);
+    assert_eq!(receive(&mut second), frame[12..]);
+
+    // Disabled - blanked once, then left alone while the other keeps going
+    let mut disabled = both.clone();
+    disabled.wled_devices[0].enabled = false;
+    manager.reconfigure(MultiDeviceConfig::from_config(&disabled)).unwrap();
+    assert_eq!(receive(&mut first), vec![0; 12]);
+    manager.send_frame(&frame).unwrap();
+    assert_eq!(receive(&mut second), frame[12..]);
+    assert!(first.recv_frame(Duration::from_millis(200)).unwrap().is_none());
+
+    // An invalid setup is refused and the current one keeps running
+    let mut overlapping = both.clone();
+    overlapping.wled_devices[1].led_offset = 2;
+    assert!(manager.reconfigure(MultiDeviceConfig::from_config(&overlapping)).is_err());
+    manager.send_frame(&frame).unwrap();
+    assert_eq!(receive(&mut second), frame[12..]);
+}
+
+#[test]
+fn test_bandwidth_mode_brightness_reload() {
+    let mut capture = VirtualDevice::bind(0).unwrap();
+    let config = config_with(vec![loopback_device(&capture, 0, 60)]);
+
+    // Half of max bandwidth each way, so both halves are lit
+    let mut state = SharedRenderState::from_config(&config);
+    state.current_rx_kbps = state.max_bandwidth_kbps / 2.0;
+    state.current_tx_kbps = state.max_bandwidth_kbps / 2.0;
+    state.enable_interpolation = false;
+    state.global_brightness = 1.0;
+    let shared_state = Arc::new(RenderStateCell::new(state));
+    let shutdown = Arc::new(AtomicBool::new(false));
+    let renderer = Renderer::new(&config, shared_state.clone(), shutdown.clone()).unwrap();
+    let handle = thread::spawn(move || renderer.run());
+
+    let frame = receive(&mut capture);
+    assert_eq!(frame.len(), 60 * 3);
+    assert!(frame.iter().any(|&v| v > 0));
+
+    // Brightness changes reach the running renderer without a restart
+    shared_state.write().global_brightness = 0.25;
+    for _ in 0..5 {
+        receive(&mut capture);  // Frames already on their way at full brightness
+    }
+    let dimmed = receive(&mut capture);
+    assert_eq!(dimmed.len(), 60 * 3);
+    assert!(dimmed.iter().all(|&v| v <= quantize(255.0 * 0.25)));
+
+    shutdown.store(true, Ordering::Relaxed);
+    handle.join().unwrap();
+}
@@ -0,0 +1,216 @@
+// Post FX Module - Optional blur/bloom/mirror stage applied to every mode's frame before it's sent
+use arc_swap::ArcSwap;
+use std::sync::{Arc, OnceLock};
+use tokio::sync::broadcast;
+
+use crate::config::{self, BandwidthConfig, ConfigDelta, PostFxConfig};
+
+// Settings for the running mode (None = no effects) - swapped whole on config changes, read by the send path
+fn settings() -> &'static ArcSwap<Option<PostFxConfig>> {
+    static SETTINGS: OnceLock<ArcSwap<Option<PostFxConfig>>> = OnceLock::new();
+    SETTINGS.get_or_init(|| ArcSwap::from_pointee(None))
+}
+
+/// Pick the postfx entry for the configured mode (falling back to the "" entry) and make it live
+pub fn configure(config: &BandwidthConfig) {
+    let entry = config.postfx.iter().find(|fx| fx.mode == config.mode)
+        .or_else(|| config.postfx.iter().find(|fx| fx.mode.is_empty()))
+        .filter(|fx| fx.blur_radius > 0.0 || fx.bloom_strength > 0.0 || fx.mirror != "off")
+        .cloned();
+    settings().store(Arc::new(entry));
+}
+
+/// Whether the running mode has any effects enabled
+pub fn enabled() -> bool {
+    settings().load().is_some()
+}
+
+/// Keep the live settings in step with config changes (web UI, API, or file edits)
+pub fn spawn_watcher(config_change_tx: broadcast::Sender<ConfigDelta>) {
+    config::spawn_config_listener(&config_change_tx, &["postfx", "mode"], configure);
+}
+
+/// Effect processor with its scratch buffers and cached kernels - owned by the output path
+#[derive(Default)]
+pub struct PostFx {
+    blur_kernel: Vec<f32>,
+    blur_sigma: f64,  // Sigma blur_kernel was built for
+    bloom_kernel: Vec<f32>,
+    bloom_sigma: f64,
+    bright: Vec<f32>,  // Bloom source (pixels above the threshold)
+    temp: Vec<f32>,  // Intermediate pass of the separable blur
+}
+
+impl PostFx {
+    /// Apply the running mode's effects to a float RGB frame (0.0-255.0) in place
+    pub fn apply(&mut self, frame: &mut [f32]) {
+        let guard = settings().load();
+        if let Some(fx) = &**guard {
+            self.apply_with(fx, frame);
+        }
+    }
+
+    pub fn apply_with(&mut self, fx: &PostFxConfig, frame: &mut [f32]) {
+        let pixels = frame.len() / 3;
+        if pixels == 0 {
+            return;
+        }
+
+        // Frames that don't fit the configured matrix are treated as a strip
+        let (width, height) = if fx.width > 0 && pixels % fx.width == 0 {
+            (fx.width, pixels / fx.width)
+        } else {
+            (pixels, 1)
+        };
+
+        if fx.blur_radius > 0.0 {
+            if fx.blur_radius != self.blur_sigma {
+                gaussian_kernel(fx.blur_radius as f32, &mut self.blur_kernel);
+                self.blur_sigma = fx.blur_radius;
+            }
+            blur(frame, width, height, &self.blur_kernel, &mut self.temp);
+        }
+
+        if fx.bloom_strength > 0.0 {
+            if fx.bloom_radius != self.bloom_sigma {
+                gaussian_kernel(fx.bloom_radius as f32, &mut self.bloom_kernel);
+                self.bloom_sigma = fx.bloom_radius;
+            }
+
+            // Only the part of each channel above the threshold glows
+            let threshold = fx.bloom_threshold as f32;
+            self.bright.clear();
+            self.bright.extend(frame.iter().map(|&v| (v - threshold).max(0.0)));
+            blur(&mut self.bright, width, height, &self.bloom_kernel, &mut self.temp);
+
+            let strength = fx.bloom_strength as f32;
+            for (value, glow) in frame.iter_mut().zip(&self.bright) {
+                *value = (*value + glow * strength).min(255.0);
+            }
+        }
+
+        if fx.mirror == "horizontal" || fx.mirror == "both" {
+            for y in 0..height {
+                let row = y * width;
+                for x in width.div_ceil(2)..width {
+                    copy_pixel(frame, row + width - 1 - x, row + x);
+                }
+            }
+        }
+        if (fx.mirror == "vertical" || fx.mirror == "both") && height > 1 {
+            for y in height.div_ceil(2)..height {
+                let (src, dst) = ((height - 1 - y) * width * 3, y * width * 3);
+                frame.copy_within(src..src + width * 3, dst);
+            }
+        }
+    }
+}
+
+fn copy_pixel(frame: &mut [f32], src: usize, dst: usize) {
+    frame.copy_within(src * 3..src * 3 + 3, dst * 3);
+}
+
+// Normalized 1D gaussian, 3 sigma each side
+fn gaussian_kernel(sigma: f32, kernel: &mut Vec<f32>) {
+    let radius = (sigma * 3.0).ceil().max(1.0) as i32;
+    kernel.clear();
+    kernel.extend((-radius..=radius).map(|i| (-((i * i) as f32) / (2.0 * sigma * sigma)).exp()));
+    let sum: f32 = kernel.iter().sum();
+    for weight in kernel.iter_mut() {
+        *weight /= sum;
+    }
+}
+
+// Separable gaussian blur of a width x height RGB image, clamping at the edges
+fn blur(image: &mut [f32], width: usize, height: usize, kernel: &[f32], temp: &mut Vec<f32>) {
+    let radius = (kernel.len() / 2) as isize;
+    temp.clear();
+    temp.resize(image.len(), 0.0);
+
+    // Horizontal pass: image -> temp
+    for y in 0..height {
+        for x in 0..width {
+            let mut acc = [0.0f32; 3];
+            for (k, weight) in kernel.iter().enumerate() {
+                let sx = (x as isize + k as isize - radius).clamp(0, width as isize - 1) as usize;
+                let i = (y * width + sx) * 3;
+                acc[0] += image[i] * weight;
+                acc[1] += image[i + 1] * weight;
+                acc[2] += image[i + 2] * weight;
+            }
+            let o = (y * width + x) * 3;
+            temp[o..o + 3].copy_from_slice(&acc);
+        }
+    }
+
+    if height == 1 {
+        image.copy_from_slice(temp);
+        return;
+    }
+
+    // Vertical pass: temp -> image
+    for y in 0..height {
+        for x in 0..width {
+            let mut acc = [0.0f32; 3];
+            for (k, weight) in kernel.iter().enumerate() {
+                let sy = (y as isize + k as isize - radius).clamp(0, height as isize - 1) as usize;
+                let i = (sy * width + x) * 3;
+                acc[0] += temp[i] * weight;
+                acc[1] += temp[i + 1] * weight;
+                acc[2] += temp[i + 2] * weight;
+            }
+            let o = (y * width + x) * 3;
+            image[o..o + 3].copy_from_slice(&acc);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fx(mirror: &str) -> PostFxConfig {
+        PostFxConfig { mirror: mirror.to_string(), ..PostFxConfig::default() }
+    }
+
+    #[test]
+    fn test_blur_spreads_and_keeps_energy() {
+        let mut frame = vec![0.0f32; 9 * 3];
+        frame[4 * 3] = 255.0;
+        let settings = PostFxConfig { blur_radius: 1.0, ..PostFxConfig::default() };
+        PostFx::default().apply_with(&settings, &mut frame);
+
+        let reds: Vec<f32> = frame.chunks(3).map(|p| p[0]).collect();
+        assert!(reds[4] < 255.0 && reds[3] > 0.0 && reds[5] > 0.0);
+        assert!((reds[3] - reds[5]).abs() < 1e-3);
+        assert!((reds.iter().sum::<f32>() - 255.0).abs() < 0.1);
+    }
+
+    #[test]
+    fn test_bloom_only_from_bright_pixels() {
+        let mut frame = vec![0.0f32; 5 * 3];
+        frame[0] = 100.0;  // Below threshold - no glow
+        frame[4 * 3] = 255.0;
+        let settings = PostFxConfig { bloom_strength: 1.0, bloom_threshold: 200.0, bloom_radius: 1.0, ..PostFxConfig::default() };
+        PostFx::default().apply_with(&settings, &mut frame);
+
+        assert_eq!(frame[0], 100.0);
+        assert!(frame[3 * 3] > 0.0);
+        assert_eq!(frame[4 * 3], 255.0);
+    }
+
+    #[test]
+    fn test_mirror() {
+        let mut strip: Vec<f32> = (0..5).flat_map(|i| [i as f32; 3]).collect();
+        PostFx::default().apply_with(&fx("horizontal"), &mut strip);
+        let reds: Vec<f32> = strip.chunks(3).map(|p| p[0]).collect();
+        assert_eq!(reds, vec![0.0, 1.0, 2.0, 1.0, 0.0]);
+
+        // 2x2 matrix, vertical: the top row is copied onto the bottom row
+        let mut matrix: Vec<f32> = (0..4).flat_map(|i| [i as f32; 3]).collect();
+        let settings = PostFxConfig { width: 2, ..fx("vertical") };
+        PostFx::default().apply_with(&settings, &mut matrix);
+        let reds: Vec<f32> = matrix.chunks(3).map(|p| p[0]).collect();
+        assert_eq!(reds, vec![0.0, 1.0, 0.0, 1.0]);
+    }
+}
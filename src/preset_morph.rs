@@ -0,0 +1,225 @@
+// Preset Morph Module - MIDI notes outside the playing range that morph the look to a saved preset over a few
+// seconds: numbers and colors are blended step by step, everything else switches halfway through
+use anyhow::Result;
+use serde_json::{json, Map, Value};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, Instant};
+use tokio::sync::broadcast;
+
+use crate::cli;
+use crate::config::{self, BandwidthConfig, ConfigDelta};
+use crate::sync;
+
+// Morph steps per second - each one reaches the running mode like any other config change
+const STEPS_PER_SEC: f64 = 20.0;
+
+#[derive(Clone)]
+struct MorphSettings {
+    note_a: u8,
+    note_b: u8,
+    preset_a: String,
+    preset_b: String,
+    seconds: f64,
+}
+
+static SETTINGS: Mutex<Option<MorphSettings>> = Mutex::new(None);
+
+// Bumped by every trigger - a morph stops as soon as a newer one starts
+static GENERATION: AtomicU64 = AtomicU64::new(0);
+
+// Where the running morph has got to (None when no morph is running)
+static CURRENT: Mutex<Option<BandwidthConfig>> = Mutex::new(None);
+
+/// Take the morph notes, presets and duration from the config
+pub fn configure(config: &BandwidthConfig) {
+    *SETTINGS.lock().unwrap() = Some(MorphSettings {
+        note_a: config.midi.morph_note_a,
+        note_b: config.midi.morph_note_b,
+        preset_a: config.midi.morph_preset_a.clone(),
+        preset_b: config.midi.morph_preset_b.clone(),
+        seconds: config.midi.morph_seconds,
+    });
+}
+
+/// Start a morph if `note` is mapped to a preset - true when it was a trigger note (and shouldn't light up)
+pub fn trigger(note: u8, config_change_tx: &broadcast::Sender<ConfigDelta>) -> bool {
+    let Some(settings) = SETTINGS.lock().unwrap().clone() else {
+        return false;
+    };
+    let preset = if note == settings.note_a {
+        settings.preset_a
+    } else if note == settings.note_b {
+        settings.preset_b
+    } else {
+        return false;
+    };
+    if preset.is_empty() {
+        return false;
+    }
+
+    let generation = GENERATION.fetch_add(1, Ordering::SeqCst) + 1;
+    let duration = Duration::from_secs_f64(settings.seconds);
+    let config_change_tx = config_change_tx.clone();
+    thread::spawn(move || {
+        if let Err(e) = run_morph(generation, &preset, duration, &config_change_tx) {
+            eprintln!("⚠️  Preset morph to '{}' failed: {}", preset, e);
+        }
+    });
+    true
+}
+
+fn run_morph(generation: u64, preset: &str, duration: Duration, config_change_tx: &broadcast::Sender<ConfigDelta>) -> Result<()> {
+    let target = cli::load_preset(preset)?;
+    // Pick up from wherever an interrupted morph left off
+    let start = match CURRENT.lock().unwrap().clone() {
+        Some(current) => current,
+        None => BandwidthConfig::load()?,
+    };
+    let from = config::config_key_map(&start);
+    let to = config::config_key_map(&target);
+    println!("🎛️  Morphing to preset '{}' over {:.1}s", preset, duration.as_secs_f64());
+
+    let started = Instant::now();
+    let mut previous = start;
+    loop {
+        if GENERATION.load(Ordering::SeqCst) != generation {
+            return Ok(());
+        }
+        let progress = if duration.is_zero() {
+            1.0
+        } else {
+            (started.elapsed().as_secs_f64() / duration.as_secs_f64()).min(1.0)
+        };
+        let step: BandwidthConfig = serde_json::from_value(Value::Object(blend_key_maps(&from, &to, progress)))?;
+        if let Some(delta) = ConfigDelta::between(&previous, &step) {
+            let _ = config_change_tx.send(delta);
+        }
+        *CURRENT.lock().unwrap() = Some(step.clone());
+        previous = step;
+        if progress >= 1.0 {
+            break;
+        }
+        thread::sleep(Duration::from_secs_f64(1.0 / STEPS_PER_SEC));
+    }
+
+    // Keep the result - only the morphed keys are written, anything else edited meanwhile stays
+    let file = BandwidthConfig::load()?;
+    let mut map = config::config_key_map(&file);
+    for (key, value) in config::config_key_map(&previous) {
+        if morphable(&key) {
+            map.insert(key, value);
+        }
+    }
+    let mut result: BandwidthConfig = serde_json::from_value(Value::Object(map))?;
+    result.config_path = file.config_path;
+    result.save()?;
+
+    let mut current = CURRENT.lock().unwrap();
+    if GENERATION.load(Ordering::SeqCst) == generation {
+        *current = None;
+    }
+    Ok(())
+}
+
+// The mode, MIDI settings, and device-local settings (outputs, server, sync) are left alone
+fn morphable(key: &str) -> bool {
+    key != "mode" && !key.starts_with("midi_") && !config::DEVICE_CONFIG_KEYS.contains(&key) && !sync::is_local_key(key)
+}
+
+// Config key map `progress` of the way (0.0-1.0) from `from` to `to`
+fn blend_key_maps(from: &Map<String, Value>, to: &Map<String, Value>, progress: f64) -> Map<String, Value> {
+    from.iter()
+        .map(|(key, a)| {
+            let value = match to.get(key) {
+                Some(b) if morphable(key) => blend_value(a, b, progress),
+                _ => a.clone(),
+            };
+            (key.clone(), value)
+        })
+        .collect()
+}
+
+fn blend_value(a: &Value, b: &Value, progress: f64) -> Value {
+    match (a, b) {
+        (Value::Number(x), Value::Number(y)) => {
+            let (fx, fy) = (x.as_f64().unwrap_or(0.0), y.as_f64().unwrap_or(0.0));
+            let mixed = fx + (fy - fx) * progress;
+            if x.is_f64() || y.is_f64() {
+                json!(mixed)
+            } else if x.is_u64() && y.is_u64() {
+                json!(mixed.round() as u64)
+            } else {
+                json!(mixed.round() as i64)
+            }
+        }
+        (Value::String(x), Value::String(y)) if x != y => match blend_colors(x, y, progress) {
+            Some(mixed) => Value::String(mixed),
+            None => if progress < 0.5 { a.clone() } else { b.clone() },
+        },
+        _ => if progress < 0.5 { a.clone() } else { b.clone() },
+    }
+}
+
+// Blend two hex color lists of the same length (None if either isn't one)
+fn blend_colors(from: &str, to: &str, progress: f64) -> Option<String> {
+    let parse = |list: &str| -> Option<Vec<[u8; 3]>> {
+        list.split(',')
+            .map(|hex| {
+                let hex = hex.trim().trim_start_matches('#');
+                if hex.len() != 6 || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+                    return None;
+                }
+                let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).ok();
+                Some([channel(0)?, channel(2)?, channel(4)?])
+            })
+            .collect()
+    };
+    let (from, to) = (parse(from)?, parse(to)?);
+    if from.len() != to.len() {
+        return None;
+    }
+
+    let mix = |a: u8, b: u8| (a as f64 + (b as f64 - a as f64) * progress).round() as u8;
+    Some(from.iter().zip(&to)
+        .map(|(a, b)| format!("{:02X}{:02X}{:02X}", mix(a[0], b[0]), mix(a[1], b[1]), mix(a[2], b[2])))
+        .collect::<Vec<_>>()
+        .join(","))
+}
+
+/// Keep the morph settings in step with config changes (web UI, API, or file edits)
+pub fn spawn_watcher(config_change_tx: broadcast::Sender<ConfigDelta>) {
+    config::spawn_config_listener(&config_change_tx, &["midi_morph_*"], configure);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_blend_key_maps() {
+        let from = json!({ "fps": 30.0, "total_leds": 100, "color": "000000,FF0000", "vu_scale": "linear", "mode": "live", "midi_gradient": false });
+        let to = json!({ "fps": 60.0, "total_leds": 300, "color": "FFFFFF,0000FF", "vu_scale": "db", "mode": "tron", "midi_gradient": true });
+        let (Value::Object(from), Value::Object(to)) = (from, to) else { unreachable!() };
+
+        let halfway = blend_key_maps(&from, &to, 0.5);
+        assert_eq!(halfway["fps"], json!(45.0));
+        assert_eq!(halfway["color"], json!("808080,800080"));
+        assert_eq!(halfway["vu_scale"], json!("db"));
+        assert_eq!(blend_key_maps(&from, &to, 0.25)["vu_scale"], json!("linear"));
+
+        // Device, mode and MIDI settings never morph
+        assert_eq!(halfway["total_leds"], json!(100));
+        assert_eq!(halfway["mode"], json!("live"));
+        assert_eq!(halfway["midi_gradient"], json!(false));
+    }
+
+    #[test]
+    fn test_blend_colors() {
+        assert_eq!(blend_colors("#000000", "FFFFFF", 1.0), Some("FFFFFF".to_string()));
+        assert_eq!(blend_colors("000000", "FF0000,00FF00", 0.5), None);
+        assert_eq!(blend_colors("Rainbow", "FF0000", 0.5), None);
+        assert_eq!(blend_colors("6500K", "FF0000", 0.5), None);
+    }
+}
@@ -0,0 +1,191 @@
+// Quiet Hours Module - Caps output brightness during set times of day (e.g. overnight), whatever the
+// mode, API or global_brightness ask for - applied in the send path, like the room-light scale
+use std::process::Command;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tokio::sync::broadcast;
+
+use crate::config::{self, BandwidthConfig, ConfigDelta};
+
+// How often the clock is checked against the windows
+const CHECK_INTERVAL: Duration = Duration::from_secs(5);
+
+// Brightness cap in force (f64 bits) - anything above 1.0 means outside quiet hours
+static CAP: AtomicU64 = AtomicU64::new(0x4000_0000_0000_0000);  // 2.0
+const NO_CAP: f64 = 2.0;
+
+static SETTINGS: Mutex<Option<QuietSettings>> = Mutex::new(None);
+
+// Last local UTC offset found, in seconds - used if it can't be looked up
+static UTC_OFFSET: Mutex<Option<i64>> = Mutex::new(None);
+
+#[derive(Debug, Clone, PartialEq)]
+struct QuietSettings {
+    windows: Vec<(u32, u32)>,  // (start, end) in minutes after local midnight - end before start wraps past midnight
+    brightness: f64,
+}
+
+// "22:30" -> minutes after midnight ("24:00" is allowed as an end time)
+fn parse_time(text: &str) -> Option<u32> {
+    let (hours, minutes) = text.trim().split_once(':')?;
+    let (hours, minutes): (u32, u32) = (hours.trim().parse().ok()?, minutes.trim().parse().ok()?);
+    match (hours, minutes) {
+        (24, 0) => Some(24 * 60),
+        (0..=23, 0..=59) => Some(hours * 60 + minutes),
+        _ => None,
+    }
+}
+
+// "22:00-07:00,12:00-14:00" -> windows, skipping any that don't parse
+fn parse_windows(spec: &str) -> Vec<(u32, u32)> {
+    spec.split(',')
+        .filter_map(|window| {
+            let (start, end) = window.split_once('-')?;
+            Some((parse_time(start)?, parse_time(end)?))
+        })
+        .filter(|(start, end)| start != end)
+        .collect()
+}
+
+/// Quiet hours in their canonical "HH:MM-HH:MM,..." form, with invalid windows dropped
+pub fn normalize(spec: &str) -> String {
+    let time = |minutes: u32| format!("{:02}:{:02}", minutes / 60, minutes % 60);
+    parse_windows(spec).iter().map(|&(start, end)| format!("{}-{}", time(start), time(end))).collect::<Vec<_>>().join(",")
+}
+
+fn in_window(minute: u32, (start, end): (u32, u32)) -> bool {
+    if start < end {
+        minute >= start && minute < end
+    } else {
+        minute >= start || minute < end
+    }
+}
+
+/// Take the quiet hours from the config - none set lifts the cap at once
+pub fn configure(config: &BandwidthConfig) {
+    let windows = parse_windows(&config.quiet_hours);
+    let settings = (!windows.is_empty()).then(|| QuietSettings { windows, brightness: config.quiet_hours_brightness });
+    *SETTINGS.lock().unwrap() = settings;
+    check();
+}
+
+/// Cap a brightness override during quiet hours - frames sent without one are capped too
+pub fn cap(brightness: Option<f64>) -> Option<f64> {
+    let cap = f64::from_bits(CAP.load(Ordering::Relaxed));
+    if cap > 1.0 {
+        return brightness;
+    }
+    Some(brightness.map_or(cap, |b| b.min(cap)))
+}
+
+/// Whether quiet hours are in force right now
+pub fn active() -> bool {
+    f64::from_bits(CAP.load(Ordering::Relaxed)) <= 1.0
+}
+
+// Set the cap for the current local time
+fn check() {
+    let settings = SETTINGS.lock().unwrap().clone();
+    let cap = match settings {
+        Some(settings) => {
+            let minute = local_minute();
+            if settings.windows.iter().any(|&window| in_window(minute, window)) { settings.brightness } else { NO_CAP }
+        }
+        None => NO_CAP,
+    };
+    CAP.store(cap.to_bits(), Ordering::Relaxed);
+}
+
+// Minutes after local midnight
+fn local_minute() -> u32 {
+    let utc = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs() as i64).unwrap_or(0);
+    ((utc + utc_offset()).rem_euclid(86400) / 60) as u32
+}
+
+// Local UTC offset in seconds, looked up on every check so daylight saving changes apply at once
+// time won't read the zone while other threads are running on some platforms (Linux) - date is asked
+// then, and failing that the last offset found is kept (UTC if there never was one)
+fn utc_offset() -> i64 {
+    let offset = time::UtcOffset::current_local_offset()
+        .map(|offset| offset.whole_seconds() as i64)
+        .ok()
+        .or_else(|| {
+            Command::new("date")
+                .arg("+%z")
+                .output()
+                .ok()
+                .and_then(|output| parse_utc_offset(&String::from_utf8_lossy(&output.stdout)))
+        });
+
+    let mut last = UTC_OFFSET.lock().unwrap();
+    match offset {
+        Some(offset) => {
+            *last = Some(offset);
+            offset
+        }
+        None => last.unwrap_or_else(|| {
+            eprintln!("⚠️  Couldn't find the local time zone - quiet hours are in UTC");
+            *last = Some(0);
+            0
+        }),
+    }
+}
+
+// "+0200" / "-0530" -> seconds
+fn parse_utc_offset(text: &str) -> Option<i64> {
+    let text = text.trim();
+    let (sign, digits) = if let Some(digits) = text.strip_prefix('+') {
+        (1, digits)
+    } else if let Some(digits) = text.strip_prefix('-') {
+        (-1, digits)
+    } else {
+        return None;
+    };
+    if digits.len() != 4 || !digits.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    let hours: i64 = digits[0..2].parse().ok()?;
+    let minutes: i64 = digits[2..4].parse().ok()?;
+    Some(sign * (hours * 3600 + minutes * 60))
+}
+
+/// Check the clock against the windows in the background
+pub fn spawn_clock() {
+    thread::spawn(|| loop {
+        thread::sleep(CHECK_INTERVAL);
+        check();
+    });
+}
+
+/// Keep the quiet hours in step with config changes (web UI, API, or file edits)
+pub fn spawn_watcher(config_change_tx: broadcast::Sender<ConfigDelta>) {
+    config::spawn_config_listener(&config_change_tx, &["quiet_hours", "quiet_hours_brightness"], configure);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_windows() {
+        assert_eq!(parse_windows("22:00-07:00, 12:30-14:00"), [(1320, 420), (750, 840)]);
+        assert_eq!(normalize(" 9:05-24:00,bogus,25:00-01:00,10:00-10:00"), "09:05-24:00");
+
+        // Overnight windows wrap past midnight
+        assert!(in_window(23 * 60, (1320, 420)));
+        assert!(in_window(3 * 60, (1320, 420)));
+        assert!(!in_window(7 * 60, (1320, 420)));
+        assert!(!in_window(12 * 60, (1320, 420)));
+        assert!(in_window(13 * 60, (750, 840)));
+    }
+
+    #[test]
+    fn test_parse_utc_offset() {
+        assert_eq!(parse_utc_offset("+0200\n"), Some(7200));
+        assert_eq!(parse_utc_offset("-0530"), Some(-19800));
+        assert_eq!(parse_utc_offset("UTC"), None);
+        assert_eq!(parse_utc_offset(""), None);
+    }
+}
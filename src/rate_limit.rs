@@ -0,0 +1,133 @@
+// Rate Limit Module - Per-IP request limiting and login lockout for the HTTP server
+use std::collections::HashMap;
+use std::net::IpAddr;
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+// Request counts are kept per fixed one-minute window
+const RATE_WINDOW: Duration = Duration::from_secs(60);
+
+// Failed logins older than this no longer count towards a lockout
+const FAILURE_RESET: Duration = Duration::from_secs(15 * 60);
+
+// Prune expired entries once a table grows past this many IPs
+const PRUNE_THRESHOLD: usize = 1024;
+
+struct RequestWindow {
+    start: Instant,
+    count: u32,
+}
+
+struct LoginFailures {
+    count: u32,
+    last_failure: Instant,
+    locked_until: Option<Instant>,
+}
+
+fn request_windows() -> &'static Mutex<HashMap<IpAddr, RequestWindow>> {
+    static WINDOWS: OnceLock<Mutex<HashMap<IpAddr, RequestWindow>>> = OnceLock::new();
+    WINDOWS.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+fn login_failures() -> &'static Mutex<HashMap<IpAddr, LoginFailures>> {
+    static FAILURES: OnceLock<Mutex<HashMap<IpAddr, LoginFailures>>> = OnceLock::new();
+    FAILURES.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// Count a request from an IP - returns the time until the window resets if it is over the limit
+/// per_minute = 0 disables the limit
+pub fn check_request(ip: IpAddr, per_minute: u32) -> Option<Duration> {
+    if per_minute == 0 {
+        return None;
+    }
+
+    let now = Instant::now();
+    let mut windows = request_windows().lock().unwrap();
+    if windows.len() > PRUNE_THRESHOLD {
+        windows.retain(|_, w| now.duration_since(w.start) < RATE_WINDOW);
+    }
+
+    let window = windows.entry(ip).or_insert(RequestWindow { start: now, count: 0 });
+    if now.duration_since(window.start) >= RATE_WINDOW {
+        window.start = now;
+        window.count = 0;
+    }
+
+    window.count += 1;
+    if window.count > per_minute {
+        Some(RATE_WINDOW.saturating_sub(now.duration_since(window.start)))
+    } else {
+        None
+    }
+}
+
+/// Time left on an IP's login lockout (None if it may try to log in)
+pub fn login_lockout_remaining(ip: IpAddr) -> Option<Duration> {
+    let now = Instant::now();
+    let failures = login_failures().lock().unwrap();
+    failures.get(&ip)
+        .and_then(|f| f.locked_until)
+        .filter(|&until| until > now)
+        .map(|until| until - now)
+}
+
+/// Record a failed login - returns true if this failure locked the IP out
+pub fn record_login_failure(ip: IpAddr, max_attempts: u32, lockout: Duration) -> bool {
+    let now = Instant::now();
+    let mut failures = login_failures().lock().unwrap();
+    if failures.len() > PRUNE_THRESHOLD {
+        failures.retain(|_, f| {
+            now.duration_since(f.last_failure) < FAILURE_RESET || f.locked_until.map(|u| u > now).unwrap_or(false)
+        });
+    }
+
+    let entry = failures.entry(ip).or_insert(LoginFailures { count: 0, last_failure: now, locked_until: None });
+    if now.duration_since(entry.last_failure) >= FAILURE_RESET {
+        entry.count = 0;
+    }
+    entry.count += 1;
+    entry.last_failure = now;
+
+    if entry.count >= max_attempts {
+        // Start counting again once the lockout ends
+        entry.count = 0;
+        entry.locked_until = Some(now + lockout);
+        true
+    } else {
+        false
+    }
+}
+
+/// Clear an IP's failed login history after a successful login
+pub fn record_login_success(ip: IpAddr) {
+    login_failures().lock().unwrap().remove(&ip);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_request_limit() {
+        let ip: IpAddr = "192.0.2.1".parse().unwrap();
+        for _ in 0..3 {
+            assert!(check_request(ip, 3).is_none());
+        }
+        assert!(check_request(ip, 3).is_some());
+        assert!(check_request("192.0.2.2".parse().unwrap(), 3).is_none());
+        assert!(check_request(ip, 0).is_none());
+    }
+
+    #[test]
+    fn test_login_lockout() {
+        let ip: IpAddr = "198.51.100.1".parse().unwrap();
+        let lockout = Duration::from_secs(60);
+        assert!(!record_login_failure(ip, 3, lockout));
+        assert!(!record_login_failure(ip, 3, lockout));
+        assert!(login_lockout_remaining(ip).is_none());
+        assert!(record_login_failure(ip, 3, lockout));
+        assert!(login_lockout_remaining(ip).is_some());
+        record_login_success(ip);
+        assert!(login_lockout_remaining(ip).is_none());
+    }
+}
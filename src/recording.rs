@@ -0,0 +1,133 @@
+// Recording Module - Capture output frames to a file and play them back
+//
+// File format: 8-byte magic, then per frame:
+//   u64 LE  microseconds since recording started
+//   f32 LE  brightness (negative = none)
+//   u32 LE  frame length in bytes
+//   [u8]    RGB frame data
+use anyhow::{anyhow, Result};
+use std::fs::File;
+use std::io::{BufReader, BufWriter, ErrorKind, Read, Write};
+use std::path::Path;
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+use crate::config::BandwidthConfig;
+use crate::multi_device::{MultiDeviceConfig, MultiDeviceManager};
+use crate::pacer;
+use crate::system;
+
+const MAGIC: &[u8; 8] = b"RWLREC01";
+
+struct Recorder {
+    writer: BufWriter<File>,
+    started: Instant,
+}
+
+fn recorder() -> &'static Mutex<Option<Recorder>> {
+    static RECORDER: OnceLock<Mutex<Option<Recorder>>> = OnceLock::new();
+    RECORDER.get_or_init(|| Mutex::new(None))
+}
+
+/// Start capturing every frame sent to the devices into a file
+pub fn start(path: &Path) -> Result<()> {
+    let mut writer = BufWriter::new(File::create(path)?);
+    writer.write_all(MAGIC)?;
+    *recorder().lock().unwrap() = Some(Recorder { writer, started: Instant::now() });
+    Ok(())
+}
+
+/// True while a recording is running
+pub fn active() -> bool {
+    recorder().lock().unwrap().is_some()
+}
+
+/// Append a frame if a recording is running (called from the send path)
+pub fn record_frame(frame: &[u8], brightness: Option<f64>) {
+    let mut guard = recorder().lock().unwrap();
+    let Some(rec) = guard.as_mut() else {
+        return;
+    };
+
+    let mut record = Vec::with_capacity(16 + frame.len());
+    record.extend_from_slice(&(rec.started.elapsed().as_micros() as u64).to_le_bytes());
+    record.extend_from_slice(&(brightness.map(|b| b as f32).unwrap_or(-1.0)).to_le_bytes());
+    record.extend_from_slice(&(frame.len() as u32).to_le_bytes());
+    record.extend_from_slice(frame);
+
+    // Stop recording on a write error (disk full etc.) rather than failing every frame
+    if rec.writer.write_all(&record).and_then(|_| rec.writer.flush()).is_err() {
+        *guard = None;
+    }
+}
+
+// Read one frame - None at a clean end of file
+fn read_frame(reader: &mut impl Read) -> Result<Option<(Duration, Option<f64>, Vec<u8>)>> {
+    let mut header = [0u8; 16];
+    match reader.read_exact(&mut header) {
+        Ok(()) => {}
+        Err(e) if e.kind() == ErrorKind::UnexpectedEof => return Ok(None),
+        Err(e) => return Err(e.into()),
+    }
+
+    let micros = u64::from_le_bytes(header[0..8].try_into()?);
+    let brightness = f32::from_le_bytes(header[8..12].try_into()?);
+    let len = u32::from_le_bytes(header[12..16].try_into()?) as usize;
+
+    let mut frame = vec![0u8; len];
+    reader.read_exact(&mut frame)?;
+    let brightness = if brightness < 0.0 { None } else { Some(brightness as f64) };
+    Ok(Some((Duration::from_micros(micros), brightness, frame)))
+}
+
+/// Play a recording to the configured devices with its original timing
+pub fn play(path: &Path, repeat: bool) -> Result<()> {
+    let config = BandwidthConfig::load()?;
+    let mut manager = MultiDeviceManager::new(MultiDeviceConfig::from_config(&config))?;
+
+    loop {
+        let mut reader = BufReader::new(File::open(path)?);
+        let mut magic = [0u8; 8];
+        reader.read_exact(&mut magic)?;
+        if &magic != MAGIC {
+            return Err(anyhow!("{} is not a rustwled recording", path.display()));
+        }
+
+        let started = Instant::now();
+        let mut frames = 0usize;
+        while let Some((at, brightness, frame)) = read_frame(&mut reader)? {
+            if system::shutdown_requested() {
+                return Ok(());
+            }
+            pacer::sleep_until(started + at);
+            let _ = manager.send_frame_with_brightness(&frame, brightness);
+            frames += 1;
+        }
+
+        println!("Played {} frames from {}", frames, path.display());
+        if !repeat {
+            return Ok(());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_read_frame() {
+        let mut data = Vec::new();
+        data.extend_from_slice(&1500u64.to_le_bytes());
+        data.extend_from_slice(&0.5f32.to_le_bytes());
+        data.extend_from_slice(&3u32.to_le_bytes());
+        data.extend_from_slice(&[1, 2, 3]);
+
+        let mut reader = &data[..];
+        let (at, brightness, frame) = read_frame(&mut reader).unwrap().unwrap();
+        assert_eq!(at, Duration::from_micros(1500));
+        assert_eq!(brightness, Some(0.5));
+        assert_eq!(frame, vec![1, 2, 3]);
+        assert!(read_frame(&mut reader).unwrap().is_none());
+    }
+}
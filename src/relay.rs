@@ -12,16 +12,22 @@ use ratatui::style::{Color, Style};
 use ratatui::text::{Line, Span};
 use ratatui::widgets::{Block, Borders, Paragraph};
 use ratatui::Terminal;
-use std::collections::VecDeque;
+use serde::Serialize;
+use std::collections::{HashMap, VecDeque};
 use std::io;
-use std::net::UdpSocket;
+use std::net::{Ipv4Addr, SocketAddr, UdpSocket};
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::mpsc;
 use std::sync::{Arc, Mutex};
 use std::time::{Duration, Instant};
-use crate::config::BandwidthConfig;
+use crate::artnet::{self, ArtnetPacket};
+use crate::boblight::BoblightServer;
+use crate::config::{self, ArtnetUniverseConfig, BandwidthConfig, RelayListenerConfig, RelayRemapRule};
+use crate::live_stats;
+use crate::sacn;
+use crate::system;
 use crate::types::ModeExitReason;
-use crate::multi_device::{MultiDeviceConfig, MultiDeviceManager, WLEDDevice};
+use crate::multi_device::{ColorCorrection, MultiDeviceConfig, MultiDeviceManager, WLEDDevice};
 
 /// Generate config info display for relay mode
 fn generate_relay_config_info(config: &BandwidthConfig) -> Vec<Line<'static>> {
@@ -46,6 +52,36 @@ fn generate_relay_config_info(config: &BandwidthConfig) -> Vec<Line<'static>> {
             Span::styled("Frame Size: ", Style::default().fg(Color::Cyan)),
             Span::raw(format!("{} bytes", config.relay_frame_width * config.relay_frame_height * 3)),
         ]),
+        Line::from(vec![
+            Span::styled("Extra Listeners: ", Style::default().fg(Color::Cyan)),
+            Span::raw(if config.relay_listeners.is_empty() {
+                "none".to_string()
+            } else {
+                config.relay_listeners.iter()
+                    .map(|l| format!("{}:{} ({}, {}x{} @ LED {}{})",
+                        l.listen_ip, l.listen_port, l.protocol, l.frame_width, l.frame_height, l.led_offset,
+                        if l.enabled { "" } else { ", disabled" }))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            }),
+        ]),
+        Line::from(vec![
+            Span::styled("Remap Rules: ", Style::default().fg(Color::Cyan)),
+            Span::raw(if config.relay_remap.is_empty() {
+                "none (pass-through)".to_string()
+            } else {
+                format!("{}", config.relay_remap.len())
+            }),
+        ]),
+        Line::from(vec![
+            Span::styled("Color Pipeline: ", Style::default().fg(Color::Cyan)),
+            Span::raw(if config.relay_color_pipeline {
+                format!("brightness {:.0}%, gamma {:.1}, order {}, correction {}",
+                    config.global_brightness * 100.0, config.gamma, config.color_order, config.color_correction)
+            } else {
+                "disabled (frames forwarded untouched)".to_string()
+            }),
+        ]),
         Line::from(vec![
             Span::styled("WLED IP: ", Style::default().fg(Color::Cyan)),
             Span::raw(format!("{}", config.wled_ip)),
@@ -57,18 +93,515 @@ fn generate_relay_config_info(config: &BandwidthConfig) -> Vec<Line<'static>> {
     ]
 }
 
-/// Run relay mode - listen for raw RGB24 frames on UDP and forward via DDP
+// DDP header layout (see http://www.3waylabs.com/ddp/)
+const DDP_HEADER_LEN: usize = 10;
+const DDP_VERSION_MASK: u8 = 0xC0;
+const DDP_VERSION_1: u8 = 0x40;
+const DDP_FLAG_TIMECODE: u8 = 0x10;
+const DDP_FLAG_PUSH: u8 = 0x01;
+
+/// Input protocol for a relay listener
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum RelayProtocol {
+    Raw,  // Raw RGB24 byte stream, split into frames by size
+    Ddp,  // DDP packets, frame completed by the push flag
+    Sacn,  // E1.31 universes, frame completed by the last universe in the range
+    Artnet,  // ArtDmx universes, frame completed by ArtSync (or the last mapped universe without sync)
+    Boblight,  // Boblight clients over TCP, one light per LED, frame completed by sync
+}
+
+/// Where a relay listener's packets come from
+enum RelaySocket {
+    Udp(UdpSocket),
+    Boblight(BoblightServer),
+}
+
+/// Parse a DDP packet into (byte offset, pixel data, push flag)
+/// Returns None for packets that are too short or not DDP version 1
+pub fn parse_ddp_packet(packet: &[u8]) -> Option<(usize, &[u8], bool)> {
+    if packet.len() < DDP_HEADER_LEN {
+        return None;
+    }
+
+    let flags = packet[0];
+    if flags & DDP_VERSION_MASK != DDP_VERSION_1 {
+        return None;
+    }
+
+    let header_len = if flags & DDP_FLAG_TIMECODE != 0 { DDP_HEADER_LEN + 4 } else { DDP_HEADER_LEN };
+    if packet.len() < header_len {
+        return None;
+    }
+
+    let offset = u32::from_be_bytes([packet[4], packet[5], packet[6], packet[7]]) as usize;
+    let length = u16::from_be_bytes([packet[8], packet[9]]) as usize;
+    let data_end = (header_len + length).min(packet.len());
+
+    Some((offset, &packet[header_len..data_end], flags & DDP_FLAG_PUSH != 0))
+}
+
+/// Active relay input - a bound socket and its frame reassembly buffer
+struct RelayListener {
+    label: String,
+    socket: RelaySocket,
+    protocol: RelayProtocol,
+    frame_size: usize,
+    led_offset: usize,
+    universe: u16,  // First sACN universe (sACN only)
+    artnet_universes: Vec<ArtnetUniverseConfig>,  // Universe to LED range mapping (Art-Net only)
+    last_sync: Option<Instant>,  // Last ArtSync - frames wait for the next sync while syncs keep coming
+    buffer: Vec<u8>,
+    frame_ready: bool,
+    last_receive_time: Instant,
+}
+
+impl RelayListener {
+    /// Bind a listener - Art-Net uses `artnet_universes` when given, otherwise consecutive universes from config.universe
+    fn bind(config: &RelayListenerConfig, artnet_universes: &[ArtnetUniverseConfig]) -> Result<Self> {
+        let protocol = match config.protocol.as_str() {
+            "ddp" => RelayProtocol::Ddp,
+            "sacn" => RelayProtocol::Sacn,
+            "artnet" => RelayProtocol::Artnet,
+            "boblight" => RelayProtocol::Boblight,
+            _ => RelayProtocol::Raw,
+        };
+        let frame_size = config.frame_width * config.frame_height * 3;
+
+        let address = format!("{}:{}", config.listen_ip, config.listen_port);
+        let socket = if protocol == RelayProtocol::Boblight {
+            RelaySocket::Boblight(BoblightServer::bind(&address, frame_size / 3)?)
+        } else {
+            let socket = UdpSocket::bind(&address)?;
+            socket.set_nonblocking(true)?;
+            RelaySocket::Udp(socket)
+        };
+
+        // Consoles usually multicast each universe to its own group - unicast works without joining
+        if let (RelayProtocol::Sacn, RelaySocket::Udp(socket)) = (protocol, &socket) {
+            let interface = config.listen_ip.parse().unwrap_or(Ipv4Addr::UNSPECIFIED);
+            for universe in (config.universe..).take(sacn::universe_count(frame_size / 3)) {
+                if let Err(e) = socket.join_multicast_v4(&sacn::multicast_addr(universe), &interface) {
+                    eprintln!("⚠️  Could not join sACN multicast for universe {}: {}", universe, e);
+                }
+            }
+        }
+
+        // Raw streams accumulate bytes, DDP, sACN, Art-Net and boblight data is written in place at its offset
+        let buffer = match protocol {
+            RelayProtocol::Raw => Vec::with_capacity(frame_size),
+            RelayProtocol::Ddp | RelayProtocol::Sacn | RelayProtocol::Artnet | RelayProtocol::Boblight => vec![0u8; frame_size],
+        };
+
+        Ok(RelayListener {
+            label: format!("{}:{} ({})", config.listen_ip, config.listen_port, config.protocol),
+            socket,
+            protocol,
+            frame_size,
+            led_offset: config.led_offset,
+            universe: config.universe,
+            artnet_universes: artnet::universe_map(artnet_universes, config.universe, frame_size / 3),
+            last_sync: None,
+            buffer,
+            frame_ready: false,
+            last_receive_time: Instant::now(),
+        })
+    }
+
+    /// Read one pending packet into the reassembly buffer
+    /// Returns the packet source and size, or Ok(None) if nothing was waiting on the socket
+    fn recv(&mut self, packet_buf: &mut [u8]) -> io::Result<Option<(SocketAddr, usize)>> {
+        let socket = match &mut self.socket {
+            RelaySocket::Udp(socket) => socket,
+            RelaySocket::Boblight(server) => {
+                // Clients set lights one line at a time - sync completes the frame
+                let (received, synced) = server.poll(&mut self.buffer)?;
+                self.frame_ready |= synced;
+                if received.is_some() {
+                    self.last_receive_time = Instant::now();
+                }
+                return Ok(received);
+            }
+        };
+        match socket.recv_from(packet_buf) {
+            Ok((size, src)) => {
+                let packet = &packet_buf[..size];
+                match self.protocol {
+                    RelayProtocol::Raw => self.buffer.extend_from_slice(packet),
+                    RelayProtocol::Ddp => {
+                        if let Some((offset, data, push)) = parse_ddp_packet(packet) {
+                            if offset < self.frame_size {
+                                let end = (offset + data.len()).min(self.frame_size);
+                                self.buffer[offset..end].copy_from_slice(&data[..end - offset]);
+                            }
+                            if push {
+                                self.frame_ready = true;
+                            }
+                        }
+                    }
+                    RelayProtocol::Sacn => {
+                        if let Some((universe, data)) = sacn::parse_packet(packet) {
+                            let index = universe.wrapping_sub(self.universe) as usize;
+                            let offset = index * sacn::LEDS_PER_UNIVERSE * 3;
+                            if universe >= self.universe && offset < self.frame_size {
+                                let end = (offset + data.len().min(sacn::LEDS_PER_UNIVERSE * 3)).min(self.frame_size);
+                                self.buffer[offset..end].copy_from_slice(&data[..end - offset]);
+                            }
+                            // Consoles send universes in order each refresh
+                            if index + 1 == sacn::universe_count(self.frame_size / 3) {
+                                self.frame_ready = true;
+                            }
+                        }
+                    }
+                    RelayProtocol::Artnet => match artnet::parse_packet(packet) {
+                        Some(ArtnetPacket::Dmx { universe, data }) => {
+                            for range in self.artnet_universes.iter().filter(|r| r.universe == universe) {
+                                let offset = range.led_offset * 3;
+                                let len = data.len().min(range.led_count * 3);
+                                if offset < self.frame_size {
+                                    let end = (offset + len).min(self.frame_size);
+                                    self.buffer[offset..end].copy_from_slice(&data[..end - offset]);
+                                }
+                            }
+                            // Without ArtSync, the last mapped universe completes the frame
+                            let synced = matches!(self.last_sync, Some(at) if at.elapsed() < artnet::SYNC_TIMEOUT);
+                            if !synced && self.artnet_universes.last().map(|r| r.universe) == Some(universe) {
+                                self.frame_ready = true;
+                            }
+                        }
+                        Some(ArtnetPacket::Sync) => {
+                            self.last_sync = Some(Instant::now());
+                            self.frame_ready = true;
+                        }
+                        None => {}
+                    },
+                    RelayProtocol::Boblight => {}  // Handled by the boblight server above
+                }
+                self.last_receive_time = Instant::now();
+                Ok(Some((src, size)))
+            },
+            Err(e) if e.kind() == io::ErrorKind::WouldBlock => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Take the next complete frame, if one is available
+    fn next_frame(&mut self) -> Option<Vec<u8>> {
+        match self.protocol {
+            RelayProtocol::Raw if self.buffer.len() >= self.frame_size => {
+                // Extract exactly frame_size bytes for this frame
+                Some(self.buffer.drain(0..self.frame_size).collect())
+            },
+            RelayProtocol::Ddp | RelayProtocol::Sacn | RelayProtocol::Artnet | RelayProtocol::Boblight if self.frame_ready => {
+                self.frame_ready = false;
+                Some(self.buffer.clone())
+            },
+            _ => None,
+        }
+    }
+}
+
+/// Per-source packet counters exposed via the relay stats API
+#[derive(Debug, Clone, Serialize)]
+pub struct RelaySourceStats {
+    pub address: String,
+    pub listener: String,
+    pub packets: u64,
+    pub bytes: u64,
+    pub last_seen_ms: u64,  // Milliseconds since the last packet from this source
+}
+
+/// Point-in-time relay statistics (published by the relay loop, read by the HTTP API)
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct RelayStatsSnapshot {
+    pub active: bool,
+    pub uptime_secs: f64,
+    pub packets_received: u64,
+    pub bytes_received: u64,
+    pub frames_forwarded: u64,
+    pub fps: f64,
+    pub jitter_ms: f64,  // Smoothed inter-frame interval variation (RFC 3550 style)
+    pub sources: Vec<RelaySourceStats>,
+}
+
+/// Relay statistics shared between the relay loop and the HTTP server
+#[derive(Default)]
+pub struct RelayStats {
+    snapshot: Mutex<RelayStatsSnapshot>,
+}
+
+impl RelayStats {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn snapshot(&self) -> RelayStatsSnapshot {
+        self.snapshot.lock().unwrap().clone()
+    }
+
+    fn publish(&self, snapshot: RelayStatsSnapshot) {
+        *self.snapshot.lock().unwrap() = snapshot;
+    }
+
+    fn set_active(&self, active: bool) {
+        self.snapshot.lock().unwrap().active = active;
+    }
+}
+
+// Sources silent for this long are dropped from the stats, and at most this many are tracked -
+// senders on ephemeral ports would otherwise add an entry per restart for as long as the relay runs
+const SOURCE_STATS_WINDOW: Duration = Duration::from_secs(60);
+const MAX_TRACKED_SOURCES: usize = 64;
+
+/// Running counters owned by the relay loop
+struct RelayStatsTracker {
+    started: Instant,
+    packets_received: u64,
+    bytes_received: u64,
+    frames_forwarded: u64,
+    last_frame: Option<Instant>,
+    last_interval_ms: f64,
+    jitter_ms: f64,
+    sources: HashMap<SocketAddr, (String, u64, u64, Instant)>,  // listener, packets, bytes, last seen
+}
+
+impl RelayStatsTracker {
+    fn new() -> Self {
+        RelayStatsTracker {
+            started: Instant::now(),
+            packets_received: 0,
+            bytes_received: 0,
+            frames_forwarded: 0,
+            last_frame: None,
+            last_interval_ms: 0.0,
+            jitter_ms: 0.0,
+            sources: HashMap::new(),
+        }
+    }
+
+    fn record_packet(&mut self, listener: &str, src: SocketAddr, size: usize) {
+        self.packets_received += 1;
+        self.bytes_received += size as u64;
+        if !self.sources.contains_key(&src) && self.sources.len() >= MAX_TRACKED_SOURCES {
+            self.prune_sources();
+            // Still full of active sources - make room by dropping the one heard from longest ago
+            if self.sources.len() >= MAX_TRACKED_SOURCES {
+                if let Some(oldest) = self.sources.iter().min_by_key(|(_, entry)| entry.3).map(|(addr, _)| *addr) {
+                    self.sources.remove(&oldest);
+                }
+            }
+        }
+        let entry = self.sources.entry(src).or_insert_with(|| (listener.to_string(), 0, 0, Instant::now()));
+        entry.1 += 1;
+        entry.2 += size as u64;
+        entry.3 = Instant::now();
+    }
+
+    fn record_frame(&mut self) {
+        let now = Instant::now();
+        self.frames_forwarded += 1;
+        if let Some(last) = self.last_frame {
+            let interval_ms = now.duration_since(last).as_secs_f64() * 1000.0;
+            if self.frames_forwarded > 2 {
+                let deviation = (interval_ms - self.last_interval_ms).abs();
+                self.jitter_ms += (deviation - self.jitter_ms) / 16.0;
+            }
+            self.last_interval_ms = interval_ms;
+        }
+        self.last_frame = Some(now);
+    }
+
+    fn prune_sources(&mut self) {
+        self.sources.retain(|_, entry| entry.3.elapsed() < SOURCE_STATS_WINDOW);
+    }
+
+    fn snapshot(&mut self, fps: f64) -> RelayStatsSnapshot {
+        self.prune_sources();
+        let mut sources: Vec<RelaySourceStats> = self.sources.iter()
+            .map(|(addr, (listener, packets, bytes, last_seen))| RelaySourceStats {
+                address: addr.to_string(),
+                listener: listener.clone(),
+                packets: *packets,
+                bytes: *bytes,
+                last_seen_ms: last_seen.elapsed().as_millis() as u64,
+            })
+            .collect();
+        sources.sort_by(|a, b| a.address.cmp(&b.address));
+
+        RelayStatsSnapshot {
+            active: true,
+            uptime_secs: self.started.elapsed().as_secs_f64(),
+            packets_received: self.packets_received,
+            bytes_received: self.bytes_received,
+            frames_forwarded: self.frames_forwarded,
+            fps,
+            jitter_ms: self.jitter_ms,
+            sources,
+        }
+    }
+}
+
+/// Generate the statistics panel for the relay TUI
+fn generate_relay_stats_lines(stats: &RelayStatsSnapshot) -> Vec<Line<'static>> {
+    let mut lines = vec![
+        Line::from(vec![
+            Span::styled("Packets: ", Style::default().fg(Color::Cyan)),
+            Span::raw(format!("{}", stats.packets_received)),
+        ]),
+        Line::from(vec![
+            Span::styled("Received: ", Style::default().fg(Color::Cyan)),
+            Span::raw(format!("{:.1} KB", stats.bytes_received as f64 / 1024.0)),
+        ]),
+        Line::from(vec![
+            Span::styled("Forwarded: ", Style::default().fg(Color::Cyan)),
+            Span::raw(format!("{} frames", stats.frames_forwarded)),
+        ]),
+        Line::from(vec![
+            Span::styled("Jitter: ", Style::default().fg(Color::Cyan)),
+            Span::raw(format!("{:.2} ms", stats.jitter_ms)),
+        ]),
+        Line::from(""),
+        Line::from(Span::styled("Sources:", Style::default().fg(Color::Cyan))),
+    ];
+
+    if stats.sources.is_empty() {
+        lines.push(Line::from("  (none yet)"));
+    }
+    for source in &stats.sources {
+        let color = if source.last_seen_ms < 2000 { Color::Green } else { Color::DarkGray };
+        lines.push(Line::from(Span::styled(
+            format!("  {} ({} pkts)", source.address, source.packets),
+            Style::default().fg(color),
+        )));
+    }
+
+    lines
+}
+
+/// Collect the inputs for the current mode
+/// DDP, sACN, Art-Net and boblight input modes use a single listener covering all LEDs, relay mode uses the relay listeners
+pub fn input_listener_configs(config: &BandwidthConfig) -> Vec<RelayListenerConfig> {
+    if config.mode == "ddp" {
+        vec![RelayListenerConfig {
+            listen_ip: config.ddp_listen_ip.clone(),
+            listen_port: config.ddp_listen_port,
+            protocol: "ddp".to_string(),
+            frame_width: config.total_leds,
+            frame_height: 1,
+            led_offset: 0,
+            universe: 1,
+            enabled: true,
+        }]
+    } else if config.mode == "sacn" {
+        vec![RelayListenerConfig {
+            listen_ip: config.sacn_listen_ip.clone(),
+            listen_port: sacn::SACN_PORT,
+            protocol: "sacn".to_string(),
+            frame_width: config.total_leds,
+            frame_height: 1,
+            led_offset: 0,
+            universe: config.sacn_start_universe,
+            enabled: true,
+        }]
+    } else if config.mode == "artnet" {
+        vec![RelayListenerConfig {
+            listen_ip: config.artnet_listen_ip.clone(),
+            listen_port: artnet::ARTNET_PORT,
+            protocol: "artnet".to_string(),
+            frame_width: config.total_leds,
+            frame_height: 1,
+            led_offset: 0,
+            universe: 0,  // Art-Net universes start at 0
+            enabled: true,
+        }]
+    } else if config.mode == "boblight" {
+        vec![RelayListenerConfig {
+            listen_ip: config.boblight_listen_ip.clone(),
+            listen_port: config.boblight_listen_port,
+            protocol: "boblight".to_string(),
+            frame_width: config.total_leds,
+            frame_height: 1,
+            led_offset: 0,
+            universe: 1,
+            enabled: true,
+        }]
+    } else {
+        relay_listener_configs(config)
+    }
+}
+
+/// Collect all relay inputs - the primary relay_listen_ip/port listener followed by enabled extra listeners
+pub fn relay_listener_configs(config: &BandwidthConfig) -> Vec<RelayListenerConfig> {
+    let mut listeners = vec![RelayListenerConfig {
+        listen_ip: config.relay_listen_ip.clone(),
+        listen_port: config.relay_listen_port,
+        protocol: "raw".to_string(),
+        frame_width: config.relay_frame_width,
+        frame_height: config.relay_frame_height,
+        led_offset: 0,
+        universe: 1,
+        enabled: true,
+    }];
+    listeners.extend(config.relay_listeners.iter().filter(|l| l.enabled).cloned());
+    listeners
+}
+
+/// Rearrange an incoming RGB24 frame according to the relay remap rules
+/// LEDs not covered by any rule are left black; ranges falling outside the source frame are clipped
+/// Rules are bounded by sanitize(), but one whose range overflows is skipped rather than trusted
+pub fn remap_frame(frame: &[u8], rules: &[RelayRemapRule]) -> Vec<u8> {
+    let src_leds = frame.len() / 3;
+    let rules = || rules.iter().filter(|r| r.dst_start.checked_add(r.length).is_some());
+    let dst_leds = rules()
+        .map(|r| r.dst_start + r.length)
+        .max()
+        .unwrap_or(0)
+        .max(src_leds);
+
+    let mut output = vec![0u8; dst_leds * 3];
+
+    for rule in rules() {
+        for i in 0..rule.length {
+            let src = match rule.src_start.checked_add(i) {
+                Some(src) if src < src_leds => src,
+                _ => break,
+            };
+            let dst = if rule.reverse {
+                rule.dst_start + rule.length - 1 - i
+            } else {
+                rule.dst_start + i
+            };
+            output[dst * 3..dst * 3 + 3].copy_from_slice(&frame[src * 3..src * 3 + 3]);
+        }
+    }
+
+    output
+}
+
+/// Run relay mode (or DDP input mode) - listen for frames on UDP and forward via DDP
 pub fn run_relay_mode(
     config: BandwidthConfig,
     shutdown: Arc<AtomicBool>,
+    relay_stats: Arc<RelayStats>,
+) -> Result<ModeExitReason> {
+    relay_stats.publish(RelayStatsSnapshot { active: true, ..Default::default() });
+    let result = run_relay_loop(config, shutdown, &relay_stats);
+    relay_stats.set_active(false);
+    result
+}
+
+fn run_relay_loop(
+    config: BandwidthConfig,
+    shutdown: Arc<AtomicBool>,
+    relay_stats: &RelayStats,
 ) -> Result<ModeExitReason> {
     // Set up config file watcher for dynamic reloading
     let (config_tx, config_rx) = mpsc::channel::<BandwidthConfig>();
     let config_path = BandwidthConfig::config_path(None)?;
+    let watched_path = config_path.clone();
 
     let mut watcher = RecommendedWatcher::new(
         move |res: Result<NotifyEvent, _>| {
-            if res.is_ok() {
+            if matches!(res, Ok(ref event) if config::is_config_event(event, &watched_path)) {
                 if let Ok(new_config) = BandwidthConfig::load() {
                     let _ = config_tx.send(new_config);
                 }
@@ -77,18 +610,45 @@ pub fn run_relay_mode(
         Config::default(),
     )?;
 
-    if watcher.watch(&config_path, RecursiveMode::NonRecursive).is_err() {
+    if watcher.watch(&config::config_watch_dir(&config_path), RecursiveMode::NonRecursive).is_err() {
         eprintln!("⚠️  Could not watch config file for changes");
     }
 
     // Track current config values
     let mut current_config = config.clone();
     let mut current_ddp_delay = current_config.ddp_delay_ms;
-    let frame_size = current_config.relay_frame_width * current_config.relay_frame_height * 3;
+    let mode = current_config.mode.clone();
+    let mode_name = match mode.as_str() {
+        "ddp" => "DDP input",
+        "sacn" => "sACN input",
+        "artnet" => "Art-Net input",
+        "boblight" => "Boblight input",
+        _ => "Relay",
+    };
+
+    // Bind a non-blocking socket for every relay input
+    let listener_configs = input_listener_configs(&current_config);
+    let (frame_width, frame_height) = (listener_configs[0].frame_width, listener_configs[0].frame_height);
+    let frame_size = frame_width * frame_height * 3;
+    let mut listeners = Vec::new();
+    for listener_config in &listener_configs {
+        // Art-Net input mode maps universes to LED ranges from the config
+        let artnet_universes: &[ArtnetUniverseConfig] = if mode == "artnet" { &current_config.artnet_universes } else { &[] };
+        listeners.push(RelayListener::bind(listener_config, artnet_universes)?);
+    }
+
+    // Composite frame that every listener writes its LED range into
+    let composite_size = listener_configs.iter()
+        .map(|l| (l.led_offset + l.frame_width * l.frame_height) * 3)
+        .max()
+        .unwrap_or(frame_size);
+    let mut composite_frame = vec![0u8; composite_size];
 
-    // Create UDP socket for receiving with timeout for non-blocking operation
-    let socket = UdpSocket::bind(format!("{}:{}", current_config.relay_listen_ip, current_config.relay_listen_port))?;
-    socket.set_read_timeout(Some(Duration::from_millis(10)))?;  // 10ms timeout for responsive UI
+    let listen_summary = if listeners.len() == 1 {
+        format!("{}:{}", listener_configs[0].listen_ip, listener_configs[0].listen_port)
+    } else {
+        format!("{} listeners", listeners.len())
+    };
 
     // Create multi-device manager for forwarding
     let devices: Vec<WLEDDevice> = current_config.wled_devices.iter().map(|d| WLEDDevice {
@@ -96,22 +656,41 @@ pub fn run_relay_mode(
         led_offset: d.led_offset,
         led_count: d.led_count,
         enabled: d.enabled,
+        max_fps: d.max_fps,
+        led_ma: d.led_ma,
+        psu_amps: d.psu_amps,
+        backup_for: d.backup_for.clone(),
+        reversed: d.reversed,
+        mirrored: d.mirrored,
+        protocol: d.protocol.clone(),
+        universe: d.universe,
+        start_channel: d.start_channel,
+        net: d.net,
+        subnet: d.subnet,
     }).collect();
 
     let md_config = MultiDeviceConfig {
         devices,
         send_parallel: current_config.multi_device_send_parallel,
         fail_fast: current_config.multi_device_fail_fast,
+        stagger_sends: current_config.multi_device_stagger_sends,
+        color_correction: if current_config.relay_color_pipeline {
+            ColorCorrection::from_config(&current_config)
+        } else {
+            None
+        },
     };
 
     let mut multi_device_manager = MultiDeviceManager::new(md_config)?;
 
-    let mut frame_buffer = Vec::with_capacity(frame_size);
     let mut frame_count = 0u64;
     let mut last_frame_time = Instant::now();
     let mut current_fps = 0.0;
-    let mut last_receive_time = Instant::now();
     let mut first_frame_received = false;
+    let mut packet_buf = vec![0u8; 65535];  // Max UDP packet size
+    let mut stats_tracker = RelayStatsTracker::new();
+    let mut stats_snapshot = stats_tracker.snapshot(0.0);
+    let mut last_stats_publish = Instant::now();
 
     // DDP delay ring buffer - stores (send_time, frame_data)
     let mut ddp_buffer: VecDeque<(Instant, Vec<u8>)> = VecDeque::new();
@@ -134,16 +713,62 @@ pub fn run_relay_mode(
     // Add ffmpeg example command to event log
     {
         let mut log = event_log.lock().unwrap();
-        log.push(format!("🔄 Relay mode started"));
+        log.push(format!("🔄 {} mode started", mode_name));
         log.push(format!(""));
-        log.push(format!("Example ffmpeg command:"));
-        log.push(format!("  ffmpeg -re -i <input> -an -vf scale={}:{} -f rawvideo -pix_fmt rgb24 -s {}x{} udp://{}:{}",
-            current_config.relay_frame_width,
-            current_config.relay_frame_height,
-            current_config.relay_frame_width,
-            current_config.relay_frame_height,
-            current_config.relay_listen_ip,
-            current_config.relay_listen_port));
+        if mode == "ddp" {
+            log.push(format!("Listening for DDP frames on {}:{} ({} LEDs)",
+                current_config.ddp_listen_ip,
+                current_config.ddp_listen_port,
+                current_config.total_leds));
+            log.push(format!("Add this host as a device on the leader instance to chain rustwled"));
+        } else if mode == "sacn" {
+            let universes = sacn::universe_count(current_config.total_leds);
+            log.push(format!("Listening for sACN universes {}-{} on {}:{} ({} LEDs, {} per universe)",
+                current_config.sacn_start_universe,
+                current_config.sacn_start_universe as usize + universes - 1,
+                current_config.sacn_listen_ip,
+                sacn::SACN_PORT,
+                current_config.total_leds,
+                sacn::LEDS_PER_UNIVERSE));
+            log.push(format!("Patch the console's RGB fixtures from channel 1 of universe {}", current_config.sacn_start_universe));
+        } else if mode == "artnet" {
+            log.push(format!("Listening for Art-Net on {}:{} ({} LEDs)",
+                current_config.artnet_listen_ip,
+                artnet::ARTNET_PORT,
+                current_config.total_leds));
+            for range in &listeners[0].artnet_universes {
+                log.push(format!("  Universe {} -> LEDs {}-{}",
+                    range.universe,
+                    range.led_offset,
+                    range.led_offset + range.led_count.max(1) - 1));
+            }
+            log.push(format!("Frames are sent on ArtSync, or after the last universe if the source doesn't sync"));
+        } else if mode == "boblight" {
+            log.push(format!("Listening for boblight clients on {}:{} ({} lights, one per LED)",
+                current_config.boblight_listen_ip,
+                current_config.boblight_listen_port,
+                current_config.total_leds));
+            log.push(format!("Lights run clockwise around the screen edge from the top left corner"));
+        } else {
+            log.push(format!("Example ffmpeg command:"));
+            log.push(format!("  ffmpeg -re -i <input> -an -vf scale={}:{} -f rawvideo -pix_fmt rgb24 -s {}x{} udp://{}:{}",
+                current_config.relay_frame_width,
+                current_config.relay_frame_height,
+                current_config.relay_frame_width,
+                current_config.relay_frame_height,
+                current_config.relay_listen_ip,
+                current_config.relay_listen_port));
+        }
+        if listeners.len() > 1 {
+            log.push(format!(""));
+            log.push(format!("Listening on {} inputs:", listeners.len()));
+            for listener in &listeners {
+                log.push(format!("  {} -> LEDs {}-{}",
+                    listener.label,
+                    listener.led_offset,
+                    listener.led_offset + listener.frame_size / 3 - 1));
+            }
+        }
         log.push(format!(""));
         log.push(format!("Waiting for frames..."));
     }
@@ -160,7 +785,7 @@ pub fn run_relay_mode(
                         terminal.show_cursor()?;
                         disable_raw_mode()?;
                         terminal.backend_mut().execute(LeaveAlternateScreen)?;
-                        println!("\n👋 Relay mode stopped.\n");
+                        println!("\n👋 {} mode stopped.\n", mode_name);
                         return Ok(ModeExitReason::UserQuit);
                     },
                     KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
@@ -168,7 +793,7 @@ pub fn run_relay_mode(
                         terminal.show_cursor()?;
                         disable_raw_mode()?;
                         terminal.backend_mut().execute(LeaveAlternateScreen)?;
-                        println!("\n👋 Relay mode stopped.\n");
+                        println!("\n👋 {} mode stopped.\n", mode_name);
                         return Ok(ModeExitReason::UserQuit);
                     },
                     KeyCode::Char('i') | KeyCode::Char('I') => {
@@ -180,24 +805,30 @@ pub fn run_relay_mode(
             }
         }
 
+        // Restart or shutdown requested via the API
+        if system::restart_requested() {
+            terminal.show_cursor()?;
+            disable_raw_mode()?;
+            terminal.backend_mut().execute(LeaveAlternateScreen)?;
+            return Ok(ModeExitReason::ModeChanged);
+        }
+
         // Check for shutdown signal
         if shutdown.load(Ordering::Relaxed) {
             // Cleanup terminal
             terminal.show_cursor()?;
             disable_raw_mode()?;
             terminal.backend_mut().execute(LeaveAlternateScreen)?;
-            println!("\n👋 Relay mode stopped.\n");
+            println!("\n👋 {} mode stopped.\n", mode_name);
             return Ok(ModeExitReason::UserQuit);
         }
 
         // Check for config changes
         if let Ok(new_config) = config_rx.try_recv() {
-            // Check if we need to restart (IP, port, or frame dimensions changed)
-            if new_config.relay_listen_ip != current_config.relay_listen_ip ||
-               new_config.relay_listen_port != current_config.relay_listen_port ||
-               new_config.relay_frame_width != current_config.relay_frame_width ||
-               new_config.relay_frame_height != current_config.relay_frame_height ||
-               new_config.mode != "relay" {
+            // Check if we need to restart (any listener IP, port, protocol, or frame dimensions changed)
+            if input_listener_configs(&new_config) != listener_configs ||
+               new_config.artnet_universes != current_config.artnet_universes ||
+               new_config.mode != mode {
                 // Cleanup terminal before restart
                 terminal.show_cursor()?;
                 disable_raw_mode()?;
@@ -211,6 +842,28 @@ pub fn run_relay_mode(
             }
 
             // Handle in-place updates
+            if new_config.relay_remap.len() != current_config.relay_remap.len() {
+                let mut log = event_log.lock().unwrap();
+                log.push(format!("🔀 Remap rules updated: {} rule(s)", new_config.relay_remap.len()));
+                if log.len() > 100 {
+                    log.remove(0);
+                }
+            }
+
+            // Rebuild color calibration so gamma/order/correction changes apply immediately
+            if new_config.relay_color_pipeline {
+                multi_device_manager.set_color_correction(ColorCorrection::from_config(&new_config));
+            } else {
+                multi_device_manager.set_color_correction(None);
+            }
+            if new_config.relay_color_pipeline != current_config.relay_color_pipeline {
+                let mut log = event_log.lock().unwrap();
+                log.push(format!("🎨 Color pipeline {}", if new_config.relay_color_pipeline { "enabled" } else { "disabled" }));
+                if log.len() > 100 {
+                    log.remove(0);
+                }
+            }
+
             if new_config.ddp_delay_ms != current_ddp_delay {
                 current_ddp_delay = new_config.ddp_delay_ms;
                 let mut log = event_log.lock().unwrap();
@@ -223,76 +876,91 @@ pub fn run_relay_mode(
             current_config = new_config;
         }
 
-        // Receive packets (non-blocking) - accumulate data into buffer
-        let mut packet_buf = [0u8; 65535];  // Max UDP packet size
-        match socket.recv_from(&mut packet_buf) {
-            Ok((size, _src)) => {
-                frame_buffer.extend_from_slice(&packet_buf[..size]);
-                last_receive_time = Instant::now();
-            },
-            Err(e) if e.kind() == std::io::ErrorKind::WouldBlock => {
-                // No data available - check if we've been waiting too long
-                if last_receive_time.elapsed() > Duration::from_secs(5) {
-                    // Reset buffer if no data for 5 seconds - forces resync on stream restart
-                    if !frame_buffer.is_empty() {
+        // Receive packets (non-blocking) from every listener - accumulate data into buffers
+        let mut received_any = false;
+        for listener in &mut listeners {
+            loop {
+                match listener.recv(&mut packet_buf) {
+                    Ok(Some((src, size))) => {
+                        stats_tracker.record_packet(&listener.label, src, size);
+                        received_any = true;
+                    },
+                    Ok(None) => break,
+                    Err(e) => {
                         let mut log = event_log.lock().unwrap();
-                        log.push(format!("⚠️  Stream timeout - clearing {} bytes to resync", frame_buffer.len()));
+                        log.push(format!("❌ Recv error on {}: {}", listener.label, e));
                         if log.len() > 100 {
                             log.remove(0);
                         }
-                        frame_buffer.clear();
+                        break;
                     }
-                    last_receive_time = Instant::now();
                 }
-            },
-            Err(e) => {
-                let mut log = event_log.lock().unwrap();
-                log.push(format!("❌ UDP recv error: {}", e));
-                if log.len() > 100 {
-                    log.remove(0);
+            }
+
+            // No data for a while - reset raw buffer to force resync on stream restart
+            if listener.protocol == RelayProtocol::Raw && listener.last_receive_time.elapsed() > Duration::from_secs(5) {
+                if !listener.buffer.is_empty() {
+                    let mut log = event_log.lock().unwrap();
+                    log.push(format!("⚠️  Stream timeout on {} - clearing {} bytes to resync", listener.label, listener.buffer.len()));
+                    if log.len() > 100 {
+                        log.remove(0);
+                    }
+                    listener.buffer.clear();
                 }
+                listener.last_receive_time = Instant::now();
             }
         }
 
-        // Process ALL complete frames available in the buffer
-        while frame_buffer.len() >= frame_size {
-            // Extract exactly frame_size bytes for this frame
-            let frame_data: Vec<u8> = frame_buffer.drain(0..frame_size).collect();
-
-            // Add frame to delay buffer with timestamp
-            let delay_duration = Duration::from_micros((current_ddp_delay * 1000.0) as u64);
-            let send_time = loop_start + delay_duration;
-            ddp_buffer.push_back((send_time, frame_data));
-
-            // Update stats
-            frame_count += 1;
-            let frame_elapsed = last_frame_time.elapsed();
-            if frame_elapsed.as_secs_f64() > 0.0 {
-                current_fps = 1.0 / frame_elapsed.as_secs_f64();
-            }
-            last_frame_time = Instant::now();
+        // Process ALL complete frames available in the listener buffers
+        for listener in &mut listeners {
+            while let Some(frame_data) = listener.next_frame() {
+                // Write this listener's frame into its LED range of the composite frame
+                let start = listener.led_offset * 3;
+                composite_frame[start..start + frame_data.len()].copy_from_slice(&frame_data);
 
-            // Log when first frame is received
-            if !first_frame_received {
-                first_frame_received = true;
-                let mut log = event_log.lock().unwrap();
-                log.push(format!("✅ First frame received! Relay active."));
-                log.push(format!("Expected frame size: {} bytes ({}x{} @ RGB24)",
-                    frame_size,
-                    current_config.relay_frame_width,
-                    current_config.relay_frame_height));
-            }
+                // Rearrange LED ranges if remap rules are configured
+                let frame_data = if current_config.relay_remap.is_empty() {
+                    composite_frame.clone()
+                } else {
+                    remap_frame(&composite_frame, &current_config.relay_remap)
+                };
 
-            // Don't log routine frames - stats are in footer
-        }
+                // Add frame to delay buffer with timestamp
+                let delay_duration = Duration::from_micros((current_ddp_delay * 1000.0) as u64);
+                let send_time = loop_start + delay_duration;
+                ddp_buffer.push_back((send_time, frame_data));
+
+                // Update stats
+                stats_tracker.record_frame();
+                frame_count += 1;
+                let frame_elapsed = last_frame_time.elapsed();
+                if frame_elapsed.as_secs_f64() > 0.0 {
+                    current_fps = 1.0 / frame_elapsed.as_secs_f64();
+                }
+                last_frame_time = Instant::now();
+
+                // Log when first frame is received
+                if !first_frame_received {
+                    first_frame_received = true;
+                    let mut log = event_log.lock().unwrap();
+                    log.push(format!("✅ First frame received on {}! Relay active.", listener.label));
+                    log.push(format!("Expected frame size: {} bytes ({}x{} @ RGB24)",
+                        frame_size,
+                        frame_width,
+                        frame_height));
+                }
 
-        // Safety check: if buffer is growing unbounded, log warning (but don't clear!)
-        if frame_buffer.len() > frame_size * 10 {
-            let mut log = event_log.lock().unwrap();
-            log.push(format!("⚠️  Buffer very large: {} bytes ({} frames behind)",
-                frame_buffer.len(), frame_buffer.len() / frame_size));
-            if log.len() > 100 {
-                log.remove(0);
+                // Don't log routine frames - stats are in footer
+            }
+
+            // Safety check: if buffer is growing unbounded, log warning (but don't clear!)
+            if listener.protocol == RelayProtocol::Raw && listener.buffer.len() > listener.frame_size * 10 {
+                let mut log = event_log.lock().unwrap();
+                log.push(format!("⚠️  Buffer very large on {}: {} bytes ({} frames behind)",
+                    listener.label, listener.buffer.len(), listener.buffer.len() / listener.frame_size));
+                if log.len() > 100 {
+                    log.remove(0);
+                }
             }
         }
 
@@ -301,12 +969,23 @@ pub fn run_relay_mode(
         while let Some((send_time, _)) = ddp_buffer.front() {
             if *send_time <= now {
                 if let Some((_, frame_to_send)) = ddp_buffer.pop_front() {
-                    let _ = multi_device_manager.send_frame_with_brightness(&frame_to_send, Some(current_config.global_brightness));
+                    let brightness = if current_config.relay_color_pipeline { Some(current_config.global_brightness) } else { None };
+                    let _ = multi_device_manager.send_frame_with_brightness(&frame_to_send, brightness);
                 }
             } else {
                 break;
             }
         }
+        // Fill in between slow senders' frames while frame interpolation is on
+        let _ = multi_device_manager.tick();
+        live_stats::record_buffer_depth(ddp_buffer.len());
+
+        // Publish stats for the HTTP API and TUI panel (4x per second is plenty)
+        if last_stats_publish.elapsed() >= Duration::from_millis(250) {
+            stats_snapshot = stats_tracker.snapshot(current_fps);
+            relay_stats.publish(stats_snapshot.clone());
+            last_stats_publish = Instant::now();
+        }
 
         // Draw TUI
         terminal.draw(|f| {
@@ -315,15 +994,16 @@ pub fn run_relay_mode(
                 .constraints([
                     Constraint::Length(3),  // Header
                     Constraint::Min(10),    // Main content
-                    Constraint::Length(3),  // Footer
+                    Constraint::Length(4),  // Footer
                 ])
                 .split(f.size());
 
             // Header - Mode and frame info with controls on right
             let header_width = chunks[0].width.saturating_sub(2) as usize; // Subtract borders
-            let left_text = format!("🔄 Relay Mode | Frame: {}x{} ({} bytes)",
-                current_config.relay_frame_width,
-                current_config.relay_frame_height,
+            let left_text = format!("🔄 {} Mode | Frame: {}x{} ({} bytes)",
+                mode_name,
+                frame_width,
+                frame_height,
                 frame_size);
             let right_text = "Press 'i' for config, 'q' or Ctrl+C to quit";
             let spacing = header_width.saturating_sub(left_text.len() + right_text.len());
@@ -343,31 +1023,188 @@ pub fn run_relay_mode(
                     .block(Block::default().borders(Borders::ALL).title("Configuration (Press 'i' to hide)"));
                 f.render_widget(config_widget, chunks[1]);
             } else {
+                let main_chunks = Layout::default()
+                    .direction(Direction::Horizontal)
+                    .constraints([
+                        Constraint::Min(40),     // Event log
+                        Constraint::Length(40),  // Statistics
+                    ])
+                    .split(chunks[1]);
+
                 // Event log
                 let log = event_log_render.lock().unwrap();
                 let log_text: Vec<Line> = log.iter().map(|s| Line::from(s.as_str())).collect();
                 let log_widget = Paragraph::new(log_text)
                     .block(Block::default().borders(Borders::ALL).title("Relay Events"));
-                f.render_widget(log_widget, chunks[1]);
+                f.render_widget(log_widget, main_chunks[0]);
+
+                // Statistics panel
+                let stats_widget = Paragraph::new(generate_relay_stats_lines(&stats_snapshot))
+                    .block(Block::default().borders(Borders::ALL).title("Relay Stats"));
+                f.render_widget(stats_widget, main_chunks[1]);
             }
 
             // Footer - Status info only
             let footer_text = format!(
-                "Frames: {} | FPS: {:.1} | Delay: {:.1}ms | UDP: {}:{} -> WLED: {} | LEDs: {}",
+                "Frames: {} | FPS: {:.1} | Delay: {:.1}ms | UDP: {} -> WLED: {} | LEDs: {}\n{}",
                 frame_count,
                 current_fps,
                 current_ddp_delay,
-                current_config.relay_listen_ip,
-                current_config.relay_listen_port,
+                listen_summary,
                 current_config.wled_ip,
-                current_config.total_leds
+                current_config.total_leds,
+                live_stats::profile_line()
             );
             let footer = Paragraph::new(footer_text)
                 .block(Block::default().borders(Borders::ALL));
             f.render_widget(footer, chunks[2]);
         })?;
 
-        // No sleep - we want minimal latency for real-time relay
-        // The non-blocking socket with 10ms timeout prevents CPU spinning
+        // Minimal sleep only when idle - we want minimal latency for real-time relay
+        // while keeping the non-blocking sockets from spinning the CPU
+        if !received_any {
+            std::thread::sleep(Duration::from_millis(1));
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(src_start: usize, dst_start: usize, length: usize, reverse: bool) -> RelayRemapRule {
+        RelayRemapRule { src_start, dst_start, length, reverse }
+    }
+
+    #[test]
+    fn test_stats_tracker_bounds_sources() {
+        let mut tracker = RelayStatsTracker::new();
+        let addr = |port: u16| SocketAddr::from(([10, 0, 0, 1], port));
+        for port in 0..MAX_TRACKED_SOURCES as u16 + 10 {
+            tracker.record_packet("ddp", addr(port), 100);
+        }
+        assert_eq!(tracker.sources.len(), MAX_TRACKED_SOURCES);
+        assert!(tracker.sources.contains_key(&addr(MAX_TRACKED_SOURCES as u16 + 9)));
+        assert_eq!(tracker.packets_received, MAX_TRACKED_SOURCES as u64 + 10);
+
+        // Sources silent for longer than the window are dropped
+        tracker.sources.get_mut(&addr(20)).unwrap().3 = Instant::now() - SOURCE_STATS_WINDOW;
+        assert_eq!(tracker.snapshot(0.0).sources.len(), MAX_TRACKED_SOURCES - 1);
+    }
+
+    #[test]
+    fn test_stats_tracker_evicts_silent_sources() {
+        let mut tracker = RelayStatsTracker::new();
+        let addr = |port: u16| SocketAddr::from(([10, 0, 0, 2], port));
+        for port in 0..MAX_TRACKED_SOURCES as u16 {
+            tracker.record_packet("ddp", addr(port), 100);
+            // Heard from in order, all within the window - addr(0) longest ago
+            tracker.sources.get_mut(&addr(port)).unwrap().3 = Instant::now() - Duration::from_millis(50_000 - port as u64 * 100);
+        }
+
+        // A source silent for the window is pruned to make room, not the one heard from longest ago
+        tracker.sources.get_mut(&addr(30)).unwrap().3 = Instant::now() - SOURCE_STATS_WINDOW - Duration::from_secs(1);
+        tracker.record_packet("ddp", addr(1000), 100);
+        assert!(!tracker.sources.contains_key(&addr(30)));
+        assert!(tracker.sources.contains_key(&addr(0)));
+
+        // Every source still active - the one heard from longest ago makes room
+        tracker.record_packet("ddp", addr(1001), 100);
+        assert_eq!(tracker.sources.len(), MAX_TRACKED_SOURCES);
+        assert!(!tracker.sources.contains_key(&addr(0)));
+        assert!(tracker.sources.contains_key(&addr(1000)) && tracker.sources.contains_key(&addr(1001)));
+    }
+
+    #[test]
+    fn test_ddp_input_assembles_frame() {
+        let mut config = BandwidthConfig::default();
+        config.mode = "ddp".to_string();
+        config.total_leds = 4;
+        config.ddp_listen_ip = "127.0.0.1".to_string();
+        config.ddp_listen_port = 0;
+        let listener_configs = input_listener_configs(&config);
+        assert_eq!(listener_configs.len(), 1);
+        assert_eq!((listener_configs[0].protocol.as_str(), listener_configs[0].frame_width), ("ddp", 4));
+
+        let mut listener = RelayListener::bind(&listener_configs[0], &[]).unwrap();
+        let RelaySocket::Udp(ref socket) = listener.socket else { panic!("DDP input should listen on UDP") };
+        let target = socket.local_addr().unwrap();
+
+        // The leader splits a frame across packets - only the one with push set completes it
+        let sender = UdpSocket::bind("127.0.0.1:0").unwrap();
+        sender.send_to(&[0x40, 0x00, 0x01, 0x01, 0, 0, 0, 0, 0, 6, 1, 1, 1, 2, 2, 2], target).unwrap();
+        sender.send_to(&[0x41, 0x00, 0x01, 0x01, 0, 0, 0, 6, 0, 6, 3, 3, 3, 4, 4, 4], target).unwrap();
+
+        let mut packet_buf = [0u8; 1500];
+        let deadline = Instant::now() + Duration::from_secs(2);
+        let mut packets = 0;
+        while packets < 2 && Instant::now() < deadline {
+            match listener.recv(&mut packet_buf).unwrap() {
+                Some(_) => packets += 1,
+                None => std::thread::sleep(Duration::from_millis(5)),
+            }
+            assert_eq!(listener.frame_ready, packets == 2);
+        }
+        assert_eq!(listener.next_frame(), Some(vec![1, 1, 1, 2, 2, 2, 3, 3, 3, 4, 4, 4]));
+        assert!(listener.next_frame().is_none());
+    }
+
+    #[test]
+    fn test_parse_ddp_packet() {
+        // Version 1 + push flag, offset 6, length 3
+        let packet = [0x41, 0x00, 0x01, 0x01, 0, 0, 0, 6, 0, 3, 10, 20, 30];
+        let (offset, data, push) = parse_ddp_packet(&packet).unwrap();
+        assert_eq!(offset, 6);
+        assert_eq!(data, &[10, 20, 30]);
+        assert!(push);
+
+        // Wrong version is rejected
+        let packet = [0x81, 0x00, 0x01, 0x01, 0, 0, 0, 0, 0, 0];
+        assert!(parse_ddp_packet(&packet).is_none());
+
+        // Truncated header is rejected
+        assert!(parse_ddp_packet(&[0x41, 0x00]).is_none());
+    }
+
+    #[test]
+    fn test_remap_forward() {
+        let frame = vec![1, 1, 1, 2, 2, 2, 3, 3, 3, 4, 4, 4];
+        let out = remap_frame(&frame, &[rule(0, 2, 2, false), rule(2, 0, 2, false)]);
+        assert_eq!(out, vec![3, 3, 3, 4, 4, 4, 1, 1, 1, 2, 2, 2]);
+    }
+
+    #[test]
+    fn test_remap_reverse() {
+        let frame = vec![1, 1, 1, 2, 2, 2, 3, 3, 3];
+        let out = remap_frame(&frame, &[rule(0, 0, 3, true)]);
+        assert_eq!(out, vec![3, 3, 3, 2, 2, 2, 1, 1, 1]);
+    }
+
+    #[test]
+    fn test_remap_extends_and_clips() {
+        let frame = vec![1, 1, 1, 2, 2, 2];
+        // Destination beyond source grows the frame, source beyond frame is clipped
+        let out = remap_frame(&frame, &[rule(1, 3, 4, false)]);
+        assert_eq!(out.len(), 7 * 3);
+        assert_eq!(&out[9..12], &[2, 2, 2]);
+        assert!(out[12..].iter().all(|&b| b == 0));
+        assert!(out[..9].iter().all(|&b| b == 0));
+    }
+
+    #[test]
+    fn test_remap_out_of_range_rule() {
+        let frame = vec![1, 1, 1, 2, 2, 2];
+        // A range that overflows is skipped, not sized into the output
+        let out = remap_frame(&frame, &[rule(0, usize::MAX, 2, false), rule(usize::MAX, 0, 2, false), rule(0, 0, 1, false)]);
+        assert_eq!(out, vec![1, 1, 1, 0, 0, 0]);
+
+        // sanitize() bounds the rules to total_leds before they get here
+        let mut config = BandwidthConfig::default();
+        config.total_leds = 10;
+        config.relay_remap = vec![rule(0, 8, 5, false), rule(0, 10, 2, false), rule(0, usize::MAX, 2, true)];
+        config.sanitize();
+        assert_eq!(config.relay_remap.len(), 1);
+        assert_eq!((config.relay_remap[0].dst_start, config.relay_remap[0].length), (8, 2));
     }
 }
@@ -0,0 +1,177 @@
+// Remote Link Module - Keeps the SSH session feeding bandwidth mode alive: a session that closes or goes
+// quiet is restarted with backoff, and the link state is shown in the TUI and the status API
+use anyhow::Result;
+use serde::Serialize;
+use std::future::Future;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::io::{AsyncBufReadExt, BufReader};
+use tokio::process::Child;
+
+// The monitor prints every second - this long without a line means the session is stuck
+const STALL_TIMEOUT: Duration = Duration::from_secs(10);
+
+// Reconnect attempts wait twice as long each time, between these limits
+const MIN_BACKOFF: Duration = Duration::from_secs(1);
+const MAX_BACKOFF: Duration = Duration::from_secs(30);
+
+// How often a reconnect backoff checks whether bandwidth mode has exited
+const SHUTDOWN_POLL: Duration = Duration::from_millis(100);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LinkState {
+    Local,  // Monitoring this machine - no link
+    Connected,
+    Reconnecting,
+}
+
+/// State of the SSH session bandwidth mode reads from, exposed via the API
+#[derive(Debug, Clone, Serialize)]
+pub struct LinkStatus {
+    pub state: LinkState,
+    pub host: String,
+    pub attempts: u32,  // Reconnect attempts since the link went down
+    pub reconnects: u32,  // Times the link has come back since bandwidth mode started
+    pub last_error: Option<String>,  // Why the link last went down (or the last attempt failed)
+}
+
+static STATUS: Mutex<LinkStatus> = Mutex::new(LinkStatus {
+    state: LinkState::Local,
+    host: String::new(),
+    attempts: 0,
+    reconnects: 0,
+    last_error: None,
+});
+
+pub fn status() -> LinkStatus {
+    STATUS.lock().unwrap().clone()
+}
+
+/// Bandwidth mode is monitoring this machine
+pub fn set_local() {
+    update(|status| {
+        status.state = LinkState::Local;
+        status.host.clear();
+        status.attempts = 0;
+        status.reconnects = 0;
+        status.last_error = None;
+    });
+}
+
+fn update(change: impl FnOnce(&mut LinkStatus)) {
+    if let Ok(mut status) = STATUS.lock() {
+        change(&mut status);
+    }
+}
+
+/// Wait before reconnect attempt `attempt` (1 = the first)
+pub fn backoff(attempt: u32) -> Duration {
+    MIN_BACKOFF.saturating_mul(1 << attempt.saturating_sub(1).min(5)).min(MAX_BACKOFF)
+}
+
+/// Sleep out a reconnect backoff in short steps so leaving bandwidth mode isn't held up
+/// Returns false if `shutdown` was set meanwhile
+pub async fn sleep_unless_shutdown(wait: Duration, shutdown: &AtomicBool) -> bool {
+    let mut waited = Duration::ZERO;
+    while waited < wait {
+        if shutdown.load(Ordering::Relaxed) {
+            return false;
+        }
+        tokio::time::sleep(SHUTDOWN_POLL).await;
+        waited += SHUTDOWN_POLL;
+    }
+    true
+}
+
+/// Read the remote monitor's output, handing each line to `on_line`, and restart the session with
+/// `respawn` whenever it closes or stalls - until `shutdown` is set or `on_line` returns false
+pub async fn supervise<F, Fut>(
+    host: &str,
+    mut child: Child,
+    mut respawn: F,
+    shutdown: Arc<AtomicBool>,
+    mut on_line: impl FnMut(String) -> bool,
+) where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<Child>>,
+{
+    update(|status| {
+        status.state = LinkState::Connected;
+        status.host = host.to_string();
+        status.attempts = 0;
+        status.reconnects = 0;
+        status.last_error = None;
+    });
+
+    loop {
+        let reason = match child.stdout.take() {
+            Some(stdout) => {
+                let mut lines = BufReader::new(stdout).lines();
+                loop {
+                    if shutdown.load(Ordering::Relaxed) {
+                        let _ = child.kill().await;
+                        return;
+                    }
+                    match tokio::time::timeout(STALL_TIMEOUT, lines.next_line()).await {
+                        Ok(Ok(Some(line))) => {
+                            // Back up once a new session delivers its first line
+                            update(|status| {
+                                if status.state == LinkState::Reconnecting {
+                                    status.state = LinkState::Connected;
+                                    status.attempts = 0;
+                                    status.reconnects += 1;
+                                }
+                            });
+                            if !on_line(line) {
+                                let _ = child.kill().await;
+                                return;
+                            }
+                        }
+                        Ok(Ok(None)) => break "session closed".to_string(),
+                        Ok(Err(e)) => break format!("read failed: {}", e),
+                        Err(_) => break format!("no output for {}s", STALL_TIMEOUT.as_secs()),
+                    }
+                }
+            }
+            None => "no output from the monitor".to_string(),
+        };
+        let _ = child.kill().await;
+        update(|status| {
+            status.state = LinkState::Reconnecting;
+            status.last_error = Some(reason);
+        });
+
+        child = loop {
+            let mut attempt = 0;
+            update(|status| {
+                status.attempts += 1;
+                attempt = status.attempts;
+            });
+
+            if !sleep_unless_shutdown(backoff(attempt), &shutdown).await {
+                return;
+            }
+
+            match respawn().await {
+                Ok(child) => break child,
+                Err(e) => update(|status| status.last_error = Some(e.to_string())),
+            }
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backoff() {
+        assert_eq!(backoff(1), Duration::from_secs(1));
+        assert_eq!(backoff(2), Duration::from_secs(2));
+        assert_eq!(backoff(5), Duration::from_secs(16));
+        assert_eq!(backoff(6), MAX_BACKOFF);
+        assert_eq!(backoff(100), MAX_BACKOFF);
+    }
+}
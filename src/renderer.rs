@@ -2,14 +2,24 @@
 use anyhow::Result;
 use std::collections::VecDeque;
 use std::sync::atomic::{AtomicBool, Ordering};
-use std::sync::{Arc, Mutex};
+use arc_swap::ArcSwap;
+use std::ops::{Deref, DerefMut};
+use std::sync::{Arc, Mutex, MutexGuard};
 use std::thread;
-use crate::multi_device::{MultiDeviceConfig, MultiDeviceManager, WLEDDevice};
-use crate::config::BandwidthConfig;
-use std::time::{Duration, Instant, SystemTime};
+use crate::multi_device::{ColorCorrection, MultiDeviceConfig, MultiDeviceManager, WLEDDevice};
+use crate::config::{resolve_tx_rx_colors, BandwidthConfig};
+use crate::dashboard::{self, InterfaceRate};
+use crate::matrix::MatrixLayout;
+use crate::sync;
+use crate::alloc_track::FrameAllocCounter;
+use crate::idle::{IdleAnimator, IdleSettings};
+use crate::live_stats;
+use crate::strobe::{Strobe, StrobeSettings};
+use crate::pacer::{self, FramePacer};
+use std::time::{Duration, Instant};
 
 // Import shared types
-use crate::types::{build_gradient_from_color, build_intensity_gradient, InterpolationMode, Rgb};
+use crate::types::{build_gradient_from_color, build_intensity_gradient, ColorSpace, Easing, GradientLut, InterpolationMode, Rgb};
 
 // Import midi module for MIDI rendering functions
 use crate::midi;
@@ -37,6 +47,8 @@ pub struct SharedRenderState {
     pub rx_animation_direction: String,
     pub interpolation_time_ms: f64,
     pub enable_interpolation: bool,
+    pub fill_easing: Easing,  // Curve for the meter fill between bandwidth updates
+    pub animation_easing: Easing,  // Curve for animation speed between bandwidth updates
     pub max_bandwidth_kbps: f64,
 
     // Color configuration (as strings, renderer will rebuild gradients when changed)
@@ -45,6 +57,7 @@ pub struct SharedRenderState {
     pub use_gradient: bool,
     pub intensity_colors: bool,  // Map utilization to color gradient position (all LEDs same color)
     pub interpolation_mode: InterpolationMode,
+    pub color_space: ColorSpace,
 
     // Rendering configuration
     pub direction: DirectionMode,
@@ -59,26 +72,160 @@ pub struct SharedRenderState {
     pub strobe_duration_ms: f64,
     pub strobe_color: String,
     pub test_mode: bool,  // Use exponential smoothing instead of time-based interpolation
+    pub idle: IdleSettings,  // Idle animation shown while there's no traffic
+    pub dashboard: Option<Arc<MatrixLayout>>,  // Matrix for the per-interface dashboard (None = RX/TX meter)
+    pub interface_rates: Vec<InterfaceRate>,  // Latest traffic per monitored interface, for the dashboard
 
     // Generation counter to detect changes
     pub generation: u64,
 }
 
+impl SharedRenderState {
+    /// Initial render state for a config (no bandwidth measured yet)
+    pub fn from_config(config: &BandwidthConfig) -> Self {
+        // Resolve color strings (could be gradient names or hex colors)
+        let (tx_color, rx_color) = resolve_tx_rx_colors(config);
+
+        let interpolation_mode = match config.interpolation.to_lowercase().as_str() {
+            "basis" => InterpolationMode::Basis,
+            "catmullrom" | "catmull-rom" => InterpolationMode::CatmullRom,
+            _ => InterpolationMode::Linear,
+        };
+
+        let direction = match config.direction.to_lowercase().as_str() {
+            "mirrored" => DirectionMode::Mirrored,
+            "opposing" => DirectionMode::Opposing,
+            "left" => DirectionMode::Left,
+            "right" => DirectionMode::Right,
+            _ => DirectionMode::Mirrored,
+        };
+
+        SharedRenderState {
+            current_rx_kbps: 0.0,
+            current_tx_kbps: 0.0,
+            start_rx_kbps: 0.0,
+            start_tx_kbps: 0.0,
+            last_bandwidth_update: None,
+            animation_speed: config.animation_speed,
+            scale_animation_speed: config.scale_animation_speed,
+            tx_animation_direction: config.tx_animation_direction.clone(),
+            rx_animation_direction: config.rx_animation_direction.clone(),
+            interpolation_time_ms: config.interpolation_time_ms,
+            enable_interpolation: config.enable_interpolation,
+            fill_easing: Easing::from_name(&config.fill_easing),
+            animation_easing: Easing::from_name(&config.animation_easing),
+            max_bandwidth_kbps: config.max_gbps * 1000.0 * 1000.0,
+            tx_color,
+            rx_color,
+            use_gradient: config.use_gradient,
+            intensity_colors: config.intensity_colors,
+            interpolation_mode,
+            color_space: ColorSpace::from_name(&config.color_space),
+            direction,
+            swap: config.swap,
+            fps: config.fps,
+            ddp_delay_ms: config.ddp_delay_ms,
+            global_brightness: config.global_brightness,
+            total_leds: config.total_leds,
+            rx_split_percent: config.rx_split_percent,
+            strobe_on_max: config.strobe_on_max,
+            strobe_rate_hz: config.strobe_rate_hz,
+            strobe_duration_ms: config.strobe_duration_ms,
+            strobe_color: config.strobe_color.clone(),
+            test_mode: config.test_tx || config.test_rx,
+            idle: IdleSettings::from_config(config),
+            dashboard: dashboard_layout(config),
+            interface_rates: Vec::new(),
+            generation: 0,
+        }
+    }
+}
+
+/// Matrix for the bandwidth dashboard layout (only with a 2D matrix configured)
+pub fn dashboard_layout(config: &BandwidthConfig) -> Option<Arc<MatrixLayout>> {
+    (config.bandwidth_layout == "dashboard" && config.matrix.enabled)
+        .then(|| Arc::new(MatrixLayout::for_mode(config, config.matrix.width, config.matrix.height, true)))
+}
+
+/// SharedRenderState published to the render thread without locking it
+/// Readers get the latest snapshot lock-free; writers edit a copy that is swapped in when the writer is dropped
+pub struct RenderStateCell {
+    current: ArcSwap<SharedRenderState>,
+    write_lock: Mutex<()>,  // Serializes writers so concurrent updates aren't lost - readers never take it
+}
+
+impl RenderStateCell {
+    pub fn new(state: SharedRenderState) -> Self {
+        Self {
+            current: ArcSwap::from_pointee(state),
+            write_lock: Mutex::new(()),
+        }
+    }
+
+    /// Latest published state (never blocks)
+    pub fn load(&self) -> arc_swap::Guard<Arc<SharedRenderState>> {
+        self.current.load()
+    }
+
+    /// Edit a copy of the state - changes are published together when the writer is dropped
+    pub fn write(&self) -> RenderStateWriter<'_> {
+        let lock = self.write_lock.lock().unwrap();
+        RenderStateWriter {
+            cell: self,
+            state: Some(SharedRenderState::clone(&self.current.load())),
+            _lock: lock,
+        }
+    }
+}
+
+pub struct RenderStateWriter<'a> {
+    cell: &'a RenderStateCell,
+    state: Option<SharedRenderState>,
+    _lock: MutexGuard<'a, ()>,
+}
+
+impl Deref for RenderStateWriter<'_> {
+    type Target = SharedRenderState;
+
+    fn deref(&self) -> &SharedRenderState {
+        self.state.as_ref().unwrap()
+    }
+}
+
+impl DerefMut for RenderStateWriter<'_> {
+    fn deref_mut(&mut self) -> &mut SharedRenderState {
+        self.state.as_mut().unwrap()
+    }
+}
+
+impl Drop for RenderStateWriter<'_> {
+    fn drop(&mut self) {
+        // Publish while still holding the write lock (fields drop after this runs)
+        if let Some(state) = self.state.take() {
+            self.cell.current.store(Arc::new(state));
+        }
+    }
+}
+
 // Dedicated renderer that runs in its own thread at configurable FPS
 pub struct Renderer {
     multi_device_manager: Arc<Mutex<MultiDeviceManager>>,
-    shared_state: Arc<Mutex<SharedRenderState>>,
+    shared_state: Arc<RenderStateCell>,
     shutdown: Arc<AtomicBool>,
 
     // Owned by renderer thread
     tx_animation_offset: f64,
     rx_animation_offset: f64,
+    smoothed_kbps: Option<(f64, f64)>,  // Test mode exponential smoothing position (rx, tx)
+    idle: IdleAnimator,
+    tx_strobe: Strobe,
+    rx_strobe: Strobe,
 
     // Built from shared state
-    tx_gradient: Option<colorgrad::Gradient>,
-    rx_gradient: Option<colorgrad::Gradient>,
-    tx_intensity_gradient: Option<colorgrad::Gradient>,  // Linear gradient for intensity mode
-    rx_intensity_gradient: Option<colorgrad::Gradient>,  // Linear gradient for intensity mode
+    tx_gradient: Option<GradientLut>,
+    rx_gradient: Option<GradientLut>,
+    tx_intensity_gradient: Option<GradientLut>,  // Linear gradient for intensity mode
+    rx_intensity_gradient: Option<GradientLut>,  // Linear gradient for intensity mode
     tx_colors: Vec<Rgb>,
     rx_colors: Vec<Rgb>,
     tx_solid_color: Rgb,
@@ -86,12 +233,18 @@ pub struct Renderer {
 
     // Cache to detect when gradients need rebuilding
     last_generation: u64,
+
+    // Reused every frame so steady-state rendering doesn't allocate
+    frame: Vec<u8>,
+    tx_positions: Vec<usize>,
+    rx_positions: Vec<usize>,
+    dashboard_rates: Vec<InterfaceRate>,  // Dashboard rows, eased toward the latest rates
 }
 
 impl Renderer {
     pub fn new(
         config: &BandwidthConfig,
-        shared_state: Arc<Mutex<SharedRenderState>>,
+        shared_state: Arc<RenderStateCell>,
         shutdown: Arc<AtomicBool>,
     ) -> Result<Self> {
         // Create multi-device manager
@@ -100,26 +253,39 @@ impl Renderer {
             led_offset: d.led_offset,
             led_count: d.led_count,
             enabled: d.enabled,
+            max_fps: d.max_fps,
+            led_ma: d.led_ma,
+            psu_amps: d.psu_amps,
+            backup_for: d.backup_for.clone(),
+            reversed: d.reversed,
+            mirrored: d.mirrored,
+            protocol: d.protocol.clone(),
+            universe: d.universe,
+            start_channel: d.start_channel,
+            net: d.net,
+            subnet: d.subnet,
         }).collect();
 
         let md_config = MultiDeviceConfig {
             devices,
             send_parallel: config.multi_device_send_parallel,
             fail_fast: config.multi_device_fail_fast,
+            stagger_sends: config.multi_device_stagger_sends,
+            color_correction: ColorCorrection::from_config(&config),
         };
 
         let manager = MultiDeviceManager::new(md_config)?;
 
-        // Lock shared state to get initial colors
-        let state = shared_state.lock().unwrap();
+        // Read shared state to get initial colors
+        let state = shared_state.load();
         let (tx_gradient, tx_colors, tx_solid_color) =
-            build_gradient_from_color(&state.tx_color, state.use_gradient, state.interpolation_mode)?;
+            build_gradient_from_color(&state.tx_color, state.use_gradient, state.interpolation_mode, state.color_space)?;
         let (rx_gradient, rx_colors, rx_solid_color) =
-            build_gradient_from_color(&state.rx_color, state.use_gradient, state.interpolation_mode)?;
+            build_gradient_from_color(&state.rx_color, state.use_gradient, state.interpolation_mode, state.color_space)?;
         let tx_intensity_gradient =
-            build_intensity_gradient(&state.tx_color, state.use_gradient, state.interpolation_mode)?;
+            build_intensity_gradient(&state.tx_color, state.use_gradient, state.interpolation_mode, state.color_space)?;
         let rx_intensity_gradient =
-            build_intensity_gradient(&state.rx_color, state.use_gradient, state.interpolation_mode)?;
+            build_intensity_gradient(&state.rx_color, state.use_gradient, state.interpolation_mode, state.color_space)?;
         let last_generation = state.generation;
         drop(state);
 
@@ -129,6 +295,10 @@ impl Renderer {
             shutdown,
             tx_animation_offset: 0.0,
             rx_animation_offset: 0.0,
+            smoothed_kbps: None,
+            idle: IdleAnimator::new(),
+            tx_strobe: Strobe::new(),
+            rx_strobe: Strobe::new(),
             tx_gradient,
             rx_gradient,
             tx_intensity_gradient,
@@ -138,22 +308,26 @@ impl Renderer {
             tx_solid_color,
             rx_solid_color,
             last_generation,
+            frame: Vec::new(),
+            tx_positions: Vec::new(),
+            rx_positions: Vec::new(),
+            dashboard_rates: Vec::new(),
         })
     }
 
     fn rebuild_gradients_if_needed(&mut self) -> Result<()> {
-        let state = self.shared_state.lock().unwrap();
+        let state = self.shared_state.load();
 
         // Check if generation changed (config updated)
         if state.generation != self.last_generation {
             let (tx_gradient, tx_colors, tx_solid_color) =
-                build_gradient_from_color(&state.tx_color, state.use_gradient, state.interpolation_mode)?;
+                build_gradient_from_color(&state.tx_color, state.use_gradient, state.interpolation_mode, state.color_space)?;
             let (rx_gradient, rx_colors, rx_solid_color) =
-                build_gradient_from_color(&state.rx_color, state.use_gradient, state.interpolation_mode)?;
+                build_gradient_from_color(&state.rx_color, state.use_gradient, state.interpolation_mode, state.color_space)?;
             let tx_intensity_gradient =
-                build_intensity_gradient(&state.tx_color, state.use_gradient, state.interpolation_mode)?;
+                build_intensity_gradient(&state.tx_color, state.use_gradient, state.interpolation_mode, state.color_space)?;
             let rx_intensity_gradient =
-                build_intensity_gradient(&state.rx_color, state.use_gradient, state.interpolation_mode)?;
+                build_intensity_gradient(&state.rx_color, state.use_gradient, state.interpolation_mode, state.color_space)?;
 
             self.tx_gradient = tx_gradient;
             self.tx_intensity_gradient = tx_intensity_gradient;
@@ -196,7 +370,8 @@ impl Renderer {
         }
     }
 
-    fn calculate_led_positions(&self, tx_leds: usize, rx_leds: usize, direction: DirectionMode, swap: bool, total_leds: usize, leds_per_direction: usize) -> (Vec<usize>, Vec<usize>) {
+    // Fill tx_positions/rx_positions with the LED index of each lit LED, in fill order
+    fn calculate_led_positions(tx_positions: &mut Vec<usize>, rx_positions: &mut Vec<usize>, tx_leds: usize, rx_leds: usize, direction: DirectionMode, swap: bool, total_leds: usize, leds_per_direction: usize) {
         let half = leds_per_direction;
 
         let (first_half_leds, second_half_leds) = if swap {
@@ -205,73 +380,83 @@ impl Renderer {
             (rx_leds, tx_leds)
         };
 
-        let (first_half_pos, second_half_pos) = match direction {
+        let (first, second) = if swap {
+            (tx_positions, rx_positions)
+        } else {
+            (rx_positions, tx_positions)
+        };
+        first.clear();
+        second.clear();
+
+        match direction {
             DirectionMode::Mirrored => {
-                let first: Vec<usize> = (0..first_half_leds).map(|i| half - 1 - i).collect();
-                let second: Vec<usize> = (0..second_half_leds).map(|i| half + i).collect();
-                (first, second)
+                first.extend((0..first_half_leds).map(|i| half - 1 - i));
+                second.extend((0..second_half_leds).map(|i| half + i));
             }
             DirectionMode::Opposing => {
-                let first: Vec<usize> = (0..first_half_leds).collect();
-                let second: Vec<usize> = (0..second_half_leds)
-                    .map(|i| total_leds - 1 - i)
-                    .collect();
-                (first, second)
+                first.extend(0..first_half_leds);
+                second.extend((0..second_half_leds).map(|i| total_leds - 1 - i));
             }
             DirectionMode::Left => {
-                let first: Vec<usize> = (0..first_half_leds).map(|i| half - 1 - i).collect();
-                let second: Vec<usize> = (0..second_half_leds)
-                    .map(|i| total_leds - 1 - i)
-                    .collect();
-                (first, second)
+                first.extend((0..first_half_leds).map(|i| half - 1 - i));
+                second.extend((0..second_half_leds).map(|i| total_leds - 1 - i));
             }
             DirectionMode::Right => {
-                let first: Vec<usize> = (0..first_half_leds).collect();
-                let second: Vec<usize> = (0..second_half_leds).map(|i| half + i).collect();
-                (first, second)
+                first.extend(0..first_half_leds);
+                second.extend((0..second_half_leds).map(|i| half + i));
             }
-        };
-
-        if swap {
-            (first_half_pos, second_half_pos)
-        } else {
-            (second_half_pos, first_half_pos)
         }
     }
 
-    fn render_frame(&mut self, delta_seconds: f64) -> Result<Vec<u8>> {
+    /// Render the next frame into the renderer's own buffer (valid until the next call)
+    pub fn render_frame(&mut self, delta_seconds: f64) -> Result<&[u8]> {
         // Rebuild gradients if config changed (very quick check)
         self.rebuild_gradients_if_needed()?;
 
-        // Lock shared state only long enough to read current values
-        let state = self.shared_state.lock().unwrap();
+        // Snapshot of the current values (lock-free)
+        let state = self.shared_state.load();
+
+        if let Some(layout) = state.dashboard.clone() {
+            drop(state);
+            return self.render_dashboard(&layout, delta_seconds);
+        }
 
         // Get bandwidth values (interpolated or instant based on enable_interpolation)
-        let (rx_kbps, tx_kbps, test_mode) = if !state.enable_interpolation {
+        // The meter fill and the animation speed each follow their own easing curve; anim_* is what drives the animation
+        let (rx_kbps, tx_kbps, anim_rx_kbps, anim_tx_kbps, test_mode) = if !state.enable_interpolation {
             // Interpolation disabled: instant response
-            (state.current_rx_kbps, state.current_tx_kbps, false)
+            (state.current_rx_kbps, state.current_tx_kbps, state.current_rx_kbps, state.current_tx_kbps, false)
         } else if state.test_mode {
             // Test mode: use exponential smoothing for continuous smooth motion
             // Smoothing factor: move 20% toward target per second (adjusted by delta_seconds)
             let smoothing = (1.0 - (-5.0 * delta_seconds).exp()).min(1.0);
 
-            let rx = state.start_rx_kbps + (state.current_rx_kbps - state.start_rx_kbps) * smoothing;
-            let tx = state.start_tx_kbps + (state.current_tx_kbps - state.start_tx_kbps) * smoothing;
+            // Smoothing continues from where the last frame ended (start values seed the first frame)
+            let (start_rx, start_tx) = self.smoothed_kbps.unwrap_or((state.start_rx_kbps, state.start_tx_kbps));
+            let rx = start_rx + (state.current_rx_kbps - start_rx) * smoothing;
+            let tx = start_tx + (state.current_tx_kbps - start_tx) * smoothing;
 
-            (rx, tx, true)
+            (rx, tx, rx, tx, true)
         } else if let Some(last_update) = state.last_bandwidth_update {
-            // Normal mode: time-based linear interpolation
+            // Normal mode: time-based interpolation along the configured easing curves
             let elapsed_ms = last_update.elapsed().as_secs_f64() * 1000.0;
             let interpolation_time = state.interpolation_time_ms;
             let t = (elapsed_ms / interpolation_time).min(1.0);
-
-            let interpolated_rx = state.start_rx_kbps + (state.current_rx_kbps - state.start_rx_kbps) * t;
-            let interpolated_tx = state.start_tx_kbps + (state.current_tx_kbps - state.start_tx_kbps) * t;
-
-            (interpolated_rx, interpolated_tx, false)
+            let fill_t = state.fill_easing.apply(t);
+            let anim_t = state.animation_easing.apply(t);
+
+            // Spring easing overshoots, so keep the values from going negative on the way down
+            let rx_change = state.current_rx_kbps - state.start_rx_kbps;
+            let tx_change = state.current_tx_kbps - state.start_tx_kbps;
+            let interpolated_rx = (state.start_rx_kbps + rx_change * fill_t).max(0.0);
+            let interpolated_tx = (state.start_tx_kbps + tx_change * fill_t).max(0.0);
+            let anim_rx = (state.start_rx_kbps + rx_change * anim_t).max(0.0);
+            let anim_tx = (state.start_tx_kbps + tx_change * anim_t).max(0.0);
+
+            (interpolated_rx, interpolated_tx, anim_rx, anim_tx, false)
         } else {
             // No update yet, use current values
-            (state.current_rx_kbps, state.current_tx_kbps, false)
+            (state.current_rx_kbps, state.current_tx_kbps, state.current_rx_kbps, state.current_tx_kbps, false)
         };
 
         let max_bandwidth_kbps = state.max_bandwidth_kbps;
@@ -279,20 +464,23 @@ impl Renderer {
         let swap = state.swap;
         let use_gradient = state.use_gradient;
         let intensity_colors = state.intensity_colors;
-        let (tx_effective_speed, rx_effective_speed) = self.calculate_effective_speed(rx_kbps, tx_kbps, &state);
+        let (tx_effective_speed, rx_effective_speed) = self.calculate_effective_speed(anim_rx_kbps, anim_tx_kbps, &state);
         let fps = state.fps;
-        let tx_animation_direction = state.tx_animation_direction.clone();
-        let rx_animation_direction = state.rx_animation_direction.clone();
+        let tx_animation_right = state.tx_animation_direction == "right";
+        let rx_animation_right = state.rx_animation_direction == "right";
+        let rx_animation_left = state.rx_animation_direction == "left";
         let total_leds = state.total_leds;
         let rx_split_percent = state.rx_split_percent.clamp(0.0, 100.0);
-        let strobe_on_max = state.strobe_on_max;
-        let strobe_rate_hz = state.strobe_rate_hz;
-        let strobe_duration_ms = state.strobe_duration_ms;
-        let strobe_color_str = state.strobe_color.clone();
-        drop(state); // Release lock immediately
-
-        // Parse strobe color
-        let strobe_color = Rgb::from_hex(&strobe_color_str).unwrap_or(Rgb { r: 0, g: 0, b: 0 });
+        // Bandwidth mode has no audio or MIDI input, so only the max trigger applies
+        let strobe_settings = StrobeSettings {
+            on_max: state.strobe_on_max,
+            on_beat: false,
+            midi_note: None,
+            rate_hz: state.strobe_rate_hz,
+            duration_ms: state.strobe_duration_ms,
+            color: Rgb::from_hex(&state.strobe_color).unwrap_or(Rgb { r: 255, g: 255, b: 255 }),
+        };
+        drop(state); // Done with the snapshot
 
         // Calculate LED split based on rx_split_percent
         let rx_leds_available = ((total_leds as f64 * rx_split_percent) / 100.0) as usize;
@@ -303,33 +491,9 @@ impl Renderer {
         let rx_leds = self.calculate_leds(rx_kbps, max_bandwidth_kbps, rx_leds_available);
         let tx_leds = self.calculate_leds(tx_kbps, max_bandwidth_kbps, tx_leds_available);
 
-        // Determine if we're in strobe mode for each segment
-        let mut rx_strobe_active = false;
-        let mut tx_strobe_active = false;
-
-        if strobe_on_max && strobe_rate_hz > 0.0 {
-            let now = SystemTime::now();
-            let elapsed_millis = now.duration_since(SystemTime::UNIX_EPOCH).unwrap().as_millis();
-
-            // Calculate full cycle time in milliseconds
-            let cycle_ms = (1000.0 / strobe_rate_hz) as u128;
-            // Clamp strobe duration to not exceed the full cycle
-            let clamped_duration = (strobe_duration_ms as u128).min(cycle_ms);
-
-            // Determine position within the current cycle
-            let position_in_cycle = elapsed_millis % cycle_ms;
-            // Strobe is active during the last 'duration' milliseconds of each cycle
-            let strobe_phase_active = position_in_cycle >= (cycle_ms - clamped_duration);
-
-            // Activate strobe if at max and in strobe phase
-            if rx_leds >= rx_leds_available && strobe_phase_active {
-                rx_strobe_active = true;
-            }
-
-            if tx_leds >= tx_leds_available && strobe_phase_active {
-                tx_strobe_active = true;
-            }
-        }
+        // Strobe each segment while it's at max
+        let rx_strobe_active = self.rx_strobe.update(&strobe_settings, rx_leds >= rx_leds_available);
+        let tx_strobe_active = self.tx_strobe.update(&strobe_settings, tx_leds >= tx_leds_available);
 
         // Update animation offsets independently for TX and RX
         if tx_effective_speed > 0.0 {
@@ -344,27 +508,31 @@ impl Renderer {
             self.rx_animation_offset = (self.rx_animation_offset + offset_delta) % 1.0;
         }
 
-        // Prepare frame
+        // Prepare frame (cleared in place - the buffer is kept between frames)
         let frame_size = total_leds * 3;
-        let mut frame = vec![0u8; frame_size];
+        let frame = &mut self.frame;
+        frame.clear();
+        frame.resize(frame_size, 0);
 
-        let (tx_positions, rx_positions) = self.calculate_led_positions(tx_leds, rx_leds, direction, swap, total_leds, leds_per_direction);
+        Self::calculate_led_positions(&mut self.tx_positions, &mut self.rx_positions, tx_leds, rx_leds, direction, swap, total_leds, leds_per_direction);
+        let tx_positions = &self.tx_positions;
+        let rx_positions = &self.rx_positions;
 
         // Render TX positions
         if tx_strobe_active {
             // Strobe mode: fill all TX LEDs with strobe color
             for &led_pos in tx_positions.iter() {
                 let offset = led_pos * 3;
-                frame[offset] = strobe_color.r;
-                frame[offset + 1] = strobe_color.g;
-                frame[offset + 2] = strobe_color.b;
+                frame[offset] = strobe_settings.color.r;
+                frame[offset + 1] = strobe_settings.color.g;
+                frame[offset + 2] = strobe_settings.color.b;
             }
         } else if intensity_colors && self.tx_intensity_gradient.is_some() {
             // Intensity Colors Mode: Map utilization to gradient position (all LEDs same color)
             // Use the linear intensity gradient (0.0 = first color, 1.0 = last color)
             let tx_utilization = (tx_kbps / max_bandwidth_kbps).clamp(0.0, 1.0);
             let tx_gradient = self.tx_intensity_gradient.as_ref().unwrap();
-            let rgba = tx_gradient.at(tx_utilization).to_rgba8();
+            let rgba = tx_gradient.at(tx_utilization);
 
             for &led_pos in tx_positions.iter() {
                 let offset = led_pos * 3;
@@ -375,7 +543,7 @@ impl Renderer {
         } else if !use_gradient && self.tx_colors.len() >= 2 && !tx_positions.is_empty() {
             // Use total available LEDs for pattern, not just lit LEDs (so segments don't scale with level)
             let total_pattern_leds = tx_leds_available as f64;
-            let pattern_offset = if tx_animation_direction == "right" {
+            let pattern_offset = if tx_animation_right {
                 -self.tx_animation_offset * total_pattern_leds
             } else {
                 self.tx_animation_offset * total_pattern_leds
@@ -397,20 +565,20 @@ impl Renderer {
             for &led_pos in tx_positions.iter() {
                 // Map LED position to gradient position (0.0-1.0 across the full TX half)
                 let pos_ratio = (led_pos % leds_per_direction) as f64 / leds_per_direction as f64;
-                let animated_pos = if tx_animation_direction == "right" {
+                let animated_pos = if tx_animation_right {
                     (1.0 + pos_ratio - self.tx_animation_offset) % 1.0
                 } else {
                     (pos_ratio + self.tx_animation_offset) % 1.0
                 };
 
-                let rgba = tx_gradient.at(animated_pos).to_rgba8();
+                let rgba = tx_gradient.at(animated_pos);
                 let offset = led_pos * 3;
                 frame[offset] = rgba[0];
                 frame[offset + 1] = rgba[1];
                 frame[offset + 2] = rgba[2];
             }
         } else {
-            for &led_pos in &tx_positions {
+            for &led_pos in tx_positions {
                 let offset = led_pos * 3;
                 frame[offset] = self.tx_solid_color.r;
                 frame[offset + 1] = self.tx_solid_color.g;
@@ -423,16 +591,16 @@ impl Renderer {
             // Strobe mode: fill all RX LEDs with strobe color
             for &led_pos in rx_positions.iter() {
                 let offset = led_pos * 3;
-                frame[offset] = strobe_color.r;
-                frame[offset + 1] = strobe_color.g;
-                frame[offset + 2] = strobe_color.b;
+                frame[offset] = strobe_settings.color.r;
+                frame[offset + 1] = strobe_settings.color.g;
+                frame[offset + 2] = strobe_settings.color.b;
             }
         } else if intensity_colors && self.rx_intensity_gradient.is_some() {
             // Intensity Colors Mode: Map utilization to gradient position (all LEDs same color)
             // Use the linear intensity gradient (0.0 = first color, 1.0 = last color)
             let rx_utilization = (rx_kbps / max_bandwidth_kbps).clamp(0.0, 1.0);
             let rx_gradient = self.rx_intensity_gradient.as_ref().unwrap();
-            let rgba = rx_gradient.at(rx_utilization).to_rgba8();
+            let rgba = rx_gradient.at(rx_utilization);
 
             for &led_pos in rx_positions.iter() {
                 let offset = led_pos * 3;
@@ -444,7 +612,7 @@ impl Renderer {
             // Use total available LEDs for pattern, not just lit LEDs (so segments don't scale with level)
             let total_pattern_leds = rx_leds_available as f64;
             // Invert direction logic for RX so "right" means same visual direction as TX "right"
-            let pattern_offset = if rx_animation_direction == "left" {
+            let pattern_offset = if rx_animation_left {
                 -self.rx_animation_offset * total_pattern_leds
             } else {
                 self.rx_animation_offset * total_pattern_leds
@@ -466,20 +634,20 @@ impl Renderer {
             for &led_pos in rx_positions.iter() {
                 // Map LED position to gradient position (0.0-1.0 across the full RX half)
                 let pos_ratio = (led_pos % leds_per_direction) as f64 / leds_per_direction as f64;
-                let animated_pos = if rx_animation_direction == "right" {
+                let animated_pos = if rx_animation_right {
                     (1.0 + pos_ratio - self.rx_animation_offset) % 1.0
                 } else {
                     (pos_ratio + self.rx_animation_offset) % 1.0
                 };
 
-                let rgba = rx_gradient.at(animated_pos).to_rgba8();
+                let rgba = rx_gradient.at(animated_pos);
                 let offset = led_pos * 3;
                 frame[offset] = rgba[0];
                 frame[offset + 1] = rgba[1];
                 frame[offset + 2] = rgba[2];
             }
         } else {
-            for &led_pos in &rx_positions {
+            for &led_pos in rx_positions {
                 let offset = led_pos * 3;
                 frame[offset] = self.rx_solid_color.r;
                 frame[offset + 1] = self.rx_solid_color.g;
@@ -487,24 +655,73 @@ impl Renderer {
             }
         }
 
-        // Update start values for exponential smoothing in test mode
-        if test_mode {
-            let mut state = self.shared_state.lock().unwrap();
-            state.start_rx_kbps = rx_kbps;
-            state.start_tx_kbps = tx_kbps;
-            drop(state);
+        // Remember where exponential smoothing got to in test mode
+        self.smoothed_kbps = if test_mode { Some((rx_kbps, tx_kbps)) } else { None };
+
+        // Fade into the idle animation while there's no traffic
+        let state = self.shared_state.load();
+        let active = state.test_mode || state.current_rx_kbps > 0.0 || state.current_tx_kbps > 0.0;
+        self.idle.update(&state.idle, active, &mut self.frame);
+
+        Ok(&self.frame)
+    }
+
+    // One labeled row band per interface on the 2D matrix, each row easing toward its latest rates
+    fn render_dashboard(&mut self, layout: &MatrixLayout, delta_seconds: f64) -> Result<&[u8]> {
+        let state = self.shared_state.load();
+        let easing = if state.enable_interpolation {
+            (1.0 - (-3000.0 * delta_seconds / state.interpolation_time_ms.max(1.0)).exp()).min(1.0)
+        } else {
+            1.0
+        };
+        self.dashboard_rates.resize_with(state.interface_rates.len(), InterfaceRate::default);
+        for (row, rate) in self.dashboard_rates.iter_mut().zip(&state.interface_rates) {
+            if row.label != rate.label {
+                row.label.clone_from(&rate.label);
+            }
+            row.rx_kbps += (rate.rx_kbps - row.rx_kbps) * easing;
+            row.tx_kbps += (rate.tx_kbps - row.tx_kbps) * easing;
         }
 
-        // Return frame buffer for delayed sending
-        Ok(frame)
+        self.frame.clear();
+        self.frame.resize(state.total_leds * 3, 0);
+        let color_at = |gradient: &Option<GradientLut>, solid: Rgb, pos: f64| match gradient {
+            Some(lut) => {
+                let [r, g, b, _] = lut.at(pos);
+                [r, g, b]
+            }
+            None => [solid.r, solid.g, solid.b],
+        };
+        dashboard::render(
+            &mut self.frame,
+            layout,
+            &self.dashboard_rates,
+            state.max_bandwidth_kbps,
+            |pos| color_at(&self.rx_gradient, self.rx_solid_color, pos),
+            |pos| color_at(&self.tx_gradient, self.tx_solid_color, pos),
+        );
+
+        let active = state.test_mode || state.current_rx_kbps > 0.0 || state.current_tx_kbps > 0.0;
+        self.idle.update(&state.idle, active, &mut self.frame);
+        Ok(&self.frame)
     }
 
     // Main render loop that runs at configurable FPS
+    /// Handle to the renderer's devices, for switching them over in place while it runs
+    pub fn device_manager(&self) -> Arc<Mutex<MultiDeviceManager>> {
+        Arc::clone(&self.multi_device_manager)
+    }
+
     pub fn run(mut self) {
         let mut last_frame = Instant::now();
+        let mut last_frame_index = 0i64;
 
         // Frame buffer for delay - stores (send_time, frame_data)
         let mut frame_buffer: VecDeque<(Instant, Vec<u8>)> = VecDeque::new();
+        // Sent frames are recycled here instead of being freed, so queueing doesn't allocate
+        let mut spare_frames: Vec<Vec<u8>> = Vec::new();
+        let mut alloc_counter = FrameAllocCounter::new("bandwidth");
+        let mut pacer = FramePacer::new(self.shared_state.load().fps);
 
         loop {
             let loop_start = Instant::now();
@@ -516,7 +733,7 @@ impl Renderer {
 
             // Read FPS, delay, and brightness from shared state
             let (fps, delay_ms, global_brightness) = {
-                let state = self.shared_state.lock().unwrap();
+                let state = self.shared_state.load();
                 (state.fps, state.ddp_delay_ms, state.global_brightness)
             };
 
@@ -524,19 +741,35 @@ impl Renderer {
 
             // Calculate frame duration based on FPS
             let frame_duration_micros = (1_000_000.0 / fps) as u64;
-            let frame_duration = Duration::from_micros(frame_duration_micros);
+            pacer.set_fps(fps);
 
             let elapsed = loop_start.duration_since(last_frame);
 
-            // Render new frame if it's time
-            if elapsed >= frame_duration {
+            // Render new frame if it's time - when synced, frames start on shared clock boundaries
+            // so every instance renders the same frame at the same moment
+            let render_due = if sync::clock_enabled() {
+                let frame_index = sync::synced_frame_index(frame_duration_micros);
+                let due = frame_index != last_frame_index;
+                last_frame_index = frame_index;
+                due
+            } else {
+                pacer.frame_due(loop_start)
+            };
+
+            if render_due {
+                alloc_counter.begin_frame();
                 let delta_seconds = elapsed.as_secs_f64();
                 last_frame = loop_start;
 
-                // Render frame and add to buffer with scheduled send time
+                // Render frame and add a copy to the buffer with scheduled send time
+                let render_start = Instant::now();
                 if let Ok(frame) = self.render_frame(delta_seconds) {
+                    live_stats::record_render_time(render_start.elapsed());
+                    let mut queued = spare_frames.pop().unwrap_or_default();
+                    queued.clear();
+                    queued.extend_from_slice(frame);
                     let send_time = loop_start + delay_duration;
-                    frame_buffer.push_back((send_time, frame));
+                    frame_buffer.push_back((send_time, queued));
                 }
             }
 
@@ -549,14 +782,25 @@ impl Renderer {
                             // Apply global brightness
                             let _ = manager.send_frame_with_brightness(&frame_to_send, Some(global_brightness));
                         }
+                        spare_frames.push(frame_to_send);
                     }
                 } else {
                     break;
                 }
             }
+            live_stats::record_buffer_depth(frame_buffer.len());
+            if render_due {
+                alloc_counter.end_frame();
+            }
 
-            // Tiny sleep to avoid spinning CPU at 100%
-            thread::sleep(Duration::from_micros(100));
+            // Synced frame boundaries come from the shared clock, so poll for those; otherwise
+            // sleep until the next frame or the next delayed send, whichever comes first
+            if sync::clock_enabled() {
+                thread::sleep(Duration::from_micros(100));
+            } else {
+                let next_send = frame_buffer.front().map(|(send_time, _)| *send_time);
+                pacer::sleep_until(next_send.map_or(pacer.next_frame(), |t| t.min(pacer.next_frame())));
+            }
         }
     }
 }
@@ -612,8 +856,36 @@ impl Renderer {
 //     }
 // }
 
+/// Scratch buffers for render_midi_to_leds, kept by the caller between frames so rendering doesn't allocate
+#[derive(Default)]
+pub struct MidiFrameBuffers {
+    active_notes: Vec<(u8, u8, u8)>,
+    gradient_stops: Vec<(f64, (u8, u8, u8))>,  // (position 0.0-1.0, color) per note in gradient mode
+    target_frame: Vec<u8>,
+    active_leds: Vec<bool>,
+    frame: Vec<f32>,  // Output kept in float (0.0-255.0) so smoothing isn't rounded until send time
+}
+
+// Linear blend between color stops at ascending positions, like a linear RGB colorgrad gradient
+fn blend_stops(stops: &[(f64, (u8, u8, u8))], t: f64) -> (u8, u8, u8) {
+    let (first_pos, first) = stops[0];
+    if t <= first_pos {
+        return first;
+    }
+    for pair in stops.windows(2) {
+        let ((p0, c0), (p1, c1)) = (pair[0], pair[1]);
+        if t <= p1 {
+            let f = if p1 > p0 { (t - p0) / (p1 - p0) } else { 1.0 };
+            let lerp = |a: u8, b: u8| (a as f64 + (b as f64 - a as f64) * f).round() as u8;
+            return (lerp(c0.0, c1.0), lerp(c0.1, c1.1), lerp(c0.2, c1.2));
+        }
+    }
+    stops[stops.len() - 1].1
+}
+
 /// Render MIDI notes to LED frame with attack/decay smoothing
-pub fn render_midi_to_leds(
+/// The frame is float RGB (0.0-255.0) - send it with MultiDeviceManager::send_frame_f32
+pub fn render_midi_to_leds<'a>(
     note_state: &midi::NoteState,
     total_leds: usize,
     gradient_enabled: bool,
@@ -621,28 +893,35 @@ pub fn render_midi_to_leds(
     velocity_colors: bool,
     one_to_one: bool,  // 1-to-1 note mapping (centered at middle C) vs spread across all LEDs
     channel_mode: bool,  // Use MIDI channels to address different LED sections
+    channel: Option<u8>,  // Only render notes on this MIDI channel (0-15) - None = all channels
     smoothed_frame: &mut Vec<f32>,  // Current brightness per LED (smoothed)
     target_brightness: &mut Vec<f32>,  // Target brightness per LED (NOT from velocity, independently controlled)
     last_colors: &mut Vec<(u8, u8, u8)>,  // Store base RGB color (0-255) per LED, brightness applied separately
     attack_factor: f32,
     decay_factor: f32,
     debug_info: Option<&Arc<Mutex<Vec<String>>>>,  // Optional debug output
-) -> Result<Vec<u8>> {
-    let active_notes = note_state.get_active_notes();
+    buffers: &'a mut MidiFrameBuffers,
+) -> Result<&'a [f32]> {
+    let MidiFrameBuffers { active_notes, gradient_stops, target_frame, active_leds, frame: final_frame } = buffers;
+    note_state.active_notes_into(active_notes);
+    if let Some(channel) = channel {
+        active_notes.retain(|(ch, _note, _velocity)| *ch == channel);
+    }
 
     // Calculate LED layout (only used in spread mode)
     let (leds_per_note, start_offset, _end_offset) = midi::calculate_led_layout(total_leds);
 
     // Create target frame (what we want to display before smoothing)
     let frame_size = total_leds * 3;
-    let mut target_frame = vec![0u8; frame_size];
+    target_frame.clear();
+    target_frame.resize(frame_size, 0);
 
     if active_notes.is_empty() {
         // No notes active - all LEDs off (already zeroed)
     } else if channel_mode {
         // Channel mode: Use MIDI channels to address different LED sections
         // Channel 0 (MIDI channel 1) = LEDs 0-127, Channel 1 (MIDI channel 2) = LEDs 128-255, etc.
-        for (channel, note, velocity) in &active_notes {
+        for (channel, note, velocity) in active_notes.iter() {
             if let Some(led) = midi::channel_and_note_to_led(*channel, *note, total_leds) {
                 let (r, g, b) = if velocity_colors {
                     // Velocity mode: color determined by velocity, full brightness
@@ -667,7 +946,7 @@ pub fn render_midi_to_leds(
         }
     } else if one_to_one {
         // 1-to-1 mode: Each note lights up multiple LEDs (pattern repeats every 128 LEDs)
-        for (_channel, note, velocity) in &active_notes {
+        for (_channel, note, velocity) in active_notes.iter() {
             let (r, g, b) = if velocity_colors {
                 // Velocity mode: color determined by velocity, full brightness
                 let color = midi::velocity_to_color(*velocity);
@@ -721,24 +1000,20 @@ pub fn render_midi_to_leds(
         }
     } else if gradient_enabled {
         // Multiple notes with gradient: create gradient spanning from lowest to highest note
-        let mut sorted_notes = active_notes.clone();
-        sorted_notes.sort_by_key(|(_channel, note, _velocity)| *note);
+        active_notes.sort_by_key(|(_channel, note, _velocity)| *note);
 
-        let min_note = sorted_notes[0].1;  // .1 = note
-        let max_note = sorted_notes[sorted_notes.len() - 1].1;  // .1 = note
+        let min_note = active_notes[0].1;  // .1 = note
+        let max_note = active_notes[active_notes.len() - 1].1;  // .1 = note
 
         // Get LED span
         let (span_start, _) = midi::note_to_led_range(min_note, leds_per_note, start_offset);
         let (_, span_end) = midi::note_to_led_range(max_note, leds_per_note, start_offset);
 
-        // Build gradient with color stops at each note position
-        use colorgrad::{CustomGradient, Color};
-
+        // Color stops at each note position
         let note_range = (max_note - min_note) as f64;
-        let mut colors = Vec::new();
-        let mut positions = Vec::new();
+        gradient_stops.clear();
 
-        for (_channel, note, velocity) in &sorted_notes {
+        for (_channel, note, velocity) in active_notes.iter() {
             let (r, g, b) = if velocity_colors {
                 // Velocity mode: color determined by velocity, full brightness
                 let rgb = midi::velocity_to_color(*velocity);
@@ -754,39 +1029,30 @@ pub fn render_midi_to_leds(
                 )
             };
 
-            colors.push(Color::from_rgba8(r, g, b, 255));
-
             // Calculate position (0.0 to 1.0) within the note span
             let position = if note_range > 0.0 {
                 (*note - min_note) as f64 / note_range
             } else {
                 0.5 // Single note position (shouldn't happen but safety)
             };
-            positions.push(position);
+            gradient_stops.push((position, (r, g, b)));
         }
 
-        // Build the gradient
-        let gradient = CustomGradient::new()
-            .colors(&colors)
-            .domain(&positions)
-            .build()
-            .unwrap();
-
         // Apply gradient across the span
         let span_length = span_end - span_start;
         for i in 0..span_length {
             let t = i as f64 / span_length as f64;
-            let color = gradient.at(t).to_rgba8();
+            let (r, g, b) = blend_stops(gradient_stops, t);
 
             let led = span_start + i;
             let offset = led * 3;
-            target_frame[offset] = color[0];
-            target_frame[offset + 1] = color[1];
-            target_frame[offset + 2] = color[2];
+            target_frame[offset] = r;
+            target_frame[offset + 1] = g;
+            target_frame[offset + 2] = b;
         }
     } else {
         // Multiple notes without gradient: light each note's segment independently
-        for (_channel, note, velocity) in &active_notes {
+        for (_channel, note, velocity) in active_notes.iter() {
             let (r, g, b) = if velocity_colors {
                 // Velocity mode: color determined by velocity, full brightness
                 let color = midi::velocity_to_color(*velocity);
@@ -817,18 +1083,19 @@ pub fn render_midi_to_leds(
     // We DON'T clear the buffer - we selectively update based on note state
 
     // Build a set of which LEDs should be lit by active notes
-    let mut active_leds = vec![false; total_leds];
+    active_leds.clear();
+    active_leds.resize(total_leds, false);
 
     if channel_mode {
         // Channel mode: mark single LED per (channel, note) pair
-        for (channel, note, _velocity) in &active_notes {
+        for (channel, note, _velocity) in active_notes.iter() {
             if let Some(led) = midi::channel_and_note_to_led(*channel, *note, total_leds) {
                 active_leds[led] = true;
             }
         }
     } else if one_to_one {
         // 1-to-1 mode: mark all LEDs for each note (pattern repeats every 128 LEDs)
-        for (_channel, note, _velocity) in &active_notes {
+        for (_channel, note, _velocity) in active_notes.iter() {
             let leds = midi::note_to_leds_one_to_one(*note, total_leds);
             for led in leds {
                 active_leds[led] = true;
@@ -847,7 +1114,7 @@ pub fn render_midi_to_leds(
         }
     } else {
         // Spread mode: mark each note's segment
-        for (_channel, note, _velocity) in &active_notes {
+        for (_channel, note, _velocity) in active_notes.iter() {
             let (start_led, end_led) = midi::note_to_led_range(*note, leds_per_note, start_offset);
             for led in start_led..end_led {
                 active_leds[led] = true;
@@ -858,7 +1125,7 @@ pub fn render_midi_to_leds(
     // Now update targets based on whether LEDs are active or not
     if channel_mode {
         // Channel mode: direct (channel, note) to LED mapping
-        for (channel, note, velocity) in &active_notes {
+        for (channel, note, velocity) in active_notes.iter() {
             if let Some(led) = midi::channel_and_note_to_led(*channel, *note, total_leds) {
                 // Get color DIRECTLY from the color function
                 let color = if velocity_colors {
@@ -896,7 +1163,7 @@ pub fn render_midi_to_leds(
         }
     } else if one_to_one {
         // 1-to-1 mode: direct note-to-LED mapping (repeating every 128 LEDs)
-        for (_channel, note, velocity) in &active_notes {
+        for (_channel, note, velocity) in active_notes.iter() {
             let leds = midi::note_to_leds_one_to_one(*note, total_leds);
 
             // Get color DIRECTLY from the color function
@@ -963,7 +1230,7 @@ pub fn render_midi_to_leds(
         }
     } else {
         // Non-gradient mode
-        for (_channel, note, velocity) in &active_notes {
+        for (_channel, note, velocity) in active_notes.iter() {
             let (start_led, end_led) = midi::note_to_led_range(*note, leds_per_note, start_offset);
 
             // Get color DIRECTLY from the color function
@@ -1005,7 +1272,8 @@ pub fn render_midi_to_leds(
     }
 
     // Step 2: Apply attack/decay smoothing - completely independent of velocity functions
-    let mut final_frame = vec![0u8; frame_size];
+    final_frame.clear();
+    final_frame.resize(frame_size, 0.0);
 
     // Debug: track decaying LED (using thread_local to avoid unsafe static mut)
     use std::cell::Cell;
@@ -1043,16 +1311,16 @@ pub fn render_midi_to_leds(
         // Calculate brightness factor (0.0 to 1.0)
         let brightness_factor = smoothed_brightness / 255.0;
 
-        // Multiply each RGB component by brightness factor
-        final_frame[offset] = (base_r as f32 * brightness_factor).round() as u8;
-        final_frame[offset + 1] = (base_g as f32 * brightness_factor).round() as u8;
-        final_frame[offset + 2] = (base_b as f32 * brightness_factor).round() as u8;
+        // Multiply each RGB component by brightness factor (rounded later, at send time)
+        final_frame[offset] = base_r as f32 * brightness_factor;
+        final_frame[offset + 1] = base_g as f32 * brightness_factor;
+        final_frame[offset + 2] = base_b as f32 * brightness_factor;
 
         // Track decaying LEDs for debug
         if !is_attack && smoothed_brightness > 1.0 && target_bright < 1.0 {
             found_decaying_led = Some((led, smoothed_brightness, current_brightness, target_bright,
                                        base_r, base_g, base_b,  // Store RGB for debug
-                                       final_frame[offset], final_frame[offset+1], final_frame[offset+2]));
+                                       final_frame[offset].round() as u8, final_frame[offset+1].round() as u8, final_frame[offset+2].round() as u8));
         }
 
         // Track new attacks (lowered threshold from 50 to 1 to catch all notes)
@@ -1082,13 +1350,13 @@ pub fn render_midi_to_leds(
             let brightness_drop = current - smoothed;
             let percent_dropped = (brightness_drop / current) * 100.0;
 
-            let msg = format!("F{:3}: brightness {:.2}→{:.2} (drop={:.2}, {:.1}%) | factor={:.6} | Base RGB ({},{},{}) | Final RGB ({},{},{})",
+            let msg = || format!("F{:3}: brightness {:.2}→{:.2} (drop={:.2}, {:.1}%) | factor={:.6} | Base RGB ({},{},{}) | Final RGB ({},{},{})",
                 frame_count, current, smoothed, brightness_drop, percent_dropped,
                 decay_factor, cr, cg, cb, r, g, b);
 
             if let Some(debug) = debug_info {
                 let mut dbg = debug.lock().unwrap();
-                dbg.push(msg);
+                dbg.push(msg());
                 // Keep only last 25 lines
                 if dbg.len() > 25 {
                     dbg.remove(0);
@@ -1106,8 +1374,85 @@ pub fn render_midi_to_leds(
         }
     }
 
-    // Return frame buffer for delayed sending
-    Ok(final_frame)
+    Ok(&final_frame[..])
+}
+
+// One MIDI zone - a channel's notes on an LED range, with its own attack/decay state
+struct MidiZone {
+    channel: u8,  // 0-15
+    leds: std::ops::Range<usize>,
+    smoothed_frame: Vec<f32>,
+    target_brightness: Vec<f32>,
+    last_colors: Vec<(u8, u8, u8)>,
+    buffers: MidiFrameBuffers,
+}
+
+/// MIDI channels routed to their own LED ranges (midi_zones) - each range shows its channel's notes as if
+/// it were the whole strip, and LEDs outside every range stay dark
+#[derive(Default)]
+pub struct MidiZones {
+    zones: Vec<MidiZone>,
+    frame: Vec<f32>,
+}
+
+impl MidiZones {
+    /// Zones for (channel 0-15, LEDs) ranges, as from midi::zone_ranges
+    pub fn new(ranges: Vec<(u8, std::ops::Range<usize>)>) -> Self {
+        let zones = ranges.into_iter().map(|(channel, leds)| MidiZone {
+            channel,
+            smoothed_frame: vec![0.0; leds.len()],
+            target_brightness: vec![0.0; leds.len()],
+            last_colors: vec![(0, 0, 0); leds.len()],
+            leds,
+            buffers: MidiFrameBuffers::default(),
+        }).collect();
+        MidiZones { zones, frame: Vec::new() }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.zones.is_empty()
+    }
+
+    /// Render every zone into one float frame of total_leds (overlapping zones show the brighter color)
+    pub fn render(
+        &mut self,
+        note_state: &midi::NoteState,
+        total_leds: usize,
+        gradient_enabled: bool,
+        color_map: Option<&midi::ColorMap>,
+        velocity_colors: bool,
+        one_to_one: bool,
+        attack_factor: f32,
+        decay_factor: f32,
+    ) -> Result<&[f32]> {
+        self.frame.clear();
+        self.frame.resize(total_leds * 3, 0.0);
+        for zone in &mut self.zones {
+            let zone_frame = render_midi_to_leds(
+                note_state,
+                zone.leds.len(),
+                gradient_enabled,
+                color_map,
+                velocity_colors,
+                one_to_one,
+                false,
+                Some(zone.channel),
+                &mut zone.smoothed_frame,
+                &mut zone.target_brightness,
+                &mut zone.last_colors,
+                attack_factor,
+                decay_factor,
+                None,
+                &mut zone.buffers,
+            )?;
+            let end = (zone.leds.end * 3).min(self.frame.len());
+            let start = (zone.leds.start * 3).min(end);
+            for (out, value) in self.frame[start..end].iter_mut().zip(zone_frame) {
+                *out = out.max(*value);
+            }
+        }
+        Ok(&self.frame)
+    }
 }
 
 /// Render one channel of VU meter
@@ -1119,7 +1464,7 @@ pub fn render_vu_channel(
     direction: &str,
     animation_direction: &str,
     animation_offset: f64,
-    gradient: Option<&colorgrad::Gradient>,
+    gradient: Option<&GradientLut>,
     colors: &[Rgb],
     solid_color: Rgb,
     is_left_channel: bool,
@@ -1195,8 +1540,7 @@ pub fn render_vu_channel(
                 // This is a limitation - ideally we'd pass intensity gradients here too
                 // For now, use the gradient directly and accept slight color shift at 100%
                 let grad = gradient.unwrap();
-                let color = grad.at(level as f64);
-                let rgba = color.to_rgba8();
+                let rgba = grad.at(level as f64);
                 (rgba[0], rgba[1], rgba[2])
             } else {
                 // Normal Mode: Spatial gradient with animation
@@ -1215,8 +1559,7 @@ pub fn render_vu_channel(
                 // Get color using gradient system (same as bandwidth meter)
                 if let Some(grad) = gradient {
                     // Use gradient
-                    let color = grad.at(animated_pos);
-                    let rgba = color.to_rgba8();
+                    let rgba = grad.at(animated_pos);
                     (rgba[0], rgba[1], rgba[2])
                 } else if colors.len() > 1 {
                     // Multiple solid colors - pick one based on position
@@ -1259,3 +1602,35 @@ pub fn render_vu_channel(
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_state_cell() {
+        let cell = RenderStateCell::new(SharedRenderState::from_config(&BandwidthConfig::default()));
+        let before = Arc::clone(&cell.load());
+
+        // Edits are published together, once the writer is dropped
+        {
+            let mut state = cell.write();
+            state.current_rx_kbps = 500.0;
+            state.current_tx_kbps = 250.0;
+            assert_eq!(cell.load().current_rx_kbps, 0.0);
+        }
+        assert_eq!((cell.load().current_rx_kbps, cell.load().current_tx_kbps), (500.0, 250.0));
+        assert_eq!(before.current_rx_kbps, 0.0);  // A snapshot already taken is left alone
+
+        // Concurrent writers don't lose each other's updates
+        let generation = cell.load().generation;
+        std::thread::scope(|scope| {
+            for _ in 0..4 {
+                scope.spawn(|| for _ in 0..100 {
+                    cell.write().generation += 1;
+                });
+            }
+        });
+        assert_eq!(cell.load().generation, generation + 400);
+    }
+}
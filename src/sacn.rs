@@ -0,0 +1,261 @@
+// sACN Module - E1.31 (streaming ACN) DMX packets, as sent by lighting consoles like QLC+ - and sent by
+// rustwled to pixel controllers that only speak sACN
+use anyhow::{anyhow, Result};
+use std::net::{Ipv4Addr, SocketAddr, ToSocketAddrs, UdpSocket};
+
+/// UDP port every E1.31 source sends to
+pub const SACN_PORT: u16 = 5568;
+
+/// RGB LEDs carried per universe - 510 of the 512 DMX channels, so an LED never spans two universes
+pub const LEDS_PER_UNIVERSE: usize = 170;
+
+// Root layer
+const ACN_PACKET_ID: &[u8; 12] = b"ASC-E1.17\0\0\0";
+const VECTOR_ROOT_E131_DATA: u32 = 0x0000_0004;
+
+// Framing layer
+const VECTOR_E131_DATA_PACKET: u32 = 0x0000_0002;
+const OPTION_PREVIEW_DATA: u8 = 0x80;  // For visualizers only, not live output
+const OPTION_STREAM_TERMINATED: u8 = 0x40;
+
+const SOURCE_NAME: &[u8] = b"rustwled";  // Up to 63 bytes, null-padded to 64
+const DEFAULT_PRIORITY: u8 = 100;
+
+// DMP layer
+const VECTOR_DMP_SET_PROPERTY: u8 = 0x02;
+const DMP_ADDRESS_TYPE: u8 = 0xa1;  // 16-bit addresses, increment of 1
+const DMX_START_CODE: u8 = 0x00;  // Other start codes carry non-level data
+const DMX_DATA_OFFSET: usize = 126;  // First DMX channel after the start code
+
+// DMX channels in a universe
+const DMX_CHANNELS: usize = 512;
+
+/// Parse an E1.31 data packet into (universe, DMX channel data)
+/// Returns None for anything that isn't live level data (wrong vectors, preview, stream end, other start codes)
+pub fn parse_packet(packet: &[u8]) -> Option<(u16, &[u8])> {
+    if packet.len() < DMX_DATA_OFFSET || &packet[4..16] != ACN_PACKET_ID {
+        return None;
+    }
+    let be32 = |at: usize| u32::from_be_bytes([packet[at], packet[at + 1], packet[at + 2], packet[at + 3]]);
+    if be32(18) != VECTOR_ROOT_E131_DATA || be32(40) != VECTOR_E131_DATA_PACKET || packet[117] != VECTOR_DMP_SET_PROPERTY {
+        return None;
+    }
+    if packet[112] & (OPTION_PREVIEW_DATA | OPTION_STREAM_TERMINATED) != 0 || packet[125] != DMX_START_CODE {
+        return None;
+    }
+
+    let universe = u16::from_be_bytes([packet[113], packet[114]]);
+    // Property count includes the start code
+    let channels = (u16::from_be_bytes([packet[123], packet[124]]) as usize).saturating_sub(1);
+    let end = (DMX_DATA_OFFSET + channels).min(packet.len());
+    Some((universe, &packet[DMX_DATA_OFFSET..end]))
+}
+
+/// Multicast group a universe is sent to (239.255.hi.lo)
+pub fn multicast_addr(universe: u16) -> Ipv4Addr {
+    let [hi, lo] = universe.to_be_bytes();
+    Ipv4Addr::new(239, 255, hi, lo)
+}
+
+/// Universes needed to carry `leds` RGB LEDs
+pub fn universe_count(leds: usize) -> usize {
+    leds.div_ceil(LEDS_PER_UNIVERSE).max(1)
+}
+
+/// Where LED `index` of a device lands: (universe, DMX channel of its red, 1-512)
+/// The first LEDs fill `universe` from `start_channel`, the rest go 170 to a universe from channel 1
+/// (DMX sends whole universes, so channels before `start_channel` go out as 0)
+pub fn led_address(universe: u16, start_channel: u16, index: usize) -> (u16, u16) {
+    let first_universe_leds = (DMX_CHANNELS + 1 - start_channel.max(1) as usize) / 3;
+    if index < first_universe_leds {
+        return (universe, start_channel.max(1) + (index * 3) as u16);
+    }
+    let rest = index - first_universe_leds;
+    (universe.saturating_add(1 + (rest / LEDS_PER_UNIVERSE) as u16), 1 + ((rest % LEDS_PER_UNIVERSE) * 3) as u16)
+}
+
+/// Lay RGB data for the LEDs from `led_offset` on out as DMX channel data, per universe (see led_address)
+/// Also used for Art-Net, which carries the same universes
+pub fn dmx_universes(rgb: &[u8], universe: u16, start_channel: u16, led_offset: usize, universes: &mut Vec<(u16, Vec<u8>)>) {
+    universes.clear();
+    for (i, pixel) in rgb.chunks_exact(3).enumerate() {
+        let (universe, channel) = led_address(universe, start_channel, led_offset + i);
+        if universes.last().map(|(u, _)| *u) != Some(universe) {
+            universes.push((universe, Vec::with_capacity(DMX_CHANNELS)));
+        }
+        let data = &mut universes.last_mut().unwrap().1;
+        let at = channel as usize - 1;
+        if data.len() < at + 3 {
+            data.resize(at + 3, 0);
+        }
+        data[at..at + 3].copy_from_slice(pixel);
+    }
+}
+
+/// Sends RGB frames as E1.31 data packets, unicast to one receiver
+pub struct SacnSender {
+    socket: UdpSocket,
+    dest: SocketAddr,
+    universe: u16,
+    start_channel: u16,
+    cid: [u8; 16],  // Identifies this source to receivers, fixed for its lifetime
+    sequences: Vec<(u16, u8)>,  // Next sequence number per universe
+    universes: Vec<(u16, Vec<u8>)>,  // Channel data of the frame being sent, per universe
+}
+
+impl SacnSender {
+    /// Sender for a receiver at `host` whose LEDs start at `start_channel` of `universe`
+    pub fn new(host: &str, universe: u16, start_channel: u16) -> Result<Self> {
+        let dest = (host, SACN_PORT).to_socket_addrs()?.next().ok_or_else(|| anyhow!("Could not resolve {}", host))?;
+        let socket = UdpSocket::bind("0.0.0.0:0")?;
+        Ok(SacnSender {
+            socket,
+            dest,
+            universe,
+            start_channel,
+            cid: rand::random(),
+            sequences: Vec::new(),
+            universes: Vec::new(),
+        })
+    }
+
+    /// Send RGB data for the LEDs from `led_offset` on, one packet per universe they span
+    /// Returns the number of LED bytes sent
+    pub fn send(&mut self, rgb: &[u8], led_offset: usize) -> Result<usize> {
+        let mut universes = std::mem::take(&mut self.universes);
+        dmx_universes(rgb, self.universe, self.start_channel, led_offset, &mut universes);
+
+        let mut result = Ok(rgb.len());
+        for (universe, data) in &universes {
+            let sequence = self.next_sequence(*universe);
+            let packet = data_packet(&self.cid, *universe, sequence, data);
+            if let Err(e) = self.socket.send_to(&packet, self.dest) {
+                result = Err(anyhow!("sACN universe {}: {}", universe, e));
+            }
+        }
+        self.universes = universes;
+        result
+    }
+
+    fn next_sequence(&mut self, universe: u16) -> u8 {
+        match self.sequences.iter_mut().find(|(u, _)| *u == universe) {
+            Some((_, sequence)) => {
+                *sequence = sequence.wrapping_add(1);
+                *sequence
+            }
+            None => {
+                self.sequences.push((universe, 0));
+                0
+            }
+        }
+    }
+}
+
+// An E1.31 data packet carrying `dmx` from channel 1
+fn data_packet(cid: &[u8; 16], universe: u16, sequence: u8, dmx: &[u8]) -> Vec<u8> {
+    let dmx = &dmx[..dmx.len().min(DMX_CHANNELS)];
+    let len = DMX_DATA_OFFSET + dmx.len();
+    // PDU lengths count from their own flags & length field, with the top nibble set to 0x7
+    let flags_length = |from: usize| (0x7000 | (len - from) as u16).to_be_bytes();
+
+    let mut p = vec![0u8; DMX_DATA_OFFSET];
+    p[0..2].copy_from_slice(&0x0010u16.to_be_bytes());  // Preamble size
+    p[4..16].copy_from_slice(ACN_PACKET_ID);
+    p[16..18].copy_from_slice(&flags_length(16));
+    p[18..22].copy_from_slice(&VECTOR_ROOT_E131_DATA.to_be_bytes());
+    p[22..38].copy_from_slice(cid);
+
+    p[38..40].copy_from_slice(&flags_length(38));
+    p[40..44].copy_from_slice(&VECTOR_E131_DATA_PACKET.to_be_bytes());
+    p[44..44 + SOURCE_NAME.len()].copy_from_slice(SOURCE_NAME);
+    p[108] = DEFAULT_PRIORITY;
+    p[111] = sequence;
+    p[113..115].copy_from_slice(&universe.to_be_bytes());
+
+    p[115..117].copy_from_slice(&flags_length(115));
+    p[117] = VECTOR_DMP_SET_PROPERTY;
+    p[118] = DMP_ADDRESS_TYPE;
+    p[121..123].copy_from_slice(&1u16.to_be_bytes());  // Address increment
+    p[123..125].copy_from_slice(&(dmx.len() as u16 + 1).to_be_bytes());  // Property count includes the start code
+    p[125] = DMX_START_CODE;
+    p.extend_from_slice(dmx);
+    p
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Minimal E1.31 data packet for a universe
+    fn packet(universe: u16, options: u8, dmx: &[u8]) -> Vec<u8> {
+        let mut p = vec![0u8; DMX_DATA_OFFSET];
+        p[1] = 0x10;
+        p[4..16].copy_from_slice(ACN_PACKET_ID);
+        p[18..22].copy_from_slice(&VECTOR_ROOT_E131_DATA.to_be_bytes());
+        p[40..44].copy_from_slice(&VECTOR_E131_DATA_PACKET.to_be_bytes());
+        p[112] = options;
+        p[113..115].copy_from_slice(&universe.to_be_bytes());
+        p[117] = VECTOR_DMP_SET_PROPERTY;
+        p[118] = 0xa1;
+        p[122] = 1;
+        p[123..125].copy_from_slice(&(dmx.len() as u16 + 1).to_be_bytes());
+        p.extend_from_slice(dmx);
+        p
+    }
+
+    #[test]
+    fn test_parse_packet() {
+        let (universe, data) = parse_packet(&packet(7, 0, &[255, 128, 0])).unwrap();
+        assert_eq!(universe, 7);
+        assert_eq!(data, &[255, 128, 0]);
+
+        // Preview data and stream termination aren't output
+        assert!(parse_packet(&packet(7, OPTION_PREVIEW_DATA, &[1])).is_none());
+        assert!(parse_packet(&packet(7, OPTION_STREAM_TERMINATED, &[1])).is_none());
+
+        // Not E1.31
+        let mut bad = packet(7, 0, &[1]);
+        bad[4] = b'X';
+        assert!(parse_packet(&bad).is_none());
+        assert!(parse_packet(&[0u8; 20]).is_none());
+    }
+
+    #[test]
+    fn test_universes() {
+        assert_eq!(multicast_addr(1), Ipv4Addr::new(239, 255, 0, 1));
+        assert_eq!(multicast_addr(300), Ipv4Addr::new(239, 255, 1, 44));
+        assert_eq!(universe_count(170), 1);
+        assert_eq!(universe_count(171), 2);
+        assert_eq!(universe_count(0), 1);
+    }
+
+    #[test]
+    fn test_data_packet_round_trip() {
+        let packet = data_packet(&[7; 16], 12, 3, &[0, 0, 255, 128, 0]);
+        assert_eq!(packet.len(), DMX_DATA_OFFSET + 5);
+        assert_eq!(u16::from_be_bytes([packet[16], packet[17]]), 0x7000 | (packet.len() - 16) as u16);
+        assert_eq!(parse_packet(&packet), Some((12, &[0, 0, 255, 128, 0][..])));
+    }
+
+    #[test]
+    fn test_dmx_universes() {
+        // 3 LEDs from channel 505: two fit in the first universe, the third starts the next
+        let mut universes = Vec::new();
+        dmx_universes(&[1, 2, 3, 4, 5, 6, 7, 8, 9], 1, 505, 0, &mut universes);
+        assert_eq!(universes.len(), 2);
+        assert_eq!((universes[0].0, universes[0].1.len()), (1, 510));
+        assert_eq!(&universes[0].1[504..], &[1, 2, 3, 4, 5, 6]);
+        assert_eq!(universes[1], (2, vec![7, 8, 9]));
+    }
+
+    #[test]
+    fn test_led_address() {
+        assert_eq!(led_address(1, 1, 0), (1, 1));
+        assert_eq!(led_address(1, 1, 169), (1, 508));
+        assert_eq!(led_address(1, 1, 170), (2, 1));
+        // From channel 301, 70 LEDs fit in the first universe
+        assert_eq!(led_address(5, 301, 69), (5, 508));
+        assert_eq!(led_address(5, 301, 70), (6, 1));
+        assert_eq!(led_address(5, 301, 70 + 170), (7, 1));
+    }
+}
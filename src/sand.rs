@@ -1,6 +1,9 @@
 use rand::Rng;
 use std::collections::HashMap;
 
+use crate::config::SandConfig;
+use crate::matrix::MatrixLayout;
+
 /// Parse hex color string (with or without #) to RGB tuple
 fn parse_hex_color(hex: &str) -> (u8, u8, u8) {
     let hex = hex.trim_start_matches('#');
@@ -28,6 +31,19 @@ pub enum Particle {
 }
 
 impl Particle {
+    /// Parse a particle name from config (unknown names fall back to sand)
+    pub fn from_name(name: &str) -> Particle {
+        match name.to_lowercase().as_str() {
+            "water" => Particle::Water,
+            "stone" => Particle::Stone,
+            "fire" => Particle::Fire,
+            "smoke" => Particle::Smoke,
+            "wood" => Particle::Wood,
+            "lava" => Particle::Lava,
+            _ => Particle::Sand,
+        }
+    }
+
     /// Returns true if this particle type falls due to gravity
     pub fn falls(&self) -> bool {
         matches!(self, Particle::Sand | Particle::Water | Particle::Lava | Particle::Wood | Particle::Stone | Particle::Fire)
@@ -87,82 +103,46 @@ pub struct SandSimulation {
 }
 
 impl SandSimulation {
-    pub fn new(
-        width: usize,
-        height: usize,
-        spawn_particle: Particle,
-        spawn_rate: f32,
-        spawn_radius: usize,
-        spawn_x: usize,
-        fire_enabled: bool,
-        color_sand: &str,
-        color_water: &str,
-        color_stone: &str,
-        color_fire: &str,
-        color_smoke: &str,
-        color_wood: &str,
-        color_lava: &str,
-    ) -> Self {
+    pub fn new(width: usize, height: usize, sand: &SandConfig) -> Self {
         let size = width * height;
 
-        // Initialize color map
         let mut colors = HashMap::new();
         colors.insert(Particle::Empty, (0, 0, 0));
-        colors.insert(Particle::Sand, parse_hex_color(color_sand));
-        colors.insert(Particle::Water, parse_hex_color(color_water));
-        colors.insert(Particle::Stone, parse_hex_color(color_stone));
-        colors.insert(Particle::Fire, parse_hex_color(color_fire));
-        colors.insert(Particle::Smoke, parse_hex_color(color_smoke));
-        colors.insert(Particle::Wood, parse_hex_color(color_wood));
-        colors.insert(Particle::Lava, parse_hex_color(color_lava));
-
-        // Clamp spawn_x to valid range
-        let spawn_x = spawn_x.min(if width > 0 { width - 1 } else { 0 });
 
-        Self {
+        let mut sim = Self {
             width,
             height,
             grid: vec![Particle::Empty; size],
             velocity: vec![(0, 0); size],
             fixed: vec![false; size],
-            spawn_particle,
-            spawn_rate,
-            spawn_radius,
-            spawn_x,
-            fire_enabled,
+            spawn_particle: Particle::Sand,
+            spawn_rate: 0.0,
+            spawn_radius: 0,
+            spawn_x: 0,
+            fire_enabled: false,
             colors,
-        }
+        };
+        sim.update_config(sand);
+        sim
     }
 
-    pub fn update_config(
-        &mut self,
-        spawn_particle: Particle,
-        spawn_rate: f32,
-        spawn_radius: usize,
-        spawn_x: usize,
-        fire_enabled: bool,
-        color_sand: &str,
-        color_water: &str,
-        color_stone: &str,
-        color_fire: &str,
-        color_smoke: &str,
-        color_wood: &str,
-        color_lava: &str,
-    ) {
-        self.spawn_particle = spawn_particle;
-        self.spawn_rate = spawn_rate.clamp(0.0, 1.0);
-        self.spawn_radius = spawn_radius;
-        self.spawn_x = spawn_x.min(if self.width > 0 { self.width - 1 } else { 0 });
-        self.fire_enabled = fire_enabled;
+    /// Apply spawn and color settings without clearing the grid
+    pub fn update_config(&mut self, sand: &SandConfig) {
+        self.spawn_particle = Particle::from_name(&sand.particle_type);
+        self.spawn_rate = (sand.spawn_rate as f32).clamp(0.0, 1.0);
+        self.spawn_radius = sand.spawn_radius;
+        // Clamp spawn_x to valid range
+        self.spawn_x = sand.spawn_x.min(if self.width > 0 { self.width - 1 } else { 0 });
+        self.fire_enabled = sand.fire_enabled;
 
         // Update colors
-        self.colors.insert(Particle::Sand, parse_hex_color(color_sand));
-        self.colors.insert(Particle::Water, parse_hex_color(color_water));
-        self.colors.insert(Particle::Stone, parse_hex_color(color_stone));
-        self.colors.insert(Particle::Fire, parse_hex_color(color_fire));
-        self.colors.insert(Particle::Smoke, parse_hex_color(color_smoke));
-        self.colors.insert(Particle::Wood, parse_hex_color(color_wood));
-        self.colors.insert(Particle::Lava, parse_hex_color(color_lava));
+        self.colors.insert(Particle::Sand, parse_hex_color(&sand.color_sand));
+        self.colors.insert(Particle::Water, parse_hex_color(&sand.color_water));
+        self.colors.insert(Particle::Stone, parse_hex_color(&sand.color_stone));
+        self.colors.insert(Particle::Fire, parse_hex_color(&sand.color_fire));
+        self.colors.insert(Particle::Smoke, parse_hex_color(&sand.color_smoke));
+        self.colors.insert(Particle::Wood, parse_hex_color(&sand.color_wood));
+        self.colors.insert(Particle::Lava, parse_hex_color(&sand.color_lava));
     }
 
     fn get(&self, x: usize, y: usize) -> Particle {
@@ -278,13 +258,10 @@ impl SandSimulation {
 
         // Process grid from bottom to top, randomizing left/right to avoid bias
         for y in (0..self.height).rev() {
-            let x_order: Vec<usize> = if rng.gen::<bool>() {
-                (0..self.width).collect()
-            } else {
-                (0..self.width).rev().collect()
-            };
+            let left_to_right = rng.gen::<bool>();
 
-            for &x in &x_order {
+            for i in 0..self.width {
+                let x = if left_to_right { i } else { self.width - 1 - i };
                 let particle = self.get(x, y);
                 if particle == Particle::Empty {
                     continue;
@@ -446,23 +423,19 @@ impl SandSimulation {
         }
     }
 
-    /// Render grid to RGB frame for LEDs
-    pub fn render(&self, total_leds: usize) -> Vec<u8> {
-        let mut frame = vec![0u8; total_leds * 3];
+    /// Render grid to RGB frame for LEDs (into `frame`, reusing its allocation)
+    /// The layout maps grid cells to LEDs (serpentine on a single matrix, or tiled panels)
+    pub fn render(&self, total_leds: usize, layout: &MatrixLayout, frame: &mut Vec<u8>) {
+        frame.clear();
+        frame.resize(total_leds * 3, 0);
 
-        // Map 2D grid to 1D LED strip (serpentine pattern)
         for y in 0..self.height {
             for x in 0..self.width {
                 let particle = self.get(x, y);
                 let (r, g, b) = self.colors.get(&particle).copied().unwrap_or((0, 0, 0));
 
-                // Calculate LED index with serpentine mapping
-                let led_idx = if y % 2 == 0 {
-                    // Even rows go left to right
-                    y * self.width + x
-                } else {
-                    // Odd rows go right to left
-                    y * self.width + (self.width - 1 - x)
+                let Some(led_idx) = layout.index(x, y) else {
+                    continue;
                 };
 
                 if led_idx < total_leds {
@@ -473,8 +446,6 @@ impl SandSimulation {
                 }
             }
         }
-
-        frame
     }
 
     /// Clear the grid
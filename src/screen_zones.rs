@@ -0,0 +1,142 @@
+// Screen Zones Module - Ambilight-style color extraction for webcam/screen capture: the image is split into
+// zones (a grid, the screen edges, or custom rectangles), each averaged, smoothed and written to its own LED range
+use crate::config::{BandwidthConfig, ScreenZoneConfig};
+
+/// Zones for the configured layout - None for "pixels" (each captured pixel drives one LED)
+pub fn zones_for(config: &BandwidthConfig) -> Option<Vec<ScreenZoneConfig>> {
+    let webcam = &config.webcam;
+    match webcam.zone_layout.as_str() {
+        "grid" => Some(grid_zones(webcam.zone_columns, webcam.zone_rows, config.total_leds)),
+        "edges" => Some(edge_zones(webcam.zone_columns, webcam.zone_rows, webcam.zone_depth)),
+        "custom" => Some(config.webcam_zones.clone()),
+        _ => None,
+    }
+}
+
+/// Grid cells in row-major order, sharing the strip evenly
+fn grid_zones(columns: usize, rows: usize, total_leds: usize) -> Vec<ScreenZoneConfig> {
+    let (columns, rows) = (columns.max(1), rows.max(1));
+    let leds_per_zone = (total_leds / (columns * rows)).max(1);
+    let (cell_w, cell_h) = (100.0 / columns as f64, 100.0 / rows as f64);
+    (0..rows * columns)
+        .map(|i| ScreenZoneConfig {
+            x: (i % columns) as f64 * cell_w,
+            y: (i / columns) as f64 * cell_h,
+            width: cell_w,
+            height: cell_h,
+            led_offset: i * leds_per_zone,
+            led_count: leds_per_zone,
+            smoothing: None,
+        })
+        .collect()
+}
+
+/// One LED per zone around the screen edge, clockwise from the top left corner: `columns` LEDs along
+/// the top and bottom, `rows` down each side - the usual TV backlight layout
+fn edge_zones(columns: usize, rows: usize, depth: f64) -> Vec<ScreenZoneConfig> {
+    let (cell_w, cell_h) = (100.0 / columns.max(1) as f64, 100.0 / rows.max(1) as f64);
+    let zone = |x: f64, y: f64, width: f64, height: f64| ScreenZoneConfig { x, y, width, height, led_offset: 0, led_count: 1, smoothing: None };
+
+    let mut zones = Vec::with_capacity(2 * (columns + rows));
+    zones.extend((0..columns).map(|i| zone(i as f64 * cell_w, 0.0, cell_w, depth)));  // Top, left to right
+    zones.extend((0..rows).map(|i| zone(100.0 - depth, i as f64 * cell_h, depth, cell_h)));  // Right, downwards
+    zones.extend((0..columns).rev().map(|i| zone(i as f64 * cell_w, 100.0 - depth, cell_w, depth)));  // Bottom, right to left
+    zones.extend((0..rows).rev().map(|i| zone(0.0, i as f64 * cell_h, depth, cell_h)));  // Left, upwards
+    for (led, zone) in zones.iter_mut().enumerate() {
+        zone.led_offset = led;
+    }
+    zones
+}
+
+/// Smoothed zone colors carried between frames
+#[derive(Default)]
+pub struct ScreenZones {
+    smoothed: Vec<[f32; 3]>,
+}
+
+impl ScreenZones {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Average each zone of an RGB image, ease it toward the new color, and fill its LED range
+    /// `smoothing` is the default for zones without their own (0 = follow the picture instantly)
+    pub fn render(&mut self, zones: &[ScreenZoneConfig], image: &[u8], width: usize, height: usize,
+                  smoothing: f64, total_leds: usize) -> Vec<u8> {
+        if self.smoothed.len() != zones.len() {
+            self.smoothed = vec![[0.0; 3]; zones.len()];
+        }
+
+        let mut frame = vec![0u8; total_leds * 3];
+        for (zone, smoothed) in zones.iter().zip(&mut self.smoothed) {
+            let target = average_rect(image, width, height, zone);
+            let keep = zone.smoothing.unwrap_or(smoothing).clamp(0.0, 0.99) as f32;
+            for (value, target) in smoothed.iter_mut().zip(target) {
+                *value = *value * keep + target * (1.0 - keep);
+            }
+
+            let color = smoothed.map(|v| v.round().clamp(0.0, 255.0) as u8);
+            let start = (zone.led_offset * 3).min(frame.len());
+            let end = ((zone.led_offset + zone.led_count) * 3).min(frame.len());
+            for pixel in frame[start..end].chunks_exact_mut(3) {
+                pixel.copy_from_slice(&color);
+            }
+        }
+        frame
+    }
+}
+
+// Average color of a zone's rectangle (percent of the image) - at least one pixel is always sampled
+fn average_rect(image: &[u8], width: usize, height: usize, zone: &ScreenZoneConfig) -> [f32; 3] {
+    if width == 0 || height == 0 || image.len() < width * height * 3 {
+        return [0.0; 3];
+    }
+    let span = |start: f64, size: f64, pixels: usize| {
+        let from = ((start / 100.0 * pixels as f64).floor() as usize).min(pixels - 1);
+        let to = (((start + size) / 100.0 * pixels as f64).ceil() as usize).clamp(from + 1, pixels);
+        from..to
+    };
+    let (xs, ys) = (span(zone.x, zone.width, width), span(zone.y, zone.height, height));
+
+    let mut sums = [0u32; 3];
+    for y in ys.clone() {
+        let row = &image[(y * width + xs.start) * 3..(y * width + xs.end) * 3];
+        for pixel in row.chunks_exact(3) {
+            for (sum, &value) in sums.iter_mut().zip(pixel) {
+                *sum += value as u32;
+            }
+        }
+    }
+    let count = (xs.len() * ys.len()) as f32;
+    sums.map(|sum| sum as f32 / count)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_edge_zones() {
+        let zones = edge_zones(4, 2, 10.0);
+        assert_eq!(zones.len(), 12);
+        // Clockwise from the top left: first top zone, first right zone, last bottom zone is bottom left
+        assert_eq!((zones[0].x, zones[0].y, zones[0].led_offset), (0.0, 0.0, 0));
+        assert_eq!((zones[4].x, zones[4].y, zones[4].led_offset), (90.0, 0.0, 4));
+        assert_eq!((zones[9].x, zones[9].y), (0.0, 90.0));
+        assert_eq!((zones[11].x, zones[11].y, zones[11].led_offset), (0.0, 0.0, 11));
+    }
+
+    #[test]
+    fn test_render() {
+        // 2x1 image: red on the left, blue on the right
+        let image = [255, 0, 0, 0, 0, 255];
+        let zones = grid_zones(2, 1, 4);
+        let mut screen_zones = ScreenZones::new();
+        let frame = screen_zones.render(&zones, &image, 2, 1, 0.0, 4);
+        assert_eq!(frame, vec![255, 0, 0, 255, 0, 0, 0, 0, 255, 0, 0, 255]);
+
+        // Smoothing eases toward a changed picture instead of jumping
+        let frame = screen_zones.render(&zones, &[0; 6], 2, 1, 0.5, 4);
+        assert_eq!(&frame[..3], &[128, 0, 0]);
+    }
+}
@@ -0,0 +1,200 @@
+// Segments Module - Confines a device's output to one of its WLED segments, so the rest of the strip keeps
+// running WLED's own effects. Segment bounds are read from the device's JSON API and kept up to date, and
+// frames are truncated to the segment and sent at its offset
+use std::sync::Mutex;
+use std::thread;
+use std::time::Duration;
+use tokio::sync::broadcast;
+
+use crate::cli;
+use crate::config::{self, BandwidthConfig, ConfigDelta};
+use crate::live_stats;
+use crate::virtual_device;
+
+// How long to wait on each device's JSON API
+const API_TIMEOUT: Duration = Duration::from_secs(2);
+
+// How often segment bounds are read again (they can be edited in the WLED app at any time)
+const REFRESH_INTERVAL: Duration = Duration::from_secs(10);
+
+// Devices confined to a segment: (IP, segment id)
+static CONFIGURED: Mutex<Vec<(String, u32)>> = Mutex::new(Vec::new());
+
+// Where each confined device's segment was found - None until it has been read
+static TARGETS: Mutex<Vec<(String, Option<SegmentTarget>)>> = Mutex::new(Vec::new());
+
+/// Where a device's frames go within its strip
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SegmentTarget {
+    pub start: usize,  // First LED of the segment
+    pub len: usize,  // LEDs in the segment
+    pub relative: bool,  // The device maps realtime data onto its main segment ("Use main segment only")
+}
+
+impl SegmentTarget {
+    /// The part of a device frame that fits in the segment
+    pub fn frame<'a>(&self, frame: &'a [u8]) -> &'a [u8] {
+        &frame[..frame.len().min(self.len * 3)]
+    }
+
+    /// DDP byte offset to send the segment's pixels at
+    pub fn ddp_offset(&self) -> u32 {
+        if self.relative { 0 } else { (self.start * 3) as u32 }
+    }
+}
+
+/// How a device's frames are placed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Placement {
+    Whole,  // No segment configured - the frame covers the strip from its start
+    Pending,  // Segment configured but not read from the device yet - nothing is sent
+    Segment(SegmentTarget),
+}
+
+pub fn placement(ip: &str) -> Placement {
+    match TARGETS.lock().unwrap().iter().find(|(target_ip, _)| target_ip == ip) {
+        Some((_, Some(target))) => Placement::Segment(*target),
+        Some((_, None)) => Placement::Pending,
+        None => Placement::Whole,
+    }
+}
+
+/// Take the segment settings from the config and read the segments in the background
+pub fn configure(config: &BandwidthConfig) {
+    let configured: Vec<(String, u32)> = config.wled_devices.iter()
+        .filter(|d| d.enabled && d.segment >= 0 && d.protocol == "ddp" && !virtual_device::is_virtual(&d.ip))
+        .map(|d| (d.ip.clone(), d.segment as u32))
+        .collect();
+
+    // Devices whose segment changed wait for it to be read again
+    {
+        let previous = CONFIGURED.lock().unwrap();
+        let mut targets = TARGETS.lock().unwrap();
+        let kept: Vec<_> = configured.iter()
+            .map(|(ip, id)| {
+                let unchanged = previous.iter().any(|(p_ip, p_id)| p_ip == ip && p_id == id);
+                let target = targets.iter().find(|(t_ip, _)| t_ip == ip).and_then(|(_, t)| *t);
+                (ip.clone(), if unchanged { target } else { None })
+            })
+            .collect();
+        *targets = kept;
+    }
+    *CONFIGURED.lock().unwrap() = configured.clone();
+
+    if !configured.is_empty() {
+        thread::spawn(move || refresh(&configured));
+    }
+}
+
+// Read each device's segment bounds and record where its frames go
+fn refresh(configured: &[(String, u32)]) {
+    for (ip, id) in configured {
+        let Some(target) = read_target(ip, *id) else { continue };
+        let mut targets = TARGETS.lock().unwrap();
+        // Skip devices dropped from the config meanwhile
+        if let Some(entry) = targets.iter_mut().find(|(t_ip, _)| t_ip == ip) {
+            if entry.1 != Some(target) {
+                live_stats::record_notice(format!("🧩 {} segment {}: LEDs {}-{}", ip, id, target.start, target.start + target.len.max(1) - 1));
+            }
+            entry.1 = Some(target);
+        }
+    }
+}
+
+// Find segment `id` on the device - None if the device can't be reached or has no such segment
+fn read_target(ip: &str, id: u32) -> Option<SegmentTarget> {
+    let state = cli::fetch_wled_json(ip, "/json/state", API_TIMEOUT)?;
+    let Some((start, len)) = segment_bounds(&state, id) else {
+        live_stats::record_notice(format!("Warning: {} has no segment {} - nothing is sent to it", ip, id));
+        return None;
+    };
+
+    // With "Use main segment only" on, realtime data lands on the main segment and the other segments keep
+    // their effects; without it realtime takes over the whole strip
+    let relative = cli::fetch_wled_json(ip, "/json/cfg", API_TIMEOUT)
+        .and_then(|cfg| cfg["if"]["live"]["mso"].as_bool())
+        .unwrap_or(false);
+    if relative {
+        if state["mainseg"].as_u64() != Some(id as u64) {
+            if let Err(e) = cli::post_wled_json(ip, "/json/state", &serde_json::json!({ "mainseg": id }), API_TIMEOUT) {
+                live_stats::record_notice(format!("Warning: Could not make segment {} the main segment on {}: {}", id, ip, e));
+                return None;
+            }
+        }
+    } else if !warned(ip) {
+        live_stats::record_notice(format!(
+            "Warning: \"Use main segment only\" is off on {} - the rest of the strip can't run effects while rustwled sends to segment {}",
+            ip, id
+        ));
+    }
+
+    Some(SegmentTarget { start, len, relative })
+}
+
+// Only warn about a device's realtime settings once per run
+fn warned(ip: &str) -> bool {
+    static WARNED: Mutex<Vec<String>> = Mutex::new(Vec::new());
+    let mut warned = WARNED.lock().unwrap();
+    if warned.iter().any(|w| w == ip) {
+        return true;
+    }
+    warned.push(ip.to_string());
+    false
+}
+
+// (start, length) of segment `id` in a /json/state response
+fn segment_bounds(state: &serde_json::Value, id: u32) -> Option<(usize, usize)> {
+    let segment = state["seg"].as_array()?.iter()
+        .find(|seg| seg["id"].as_u64() == Some(id as u64))?;
+    let start = segment["start"].as_u64()? as usize;
+    let stop = segment["stop"].as_u64()? as usize;  // One past the last LED
+    (stop > start).then_some((start, stop - start))
+}
+
+/// Read segment bounds again every few seconds, following edits made in the WLED app
+pub fn spawn_poller() {
+    thread::spawn(|| loop {
+        thread::sleep(REFRESH_INTERVAL);
+        let configured = CONFIGURED.lock().unwrap().clone();
+        refresh(&configured);
+    });
+}
+
+/// Keep the segment settings in step with config changes (web UI, API, or file edits)
+pub fn spawn_watcher(config_change_tx: broadcast::Sender<ConfigDelta>) {
+    config::spawn_config_listener(&config_change_tx, config::DEVICE_CONFIG_KEYS, configure);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_segment_bounds() {
+        let state = serde_json::json!({
+            "mainseg": 0,
+            "seg": [
+                { "id": 0, "start": 0, "stop": 60 },
+                { "id": 2, "start": 60, "stop": 150 },
+                { "id": 3, "start": 150, "stop": 150 },
+            ]
+        });
+        assert_eq!(segment_bounds(&state, 0), Some((0, 60)));
+        assert_eq!(segment_bounds(&state, 2), Some((60, 90)));
+        assert_eq!(segment_bounds(&state, 1), None);
+        assert_eq!(segment_bounds(&state, 3), None);  // Empty
+        assert_eq!(segment_bounds(&serde_json::json!({}), 0), None);
+    }
+
+    #[test]
+    fn test_segment_target() {
+        let frame = [7u8; 300];
+        let absolute = SegmentTarget { start: 60, len: 90, relative: false };
+        assert_eq!(absolute.frame(&frame).len(), 270);
+        assert_eq!(absolute.ddp_offset(), 180);
+
+        let relative = SegmentTarget { start: 60, len: 200, relative: true };
+        assert_eq!(relative.frame(&frame).len(), 300);
+        assert_eq!(relative.ddp_offset(), 0);
+    }
+}
@@ -0,0 +1,195 @@
+// Signal Generator Module - Built-in test signals (sine sweep, pink noise, metronome clicks) that live mode
+// can take in place of a capture device, to check the audio -> FFT -> LED chain without playing music
+use std::f64::consts::TAU;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Sample rate of the generated signals (mono)
+pub const SAMPLE_RATE: f32 = 44100.0;
+
+// Sweep: 20Hz to 20kHz on a log scale, so each octave gets the same time on the strip
+const SWEEP_FROM_HZ: f64 = 20.0;
+const SWEEP_TO_HZ: f64 = 20000.0;
+const SWEEP_SECS: f64 = 10.0;
+
+// Metronome: 120 BPM in 4/4, the first beat of each bar higher
+const CLICK_BPM: f64 = 120.0;
+const CLICK_HZ: f64 = 1000.0;
+const ACCENT_HZ: f64 = 2000.0;
+
+// Generated signals peak at this level, leaving headroom like real program material
+const LEVEL: f64 = 0.5;
+
+/// A built-in test signal
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Signal {
+    Sweep,
+    PinkNoise,
+    Clicks,
+}
+
+impl Signal {
+    const ALL: [Signal; 3] = [Signal::Sweep, Signal::PinkNoise, Signal::Clicks];
+
+    /// audio_device name that selects this signal
+    pub fn device_name(self) -> &'static str {
+        match self {
+            Signal::Sweep => "generator:sweep",
+            Signal::PinkNoise => "generator:pink",
+            Signal::Clicks => "generator:clicks",
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Signal::Sweep => "sine sweep 20Hz-20kHz",
+            Signal::PinkNoise => "pink noise",
+            Signal::Clicks => "metronome clicks",
+        }
+    }
+
+    /// The signal an audio_device name selects (None = a real capture device)
+    pub fn from_device_name(name: &str) -> Option<Signal> {
+        Signal::ALL.into_iter().find(|signal| signal.device_name() == name)
+    }
+}
+
+/// audio_device names of the test signals, for device pickers
+pub fn device_names() -> Vec<String> {
+    Signal::ALL.iter().map(|signal| signal.device_name().to_string()).collect()
+}
+
+/// Produces a signal one sample at a time
+pub struct Generator {
+    signal: Signal,
+    n: u64,  // Samples generated so far
+    noise: u32,  // xorshift state
+    pink: [f64; 7],  // Pink noise filter state
+}
+
+impl Generator {
+    pub fn new(signal: Signal) -> Self {
+        Generator { signal, n: 0, noise: 0x2545_F491, pink: [0.0; 7] }
+    }
+
+    pub fn next_sample(&mut self) -> f32 {
+        let t = self.n as f64 / SAMPLE_RATE as f64;
+        self.n += 1;
+        let sample = match self.signal {
+            Signal::Sweep => sweep(t),
+            Signal::PinkNoise => {
+                let white = self.white();
+                self.pink_filter(white)
+            }
+            Signal::Clicks => click(t),
+        };
+        (sample.clamp(-1.0, 1.0) * LEVEL) as f32
+    }
+
+    // White noise in -1.0..1.0 (xorshift)
+    fn white(&mut self) -> f64 {
+        self.noise ^= self.noise << 13;
+        self.noise ^= self.noise >> 17;
+        self.noise ^= self.noise << 5;
+        self.noise as f64 / u32::MAX as f64 * 2.0 - 1.0
+    }
+
+    // Paul Kellet's filter - white noise falling off at 3dB per octave, the same energy in every octave
+    fn pink_filter(&mut self, white: f64) -> f64 {
+        let b = &mut self.pink;
+        b[0] = 0.99886 * b[0] + white * 0.0555179;
+        b[1] = 0.99332 * b[1] + white * 0.0750759;
+        b[2] = 0.96900 * b[2] + white * 0.1538520;
+        b[3] = 0.86650 * b[3] + white * 0.3104856;
+        b[4] = 0.55000 * b[4] + white * 0.5329522;
+        b[5] = -0.7616 * b[5] - white * 0.0168980;
+        let pink = b[0] + b[1] + b[2] + b[3] + b[4] + b[5] + b[6] + white * 0.5362;
+        b[6] = white * 0.115926;
+        pink * 0.11  // Back to roughly unit level
+    }
+}
+
+// Exponential sine sweep, starting over every SWEEP_SECS
+fn sweep(t: f64) -> f64 {
+    let t = t % SWEEP_SECS;
+    let ratio = (SWEEP_TO_HZ / SWEEP_FROM_HZ).ln();
+    // Phase is the integral of the frequency SWEEP_FROM_HZ * e^(ratio * t / SWEEP_SECS)
+    let phase = SWEEP_FROM_HZ * SWEEP_SECS / ratio * ((ratio * t / SWEEP_SECS).exp() - 1.0);
+    (TAU * phase).sin()
+}
+
+// Short decaying tone burst on every beat
+fn click(t: f64) -> f64 {
+    let beat_len = 60.0 / CLICK_BPM;
+    let beat = (t / beat_len) as u64;
+    let since_beat = t - beat as f64 * beat_len;
+    let hz = if beat % 4 == 0 { ACCENT_HZ } else { CLICK_HZ };
+    (TAU * hz * since_beat).sin() * (-250.0 * since_beat).exp()
+}
+
+/// Feed live mode's sample buffer with a signal in real time, in place of a capture device
+/// Stops once live mode drops the buffer
+pub fn spawn_feed(buffer: &Arc<Mutex<Vec<f32>>>, samples_received: &Arc<AtomicUsize>, mut next_sample: impl FnMut() -> f32 + Send + 'static) {
+    let buffer = Arc::downgrade(buffer);
+    let samples_received = samples_received.clone();
+    thread::spawn(move || {
+        let started = Instant::now();
+        let mut generated = 0usize;
+        loop {
+            {
+                let Some(buffer) = buffer.upgrade() else { break };
+                let due = (started.elapsed().as_secs_f64() * SAMPLE_RATE as f64) as usize;
+                let samples: Vec<f32> = (generated..due).map(|_| next_sample()).collect();
+                generated = due;
+
+                let mut buffer = buffer.lock().unwrap();
+                buffer.extend_from_slice(&samples);
+                // Keep last 2 seconds, like a capture stream
+                let max_size = SAMPLE_RATE as usize * 2;
+                if buffer.len() > max_size {
+                    let drain = buffer.len() - max_size;
+                    buffer.drain(0..drain);
+                }
+                samples_received.fetch_add(samples.len(), Ordering::Relaxed);
+            }
+            thread::sleep(Duration::from_millis(10));
+        }
+    });
+}
+
+/// Feed live mode's sample buffer with a test signal
+pub fn spawn(signal: Signal, buffer: &Arc<Mutex<Vec<f32>>>, samples_received: &Arc<AtomicUsize>) {
+    let mut generator = Generator::new(signal);
+    spawn_feed(buffer, samples_received, move || generator.next_sample());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_device_names() {
+        for signal in Signal::ALL {
+            assert_eq!(Signal::from_device_name(signal.device_name()), Some(signal));
+        }
+        assert_eq!(Signal::from_device_name("Built-in Microphone [INPUT]"), None);
+        assert_eq!(device_names().len(), 3);
+    }
+
+    #[test]
+    fn test_signals_in_range() {
+        for signal in Signal::ALL {
+            let mut generator = Generator::new(signal);
+            let samples: Vec<f32> = (0..SAMPLE_RATE as usize).map(|_| generator.next_sample()).collect();
+            assert!(samples.iter().all(|s| s.abs() <= LEVEL as f32), "{:?} out of range", signal);
+            assert!(samples.iter().any(|s| s.abs() > 0.05), "{:?} is silent", signal);
+        }
+
+        // The sweep starts at the bottom of the range: 20Hz crosses zero about every 25ms
+        let mut generator = Generator::new(Signal::Sweep);
+        let first: Vec<f32> = (0..441).map(|_| generator.next_sample()).collect();
+        assert!(first.iter().all(|&s| s >= 0.0));
+    }
+}
@@ -0,0 +1,303 @@
+// Spectrogram Module - Rolling history of spectrogram columns, exportable as a PNG image, onset pulses
+// that flash the live display on transients, and the decaying trail of the 1D (strip) layout
+use anyhow::{anyhow, Result};
+use std::collections::VecDeque;
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+use crate::gradients;
+use crate::types::{build_gradient_from_color, ColorSpace, GradientLut, InterpolationMode, Rgb};
+
+/// Longest stretch of history kept for export (10 minutes)
+pub const HISTORY_MAX_SECS: f64 = 600.0;
+
+// Columns stored per second of history
+const COLUMNS_PER_SEC: f64 = 10.0;
+
+// Frequency rows per stored column - live spectra are resampled to this
+const HISTORY_ROWS: usize = 128;
+
+// How long an onset pulse takes to fade out
+const PULSE_FADE_MS: f64 = 250.0;
+
+// How quickly the running average of spectral flux follows the music (fraction per frame)
+const FLUX_SMOOTHING: f32 = 0.1;
+
+// How far a brighten pulse lifts a cell toward white
+const BRIGHTEN_MIX: f32 = 0.6;
+
+#[derive(Default)]
+struct History {
+    columns: VecDeque<(Instant, Vec<f32>)>,  // Oldest first, each column low to high frequency
+}
+
+fn history() -> &'static Mutex<History> {
+    static HISTORY: OnceLock<Mutex<History>> = OnceLock::new();
+    HISTORY.get_or_init(|| Mutex::new(History::default()))
+}
+
+/// Record the latest spectrum (low to high frequency) - call every frame, columns are kept at a fixed rate
+pub fn record_column(magnitudes: &[f32]) {
+    if magnitudes.is_empty() {
+        return;
+    }
+    let now = Instant::now();
+    let mut history = history().lock().unwrap();
+    if let Some((last, _)) = history.columns.back() {
+        if now.duration_since(*last).as_secs_f64() < 1.0 / COLUMNS_PER_SEC {
+            return;
+        }
+    }
+
+    // Expired columns are recycled for the new one
+    let max_age = Duration::from_secs_f64(HISTORY_MAX_SECS);
+    let mut column = Vec::new();
+    while let Some((at, _)) = history.columns.front() {
+        if now.duration_since(*at) <= max_age {
+            break;
+        }
+        if let Some((_, old)) = history.columns.pop_front() {
+            column = old;
+        }
+    }
+
+    resample_column(magnitudes, &mut column);
+    history.columns.push_back((now, column));
+}
+
+// Resample a spectrum to HISTORY_ROWS rows, keeping the loudest bin in each row
+fn resample_column(magnitudes: &[f32], column: &mut Vec<f32>) {
+    column.clear();
+    let len = magnitudes.len();
+    for row in 0..HISTORY_ROWS {
+        let start = row * len / HISTORY_ROWS;
+        let end = ((row + 1) * len / HISTORY_ROWS).max(start + 1).min(len);
+        column.push(magnitudes[start.min(len - 1)..end].iter().fold(0.0_f32, |max, &v| max.max(v)));
+    }
+}
+
+/// Render the last `secs` of history as a PNG, one pixel column per stored column (time left to right,
+/// low frequencies at the bottom), colored by magnitude through the given color spec
+pub fn render_png(secs: f64, height: u32, spec: &str, interpolation_mode: InterpolationMode, color_space: ColorSpace) -> Result<Vec<u8>> {
+    use image::ImageEncoder;
+
+    let (gradient, _, solid) = build_gradient_from_color(&gradients::resolve_color_string(spec), true, interpolation_mode, color_space)?;
+    let height = height.max(1);
+
+    let (pixels, width) = {
+        let history = history().lock().unwrap();
+        let now = Instant::now();
+        let window = Duration::from_secs_f64(secs.clamp(0.0, HISTORY_MAX_SECS));
+        let columns: Vec<&[f32]> = history.columns.iter()
+            .filter(|(at, _)| now.duration_since(*at) <= window)
+            .map(|(_, column)| column.as_slice())
+            .collect();
+        if columns.is_empty() {
+            return Err(anyhow!("No spectrogram history yet - run live mode with spectrogram enabled"));
+        }
+        (render_pixels(&columns, height as usize, gradient.as_ref(), solid), columns.len() as u32)
+    };
+
+    let mut png = Vec::new();
+    image::codecs::png::PngEncoder::new(&mut png).write_image(&pixels, width, height, image::ColorType::Rgb8)?;
+    Ok(png)
+}
+
+/// What an onset does to the newest spectrogram column
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum OnsetOverlay {
+    Off,
+    Brighten,  // Lift the column toward white
+    Accent(Rgb),  // Flash the column in an accent color
+}
+
+impl OnsetOverlay {
+    pub fn from_config(mode: &str, accent_color: &str) -> Self {
+        match mode {
+            "brighten" => OnsetOverlay::Brighten,
+            "accent" => OnsetOverlay::Accent(Rgb::from_hex(accent_color).unwrap_or(Rgb { r: 255, g: 255, b: 255 })),
+            _ => OnsetOverlay::Off,
+        }
+    }
+
+    /// A cell's color with a pulse of strength `pulse` (0.0-1.0) applied
+    pub fn apply(&self, [r, g, b]: [u8; 3], pulse: f32) -> [u8; 3] {
+        let target = match self {
+            OnsetOverlay::Off => return [r, g, b],
+            OnsetOverlay::Brighten => [255, 255, 255],
+            OnsetOverlay::Accent(color) => [color.r, color.g, color.b],
+        };
+        let mix = match self {
+            OnsetOverlay::Brighten => pulse * BRIGHTEN_MIX,
+            _ => pulse,
+        }.clamp(0.0, 1.0);
+        let blend = |c: u8, t: u8| (c as f32 + (t as f32 - c as f32) * mix).round() as u8;
+        [blend(r, target[0]), blend(g, target[1]), blend(b, target[2])]
+    }
+}
+
+/// Spots transients as a jump in spectral flux (the summed rise of every bin since the last spectrum)
+/// over its running average, and turns them into a pulse that fades out over PULSE_FADE_MS
+#[derive(Default)]
+pub struct OnsetDetector {
+    previous: Vec<f32>,
+    average_flux: f32,
+    pulse: f32,
+}
+
+impl OnsetDetector {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Feed the latest spectrum; returns the pulse strength (1.0 right on an onset, fading to 0.0)
+    /// `sensitivity` is how many times the average flux counts as an onset (lower = more onsets)
+    pub fn update(&mut self, magnitudes: &[f32], sensitivity: f32, frame_secs: f64) -> f32 {
+        self.pulse *= 0.01_f64.powf(frame_secs * 1000.0 / PULSE_FADE_MS) as f32;
+
+        if self.previous.len() == magnitudes.len() {
+            let flux: f32 = magnitudes.iter().zip(&self.previous).map(|(&now, &before)| (now - before).max(0.0)).sum();
+            if self.average_flux <= 0.0 {
+                self.average_flux = flux;  // Nothing to compare against yet
+            } else {
+                if flux > self.average_flux * sensitivity {
+                    self.pulse = 1.0;
+                }
+                self.average_flux += (flux - self.average_flux) * FLUX_SMOOTHING;
+            }
+        }
+        self.previous.clear();
+        self.previous.extend_from_slice(magnitudes);
+        self.pulse
+    }
+}
+
+/// 1D spectrogram on a strip - each LED holds its band's peak, fading to 1% over the trail time,
+/// so brightness carries the recent history
+#[derive(Default)]
+pub struct StripTrail {
+    levels: Vec<f32>,
+}
+
+impl StripTrail {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Feed the latest spectrum (one band per LED); returns each LED's level relative to the loudest (0.0-1.0)
+    pub fn update(&mut self, magnitudes: &[f32], leds: usize, trail_ms: f64, frame_secs: f64) -> impl Iterator<Item = f32> + '_ {
+        let decay = 0.01_f64.powf(frame_secs * 1000.0 / trail_ms) as f32;
+        self.levels.resize(leds, 0.0);
+        for (level, &magnitude) in self.levels.iter_mut().zip(magnitudes) {
+            *level = magnitude.max(*level * decay);
+        }
+
+        let max = self.levels.iter().fold(0.0_f32, |max, &v| max.max(v));
+        let normalization = if max > 0.0 { 1.0 / max } else { 1.0 };
+        self.levels.iter().map(move |&level| (level * normalization).min(1.0))
+    }
+}
+
+// Lay columns out as RGB rows (top row = highest frequency), normalized to the loudest value shown
+fn render_pixels(columns: &[&[f32]], height: usize, gradient: Option<&GradientLut>, solid: Rgb) -> Vec<u8> {
+    let max = columns.iter().flat_map(|column| column.iter()).fold(0.0_f32, |max, &v| max.max(v));
+    let normalization = if max > 0.0 { 1.0 / max } else { 1.0 };
+
+    let mut pixels = Vec::with_capacity(columns.len() * height * 3);
+    for y in 0..height {
+        for column in columns {
+            let row = ((height - 1 - y) * column.len() / height).min(column.len().saturating_sub(1));
+            let magnitude = (column.get(row).copied().unwrap_or(0.0) * normalization).min(1.0) as f64;
+            let [r, g, b] = match gradient {
+                Some(lut) => {
+                    let [r, g, b, _] = lut.at(magnitude);
+                    [r, g, b]
+                }
+                None => [
+                    (solid.r as f64 * magnitude) as u8,
+                    (solid.g as f64 * magnitude) as u8,
+                    (solid.b as f64 * magnitude) as u8,
+                ],
+            };
+            pixels.extend_from_slice(&[r, g, b]);
+        }
+    }
+    pixels
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resample_keeps_peaks() {
+        let mut spectrum = vec![0.0; 512];
+        spectrum[0] = 1.0;
+        spectrum[511] = 0.5;
+        let mut column = Vec::new();
+        resample_column(&spectrum, &mut column);
+        assert_eq!(column.len(), HISTORY_ROWS);
+        assert_eq!(column[0], 1.0);
+        assert_eq!(column[HISTORY_ROWS - 1], 0.5);
+
+        // Short spectra stretch to fill every row
+        resample_column(&[0.25, 0.75], &mut column);
+        assert_eq!(column.len(), HISTORY_ROWS);
+        assert_eq!(column[0], 0.25);
+        assert_eq!(column[HISTORY_ROWS - 1], 0.75);
+    }
+
+    #[test]
+    fn test_onset_detector() {
+        // A steady swell never counts as an onset
+        let mut detector = OnsetDetector::new();
+        for i in 0..20 {
+            let level = 0.1 + i as f32 * 0.01;
+            assert_eq!(detector.update(&[level, level, level], 2.0, 0.016), 0.0);
+        }
+
+        // A hit well above the usual flux fires a full pulse, which then fades
+        assert_eq!(detector.update(&[1.0, 0.8, 0.5], 2.0, 0.016), 1.0);
+        let fading = detector.update(&[1.0, 0.8, 0.5], 2.0, 0.016);
+        assert!(fading > 0.0 && fading < 1.0);
+        assert!(detector.update(&[1.0, 0.8, 0.5], 2.0, 1.0) < 0.01);
+    }
+
+    #[test]
+    fn test_strip_trail() {
+        let mut trail = StripTrail::new();
+        let close = |levels: Vec<f32>, expected: &[f32]| levels.iter().zip(expected).all(|(a, b)| (a - b).abs() < 1e-4);
+        assert!(close(trail.update(&[0.5, 1.0, 0.0], 3, 500.0, 0.016).collect(), &[0.5, 1.0, 0.0]));
+
+        // Gone quiet - the peaks fade together to 1% over the trail time, still shown relative to the loudest
+        assert!(close(trail.update(&[0.0, 0.0, 0.0], 3, 500.0, 0.5).collect(), &[0.5, 1.0, 0.0]));
+        assert!((trail.levels[1] - 0.01).abs() < 1e-6);
+
+        // A new hit outshines the fading trail
+        assert!(close(trail.update(&[0.0, 0.0, 0.02], 3, 500.0, 0.0).collect(), &[0.25, 0.5, 1.0]));
+    }
+
+    #[test]
+    fn test_onset_overlay() {
+        let accent = OnsetOverlay::from_config("accent", "FF0000");
+        assert_eq!(accent.apply([0, 0, 255], 1.0), [255, 0, 0]);
+        assert_eq!(accent.apply([0, 0, 255], 0.0), [0, 0, 255]);
+        assert_eq!(OnsetOverlay::from_config("brighten", "").apply([0, 0, 0], 1.0), [153, 153, 153]);
+        assert_eq!(OnsetOverlay::from_config("off", "FF0000").apply([10, 20, 30], 1.0), [10, 20, 30]);
+    }
+
+    #[test]
+    fn test_render_pixels_layout() {
+        // Two columns: loud bass, then loud treble
+        let bass = [1.0, 0.0];
+        let treble = [0.0, 1.0];
+        let white = Rgb { r: 255, g: 255, b: 255 };
+        let pixels = render_pixels(&[&bass, &treble], 2, None, white);
+
+        // Top row is treble, bottom row is bass
+        assert_eq!(pixels, vec![
+            0, 0, 0, 255, 255, 255,
+            255, 255, 255, 0, 0, 0,
+        ]);
+    }
+}
@@ -0,0 +1,138 @@
+// Spectrum Bars Module - Bar chart of the FFT spectrum for the live mode TUI, in LED order with peak markers
+use ratatui::style::{Color, Style};
+use ratatui::text::{Line, Span};
+use std::time::{Duration, Instant};
+
+// How fast a peak marker falls once its hold runs out (full height per second)
+const PEAK_FALL_PER_SEC: f32 = 1.5;
+
+// Partial bar tops, in eighths of a row
+const BAR_EIGHTHS: [&str; 8] = [" ", "▁", "▂", "▃", "▄", "▅", "▆", "▇"];
+
+/// Smoothed level and held peak per LED, squeezed into terminal columns when drawn
+pub struct SpectrumBars {
+    levels: Vec<f32>,
+    colors: Vec<(u8, u8, u8)>,
+    peaks: Vec<(f32, Instant)>,  // Peak level and when it was set
+    last_update: Option<Instant>,
+}
+
+impl SpectrumBars {
+    pub fn new() -> Self {
+        Self {
+            levels: Vec::new(),
+            colors: Vec::new(),
+            peaks: Vec::new(),
+            last_update: None,
+        }
+    }
+
+    /// Take the smoothed level of each LED and the frame they were drawn into - peaks hold for
+    /// `hold` before falling back towards the level
+    pub fn update(&mut self, levels: &[f32], frame: &[u8], hold: Duration, now: Instant) {
+        if self.peaks.len() != levels.len() {
+            self.peaks = vec![(0.0, now); levels.len()];
+        }
+        let fall = self.last_update.map_or(0.0, |last| now.duration_since(last).as_secs_f32() * PEAK_FALL_PER_SEC);
+        self.last_update = Some(now);
+
+        self.levels.clear();
+        self.levels.extend(levels.iter().map(|level| level.clamp(0.0, 1.0)));
+        self.colors.clear();
+        self.colors.extend((0..levels.len()).map(|led| {
+            frame.get(led * 3..led * 3 + 3).map_or((0, 0, 0), |rgb| (rgb[0], rgb[1], rgb[2]))
+        }));
+
+        for (peak, &level) in self.peaks.iter_mut().zip(&self.levels) {
+            if level >= peak.0 {
+                *peak = (level, now);
+            } else if now.duration_since(peak.1) > hold {
+                peak.0 = (peak.0 - fall).max(level);
+            }
+        }
+    }
+
+    /// Draw `height` rows of `width` bars, LED 0 on the left - each column shows the loudest of its
+    /// LEDs in that LED's color at full brightness, with its peak marked above
+    pub fn lines(&self, width: usize, height: usize) -> Vec<Line<'static>> {
+        let leds = self.levels.len();
+        if leds == 0 || width == 0 || height == 0 {
+            return Vec::new();
+        }
+        let columns = width.min(leds);
+
+        let bars: Vec<(f32, f32, Color)> = (0..columns).map(|column| {
+            let range = column * leds / columns..((column + 1) * leds / columns).max(column * leds / columns + 1);
+            let loudest = range.clone().max_by(|&a, &b| self.levels[a].total_cmp(&self.levels[b])).unwrap_or(range.start);
+            let peak = range.map(|led| self.peaks[led].0).fold(0.0_f32, f32::max);
+            (self.levels[loudest], peak, full_brightness(self.colors[loudest]))
+        }).collect();
+
+        (0..height).map(|row| {
+            // Eighths of a row filled from the bottom of this row
+            let row_floor = (height - 1 - row) * 8;
+            let spans: Vec<Span<'static>> = bars.iter().map(|&(level, peak, color)| {
+                let filled = (level * (height * 8) as f32).round() as usize;
+                let peak_row = ((peak * height as f32).ceil() as usize).clamp(1, height) - 1;
+                if filled >= row_floor + 8 {
+                    Span::styled("█", Style::default().fg(color))
+                } else if filled > row_floor {
+                    Span::styled(BAR_EIGHTHS[filled - row_floor], Style::default().fg(color))
+                } else if peak > 0.0 && peak_row == height - 1 - row {
+                    Span::styled("▔", Style::default().fg(Color::White))
+                } else {
+                    Span::raw(" ")
+                }
+            }).collect();
+            Line::from(spans)
+        }).collect()
+    }
+}
+
+impl Default for SpectrumBars {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// An LED color scaled up to full brightness, so quiet bars keep their hue (dark gray if unlit)
+fn full_brightness((r, g, b): (u8, u8, u8)) -> Color {
+    let max = r.max(g).max(b);
+    if max == 0 {
+        return Color::DarkGray;
+    }
+    let scale = |c: u8| (c as u32 * 255 / max as u32) as u8;
+    Color::Rgb(scale(r), scale(g), scale(b))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn text(line: &Line) -> String {
+        line.spans.iter().map(|span| span.content.as_ref()).collect()
+    }
+
+    #[test]
+    fn test_bars_and_peaks() {
+        let start = Instant::now();
+        let hold = Duration::from_millis(500);
+        let mut bars = SpectrumBars::new();
+        let frame = [255, 0, 0, 0, 128, 0, 0, 0, 0, 0, 0, 0];
+        bars.update(&[1.0, 0.5, 0.0, 0.0], &frame, hold, start);
+
+        // Full bar, half bar, nothing - half height lands exactly on a row boundary
+        let rows: Vec<String> = bars.lines(4, 2).iter().map(text).collect();
+        assert_eq!(rows, vec!["█   ", "██  "]);
+
+        // The loud LED drops - its peak holds, then falls
+        bars.update(&[0.0, 0.5, 0.0, 0.0], &frame, hold, start + Duration::from_millis(100));
+        assert_eq!(text(&bars.lines(4, 2)[0]), "▔   ");
+        bars.update(&[0.0, 0.5, 0.0, 0.0], &frame, hold, start + Duration::from_millis(900));
+        assert!(bars.peaks[0].0 < 1.0);
+
+        // Columns take the loudest of their LEDs
+        let rows: Vec<String> = bars.lines(2, 2).iter().map(text).collect();
+        assert_eq!(rows[1], "█ ");
+    }
+}
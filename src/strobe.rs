@@ -0,0 +1,181 @@
+// Strobe Module - Shared strobe helper: flash a span of LEDs in the strobe color while a trigger holds
+use std::ops::Range;
+use std::time::{Duration, Instant};
+
+use crate::config::BandwidthConfig;
+use crate::types::Rgb;
+
+// A frame counts as a beat when its level jumps this far above the running average
+const BEAT_THRESHOLD: f32 = 1.5;
+
+// Fraction of each new frame's level mixed into the running average
+const BEAT_AVERAGE_RATE: f32 = 0.05;
+
+// Levels below this never count as beats, so hiss and room noise don't strobe
+const BEAT_MIN_LEVEL: f32 = 0.05;
+
+// Minimum gap between beats
+const BEAT_HOLDOFF: Duration = Duration::from_millis(200);
+
+/// Strobe settings resolved from the config (strobe_* keys) - shared by every mode
+#[derive(Debug, Clone)]
+pub struct StrobeSettings {
+    pub on_max: bool,  // Strobe while the mode is at its maximum (bandwidth max, audio clipping)
+    pub on_beat: bool,  // Flash once per detected beat (audio modes)
+    pub midi_note: Option<u8>,  // Strobe while this note is held (MIDI mode)
+    pub rate_hz: f64,
+    pub duration_ms: f64,
+    pub color: Rgb,
+}
+
+impl StrobeSettings {
+    pub fn from_config(config: &BandwidthConfig) -> Self {
+        StrobeSettings {
+            on_max: config.strobe_on_max,
+            on_beat: config.strobe_on_beat,
+            midi_note: u8::try_from(config.strobe_midi_note).ok(),
+            rate_hz: config.strobe_rate_hz,
+            duration_ms: config.strobe_duration_ms,
+            color: Rgb::from_hex(&config.strobe_color).unwrap_or(Rgb { r: 255, g: 255, b: 255 }),
+        }
+    }
+}
+
+/// Strobe state for one segment - tracks its triggers and where it is in the flash cycle
+pub struct Strobe {
+    since: Option<Instant>,  // When the current trigger started (flash cycles count from here)
+    beat_until: Option<Instant>,  // A beat holds the trigger for one flash cycle
+    last_beat: Option<Instant>,
+    level_average: f32,
+    note_held: bool,
+}
+
+impl Strobe {
+    pub fn new() -> Self {
+        Self {
+            since: None,
+            beat_until: None,
+            last_beat: None,
+            level_average: 0.0,
+            note_held: false,
+        }
+    }
+
+    /// Feed this frame's audio level (0.0-1.0) for beat triggering - modes without audio skip this
+    pub fn feed_level(&mut self, settings: &StrobeSettings, level: f32) {
+        let average = self.level_average;
+        self.level_average += (level - average) * BEAT_AVERAGE_RATE;
+        if !settings.on_beat || settings.rate_hz <= 0.0 {
+            return;
+        }
+
+        let now = Instant::now();
+        let holdoff_over = match self.last_beat {
+            Some(last) => now.duration_since(last) >= BEAT_HOLDOFF,
+            None => true,
+        };
+        if level >= BEAT_MIN_LEVEL && level > average * BEAT_THRESHOLD && holdoff_over {
+            self.last_beat = Some(now);
+            self.beat_until = Some(now + Duration::from_secs_f64(1.0 / settings.rate_hz));
+        }
+    }
+
+    /// Feed the currently held MIDI notes (channel, note, velocity) for note triggering
+    pub fn feed_notes(&mut self, settings: &StrobeSettings, notes: &[(u8, u8, u8)]) {
+        self.note_held = settings.midi_note.is_some_and(|strobe_note| notes.iter().any(|&(_, note, _)| note == strobe_note));
+    }
+
+    /// Call once per frame - `at_max` is the mode's own max condition (false if it has none)
+    /// Returns whether the strobe color should show this frame
+    pub fn update(&mut self, settings: &StrobeSettings, at_max: bool) -> bool {
+        let now = Instant::now();
+        let beat = self.beat_until.is_some_and(|until| now < until);
+        let triggered = (settings.on_max && at_max) || beat || self.note_held;
+        if !triggered || settings.rate_hz <= 0.0 {
+            self.since = None;
+            return false;
+        }
+
+        // Flash for the first `duration` of each cycle, so a trigger shows straight away
+        let since = *self.since.get_or_insert(now);
+        let cycle_ms = 1000.0 / settings.rate_hz;
+        let phase_ms = (now.duration_since(since).as_secs_f64() * 1000.0) % cycle_ms;
+        phase_ms < settings.duration_ms.min(cycle_ms)
+    }
+}
+
+impl Default for Strobe {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Fill a range of LEDs in an RGB frame (bytes or floats) with the strobe color
+pub fn fill<T: From<u8>>(frame: &mut [T], leds: Range<usize>, color: Rgb) {
+    let end = leds.end.min(frame.len() / 3);
+    for pixel in frame[leds.start.min(end) * 3..end * 3].chunks_exact_mut(3) {
+        pixel[0] = T::from(color.r);
+        pixel[1] = T::from(color.g);
+        pixel[2] = T::from(color.b);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn settings() -> StrobeSettings {
+        StrobeSettings {
+            on_max: true,
+            on_beat: true,
+            midi_note: Some(36),
+            rate_hz: 3.0,
+            duration_ms: 100.0,
+            color: Rgb { r: 255, g: 255, b: 255 },
+        }
+    }
+
+    #[test]
+    fn test_strobe_triggers() {
+        let settings = settings();
+
+        // Nothing held - no flash
+        let mut strobe = Strobe::new();
+        assert!(!strobe.update(&settings, false));
+
+        // Max condition flashes straight away, and stops when released
+        assert!(strobe.update(&settings, true));
+        assert!(!strobe.update(&settings, false));
+
+        // Max trigger is ignored when disabled
+        let off = StrobeSettings { on_max: false, ..settings.clone() };
+        assert!(!strobe.update(&off, true));
+
+        // The strobe note triggers, other notes don't
+        strobe.feed_notes(&settings, &[(0, 40, 100)]);
+        assert!(!strobe.update(&settings, false));
+        strobe.feed_notes(&settings, &[(0, 40, 100), (9, 36, 127)]);
+        assert!(strobe.update(&settings, false));
+        strobe.feed_notes(&settings, &[]);
+        assert!(!strobe.update(&settings, false));
+
+        // A jump in level over a quiet average is a beat
+        let mut strobe = Strobe::new();
+        strobe.feed_level(&settings, 0.01);
+        assert!(!strobe.update(&settings, false));
+        strobe.feed_level(&settings, 0.8);
+        assert!(strobe.update(&settings, false));
+    }
+
+    #[test]
+    fn test_fill_clamps_to_frame() {
+        let color = Rgb { r: 1, g: 2, b: 3 };
+        let mut frame = vec![0u8; 9];
+        fill(&mut frame, 1..10, color);
+        assert_eq!(frame, vec![0, 0, 0, 1, 2, 3, 1, 2, 3]);
+
+        let mut frame = vec![0.0f32; 3];
+        fill(&mut frame, 0..1, color);
+        assert_eq!(frame, vec![1.0, 2.0, 3.0]);
+    }
+}
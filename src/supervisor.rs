@@ -0,0 +1,138 @@
+// Supervisor Module - Runs a mode, recovering from panics and errors: restores the terminal, blanks the strip,
+// and hands back to the mode loop to restart the mode after a backoff
+use anyhow::Result;
+use crossterm::cursor::Show;
+use crossterm::execute;
+use crossterm::terminal::{disable_raw_mode, LeaveAlternateScreen};
+use std::panic::{self, AssertUnwindSafe};
+use std::time::{Duration, Instant};
+
+use crate::config::BandwidthConfig;
+use crate::system;
+use crate::types::ModeExitReason;
+use crate::wled_state;
+
+// Restart delay after the first failure, doubling on each failure in a row up to the max
+const INITIAL_BACKOFF: Duration = Duration::from_secs(1);
+const MAX_BACKOFF: Duration = Duration::from_secs(30);
+
+// A mode that ran at least this long before failing starts over at the initial backoff
+const HEALTHY_RUN: Duration = Duration::from_secs(60);
+
+/// Leave raw mode and the alternate screen so messages (and the shell afterwards) are readable
+pub fn restore_terminal() {
+    let _ = disable_raw_mode();
+    let _ = execute!(std::io::stdout(), LeaveAlternateScreen, Show);
+}
+
+/// Restore the terminal before the panic message prints, so it isn't lost on the alternate screen
+/// Only for panics on the main thread (where modes run) - a worker thread panicking leaves the TUI alone
+pub fn install_panic_hook() {
+    let default_hook = panic::take_hook();
+    panic::set_hook(Box::new(move |info| {
+        if std::thread::current().name() == Some("main") {
+            restore_terminal();
+        }
+        default_hook(info);
+    }));
+}
+
+/// Tracks failures in a row across restarts of the modes it runs
+pub struct Supervisor {
+    backoff: Duration,
+}
+
+impl Supervisor {
+    pub fn new() -> Self {
+        Self { backoff: INITIAL_BACKOFF }
+    }
+
+    /// Run a mode to completion - None if it panicked or failed, after recovering and waiting out the backoff
+    pub fn run<F>(&mut self, mode: &str, config: &BandwidthConfig, run_mode: F) -> Option<ModeExitReason>
+    where
+        F: FnOnce() -> Result<ModeExitReason>,
+    {
+        let started = Instant::now();
+        let failure = match catch_failure(run_mode) {
+            Ok(reason) => {
+                self.backoff = INITIAL_BACKOFF;
+                return Some(reason);
+            }
+            Err(failure) => failure,
+        };
+
+        restore_terminal();
+        wled_state::blank_all(config);
+
+        let backoff = self.next_backoff(started.elapsed());
+        eprintln!("\n❌ {} mode {} - restarting in {}s", mode, failure, backoff.as_secs());
+
+        // Sleep in short steps so a shutdown request isn't held up by the backoff
+        let wait_until = Instant::now() + backoff;
+        while Instant::now() < wait_until && !system::shutdown_requested() {
+            std::thread::sleep(Duration::from_millis(100));
+        }
+        None
+    }
+
+    // The wait before restarting a mode that failed after running for `ran_for` - doubled for the next failure
+    fn next_backoff(&mut self, ran_for: Duration) -> Duration {
+        if ran_for >= HEALTHY_RUN {
+            self.backoff = INITIAL_BACKOFF;
+        }
+        let backoff = self.backoff;
+        self.backoff = (self.backoff * 2).min(MAX_BACKOFF);
+        backoff
+    }
+}
+
+// Run a mode, turning a panic or an error into a description of the failure
+fn catch_failure<F>(run_mode: F) -> Result<ModeExitReason, String>
+where
+    F: FnOnce() -> Result<ModeExitReason>,
+{
+    match panic::catch_unwind(AssertUnwindSafe(run_mode)) {
+        Ok(Ok(reason)) => Ok(reason),
+        Ok(Err(e)) => Err(format!("error: {}", e)),
+        Err(payload) => {
+            let message = payload.downcast_ref::<&str>().map(|s| s.to_string())
+                .or_else(|| payload.downcast_ref::<String>().cloned())
+                .unwrap_or_else(|| "unknown panic".to_string());
+            Err(format!("panic: {}", message))
+        }
+    }
+}
+
+impl Default for Supervisor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::anyhow;
+
+    #[test]
+    fn test_catch_failure() {
+        assert!(matches!(catch_failure(|| Ok(ModeExitReason::ModeChanged)), Ok(ModeExitReason::ModeChanged)));
+        assert_eq!(catch_failure(|| Err(anyhow!("device gone"))).unwrap_err(), "error: device gone");
+        assert_eq!(catch_failure(|| panic!("bad frame")).unwrap_err(), "panic: bad frame");
+        assert_eq!(catch_failure(|| panic!("bad frame {}", 3)).unwrap_err(), "panic: bad frame 3");
+    }
+
+    #[test]
+    fn test_backoff() {
+        let mut supervisor = Supervisor::new();
+        let quick = Duration::from_secs(1);
+
+        // Failures in a row double the wait, up to the max
+        let waits: Vec<u64> = (0..7).map(|_| supervisor.next_backoff(quick).as_secs()).collect();
+        assert_eq!(waits, vec![1, 2, 4, 8, 16, 30, 30]);
+
+        // A long healthy run starts over
+        assert_eq!(supervisor.next_backoff(HEALTHY_RUN), INITIAL_BACKOFF);
+        assert_eq!(supervisor.next_backoff(quick), Duration::from_secs(2));
+    }
+}
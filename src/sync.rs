@@ -0,0 +1,434 @@
+// Sync Module - Leader/follower synchronization between rustwled instances over UDP broadcast
+use anyhow::{bail, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::net::UdpSocket;
+use std::sync::atomic::{AtomicBool, AtomicI64, Ordering};
+use std::thread;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use tokio::sync::broadcast;
+
+use crate::agent;
+use crate::config::{BandwidthConfig, ConfigDelta};
+
+// Send the full shareable config every N clock beacons so late-joining followers catch up
+const FULL_CONFIG_EVERY_BEACONS: u64 = 10;
+
+// Offset smoothing factor - each new clock sample moves the offset 1/8 of the way
+const CLOCK_SMOOTHING: i64 = 8;
+
+// Config keys that describe local hardware/network setup and are never synchronized
+const LOCAL_CONFIG_KEYS: &[&str] = &[
+    "wled_ip",
+    "wled_devices",
+    "total_leds",
+    "multi_device_enabled",
+    "multi_device_send_parallel",
+    "multi_device_fail_fast",
+    "multi_device_stagger_sends",
+    "adaptive_fps_enabled",
+    "adaptive_fps_min",
+    "bandwidth_history",
+    "bandwidth_history_hours",
+    "wled_pause_when_off",
+    "wled_brightness_sync",
+    "gamma",
+    "color_order",
+    "color_correction",
+    "white_point_kelvin",
+    "matrix_panels",
+    "matrix_2d_rotation",
+    "matrix_2d_flip_h",
+    "matrix_2d_flip_v",
+    "interface",
+    "ssh_host",
+    "ssh_user",
+    "agent_hosts",
+    "agent_token",
+    "audio_device",
+    "midi_device",
+    "midi_zones",
+    "httpd_enabled",
+    "httpd_https_enabled",
+    "httpd_ip",
+    "httpd_port",
+    "httpd_assets_dir",
+    "httpd_auth_enabled",
+    "httpd_auth_user",
+    "httpd_auth_pass",
+    "httpd_auth_pass_hash",
+    "httpd_rate_limit_per_minute",
+    "httpd_login_max_attempts",
+    "httpd_login_lockout_secs",
+    "api_tokens",
+    "httpd_users",
+    "acme_enabled",
+    "acme_domain",
+    "acme_email",
+    "acme_staging",
+    "acme_http_port",
+    "httpd_mtls_enabled",
+    "httpd_mtls_ca_path",
+    "relay_listen_ip",
+    "relay_listen_port",
+    "relay_listeners",
+    "relay_frame_width",
+    "relay_frame_height",
+    "relay_remap",
+    "relay_color_pipeline",
+    "ddp_listen_ip",
+    "ddp_listen_port",
+    "sacn_listen_ip",
+    "sacn_start_universe",
+    "artnet_listen_ip",
+    "artnet_universes",
+    "boblight_listen_ip",
+    "boblight_listen_port",
+    "ambient_source",
+    "ambient_sensor",
+    "hue_enabled",
+    "hue_bridge_ip",
+    "hue_username",
+    "hue_clientkey",
+    "hue_entertainment_group",
+    "hue_lights",
+    "lifx_devices",
+    "nanoleaf_ip",
+    "nanoleaf_token",
+    "nanoleaf_panels",
+    "gpio_buttons",
+    "gpio_mode_cycle",
+    "sync_role",
+    "sync_group",
+    "sync_key",
+    "sync_address",
+    "sync_port",
+    "sync_interval_ms",
+];
+
+// Synced clock state (offset from local clock to leader clock in microseconds)
+static CLOCK_ENABLED: AtomicBool = AtomicBool::new(false);
+static CLOCK_LOCKED: AtomicBool = AtomicBool::new(false);
+static CLOCK_OFFSET_US: AtomicI64 = AtomicI64::new(0);
+
+/// Sync packet exchanged between leader and followers
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+enum SyncPacket {
+    Clock {
+        group: String,
+        leader_time_us: i64,
+        mode: String,
+    },
+    Config {
+        group: String,
+        changes: Map<String, Value>,
+    },
+}
+
+/// Datagram on the wire - the packet's JSON and its HMAC-SHA256 under sync_key
+#[derive(Debug, Serialize, Deserialize)]
+struct SignedPacket {
+    body: String,
+    mac: String,
+}
+
+fn sign(key: &str, body: &str) -> String {
+    agent::hex(&agent::hmac_sha256(key.as_bytes(), body.as_bytes()))
+}
+
+fn encode_packet(packet: &SyncPacket, key: &str) -> Result<Vec<u8>> {
+    let body = serde_json::to_string(packet)?;
+    let mac = sign(key, &body);
+    Ok(serde_json::to_vec(&SignedPacket { body, mac })?)
+}
+
+/// The packet in a datagram, or None if it isn't a sync packet signed with `key`
+fn decode_packet(data: &[u8], key: &str) -> Option<SyncPacket> {
+    let signed: SignedPacket = serde_json::from_slice(data).ok()?;
+    if !agent::answer_matches(&sign(key, &signed.body), &signed.mac) {
+        return None;
+    }
+    serde_json::from_str(&signed.body).ok()
+}
+
+fn local_time_us() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_micros() as i64)
+        .unwrap_or(0)
+}
+
+/// Current time on the shared show clock (leader time on followers, local time otherwise)
+pub fn synced_time_us() -> i64 {
+    local_time_us() + CLOCK_OFFSET_US.load(Ordering::Relaxed)
+}
+
+/// Whether renderers should align frames to the shared show clock
+pub fn clock_enabled() -> bool {
+    CLOCK_ENABLED.load(Ordering::Relaxed)
+}
+
+/// Whether a follower has received at least one clock beacon from its leader
+pub fn clock_locked() -> bool {
+    CLOCK_LOCKED.load(Ordering::Relaxed)
+}
+
+/// Frame index on the shared show clock for the given frame duration
+/// Instances with the same FPS render frame N at the same wall-clock moment
+pub fn synced_frame_index(frame_duration_micros: u64) -> i64 {
+    synced_time_us() / frame_duration_micros.max(1) as i64
+}
+
+/// Whether a config key is specific to this machine (outputs, server, sync) rather than the look
+pub fn is_local_key(key: &str) -> bool {
+    LOCAL_CONFIG_KEYS.contains(&key)
+}
+
+/// Serialize the config into the map of keys that are shared with followers
+fn shareable_config(config: &BandwidthConfig) -> Map<String, Value> {
+    match serde_json::to_value(config) {
+        Ok(Value::Object(map)) => map.into_iter().filter(|(k, _)| !is_local_key(k)).collect(),
+        _ => Map::new(),
+    }
+}
+
+/// Keys whose values differ between two shareable config maps
+fn config_delta(previous: &Map<String, Value>, current: &Map<String, Value>) -> Map<String, Value> {
+    current.iter()
+        .filter(|(k, v)| previous.get(*k) != Some(*v))
+        .map(|(k, v)| (k.clone(), v.clone()))
+        .collect()
+}
+
+/// Merge changed keys from the leader into the local config file
+/// Saving the file triggers the normal config watcher so running modes pick up the change
+fn apply_config_delta(changes: &Map<String, Value>) -> Result<bool> {
+    let config = BandwidthConfig::load()?;
+    let mut value = serde_json::to_value(&config)?;
+
+    let mut changed = false;
+    if let Some(obj) = value.as_object_mut() {
+        for (key, new_value) in changes {
+            if is_local_key(key) || !obj.contains_key(key) {
+                continue;
+            }
+            if obj.get(key) != Some(new_value) {
+                obj.insert(key.clone(), new_value.clone());
+                changed = true;
+            }
+        }
+    }
+
+    if changed {
+        let mut new_config: BandwidthConfig = serde_json::from_value(value)?;
+        new_config.config_path = config.config_path.clone();
+        new_config.save()?;
+    }
+
+    Ok(changed)
+}
+
+/// Start the sync subsystem for the configured role ("leader", "follower", or "off")
+/// Role changes require a restart, like the HTTP server settings
+pub fn spawn_sync(config: &BandwidthConfig, config_change_tx: broadcast::Sender<ConfigDelta>) -> Result<()> {
+    if matches!(config.sync_role.as_str(), "leader" | "follower") && config.sync_key.is_empty() {
+        bail!("sync_role is \"{}\" but sync_key is empty - set the same sync_key on the leader and every follower", config.sync_role);
+    }
+
+    match config.sync_role.as_str() {
+        "leader" => {
+            let socket = UdpSocket::bind("0.0.0.0:0")?;
+            socket.set_broadcast(true)?;
+            let target = format!("{}:{}", config.sync_address, config.sync_port);
+            let group = config.sync_group.clone();
+            let key = config.sync_key.clone();
+            let interval = Duration::from_millis(config.sync_interval_ms);
+            let config_change_rx = config_change_tx.subscribe();
+
+            CLOCK_ENABLED.store(true, Ordering::Relaxed);
+            CLOCK_LOCKED.store(true, Ordering::Relaxed);
+            println!("Sync leader broadcasting to {} (group \"{}\")", target, group);
+
+            thread::spawn(move || run_leader(socket, target, group, key, interval, config_change_rx));
+        }
+        "follower" => {
+            let socket = UdpSocket::bind(format!("0.0.0.0:{}", config.sync_port))?;
+            socket.set_read_timeout(Some(Duration::from_millis(500)))?;
+            let group = config.sync_group.clone();
+            let key = config.sync_key.clone();
+
+            CLOCK_ENABLED.store(true, Ordering::Relaxed);
+            println!("Sync follower listening on port {} (group \"{}\")", config.sync_port, group);
+
+            thread::spawn(move || run_follower(socket, group, key));
+        }
+        _ => {}
+    }
+
+    Ok(())
+}
+
+fn send_packet(socket: &UdpSocket, target: &str, key: &str, packet: &SyncPacket) {
+    if let Ok(data) = encode_packet(packet, key) {
+        if let Err(e) = socket.send_to(&data, target) {
+            eprintln!("Sync send error: {}", e);
+        }
+    }
+}
+
+fn run_leader(
+    socket: UdpSocket,
+    target: String,
+    group: String,
+    key: String,
+    interval: Duration,
+    mut config_change_rx: broadcast::Receiver<ConfigDelta>,
+) {
+    let mut last_shared = Map::new();
+    let mut last_beacon = Instant::now() - interval;
+    let mut beacon_count = 0u64;
+
+    loop {
+        // Broadcast config deltas as soon as the local config changes
+        let mut config_changed = false;
+        while let Ok(_) | Err(broadcast::error::TryRecvError::Lagged(_)) = config_change_rx.try_recv() {
+            config_changed = true;
+        }
+
+        let beacon_due = last_beacon.elapsed() >= interval;
+
+        if config_changed || beacon_due {
+            if let Ok(config) = BandwidthConfig::load() {
+                let shared = shareable_config(&config);
+
+                // Periodically resend everything so followers that joined late converge
+                let changes = if beacon_due && beacon_count % FULL_CONFIG_EVERY_BEACONS == 0 {
+                    shared.clone()
+                } else {
+                    config_delta(&last_shared, &shared)
+                };
+
+                if !changes.is_empty() {
+                    send_packet(&socket, &target, &key, &SyncPacket::Config { group: group.clone(), changes });
+                }
+                last_shared = shared;
+
+                if beacon_due {
+                    send_packet(&socket, &target, &key, &SyncPacket::Clock {
+                        group: group.clone(),
+                        leader_time_us: local_time_us(),
+                        mode: config.mode.clone(),
+                    });
+                    beacon_count += 1;
+                    last_beacon = Instant::now();
+                }
+            }
+        }
+
+        thread::sleep(Duration::from_millis(20));
+    }
+}
+
+fn run_follower(socket: UdpSocket, group: String, key: String) {
+    let mut buf = [0u8; 65535];
+    let mut leader_mode: Option<String> = None;  // Mode from the last beacon, so it's only applied when it changes
+
+    loop {
+        let size = match socket.recv_from(&mut buf) {
+            Ok((size, _src)) => size,
+            Err(_) => continue,  // Timeout - keep listening
+        };
+
+        let packet = match decode_packet(&buf[..size], &key) {
+            Some(p) => p,
+            None => continue,  // Not a sync packet, or not signed with our key
+        };
+
+        match packet {
+            SyncPacket::Clock { group: packet_group, leader_time_us, mode } => {
+                if packet_group != group {
+                    continue;
+                }
+
+                // Estimate leader clock offset (LAN latency is well below a frame)
+                let sample = leader_time_us - local_time_us();
+                if CLOCK_LOCKED.swap(true, Ordering::Relaxed) {
+                    let offset = CLOCK_OFFSET_US.load(Ordering::Relaxed);
+                    CLOCK_OFFSET_US.store(offset + (sample - offset) / CLOCK_SMOOTHING, Ordering::Relaxed);
+                } else {
+                    CLOCK_OFFSET_US.store(sample, Ordering::Relaxed);
+                }
+
+                // Follow the leader's mode even if a config delta was missed
+                if leader_mode.as_deref() != Some(mode.as_str()) {
+                    let mut changes = Map::new();
+                    changes.insert("mode".to_string(), Value::String(mode.clone()));
+                    match apply_config_delta(&changes) {
+                        Ok(_) => leader_mode = Some(mode),
+                        Err(e) => eprintln!("Sync: failed to apply mode from leader: {}", e),
+                    }
+                }
+            }
+            SyncPacket::Config { group: packet_group, changes } => {
+                if packet_group != group {
+                    continue;
+                }
+                if let Err(e) = apply_config_delta(&changes) {
+                    eprintln!("Sync: failed to apply config from leader: {}", e);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_config_delta() {
+        let mut previous = Map::new();
+        previous.insert("fps".to_string(), Value::from(60));
+        previous.insert("mode".to_string(), Value::from("bandwidth"));
+
+        let mut current = previous.clone();
+        current.insert("mode".to_string(), Value::from("live"));
+
+        let delta = config_delta(&previous, &current);
+        assert_eq!(delta.len(), 1);
+        assert_eq!(delta.get("mode"), Some(&Value::from("live")));
+    }
+
+    #[test]
+    fn test_shareable_config_skips_local_keys() {
+        let shared = shareable_config(&BandwidthConfig::default());
+        assert!(shared.contains_key("mode"));
+        assert!(!shared.contains_key("wled_devices"));
+        assert!(!shared.contains_key("httpd_port"));
+        assert!(!shared.contains_key("agent_token"));
+        assert!(!shared.contains_key("sync_key"));
+        assert!(!shared.contains_key("relay_remap"));
+        assert!(!shared.contains_key("bandwidth_history"));
+    }
+
+    #[test]
+    fn test_packets_must_be_signed_with_key() {
+        let packet = SyncPacket::Clock { group: "default".to_string(), leader_time_us: 1, mode: "live".to_string() };
+
+        let signed = encode_packet(&packet, "secret").unwrap();
+        assert!(matches!(decode_packet(&signed, "secret"), Some(SyncPacket::Clock { .. })));
+
+        // Wrong key
+        assert!(decode_packet(&signed, "other").is_none());
+
+        // Unsigned packet
+        let unsigned = serde_json::to_vec(&packet).unwrap();
+        assert!(decode_packet(&unsigned, "secret").is_none());
+
+        // Body changed after signing
+        let mut tampered: SignedPacket = serde_json::from_slice(&signed).unwrap();
+        tampered.body = tampered.body.replace("live", "sand");
+        assert!(decode_packet(&serde_json::to_vec(&tampered).unwrap(), "secret").is_none());
+    }
+}
@@ -0,0 +1,99 @@
+// System Module - Restart/shutdown/blank requests from the HTTP API, honored by the mode loop
+use std::sync::atomic::{AtomicBool, Ordering};
+
+struct SystemControl {
+    // Set by the API, cleared by the mode loop when it starts the next mode
+    restart_requested: AtomicBool,
+    shutdown_requested: AtomicBool,
+    // While set, every frame sent to the devices is replaced with black
+    blanked: AtomicBool,
+}
+
+impl SystemControl {
+    const fn new() -> Self {
+        SystemControl {
+            restart_requested: AtomicBool::new(false),
+            shutdown_requested: AtomicBool::new(false),
+            blanked: AtomicBool::new(false),
+        }
+    }
+
+    fn request_restart(&self) {
+        self.restart_requested.store(true, Ordering::SeqCst);
+    }
+
+    fn request_shutdown(&self) {
+        self.shutdown_requested.store(true, Ordering::SeqCst);
+        self.restart_requested.store(true, Ordering::SeqCst);
+    }
+
+    fn restart_requested(&self) -> bool {
+        self.restart_requested.load(Ordering::SeqCst)
+    }
+
+    fn clear_restart(&self) {
+        self.restart_requested.store(false, Ordering::SeqCst);
+    }
+
+    fn shutdown_requested(&self) -> bool {
+        self.shutdown_requested.load(Ordering::SeqCst)
+    }
+}
+
+// One per process - the API, GPIO buttons and the mode loop all share it
+static CONTROL: SystemControl = SystemControl::new();
+
+/// Ask the running mode to exit so the mode loop starts it again
+pub fn request_restart() {
+    CONTROL.request_restart();
+}
+
+/// Ask the running mode to exit and the application to shut down
+pub fn request_shutdown() {
+    CONTROL.request_shutdown();
+}
+
+/// Whether the running mode should exit (restart or shutdown pending)
+pub fn restart_requested() -> bool {
+    CONTROL.restart_requested()
+}
+
+/// Called by the mode loop once the previous mode has exited
+pub fn clear_restart() {
+    CONTROL.clear_restart();
+}
+
+pub fn shutdown_requested() -> bool {
+    CONTROL.shutdown_requested()
+}
+
+pub fn set_blanked(blanked: bool) {
+    CONTROL.blanked.store(blanked, Ordering::SeqCst);
+}
+
+pub fn blanked() -> bool {
+    CONTROL.blanked.load(Ordering::SeqCst)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_restart_and_shutdown_requests() {
+        let control = SystemControl::new();
+
+        // A restart ends the running mode once, then the loop carries on
+        control.request_restart();
+        assert!(control.restart_requested());
+        control.clear_restart();
+        assert!(!control.restart_requested());
+        assert!(!control.shutdown_requested());
+
+        // A shutdown also ends the running mode, and stays set after the loop clears the restart
+        control.request_shutdown();
+        assert!(control.restart_requested());
+        control.clear_restart();
+        assert!(control.shutdown_requested());
+    }
+}
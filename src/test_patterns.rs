@@ -0,0 +1,198 @@
+// Test Patterns Module - Named patterns and per-device targeting for checking wiring, color order and power capacity
+use anyhow::{anyhow, Result};
+
+use crate::config::WLEDDeviceConfig;
+
+// Length of the lit block in the chase pattern
+const CHASE_LENGTH: usize = 10;
+
+// LEDs per second the chase moves at speed 1.0
+const CHASE_LEDS_PER_SECOND: f64 = 30.0;
+
+// Seconds each color is shown in the RGB order pattern at speed 1.0
+const RGB_ORDER_STEP_SECONDS: f64 = 2.0;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TestPattern {
+    Chase,     // A block of white LEDs running along the strip - wiring, gaps and device boundaries
+    Rainbow,   // Moving rainbow across the whole strip - general sanity check
+    RgbOrder,  // Whole strip red, then green, then blue - color_order check
+    White,     // Whole strip white - power supply and voltage drop check
+}
+
+pub const TEST_PATTERN_NAMES: &[&str] = &["chase", "rainbow", "rgborder", "white"];
+
+impl TestPattern {
+    pub fn from_name(name: &str) -> Result<Self> {
+        match name.to_lowercase().as_str() {
+            "chase" => Ok(TestPattern::Chase),
+            "rainbow" => Ok(TestPattern::Rainbow),
+            "rgborder" | "rgb-order" | "rgb" => Ok(TestPattern::RgbOrder),
+            "white" => Ok(TestPattern::White),
+            _ => Err(anyhow!("Unknown test pattern '{}' (expected one of: {})", name, TEST_PATTERN_NAMES.join(", "))),
+        }
+    }
+
+    /// Render the pattern at `seconds` into the animation
+    /// speed scales how fast it moves, brightness is 0.0-1.0
+    pub fn render(&self, seconds: f64, speed: f64, total_leds: usize, brightness: f64) -> Vec<u8> {
+        let t = seconds * speed;
+        let level = |v: u8| (v as f64 * brightness.clamp(0.0, 1.0)).round() as u8;
+        let mut frame = vec![0u8; total_leds * 3];
+        if total_leds == 0 {
+            return frame;
+        }
+
+        match self {
+            TestPattern::Chase => {
+                let head = (t * CHASE_LEDS_PER_SECOND) as usize % total_leds;
+                for i in 0..CHASE_LENGTH.min(total_leds) {
+                    let led = (head + total_leds - i) % total_leds;
+                    frame[led * 3..led * 3 + 3].fill(level(255));
+                }
+            }
+            TestPattern::Rainbow => {
+                for led in 0..total_leds {
+                    let (r, g, b) = hue_to_rgb(led as f64 / total_leds as f64 + t * 0.2);
+                    frame[led * 3] = level(r);
+                    frame[led * 3 + 1] = level(g);
+                    frame[led * 3 + 2] = level(b);
+                }
+            }
+            TestPattern::RgbOrder => {
+                let channel = self.rgb_order_step(t);
+                for led in 0..total_leds {
+                    frame[led * 3 + channel] = level(255);
+                }
+            }
+            TestPattern::White => frame.fill(level(255)),
+        }
+        frame
+    }
+
+    /// What the strip should be showing right now, for the console
+    pub fn describe(&self, seconds: f64, speed: f64) -> String {
+        match self {
+            TestPattern::Chase => "Chase - the block should run smoothly across every device".to_string(),
+            TestPattern::Rainbow => "Rainbow - colors should flow smoothly along the strip".to_string(),
+            TestPattern::RgbOrder => {
+                let name = ["RED", "GREEN", "BLUE"][self.rgb_order_step(seconds * speed)];
+                format!("Strip should be {} - if not, fix color_order", name)
+            }
+            TestPattern::White => "White - watch for dimming/yellowing at the far end (voltage drop)".to_string(),
+        }
+    }
+
+    fn rgb_order_step(&self, t: f64) -> usize {
+        (t / RGB_ORDER_STEP_SECONDS) as usize % 3
+    }
+}
+
+/// Pick the devices a test targets: a device index, an IP, or "all" (every enabled primary device)
+pub fn select_devices<'a>(devices: &'a [WLEDDeviceConfig], selector: &str) -> Result<Vec<&'a WLEDDeviceConfig>> {
+    // Backups mirror their primary's range, so they're never targeted on their own
+    let primaries = || devices.iter().filter(|d| d.backup_for.is_empty());
+
+    if selector.eq_ignore_ascii_case("all") {
+        let selected: Vec<_> = primaries().filter(|d| d.enabled).collect();
+        if selected.is_empty() {
+            return Err(anyhow!("No enabled devices configured"));
+        }
+        return Ok(selected);
+    }
+
+    if let Ok(index) = selector.parse::<usize>() {
+        return devices.get(index)
+            .filter(|d| d.backup_for.is_empty())
+            .map(|d| vec![d])
+            .ok_or_else(|| anyhow!("No device {} (there are {} configured)", index, devices.len()));
+    }
+
+    primaries()
+        .find(|d| d.ip == selector)
+        .map(|d| vec![d])
+        .ok_or_else(|| anyhow!("No configured device with IP {}", selector))
+}
+
+/// Render a pattern into each device's own LED range, so every device shows it from its first LED
+pub fn render_on_devices(pattern: TestPattern, seconds: f64, speed: f64, total_leds: usize, brightness: f64, devices: &[&WLEDDeviceConfig]) -> Vec<u8> {
+    let mut frame = vec![0u8; total_leds * 3];
+    for device in devices {
+        let start = (device.led_offset * 3).min(frame.len());
+        let end = ((device.led_offset + device.led_count) * 3).min(frame.len());
+        let rendered = pattern.render(seconds, speed, device.led_count, brightness);
+        frame[start..end].copy_from_slice(&rendered[..end - start]);
+    }
+    frame
+}
+
+// Fully saturated color for a hue in turns (0.0-1.0 wraps around)
+fn hue_to_rgb(hue: f64) -> (u8, u8, u8) {
+    let h = hue.rem_euclid(1.0) * 6.0;
+    let x = ((1.0 - (h % 2.0 - 1.0).abs()) * 255.0) as u8;
+    match h as u32 {
+        0 => (255, x, 0),
+        1 => (x, 255, 0),
+        2 => (0, 255, x),
+        3 => (0, x, 255),
+        4 => (x, 0, 255),
+        _ => (255, 0, x),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_patterns() {
+        assert_eq!(TestPattern::from_name("RGBOrder").unwrap(), TestPattern::RgbOrder);
+        assert!(TestPattern::from_name("plaid").is_err());
+
+        let white = TestPattern::White.render(0.0, 1.0, 4, 0.5);
+        assert!(white.iter().all(|&v| v == 128));
+
+        // Red first, then green after one step
+        let red = TestPattern::RgbOrder.render(0.0, 1.0, 2, 1.0);
+        assert_eq!(red, vec![255, 0, 0, 255, 0, 0]);
+        let green = TestPattern::RgbOrder.render(RGB_ORDER_STEP_SECONDS, 1.0, 1, 1.0);
+        assert_eq!(green, vec![0, 255, 0]);
+
+        let chase = TestPattern::Chase.render(0.0, 1.0, 100, 1.0);
+        assert_eq!(chase.iter().filter(|&&v| v == 255).count(), CHASE_LENGTH * 3);
+    }
+
+    #[test]
+    fn test_select_devices() {
+        let device = |ip: &str, led_offset: usize, backup_for: &str| WLEDDeviceConfig {
+            ip: ip.to_string(),
+            led_offset,
+            led_count: 4,
+            enabled: true,
+            max_fps: 0.0,
+            led_ma: 55.0,
+            psu_amps: 0.0,
+            backup_for: backup_for.to_string(),
+            reversed: false,
+            mirrored: false,
+            segment: -1,
+            protocol: "ddp".to_string(),
+            universe: 1,
+            start_channel: 1,
+            net: 0,
+            subnet: 0,
+        };
+        let devices = vec![device("10.0.0.1", 0, ""), device("10.0.0.2", 4, ""), device("10.0.0.3", 4, "10.0.0.2")];
+
+        assert_eq!(select_devices(&devices, "1").unwrap()[0].ip, "10.0.0.2");
+        assert_eq!(select_devices(&devices, "10.0.0.1").unwrap()[0].led_offset, 0);
+        assert_eq!(select_devices(&devices, "all").unwrap().len(), 2);
+        assert!(select_devices(&devices, "2").is_err());
+        assert!(select_devices(&devices, "10.0.0.9").is_err());
+
+        // Second device only - its range starts at LED 4
+        let frame = render_on_devices(TestPattern::White, 0.0, 1.0, 8, 1.0, &select_devices(&devices, "1").unwrap());
+        assert_eq!(&frame[..12], &[0; 12]);
+        assert_eq!(&frame[12..], &[255; 12]);
+    }
+}
@@ -0,0 +1,248 @@
+// Test TUI Module - Interactive LED stepping for test mode (arrow keys, brightness, color, jump to index)
+use anyhow::Result;
+use crossterm::event::{poll, read, Event, KeyCode, KeyEventKind, KeyModifiers};
+use crossterm::terminal::{EnterAlternateScreen, LeaveAlternateScreen};
+use crossterm::ExecutableCommand;
+use ratatui::backend::CrosstermBackend;
+use ratatui::layout::Alignment;
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, Paragraph};
+use ratatui::Terminal;
+use std::io;
+use std::time::{Duration, Instant};
+
+use crate::types::Rgb;
+
+// Colors cycled with 'c'
+const TEST_COLORS: &[(&str, Rgb)] = &[
+    ("red", Rgb { r: 255, g: 0, b: 0 }),
+    ("green", Rgb { r: 0, g: 255, b: 0 }),
+    ("blue", Rgb { r: 0, g: 0, b: 255 }),
+    ("white", Rgb { r: 255, g: 255, b: 255 }),
+    ("yellow", Rgb { r: 255, g: 255, b: 0 }),
+    ("cyan", Rgb { r: 0, g: 255, b: 255 }),
+    ("magenta", Rgb { r: 255, g: 0, b: 255 }),
+];
+
+// Brightness change per +/- press
+const BRIGHTNESS_STEP: f64 = 0.1;
+
+// Frames are re-sent at this interval even without input, so WLED doesn't time out of realtime mode
+const KEEPALIVE_INTERVAL: Duration = Duration::from_millis(500);
+
+/// State of the interactive test - which LED of the sequence is lit and how
+pub struct TestTuiState {
+    pub leds: Vec<usize>,  // LED sequence being stepped through (absolute LED numbers)
+    pub position: usize,  // Index into leds of the lit LED
+    pub brightness: f64,  // 0.0-1.0
+    pub color_index: usize,  // Index into TEST_COLORS
+    pub auto_advance: bool,  // Step automatically at the test FPS (the old fixed sequence)
+    pub input: String,  // LED number being typed for a jump
+}
+
+impl TestTuiState {
+    pub fn new(leds: Vec<usize>) -> Self {
+        Self {
+            leds,
+            position: 0,
+            brightness: 1.0,
+            color_index: 0,
+            auto_advance: false,
+            input: String::new(),
+        }
+    }
+
+    pub fn current_led(&self) -> Option<usize> {
+        self.leds.get(self.position).copied()
+    }
+
+    pub fn step(&mut self, forward: bool) {
+        if self.leds.is_empty() {
+            return;
+        }
+        self.position = if forward {
+            (self.position + 1) % self.leds.len()
+        } else {
+            (self.position + self.leds.len() - 1) % self.leds.len()
+        };
+    }
+
+    /// Jump to the typed LED number, or the next one in the sequence after it
+    pub fn jump_to_input(&mut self) {
+        if let Ok(target) = self.input.parse::<usize>() {
+            if let Some(position) = self.leds.iter().position(|&led| led == target)
+                .or_else(|| self.leds.iter().position(|&led| led > target))
+            {
+                self.position = position;
+            }
+        }
+        self.input.clear();
+    }
+
+    pub fn adjust_brightness(&mut self, delta: f64) {
+        self.brightness = (self.brightness + delta).clamp(0.0, 1.0);
+    }
+
+    pub fn color(&self) -> (&'static str, Rgb) {
+        TEST_COLORS[self.color_index % TEST_COLORS.len()]
+    }
+
+    /// Frame with only the current LED lit
+    pub fn frame(&self, total_leds: usize) -> Vec<u8> {
+        let mut frame = vec![0u8; total_leds * 3];
+        let (_, color) = self.color();
+        if let Some(led) = self.current_led().filter(|&led| led < total_leds) {
+            let scale = |v: u8| (v as f64 * self.brightness).round() as u8;
+            frame[led * 3] = scale(color.r);
+            frame[led * 3 + 1] = scale(color.g);
+            frame[led * 3 + 2] = scale(color.b);
+        }
+        frame
+    }
+}
+
+fn draw(terminal: &mut Terminal<CrosstermBackend<io::Stdout>>, state: &TestTuiState, describe: &dyn Fn(usize) -> String) -> Result<()> {
+    let (color_name, _) = state.color();
+    let led_line = match state.current_led() {
+        Some(led) => describe(led),
+        None => "No LEDs to test".to_string(),
+    };
+
+    let label = Style::default().fg(Color::Gray);
+    let value = Style::default().fg(Color::White).add_modifier(Modifier::BOLD);
+    let lines = vec![
+        Line::from(Span::styled(led_line, Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD))),
+        Line::from(""),
+        Line::from(vec![
+            Span::styled("Position: ", label),
+            Span::styled(format!("{}/{}", state.position + 1, state.leds.len()), value),
+        ]),
+        Line::from(vec![
+            Span::styled("Brightness: ", label),
+            Span::styled(format!("{:.0}%", state.brightness * 100.0), value),
+            Span::styled("   Color: ", label),
+            Span::styled(color_name, value),
+            Span::styled("   Auto: ", label),
+            Span::styled(if state.auto_advance { "on" } else { "off" }, value),
+        ]),
+        Line::from(vec![
+            Span::styled("Go to LED: ", label),
+            Span::styled(format!("{}_", state.input), value),
+        ]),
+        Line::from(""),
+        Line::from(Span::styled(
+            "←/→ step  Home/End first/last  +/- brightness  c color  a auto  0-9 + Enter jump  q quit",
+            Style::default().fg(Color::DarkGray),
+        )),
+    ];
+
+    terminal.draw(|f| {
+        let block = Block::default().borders(Borders::ALL).title(" LED Test ");
+        f.render_widget(Paragraph::new(lines).block(block).alignment(Alignment::Left), f.size());
+    })?;
+    Ok(())
+}
+
+/// Run the interactive test until the user quits
+/// describe labels an LED for display, send delivers a frame to the devices
+/// Expects the terminal to already be in raw mode
+pub fn run(
+    leds: Vec<usize>,
+    total_leds: usize,
+    step_interval: Duration,
+    describe: &dyn Fn(usize) -> String,
+    mut send: impl FnMut(&[u8]) -> Result<()>,
+) -> Result<()> {
+    let mut stdout = io::stdout();
+    stdout.execute(EnterAlternateScreen)?;
+    let mut terminal = Terminal::new(CrosstermBackend::new(stdout))?;
+    terminal.hide_cursor()?;
+
+    let mut state = TestTuiState::new(leds);
+    let mut last_step = Instant::now();
+    let mut last_send: Option<Instant> = None;
+    let mut dirty = true;
+
+    let result = (|| -> Result<()> {
+        loop {
+            if dirty || last_send.map(|t| t.elapsed() >= KEEPALIVE_INTERVAL).unwrap_or(true) {
+                send(&state.frame(total_leds))?;
+                draw(&mut terminal, &state, describe)?;
+                last_send = Some(Instant::now());
+                dirty = false;
+            }
+
+            if state.auto_advance && last_step.elapsed() >= step_interval {
+                state.step(true);
+                last_step = Instant::now();
+                dirty = true;
+            }
+
+            if !poll(Duration::from_millis(10))? {
+                continue;
+            }
+            let Event::Key(key) = read()? else {
+                continue;
+            };
+            if key.kind != KeyEventKind::Press {
+                continue;
+            }
+
+            dirty = true;
+            match key.code {
+                KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => return Ok(()),
+                KeyCode::Char('q') | KeyCode::Char('Q') => return Ok(()),
+                KeyCode::Esc if state.input.is_empty() => return Ok(()),
+                KeyCode::Esc => state.input.clear(),
+                KeyCode::Right | KeyCode::Down => state.step(true),
+                KeyCode::Left | KeyCode::Up => state.step(false),
+                KeyCode::Home => state.position = 0,
+                KeyCode::End => state.position = state.leds.len().saturating_sub(1),
+                KeyCode::Char('+') | KeyCode::Char('=') => state.adjust_brightness(BRIGHTNESS_STEP),
+                KeyCode::Char('-') | KeyCode::Char('_') => state.adjust_brightness(-BRIGHTNESS_STEP),
+                KeyCode::Char('c') | KeyCode::Char('C') => state.color_index = (state.color_index + 1) % TEST_COLORS.len(),
+                KeyCode::Char('a') | KeyCode::Char('A') | KeyCode::Char(' ') => {
+                    state.auto_advance = !state.auto_advance;
+                    last_step = Instant::now();
+                }
+                KeyCode::Char(c) if c.is_ascii_digit() && state.input.len() < 6 => state.input.push(c),
+                KeyCode::Backspace => {
+                    state.input.pop();
+                }
+                KeyCode::Enter => state.jump_to_input(),
+                _ => dirty = false,
+            }
+        }
+    })();
+
+    // Leave the strip dark and restore the terminal even if sending failed
+    let _ = send(&vec![0u8; total_leds * 3]);
+    terminal.show_cursor()?;
+    terminal.backend_mut().execute(LeaveAlternateScreen)?;
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stepping_and_jump() {
+        let mut state = TestTuiState::new(vec![10, 11, 12, 20]);
+        state.step(false);
+        assert_eq!(state.current_led(), Some(20));
+        state.step(true);
+        assert_eq!(state.current_led(), Some(10));
+
+        // Not in the sequence - lands on the next LED after it
+        state.input = "15".to_string();
+        state.jump_to_input();
+        assert_eq!(state.current_led(), Some(20));
+        assert!(state.input.is_empty());
+
+        state.adjust_brightness(-0.5);
+        let frame = state.frame(21);
+        assert_eq!(&frame[60..63], &[128, 0, 0]);
+    }
+}
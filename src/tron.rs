@@ -1,16 +1,20 @@
 // Tron Game Mode - 2 AI players with gradient trails
 use anyhow::Result;
-use colorgrad::Gradient;
 use ddp_rs::connection::DDPConnection;
+use rand::seq::SliceRandom;
 use rand::Rng;
 use std::collections::{VecDeque, BinaryHeap, HashSet};
 use std::sync::{Arc, Mutex};
 use std::time::{Duration, Instant};
+use tokio::sync::broadcast;
 
-use crate::config::BandwidthConfig;
-use crate::multi_device::{MultiDeviceConfig, MultiDeviceManager, WLEDDevice};
-use crate::types::{build_gradient_from_color, InterpolationMode};
+use crate::alloc_track::FrameAllocCounter;
+use crate::config::{BandwidthConfig, ConfigDelta, TronConfig};
+use crate::matrix::MatrixLayout;
+use crate::multi_device::{ColorCorrection, MultiDeviceConfig, MultiDeviceManager, WLEDDevice};
+use crate::types::{build_gradient_from_color, ColorSpace, GradientLut, InterpolationMode};
 use crate::gradients;
+use crate::live_stats;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Direction {
@@ -172,6 +176,99 @@ impl PartialOrd for AStarNode {
 }
 
 // Manhattan distance heuristic
+// Random obstacles: seeds cover this share of the target density, then grow over life-like generations
+const OBSTACLE_SEED_SHARE: f64 = 0.3;
+const MAX_OBSTACLE_GENERATIONS: usize = 8;
+
+// Cells within this distance of a player's start are kept clear of walls
+const SPAWN_CLEARANCE: i32 = 2;
+
+// Walls are drawn dim so trails stand out against them
+const WALL_COLOR: (u8, u8, u8) = (50, 50, 70);
+
+// Random walls covering `density` of the arena. Walls grow from scattered seeds like a Game of Life run where
+// nothing dies and an empty cell next to exactly two walls becomes one - which joins nearby seeds into clumps and
+// short walls - until the density is reached
+fn random_walls(width: usize, height: usize, density: f64, rng: &mut impl Rng) -> Vec<Vec<bool>> {
+    let mut walls = vec![vec![false; width]; height];
+    if density <= 0.0 {
+        return walls;
+    }
+    let target = (width * height) as f64 * density;
+    let mut count = 0;
+    for cell in walls.iter_mut().flatten() {
+        if rng.gen_bool((density * OBSTACLE_SEED_SHARE).min(1.0)) {
+            *cell = true;
+            count += 1;
+        }
+    }
+
+    for _ in 0..MAX_OBSTACLE_GENERATIONS {
+        if count as f64 >= target {
+            break;
+        }
+        let mut births: Vec<(usize, usize)> = (0..height)
+            .flat_map(|y| (0..width).map(move |x| (x, y)))
+            .filter(|&(x, y)| !walls[y][x] && wall_neighbours(&walls, x, y) == 2)
+            .collect();
+        if births.is_empty() {
+            break;
+        }
+        // Born in random order, stopping at the density
+        births.shuffle(rng);
+        for (x, y) in births.into_iter().take(target as usize - count) {
+            walls[y][x] = true;
+            count += 1;
+        }
+    }
+    walls
+}
+
+fn wall_neighbours(walls: &[Vec<bool>], x: usize, y: usize) -> usize {
+    let mut count = 0;
+    for dy in -1..=1i32 {
+        for dx in -1..=1i32 {
+            if (dx, dy) == (0, 0) {
+                continue;
+            }
+            let (nx, ny) = (x as i32 + dx, y as i32 + dy);
+            if nx >= 0 && ny >= 0 && walls.get(ny as usize).and_then(|row| row.get(nx as usize)) == Some(&true) {
+                count += 1;
+            }
+        }
+    }
+    count
+}
+
+// Mark walls drawn as "x,y" cells and "x1,y1-x2,y2" filled rectangles, separated by ';' (parts off the arena are dropped)
+fn draw_walls(walls: &mut [Vec<bool>], spec: &str) {
+    let parse_cell = |text: &str| -> Option<(usize, usize)> {
+        let (x, y) = text.split_once(',')?;
+        Some((x.trim().parse().ok()?, y.trim().parse().ok()?))
+    };
+    for part in spec.split(';').map(str::trim).filter(|part| !part.is_empty()) {
+        let corners = match part.split_once('-') {
+            Some((from, to)) => parse_cell(from).zip(parse_cell(to)),
+            None => parse_cell(part).map(|cell| (cell, cell)),
+        };
+        let Some(((x1, y1), (x2, y2))) = corners else {
+            continue;
+        };
+        for y in y1.min(y2)..=y1.max(y2) {
+            for x in x1.min(x2)..=x1.max(x2) {
+                if let Some(cell) = walls.get_mut(y).and_then(|row| row.get_mut(x)) {
+                    *cell = true;
+                }
+            }
+        }
+    }
+}
+
+// Outside the arena or on a wall
+fn is_wall(walls: &[Vec<bool>], pos: Position) -> bool {
+    pos.x < 0 || pos.y < 0 || walls.get(pos.y as usize).and_then(|row| row.get(pos.x as usize)) != Some(&false)
+}
+
 fn manhattan_distance(a: Position, b: Position) -> i32 {
     (a.x - b.x).abs() + (a.y - b.y).abs()
 }
@@ -197,7 +294,7 @@ struct Player {
     direction: Direction,
     trail: VecDeque<Position>,
     alive: bool,
-    gradient: Gradient,
+    gradient: GradientLut,
     death_time: Option<Instant>,  // When player died, for blink animation
     aggression_modifier: f64,      // Current aggression multiplier (0.9 to 1.1)
     aggression_phase: f64,         // Phase for oscillation (0 to 2π)
@@ -226,7 +323,7 @@ struct Player {
 }
 
 impl Player {
-    fn new(id: u8, start_x: i32, start_y: i32, direction: Direction, gradient: Gradient, initial_trail_length: usize) -> Self {
+    fn new(id: u8, start_x: i32, start_y: i32, direction: Direction, gradient: GradientLut, initial_trail_length: usize) -> Self {
         let pos = Position { x: start_x, y: start_y };
         let mut trail = VecDeque::new();
         trail.push_back(pos);
@@ -294,25 +391,33 @@ pub struct TronGame {
     trail_fade: bool,  // Enable trail brightness fading effect
     super_food_enabled: bool,  // Enable super food spawning (red, 10% chance, +5 length)
     diagonal_movement: bool,  // Enable diagonal movement (8 directions instead of 4)
+    walls: Vec<Vec<bool>>,  // Obstacles - deadly like the arena edge
+    obstacle_density: f64,  // Share of the arena covered by random walls (laid out again each game)
+    obstacle_spec: String,  // Walls drawn through the config/API
+    settings: TronConfig,  // Current tron settings - power food, food flips and gradient animation are read from here each update
+    color_space: String,  // Kept with the settings so a new game builds the same gradients
 }
 
 impl TronGame {
-    pub fn new(width: usize, height: usize, speed_ms: f64, look_ahead: i32, trail_length: usize, ai_aggression: f64, num_players: usize, player_colors: &[String], food_mode: bool, food_max_count: usize, food_ttl_seconds: u64, trail_fade: bool, super_food_enabled: bool, diagonal_movement: bool, interpolation: &str) -> Self {
+    /// New game on a width x height arena (the tron grid or the matrix canvas) with the tron settings
+    pub fn new(width: usize, height: usize, tron: &TronConfig, color_space_name: &str) -> Self {
         // Create players distributed around the perimeter
         let mut players = Vec::new();
         let mut rng = rand::thread_rng();
+        let player_colors = tron.player_colors();
 
         // Parse interpolation mode
-        let interp_mode = match interpolation {
+        let interp_mode = match tron.interpolation.as_str() {
             "basis" => InterpolationMode::Basis,
             "catmullrom" => InterpolationMode::CatmullRom,
             _ => InterpolationMode::Linear,
         };
+        let color_space = ColorSpace::from_name(color_space_name);
 
         // Track already used positions
         let mut used_positions: Vec<Position> = Vec::new();
 
-        for i in 0..num_players {
+        for i in 0..tron.num_players {
             let player_id = (i + 1) as u8;
 
             // Randomize starting position - find empty spot not too close to others
@@ -370,21 +475,21 @@ impl TronGame {
                 hex_colors.clone()
             };
 
-            let (gradient_opt, _, _) = build_gradient_from_color(&hex_for_gradient, true, interp_mode).unwrap_or_else(|_e| {
+            let (gradient_opt, _, _) = build_gradient_from_color(&hex_for_gradient, true, interp_mode, color_space).unwrap_or_else(|_e| {
                 // Fallback to rainbow if parsing fails
                 let fallback_hex = gradients::resolve_color_string("Rainbow");
-                build_gradient_from_color(&fallback_hex, true, interp_mode).unwrap()
+                build_gradient_from_color(&fallback_hex, true, interp_mode, color_space).unwrap()
             });
             let gradient = gradient_opt.unwrap_or_else(|| {
                 // Fallback gradient if None (should not happen now)
-                colorgrad::CustomGradient::new()
+                GradientLut::from_gradient(&colorgrad::CustomGradient::new()
                     .html_colors(&["#ff0000", "#00ff00", "#0000ff"])
                     .build()
-                    .unwrap()
+                    .unwrap())
             });
 
             // In food mode, players start with trail length 1, otherwise use global trail_length (0 = infinite)
-            let initial_trail_length = if food_mode { 1 } else { trail_length };
+            let initial_trail_length = if tron.food_mode { 1 } else { tron.trail_length };
             players.push(Player::new(player_id, start_x, start_y, direction, gradient, initial_trail_length));
         }
 
@@ -405,20 +510,95 @@ impl TronGame {
             grid,
             game_over: false,
             last_update: Instant::now(),
-            update_interval: Duration::from_secs_f64(speed_ms / 1000.0),
-            look_ahead,
-            trail_length,
-            ai_aggression,
-            food_mode,
+            update_interval: Duration::from_secs_f64(tron.speed_ms / 1000.0),
+            look_ahead: tron.look_ahead,
+            trail_length: tron.trail_length,
+            ai_aggression: tron.ai_aggression,
+            food_mode: tron.food_mode,
             food_positions: Vec::new(),  // Will spawn on first update
-            food_max_count,
-            food_ttl_seconds,
-            trail_fade,
-            super_food_enabled,
-            diagonal_movement,
+            food_max_count: tron.food_max_count,
+            food_ttl_seconds: tron.food_ttl_seconds,
+            trail_fade: tron.trail_fade,
+            super_food_enabled: tron.super_food_enabled,
+            diagonal_movement: tron.diagonal_movement,
+            walls: vec![vec![false; width]; height],
+            obstacle_density: 0.0,
+            obstacle_spec: String::new(),
+            settings: tron.clone(),
+            color_space: color_space_name.to_string(),
         }
     }
 
+    /// Take the settings that apply without starting a new game (speed, AI, trails, food timing and
+    /// types, gradient animation) - the rest take effect at the next new game
+    pub fn apply_settings(&mut self, tron: &TronConfig) {
+        self.update_interval = Duration::from_secs_f64(tron.speed_ms / 1000.0);
+        self.look_ahead = tron.look_ahead;
+        self.trail_length = tron.trail_length;
+        self.ai_aggression = tron.ai_aggression;
+        self.food_ttl_seconds = tron.food_ttl_seconds;
+        self.trail_fade = tron.trail_fade;
+        self.super_food_enabled = tron.super_food_enabled;
+        self.settings.clone_from(tron);
+    }
+
+    /// Rebuild each player's gradient from the player colors in the settings
+    pub fn recolor_players(&mut self) {
+        let interp_mode = match self.settings.interpolation.as_str() {
+            "basis" => InterpolationMode::Basis,
+            "catmullrom" => InterpolationMode::CatmullRom,
+            _ => InterpolationMode::Linear,
+        };
+        let color_space = ColorSpace::from_name(&self.color_space);
+        let player_colors = self.settings.player_colors();
+
+        for (i, player) in self.players.iter_mut().enumerate() {
+            let color_name = player_colors.get(i).map(|s| s.as_str()).unwrap_or("Rainbow");
+
+            // Resolve gradient name to hex colors
+            let hex_colors = gradients::resolve_color_string(color_name);
+
+            // If it's a single color, duplicate it to make a solid "gradient"
+            let hex_for_gradient = if !hex_colors.contains(',') {
+                format!("{},{}", hex_colors, hex_colors)
+            } else {
+                hex_colors.clone()
+            };
+
+            let (gradient_opt, _, _) = build_gradient_from_color(&hex_for_gradient, true, interp_mode, color_space).unwrap_or_else(|_e| {
+                // Fallback to rainbow if parsing fails
+                let fallback_hex = gradients::resolve_color_string("Rainbow");
+                build_gradient_from_color(&fallback_hex, true, interp_mode, color_space).unwrap()
+            });
+            player.gradient = gradient_opt.unwrap_or_else(|| {
+                // Fallback gradient if None (should not happen now)
+                GradientLut::from_gradient(&colorgrad::CustomGradient::new()
+                    .html_colors(&["#ff0000", "#00ff00", "#0000ff"])
+                    .build()
+                    .unwrap())
+            });
+        }
+    }
+
+    /// Lay out the arena's walls: random ones covering `density` of it, plus the drawn ones (see draw_walls).
+    /// The cells around each player are kept clear so nobody starts boxed in
+    pub fn set_obstacles(&mut self, density: f64, spec: &str) {
+        let mut walls = random_walls(self.width, self.height, density, &mut rand::thread_rng());
+        draw_walls(&mut walls, spec);
+        for player in &self.players {
+            for (y, row) in walls.iter_mut().enumerate() {
+                for (x, cell) in row.iter_mut().enumerate() {
+                    if manhattan_distance(player.pos, Position { x: x as i32, y: y as i32 }) <= SPAWN_CLEARANCE {
+                        *cell = false;
+                    }
+                }
+            }
+        }
+        self.walls = walls;
+        self.obstacle_density = density;
+        self.obstacle_spec = spec.to_string();
+    }
+
     /// Spawn food at a random empty position (avoiding visible trails and existing foods)
     fn spawn_food(&mut self) {
         // Check if we've already reached max food count
@@ -445,14 +625,9 @@ impl TronGame {
                 food_pos.x == x && food_pos.y == y
             });
 
-            if !occupied_by_trail && !occupied_by_food {
-                // Load config to check if power food is enabled
-                let power_food_enabled = BandwidthConfig::load()
-                    .map(|cfg| cfg.tron_power_food_enabled)
-                    .unwrap_or(false);
-
+            if !occupied_by_trail && !occupied_by_food && !is_wall(&self.walls, pos) {
                 // Determine food type with priority: Power (1%) > Super (10%) > Normal
-                let food_type = if power_food_enabled && rng.gen_bool(0.01) {
+                let food_type = if self.settings.power_food_enabled && rng.gen_bool(0.01) {
                     FoodType::Power  // 1% chance for power food
                 } else if self.super_food_enabled && rng.gen_bool(0.1) {
                     FoodType::Super  // 10% chance for super food (if power food didn't spawn)
@@ -489,7 +664,7 @@ impl TronGame {
             let occupied_by_food = self.food_positions.iter().any(|(food_pos, _, _)| {
                 food_pos.x == x && food_pos.y == y
             });
-            let occupied = occupied_by_trail || occupied_by_food;
+            let occupied = occupied_by_trail || occupied_by_food || is_wall(&self.walls, pos);
 
             if !occupied {
                 // Respawn player here
@@ -529,35 +704,18 @@ impl TronGame {
         player.direction = Direction::Right;
     }
 
-    pub fn reset(&mut self, num_players: usize, player_colors: &[String]) {
-        // Load interpolation from config
-        let interpolation = BandwidthConfig::load()
-            .map(|cfg| cfg.tron_interpolation)
-            .unwrap_or_else(|_| "catmullrom".to_string());
-
-        *self = TronGame::new(
-            self.width,
-            self.height,
-            self.update_interval.as_secs_f64() * 1000.0,
-            self.look_ahead,
-            self.trail_length,
-            self.ai_aggression,
-            num_players,
-            player_colors,
-            self.food_mode,
-            self.food_max_count,
-            self.food_ttl_seconds,
-            self.trail_fade,
-            self.super_food_enabled,
-            self.diagonal_movement,
-            &interpolation,
-        );
+    /// Start a new game with the current settings (random walls are laid out afresh)
+    pub fn reset(&mut self) {
+        let (settings, color_space) = (std::mem::take(&mut self.settings), std::mem::take(&mut self.color_space));
+        let (obstacle_density, obstacle_spec) = (self.obstacle_density, std::mem::take(&mut self.obstacle_spec));
+        *self = TronGame::new(self.width, self.height, &settings, &color_space);
+        self.set_obstacles(obstacle_density, &obstacle_spec);
     }
 
     // Check if a position is occupied (considering game mode)
     fn is_occupied(&self, pos: Position, _player_id: u8) -> bool {
-        if pos.x < 0 || pos.x >= self.width as i32 || pos.y < 0 || pos.y >= self.height as i32 {
-            return true; // Out of bounds = occupied
+        if is_wall(&self.walls, pos) {
+            return true; // Out of bounds or a wall = occupied
         }
 
         if self.food_mode {
@@ -573,8 +731,8 @@ impl TronGame {
 
     // Check if position is blocked for a powered player (walls and own trail only, can cross other trails)
     fn is_occupied_powered(&self, pos: Position, player_id: u8) -> bool {
-        if pos.x < 0 || pos.x >= self.width as i32 || pos.y < 0 || pos.y >= self.height as i32 {
-            return true; // Out of bounds = occupied
+        if is_wall(&self.walls, pos) {
+            return true; // Out of bounds or a wall = occupied
         }
 
         if self.food_mode {
@@ -1508,9 +1666,8 @@ impl TronGame {
         for _ in 0..prediction_steps {
             predicted_pos = opponent.direction.next_position(predicted_pos);
 
-            // Stop if predicted position goes out of bounds
-            if predicted_pos.x < 0 || predicted_pos.x >= self.width as i32 ||
-               predicted_pos.y < 0 || predicted_pos.y >= self.height as i32 {
+            // Stop if predicted position goes out of bounds or into a wall
+            if is_wall(&self.walls, predicted_pos) {
                 break;
             }
         }
@@ -1520,8 +1677,7 @@ impl TronGame {
         for _ in 0..prediction_steps {
             test_pos = dir.next_position(test_pos);
 
-            if test_pos.x < 0 || test_pos.x >= self.width as i32 ||
-               test_pos.y < 0 || test_pos.y >= self.height as i32 {
+            if is_wall(&self.walls, test_pos) {
                 break;
             }
         }
@@ -1572,9 +1728,8 @@ impl TronGame {
             // Calculate next position in this direction
             pos = direction.next_position(pos);
 
-            // Check boundaries
-            if pos.x < 0 || pos.x >= self.width as i32 ||
-               pos.y < 0 || pos.y >= self.height as i32 {
+            // Check boundaries and walls
+            if is_wall(&self.walls, pos) {
                 break;
             }
 
@@ -1728,7 +1883,7 @@ impl TronGame {
                             idx != food_idx && other_pos.x == x && other_pos.y == y
                         });
 
-                    if !occupied_by_trail && !occupied_by_other_food {
+                    if !occupied_by_trail && !occupied_by_other_food && !is_wall(&self.walls, new_pos) {
                         // Found empty spot, relocate food here
                         if let Some((food_pos, spawn_time, _)) = self.food_positions.get_mut(food_idx) {
                             *food_pos = new_pos;
@@ -1796,15 +1951,8 @@ impl TronGame {
             player.aggression_modifier = 1.0 + (player.aggression_phase.sin() * 0.1);
         }
 
-        // Update gradient animation offsets (reload config each frame to pick up changes)
-        let (animation_speed, scale_animation_speed, animation_direction) = {
-            if let Ok(cfg) = BandwidthConfig::load() {
-                (cfg.tron_animation_speed, cfg.tron_scale_animation_speed, cfg.tron_animation_direction)
-            } else {
-                // Fallback to no animation if config load fails
-                (0.0, false, "forward".to_string())
-            }
-        };
+        // Update gradient animation offsets
+        let (animation_speed, scale_animation_speed, animation_direction) = (self.settings.animation_speed, self.settings.scale_animation_speed, self.settings.animation_direction.as_str());
 
         if animation_speed > 0.0 {
             let delta_seconds = self.update_interval.as_secs_f64();
@@ -1843,7 +1991,7 @@ impl TronGame {
                         "forward"
                     }
                 } else {
-                    animation_direction.as_str()
+                    animation_direction
                 };
 
                 // Apply direction (forward = head to tail, backward = tail to head)
@@ -1952,9 +2100,8 @@ impl TronGame {
 
                 // Check if any intermediate position is occupied or out of bounds
                 for intermediate_pos in intermediate_positions {
-                    // Check boundaries for intermediate positions
-                    if intermediate_pos.x < 0 || intermediate_pos.x >= self.width as i32 ||
-                       intermediate_pos.y < 0 || intermediate_pos.y >= self.height as i32 {
+                    // Check boundaries and walls for intermediate positions
+                    if is_wall(&self.walls, intermediate_pos) {
                         diagonal_blocked = true;
                         break;
                     }
@@ -2016,9 +2163,8 @@ impl TronGame {
                 }
             }
 
-            // Check boundaries
-            if next_pos.x < 0 || next_pos.x >= self.width as i32 ||
-               next_pos.y < 0 || next_pos.y >= self.height as i32 {
+            // Check boundaries and walls (deadly even in power mode)
+            if is_wall(&self.walls, next_pos) {
                 player.alive = false;
                 player.death_time = Some(Instant::now());
                 continue;
@@ -2130,10 +2276,7 @@ impl TronGame {
 
             // Food mode: check if player ate any food
             if self.food_mode {
-                // Check if direction flipping is enabled
-                let flip_direction_on_food = BandwidthConfig::load()
-                    .map(|cfg| cfg.tron_flip_direction_on_food)
-                    .unwrap_or(false);
+                let flip_direction_on_food = self.settings.flip_direction_on_food;
 
                 for (food_idx, (food_pos, _spawn_time, food_type)) in self.food_positions.iter().enumerate() {
                     if next_pos.x == food_pos.x && next_pos.y == food_pos.y {
@@ -2328,15 +2471,26 @@ impl TronGame {
         true // Updated
     }
 
-    pub fn render(&self, total_leds: usize) -> Vec<u8> {
-        let mut frame = vec![0u8; total_leds * 3];
+    /// Render the game into `frame` (resized to total_leds, reusing its allocation)
+    /// The layout maps grid cells to LEDs (row-major on a single matrix, or tiled panels)
+    pub fn render(&self, total_leds: usize, layout: &MatrixLayout, frame: &mut Vec<u8>) {
+        frame.clear();
+        frame.resize(total_leds * 3, 0);
+
+        // Render walls underneath everything else
+        for (y, row) in self.walls.iter().enumerate() {
+            for (x, _) in row.iter().enumerate().filter(|&(_, &wall)| wall) {
+                if let Some(led_idx) = layout.index(x, y).filter(|&i| i < total_leds) {
+                    frame[led_idx * 3..led_idx * 3 + 3].copy_from_slice(&[WALL_COLOR.0, WALL_COLOR.1, WALL_COLOR.2]);
+                }
+            }
+        }
 
         // Render all foods (white for regular, red for super, yellow for power)
         for (food_pos, _spawn_time, food_type) in &self.food_positions {
             let x = food_pos.x as usize;
             let y = food_pos.y as usize;
-            if x < self.width && y < self.height {
-                let led_idx = y * self.width + x;
+            if let Some(led_idx) = layout.index(x, y) {
                 if led_idx < total_leds {
                     let offset = led_idx * 3;
                     match food_type {
@@ -2404,12 +2558,10 @@ impl TronGame {
                 let x = pos.x as usize;
                 let y = pos.y as usize;
 
-                if x >= self.width || y >= self.height {
-                    continue;
-                }
-
                 // Calculate LED index (depends on matrix layout)
-                let led_idx = y * self.width + x;
+                let Some(led_idx) = layout.index(x, y) else {
+                    continue;
+                };
                 if led_idx >= total_leds {
                     continue;
                 }
@@ -2459,16 +2611,14 @@ impl TronGame {
                     let gradient_pos = idx as f64 / trail_len.max(1) as f64;
                     // Apply animation offset to gradient position
                     let animated_pos = (gradient_pos + player.animation_offset) % 1.0;
-                    let color = player.gradient.at(animated_pos);
+                    let rgba = player.gradient.at(animated_pos);
 
-                    frame[offset] = (color.r * 255.0 * brightness) as u8;
-                    frame[offset + 1] = (color.g * 255.0 * brightness) as u8;
-                    frame[offset + 2] = (color.b * 255.0 * brightness) as u8;
+                    frame[offset] = (rgba[0] as f64 * brightness) as u8;
+                    frame[offset + 1] = (rgba[1] as f64 * brightness) as u8;
+                    frame[offset + 2] = (rgba[2] as f64 * brightness) as u8;
                 }
             }
         }
-
-        frame
     }
 
     pub fn is_game_over(&self) -> bool {
@@ -2478,14 +2628,15 @@ impl TronGame {
 
 
 pub async fn run_tron_mode(
-    config: Arc<Mutex<BandwidthConfig>>,
+    config: BandwidthConfig,
+    mut config_change_rx: broadcast::Receiver<ConfigDelta>,
     ddp_client: Arc<Mutex<Option<DDPConnection>>>,
     shutdown: Arc<std::sync::atomic::AtomicBool>,
 ) -> Result<()> {
     // Check if multi-device mode is enabled and create manager
     let mut multi_device_manager: Option<MultiDeviceManager> = None;
     let multi_device_enabled = {
-        let cfg = config.lock().unwrap();
+        let cfg = &config;
         if cfg.multi_device_enabled && !cfg.wled_devices.is_empty() {
             // Convert config to multi-device format
             let devices: Vec<WLEDDevice> = cfg.wled_devices.iter().map(|d| WLEDDevice {
@@ -2493,12 +2644,25 @@ pub async fn run_tron_mode(
                 led_offset: d.led_offset,
                 led_count: d.led_count,
                 enabled: d.enabled,
+                max_fps: d.max_fps,
+                led_ma: d.led_ma,
+                psu_amps: d.psu_amps,
+                backup_for: d.backup_for.clone(),
+                reversed: d.reversed,
+                mirrored: d.mirrored,
+                protocol: d.protocol.clone(),
+                universe: d.universe,
+                start_channel: d.start_channel,
+                net: d.net,
+                subnet: d.subnet,
             }).collect();
 
             let md_config = MultiDeviceConfig {
                 devices,
                 send_parallel: cfg.multi_device_send_parallel,
                 fail_fast: cfg.multi_device_fail_fast,
+                stagger_sends: cfg.multi_device_stagger_sends,
+                color_correction: ColorCorrection::from_config(cfg),
             };
 
             match MultiDeviceManager::new(md_config) {
@@ -2517,44 +2681,19 @@ pub async fn run_tron_mode(
         }
     };
 
-    // Initial config
-    let (mut width, mut height, mut speed_ms, mut reset_delay_ms, mut look_ahead, mut trail_length, mut ai_aggression, mut num_players, mut player_colors, mut food_mode, mut food_max_count, mut food_ttl_seconds, mut trail_fade, mut super_food_enabled, mut diagonal_movement, mut interpolation, mut global_brightness) = {
-        let cfg = config.lock().unwrap();
-        let colors = vec![
-            cfg.tron_player_1_color.clone(),
-            cfg.tron_player_2_color.clone(),
-            cfg.tron_player_3_color.clone(),
-            cfg.tron_player_4_color.clone(),
-            cfg.tron_player_5_color.clone(),
-            cfg.tron_player_6_color.clone(),
-            cfg.tron_player_7_color.clone(),
-            cfg.tron_player_8_color.clone(),
-        ];
-        (
-            cfg.tron_width,
-            cfg.tron_height,
-            cfg.tron_speed_ms,
-            cfg.tron_reset_delay_ms,
-            cfg.tron_look_ahead,
-            cfg.tron_trail_length,
-            cfg.tron_ai_aggression,
-            cfg.tron_num_players,
-            colors,
-            cfg.tron_food_mode,
-            cfg.tron_food_max_count,
-            cfg.tron_food_ttl_seconds,
-            cfg.tron_trail_fade,
-            cfg.tron_super_food_enabled,
-            cfg.tron_diagonal_movement,
-            cfg.tron_interpolation.clone(),
-            cfg.global_brightness,
-        )
-    };
+    // The game only sees its own settings - global brightness and color space are the only others it follows
+    let mut tron = config.tron.clone();
+    let mut color_space = config.color_space.clone();
+    let mut global_brightness = config.global_brightness;
 
-    let mut total_leds = width * height;
-    let mut game = TronGame::new(width, height, speed_ms, look_ahead, trail_length, ai_aggression, num_players, &player_colors, food_mode, food_max_count, food_ttl_seconds, trail_fade, super_food_enabled, diagonal_movement, &interpolation);
+    // Play on the tiled matrix canvas if panels are configured, otherwise the tron grid
+    let mut matrix_layout = MatrixLayout::for_mode(&config, tron.width, tron.height, false);
+    let mut total_leds = matrix_layout.led_count();
+    let mut game = TronGame::new(matrix_layout.width(), matrix_layout.height(), &tron, &color_space);
+    game.set_obstacles(tron.obstacle_density, &tron.obstacles);
 
-    let mut last_config_check = Instant::now();
+    let mut frame = Vec::new();  // Reused for every rendered frame
+    let mut alloc_counter = FrameAllocCounter::new("tron");
 
     loop {
         // Check shutdown signal
@@ -2562,146 +2701,54 @@ pub async fn run_tron_mode(
             return Ok(());
         }
 
-        // Check for config changes every 500ms
-        if last_config_check.elapsed() > Duration::from_millis(500) {
-            last_config_check = Instant::now();
-
-            // Reload config from disk to pick up web UI changes
-            let cfg = match BandwidthConfig::load() {
-                Ok(c) => c,
-                Err(_) => {
-                    // If reload fails, use cached config
-                    config.lock().unwrap().clone()
-                }
-            };
-
-            // Build new player colors vector
-            let new_player_colors = vec![
-                cfg.tron_player_1_color.clone(),
-                cfg.tron_player_2_color.clone(),
-                cfg.tron_player_3_color.clone(),
-                cfg.tron_player_4_color.clone(),
-                cfg.tron_player_5_color.clone(),
-                cfg.tron_player_6_color.clone(),
-                cfg.tron_player_7_color.clone(),
-                cfg.tron_player_8_color.clone(),
-            ];
-
-            // Check if any individual player color changed
-            let colors_changed = new_player_colors != player_colors;
+        // Follow config changes (web UI, API, or file edits)
+        if let Ok(delta) = config_change_rx.try_recv() {
+            let Some(cfg) = delta.config() else { continue };
 
             // Update global brightness immediately (even if other config hasn't changed)
             global_brightness = cfg.global_brightness;
 
-            let config_changed = cfg.tron_width != width
-                || cfg.tron_height != height
-                || cfg.tron_speed_ms != speed_ms
-                || cfg.tron_look_ahead != look_ahead
-                || cfg.tron_trail_length != trail_length
-                || cfg.tron_ai_aggression != ai_aggression
-                || cfg.tron_num_players != num_players
-                || cfg.tron_food_mode != food_mode
-                || cfg.tron_food_max_count != food_max_count
-                || cfg.tron_food_ttl_seconds != food_ttl_seconds
-                || cfg.tron_trail_fade != trail_fade
-                || cfg.tron_super_food_enabled != super_food_enabled
-                || cfg.tron_diagonal_movement != diagonal_movement
-                || cfg.tron_interpolation != interpolation
-                || colors_changed;
-
-            if config_changed {
-                // Check if we need to reset the game (grid size, player count, food mode, max count, diagonal movement, or interpolation changed)
-                let needs_reset = cfg.tron_width != width
-                    || cfg.tron_height != height
-                    || cfg.tron_num_players != num_players
-                    || cfg.tron_food_mode != food_mode
-                    || cfg.tron_food_max_count != food_max_count
-                    || cfg.tron_diagonal_movement != diagonal_movement
-                    || cfg.tron_interpolation != interpolation;
-
-                // Update local vars
-                width = cfg.tron_width;
-                height = cfg.tron_height;
-                speed_ms = cfg.tron_speed_ms;
-                reset_delay_ms = cfg.tron_reset_delay_ms;
-                look_ahead = cfg.tron_look_ahead;
-                trail_length = cfg.tron_trail_length;
-                ai_aggression = cfg.tron_ai_aggression;
-                num_players = cfg.tron_num_players;
-                food_mode = cfg.tron_food_mode;
-                food_max_count = cfg.tron_food_max_count;
-                food_ttl_seconds = cfg.tron_food_ttl_seconds;
-                trail_fade = cfg.tron_trail_fade;
-                super_food_enabled = cfg.tron_super_food_enabled;
-                diagonal_movement = cfg.tron_diagonal_movement;
-                interpolation = cfg.tron_interpolation.clone();
-                player_colors = new_player_colors;
-
-                // Update cached config
-                if let Ok(mut cached) = config.lock() {
-                    *cached = cfg;
-                }
-
-                if needs_reset {
-                    // Reset game with new config
-                    total_leds = width * height;
-                    game = TronGame::new(width, height, speed_ms, look_ahead, trail_length, ai_aggression, num_players, &player_colors, food_mode, food_max_count, food_ttl_seconds, trail_fade, super_food_enabled, diagonal_movement, &interpolation);
-                } else {
-                    // Update game parameters without resetting
-                    game.update_interval = Duration::from_secs_f64(speed_ms / 1000.0);
-                    game.look_ahead = look_ahead;
-                    game.trail_length = trail_length;
-                    game.ai_aggression = ai_aggression;
-                    game.food_ttl_seconds = food_ttl_seconds;
-                    game.trail_fade = trail_fade;
-                    game.super_food_enabled = super_food_enabled;
-
-                    // Update player colors if they changed
-                    if colors_changed {
-                        // Parse interpolation mode
-                        let interp_mode = match interpolation.as_str() {
-                            "basis" => InterpolationMode::Basis,
-                            "catmullrom" => InterpolationMode::CatmullRom,
-                            _ => InterpolationMode::Linear,
-                        };
-
-                        for (i, player) in game.players.iter_mut().enumerate() {
-                            let color_name = player_colors.get(i).map(|s| s.as_str()).unwrap_or("Rainbow");
-
-                            // Resolve gradient name to hex colors
-                            let hex_colors = gradients::resolve_color_string(color_name);
-
-                            // If it's a single color, duplicate it to make a solid "gradient"
-                            let hex_for_gradient = if !hex_colors.contains(',') {
-                                format!("{},{}", hex_colors, hex_colors)
-                            } else {
-                                hex_colors.clone()
-                            };
-
-                            let (gradient_opt, _, _) = build_gradient_from_color(&hex_for_gradient, true, interp_mode).unwrap_or_else(|_e| {
-                                // Fallback to rainbow if parsing fails
-                                let fallback_hex = gradients::resolve_color_string("Rainbow");
-                                build_gradient_from_color(&fallback_hex, true, interp_mode).unwrap()
-                            });
-                            player.gradient = gradient_opt.unwrap_or_else(|| {
-                                // Fallback gradient if None (should not happen now)
-                                colorgrad::CustomGradient::new()
-                                    .html_colors(&["#ff0000", "#00ff00", "#0000ff"])
-                                    .build()
-                                    .unwrap()
-                            });
-                        }
-                    }
+            // Grid size, player count, food mode, max count, diagonal movement, obstacles, interpolation,
+            // color space or matrix layout changing starts a new game - everything else applies in place
+            let new_layout = MatrixLayout::for_mode(cfg, cfg.tron.width, cfg.tron.height, false);
+            let needs_reset = cfg.tron.width != tron.width
+                || cfg.tron.height != tron.height
+                || cfg.tron.num_players != tron.num_players
+                || cfg.tron.food_mode != tron.food_mode
+                || cfg.tron.food_max_count != tron.food_max_count
+                || cfg.tron.diagonal_movement != tron.diagonal_movement
+                || cfg.tron.obstacle_density != tron.obstacle_density
+                || cfg.tron.obstacles != tron.obstacles
+                || cfg.tron.interpolation != tron.interpolation
+                || cfg.color_space != color_space
+                || new_layout != matrix_layout;
+            let colors_changed = cfg.tron.player_colors() != tron.player_colors();
+
+            tron.clone_from(&cfg.tron);
+            color_space.clone_from(&cfg.color_space);
+            matrix_layout = new_layout;
+
+            if needs_reset {
+                total_leds = matrix_layout.led_count();
+                game = TronGame::new(matrix_layout.width(), matrix_layout.height(), &tron, &color_space);
+                game.set_obstacles(tron.obstacle_density, &tron.obstacles);
+            } else {
+                game.apply_settings(&tron);
+                if colors_changed {
+                    game.recolor_players();
                 }
             }
         }
 
         // Update game state
+        alloc_counter.begin_frame();
+        let render_start = Instant::now();
         let updated = game.update();
 
         if updated {
             // Only render and send when game actually updated
-            let frame = game.render(total_leds);
+            game.render(total_leds, &matrix_layout, &mut frame);
+            live_stats::record_render_time(render_start.elapsed());
 
             // Send to WLED (multi-device or single device)
             if multi_device_enabled {
@@ -2711,24 +2758,25 @@ pub async fn run_tron_mode(
                     }
                 }
             } else {
-                // Apply brightness to frame for single-device mode
-                let frame_to_send: Vec<u8> = if global_brightness < 1.0 {
-                    frame.iter().map(|&val| (val as f64 * global_brightness).round() as u8).collect()
-                } else {
-                    frame
-                };
+                // Apply brightness to frame for single-device mode (in place - it's re-rendered next update)
+                if global_brightness < 1.0 {
+                    for val in frame.iter_mut() {
+                        *val = (*val as f64 * global_brightness).round() as u8;
+                    }
+                }
 
                 if let Ok(mut client_guard) = ddp_client.lock() {
                     if let Some(conn) = client_guard.as_mut() {
-                        let _ = conn.write(&frame_to_send);
+                        let _ = conn.write(&frame);
                     }
                 }
             }
+            alloc_counter.end_frame();
 
             // If game over, wait and reset
             if game.is_game_over() {
-                tokio::time::sleep(Duration::from_millis(reset_delay_ms)).await;
-                game.reset(num_players, &player_colors);
+                tokio::time::sleep(Duration::from_millis(tron.reset_delay_ms)).await;
+                game.reset();
             }
         } else {
             // Game didn't update yet - sleep briefly to avoid busy-waiting
@@ -2736,3 +2784,50 @@ pub async fn run_tron_mode(
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_draw_walls() {
+        let mut walls = vec![vec![false; 8]; 4];
+        draw_walls(&mut walls, "1,1; 6,3-5,2 ;bogus;7,9-7,0");
+        let cells: Vec<(usize, usize)> = (0..4).flat_map(|y| (0..8).map(move |x| (x, y))).filter(|&(x, y)| walls[y][x]).collect();
+        assert_eq!(cells, [(7, 0), (1, 1), (7, 1), (5, 2), (6, 2), (7, 2), (5, 3), (6, 3), (7, 3)]);
+
+        assert!(is_wall(&walls, Position { x: 1, y: 1 }));
+        assert!(is_wall(&walls, Position { x: -1, y: 0 }));
+        assert!(is_wall(&walls, Position { x: 8, y: 0 }));
+        assert!(!is_wall(&walls, Position { x: 0, y: 0 }));
+    }
+
+    #[test]
+    fn test_random_walls_density() {
+        let mut rng = rand::thread_rng();
+        let walls = random_walls(64, 32, 0.2, &mut rng);
+        let count = walls.iter().flatten().filter(|&&wall| wall).count();
+        assert!(count <= (64.0 * 32.0 * 0.2) as usize);
+        assert!(count > 64 * 32 / 10);
+        assert!(random_walls(64, 32, 0.0, &mut rng).iter().flatten().all(|&wall| !wall));
+    }
+
+    #[test]
+    fn test_players_start_clear_of_walls() {
+        let tron = TronConfig {
+            num_players: 2,
+            player_1_color: "red".to_string(),
+            player_2_color: "blue".to_string(),
+            super_food_enabled: false,
+            interpolation: "linear".to_string(),
+            ..TronConfig::default()
+        };
+        let mut game = TronGame::new(16, 16, &tron, "rgb");
+        game.set_obstacles(0.0, "0,0-15,15");
+        for player in &game.players {
+            assert!(!is_wall(&game.walls, player.pos));
+            assert!(!game.is_occupied(player.direction.next_position(player.pos), player.id));
+        }
+        assert!(game.walls.iter().flatten().any(|&wall| wall));
+    }
+}
@@ -11,13 +11,199 @@ pub enum ModeExitReason {
 }
 
 // Gradient interpolation mode
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub enum InterpolationMode {
     Linear,
     Basis,
     CatmullRom,
 }
 
+// Color space gradients are blended in
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ColorSpace {
+    Rgb,
+    Hsv,  // Around the hue wheel (shortest way)
+    Oklab,  // Perceptually uniform - no muddy or dark midpoints
+    Lch,  // Oklab in polar form (OkLCh) - perceptual, keeps saturation through hue changes
+}
+
+impl ColorSpace {
+    pub fn from_name(name: &str) -> Self {
+        match name.trim().to_lowercase().as_str() {
+            "hsv" => ColorSpace::Hsv,
+            "oklab" => ColorSpace::Oklab,
+            "lch" | "oklch" => ColorSpace::Lch,
+            _ => ColorSpace::Rgb,
+        }
+    }
+
+    /// Blend two colors t (0.0-1.0) of the way from a to b in this color space
+    pub fn mix(self, a: Rgb, b: Rgb, t: f64) -> Rgb {
+        match self {
+            ColorSpace::Rgb => {
+                let lerp = |x: u8, y: u8| (x as f64 + (y as f64 - x as f64) * t).round() as u8;
+                Rgb { r: lerp(a.r, b.r), g: lerp(a.g, b.g), b: lerp(a.b, b.b) }
+            }
+            ColorSpace::Hsv => {
+                let ([h1, s1, v1], [h2, s2, v2]) = (rgb_to_hsv(a), rgb_to_hsv(b));
+                hsv_to_rgb([lerp_hue(h1, h2, t), s1 + (s2 - s1) * t, v1 + (v2 - v1) * t])
+            }
+            ColorSpace::Oklab => {
+                let (x, y) = (rgb_to_oklab(a), rgb_to_oklab(b));
+                oklab_to_rgb([0, 1, 2].map(|i| x[i] + (y[i] - x[i]) * t))
+            }
+            ColorSpace::Lch => {
+                let ([l1, c1, h1], [l2, c2, h2]) = (oklab_to_lch(rgb_to_oklab(a)), oklab_to_lch(rgb_to_oklab(b)));
+                oklab_to_rgb(lch_to_oklab([l1 + (l2 - l1) * t, c1 + (c2 - c1) * t, lerp_hue(h1, h2, t)]))
+            }
+        }
+    }
+}
+
+// Hues in degrees, blended the short way around the wheel
+fn lerp_hue(h1: f64, h2: f64, t: f64) -> f64 {
+    let delta = (h2 - h1 + 540.0).rem_euclid(360.0) - 180.0;
+    (h1 + delta * t).rem_euclid(360.0)
+}
+
+pub(crate) fn rgb_to_hsv(c: Rgb) -> [f64; 3] {
+    let (r, g, b) = (c.r as f64 / 255.0, c.g as f64 / 255.0, c.b as f64 / 255.0);
+    let max = r.max(g).max(b);
+    let delta = max - r.min(g).min(b);
+    let hue = if delta == 0.0 {
+        0.0
+    } else if max == r {
+        60.0 * ((g - b) / delta).rem_euclid(6.0)
+    } else if max == g {
+        60.0 * ((b - r) / delta + 2.0)
+    } else {
+        60.0 * ((r - g) / delta + 4.0)
+    };
+    [hue, if max == 0.0 { 0.0 } else { delta / max }, max]
+}
+
+pub(crate) fn hsv_to_rgb([h, s, v]: [f64; 3]) -> Rgb {
+    let c = v * s;
+    let x = c * (1.0 - ((h / 60.0).rem_euclid(2.0) - 1.0).abs());
+    let (r, g, b) = match (h / 60.0) as u32 {
+        0 => (c, x, 0.0),
+        1 => (x, c, 0.0),
+        2 => (0.0, c, x),
+        3 => (0.0, x, c),
+        4 => (x, 0.0, c),
+        _ => (c, 0.0, x),
+    };
+    let m = v - c;
+    let channel = |v: f64| ((v + m) * 255.0).round().clamp(0.0, 255.0) as u8;
+    Rgb { r: channel(r), g: channel(g), b: channel(b) }
+}
+
+// sRGB <-> Oklab (Björn Ottosson's reference matrices)
+fn rgb_to_oklab(c: Rgb) -> [f64; 3] {
+    let linear = |v: u8| {
+        let v = v as f64 / 255.0;
+        if v <= 0.04045 { v / 12.92 } else { ((v + 0.055) / 1.055).powf(2.4) }
+    };
+    let (r, g, b) = (linear(c.r), linear(c.g), linear(c.b));
+    let l = (0.4122214708 * r + 0.5363325363 * g + 0.0514459929 * b).cbrt();
+    let m = (0.2119034982 * r + 0.6806995451 * g + 0.1073969566 * b).cbrt();
+    let s = (0.0883024619 * r + 0.2817188376 * g + 0.6299787005 * b).cbrt();
+    [
+        0.2104542553 * l + 0.7936177850 * m - 0.0040720468 * s,
+        1.9779984951 * l - 2.4285922050 * m + 0.4505937099 * s,
+        0.0259040371 * l + 0.7827717662 * m - 0.8086757660 * s,
+    ]
+}
+
+fn oklab_to_rgb([l, a, b]: [f64; 3]) -> Rgb {
+    let l_ = (l + 0.3963377774 * a + 0.2158037573 * b).powi(3);
+    let m_ = (l - 0.1055613458 * a - 0.0638541728 * b).powi(3);
+    let s_ = (l - 0.0894841775 * a - 1.2914855480 * b).powi(3);
+    let encode = |v: f64| {
+        let v = v.clamp(0.0, 1.0);
+        let v = if v <= 0.0031308 { v * 12.92 } else { 1.055 * v.powf(1.0 / 2.4) - 0.055 };
+        (v * 255.0).round().clamp(0.0, 255.0) as u8
+    };
+    Rgb {
+        r: encode(4.0767416621 * l_ - 3.3077115913 * m_ + 0.2309699292 * s_),
+        g: encode(-1.2684380046 * l_ + 2.6097574011 * m_ - 0.3413193965 * s_),
+        b: encode(-0.0041960863 * l_ - 0.7034186147 * m_ + 1.7076147010 * s_),
+    }
+}
+
+fn oklab_to_lch([l, a, b]: [f64; 3]) -> [f64; 3] {
+    [l, (a * a + b * b).sqrt(), b.atan2(a).to_degrees().rem_euclid(360.0)]
+}
+
+fn lch_to_oklab([l, c, h]: [f64; 3]) -> [f64; 3] {
+    let h = h.to_radians();
+    [l, c * h.cos(), c * h.sin()]
+}
+
+// Steps each pair of neighbouring colors is split into when blending outside RGB
+const COLOR_SPACE_STEPS: usize = 16;
+
+// Fill in the path between neighbouring colors as it runs through the color space, so colorgrad's
+// RGB interpolation between the (now close) points follows it - a no-op for RGB
+fn expand_in_color_space(colors: &[Rgb], color_space: ColorSpace) -> Vec<Rgb> {
+    if color_space == ColorSpace::Rgb || colors.len() < 2 {
+        return colors.to_vec();
+    }
+    let mut expanded = Vec::with_capacity((colors.len() - 1) * COLOR_SPACE_STEPS + 1);
+    for pair in colors.windows(2) {
+        for step in 0..COLOR_SPACE_STEPS {
+            // The given colors are kept exactly, only the points between them are blended
+            expanded.push(if step == 0 { pair[0] } else { color_space.mix(pair[0], pair[1], step as f64 / COLOR_SPACE_STEPS as f64) });
+        }
+    }
+    expanded.extend(colors.last().copied());
+    expanded
+}
+
+// Easing curve for interpolating between bandwidth updates
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Easing {
+    Linear,
+    EaseInOut,  // Cubic - slow start and finish
+    Exponential,  // Fast start, long settle (exponential ease-out)
+    Spring,  // Overshoots the target, then settles with a damped wobble
+}
+
+impl Easing {
+    pub fn from_name(name: &str) -> Self {
+        match name.trim().to_lowercase().as_str() {
+            "ease-in-out" | "easeinout" => Easing::EaseInOut,
+            "exponential" => Easing::Exponential,
+            "spring" => Easing::Spring,
+            _ => Easing::Linear,
+        }
+    }
+
+    /// Map linear progress (0.0-1.0) to eased progress - 0 and 1 map to themselves, spring overshoots 1 in between
+    pub fn apply(self, t: f64) -> f64 {
+        let t = t.clamp(0.0, 1.0);
+        match self {
+            Easing::Linear => t,
+            Easing::EaseInOut => {
+                if t < 0.5 {
+                    4.0 * t * t * t
+                } else {
+                    1.0 - (-2.0 * t + 2.0).powi(3) / 2.0
+                }
+            }
+            Easing::Exponential => {
+                if t >= 1.0 {
+                    1.0
+                } else {
+                    1.0 - 2f64.powf(-10.0 * t)
+                }
+            }
+            // cos reaches 0 exactly at t = 1, so the wobble ends on the target
+            Easing::Spring => 1.0 - (-6.0 * t).exp() * (4.5 * std::f64::consts::PI * t).cos(),
+        }
+    }
+}
+
 // RGB color representation
 #[derive(Clone, Copy, Debug)]
 pub struct Rgb {
@@ -27,8 +213,13 @@ pub struct Rgb {
 }
 
 impl Rgb {
+    /// Parse "RRGGBB", "#RRGGBB", or a color temperature like "3000K"
     pub fn from_hex(hex: &str) -> Result<Self> {
         let hex = hex.trim_start_matches('#');
+        if let Some(kelvin) = hex.strip_suffix(['K', 'k']) {
+            let kelvin: f64 = kelvin.trim().parse().map_err(|_| anyhow::anyhow!("Invalid color temperature: {}", hex))?;
+            return Ok(kelvin_to_rgb(kelvin));
+        }
         if hex.len() != 6 {
             anyhow::bail!("Invalid hex color: {}", hex);
         }
@@ -40,12 +231,110 @@ impl Rgb {
     }
 }
 
+/// Approximate color of a black body at a temperature in Kelvin (clamped to 1000-40000K)
+/// Tanner Helland's curve fit - 6500K is close to white, lower is warmer, higher is cooler
+pub fn kelvin_to_rgb(kelvin: f64) -> Rgb {
+    let temp = kelvin.clamp(1000.0, 40000.0) / 100.0;
+    let channel = |v: f64| v.round().clamp(0.0, 255.0) as u8;
+    let r = if temp <= 66.0 { 255.0 } else { 329.698727446 * (temp - 60.0).powf(-0.1332047592) };
+    let g = if temp <= 66.0 {
+        99.4708025861 * temp.ln() - 161.1195681661
+    } else {
+        288.1221695283 * (temp - 60.0).powf(-0.0755148492)
+    };
+    let b = if temp >= 66.0 {
+        255.0
+    } else if temp <= 19.0 {
+        0.0
+    } else {
+        138.5177312231 * (temp - 10.0).ln() - 305.0447927307
+    };
+    Rgb { r: channel(r), g: channel(g), b: channel(b) }
+}
+
+/// Per-channel multipliers (0.0-1.0) that shift white to a color temperature - 6500K is neutral
+pub fn white_point_scales(kelvin: f64) -> [f64; 3] {
+    let (target, neutral) = (kelvin_to_rgb(kelvin), kelvin_to_rgb(6500.0));
+    let scales = [
+        target.r as f64 / neutral.r.max(1) as f64,
+        target.g as f64 / neutral.g.max(1) as f64,
+        target.b as f64 / neutral.b.max(1) as f64,
+    ];
+    let max = scales.iter().cloned().fold(f64::MIN, f64::max).max(f64::EPSILON);
+    scales.map(|s| s / max)
+}
+
+// Entries in a gradient lookup table - enough that neighbouring entries are indistinguishable on LEDs
+pub const GRADIENT_LUT_SIZE: usize = 1024;
+
+/// Gradient sampled into a lookup table once, so per-LED lookups are an index instead of an interpolation
+#[derive(Debug, Clone)]
+pub struct GradientLut {
+    table: Vec<[u8; 4]>,
+}
+
+impl GradientLut {
+    pub fn from_gradient(gradient: &colorgrad::Gradient) -> Self {
+        let last = (GRADIENT_LUT_SIZE - 1) as f64;
+        let table = (0..GRADIENT_LUT_SIZE)
+            .map(|i| gradient.at(i as f64 / last).to_rgba8())
+            .collect();
+        Self { table }
+    }
+
+    /// RGBA at a position 0.0-1.0 (clamped, like colorgrad), with any gradient animation applied
+    pub fn at(&self, pos: f64) -> [u8; 4] {
+        crate::gradient_anim::animate(self.base_at(pos), pos)
+    }
+
+    /// RGBA at a position 0.0-1.0 as built, ignoring gradient animation
+    pub fn base_at(&self, pos: f64) -> [u8; 4] {
+        let index = (pos.clamp(0.0, 1.0) * (self.table.len() - 1) as f64).round() as usize;
+        self.table[index]
+    }
+}
+
+/// Gradient built from a color string, rebuilt only when the color string or settings change
+/// For loops that would otherwise call build_gradient_from_color every frame
+pub struct GradientCache {
+    key: Option<(String, bool, InterpolationMode, ColorSpace)>,
+    value: (Option<GradientLut>, Vec<Rgb>, Rgb),
+}
+
+impl GradientCache {
+    pub fn new() -> Self {
+        Self {
+            key: None,
+            value: (None, Vec::new(), Rgb { r: 255, g: 255, b: 255 }),
+        }
+    }
+
+    pub fn get(&mut self, color_str: &str, use_gradient: bool, interpolation_mode: InterpolationMode, color_space: ColorSpace) -> Result<&(Option<GradientLut>, Vec<Rgb>, Rgb)> {
+        let stale = match &self.key {
+            Some((s, g, m, c)) => s != color_str || *g != use_gradient || *m != interpolation_mode || *c != color_space,
+            None => true,
+        };
+        if stale {
+            self.value = build_gradient_from_color(color_str, use_gradient, interpolation_mode, color_space)?;
+            self.key = Some((color_str.to_string(), use_gradient, interpolation_mode, color_space));
+        }
+        Ok(&self.value)
+    }
+}
+
+impl Default for GradientCache {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 // Helper function to build gradient from color string (cyclic for animation)
 pub fn build_gradient_from_color(
     color_str: &str,
     use_gradient: bool,
     interpolation_mode: InterpolationMode,
-) -> Result<(Option<colorgrad::Gradient>, Vec<Rgb>, Rgb)> {
+    color_space: ColorSpace,
+) -> Result<(Option<GradientLut>, Vec<Rgb>, Rgb)> {
     let hex_colors: Vec<&str> = color_str.split(',').map(|s| s.trim()).collect();
 
     // Parse all colors into RGB
@@ -57,19 +346,15 @@ pub fn build_gradient_from_color(
     // Build gradient only if we have multiple colors and use_gradient is enabled
     let gradient = if rgb_colors.len() >= 2 && use_gradient {
         // Create smooth gradient through all colors (no plateaus)
-        let mut colorgrad_colors = Vec::new();
+        // Add first color at end to make it cyclic/repeating
+        let mut cyclic_colors = rgb_colors.clone();
+        cyclic_colors.extend(rgb_colors.first().copied());
 
         // Convert RGB colors to colorgrad colors
-        for rgb in rgb_colors.iter() {
-            let color = Color::from_rgba8(rgb.r, rgb.g, rgb.b, 255);
-            colorgrad_colors.push(color);
-        }
-
-        // Add first color at end to make it cyclic/repeating
-        if let Some(first_rgb) = rgb_colors.first() {
-            let first_color = Color::from_rgba8(first_rgb.r, first_rgb.g, first_rgb.b, 255);
-            colorgrad_colors.push(first_color);
-        }
+        let colorgrad_colors: Vec<Color> = expand_in_color_space(&cyclic_colors, color_space)
+            .iter()
+            .map(|rgb| Color::from_rgba8(rgb.r, rgb.g, rgb.b, 255))
+            .collect();
 
         let cg_interpolation = match interpolation_mode {
             InterpolationMode::Basis => colorgrad::Interpolation::Basis,
@@ -82,7 +367,7 @@ pub fn build_gradient_from_color(
             .interpolation(cg_interpolation)
             .build()?;
 
-        Some(gradient)
+        Some(GradientLut::from_gradient(&gradient))
     } else {
         None
     };
@@ -98,7 +383,8 @@ pub fn build_intensity_gradient(
     color_str: &str,
     use_gradient: bool,
     interpolation_mode: InterpolationMode,
-) -> Result<Option<colorgrad::Gradient>> {
+    color_space: ColorSpace,
+) -> Result<Option<GradientLut>> {
     if !use_gradient {
         return Ok(None);
     }
@@ -110,12 +396,11 @@ pub fn build_intensity_gradient(
     }
 
     let gradient = if rgb_colors.len() >= 2 {
-        let mut colorgrad_colors = Vec::new();
-        for rgb in rgb_colors.iter() {
-            let color = Color::from_rgba8(rgb.r, rgb.g, rgb.b, 255);
-            colorgrad_colors.push(color);
-        }
         // NO cyclic behavior - do NOT add first color at end
+        let colorgrad_colors: Vec<Color> = expand_in_color_space(&rgb_colors, color_space)
+            .iter()
+            .map(|rgb| Color::from_rgba8(rgb.r, rgb.g, rgb.b, 255))
+            .collect();
 
         let cg_interpolation = match interpolation_mode {
             InterpolationMode::Basis => colorgrad::Interpolation::Basis,
@@ -128,10 +413,81 @@ pub fn build_intensity_gradient(
             .interpolation(cg_interpolation)
             .build()?;
 
-        Some(gradient)
+        Some(GradientLut::from_gradient(&gradient))
     } else {
         None
     };
 
     Ok(gradient)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_easing_endpoints() {
+        for name in ["linear", "ease-in-out", "exponential", "spring"] {
+            let easing = Easing::from_name(name);
+            assert!(easing.apply(0.0).abs() < 1e-9, "{}", name);
+            assert!((easing.apply(1.0) - 1.0).abs() < 1e-9, "{}", name);
+        }
+        assert_eq!(Easing::from_name("bogus"), Easing::Linear);
+        assert!(Easing::EaseInOut.apply(0.25) < 0.25);
+        assert!(Easing::Exponential.apply(0.25) > 0.25);
+        assert!((0..100).any(|i| Easing::Spring.apply(i as f64 / 100.0) > 1.0));
+    }
+
+    #[test]
+    fn test_gradient_lut_and_cache() {
+        let (lut, colors, _) = build_gradient_from_color("#ff0000,#0000ff", true, InterpolationMode::Linear, ColorSpace::Rgb).unwrap();
+        let lut = lut.unwrap();
+        assert_eq!(colors.len(), 2);
+        assert_eq!(lut.at(0.0), [255, 0, 0, 255]);
+        assert_eq!(lut.at(1.0), [0, 0, 255, 255]);
+        assert_eq!(lut.at(2.0), lut.at(1.0));
+
+        let mut cache = GradientCache::new();
+        assert!(cache.get("#ff0000,#0000ff", true, InterpolationMode::Linear, ColorSpace::Rgb).unwrap().0.is_some());
+        // Same key is served from the cache, a change rebuilds
+        assert!(cache.get("#ff0000,#0000ff", true, InterpolationMode::Linear, ColorSpace::Rgb).unwrap().0.is_some());
+        assert!(cache.get("#ff0000,#0000ff", false, InterpolationMode::Linear, ColorSpace::Rgb).unwrap().0.is_none());
+    }
+
+    #[test]
+    fn test_color_temperature() {
+        let warm = Rgb::from_hex("2700K").unwrap();
+        assert_eq!(warm.r, 255);
+        assert!(warm.b < warm.g && warm.g < warm.r);
+        let cool = Rgb::from_hex("10000k").unwrap();
+        assert!(cool.b == 255 && cool.r < 255);
+        assert!(Rgb::from_hex("warmK").is_err());
+
+        assert_eq!(white_point_scales(6500.0), [1.0, 1.0, 1.0]);
+        let scales = white_point_scales(3000.0);
+        assert_eq!(scales[0], 1.0);
+        assert!(scales[2] < scales[1] && scales[1] < 1.0);
+    }
+
+    #[test]
+    fn test_color_space_mix() {
+        let red = Rgb { r: 255, g: 0, b: 0 };
+        let green = Rgb { r: 0, g: 255, b: 0 };
+        for space in [ColorSpace::Rgb, ColorSpace::Hsv, ColorSpace::Oklab, ColorSpace::Lch] {
+            let start = space.mix(red, green, 0.0);
+            let end = space.mix(red, green, 1.0);
+            assert!(start.r >= 254 && start.g <= 1 && start.b <= 1, "{:?}", space);
+            assert!(end.r <= 1 && end.g >= 254 && end.b <= 1, "{:?}", space);
+        }
+        // RGB goes through a dark olive, the others stay bright
+        let rgb_mid = ColorSpace::Rgb.mix(red, green, 0.5);
+        let hsv_mid = ColorSpace::Hsv.mix(red, green, 0.5);
+        let lab_mid = ColorSpace::Oklab.mix(red, green, 0.5);
+        assert_eq!((hsv_mid.r, hsv_mid.g, hsv_mid.b), (255, 255, 0));
+        assert!(lab_mid.r as u32 + lab_mid.g as u32 > rgb_mid.r as u32 + rgb_mid.g as u32);
+        assert_eq!(ColorSpace::from_name("OkLab"), ColorSpace::Oklab);
+
+        let (lut, _, _) = build_gradient_from_color("FF0000,00FF00", true, InterpolationMode::Linear, ColorSpace::Lch).unwrap();
+        assert_eq!(lut.unwrap().at(0.0), [255, 0, 0, 255]);
+    }
+}
@@ -0,0 +1,185 @@
+// Virtual Device Module - A stand-in WLED for developing effects without hardware: listens for DDP like a
+// WLED controller and draws each frame as a grid of colored blocks in the terminal ("rustwled virtual")
+// Devices with ip = "virtual" (or "virtual:PORT") send their frames to it on this machine
+use anyhow::{Context, Result};
+use std::fmt::Write as _;
+use std::io::{self, ErrorKind, Write};
+use std::net::UdpSocket;
+use std::time::{Duration, Instant};
+
+use crate::relay;
+
+/// Default port of the virtual device - 4048 is left for real WLEDs and DDP input mode
+pub const VIRTUAL_DEVICE_PORT: u16 = 4049;
+
+const VIRTUAL_PREFIX: &str = "virtual";
+
+// Largest frame accepted (100k LEDs) - offsets past it are ignored rather than growing the buffer
+const MAX_FRAME_LEN: usize = 300_000;
+
+// The terminal can't keep up with full frame rates - frames in between are received but not drawn
+const DRAW_INTERVAL: Duration = Duration::from_millis(33);
+
+/// Whether a device address selects the virtual device instead of a WLED controller
+pub fn is_virtual(ip: &str) -> bool {
+    ip == VIRTUAL_PREFIX || ip.starts_with("virtual:")
+}
+
+/// Where to send a device's DDP frames - port 4048 on a WLED controller, or the virtual device on this machine
+pub fn ddp_address(ip: &str) -> String {
+    match ip.strip_prefix(VIRTUAL_PREFIX) {
+        Some("") => format!("127.0.0.1:{}", VIRTUAL_DEVICE_PORT),
+        Some(port) if port.starts_with(':') => format!("127.0.0.1{}", port),
+        _ => format!("{}:4048", ip),
+    }
+}
+
+/// DDP receiver assembling frames the way a WLED controller would
+pub struct VirtualDevice {
+    socket: UdpSocket,
+    frame: Vec<u8>,  // Grows to the furthest pixel written - the LED count isn't known up front
+    frames: u64,
+}
+
+impl VirtualDevice {
+    pub fn bind(port: u16) -> Result<Self> {
+        let socket = UdpSocket::bind(("0.0.0.0", port))
+            .with_context(|| format!("Could not listen for DDP on port {}", port))?;
+        Ok(VirtualDevice { socket, frame: Vec::new(), frames: 0 })
+    }
+
+    /// Port the device listens on (the one the OS picked, when bound to port 0)
+    pub fn port(&self) -> Result<u16> {
+        Ok(self.socket.local_addr()?.port())
+    }
+
+    /// Wait up to `timeout` for the next complete (pushed) frame
+    pub fn recv_frame(&mut self, timeout: Duration) -> Result<Option<&[u8]>> {
+        let deadline = Instant::now() + timeout;
+        let mut packet = [0u8; 1500];
+        loop {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            if remaining.is_zero() {
+                return Ok(None);
+            }
+            self.socket.set_read_timeout(Some(remaining))?;
+            let size = match self.socket.recv(&mut packet) {
+                Ok(size) => size,
+                Err(e) if matches!(e.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut) => return Ok(None),
+                Err(e) => return Err(e.into()),
+            };
+            if let Some((offset, data, push)) = relay::parse_ddp_packet(&packet[..size]) {
+                if offset + data.len() > MAX_FRAME_LEN {
+                    continue;
+                }
+                if self.frame.len() < offset + data.len() {
+                    self.frame.resize(offset + data.len(), 0);
+                }
+                self.frame[offset..offset + data.len()].copy_from_slice(data);
+                if push {
+                    self.frames += 1;
+                    return Ok(Some(&self.frame));
+                }
+            }
+        }
+    }
+
+    /// Frames received so far
+    pub fn frames(&self) -> u64 {
+        self.frames
+    }
+}
+
+/// One terminal line per row of `columns` LEDs, each LED a two-character block in its color (24-bit ANSI)
+pub fn render_grid(frame: &[u8], columns: usize) -> Vec<String> {
+    frame.chunks(columns.max(1) * 3)
+        .map(|row| {
+            let mut line = String::new();
+            for pixel in row.chunks_exact(3) {
+                let _ = write!(line, "\x1b[48;2;{};{};{}m  ", pixel[0], pixel[1], pixel[2]);
+            }
+            line.push_str("\x1b[0m");
+            line
+        })
+        .collect()
+}
+
+/// Run the virtual device until interrupted - `columns` LEDs per row (default: as many as fit the terminal)
+pub fn run(port: u16, columns: Option<usize>) -> Result<()> {
+    let mut device = VirtualDevice::bind(port)?;
+    let port = device.port()?;
+    println!("🖥️  Virtual WLED listening for DDP on port {}", port);
+    println!("   Add a device with ip = \"{}\" to send to it - Ctrl+C to stop", if port == VIRTUAL_DEVICE_PORT {
+        VIRTUAL_PREFIX.to_string()
+    } else {
+        format!("{}:{}", VIRTUAL_PREFIX, port)
+    });
+
+    let started = Instant::now();
+    let mut last_draw: Option<Instant> = None;
+    let mut stdout = io::stdout();
+    loop {
+        let Some(frame) = device.recv_frame(Duration::from_millis(500))? else { continue };
+        if matches!(last_draw, Some(at) if at.elapsed() < DRAW_INTERVAL) {
+            continue;
+        }
+        if last_draw.is_none() {
+            write!(stdout, "\x1b[2J")?;  // Clear the startup message once frames arrive
+        }
+        last_draw = Some(Instant::now());
+
+        let leds = frame.len() / 3;
+        let columns = columns.unwrap_or_else(|| {
+            let width = crossterm::terminal::size().map(|(w, _)| w as usize).unwrap_or(80);
+            (width / 2).clamp(1, leds.max(1))
+        });
+        let grid = render_grid(frame, columns).join("\r\n");
+        let fps = device.frames() as f64 / started.elapsed().as_secs_f64();
+        write!(stdout, "\x1b[H{}\x1b[J\r\n{} LEDs, {} frames, {:.1} fps (port {})", grid, leds, device.frames(), fps, port)?;
+        stdout.flush()?;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ddp_address() {
+        assert_eq!(ddp_address("192.168.1.50"), "192.168.1.50:4048");
+        assert_eq!(ddp_address("virtual"), "127.0.0.1:4049");
+        assert_eq!(ddp_address("virtual:5000"), "127.0.0.1:5000");
+        assert!(is_virtual("virtual:5000"));
+        assert!(!is_virtual("virtualbox.local"));
+    }
+
+    #[test]
+    fn test_render_grid() {
+        let grid = render_grid(&[255, 0, 0, 0, 255, 0, 0, 0, 255], 2);
+        assert_eq!(grid.len(), 2);
+        assert_eq!(grid[0], "\x1b[48;2;255;0;0m  \x1b[48;2;0;255;0m  \x1b[0m");
+        assert_eq!(grid[1], "\x1b[48;2;0;0;255m  \x1b[0m");
+    }
+
+    #[test]
+    fn test_recv_frame() {
+        let mut device = VirtualDevice::bind(0).unwrap();
+        let port = device.port().unwrap();
+        let sender = UdpSocket::bind("127.0.0.1:0").unwrap();
+
+        // Two packets, the second at an offset and pushing the frame
+        let packet = |flags: u8, offset: u32, data: &[u8]| {
+            let mut packet = vec![flags, 0, 0x0B, 0x01];
+            packet.extend_from_slice(&offset.to_be_bytes());
+            packet.extend_from_slice(&(data.len() as u16).to_be_bytes());
+            packet.extend_from_slice(data);
+            packet
+        };
+        sender.send_to(&packet(0x40, 0, &[1, 2, 3]), ("127.0.0.1", port)).unwrap();
+        sender.send_to(&packet(0x41, 3, &[4, 5, 6]), ("127.0.0.1", port)).unwrap();
+
+        let frame = device.recv_frame(Duration::from_secs(2)).unwrap().map(|f| f.to_vec());
+        assert_eq!(frame, Some(vec![1, 2, 3, 4, 5, 6]));
+        assert_eq!(device.frames(), 1);
+    }
+}
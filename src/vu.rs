@@ -0,0 +1,262 @@
+// VU Module - Level scaling for the VU meter (boosted linear or calibrated dBFS)
+use crate::config::VuConfig;
+
+// Linear scale boost - a peak at 1/4 of full scale fills the meter
+const VU_LINEAR_GAIN: f32 = 4.0;
+
+// Quietest level reported in dBFS (digital silence would otherwise be -inf)
+const DB_FLOOR: f32 = -120.0;
+
+// True VU integration time - a steady tone reads 99% of its level after this long
+const VU_INTEGRATION_SECS: f64 = 0.3;
+
+/// How the meter measures and smooths each channel
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum VuBallistics {
+    Peak,  // Window peak with attack/decay smoothing - fast, catches transients
+    Rms,  // Window RMS with attack/decay smoothing - closer to loudness
+    Vu,  // RMS integrated over 300ms, the same rising and falling - classic analog VU feel
+}
+
+impl VuBallistics {
+    pub fn from_name(name: &str) -> Self {
+        match name {
+            "rms" => VuBallistics::Rms,
+            "vu" => VuBallistics::Vu,
+            _ => VuBallistics::Peak,
+        }
+    }
+
+    /// Measure one channel of an interleaved sample window
+    pub fn measure(&self, samples: &[f32], channels: usize, channel: usize) -> f32 {
+        self.measure_values(samples.iter().skip(channel).step_by(channels.max(1)).copied())
+    }
+
+    /// Measure the mono sum (L+R)/2 of an interleaved sample window
+    pub fn measure_mono(&self, samples: &[f32], channels: usize) -> f32 {
+        if channels < 2 {
+            return self.measure(samples, 1, 0);
+        }
+        self.measure_values(samples.chunks_exact(channels).map(|frame| (frame[0] + frame[1]) * 0.5))
+    }
+
+    /// Levels for the left and right meters - both show L+R when mono, and a mono input feeds both
+    pub fn measure_levels(&self, samples: &[f32], channels: usize, mono: bool) -> (f32, f32) {
+        if mono {
+            let level = self.measure_mono(samples, channels);
+            (level, level)
+        } else if channels >= 2 {
+            // Stereo or multi-channel - only left (ch 0) and right (ch 1) are metered
+            (self.measure(samples, channels, 0), self.measure(samples, channels, 1))
+        } else {
+            let level = self.measure(samples, 1, 0);
+            (level, level)
+        }
+    }
+
+    fn measure_values(&self, values: impl Iterator<Item = f32>) -> f32 {
+        match self {
+            VuBallistics::Peak => values.fold(0.0_f32, |peak, s| peak.max(s.abs())),
+            VuBallistics::Rms | VuBallistics::Vu => {
+                let (sum, count) = values.fold((0.0_f32, 0usize), |(sum, count), s| (sum + s * s, count + 1));
+                if count == 0 { 0.0 } else { (sum / count as f32).sqrt() }
+            }
+        }
+    }
+
+    /// Move a meter level towards this frame's measurement
+    /// Peak and RMS use the configured attack/decay factors; VU integrates over a fixed 300ms
+    pub fn smooth(&self, current: f32, target: f32, attack_factor: f32, decay_factor: f32, frame_secs: f64) -> f32 {
+        let factor = match self {
+            VuBallistics::Vu => (1.0 - (-frame_secs * 100f64.ln() / VU_INTEGRATION_SECS).exp()) as f32,
+            _ if target > current => attack_factor,
+            _ => decay_factor,
+        };
+        current + (target - current) * factor
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum VuScale {
+    Linear,  // Level boosted by a fixed gain, clipping above 1.0
+    Db { range_db: f32, reference_db: f32 },  // Meter spans (reference - range)..reference dBFS
+}
+
+impl VuScale {
+    pub fn from_config(vu: &VuConfig) -> Self {
+        if vu.scale == "db" {
+            VuScale::Db {
+                range_db: vu.db_range as f32,
+                reference_db: vu.db_reference as f32,
+            }
+        } else {
+            VuScale::Linear
+        }
+    }
+
+    /// Map a channel level (1.0 = full scale) to meter fill (0.0-1.0), and whether it's over the top of the scale
+    pub fn meter(&self, level: f32) -> (f32, bool) {
+        let fill = match *self {
+            VuScale::Linear => level * VU_LINEAR_GAIN,
+            VuScale::Db { range_db, reference_db } => {
+                (level_to_db(level) - (reference_db - range_db)) / range_db
+            }
+        };
+        (fill.clamp(0.0, 1.0), fill > 1.0)
+    }
+
+    /// Scale markers as (dB label, meter position 0.0-1.0) - none for the linear scale
+    pub fn markers(&self) -> Vec<(i32, f32)> {
+        let VuScale::Db { range_db, reference_db } = *self else {
+            return Vec::new();
+        };
+
+        // Wider ranges get sparser marks so labels don't crowd
+        let step = if range_db <= 24.0 { 3 } else if range_db <= 48.0 { 6 } else if range_db <= 96.0 { 12 } else { 20 };
+        let bottom = reference_db - range_db;
+        let mut markers = Vec::new();
+        let mut db = reference_db.floor() as i32;
+        while db as f32 >= bottom {
+            markers.push((db, (db as f32 - bottom) / range_db));
+            db -= step;
+        }
+        markers.reverse();
+        markers
+    }
+}
+
+/// LEDs given to the left meter - a mono meter filling left or right uses the whole strip,
+/// while mirrored/opposing mono show the same level on both halves
+pub fn left_meter_leds(total_leds: usize, mono: bool, direction: &str) -> usize {
+    if mono && !matches!(direction, "mirrored" | "opposing") {
+        total_leds
+    } else {
+        total_leds / 2
+    }
+}
+
+/// Convert a linear level (1.0 = full scale) to dBFS
+pub fn level_to_db(level: f32) -> f32 {
+    if level <= 0.0 {
+        DB_FLOOR
+    } else {
+        (20.0 * level.log10()).max(DB_FLOOR)
+    }
+}
+
+/// Lay the scale markers out as a text row `width` characters wide, for printing under a meter bar
+/// `reversed` puts the top of the scale on the left (for meters that fill right to left)
+pub fn marker_row(scale: &VuScale, width: usize, reversed: bool) -> String {
+    let mut row = vec![' '; width];
+    let mut free_from = 0;  // Next column a label can start at without touching the previous one
+
+    let mut markers = scale.markers();
+    if reversed {
+        markers.reverse();
+    }
+    for (db, pos) in markers {
+        let pos = if reversed { 1.0 - pos } else { pos };
+        let label: Vec<char> = db.to_string().chars().collect();
+        if label.len() > width {
+            continue;
+        }
+
+        // Centre the label on its position, nudged inside the row
+        let column = (pos * width as f32).round() as usize;
+        let start = column.saturating_sub(label.len() / 2).min(width - label.len());
+        if start < free_from {
+            continue;
+        }
+        row[start..start + label.len()].copy_from_slice(&label);
+        free_from = start + label.len() + 1;
+    }
+    row.into_iter().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_db_scale_meter() {
+        let scale = VuScale::Db { range_db: 60.0, reference_db: 0.0 };
+
+        // Full scale fills the meter, -30 dBFS is half way, silence is empty
+        assert_eq!(scale.meter(1.0), (1.0, false));
+        let (fill, clipping) = scale.meter(10f32.powf(-30.0 / 20.0));
+        assert!((fill - 0.5).abs() < 1e-4 && !clipping);
+        assert_eq!(scale.meter(0.0), (0.0, false));
+
+        // Above the reference clips
+        let scale = VuScale::Db { range_db: 60.0, reference_db: -6.0 };
+        assert!(scale.meter(1.0).1);
+
+        // Linear keeps the old fixed boost
+        assert_eq!(VuScale::Linear.meter(0.125), (0.5, false));
+        assert_eq!(VuScale::Linear.meter(0.5), (1.0, true));
+    }
+
+    #[test]
+    fn test_ballistics() {
+        // Stereo square wave at +-0.5 on the left, silence on the right
+        let samples = [0.5, 0.0, -0.5, 0.0, 0.5, 0.0, -0.5, 0.0];
+        assert_eq!(VuBallistics::Peak.measure(&samples, 2, 0), 0.5);
+        assert_eq!(VuBallistics::Rms.measure(&samples, 2, 0), 0.5);
+        assert_eq!(VuBallistics::Rms.measure(&samples, 2, 1), 0.0);
+        assert_eq!(VuBallistics::Peak.measure_mono(&samples, 2), 0.25);
+
+        // Out of phase channels cancel in the mono sum
+        assert_eq!(VuBallistics::Peak.measure_mono(&[0.5, -0.5, -0.5, 0.5], 2), 0.0);
+
+        // A sine's RMS sits 3 dB under its peak
+        let sine: Vec<f32> = (0..1000).map(|i| (i as f32 * 0.0628).sin()).collect();
+        assert!((VuBallistics::Rms.measure(&sine, 1, 0) - std::f32::consts::FRAC_1_SQRT_2).abs() < 0.01);
+
+        // VU reads 99% of a steady level after 300ms, whatever the frame rate
+        let mut level = 0.0;
+        for _ in 0..30 {
+            level = VuBallistics::Vu.smooth(level, 1.0, 1.0, 1.0, 0.01);
+        }
+        assert!((level - 0.99).abs() < 1e-3);
+
+        // Peak and RMS use the attack/decay factors
+        assert_eq!(VuBallistics::Peak.smooth(0.0, 1.0, 0.5, 0.1, 0.01), 0.5);
+        assert_eq!(VuBallistics::Rms.smooth(1.0, 0.0, 0.5, 0.1, 0.01), 0.9);
+    }
+
+    #[test]
+    fn test_mono_meter() {
+        // Signal on the left channel only - mono shows half its level on both meters
+        let samples = [0.8, 0.0, -0.8, 0.0];
+        assert_eq!(VuBallistics::Peak.measure_levels(&samples, 2, false), (0.8, 0.0));
+        assert_eq!(VuBallistics::Peak.measure_levels(&samples, 2, true), (0.4, 0.4));
+
+        // A mono input feeds both meters either way
+        assert_eq!(VuBallistics::Peak.measure_levels(&[0.3, -0.6], 1, false), (0.6, 0.6));
+
+        // Mono filling one way takes the whole strip, mirrored and opposing keep two halves
+        assert_eq!(left_meter_leds(100, true, "left"), 100);
+        assert_eq!(left_meter_leds(100, true, "mirrored"), 50);
+        assert_eq!(left_meter_leds(100, true, "opposing"), 50);
+        assert_eq!(left_meter_leds(100, false, "left"), 50);
+    }
+
+    #[test]
+    fn test_markers() {
+        let scale = VuScale::Db { range_db: 60.0, reference_db: 0.0 };
+        let markers = scale.markers();
+        assert_eq!(markers.first(), Some(&(-60, 0.0)));
+        assert_eq!(markers.last(), Some(&(0, 1.0)));
+        assert_eq!(markers.len(), 6);
+        assert!(VuScale::Linear.markers().is_empty());
+
+        let row = marker_row(&scale, 30, false);
+        assert_eq!(row.chars().count(), 30);
+        assert!(row.starts_with("-60"));
+        assert!(row.ends_with('0'));
+
+        let row = marker_row(&scale, 30, true);
+        assert!(row.starts_with('0'));
+        assert!(row.ends_with("-60"));
+    }
+}
@@ -6,8 +6,12 @@ use image::{ImageBuffer, RgbaImage};
 use std::sync::{Arc, Mutex};
 use tokio::sync::RwLock;
 
+use crate::alloc_track::FrameAllocCounter;
 use crate::config::BandwidthConfig;
-use crate::multi_device::{MultiDeviceConfig, MultiDeviceManager, WLEDDevice};
+use crate::frame_interpolation;
+use crate::live_stats;
+use crate::multi_device::{ColorCorrection, MultiDeviceConfig, MultiDeviceManager, WLEDDevice};
+use crate::screen_zones::{self, ScreenZones};
 
 use std::sync::atomic::{AtomicU64, Ordering};
 
@@ -21,6 +25,8 @@ pub struct WebcamState {
     pub frames_sent: Arc<AtomicU64>,          // Frames actually sent
     pub frames_dropped: Arc<AtomicU64>,       // Frames dropped due to backpressure
     pub last_frame_time: Arc<Mutex<Instant>>, // Last time a frame was sent to DDP
+    pub zones: Arc<Mutex<ScreenZones>>,       // Smoothed zone colors (zone layouts only)
+    alloc_counter: Mutex<FrameAllocCounter>,  // Conversion and send are synchronous, so one thread does each frame's work
 }
 
 impl WebcamState {
@@ -32,6 +38,8 @@ impl WebcamState {
             frames_sent: Arc::new(AtomicU64::new(0)),
             frames_dropped: Arc::new(AtomicU64::new(0)),
             last_frame_time: Arc::new(Mutex::new(Instant::now())),
+            zones: Arc::new(Mutex::new(ScreenZones::new())),
+            alloc_counter: Mutex::new(FrameAllocCounter::new("webcam")),
         }
     }
 
@@ -46,16 +54,33 @@ impl WebcamState {
             led_offset: d.led_offset,
             led_count: d.led_count,
             enabled: d.enabled,
+            max_fps: d.max_fps,
+            led_ma: d.led_ma,
+            psu_amps: d.psu_amps,
+            backup_for: d.backup_for.clone(),
+            reversed: d.reversed,
+            mirrored: d.mirrored,
+            protocol: d.protocol.clone(),
+            universe: d.universe,
+            start_channel: d.start_channel,
+            net: d.net,
+            subnet: d.subnet,
         }).collect();
 
         let md_config = MultiDeviceConfig {
             devices,
             send_parallel: config.multi_device_send_parallel,
             fail_fast: config.multi_device_fail_fast,
+            stagger_sends: config.multi_device_stagger_sends,
+            color_correction: ColorCorrection::from_config(&config),
         };
 
-        let manager = MultiDeviceManager::new(md_config)?;
-        *self.multi_device_manager.lock().unwrap() = Some(manager);
+        // An existing manager switches over in place, keeping its connections to devices that stay
+        let mut current = self.multi_device_manager.lock().unwrap();
+        match current.as_mut() {
+            Some(manager) => manager.reconfigure(md_config)?,
+            None => *current = Some(MultiDeviceManager::new(md_config)?),
+        }
         Ok(())
     }
 
@@ -76,6 +101,18 @@ impl WebcamState {
                 new.ip != old.ip ||
                 new.led_offset != old.led_offset ||
                 new.led_count != old.led_count ||
+                new.max_fps != old.max_fps ||
+                new.led_ma != old.led_ma ||
+                new.psu_amps != old.psu_amps ||
+                new.backup_for != old.backup_for ||
+                new.reversed != old.reversed ||
+                new.mirrored != old.mirrored ||
+                new.segment != old.segment ||
+                new.protocol != old.protocol ||
+                new.universe != old.universe ||
+                new.start_channel != old.start_channel ||
+                new.net != old.net ||
+                new.subnet != old.subnet ||
                 new.enabled != old.enabled
             });
 
@@ -112,9 +149,9 @@ pub async fn handle_webcam_ws(
     let config = state.config.read().await;
     let init_msg = serde_json::json!({
         "type": "config",
-        "width": config.webcam_frame_width,
-        "height": config.webcam_frame_height,
-        "targetFps": config.webcam_target_fps,
+        "width": config.webcam.frame_width,
+        "height": config.webcam.frame_height,
+        "targetFps": config.webcam.target_fps,
     });
     drop(config);
 
@@ -122,6 +159,9 @@ pub async fn handle_webcam_ws(
         return;
     }
 
+    // Fill in between camera frames while frame interpolation is on
+    let ticker = tokio::spawn(tick_interpolation(state.multi_device_manager.clone()));
+
     // Process incoming frames
     while let Some(msg) = socket.next().await {
         match msg {
@@ -147,6 +187,17 @@ pub async fn handle_webcam_ws(
             }
         }
     }
+    ticker.abort();
+}
+
+// Send the in-between frames frame interpolation blends from one camera frame to the next
+async fn tick_interpolation(manager: Arc<Mutex<Option<MultiDeviceManager>>>) {
+    loop {
+        tokio::time::sleep(frame_interpolation::output_interval()).await;
+        if let Some(manager) = manager.lock().unwrap().as_mut() {
+            let _ = manager.tick();
+        }
+    }
 }
 
 /// Process incoming RGBA frame and send to WLED via DDP
@@ -171,11 +222,11 @@ async fn process_frame(state: &WebcamState, data: Vec<u8>) -> Result<()> {
         let _ = state.reinit_ddp_client_if_needed().await;
     }
 
-    let target_width = config.webcam_frame_width;
-    let target_height = config.webcam_frame_height;
-    let brightness = config.webcam_brightness;
+    let target_width = config.webcam.frame_width;
+    let target_height = config.webcam.frame_height;
+    let brightness = config.webcam.brightness;
     let global_brightness = config.global_brightness;
-    let target_fps = config.webcam_target_fps;
+    let target_fps = config.webcam.target_fps;
     let ddp_delay_ms = config.ddp_delay_ms;
 
     // FPS rate limiting - check if enough time has elapsed
@@ -219,6 +270,9 @@ async fn process_frame(state: &WebcamState, data: Vec<u8>) -> Result<()> {
     }
 
     // Parse RGBA image and convert inline (no spawn_blocking for low latency)
+    let mut alloc_counter = state.alloc_counter.lock().unwrap();
+    alloc_counter.begin_frame();
+    let render_start = Instant::now();
     let img: RgbaImage = match ImageBuffer::from_raw(input_width, input_height, data) {
         Some(img) => img,
         None => {
@@ -239,6 +293,14 @@ async fn process_frame(state: &WebcamState, data: Vec<u8>) -> Result<()> {
         rgb_data.push(b); // B third
     }
 
+    // Zone layouts average regions of the picture onto LED ranges instead of one pixel per LED
+    if let Some(zones) = screen_zones::zones_for(&config) {
+        rgb_data = state.zones.lock().unwrap().render(&zones, &rgb_data, target_width, target_height,
+            config.webcam.zone_smoothing, config.total_leds);
+    }
+
+    live_stats::record_render_time(render_start.elapsed());
+
     // Send to WLED via multi-device manager with global brightness
     if let Ok(mut manager_guard) = state.multi_device_manager.lock() {
         if let Some(manager) = manager_guard.as_mut() {
@@ -253,6 +315,7 @@ async fn process_frame(state: &WebcamState, data: Vec<u8>) -> Result<()> {
     } else {
         state.frames_dropped.fetch_add(1, Ordering::SeqCst);
     }
+    alloc_counter.end_frame();
 
     Ok(())
 }
@@ -0,0 +1,289 @@
+// WLED State Module - What the strip is left showing after rustwled exits: black, the state each device
+// had before startup (restored over its JSON API), or the last frame sent
+// Also keeps on/off and brightness in step with the devices: a device switched off in the WLED app stops
+// receiving frames, and global_brightness can be written to each device's master brightness
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::thread;
+use std::time::Duration;
+use tokio::sync::broadcast;
+
+use crate::cli;
+use crate::config::{self, BandwidthConfig, ConfigDelta};
+use crate::multi_device::{DeviceOutput, MultiDeviceConfig, WLEDDevice};
+use crate::segments::{self, Placement};
+use crate::virtual_device;
+
+// How long to wait on each device's JSON API
+const API_TIMEOUT: Duration = Duration::from_secs(2);
+
+// How often each device's on/off state is read while wled_pause_when_off is on
+const STATE_POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+static SYNC: Mutex<Option<SyncSettings>> = Mutex::new(None);
+
+// Set while the devices do the dimming - frames go out at full brightness
+static BRIGHTNESS_SYNC: AtomicBool = AtomicBool::new(false);
+
+// Devices switched off in the WLED app - their frames are skipped until they're switched back on
+static SWITCHED_OFF: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+#[derive(Debug, Clone, PartialEq)]
+struct SyncSettings {
+    pause_when_off: bool,
+    brightness_sync: bool,
+    brightness: f64,
+    devices: Vec<String>,  // IPs of the enabled devices with a JSON API (WLED over DDP, not virtual)
+}
+
+/// Applies the configured exit_action when dropped (or run early, before a hard exit)
+pub struct ExitGuard {
+    action: String,  // "blank", "restore", or "none"
+    devices: Vec<WLEDDevice>,
+    saved: Vec<(String, serde_json::Value)>,  // Device IP and its /json/state at startup
+}
+
+impl ExitGuard {
+    /// Take the exit action from the config - "restore" snapshots each device's state now, before any frames are sent
+    pub fn new(config: &BandwidthConfig) -> Self {
+        let devices = enabled_devices(config);
+
+        let saved = if config.exit_action == "restore" {
+            let handles: Vec<_> = devices.iter()
+                .map(|d| {
+                    let ip = d.ip.clone();
+                    thread::spawn(move || cli::fetch_wled_json(&ip, "/json/state", API_TIMEOUT).map(|state| (ip, state)))
+                })
+                .collect();
+            handles.into_iter().filter_map(|h| h.join().ok().flatten()).collect()
+        } else {
+            Vec::new()
+        };
+
+        ExitGuard {
+            action: config.exit_action.clone(),
+            devices,
+            saved,
+        }
+    }
+
+    /// Blank or restore the devices now - later calls (and the drop) do nothing
+    pub fn run(&mut self) {
+        let devices = std::mem::take(&mut self.devices);
+        match self.action.as_str() {
+            "blank" => {
+                for device in &devices {
+                    blank(device);
+                }
+            }
+            "restore" => {
+                for device in &devices {
+                    // Leave realtime mode so the device shows its own effect again
+                    let restored = self.saved.iter().find(|(ip, _)| *ip == device.ip).map(|(_, state)| {
+                        let mut state = state.clone();
+                        state["live"] = serde_json::Value::Bool(false);
+                        cli::post_wled_json(&device.ip, "/json/state", &state, API_TIMEOUT)
+                    });
+                    match restored {
+                        Some(Ok(())) => {}
+                        Some(Err(e)) => {
+                            eprintln!("Warning: Could not restore {}: {} - blanking instead", device.ip, e);
+                            blank(device);
+                        }
+                        None => blank(device),  // No snapshot from startup
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+impl Drop for ExitGuard {
+    fn drop(&mut self) {
+        self.run();
+    }
+}
+
+/// Take the sync settings from the config - with brightness sync on, global_brightness is written to the devices
+pub fn configure(config: &BandwidthConfig) {
+    let settings = SyncSettings {
+        pause_when_off: config.wled_pause_when_off,
+        brightness_sync: config.wled_brightness_sync,
+        brightness: config.global_brightness,
+        devices: enabled_devices(config).into_iter()
+            .filter(|d| d.protocol == "ddp" && !virtual_device::is_virtual(&d.ip))
+            .map(|d| d.ip)
+            .collect(),
+    };
+    BRIGHTNESS_SYNC.store(settings.brightness_sync, Ordering::Relaxed);
+    if !settings.pause_when_off {
+        SWITCHED_OFF.lock().unwrap().clear();
+    }
+
+    let previous = SYNC.lock().unwrap().replace(settings.clone());
+    if settings.brightness_sync && previous.as_ref() != Some(&settings) {
+        thread::spawn(move || write_brightness(&settings));
+    } else if !settings.brightness_sync && previous.is_some_and(|p| p.brightness_sync) {
+        // Frames are dimmed here again - put the devices back to full so it isn't applied twice
+        thread::spawn(move || write_brightness(&SyncSettings { brightness: 1.0, ..settings }));
+    }
+}
+
+// Set each device's master brightness (0-255) - devices switched off are skipped, since WLED turns a device on
+// when it's given a brightness
+fn write_brightness(settings: &SyncSettings) {
+    let bri = device_brightness(settings.brightness);
+    for ip in &settings.devices {
+        if switched_off(ip) {
+            continue;
+        }
+        if let Err(e) = cli::post_wled_json(ip, "/json/state", &serde_json::json!({ "bri": bri }), API_TIMEOUT) {
+            eprintln!("Warning: Could not set brightness on {}: {}", ip, e);
+        }
+    }
+}
+
+/// WLED's master brightness for a global_brightness (0.0-1.0)
+fn device_brightness(brightness: f64) -> u8 {
+    (brightness.clamp(0.0, 1.0) * 255.0).round() as u8
+}
+
+/// The brightness to dim frames by - full when the devices' master brightness does the dimming instead
+pub fn frame_brightness(brightness: Option<f64>) -> Option<f64> {
+    if BRIGHTNESS_SYNC.load(Ordering::Relaxed) {
+        brightness.map(|_| 1.0)
+    } else {
+        brightness
+    }
+}
+
+/// Whether a device was switched off in the WLED app (frames to it are skipped)
+pub fn switched_off(ip: &str) -> bool {
+    SWITCHED_OFF.lock().unwrap().iter().any(|off| off == ip)
+}
+
+/// Poll each device's on/off state in the background while wled_pause_when_off is on
+pub fn spawn_poller() {
+    thread::spawn(|| loop {
+        thread::sleep(STATE_POLL_INTERVAL);
+        let Some(settings) = SYNC.lock().unwrap().clone() else { continue };
+        if !settings.pause_when_off {
+            continue;
+        }
+
+        for ip in &settings.devices {
+            // Unreachable devices keep receiving frames - there's no telling whether they're off
+            let Some(on) = cli::fetch_wled_json(ip, "/json/state", API_TIMEOUT).and_then(|state| state["on"].as_bool()) else {
+                continue;
+            };
+            let mut switched_off = SWITCHED_OFF.lock().unwrap();
+            let was_off = switched_off.contains(ip);
+            if !on && !was_off {
+                println!("⏸️  {} switched off - pausing output to it", ip);
+                switched_off.push(ip.clone());
+            } else if on && was_off {
+                println!("▶️  {} switched on - resuming output", ip);
+                switched_off.retain(|off| off != ip);
+            }
+        }
+    });
+}
+
+/// Keep the sync settings in step with config changes (web UI, API, or file edits)
+pub fn spawn_watcher(config_change_tx: broadcast::Sender<ConfigDelta>) {
+    config::spawn_config_listener(&config_change_tx, &[&["wled_pause_when_off", "wled_brightness_sync", "global_brightness"], config::DEVICE_CONFIG_KEYS].concat(), configure);
+}
+
+/// Send a black frame to every enabled device (e.g. after a mode crashed mid-frame)
+pub fn blank_all(config: &BandwidthConfig) {
+    for device in &enabled_devices(config) {
+        blank(device);
+    }
+}
+
+fn enabled_devices(config: &BandwidthConfig) -> Vec<WLEDDevice> {
+    MultiDeviceConfig::from_config(config).devices
+        .into_iter()
+        .filter(|d| d.enabled)
+        .collect()
+}
+
+// Send one black frame over the device's whole range (or just its segment, leaving the rest running WLED effects)
+fn blank(device: &WLEDDevice) {
+    let (len, offset) = match segments::placement(&device.ip) {
+        Placement::Whole => (device.led_count, 0),
+        Placement::Segment(target) => (device.led_count.min(target.len), target.ddp_offset()),
+        Placement::Pending => return,
+    };
+    let sent = DeviceOutput::open(device).ok()
+        .map(|mut output| output.write_offset(&vec![0u8; len * 3], offset).is_ok());
+    if sent != Some(true) {
+        eprintln!("Warning: Could not blank {}", device.ip);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::WLEDDeviceConfig;
+    use crate::virtual_device::VirtualDevice;
+
+    // An exit guard over one device sending to `capture`, built directly so "restore" has no startup snapshot
+    fn guard_for(capture: &VirtualDevice, action: &str) -> ExitGuard {
+        let mut config = BandwidthConfig::default();
+        config.total_leds = 4;
+        config.wled_devices = vec![WLEDDeviceConfig {
+            ip: format!("virtual:{}", capture.port().unwrap()),
+            led_offset: 0,
+            led_count: 4,
+            enabled: true,
+            max_fps: 0.0,
+            led_ma: 55.0,
+            psu_amps: 0.0,
+            backup_for: String::new(),
+            reversed: false,
+            mirrored: false,
+            segment: -1,
+            protocol: "ddp".to_string(),
+            universe: 1,
+            start_channel: 1,
+            net: 0,
+            subnet: 0,
+        }];
+        ExitGuard { action: action.to_string(), devices: enabled_devices(&config), saved: Vec::new() }
+    }
+
+    #[test]
+    fn test_exit_action() {
+        let timeout = Duration::from_secs(2);
+        let quiet = Duration::from_millis(200);
+
+        // Blank - one black frame, and nothing more from a second run or the drop
+        let mut capture = VirtualDevice::bind(0).unwrap();
+        let mut guard = guard_for(&capture, "blank");
+        guard.run();
+        assert_eq!(capture.recv_frame(timeout).unwrap().unwrap(), &[0; 12]);
+        guard.run();
+        drop(guard);
+        assert!(capture.recv_frame(quiet).unwrap().is_none());
+
+        // Restore without a snapshot from startup - blanked instead
+        let mut capture = VirtualDevice::bind(0).unwrap();
+        drop(guard_for(&capture, "restore"));
+        assert_eq!(capture.recv_frame(timeout).unwrap().unwrap(), &[0; 12]);
+
+        // None - the strip keeps the last frame
+        let mut capture = VirtualDevice::bind(0).unwrap();
+        drop(guard_for(&capture, "none"));
+        assert!(capture.recv_frame(quiet).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_device_brightness() {
+        assert_eq!(device_brightness(1.0), 255);
+        assert_eq!(device_brightness(0.5), 128);
+        assert_eq!(device_brightness(0.0), 0);
+        assert_eq!(device_brightness(1.5), 255);
+    }
+}